@@ -14,6 +14,7 @@ use delta_kernel::{engine::arrow_data::ArrowEngineData, DeltaResult, Engine, Err
 use futures::{stream::TryStreamExt, StreamExt};
 use itertools::Itertools;
 use object_store::{local::LocalFileSystem, ObjectStore};
+use url::Url;
 
 use crate::{TestCaseInfo, TestResult};
 
@@ -114,7 +115,16 @@ pub async fn assert_scan_metadata(
     engine: Arc<dyn Engine>,
     test_case: &TestCaseInfo,
 ) -> TestResult<()> {
-    let table_root = test_case.table_root()?;
+    assert_scan_metadata_of(engine, test_case, test_case.table_root()?).await
+}
+
+/// Assert that a scan of the table at `table_root` returns the expected table content of
+/// `test_case`, e.g. for a table that the operations of a writer test case were applied to.
+pub async fn assert_scan_metadata_of(
+    engine: Arc<dyn Engine>,
+    test_case: &TestCaseInfo,
+    table_root: Url,
+) -> TestResult<()> {
     let snapshot = Snapshot::builder_for(table_root).build(engine.as_ref())?;
     let scan = snapshot.scan_builder().build()?;
     let mut schema = None;
@@ -122,7 +132,7 @@ pub async fn assert_scan_metadata(
         .execute(engine)?
         .map(|scan_result| -> DeltaResult<_> {
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch: RecordBatch = data
                 .into_any()
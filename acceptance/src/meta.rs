@@ -17,6 +17,12 @@ pub enum AssertionError {
 
     #[error("Kernel error: {0}")]
     KernelError(#[from] Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Unsupported operation: {0}")]
+    UnsupportedOperation(String),
 }
 
 pub type TestResult<T, E = AssertionError> = std::result::Result<T, E>;
@@ -100,14 +106,24 @@ impl TestCaseInfo {
     }
 
     pub async fn assert_metadata(&self, engine: Arc<dyn Engine>) -> TestResult<()> {
+        self.assert_metadata_of(engine, self.table_root()?).await
+    }
+
+    /// Assert that the table at `table_root` has the expected metadata of this test case, e.g. a
+    /// table that the operations of a writer test case were applied to.
+    pub async fn assert_metadata_of(
+        &self,
+        engine: Arc<dyn Engine>,
+        table_root: Url,
+    ) -> TestResult<()> {
         let engine = engine.as_ref();
         let (latest, versions) = self.versions().await?;
 
-        let snapshot = Snapshot::builder_for(self.table_root()?).build(engine)?;
+        let snapshot = Snapshot::builder_for(table_root.clone()).build(engine)?;
         self.assert_snapshot_meta(&latest, &snapshot)?;
 
         for table_version in versions {
-            let snapshot = Snapshot::builder_for(self.table_root()?)
+            let snapshot = Snapshot::builder_for(table_root.clone())
                 .at_version(table_version.version)
                 .build(engine)?;
             self.assert_snapshot_meta(&table_version, &snapshot)?;
@@ -0,0 +1,329 @@
+//! Harness for writer test cases.
+//!
+//! A writer test case applies a list of write operations to a table with the kernel, then verifies
+//! the resulting log and table against the case's expectations. A case's directory contains:
+//! - `test_case_info.json`: the name and description of the case, like reader test cases
+//! - `delta/`: the table to apply the operations to, which is copied before it is written
+//! - `operations.json`: the [`WriteOperation`]s to apply, in order
+//! - `expected/`: the expected `table_version_metadata.json` of each version, as for reader test
+//!   cases, the expected `table_content` of the latest version (optional), and `log_files.json`,
+//!   the names of the files expected in `_delta_log` once all operations were applied
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use object_store::path::Path as ObjectStorePath;
+use object_store::PutMode;
+use roaring::RoaringTreemap;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use test_utils::table_builder::inline_deletion_vector;
+use url::Url;
+
+use delta_kernel::arrow::array::AsArray as _;
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::engine::default::executor::TaskExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use delta_kernel::scan::state::DvInfo;
+use delta_kernel::transaction::CommitResult;
+use delta_kernel::{DeltaResult, Engine, Error, FileMeta, Snapshot};
+
+use crate::{read_dat_case, AssertionError, TestCaseInfo, TestResult};
+
+/// An operation of a writer test case.
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum WriteOperation {
+    /// Append the rows of a parquet or newline-delimited JSON file in a single commit. The path of
+    /// the file is relative to the test case's directory.
+    Append { data: PathBuf },
+    /// Delete the rows at `row_indexes` of the `file`-th data file that the case's appends wrote
+    /// (counting from 0, in write order), by adding a deletion vector to the file.
+    DeleteWithDeletionVectors { file: usize, row_indexes: Vec<u64> },
+    /// Write a classic checkpoint of the latest version of the table.
+    Checkpoint,
+}
+
+#[derive(Debug)]
+pub struct WriterTestCase {
+    info: TestCaseInfo,
+    operations: Vec<WriteOperation>,
+}
+
+impl WriterTestCase {
+    pub fn info(&self) -> &TestCaseInfo {
+        &self.info
+    }
+
+    pub fn operations(&self) -> &[WriteOperation] {
+        &self.operations
+    }
+
+    /// Copy the table of this test case to `table_dir`, then apply the operations of the test case
+    /// to the copy. `engine` must be able to access `table_dir`.
+    ///
+    /// Fails with [`AssertionError::UnsupportedOperation`] if the kernel can't apply one of the
+    /// operations yet.
+    pub async fn execute<E: TaskExecutor>(
+        &self,
+        engine: &DefaultEngine<E>,
+        table_dir: &Path,
+    ) -> TestResult<()> {
+        copy_dir(&self.info.root_dir().join("delta"), table_dir)?;
+        let table_root =
+            Url::from_directory_path(table_dir).map_err(|_| AssertionError::InvalidTestCase)?;
+        let mut data_files = vec![];
+        for operation in &self.operations {
+            match operation {
+                WriteOperation::Append { data } => {
+                    let data = self.info.root_dir().join(data);
+                    data_files.extend(append(engine, &table_root, &data).await?)
+                }
+                WriteOperation::DeleteWithDeletionVectors { file, row_indexes } => {
+                    let path = data_files
+                        .get(*file)
+                        .ok_or(AssertionError::InvalidTestCase)?;
+                    delete_with_deletion_vectors(engine, &table_root, path, row_indexes).await?
+                }
+                WriteOperation::Checkpoint => checkpoint(engine, &table_root).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Assert that the table in `table_dir`, which the operations of this test case were applied
+    /// to, has the expected log files, metadata of each version and table content.
+    pub async fn assert_table(&self, engine: Arc<dyn Engine>, table_dir: &Path) -> TestResult<()> {
+        let expected_root = self.info.root_dir().join("expected");
+        let file = File::open(expected_root.join("log_files.json"))
+            .map_err(|_| AssertionError::InvalidTestCase)?;
+        let expected_log_files: Vec<String> =
+            serde_json::from_reader(file).map_err(|_| AssertionError::InvalidTestCase)?;
+        let mut log_files = vec![];
+        for entry in fs::read_dir(table_dir.join("_delta_log"))? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                log_files.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        log_files.sort();
+        assert_eq!(
+            log_files,
+            expected_log_files.into_iter().sorted().collect_vec(),
+            "Log files don't match"
+        );
+
+        let table_root =
+            Url::from_directory_path(table_dir).map_err(|_| AssertionError::InvalidTestCase)?;
+        self.info
+            .assert_metadata_of(engine.clone(), table_root.clone())
+            .await?;
+        if expected_root.join("latest/table_content").exists() {
+            crate::data::assert_scan_metadata_of(engine, &self.info, table_root).await?;
+        }
+        Ok(())
+    }
+}
+
+pub fn read_writer_case(case_root: impl AsRef<Path>) -> TestResult<WriterTestCase> {
+    let info = read_dat_case(&case_root)?;
+    let operations_path = case_root.as_ref().join("operations.json");
+    let file = File::open(operations_path).map_err(|_| AssertionError::InvalidTestCase)?;
+    let operations = serde_json::from_reader(file).map_err(|_| AssertionError::InvalidTestCase)?;
+    Ok(WriterTestCase { info, operations })
+}
+
+fn copy_dir(from: &Path, to: &Path) -> TestResult<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Append the rows of `data` in a single commit. Returns the paths of the data files written.
+async fn append<E: TaskExecutor>(
+    engine: &DefaultEngine<E>,
+    table_root: &Url,
+    data: &Path,
+) -> TestResult<Vec<String>> {
+    let snapshot = Snapshot::builder_for(table_root.clone()).build(engine)?;
+    if !snapshot.metadata().partition_columns().is_empty() {
+        return Err(AssertionError::UnsupportedOperation(
+            "appends to partitioned tables".to_string(),
+        ));
+    }
+
+    let batches = match data.extension().and_then(|ext| ext.to_str()) {
+        Some("parquet") => ParquetRecordBatchReaderBuilder::try_new(File::open(data)?)
+            .map_err(Error::from)?
+            .build()
+            .map_err(Error::from)?
+            .map(|batch| Ok(ArrowEngineData::new(batch.map_err(Error::from)?)))
+            .collect::<DeltaResult<Vec<_>>>()?,
+        Some("json") => {
+            let url = Url::from_file_path(data).map_err(|_| AssertionError::InvalidTestCase)?;
+            let file = FileMeta::new(url, 0, fs::metadata(data)?.len());
+            engine
+                .json_handler()
+                .read_json_files(&[file], snapshot.schema(), None)?
+                .map(|batch| Ok(*ArrowEngineData::try_from_engine_data(batch?)?))
+                .collect::<DeltaResult<Vec<_>>>()?
+        }
+        _ => return Err(AssertionError::InvalidTestCase),
+    };
+
+    let mut txn = snapshot
+        .transaction()?
+        .with_operation("WRITE".to_string())
+        .with_engine_info("delta-kernel-rs/acceptance");
+    let write_context = txn.get_write_context();
+    let mut paths = vec![];
+    for batch in &batches {
+        let add_files = engine
+            .write_parquet(batch, &write_context, HashMap::new(), true)
+            .await?;
+        let add_files = ArrowEngineData::try_from_engine_data(add_files)?;
+        let path_column = add_files
+            .record_batch()
+            .column_by_name("path")
+            .and_then(|column| column.as_string_opt::<i32>())
+            .ok_or_else(|| Error::generic("Add files have no path column"))?;
+        paths.extend(path_column.iter().flatten().map(String::from));
+        txn.add_files(add_files);
+    }
+    match txn.commit(engine)? {
+        CommitResult::Committed { .. } => Ok(paths),
+        _ => Err(Error::generic("Append to the writer test case table failed to commit").into()),
+    }
+}
+
+/// A data file of the table, as in its add action.
+struct DataFile {
+    path: String,
+    size: i64,
+    num_records: Option<u64>,
+    dv_info: DvInfo,
+    partition_values: HashMap<String, String>,
+}
+
+/// Delete the rows at `row_indexes` of the data file at `path`, by replacing its add action with one
+/// that has an inline deletion vector of all its deleted rows. The kernel can't write deletion
+/// vectors yet, so the commit is written directly to the log.
+async fn delete_with_deletion_vectors<E: TaskExecutor>(
+    engine: &DefaultEngine<E>,
+    table_root: &Url,
+    path: &str,
+    row_indexes: &[u64],
+) -> TestResult<()> {
+    let snapshot = Snapshot::builder_for(table_root.clone()).build(engine)?;
+    if snapshot.table_properties().enable_deletion_vectors != Some(true) {
+        return Err(AssertionError::InvalidTestCase);
+    }
+
+    let mut data_files: Vec<DataFile> = vec![];
+    let scan = snapshot.clone().scan_builder().build()?;
+    for scan_metadata in scan.scan_metadata(engine)? {
+        data_files = scan_metadata?.visit_scan_files(
+            data_files,
+            |data_files, path, size, stats, dv_info, _, partition_values, _| {
+                data_files.push(DataFile {
+                    path: path.to_string(),
+                    size,
+                    num_records: stats.map(|stats| stats.num_records),
+                    dv_info,
+                    partition_values,
+                })
+            },
+        )?;
+    }
+    let file = data_files
+        .into_iter()
+        .find(|file| file.path == path)
+        .ok_or(AssertionError::InvalidTestCase)?;
+
+    let old_deletion_vector = match file.dv_info.deletion_vector() {
+        Some(dv) => json!({
+            "storageType": dv.storage_type,
+            "pathOrInlineDv": dv.path_or_inline_dv,
+            "offset": dv.offset,
+            "sizeInBytes": dv.size_in_bytes,
+            "cardinality": dv.cardinality,
+        }),
+        None => Value::Null,
+    };
+    let mut deleted_rows: RoaringTreemap = file
+        .dv_info
+        .get_row_indexes(engine, table_root)?
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    deleted_rows.extend(row_indexes.iter().copied());
+
+    let stats = file
+        .num_records
+        .map(|num_records| json!({"numRecords": num_records}).to_string());
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as i64);
+    let actions = [
+        json!({"commitInfo": {
+            "timestamp": now,
+            "operation": "DELETE",
+            "engineInfo": "delta-kernel-rs/acceptance",
+        }}),
+        json!({"remove": {
+            "path": file.path,
+            "deletionTimestamp": now,
+            "dataChange": true,
+            "extendedFileMetadata": true,
+            "partitionValues": file.partition_values,
+            "size": file.size,
+            "deletionVector": old_deletion_vector,
+        }}),
+        json!({"add": {
+            "path": file.path,
+            "partitionValues": file.partition_values,
+            "size": file.size,
+            "modificationTime": now,
+            "dataChange": true,
+            "stats": stats,
+            "deletionVector": inline_deletion_vector(&deleted_rows)?,
+        }}),
+    ];
+    let commit = actions.iter().map(Value::to_string).join("\n");
+
+    let store = engine
+        .get_object_store_for_url(table_root)
+        .ok_or(AssertionError::InvalidTestCase)?;
+    let commit_url = table_root
+        .join("_delta_log/")
+        .and_then(|log_root| log_root.join(&format!("{:020}.json", snapshot.version() + 1)))
+        .map_err(Error::from)?;
+    let commit_path = ObjectStorePath::from_url_path(commit_url.path()).map_err(Error::from)?;
+    store
+        .put_opts(&commit_path, commit.into(), PutMode::Create.into())
+        .await
+        .map_err(Error::from)?;
+    Ok(())
+}
+
+async fn checkpoint<E: TaskExecutor>(
+    engine: &DefaultEngine<E>,
+    table_root: &Url,
+) -> TestResult<()> {
+    let snapshot = Snapshot::builder_for(table_root.clone()).build(engine)?;
+    engine.write_checkpoint(snapshot).await?;
+    Ok(())
+}
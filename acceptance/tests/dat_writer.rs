@@ -0,0 +1,61 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use acceptance::writer::read_writer_case;
+use acceptance::AssertionError;
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+
+/// Cases with operations the kernel can't apply yet, with the reason. These must fail with
+/// [`AssertionError::UnsupportedOperation`], so they are noticed once the kernel supports them.
+static EXPECTED_FAILURES: &[(&str, &str)] = &[];
+
+fn writer_test(path: &Path) -> datatest_stable::Result<()> {
+    let root_dir = format!(
+        "{}/{}",
+        env!["CARGO_MANIFEST_DIR"],
+        path.parent().unwrap().to_str().unwrap()
+    );
+    let expected_failure = EXPECTED_FAILURES
+        .iter()
+        .find(|(name, _)| root_dir.ends_with(name));
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(async {
+            let case = read_writer_case(root_dir).unwrap();
+            let table_dir = tempfile::tempdir().unwrap();
+            let table_root = url::Url::from_directory_path(table_dir.path()).unwrap();
+            let engine = Arc::new(
+                DefaultEngine::try_new(
+                    &table_root,
+                    std::iter::empty::<(&str, &str)>(),
+                    Arc::new(TokioBackgroundExecutor::new()),
+                )
+                .unwrap(),
+            );
+
+            let result = case.execute(engine.as_ref(), table_dir.path()).await;
+            if let Some((name, reason)) = expected_failure {
+                assert!(
+                    matches!(result, Err(AssertionError::UnsupportedOperation(_))),
+                    "{name} is expected to fail ({reason}), but got {result:?}"
+                );
+                println!("{name} failed as expected: {reason}");
+                return;
+            }
+            result.unwrap();
+            case.assert_table(engine, table_dir.path()).await.unwrap();
+        });
+    Ok(())
+}
+
+// Writer cases live in this repository until DAT publishes writer cases with the same layout
+datatest_stable::harness! {
+    {
+        test = writer_test,
+        root = "tests/writer_cases/",
+        pattern = r"test_case_info\.json"
+    },
+}
@@ -51,9 +51,11 @@ void scan_row_callback(
   const Stats* stats,
   const CDvInfo* cdv_info,
   const Expression* transform,
-  const CStringMap* partition_values)
+  const CStringMap* partition_values,
+  const CStringMap* tags)
 {
   (void)size; // not using this at the moment
+  (void)tags;
   struct EngineContext* context = engine_context;
   print_diag("Called back to read file: %.*s. (size: %" PRIu64 ", num records: ", (int)path.len, path.ptr, size);
   if (stats) {
@@ -0,0 +1,362 @@
+//! Support for engine-provided file readers.
+//!
+//! An engine can register its own parquet and/or JSON read callbacks on the [`EngineBuilder`]. The
+//! kernel still drives planning and log replay, but whenever it needs to read a file it calls back
+//! into the engine, which hands the data back as an arrow [C
+//! stream](https://arrow.apache.org/docs/format/CStreamInterface.html). All other engine
+//! operations (listing, expression evaluation, JSON parsing and writing) are performed by the
+//! default engine.
+
+use std::sync::Arc;
+
+use delta_kernel::arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::schema::SchemaRef;
+use delta_kernel::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandler, FileDataReadResultIterator,
+    JsonHandler, ParquetHandler, PredicateRef, StorageHandler,
+};
+use url::Url;
+
+use crate::engine_funcs::FileMeta;
+use crate::{kernel_string_slice, EngineBuilder, NullableCvoid, SharedSchema};
+
+use super::handle::Handle;
+
+/// A file reader callback provided by the engine.
+///
+/// The kernel invokes `read_file` once for each file it needs to read, passing along the engine's
+/// `context` pointer, the file to read, and the physical schema the returned data must have. The
+/// engine must initialize `out_stream` with an arrow C stream that produces the contents of the
+/// file, with exactly the columns of `physical_schema` in schema order (see
+/// [`delta_kernel::ParquetHandler::read_parquet_files`] for the full contract), and return `true`.
+/// If the file cannot be read, the engine returns `false` and leaves `out_stream` untouched.
+///
+/// The engine owns the `physical_schema` handle it is given, and must free it by calling
+/// [`free_schema`] once done with it. The `file` argument is only valid until the callback returns.
+///
+/// The `context` pointer is retained by the kernel for as long as the engine lives, and may be used
+/// from any thread on which the engine is used. The engine is responsible for keeping it valid.
+///
+/// [`free_schema`]: crate::free_schema
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EngineFileReader {
+    pub context: NullableCvoid,
+    pub read_file: extern "C" fn(
+        context: NullableCvoid,
+        file: &FileMeta,
+        physical_schema: Handle<SharedSchema>,
+        out_stream: *mut FFI_ArrowArrayStream,
+    ) -> bool,
+}
+
+/// # Safety
+///
+/// Kernel does not touch the engine's context. If engine chooses to read from multiple threads, it
+/// is responsible for handling any races that could result.
+unsafe impl Send for EngineFileReader {}
+
+/// # Safety
+///
+/// See the `Send` impl above.
+unsafe impl Sync for EngineFileReader {}
+
+impl EngineFileReader {
+    fn read_file(
+        &self,
+        file: &delta_kernel::FileMeta,
+        physical_schema: SchemaRef,
+    ) -> DeltaResult<ArrowArrayStreamReader> {
+        let path = file.location.to_string();
+        let ffi_file = FileMeta {
+            path: kernel_string_slice!(path),
+            last_modified: file.last_modified,
+            size: file
+                .size
+                .try_into()
+                .map_err(|_| Error::generic("unable to convert file size to usize"))?,
+        };
+        let mut stream = FFI_ArrowArrayStream::empty();
+        if !(self.read_file)(self.context, &ffi_file, physical_schema.into(), &mut stream) {
+            return Err(Error::generic(format!(
+                "Engine-provided reader failed to read file {path}"
+            )));
+        }
+        Ok(ArrowArrayStreamReader::try_new(stream)?)
+    }
+
+    /// Read each of the `files` in order, chaining the resulting batches.
+    fn read_files(
+        self,
+        files: &[delta_kernel::FileMeta],
+        physical_schema: SchemaRef,
+    ) -> FileDataReadResultIterator {
+        let files = files.to_vec();
+        let iter = files.into_iter().flat_map(move |file| {
+            let batches: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send> =
+                match self.read_file(&file, physical_schema.clone()) {
+                    Ok(reader) => Box::new(reader.map(|batch| {
+                        Ok(Box::new(ArrowEngineData::new(batch?)) as Box<dyn EngineData>)
+                    })),
+                    Err(err) => Box::new(std::iter::once(Err(err))),
+                };
+            batches
+        });
+        Box::new(iter)
+    }
+}
+
+/// A [`ParquetHandler`] that reads files using an [`EngineFileReader`].
+struct ExternParquetHandler {
+    reader: EngineFileReader,
+}
+
+impl ParquetHandler for ExternParquetHandler {
+    fn read_parquet_files(
+        &self,
+        files: &[delta_kernel::FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        Ok(self.reader.read_files(files, physical_schema))
+    }
+}
+
+/// A [`JsonHandler`] that reads files using an [`EngineFileReader`], and delegates parsing and
+/// writing JSON to another handler.
+struct ExternJsonHandler {
+    reader: EngineFileReader,
+    inner: Arc<dyn JsonHandler>,
+}
+
+impl JsonHandler for ExternJsonHandler {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.inner.parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[delta_kernel::FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        Ok(self.reader.read_files(files, physical_schema))
+    }
+
+    fn write_json_file(
+        &self,
+        path: &Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+        overwrite: bool,
+    ) -> DeltaResult<()> {
+        self.inner.write_json_file(path, data, overwrite)
+    }
+}
+
+/// An [`Engine`] that replaces the parquet and/or JSON handler of another engine with ones that
+/// read through engine-provided callbacks.
+pub(crate) struct ExternReaderEngine {
+    inner: Arc<dyn Engine>,
+    parquet: Option<Arc<dyn ParquetHandler>>,
+    json: Option<Arc<dyn JsonHandler>>,
+}
+
+impl ExternReaderEngine {
+    /// Wrap `inner`, if any readers were registered. Otherwise `inner` is returned unchanged.
+    pub(crate) fn wrap(
+        inner: Arc<dyn Engine>,
+        parquet_reader: Option<EngineFileReader>,
+        json_reader: Option<EngineFileReader>,
+    ) -> Arc<dyn Engine> {
+        if parquet_reader.is_none() && json_reader.is_none() {
+            return inner;
+        }
+        let parquet = parquet_reader
+            .map(|reader| -> Arc<dyn ParquetHandler> { Arc::new(ExternParquetHandler { reader }) });
+        let json = json_reader.map(|reader| -> Arc<dyn JsonHandler> {
+            Arc::new(ExternJsonHandler {
+                reader,
+                inner: inner.json_handler(),
+            })
+        });
+        Arc::new(Self {
+            inner,
+            parquet,
+            json,
+        })
+    }
+}
+
+impl Engine for ExternReaderEngine {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.inner.evaluation_handler()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+        self.inner.storage_handler()
+    }
+
+    fn json_handler(&self) -> Arc<dyn JsonHandler> {
+        self.json
+            .clone()
+            .unwrap_or_else(|| self.inner.json_handler())
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+        self.parquet
+            .clone()
+            .unwrap_or_else(|| self.inner.parquet_handler())
+    }
+}
+
+/// Register an engine-provided parquet reader on the builder. Once the engine is built, all parquet
+/// files (data files as well as checkpoints) are read by calling back into `reader`.
+///
+/// # Safety
+///
+/// Caller must pass a valid EngineBuilder pointer, and a reader whose callback and context remain
+/// valid for as long as the resulting engine lives.
+#[no_mangle]
+pub unsafe extern "C" fn set_builder_parquet_reader(
+    builder: &mut EngineBuilder,
+    reader: EngineFileReader,
+) {
+    builder.parquet_reader = Some(reader);
+}
+
+/// Register an engine-provided JSON file reader on the builder. Once the engine is built, all JSON
+/// files (i.e. commit files) are read by calling back into `reader`. Parsing JSON strings and
+/// writing JSON files is still performed by the default engine.
+///
+/// # Safety
+///
+/// Caller must pass a valid EngineBuilder pointer, and a reader whose callback and context remain
+/// valid for as long as the resulting engine lives.
+#[no_mangle]
+pub unsafe extern "C" fn set_builder_json_reader(
+    builder: &mut EngineBuilder,
+    reader: EngineFileReader,
+) {
+    builder.json_reader = Some(reader);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use delta_kernel::arrow::array::RecordBatchReader;
+    use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use delta_kernel::engine::default::DefaultEngine;
+    use delta_kernel::Snapshot;
+    use object_store::local::LocalFileSystem;
+
+    use super::*;
+    use crate::TryFromStringSlice;
+
+    struct ReaderContext {
+        engine: DefaultEngine<TokioBackgroundExecutor>,
+        calls: AtomicUsize,
+    }
+
+    // Reads the file with the default engine's parquet handler, and counts invocations
+    extern "C" fn read_with_default_engine(
+        context: NullableCvoid,
+        file: &FileMeta,
+        physical_schema: Handle<SharedSchema>,
+        out_stream: *mut FFI_ArrowArrayStream,
+    ) -> bool {
+        let context: &ReaderContext = unsafe { context.unwrap().cast().as_ref() };
+        context.calls.fetch_add(1, Ordering::SeqCst);
+        let path: &str = unsafe { TryFromStringSlice::try_from_slice(&file.path) }.unwrap();
+        let file_meta = delta_kernel::FileMeta::new(
+            Url::parse(path).unwrap(),
+            file.last_modified,
+            file.size as u64,
+        );
+        let schema = unsafe { physical_schema.into_inner() };
+        let batches: Vec<_> = context
+            .engine
+            .parquet_handler()
+            .read_parquet_files(&[file_meta], schema.clone(), None)
+            .unwrap()
+            .map(|data| {
+                let data = data.unwrap().into_any().downcast::<ArrowEngineData>();
+                data.unwrap().record_batch().clone()
+            })
+            .collect();
+        let arrow_schema = batches[0].schema();
+        let reader = delta_kernel::arrow::record_batch::RecordBatchIterator::new(
+            batches.into_iter().map(Ok),
+            arrow_schema,
+        );
+        assert!(reader.schema().fields().len() == schema.fields().len());
+        unsafe { std::ptr::write(out_stream, FFI_ArrowArrayStream::new(Box::new(reader))) };
+        true
+    }
+
+    extern "C" fn failing_reader(
+        _context: NullableCvoid,
+        _file: &FileMeta,
+        physical_schema: Handle<SharedSchema>,
+        _out_stream: *mut FFI_ArrowArrayStream,
+    ) -> bool {
+        unsafe { physical_schema.drop_handle() };
+        false
+    }
+
+    fn table_url() -> Url {
+        let path = std::fs::canonicalize("../kernel/tests/data/table-without-dv-small/").unwrap();
+        Url::from_directory_path(path).unwrap()
+    }
+
+    fn default_engine() -> DefaultEngine<TokioBackgroundExecutor> {
+        DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )
+    }
+
+    #[test]
+    fn engine_provided_parquet_reader() {
+        let context = Box::new(ReaderContext {
+            engine: default_engine(),
+            calls: AtomicUsize::new(0),
+        });
+        let reader = EngineFileReader {
+            context: std::ptr::NonNull::new(&*context as *const ReaderContext as *mut _),
+            read_file: read_with_default_engine,
+        };
+        let engine = ExternReaderEngine::wrap(Arc::new(default_engine()), Some(reader), None);
+        let snapshot = Snapshot::builder_for(table_url())
+            .build(engine.as_ref())
+            .unwrap();
+        let scan = snapshot.scan_builder().build().unwrap();
+        let num_rows: usize = scan
+            .execute(engine)
+            .unwrap()
+            .map(|res| res.unwrap().raw_data.unwrap().len())
+            .sum();
+        assert_eq!(num_rows, 10);
+        assert_eq!(context.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn engine_provided_reader_failure() {
+        let reader = EngineFileReader {
+            context: None,
+            read_file: failing_reader,
+        };
+        let engine = ExternReaderEngine::wrap(Arc::new(default_engine()), None, Some(reader));
+        let err = Snapshot::builder_for(table_url())
+            .build(engine.as_ref())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Engine-provided reader failed to read file"));
+    }
+}
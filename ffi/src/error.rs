@@ -67,6 +67,9 @@ pub enum KernelError {
     LiteralExpressionTransformError = 40,
     CheckpointWriteError = 41,
     SchemaError = 42,
+    InvalidTableStateError = 43,
+    UnsupportedFeaturesError = 44,
+    PredicateBindingError = 45,
 }
 
 impl From<Error> for KernelError {
@@ -114,6 +117,7 @@ impl From<Error> for KernelError {
                 source,
                 backtrace: _,
             } => Self::from(*source),
+            Error::WithContext { source, .. } => Self::from(*source),
             Error::InvalidExpressionEvaluation(_) => KernelError::InvalidExpression,
             Error::InvalidLogPath(_) => KernelError::InvalidLogPath,
             Error::FileAlreadyExists(_) => KernelError::FileAlreadyExists,
@@ -128,6 +132,9 @@ impl From<Error> for KernelError {
                 KernelError::LiteralExpressionTransformError
             }
             Error::Schema(_) => KernelError::SchemaError,
+            Error::InvalidTableState(_) => KernelError::InvalidTableStateError,
+            Error::UnsupportedFeatures(_) => KernelError::UnsupportedFeaturesError,
+            Error::PredicateBinding(_) => KernelError::PredicateBindingError,
             _ => KernelError::UnknownError,
         }
     }
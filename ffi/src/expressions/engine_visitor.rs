@@ -168,7 +168,9 @@ pub struct EngineExpressionVisitor {
     /// Visits the `Coalesce` variadic operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a list identified by `child_list_id`
     pub visit_coalesce: VisitVariadicFn,
-    /// Visits the `column` belonging to the list identified by `sibling_list_id`.
+    /// Visits the `column` belonging to the list identified by `sibling_list_id`. Nested column
+    /// names are rendered with fields separated by periods, and field names containing special
+    /// characters enclosed in backticks, so they can be parsed back unambiguously.
     pub visit_column:
         extern "C" fn(data: *mut c_void, sibling_list_id: usize, name: KernelStringSlice),
     /// Visits a `Struct` expression belonging to the list identified by `sibling_list_id`.
@@ -238,6 +240,11 @@ pub struct EngineExpressionVisitor {
     /// list identified by `sibling_list_id`.
     pub visit_unknown:
         extern "C" fn(data: *mut c_void, sibling_list_id: usize, name: KernelStringSlice),
+    /// Optional (may be `NULL`). Visits the `ParseJson` unary operator belonging to the list
+    /// identified by `sibling_list_id`. The sub-expression will be in a _one_ item list identified
+    /// by `child_list_id`. If not provided, the expression is visited as unknown (see
+    /// `visit_unknown`) with the name `ParseJson`.
+    pub visit_parse_json: Option<VisitUnaryFn>,
 }
 
 /// Visit the expression of the passed [`SharedExpression`] Handle using the provided `visitor`.
@@ -548,7 +555,8 @@ fn visit_expression_scalar(
             call!(visitor, visit_literal_string, sibling_list_id, val)
         }
         Scalar::Boolean(val) => call!(visitor, visit_literal_bool, sibling_list_id, *val),
-        Scalar::Timestamp(val) => {
+        // engines get the instant a timestamp with an offset denotes
+        Scalar::Timestamp(val) | Scalar::TimestampWithOffset(val, _) => {
             call!(visitor, visit_literal_timestamp, sibling_list_id, *val)
         }
         Scalar::TimestampNtz(val) => {
@@ -596,11 +604,15 @@ fn visit_expression_impl(
         }
         Expression::Predicate(pred) => visit_predicate_impl(visitor, pred, sibling_list_id),
         Expression::Unary(UnaryExpression { op, expr }) => {
-            let child_list_id = call!(visitor, make_field_list, 1);
-            visit_expression_impl(visitor, expr, child_list_id);
             let visit_fn = match op {
                 UnaryExpressionOp::ToJson => visitor.visit_to_json,
+                UnaryExpressionOp::ParseJson => match visitor.visit_parse_json {
+                    Some(visit_fn) => visit_fn,
+                    None => return visit_unknown(visitor, sibling_list_id, "ParseJson"),
+                },
             };
+            let child_list_id = call!(visitor, make_field_list, 1);
+            visit_expression_impl(visitor, expr, child_list_id);
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
         Expression::Binary(BinaryExpression { op, left, right }) => {
@@ -210,6 +210,10 @@ pub extern "C" fn visit_expression_unknown(
     name.map_or(0, |name| wrap_expression(state, Expression::Unknown(name)))
 }
 
+/// Visit a column reference. The `name` is parsed as a (possibly nested) column name, with fields
+/// separated by periods and field names containing special characters (including periods)
+/// enclosed in backticks, e.g. ``a.`b.c`.d`` references the field `d` of the field `b.c` of `a`.
+///
 /// # Safety
 /// The string slice must be valid
 #[no_mangle]
@@ -225,8 +229,7 @@ fn visit_expression_column_impl(
     state: &mut KernelExpressionVisitorState,
     name: DeltaResult<&str>,
 ) -> DeltaResult<usize> {
-    // TODO: FIXME: This is incorrect if any field name in the column path contains a period.
-    let name = ColumnName::from_naive_str_split(name?);
+    let name = ColumnName::parse(name?)?;
     Ok(wrap_expression(state, name))
 }
 
@@ -153,6 +153,8 @@ mod private {
         ///
         /// Caller asserts that the handle is [valid][Handle#Validity].
         pub unsafe fn into_inner(self) -> H::From {
+            #[cfg(feature = "handle-diagnostics")]
+            crate::handle_diagnostics::untrack::<H>(self.ptr.as_ptr().cast());
             H::into_inner(self.ptr.cast().as_ptr())
         }
         /// Drops this handle. Dropping a mutable handle always drops the underlying object as well;
@@ -242,7 +244,9 @@ mod private {
             + MutableHandleOps<T, S, From = Box<T>>,
     {
         fn from(val: Box<T>) -> Handle<H> {
-            let ptr = H::into_handle_ptr(val).cast();
+            let ptr: NonNull<H> = H::into_handle_ptr(val).cast();
+            #[cfg(feature = "handle-diagnostics")]
+            crate::handle_diagnostics::track::<H>(ptr.as_ptr().cast());
             Handle { ptr }
         }
     }
@@ -254,7 +258,9 @@ mod private {
             + SharedHandleOps<T, S, From = Arc<T>>,
     {
         fn from(val: Arc<T>) -> Handle<H> {
-            let ptr = H::into_handle_ptr(val).cast();
+            let ptr: NonNull<H> = H::into_handle_ptr(val).cast();
+            #[cfg(feature = "handle-diagnostics")]
+            crate::handle_diagnostics::track::<H>(ptr.as_ptr().cast());
             Handle { ptr }
         }
     }
@@ -0,0 +1,236 @@
+//! Opt-in diagnostics for the [`Handle`] system, enabled by the `handle-diagnostics` feature.
+//!
+//! When enabled, every handle created by kernel is recorded in a global registry, together with
+//! the type of the handle and a backtrace of where it was created. Dropping or consuming a handle
+//! removes it from the registry again. This allows engine integrators to find two classes of
+//! memory bugs that otherwise tend to show up as hard-to-debug crashes:
+//!
+//! * Leaks: handles that were handed to the engine but never released. Use
+//!   [`handle_diagnostics_live_count`], [`handle_diagnostics_visit_live_counts`] or
+//!   [`handle_diagnostics_report_leaks`] to inspect the handles that are still alive.
+//!
+//! * Double-frees: handles that are released more than once (or that kernel never created). These
+//!   are reported as `ERROR` level tracing events as soon as they happen, including the backtrace
+//!   of where the handle was created and where it was first released, and are counted by
+//!   [`handle_diagnostics_invalid_release_count`].
+//!
+//! NOTE: Diagnostics can only detect a double-free if the underlying object was not already
+//! corrupted by the first release. In particular, a double-free of a shared handle whose object was
+//! since deallocated is still undefined behavior; the report is emitted on a best-effort basis
+//! right before kernel touches the freed memory.
+//!
+//! Capturing backtraces is expensive, so this mode is intended for debugging and testing only.
+//!
+//! [`Handle`]: crate::handle::Handle
+
+use std::any::type_name;
+use std::backtrace::Backtrace;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::c_void;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use tracing::{error, warn};
+
+use crate::{kernel_string_slice, KernelStringSlice};
+
+/// A single live (or released) handle, as tracked by the registry.
+struct HandleRecord {
+    type_name: &'static str,
+    created_at: Backtrace,
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Live handles, keyed by handle address. Shared handles to a sized type are represented by
+    /// the address of the object itself, so several live handles can share the same address.
+    live: HashMap<usize, Vec<HandleRecord>>,
+    /// The most recent release of each address that currently has no live handles, used to
+    /// explain double-frees. Entries are removed once the address is reused by a new handle.
+    released: HashMap<usize, (HandleRecord, Backtrace)>,
+    /// Number of releases of handles that were not live at the time.
+    invalid_releases: usize,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(Default::default);
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // A panic while holding the lock cannot leave the registry in an inconsistent state, so it is
+    // fine to keep using it after poisoning.
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Records the creation of a handle of type `H` at address `ptr`.
+pub(crate) fn track<H>(ptr: *const c_void) {
+    let addr = ptr as usize;
+    let record = HandleRecord {
+        type_name: type_name::<H>(),
+        created_at: Backtrace::force_capture(),
+    };
+    let mut registry = registry();
+    registry.released.remove(&addr);
+    registry.live.entry(addr).or_default().push(record);
+}
+
+/// Records the release (drop or consumption) of a handle of type `H` at address `ptr`, reporting
+/// an error if no such handle is live.
+pub(crate) fn untrack<H>(ptr: *const c_void) {
+    let addr = ptr as usize;
+    let handle_type = type_name::<H>();
+    let mut registry = registry();
+    let record = registry.live.get_mut(&addr).and_then(|records| {
+        let pos = records.iter().rposition(|r| r.type_name == handle_type)?;
+        Some(records.remove(pos))
+    });
+    match record {
+        Some(record) => {
+            if registry.live.get(&addr).is_some_and(Vec::is_empty) {
+                registry.live.remove(&addr);
+                let released_at = Backtrace::force_capture();
+                registry.released.insert(addr, (record, released_at));
+            }
+        }
+        None => {
+            registry.invalid_releases += 1;
+            match registry.released.get(&addr) {
+                Some((record, released_at)) if record.type_name == handle_type => error!(
+                    "Double free of {handle_type} handle {ptr:p}.\n\
+                     Created at:\n{}\nFirst released at:\n{released_at}\n\
+                     Released again at:\n{}",
+                    record.created_at,
+                    Backtrace::force_capture()
+                ),
+                _ => error!(
+                    "Release of unknown {handle_type} handle {ptr:p} at:\n{}",
+                    Backtrace::force_capture()
+                ),
+            }
+        }
+    }
+}
+
+/// Returns the number of handles, of any type, that are currently live.
+#[no_mangle]
+pub extern "C" fn handle_diagnostics_live_count() -> usize {
+    registry().live.values().map(Vec::len).sum()
+}
+
+/// Returns the number of invalid handle releases (double-frees or releases of handles that kernel
+/// did not create) detected so far.
+#[no_mangle]
+pub extern "C" fn handle_diagnostics_invalid_release_count() -> usize {
+    registry().invalid_releases
+}
+
+/// Visitor invoked once per handle type with the number of live handles of that type.
+pub type HandleCountVisitor =
+    extern "C" fn(context: *mut c_void, type_name: KernelStringSlice, count: usize);
+
+/// Calls `visitor` once for each handle type that currently has live handles, in type name order,
+/// passing the number of live handles of that type. The type name slice is only valid for the
+/// duration of the callback.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid `visitor` that can handle the passed `context`.
+#[no_mangle]
+pub unsafe extern "C" fn handle_diagnostics_visit_live_counts(
+    context: *mut c_void,
+    visitor: HandleCountVisitor,
+) {
+    for (type_name, count) in live_counts() {
+        visitor(context, kernel_string_slice!(type_name), count);
+    }
+}
+
+/// Reports every live handle as a `WARN` level tracing event, including the backtrace of where it
+/// was created, and returns the number of live handles. Engines typically call this right before
+/// shutdown, when no handles should remain.
+#[no_mangle]
+pub extern "C" fn handle_diagnostics_report_leaks() -> usize {
+    let registry = registry();
+    let mut leaked = 0;
+    for (addr, records) in &registry.live {
+        for record in records {
+            warn!(
+                "Leaked {} handle {:#x} created at:\n{}",
+                record.type_name, addr, record.created_at
+            );
+            leaked += 1;
+        }
+    }
+    leaked
+}
+
+fn live_counts() -> BTreeMap<&'static str, usize> {
+    let mut counts = BTreeMap::new();
+    for record in registry().live.values().flatten() {
+        *counts.entry(record.type_name).or_default() += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use delta_kernel_ffi_macros::handle_descriptor;
+
+    use super::*;
+    use crate::handle::Handle;
+
+    pub struct Probe;
+
+    // Each test uses its own handle types, because the registry is shared by all tests
+    #[handle_descriptor(target=Probe, mutable=true, sized=true)]
+    pub struct MutableLeakProbe;
+
+    #[handle_descriptor(target=Probe, mutable=false, sized=true)]
+    pub struct SharedLeakProbe;
+
+    #[handle_descriptor(target=Probe, mutable=true, sized=true)]
+    pub struct MutableDoubleFreeProbe;
+
+    fn live_count<H>() -> usize {
+        live_counts()
+            .get(type_name::<H>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn tracks_live_handles() {
+        let mutable: Handle<MutableLeakProbe> = Box::new(Probe).into();
+        let shared: Handle<SharedLeakProbe> = Arc::new(Probe).into();
+        let shared_clone = unsafe { shared.clone_handle() };
+        assert_eq!(live_count::<MutableLeakProbe>(), 1);
+        assert_eq!(live_count::<SharedLeakProbe>(), 2);
+        assert!(handle_diagnostics_live_count() >= 3);
+
+        unsafe { shared.drop_handle() };
+        assert_eq!(live_count::<SharedLeakProbe>(), 1);
+        let arc = unsafe { shared_clone.into_inner() };
+        assert_eq!(live_count::<SharedLeakProbe>(), 0);
+        drop(arc);
+
+        unsafe { mutable.drop_handle() };
+        assert_eq!(live_count::<MutableLeakProbe>(), 0);
+    }
+
+    #[test]
+    fn reports_invalid_release() {
+        // Only simulate a double free by tracking calls directly, rather than actually freeing
+        // the same memory twice.
+        let probe = Box::new(Probe);
+        let ptr: *const c_void = (&*probe as *const Probe).cast();
+        track::<MutableDoubleFreeProbe>(ptr);
+        assert_eq!(live_count::<MutableDoubleFreeProbe>(), 1);
+
+        let before = handle_diagnostics_invalid_release_count();
+        untrack::<MutableDoubleFreeProbe>(ptr);
+        assert_eq!(live_count::<MutableDoubleFreeProbe>(), 0);
+        untrack::<MutableDoubleFreeProbe>(ptr);
+        assert!(handle_diagnostics_invalid_release_count() > before);
+    }
+}
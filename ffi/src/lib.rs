@@ -24,6 +24,8 @@ use delta_kernel_ffi_macros::handle_descriptor;
 pub mod handle;
 #[cfg(not(feature = "internal-api"))]
 pub(crate) mod handle;
+#[cfg(feature = "handle-diagnostics")]
+pub mod handle_diagnostics;
 
 use handle::Handle;
 
@@ -36,6 +38,8 @@ mod domain_metadata;
 pub use domain_metadata::get_domain_metadata;
 pub mod engine_data;
 pub mod engine_funcs;
+#[cfg(feature = "default-engine-base")]
+pub mod engine_readers;
 pub mod error;
 use error::{AllocateError, AllocateErrorFn, ExternResult, IntoExternResult};
 pub mod expressions;
@@ -419,6 +423,8 @@ pub struct EngineBuilder {
     url: Url,
     allocate_fn: AllocateErrorFn,
     options: HashMap<String, String>,
+    parquet_reader: Option<engine_readers::EngineFileReader>,
+    json_reader: Option<engine_readers::EngineFileReader>,
 }
 
 #[cfg(feature = "default-engine-base")]
@@ -453,6 +459,8 @@ fn get_engine_builder_impl(
         url: url?,
         allocate_fn,
         options: HashMap::default(),
+        parquet_reader: None,
+        json_reader: None,
     });
     Ok(Box::into_raw(builder))
 }
@@ -477,8 +485,12 @@ pub unsafe extern "C" fn set_builder_option(
 
 /// Consume the builder and return a `default` engine. After calling, the passed pointer is _no
 /// longer valid_. Note that this _consumes_ and frees the builder, so there is no need to
-/// drop/free it afterwards.
+/// drop/free it afterwards. If any file readers were registered on the builder (see
+/// [`set_builder_parquet_reader`] and [`set_builder_json_reader`]), the returned engine uses them
+/// in place of the default engine's readers.
 ///
+/// [`set_builder_parquet_reader`]: crate::engine_readers::set_builder_parquet_reader
+/// [`set_builder_json_reader`]: crate::engine_readers::set_builder_json_reader
 ///
 /// # Safety
 ///
@@ -489,12 +501,19 @@ pub unsafe extern "C" fn builder_build(
     builder: *mut EngineBuilder,
 ) -> ExternResult<Handle<SharedExternEngine>> {
     let builder_box = unsafe { Box::from_raw(builder) };
-    get_default_engine_impl(
-        builder_box.url,
-        builder_box.options,
-        builder_box.allocate_fn,
-    )
-    .into_extern_result(&builder_box.allocate_fn)
+    let allocate_fn = builder_box.allocate_fn;
+    builder_build_impl(*builder_box).into_extern_result(&allocate_fn)
+}
+
+#[cfg(feature = "default-engine-base")]
+fn builder_build_impl(builder: EngineBuilder) -> DeltaResult<Handle<SharedExternEngine>> {
+    let engine = get_default_engine_arc(&builder.url, builder.options)?;
+    let engine = engine_readers::ExternReaderEngine::wrap(
+        engine,
+        builder.parquet_reader,
+        builder.json_reader,
+    );
+    Ok(engine_to_handle(engine, builder.allocate_fn))
 }
 
 /// # Safety
@@ -540,14 +559,23 @@ fn get_default_engine_impl(
     options: HashMap<String, String>,
     allocate_error: AllocateErrorFn,
 ) -> DeltaResult<Handle<SharedExternEngine>> {
+    let engine = get_default_engine_arc(&url, options)?;
+    Ok(engine_to_handle(engine, allocate_error))
+}
+
+#[cfg(feature = "default-engine-base")]
+fn get_default_engine_arc(
+    url: &Url,
+    options: HashMap<String, String>,
+) -> DeltaResult<Arc<dyn Engine>> {
     use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
     use delta_kernel::engine::default::DefaultEngine;
     let engine = DefaultEngine::<TokioBackgroundExecutor>::try_new(
-        &url,
+        url,
         options,
         Arc::new(TokioBackgroundExecutor::new()),
-    );
-    Ok(engine_to_handle(Arc::new(engine?), allocate_error))
+    )?;
+    Ok(Arc::new(engine))
 }
 
 /// # Safety
@@ -4,7 +4,23 @@ use std::collections::HashMap;
 use std::ffi::c_void;
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::arrow::array::{RecordBatch, RecordBatchReader};
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::arrow::compute::filter_record_batch;
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::arrow::error::ArrowError;
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::arrow::ffi_stream::FFI_ArrowArrayStream;
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::engine::arrow_conversion::TryFromKernel as _;
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::scan::state::DvInfo;
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::scan::ScanResult;
 use delta_kernel::scan::{Scan, ScanMetadata};
 use delta_kernel::snapshot::SnapshotRef;
 use delta_kernel::{DeltaResult, Error, Expression, ExpressionRef};
@@ -295,10 +311,12 @@ pub struct CDvInfo<'a> {
 /// * `context`: a `void*` context this can be anything that engine needs to pass through to each call
 /// * `path`: a `KernelStringSlice` which is the path to the file
 /// * `size`: an `i64` which is the size of the file
-/// * `dv_info`: a [`CDvInfo`] struct, which allows getting the selection vector for this file
+/// * `dv_info`: a [`CDvInfo`] struct, which allows getting the selection vector for this file, or
+///   the raw descriptor of its deletion vector (see [`visit_deletion_vector_descriptor`])
 /// * `transform`: An optional expression that, if not `NULL`, _must_ be applied to physical data to
 ///   convert it to the correct logical format. If this is `NULL`, no transform is needed.
 /// * `partition_values`: [DEPRECATED] a `HashMap<String, String>` which are partition values
+/// * `tags`: a [`CStringMap`] of the tags of the file's add action, empty if it has none
 type CScanCallback = extern "C" fn(
     engine_context: NullableCvoid,
     path: KernelStringSlice,
@@ -307,6 +325,7 @@ type CScanCallback = extern "C" fn(
     dv_info: &CDvInfo,
     transform: Option<&Expression>,
     partition_map: &CStringMap,
+    tags: &CStringMap,
 );
 
 #[derive(Default)]
@@ -450,6 +469,50 @@ fn row_indexes_from_dv_impl(
     }
 }
 
+/// Visit the raw descriptor of the deletion vector of a [`DvInfo`] struct, for engines that apply
+/// deletion vectors themselves. If there is a deletion vector, kernel calls `visitor` once with its
+/// fields and returns true. Otherwise, it returns false without calling `visitor`.
+///
+/// The arguments to the visitor are:
+/// * `engine_context`: the `engine_context` passed to this function
+/// * `storage_type`: how the DV is stored, `u` (path relative to the table root), `i` (inline) or
+///   `p` (absolute path)
+/// * `path_or_inline_dv`: the encoded path of the DV file, or the base85 encoded DV if inline
+/// * `offset`: the start of the DV in its file, or `NULL` if the DV is inline
+/// * `size_in_bytes`: the size of the serialized DV
+/// * `cardinality`: the number of rows the DV removes from the data file
+///
+/// # Safety
+/// Engine is responsible for providing a valid [`DvInfo`] pointer and visitor
+#[no_mangle]
+pub unsafe extern "C" fn visit_deletion_vector_descriptor(
+    dv_info: &DvInfo,
+    engine_context: NullableCvoid,
+    visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        storage_type: KernelStringSlice,
+        path_or_inline_dv: KernelStringSlice,
+        offset: Option<&i32>,
+        size_in_bytes: i32,
+        cardinality: i64,
+    ),
+) -> bool {
+    let Some(dv) = dv_info.deletion_vector() else {
+        return false;
+    };
+    let storage_type = &dv.storage_type;
+    let path_or_inline_dv = &dv.path_or_inline_dv;
+    visitor(
+        engine_context,
+        kernel_string_slice!(storage_type),
+        kernel_string_slice!(path_or_inline_dv),
+        dv.offset.as_ref(),
+        dv.size_in_bytes,
+        dv.cardinality,
+    );
+    true
+}
+
 // Wrapper function that gets called by the kernel, transforms the arguments to make the ffi-able,
 // and then calls the ffi specified callback
 fn rust_callback(
@@ -460,11 +523,13 @@ fn rust_callback(
     dv_info: DvInfo,
     transform: Option<ExpressionRef>,
     partition_values: HashMap<String, String>,
+    tags: HashMap<String, String>,
 ) {
     let transform = transform.map(|e| e.as_ref().clone());
     let partition_map = CStringMap {
         values: partition_values,
     };
+    let tags = CStringMap { values: tags };
     let stats = kernel_stats.map(|ks| Stats {
         num_records: ks.num_records,
     });
@@ -480,6 +545,7 @@ fn rust_callback(
         &cdv_info,
         transform.as_ref(),
         &partition_map,
+        &tags,
     );
 }
 
@@ -512,6 +578,112 @@ pub unsafe extern "C" fn visit_scan_metadata(
         .unwrap();
 }
 
+/// An arrow [`RecordBatchReader`] over the results of [`Scan::execute`]. Each batch has already
+/// been transformed into the logical schema of the scan, and has had its selection vector (if any)
+/// applied, so consumers of the stream see exactly the rows of the table.
+#[cfg(feature = "default-engine-base")]
+struct ScanResultReader {
+    results: Box<dyn Iterator<Item = DeltaResult<ScanResult>> + Send>,
+    schema: ArrowSchemaRef,
+}
+
+#[cfg(feature = "default-engine-base")]
+impl ScanResultReader {
+    fn try_new(scan: &Scan, engine: Arc<dyn delta_kernel::Engine>) -> DeltaResult<Self> {
+        let schema = ArrowSchema::try_from_kernel(scan.logical_schema().as_ref())?;
+        Ok(Self {
+            results: Box::new(scan.execute(engine)?),
+            schema: Arc::new(schema),
+        })
+    }
+
+    fn next_batch(&mut self) -> Option<DeltaResult<RecordBatch>> {
+        let result = self.results.next()?;
+        Some(result.and_then(|scan_result| {
+            let mask = scan_result.full_mask_buffer();
+            let batch: RecordBatch = scan_result
+                .raw_data?
+                .into_any()
+                .downcast::<ArrowEngineData>()
+                .map_err(|_| Error::EngineDataType("ArrowEngineData".to_string()))?
+                .into();
+            let batch = match mask {
+                Some(mask) => filter_record_batch(&batch, &mask.into())?,
+                None => batch,
+            };
+            // Re-attach the scan's logical schema, so that every batch in the stream reports
+            // exactly the schema advertised by the stream itself.
+            Ok(RecordBatch::try_new(
+                self.schema.clone(),
+                batch.columns().to_vec(),
+            )?)
+        }))
+    }
+}
+
+#[cfg(feature = "default-engine-base")]
+impl Iterator for ScanResultReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().map(|res| {
+            res.map_err(|err| match err {
+                Error::Arrow(err) => err,
+                err => ArrowError::ExternalError(Box::new(err)),
+            })
+        })
+    }
+}
+
+#[cfg(feature = "default-engine-base")]
+impl RecordBatchReader for ScanResultReader {
+    fn schema(&self) -> ArrowSchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Execute a scan and export its results as an arrow [C stream
+/// interface](https://arrow.apache.org/docs/format/CStreamInterface.html). The batches produced by
+/// the stream are already in the logical schema of the scan (i.e. any transforms for partition
+/// values or column mapping have been applied), and rows removed by deletion vectors have already
+/// been filtered out. This allows engines to consume a scan without driving the
+/// visit/read/transform process across the FFI boundary themselves.
+///
+/// On success, `out_stream` is initialized and `true` is returned. The _engine_ then owns the
+/// stream, and must release it by calling its `release` callback once done, as required by the C
+/// stream interface. The stream keeps the scan and engine alive on its own, so both handles may be
+/// freed before the stream is fully consumed.
+///
+/// # Safety
+///
+/// Engine is responsible for passing a valid [`SharedScan`] and [`SharedExternEngine`], and
+/// `out_stream` must be a valid pointer to (possibly uninitialized) memory for an
+/// `FFI_ArrowArrayStream`.
+#[cfg(feature = "default-engine-base")]
+#[no_mangle]
+pub unsafe extern "C" fn scan_to_arrow_stream(
+    scan: Handle<SharedScan>,
+    engine: Handle<SharedExternEngine>,
+    out_stream: *mut FFI_ArrowArrayStream,
+) -> ExternResult<bool> {
+    let engine = unsafe { engine.clone_as_arc() };
+    let scan = unsafe { scan.as_ref() };
+    let res = scan_to_arrow_stream_impl(scan, engine.engine()).map(|stream| {
+        unsafe { std::ptr::write(out_stream, stream) };
+        true
+    });
+    res.into_extern_result(&engine.as_ref())
+}
+
+#[cfg(feature = "default-engine-base")]
+fn scan_to_arrow_stream_impl(
+    scan: &Scan,
+    engine: Arc<dyn delta_kernel::Engine>,
+) -> DeltaResult<FFI_ArrowArrayStream> {
+    let reader = ScanResultReader::try_new(scan, engine)?;
+    Ok(FFI_ArrowArrayStream::new(Box::new(reader)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, ptr::NonNull};
@@ -549,4 +721,92 @@ mod tests {
         let final_map: HashMap<String, String> = *unsafe { Box::from_raw(map_ptr) };
         assert_eq!(test_map, final_map);
     }
+
+    extern "C" fn visit_dv(
+        engine_context: NullableCvoid,
+        storage_type: KernelStringSlice,
+        path_or_inline_dv: KernelStringSlice,
+        offset: Option<&i32>,
+        size_in_bytes: i32,
+        cardinality: i64,
+    ) {
+        let dvs_ptr: *mut Vec<String> = engine_context.unwrap().as_ptr().cast();
+        let storage_type = unsafe { String::try_from_slice(&storage_type).unwrap() };
+        let path = unsafe { String::try_from_slice(&path_or_inline_dv).unwrap() };
+        let dv = format!("{storage_type}:{path}:{offset:?}:{size_in_bytes}:{cardinality}");
+        unsafe {
+            (*dvs_ptr).push(dv);
+        }
+    }
+
+    #[test]
+    fn visit_deletion_vector_descriptor() {
+        use delta_kernel::actions::deletion_vector::DeletionVectorDescriptor;
+        use delta_kernel::scan::state::DvInfo;
+
+        let dv_info = DvInfo::from(DeletionVectorDescriptor {
+            storage_type: "u".into(),
+            path_or_inline_dv: "vBn[lx{q8@P<9BNH/isA".into(),
+            offset: Some(1),
+            size_in_bytes: 36,
+            cardinality: 2,
+        });
+        let dvs_ptr: *mut Vec<String> = Box::into_raw(Box::default());
+        unsafe {
+            let ptr = NonNull::new_unchecked(dvs_ptr.cast());
+            assert!(super::visit_deletion_vector_descriptor(
+                &dv_info,
+                Some(ptr),
+                visit_dv
+            ));
+            assert!(!super::visit_deletion_vector_descriptor(
+                &DvInfo::default(),
+                Some(ptr),
+                visit_dv
+            ));
+        }
+        let dvs: Vec<String> = *unsafe { Box::from_raw(dvs_ptr) };
+        assert_eq!(dvs, ["u:vBn[lx{q8@P<9BNH/isA:Some(1):36:2"]);
+    }
+
+    #[test]
+    #[cfg(feature = "default-engine-base")]
+    fn scan_to_arrow_stream_applies_deletion_vectors() {
+        use delta_kernel::arrow::array::RecordBatchReader as _;
+        use delta_kernel::arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+
+        use crate::ffi_test_utils::ok_or_panic;
+        use crate::tests::get_default_engine;
+        use crate::{free_engine, free_snapshot, kernel_string_slice, snapshot};
+
+        let path = std::fs::canonicalize("../kernel/tests/data/table-with-dv-small/").unwrap();
+        let path = url::Url::from_directory_path(path).unwrap().to_string();
+        let engine = get_default_engine(&path);
+        unsafe {
+            let snapshot = ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy()));
+            let scan = ok_or_panic(super::scan(
+                snapshot.shallow_copy(),
+                engine.shallow_copy(),
+                None,
+            ));
+            let mut stream = FFI_ArrowArrayStream::empty();
+            assert!(ok_or_panic(super::scan_to_arrow_stream(
+                scan.shallow_copy(),
+                engine.shallow_copy(),
+                &mut stream,
+            )));
+            // the stream must remain usable after all kernel handles are gone
+            super::free_scan(scan);
+            free_snapshot(snapshot);
+            free_engine(engine);
+
+            let reader = ArrowArrayStreamReader::try_new(stream).unwrap();
+            let schema = reader.schema();
+            assert_eq!(schema.fields().len(), 1);
+            assert_eq!(schema.field(0).name(), "value");
+            let num_rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+            // the table has 10 rows, two of which are deleted by a deletion vector
+            assert_eq!(num_rows, 8);
+        }
+    }
 }
@@ -2,8 +2,15 @@ use std::os::raw::c_void;
 
 use crate::handle::Handle;
 use crate::scan::CStringMap;
-use crate::{kernel_string_slice, KernelStringSlice, SharedSchema};
-use delta_kernel::schema::{ArrayType, DataType, MapType, PrimitiveType, StructType};
+use crate::{
+    kernel_string_slice, ExternResult, IntoExternResult, KernelStringSlice, SharedExternEngine,
+    SharedSchema, TryFromStringSlice,
+};
+use delta_kernel::schema::{
+    ArrayType, ColumnMetadataKey, DataType, MapType, MetadataValue, PrimitiveType, StructField,
+    StructType,
+};
+use delta_kernel::{DeltaResult, Error};
 
 /// The `EngineSchemaVisitor` defines a visitor system to allow engines to build their own
 /// representation of a schema from a particular schema within kernel.
@@ -30,6 +37,17 @@ use delta_kernel::schema::{ArrayType, DataType, MapType, PrimitiveType, StructTy
 ///  3. When visiting a complex schema element, the kernel also passes the "child list" containing
 ///     that element's (already-visited) children.
 ///  4. The [`visit_schema`] method returns the id of the list of top-level columns
+///
+/// If the engine provides the optional `visit_column_mapping` callback, the kernel additionally
+/// invokes it for every struct field that carries column mapping information, immediately after the
+/// field itself was visited (see the callback's documentation for details). Likewise, the optional
+/// `visit_generation_expression`, `visit_default_value` and `visit_invariant` callbacks report the Delta metadata of struct fields that carry it, already extracted from the
+/// field metadata, and the optional `visit_field_ordinal` callback reports the position of every
+/// struct field in its struct.
+///
+/// The fields of a struct are always visited in schema order, and every complex type is visited
+/// only after all of its children were visited. [`visit_schema_projected`] visits only some of the
+/// top-level columns, in the order the engine requested them.
 // WARNING: the visitor MUST NOT retain internal references to the string slices passed to visitor methods
 #[repr(C)]
 pub struct EngineSchemaVisitor {
@@ -200,6 +218,53 @@ pub struct EngineSchemaVisitor {
         is_nullable: bool,
         metadata: &CStringMap,
     ),
+
+    /// Optional (may be `NULL`). Provide the column mapping information of the struct field that
+    /// was most recently visited in the list identified by `sibling_list_id`. It is invoked
+    /// immediately after the `visit_*` call for that field, with the field's column mapping
+    /// `field_id` and `physical_name`. Engines can use it to map logical columns to parquet columns
+    /// without having to parse the field metadata.
+    ///
+    /// Only fields of tables with column mapping enabled carry this information, so this is never
+    /// invoked for tables without column mapping, nor for array elements or map keys/values.
+    pub visit_column_mapping: Option<
+        extern "C" fn(
+            data: *mut c_void,
+            sibling_list_id: usize,
+            field_id: i64,
+            physical_name: KernelStringSlice,
+        ),
+    >,
+
+    /// Optional (may be `NULL`). Provide the generation expression (a SQL expression string) of
+    /// the generated column that was most recently visited in the list identified by
+    /// `sibling_list_id`.
+    pub visit_generation_expression: Option<
+        extern "C" fn(data: *mut c_void, sibling_list_id: usize, expression: KernelStringSlice),
+    >,
+
+    /// Optional (may be `NULL`). Provide the default value (a SQL expression string) of the column
+    /// that was most recently visited in the list identified by `sibling_list_id`.
+    pub visit_default_value: Option<
+        extern "C" fn(data: *mut c_void, sibling_list_id: usize, default_value: KernelStringSlice),
+    >,
+
+    /// Optional (may be `NULL`). Provide the invariant (a SQL expression string every row must
+    /// satisfy) of the column that was most recently visited in the list identified by
+    /// `sibling_list_id`. The expression is extracted from the JSON the invariant is stored as.
+    pub visit_invariant: Option<
+        extern "C" fn(data: *mut c_void, sibling_list_id: usize, expression: KernelStringSlice),
+    >,
+
+    /// Optional (may be `NULL`). Provide the ordinal (zero-based position) of the struct field
+    /// that was most recently visited in the list identified by `sibling_list_id`, within the
+    /// struct it belongs to. It is invoked after all other callbacks for that field.
+    ///
+    /// The ordinal refers to the full schema, so it differs from the field's position in its list
+    /// when visiting a projection of the schema with [`visit_schema_projected`]. It is never
+    /// invoked for array elements or map keys/values.
+    pub visit_field_ordinal:
+        Option<extern "C" fn(data: *mut c_void, sibling_list_id: usize, ordinal: usize)>,
 }
 
 /// Visit the given `schema` using the provided `visitor`. See the documentation of
@@ -219,11 +284,87 @@ pub unsafe extern "C" fn visit_schema(
     visit_schema_impl(schema, visitor)
 }
 
+/// Visit only the top-level columns of the given `schema` named in `column_names` (an array of
+/// `num_column_names` names), using the provided `visitor`. Engines that bind to very wide schemas
+/// can use it to visit just the columns a query needs.
+///
+/// The columns are visited in the order of `column_names`, and nested columns of the visited
+/// columns are visited in full. Set the visitor's `visit_field_ordinal` callback to learn the
+/// position of each visited column in the full schema. Fails if a name does not match any column,
+/// or if it is repeated.
+///
+/// This method returns the id of the list allocated to hold the visited top level columns.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid schema handle, engine handle and schema visitor, and
+/// for `column_names` to point to `num_column_names` valid string slices (it may be null if
+/// `num_column_names` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn visit_schema_projected(
+    schema: Handle<SharedSchema>,
+    column_names: *const KernelStringSlice,
+    num_column_names: usize,
+    engine: Handle<SharedExternEngine>,
+    visitor: &mut EngineSchemaVisitor,
+) -> ExternResult<usize> {
+    let schema = unsafe { schema.as_ref() };
+    let column_names: &[KernelStringSlice] = match num_column_names {
+        0 => &[],
+        len => unsafe { std::slice::from_raw_parts(column_names, len) },
+    };
+    let column_names: DeltaResult<Vec<&str>> = column_names
+        .iter()
+        .map(|name| unsafe { TryFromStringSlice::try_from_slice(name) })
+        .collect();
+    column_names
+        .and_then(|column_names| visit_schema_projected_impl(schema, &column_names, visitor))
+        .into_extern_result(&engine.as_ref())
+}
+
 fn visit_schema_impl(schema: &StructType, visitor: &mut EngineSchemaVisitor) -> usize {
-    // Visit all the fields of a struct and return the list of children
-    fn visit_struct_fields(visitor: &EngineSchemaVisitor, s: &StructType) -> usize {
-        let child_list_id = (visitor.make_field_list)(visitor.data, s.num_fields());
-        for field in s.fields() {
+    visit_fields_impl(schema.fields().enumerate(), visitor)
+}
+
+fn visit_schema_projected_impl(
+    schema: &StructType,
+    column_names: &[&str],
+    visitor: &mut EngineSchemaVisitor,
+) -> DeltaResult<usize> {
+    let mut fields: Vec<(usize, &StructField)> = Vec::with_capacity(column_names.len());
+    let mut unknown = vec![];
+    for name in column_names {
+        match schema.field_with_index(name) {
+            Some((ordinal, _)) if fields.iter().any(|(visited, _)| *visited == ordinal) => {
+                return Err(Error::generic(format!(
+                    "Column {name} is projected more than once"
+                )));
+            }
+            Some(field) => fields.push(field),
+            None => unknown.push(*name),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(Error::missing_column(format!(
+            "Schema has no columns named {}",
+            unknown.join(", ")
+        )));
+    }
+    Ok(visit_fields_impl(fields.into_iter(), visitor))
+}
+
+// Visit the given (ordinal, field) pairs, and return the id of the list holding them
+fn visit_fields_impl<'a>(
+    fields: impl ExactSizeIterator<Item = (usize, &'a StructField)>,
+    visitor: &EngineSchemaVisitor,
+) -> usize {
+    // Visit (ordinal, field) pairs of a struct and return the list of children
+    fn visit_fields<'a>(
+        visitor: &EngineSchemaVisitor,
+        fields: impl ExactSizeIterator<Item = (usize, &'a StructField)>,
+    ) -> usize {
+        let child_list_id = (visitor.make_field_list)(visitor.data, fields.len());
+        for (ordinal, field) in fields {
             visit_schema_item(
                 field.name(),
                 field.data_type(),
@@ -232,10 +373,78 @@ fn visit_schema_impl(schema: &StructType, visitor: &mut EngineSchemaVisitor) ->
                 visitor,
                 child_list_id,
             );
+            visit_column_mapping(visitor, field, child_list_id);
+            visit_field_metadata(visitor, field, child_list_id);
+            if let Some(visit_field_ordinal) = visitor.visit_field_ordinal {
+                visit_field_ordinal(visitor.data, child_list_id, ordinal);
+            }
         }
         child_list_id
     }
 
+    // If the engine asked for it, report the column mapping information of a struct field
+    fn visit_column_mapping(
+        visitor: &EngineSchemaVisitor,
+        field: &StructField,
+        sibling_list_id: usize,
+    ) {
+        let Some(visit_column_mapping) = visitor.visit_column_mapping else {
+            return;
+        };
+        let field_id = field.get_config_value(&ColumnMetadataKey::ColumnMappingId);
+        let physical_name = field.get_config_value(&ColumnMetadataKey::ColumnMappingPhysicalName);
+        if let (Some(MetadataValue::Number(field_id)), Some(MetadataValue::String(physical_name))) =
+            (field_id, physical_name)
+        {
+            visit_column_mapping(
+                visitor.data,
+                sibling_list_id,
+                *field_id,
+                kernel_string_slice!(physical_name),
+            );
+        }
+    }
+
+    // Report the Delta metadata of a struct field the engine asked for
+    fn visit_field_metadata(
+        visitor: &EngineSchemaVisitor,
+        field: &StructField,
+        sibling_list_id: usize,
+    ) {
+        let string_value = |key| match field.get_config_value(&key) {
+            Some(MetadataValue::String(value)) => Some(value.as_str()),
+            _ => None,
+        };
+        if let Some(visit_generation_expression) = visitor.visit_generation_expression {
+            if let Some(expression) = string_value(ColumnMetadataKey::GenerationExpression) {
+                visit_generation_expression(
+                    visitor.data,
+                    sibling_list_id,
+                    kernel_string_slice!(expression),
+                );
+            }
+        }
+        if let Some(visit_default_value) = visitor.visit_default_value {
+            if let Some(default_value) = string_value(ColumnMetadataKey::CurrentDefault) {
+                visit_default_value(
+                    visitor.data,
+                    sibling_list_id,
+                    kernel_string_slice!(default_value),
+                );
+            }
+        }
+        if let Some(visit_invariant) = visitor.visit_invariant {
+            if let Some(invariant) = string_value(ColumnMetadataKey::Invariants) {
+                let expression = invariant_expression(invariant);
+                visit_invariant(
+                    visitor.data,
+                    sibling_list_id,
+                    kernel_string_slice!(expression),
+                );
+            }
+        }
+    }
+
     fn visit_array_item(
         visitor: &EngineSchemaVisitor,
         at: &ArrayType,
@@ -302,7 +511,9 @@ fn visit_schema_impl(schema: &StructType, visitor: &mut EngineSchemaVisitor) ->
             };
         }
         match data_type {
-            DataType::Struct(st) => call!(visit_struct, visit_struct_fields(visitor, st)),
+            DataType::Struct(st) => {
+                call!(visit_struct, visit_fields(visitor, st.fields().enumerate()))
+            }
             DataType::Map(mt) => {
                 call!(
                     visit_map,
@@ -331,5 +542,337 @@ fn visit_schema_impl(schema: &StructType, visitor: &mut EngineSchemaVisitor) ->
         }
     }
 
-    visit_struct_fields(visitor, schema)
+    visit_fields(visitor, fields)
+}
+
+/// Extracts the SQL expression of an invariant, which Delta stores as JSON of the form
+/// `{"expression": {"expression": "<sql>"}}`. Invariants that are not in this form (e.g. plain SQL
+/// written by other writers) are returned as they are.
+fn invariant_expression(invariant: &str) -> String {
+    let json: Option<serde_json::Value> = serde_json::from_str(invariant).ok();
+    let expression = json.as_ref().and_then(|json| {
+        json.pointer("/expression/expression")
+            .or_else(|| json.get("expression"))
+            .and_then(serde_json::Value::as_str)
+    });
+    expression.unwrap_or(invariant).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::error::KernelError;
+    use crate::ffi_test_utils::{ok_or_panic, recover_error};
+    use crate::tests::get_default_engine;
+    use crate::{free_engine, TryFromStringSlice};
+
+    #[derive(Default)]
+    struct ColumnMappings {
+        next_list_id: usize,
+        // (list id, name) of the most recently visited field
+        last_visited: Option<(usize, String)>,
+        mappings: HashMap<String, (i64, String)>,
+        // (list id, name, ordinal) of every visited struct field, in visiting order
+        ordinals: Vec<(usize, String, usize)>,
+        // (name, kind, value) of the reported field metadata, in visiting order
+        field_metadata: Vec<(String, &'static str, String)>,
+    }
+
+    fn state(data: *mut c_void) -> &'static mut ColumnMappings {
+        unsafe { &mut *(data as *mut ColumnMappings) }
+    }
+
+    extern "C" fn make_field_list(data: *mut c_void, _reserve: usize) -> usize {
+        let state = state(data);
+        state.next_list_id += 1;
+        state.next_list_id
+    }
+
+    extern "C" fn visit_leaf(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        name: KernelStringSlice,
+        _is_nullable: bool,
+        _metadata: &CStringMap,
+    ) {
+        let name = unsafe { String::try_from_slice(&name) }.unwrap();
+        state(data).last_visited = Some((sibling_list_id, name));
+    }
+
+    extern "C" fn visit_nested(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        name: KernelStringSlice,
+        is_nullable: bool,
+        metadata: &CStringMap,
+        _child_list_id: usize,
+    ) {
+        visit_leaf(data, sibling_list_id, name, is_nullable, metadata)
+    }
+
+    extern "C" fn visit_decimal(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        name: KernelStringSlice,
+        is_nullable: bool,
+        metadata: &CStringMap,
+        _precision: u8,
+        _scale: u8,
+    ) {
+        visit_leaf(data, sibling_list_id, name, is_nullable, metadata)
+    }
+
+    extern "C" fn visit_column_mapping(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        field_id: i64,
+        physical_name: KernelStringSlice,
+    ) {
+        let state = state(data);
+        let (list_id, name) = state.last_visited.clone().unwrap();
+        assert_eq!(list_id, sibling_list_id);
+        let physical_name = unsafe { String::try_from_slice(&physical_name) }.unwrap();
+        state.mappings.insert(name, (field_id, physical_name));
+    }
+
+    fn record_metadata(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        kind: &'static str,
+        value: String,
+    ) {
+        let state = state(data);
+        let (list_id, name) = state.last_visited.clone().unwrap();
+        assert_eq!(list_id, sibling_list_id);
+        state.field_metadata.push((name, kind, value));
+    }
+
+    extern "C" fn visit_generation_expression(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        expression: KernelStringSlice,
+    ) {
+        let expression = unsafe { String::try_from_slice(&expression) }.unwrap();
+        record_metadata(data, sibling_list_id, "generation", expression);
+    }
+
+    extern "C" fn visit_default_value(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        default_value: KernelStringSlice,
+    ) {
+        let default_value = unsafe { String::try_from_slice(&default_value) }.unwrap();
+        record_metadata(data, sibling_list_id, "default", default_value);
+    }
+
+    extern "C" fn visit_invariant(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        expression: KernelStringSlice,
+    ) {
+        let expression = unsafe { String::try_from_slice(&expression) }.unwrap();
+        record_metadata(data, sibling_list_id, "invariant", expression);
+    }
+
+    extern "C" fn visit_field_ordinal(data: *mut c_void, sibling_list_id: usize, ordinal: usize) {
+        let state = state(data);
+        let (list_id, name) = state.last_visited.take().unwrap();
+        assert_eq!(list_id, sibling_list_id);
+        state.ordinals.push((list_id, name, ordinal));
+    }
+
+    fn visitor(state: &mut ColumnMappings) -> EngineSchemaVisitor {
+        EngineSchemaVisitor {
+            data: state as *mut ColumnMappings as *mut c_void,
+            make_field_list,
+            visit_struct: visit_nested,
+            visit_array: visit_nested,
+            visit_map: visit_nested,
+            visit_decimal,
+            visit_string: visit_leaf,
+            visit_long: visit_leaf,
+            visit_integer: visit_leaf,
+            visit_short: visit_leaf,
+            visit_byte: visit_leaf,
+            visit_float: visit_leaf,
+            visit_double: visit_leaf,
+            visit_boolean: visit_leaf,
+            visit_binary: visit_leaf,
+            visit_date: visit_leaf,
+            visit_timestamp: visit_leaf,
+            visit_timestamp_ntz: visit_leaf,
+            visit_variant: visit_leaf,
+            visit_column_mapping: Some(visit_column_mapping),
+            visit_generation_expression: Some(visit_generation_expression),
+            visit_default_value: Some(visit_default_value),
+            visit_invariant: Some(visit_invariant),
+            visit_field_ordinal: Some(visit_field_ordinal),
+        }
+    }
+
+    fn mapped_field(name: &str, data_type: DataType, id: i64) -> StructField {
+        StructField::nullable(name, data_type).with_metadata([
+            (
+                ColumnMetadataKey::ColumnMappingId.as_ref(),
+                MetadataValue::Number(id),
+            ),
+            (
+                ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+                MetadataValue::String(format!("col-{id}")),
+            ),
+        ])
+    }
+
+    #[test]
+    fn visit_column_mapping_info() {
+        let inner = StructType::try_new([mapped_field("b", DataType::LONG, 2)]).unwrap();
+        let schema = Arc::new(
+            StructType::try_new([
+                mapped_field("a", DataType::Struct(Box::new(inner)), 1),
+                StructField::nullable("c", DataType::STRING),
+            ])
+            .unwrap(),
+        );
+
+        let mut state = ColumnMappings::default();
+        let mut visitor = visitor(&mut state);
+        unsafe { visit_schema(schema.into(), &mut visitor) };
+
+        let expected = HashMap::from([
+            ("a".to_string(), (1, "col-1".to_string())),
+            ("b".to_string(), (2, "col-2".to_string())),
+        ]);
+        assert_eq!(state.mappings, expected);
+    }
+
+    #[test]
+    fn visit_field_metadata_info() {
+        let schema = Arc::new(
+            StructType::try_new([
+                mapped_field("a", DataType::LONG, 1).add_metadata([(
+                    ColumnMetadataKey::GenerationExpression.as_ref(),
+                    MetadataValue::from("b + 1"),
+                )]),
+                StructField::nullable("b", DataType::INTEGER).with_metadata([
+                    (
+                        ColumnMetadataKey::CurrentDefault.as_ref(),
+                        MetadataValue::from("42"),
+                    ),
+                    (
+                        ColumnMetadataKey::Invariants.as_ref(),
+                        MetadataValue::from(r#"{"expression":{"expression":"b > 0"}}"#),
+                    ),
+                ]),
+                StructField::nullable("c", DataType::STRING),
+            ])
+            .unwrap(),
+        );
+
+        let mut state = ColumnMappings::default();
+        let mut visitor = visitor(&mut state);
+        unsafe { visit_schema(schema.into(), &mut visitor) };
+
+        let expected = [
+            ("a", "generation", "b + 1"),
+            ("b", "default", "42"),
+            ("b", "invariant", "b > 0"),
+        ]
+        .map(|(name, kind, value)| (name.to_string(), kind, value.to_string()));
+        assert_eq!(state.field_metadata, expected);
+        // field ids are reported along with the physical name, by `visit_column_mapping`
+        let expected = HashMap::from([("a".to_string(), (1, "col-1".to_string()))]);
+        assert_eq!(state.mappings, expected);
+    }
+
+    #[test]
+    fn test_invariant_expression() {
+        assert_eq!(
+            invariant_expression(r#"{"expression":{"expression":"x > 3"}}"#),
+            "x > 3"
+        );
+        assert_eq!(invariant_expression("x > 3"), "x > 3");
+    }
+
+    fn wide_schema() -> Handle<SharedSchema> {
+        let inner = StructType::try_new([StructField::nullable("c", DataType::STRING)]).unwrap();
+        let schema = StructType::try_new([
+            StructField::nullable("a", DataType::LONG),
+            StructField::nullable("b", DataType::Struct(Box::new(inner))),
+            StructField::nullable("d", DataType::DATE),
+        ])
+        .unwrap();
+        Arc::new(schema).into()
+    }
+
+    #[test]
+    fn visit_projected_schema() {
+        let engine = get_default_engine("memory:///doesntmatter/foo");
+        let schema = wide_schema();
+        let names: Vec<_> = ["d", "b"]
+            .iter()
+            .map(|name| kernel_string_slice!(name))
+            .collect();
+
+        let mut state = ColumnMappings::default();
+        let mut visitor = visitor(&mut state);
+        let list_id = unsafe {
+            ok_or_panic(visit_schema_projected(
+                schema.shallow_copy(),
+                names.as_ptr(),
+                names.len(),
+                engine.shallow_copy(),
+                &mut visitor,
+            ))
+        };
+
+        // columns are visited in the requested order, with their ordinals in the full schema
+        assert_eq!(list_id, 1);
+        let expected = [
+            (1, "d".to_string(), 2),
+            (2, "c".to_string(), 0),
+            (1, "b".to_string(), 1),
+        ];
+        assert_eq!(state.ordinals, expected);
+        unsafe {
+            schema.drop_handle();
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn visit_projected_schema_unknown_columns() {
+        let engine = get_default_engine("memory:///doesntmatter/foo");
+        let schema = wide_schema();
+        let names: Vec<_> = ["a", "x", "y"]
+            .iter()
+            .map(|name| kernel_string_slice!(name))
+            .collect();
+
+        let mut state = ColumnMappings::default();
+        let mut visitor = visitor(&mut state);
+        let result = unsafe {
+            visit_schema_projected(
+                schema.shallow_copy(),
+                names.as_ptr(),
+                names.len(),
+                engine.shallow_copy(),
+                &mut visitor,
+            )
+        };
+        let ExternResult::Err(err) = result else {
+            panic!("Expected an error for unknown columns");
+        };
+        let err = unsafe { recover_error(err) };
+        assert_eq!(err.etype, KernelError::MissingColumnError);
+        assert!(err.message.contains("Schema has no columns named x, y"));
+        // nothing was visited
+        assert!(state.ordinals.is_empty());
+        unsafe {
+            schema.drop_handle();
+            free_engine(engine);
+        }
+    }
 }
@@ -77,6 +77,61 @@ fn with_engine_info_impl(
     Ok(Box::new(txn.with_engine_info(info_string?)).into())
 }
 
+/// Sets the configuration of a user domain in the transaction. The domain metadata is written to
+/// the log during commit. See [`Transaction::with_domain_metadata`] for details.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid handle. CONSUMES TRANSACTION
+#[no_mangle]
+pub unsafe extern "C" fn with_domain_metadata(
+    txn: Handle<ExclusiveTransaction>,
+    domain: KernelStringSlice,
+    configuration: KernelStringSlice,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<ExclusiveTransaction>> {
+    let txn = unsafe { txn.into_inner() };
+    let engine = unsafe { engine.as_ref() };
+    let domain = unsafe { String::try_from_slice(&domain) };
+    let configuration = unsafe { String::try_from_slice(&configuration) };
+
+    with_domain_metadata_impl(*txn, domain, configuration).into_extern_result(&engine)
+}
+
+fn with_domain_metadata_impl(
+    txn: Transaction,
+    domain: DeltaResult<String>,
+    configuration: DeltaResult<String>,
+) -> DeltaResult<Handle<ExclusiveTransaction>> {
+    Ok(Box::new(txn.with_domain_metadata(domain?, configuration?)).into())
+}
+
+/// Removes a user domain in the transaction. The removal is written to the log during commit. See
+/// [`Transaction::with_domain_metadata_removed`] for details.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid handle. CONSUMES TRANSACTION
+#[no_mangle]
+pub unsafe extern "C" fn with_domain_metadata_removed(
+    txn: Handle<ExclusiveTransaction>,
+    domain: KernelStringSlice,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<ExclusiveTransaction>> {
+    let txn = unsafe { txn.into_inner() };
+    let engine = unsafe { engine.as_ref() };
+    let domain = unsafe { String::try_from_slice(&domain) };
+
+    with_domain_metadata_removed_impl(*txn, domain).into_extern_result(&engine)
+}
+
+fn with_domain_metadata_removed_impl(
+    txn: Transaction,
+    domain: DeltaResult<String>,
+) -> DeltaResult<Handle<ExclusiveTransaction>> {
+    Ok(Box::new(txn.with_domain_metadata_removed(domain?)).into())
+}
+
 /// Add file metadata to the transaction for files that have been written. The metadata contains
 /// information about files written during the transaction that will be added to the Delta log
 /// during commit.
@@ -112,10 +167,7 @@ pub unsafe extern "C" fn commit(
     // TODO: for now this removes the enum, which prevents doing any conflict resolution. We should fix
     //       this by making the commit function return the enum somehow.
     match txn.commit(engine.as_ref()) {
-        Ok(CommitResult::Committed {
-            version: v,
-            post_commit_stats: _,
-        }) => Ok(v),
+        Ok(CommitResult::Committed { version: v, .. }) => Ok(v),
         Ok(CommitResult::Conflict(_, v)) => Err(delta_kernel::Error::Generic(format!(
             "commit conflict at version {v}"
         ))),
@@ -145,10 +197,14 @@ mod tests {
     use delta_kernel_ffi::ffi_test_utils::{allocate_str, ok_or_panic, recover_string};
     use delta_kernel_ffi::tests::get_default_engine;
 
-    use crate::{free_engine, free_schema, kernel_string_slice};
+    use crate::{
+        free_engine, free_schema, free_snapshot, get_domain_metadata, kernel_string_slice, snapshot,
+    };
     use write_context::{free_write_context, get_write_context, get_write_path, get_write_schema};
 
-    use test_utils::{set_json_value, setup_test_tables, test_read};
+    use test_utils::{
+        create_table, engine_store_setup, set_json_value, setup_test_tables, test_read,
+    };
 
     use itertools::Itertools;
     use object_store::path::Path;
@@ -381,4 +437,82 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)] // FIXME: re-enable miri (can't call foreign function `linkat` on OS `linux`)
+    async fn test_domain_metadata() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+            "number",
+            DataType::INTEGER,
+        )])?);
+
+        let tmp_test_dir = tempdir()?;
+        let tmp_dir_local_url = Url::from_directory_path(tmp_test_dir.path()).unwrap();
+        let (store, _engine, table_location) =
+            engine_store_setup("test_table", Some(&tmp_dir_local_url));
+        let table_url = create_table(
+            store,
+            table_location,
+            schema,
+            &[],
+            true,
+            vec![],
+            vec!["domainMetadata"],
+        )
+        .await?;
+        let table_path = table_url.to_file_path().unwrap();
+        let table_path_str = table_path.to_str().unwrap();
+        let engine = get_default_engine(table_path_str);
+
+        let get_domain = |domain: &str| {
+            let snapshot = ok_or_panic(unsafe {
+                snapshot(kernel_string_slice!(table_path_str), engine.shallow_copy())
+            });
+            let config = ok_or_panic(unsafe {
+                get_domain_metadata(
+                    snapshot.shallow_copy(),
+                    kernel_string_slice!(domain),
+                    engine.shallow_copy(),
+                    allocate_str,
+                )
+            });
+            unsafe { free_snapshot(snapshot) };
+            config.map(recover_string)
+        };
+
+        // set a domain
+        let txn = ok_or_panic(unsafe {
+            transaction(kernel_string_slice!(table_path_str), engine.shallow_copy())
+        });
+        let (domain, configuration) = ("app", "config");
+        let txn = ok_or_panic(unsafe {
+            with_domain_metadata(
+                txn,
+                kernel_string_slice!(domain),
+                kernel_string_slice!(configuration),
+                engine.shallow_copy(),
+            )
+        });
+        assert_eq!(
+            ok_or_panic(unsafe { commit(txn, engine.shallow_copy()) }),
+            1
+        );
+        assert_eq!(get_domain(domain).as_deref(), Some(configuration));
+
+        // remove it again
+        let txn = ok_or_panic(unsafe {
+            transaction(kernel_string_slice!(table_path_str), engine.shallow_copy())
+        });
+        let txn = ok_or_panic(unsafe {
+            with_domain_metadata_removed(txn, kernel_string_slice!(domain), engine.shallow_copy())
+        });
+        assert_eq!(
+            ok_or_panic(unsafe { commit(txn, engine.shallow_copy()) }),
+            2
+        );
+        assert_eq!(get_domain(domain), None);
+
+        unsafe { free_engine(engine) };
+        Ok(())
+    }
 }
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use aws_sdk_glue::error::DisplayErrorContext;
+use aws_sdk_glue::types::Table;
+use aws_sdk_glue::Client;
+use tracing::instrument;
+use url::Url;
+
+use crate::error::{Error, Result};
+
+// the table parameter by which Spark records the data source of a table
+const PROVIDER_PARAMETER: &str = "spark.sql.sources.provider";
+// Spark registers data source tables with a placeholder location and the actual location in the
+// `path` serde parameter
+const PATH_PARAMETER: &str = "path";
+
+/// A client for resolving tables registered in an AWS Glue Data Catalog.
+#[derive(Debug, Clone)]
+pub struct GlueCatalog {
+    client: Client,
+    catalog_id: Option<String>,
+}
+
+impl GlueCatalog {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            catalog_id: None,
+        }
+    }
+
+    /// Creates a client with the default AWS credential and region providers (environment
+    /// variables, profile files, instance metadata, ...).
+    pub async fn from_env() -> Self {
+        let config = aws_config::load_from_env().await;
+        Self::new(Client::new(&config))
+    }
+
+    /// Use the catalog with the given id (i.e. AWS account id) instead of the catalog of the
+    /// caller's account.
+    pub fn with_catalog_id(mut self, catalog_id: impl Into<String>) -> Self {
+        self.catalog_id = Some(catalog_id.into());
+        self
+    }
+
+    /// Resolves the table with the given `database.table` name.
+    #[instrument(skip(self))]
+    pub async fn get_table(&self, name: &str) -> Result<GlueTable> {
+        let (database, table) = parse_table_name(name)?;
+        let output = self
+            .client
+            .get_table()
+            .set_catalog_id(self.catalog_id.clone())
+            .database_name(database)
+            .name(table)
+            .send()
+            .await
+            .map_err(|err| {
+                let err = err.into_service_error();
+                if err.is_entity_not_found_exception() {
+                    Error::TableNotFound(name.to_string())
+                } else {
+                    Error::Glue(DisplayErrorContext(err).to_string())
+                }
+            })?;
+        let table = output
+            .table()
+            .ok_or_else(|| Error::TableNotFound(name.to_string()))?;
+        GlueTable::try_new(database, table)
+    }
+}
+
+/// A table resolved from the Glue Data Catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlueTable {
+    pub database: String,
+    pub name: String,
+    /// The storage location of the table, with a trailing slash.
+    pub location: Url,
+    /// The table parameters (i.e. table properties).
+    pub properties: HashMap<String, String>,
+}
+
+impl GlueTable {
+    fn try_new(database: &str, table: &Table) -> Result<Self> {
+        let full_name = format!("{database}.{}", table.name());
+        let location = table_location(table).ok_or_else(|| Error::MissingLocation(full_name))?;
+        Ok(Self {
+            database: database.to_string(),
+            name: table.name().to_string(),
+            location: parse_location(location)?,
+            properties: table.parameters().cloned().unwrap_or_default(),
+        })
+    }
+
+    /// The full name of the table, i.e. `database.table`.
+    pub fn full_name(&self) -> String {
+        format!("{}.{}", self.database, self.name)
+    }
+
+    /// Whether the table is registered as a Delta table.
+    pub fn is_delta_table(&self) -> bool {
+        self.properties.iter().any(|(key, value)| {
+            (key.eq_ignore_ascii_case("table_type") || key == PROVIDER_PARAMETER)
+                && value.eq_ignore_ascii_case("delta")
+        })
+    }
+}
+
+fn parse_table_name(name: &str) -> Result<(&str, &str)> {
+    match name.split_once('.') {
+        Some((database, table))
+            if !database.is_empty() && !table.is_empty() && !table.contains('.') =>
+        {
+            Ok((database, table))
+        }
+        _ => Err(Error::InvalidTableName(name.to_string())),
+    }
+}
+
+// Prefers the `path` serde parameter of Spark data source tables over the storage location.
+fn table_location(table: &Table) -> Option<&str> {
+    let descriptor = table.storage_descriptor()?;
+    descriptor
+        .serde_info()
+        .and_then(|serde_info| serde_info.parameters())
+        .and_then(|parameters| parameters.get(PATH_PARAMETER))
+        .map(String::as_str)
+        .or(descriptor.location())
+        .filter(|location| !location.is_empty())
+}
+
+// Normalizes the Hadoop `s3a://` and `s3n://` schemes to `s3://`, and adds a trailing slash so the
+// location can be used as a table root.
+fn parse_location(location: &str) -> Result<Url> {
+    let location = match location.split_once("://") {
+        Some(("s3a" | "s3n", rest)) => format!("s3://{rest}"),
+        _ => location.to_string(),
+    };
+    let mut url = Url::parse(&location)?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_glue::types::{SerDeInfo, StorageDescriptor};
+
+    use super::*;
+
+    fn table(location: &str, serde_path: Option<&str>) -> Table {
+        let mut serde_info = SerDeInfo::builder();
+        if let Some(path) = serde_path {
+            serde_info = serde_info.parameters(PATH_PARAMETER, path);
+        }
+        let descriptor = StorageDescriptor::builder()
+            .location(location)
+            .serde_info(serde_info.build())
+            .build();
+        Table::builder()
+            .name("my_table")
+            .storage_descriptor(descriptor)
+            .parameters("table_type", "DELTA")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_table_name() {
+        assert_eq!(parse_table_name("db.table").unwrap(), ("db", "table"));
+        for invalid in ["table", "db.", ".table", "catalog.db.table"] {
+            assert!(parse_table_name(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_glue_table() {
+        let resolved =
+            GlueTable::try_new("db", &table("s3a://bucket/path/to/table", None)).unwrap();
+        assert_eq!(resolved.full_name(), "db.my_table");
+        assert_eq!(resolved.location.as_str(), "s3://bucket/path/to/table/");
+        assert!(resolved.is_delta_table());
+        assert_eq!(resolved.properties["table_type"], "DELTA");
+
+        // the location of Spark data source tables is the `path` serde parameter
+        let placeholder = "s3://bucket/db/my_table-__PLACEHOLDER__";
+        let spark_table = table(placeholder, Some("s3://bucket/actual/"));
+        let resolved = GlueTable::try_new("db", &spark_table).unwrap();
+        assert_eq!(resolved.location.as_str(), "s3://bucket/actual/");
+
+        assert!(matches!(
+            GlueTable::try_new("db", &table("", None)),
+            Err(Error::MissingLocation(_))
+        ));
+    }
+}
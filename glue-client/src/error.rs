@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Glue request failed: {0}")]
+    Glue(String),
+
+    #[error("URL parse error: {0}")]
+    UrlParse(#[from] url::ParseError),
+
+    #[error("Invalid table name '{0}', expected database.table")]
+    InvalidTableName(String),
+
+    #[error("Table not found: {0}")]
+    TableNotFound(String),
+
+    #[error("Table {0} has no storage location")]
+    MissingLocation(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
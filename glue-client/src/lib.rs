@@ -0,0 +1,24 @@
+//! AWS Glue Data Catalog client for Rust
+//!
+//! This crate resolves tables registered in the AWS Glue Data Catalog by their `database.table`
+//! name to their storage location and table properties, so that tables can be opened by name.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use glue_client::GlueCatalog;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let catalog = GlueCatalog::from_env().await;
+//!     let table = catalog.get_table("my_database.my_table").await?;
+//!     println!("{} is stored at {}", table.full_name(), table.location);
+//!     Ok(())
+//! }
+//! ```
+
+pub mod catalog;
+pub mod error;
+
+pub use catalog::{GlueCatalog, GlueTable};
+pub use error::{Error, Result};
@@ -2,13 +2,15 @@
 //! utilities
 use std::{collections::HashMap, sync::Arc};
 
+use chrono::DateTime;
 use clap::Args;
 use delta_kernel::{
     arrow::array::RecordBatch,
     engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine},
+    expressions::{Expression, Predicate},
     scan::Scan,
-    schema::Schema,
-    DeltaResult, SnapshotRef,
+    schema::{DataType, Schema},
+    DeltaResult, Engine, Error, Snapshot, SnapshotRef, Version,
 };
 
 use url::Url;
@@ -42,6 +44,24 @@ pub struct ScanArgs {
     /// Comma separated list of columns to select
     #[arg(long, value_delimiter=',', num_args(0..))]
     pub columns: Option<Vec<String>>,
+
+    /// Skip files that cannot contain rows matching FILTER, a predicate of the form `column op
+    /// literal` where op is one of =, !=, <, <=, > or >=, e.g. "id >= 10". Rows of the files that
+    /// are read are not filtered.
+    #[arg(long)]
+    pub filter: Option<String>,
+}
+
+#[derive(Args)]
+pub struct SnapshotArgs {
+    /// Read the table at VERSION instead of its latest version
+    #[arg(long, conflicts_with = "timestamp")]
+    pub version: Option<Version>,
+
+    /// Read the table as of TIMESTAMP, an RFC 3339 timestamp such as 2024-01-01T00:00:00Z. This is
+    /// the latest version whose commit file was last modified at or before TIMESTAMP.
+    #[arg(long)]
+    pub timestamp: Option<String>,
 }
 
 /// Get an engine configured to read table at `url` and `LocationArgs`
@@ -60,7 +80,119 @@ pub fn get_engine(
     DefaultEngine::try_new(url, options, Arc::new(TokioBackgroundExecutor::new()))
 }
 
-/// Construct a scan at the latest snapshot. This is over the specified table and using the passed
+/// Get the snapshot of the table at `url` selected by `SnapshotArgs`, or its latest snapshot if
+/// they select none
+pub fn get_snapshot(
+    url: Url,
+    engine: &dyn Engine,
+    args: &SnapshotArgs,
+) -> DeltaResult<SnapshotRef> {
+    let version = match (args.version, &args.timestamp) {
+        (Some(version), _) => Some(version),
+        (None, Some(timestamp)) => Some(version_at_timestamp(&url, engine, timestamp)?),
+        (None, None) => None,
+    };
+    let builder = Snapshot::builder_for(url);
+    match version {
+        Some(version) => builder.at_version(version),
+        None => builder,
+    }
+    .build(engine)
+}
+
+/// Find the latest version of the table at `url` that was committed at or before `timestamp`.
+///
+/// In-commit timestamps are not consulted: the commit time of a version is taken to be the
+/// modification time of its commit file, which is only accurate as long as log files are never
+/// copied or rewritten.
+fn version_at_timestamp(url: &Url, engine: &dyn Engine, timestamp: &str) -> DeltaResult<Version> {
+    let millis = DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| Error::generic(format!("Invalid timestamp {timestamp}: {e}")))?
+        .timestamp_millis();
+    let first_commit = url.join(&format!("_delta_log/{:020}", 0))?;
+    let mut version = None;
+    for file in engine.storage_handler().list_from(&first_commit)? {
+        let file = file?;
+        let Some(commit_version) = commit_version(&file.location) else {
+            continue;
+        };
+        if file.last_modified <= millis {
+            version = version.max(Some(commit_version));
+        }
+    }
+    version
+        .ok_or_else(|| Error::generic(format!("The table has no commit at or before {timestamp}")))
+}
+
+/// The version of the commit file at `location`, or `None` if it isn't a commit file
+fn commit_version(location: &Url) -> Option<Version> {
+    let name = location.path_segments()?.next_back()?;
+    let version = name.strip_suffix(".json")?;
+    if version.len() != 20 {
+        return None;
+    }
+    version.parse().ok()
+}
+
+/// Parse a filter of the form `column op literal` into a predicate over `schema`. The column can be
+/// a nested field, e.g. `a.b`, and the literal is parsed as a value of the column's type. String
+/// literals can be wrapped in single quotes.
+pub fn parse_filter(filter: &str, schema: &Schema) -> DeltaResult<Predicate> {
+    let invalid = || {
+        Error::generic(format!(
+            "Invalid filter {filter:?}, expected `column op literal`"
+        ))
+    };
+    let (column, rest) = filter
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(invalid)?;
+    let (op, literal) = rest
+        .trim_start()
+        .split_once(char::is_whitespace)
+        .ok_or_else(invalid)?;
+    let literal = literal.trim();
+    let literal = literal
+        .strip_prefix('\'')
+        .and_then(|literal| literal.strip_suffix('\''))
+        .unwrap_or(literal);
+
+    let path: Vec<&str> = column.split('.').collect();
+    let mut fields = schema;
+    let mut data_type = None;
+    for (i, name) in path.iter().enumerate() {
+        let field = fields
+            .field(name)
+            .ok_or_else(|| Error::generic(format!("Table has no such column: {column}")))?;
+        match field.data_type() {
+            DataType::Struct(nested) if i + 1 < path.len() => fields = &**nested,
+            _ if i + 1 < path.len() => {
+                return Err(Error::generic(format!(
+                    "Table has no such column: {column}"
+                )))
+            }
+            leaf => data_type = Some(leaf),
+        }
+    }
+    let Some(DataType::Primitive(primitive)) = data_type else {
+        return Err(Error::unsupported(format!(
+            "Cannot filter on non-primitive column {column}"
+        )));
+    };
+    let column = Expression::column(path);
+    let literal = Expression::literal(primitive.parse_scalar(literal)?);
+    match op {
+        "=" | "==" => Ok(Predicate::eq(column, literal)),
+        "!=" | "<>" => Ok(Predicate::ne(column, literal)),
+        "<" => Ok(Predicate::lt(column, literal)),
+        "<=" => Ok(Predicate::le(column, literal)),
+        ">" => Ok(Predicate::gt(column, literal)),
+        ">=" => Ok(Predicate::ge(column, literal)),
+        _ => Err(Error::generic(format!("Unsupported filter operator {op}"))),
+    }
+}
+
+/// Construct a scan of `snapshot`. This is over the specified table and using the passed
 /// engine. Parameters of the scan are controlled by the specified `ScanArgs`
 pub fn get_scan(snapshot: SnapshotRef, args: &ScanArgs) -> DeltaResult<Option<Scan>> {
     if args.schema_only {
@@ -84,10 +216,16 @@ pub fn get_scan(snapshot: SnapshotRef, args: &ScanArgs) -> DeltaResult<Option<Sc
             Schema::try_from_results(selected_fields).map(Arc::new)
         })
         .transpose()?;
+    let predicate = args
+        .filter
+        .as_deref()
+        .map(|filter| parse_filter(filter, &snapshot.schema()).map(Arc::new))
+        .transpose()?;
     Ok(Some(
         snapshot
             .scan_builder()
             .with_schema_opt(read_schema_opt)
+            .with_predicate(predicate)
             .build()?,
     ))
 }
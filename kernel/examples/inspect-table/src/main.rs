@@ -4,21 +4,32 @@ use delta_kernel::actions::visitors::{
     SetTransactionVisitor,
 };
 use delta_kernel::actions::{
-    get_log_schema, ADD_NAME, CDC_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
-    SET_TRANSACTION_NAME,
+    get_log_schema, ADD_NAME, CDC_NAME, DOMAIN_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME,
+    REMOVE_NAME, SET_TRANSACTION_NAME,
+};
+use delta_kernel::arrow::array::{
+    Array as _, ArrayRef, BooleanArray, RecordBatch, StringArray, UInt64Array,
 };
+use delta_kernel::arrow::compute::{filter_record_batch, is_not_null, or};
+use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Schema};
+use delta_kernel::arrow::json::LineDelimitedWriter;
+use delta_kernel::arrow::util::display::{ArrayFormatter, FormatOptions};
+use delta_kernel::arrow::util::pretty::print_batches;
+use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine_data::{GetData, RowVisitor, TypedGetData as _};
 use delta_kernel::expressions::ColumnName;
 use delta_kernel::scan::state::{DvInfo, Stats};
 use delta_kernel::scan::ScanBuilder;
 use delta_kernel::schema::{ColumnNamesAndTypes, DataType};
-use delta_kernel::{DeltaResult, Error, ExpressionRef, Snapshot};
+use delta_kernel::{DeltaResult, Engine, Error, ExpressionRef, FileMeta, Snapshot, Version};
 
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::process::ExitCode;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use url::Url;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -47,6 +58,27 @@ enum Commands {
         #[arg(short, long)]
         oldest_first: bool,
     },
+    /// Dump the protocol, metadata, add, remove, cdc, txn and domainMetadata actions of the
+    /// commits in a range of versions, oldest first
+    Log {
+        /// The first version to dump
+        #[arg(long, default_value_t = 0)]
+        start_version: Version,
+        /// The last version to dump [default: the latest version]
+        #[arg(long)]
+        end_version: Option<Version>,
+        /// How to print the actions
+        #[arg(long, value_enum, default_value_t = LogFormat::Table)]
+        format: LogFormat,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    /// One JSON object per line and action, with the version of its commit
+    Json,
+    /// A table with the version, type and contents of each action
+    Table,
 }
 
 fn main() -> ExitCode {
@@ -150,6 +182,116 @@ impl RowVisitor for LogVisitor {
     }
 }
 
+/// The actions `Commands::Log` dumps.
+const LOG_ACTIONS: [&str; 7] = [
+    PROTOCOL_NAME,
+    METADATA_NAME,
+    ADD_NAME,
+    REMOVE_NAME,
+    CDC_NAME,
+    SET_TRANSACTION_NAME,
+    DOMAIN_METADATA_NAME,
+];
+
+/// The version of a commit file, e.g. `00000000000000000001.json`, or `None` for other log files.
+fn commit_version(location: &Url) -> Option<Version> {
+    let name = location.path_segments()?.next_back()?;
+    let version = name.strip_suffix(".json")?;
+    if version.len() != 20 {
+        return None;
+    }
+    version.parse().ok()
+}
+
+/// Print the actions of the commits with a version in `versions`.
+fn dump_log(
+    engine: &dyn Engine,
+    table_root: &Url,
+    versions: RangeInclusive<Version>,
+    format: LogFormat,
+) -> DeltaResult<()> {
+    // list from the first version on; the listing is sorted, so commits are in version order
+    let log_root = table_root.join("_delta_log/")?;
+    let start = log_root.join(&format!("{:020}", versions.start()))?;
+    let commits: Vec<(Version, FileMeta)> = engine
+        .storage_handler()
+        .list_from(&start)?
+        .filter_map(|file| match file {
+            Ok(file) => {
+                let version = commit_version(&file.location)?;
+                versions.contains(&version).then_some(Ok((version, file)))
+            }
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<DeltaResult<_>>()?;
+
+    let schema = get_log_schema().project(&LOG_ACTIONS)?;
+    let mut json_writer = LineDelimitedWriter::new(std::io::stdout().lock());
+    let (mut table_versions, mut table_actions, mut table_contents) = (vec![], vec![], vec![]);
+    for (version, file) in commits {
+        let batches = engine
+            .json_handler()
+            .read_json_files(&[file], schema.clone(), None)?;
+        for data in batches {
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data?)?.into();
+            // only keep the rows of the dumped actions, e.g. not those of commitInfo actions
+            let mut mask = BooleanArray::from(vec![false; batch.num_rows()]);
+            for column in batch.columns() {
+                mask = or(&mask, &is_not_null(column)?)?;
+            }
+            let batch = filter_record_batch(&batch, &mask)?;
+            match format {
+                LogFormat::Json => {
+                    let mut fields = vec![Arc::new(Field::new(
+                        "version",
+                        ArrowDataType::UInt64,
+                        false,
+                    ))];
+                    fields.extend(batch.schema().fields().iter().cloned());
+                    let versions: ArrayRef =
+                        Arc::new(UInt64Array::from(vec![version; batch.num_rows()]));
+                    let mut columns = vec![versions];
+                    columns.extend(batch.columns().iter().cloned());
+                    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+                    json_writer.write(&batch)?;
+                }
+                LogFormat::Table => {
+                    let options = FormatOptions::default();
+                    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+                        let formatter = ArrayFormatter::try_new(column.as_ref(), &options)?;
+                        for row in (0..column.len()).filter(|row| column.is_valid(*row)) {
+                            table_versions.push(version);
+                            table_actions.push(field.name().clone());
+                            table_contents.push(formatter.value(row).try_to_string()?);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    match format {
+        LogFormat::Json => json_writer.finish()?,
+        LogFormat::Table => {
+            let batch = RecordBatch::try_from_iter([
+                (
+                    "version",
+                    Arc::new(UInt64Array::from(table_versions)) as ArrayRef,
+                ),
+                (
+                    "action",
+                    Arc::new(StringArray::from(table_actions)) as ArrayRef,
+                ),
+                (
+                    "contents",
+                    Arc::new(StringArray::from(table_contents)) as ArrayRef,
+                ),
+            ])?;
+            print_batches(&[batch])?;
+        }
+    }
+    Ok(())
+}
+
 // This is the callback that will be called for each valid scan row
 fn print_scan_file(
     _: &mut (),
@@ -159,6 +301,7 @@ fn print_scan_file(
     dv_info: DvInfo,
     transform: Option<ExpressionRef>,
     partition_values: HashMap<String, String>,
+    tags: HashMap<String, String>,
 ) {
     let num_record_str = if let Some(s) = stats {
         format!("{}", s.num_records)
@@ -172,7 +315,8 @@ fn print_scan_file(
               Num Records:\t{num_record_str}\n  \
               Has DV?:\t{}\n  \
               Transform:\t{transform:?}\n  \
-              Part Vals:\t{partition_values:?}",
+              Part Vals:\t{partition_values:?}\n  \
+              Tags:\t\t{tags:?}",
         dv_info.has_vector()
     );
 }
@@ -182,7 +326,7 @@ fn try_main() -> DeltaResult<()> {
 
     let url = delta_kernel::try_parse_uri(&cli.location_args.path)?;
     let engine = common::get_engine(&url, &cli.location_args)?;
-    let snapshot = Snapshot::builder_for(url).build(&engine)?;
+    let snapshot = Snapshot::builder_for(url.clone()).build(&engine)?;
 
     match cli.command {
         Commands::TableVersion => {
@@ -230,6 +374,19 @@ fn try_main() -> DeltaResult<()> {
                 }
             }
         }
+        Commands::Log {
+            start_version,
+            end_version,
+            format,
+        } => {
+            let end_version = end_version.unwrap_or(snapshot.version());
+            if start_version > end_version {
+                return Err(Error::generic(format!(
+                    "The start version {start_version} is after the end version {end_version}"
+                )));
+            }
+            dump_log(&engine, &url, start_version..=end_version, format)?;
+        }
     };
     Ok(())
 }
@@ -0,0 +1,315 @@
+use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{Parser, Subcommand};
+use common::LocationArgs;
+use futures::TryStreamExt as _;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use url::Url;
+
+use delta_kernel::actions::deletion_vector::DeletionVectorDescriptor;
+use delta_kernel::actions::{get_log_schema, REMOVE_NAME};
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use delta_kernel::expressions::{column_name, ColumnName};
+use delta_kernel::scan::state::{DvInfo, Stats};
+use delta_kernel::schema::{ColumnNamesAndTypes, DataType};
+use delta_kernel::{
+    DeltaResult, Engine, EngineData, Error, ExpressionRef, Snapshot, SnapshotRef, Version,
+};
+
+/// The retention of removed files of tables that do not set `delta.deletedFileRetentionDuration`.
+const DEFAULT_DELETED_FILE_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// An example program that runs maintenance operations against a Delta table.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    #[command(flatten)]
+    location_args: LocationArgs,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Write a checkpoint of the latest version of the table
+    Checkpoint,
+    /// Compact the commits of a range of versions into a log compaction file
+    CompactLog {
+        /// The first version to compact
+        #[arg(long)]
+        start_version: Version,
+        /// The last version to compact [default: the latest version]
+        #[arg(long)]
+        end_version: Option<Version>,
+    },
+    /// List the files a VACUUM would delete, without deleting them: the data and deletion vector
+    /// files that the table no longer references, once the table's deleted file retention expired
+    VacuumDryRun,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::init();
+    match try_main().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            println!("{e:#?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn try_main() -> DeltaResult<()> {
+    let cli = Cli::parse();
+
+    let url = delta_kernel::try_parse_uri(&cli.location_args.path)?;
+    let engine = common::get_engine(&url, &cli.location_args)?;
+    let store = engine
+        .get_object_store_for_url(&url)
+        .ok_or_else(|| Error::generic(format!("No object store for {url}")))?;
+    let snapshot = Snapshot::builder_for(url).build(&engine)?;
+    println!("Using table at version {}", snapshot.version());
+
+    match cli.command {
+        Commands::Checkpoint => checkpoint(&engine, snapshot).await,
+        Commands::CompactLog {
+            start_version,
+            end_version,
+        } => compact_log(&engine, snapshot, start_version, end_version),
+        Commands::VacuumDryRun => vacuum_dry_run(&engine, store.as_ref(), snapshot).await,
+    }
+}
+
+/// Write a single-file checkpoint of `snapshot` as parquet, then finalize it, which updates the
+/// `_last_checkpoint` file. The default engine streams the checkpoint's actions into the file, so
+/// this works for tables with any number of files.
+async fn checkpoint(
+    engine: &DefaultEngine<TokioBackgroundExecutor>,
+    snapshot: SnapshotRef,
+) -> DeltaResult<()> {
+    let metadata = engine.write_checkpoint(snapshot).await?;
+    println!(
+        "✓ Wrote checkpoint {} ({} bytes)",
+        metadata.location, metadata.size
+    );
+    Ok(())
+}
+
+/// Compact the commits from `start_version` to `end_version` (or the snapshot's version) into a
+/// log compaction file.
+fn compact_log(
+    engine: &dyn Engine,
+    snapshot: SnapshotRef,
+    start_version: Version,
+    end_version: Option<Version>,
+) -> DeltaResult<()> {
+    let end_version = end_version.unwrap_or(snapshot.version());
+    let mut writer = snapshot.log_compaction_writer(start_version, end_version)?;
+    let compaction_path = writer.compaction_path().clone();
+    let compaction_data =
+        writer
+            .compaction_data(engine)?
+            .map(|data| -> DeltaResult<Box<dyn EngineData>> {
+                Ok(Box::new(ArrowEngineData::new(
+                    data?.try_into_selected_record_batch()?,
+                )))
+            });
+    engine
+        .json_handler()
+        .write_json_file(&compaction_path, Box::new(compaction_data), false)?;
+    println!("✓ Compacted versions {start_version} to {end_version} into {compaction_path}");
+    Ok(())
+}
+
+/// Collects the remove actions whose deletion is still within the deleted file retention, i.e.
+/// whose files (and deletion vectors) a VACUUM must keep.
+struct UnexpiredTombstoneVisitor {
+    /// Removes deleted before this time (in milliseconds since the epoch) are expired
+    cutoff_millis: i64,
+    /// The paths of the unexpired removes, and their deletion vectors
+    tombstones: Vec<(String, Option<DeletionVectorDescriptor>)>,
+}
+
+impl RowVisitor for UnexpiredTombstoneVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let names = vec![
+                column_name!("remove.path"),
+                column_name!("remove.deletionTimestamp"),
+                column_name!("remove.deletionVector.storageType"),
+                column_name!("remove.deletionVector.pathOrInlineDv"),
+                column_name!("remove.deletionVector.offset"),
+                column_name!("remove.deletionVector.sizeInBytes"),
+                column_name!("remove.deletionVector.cardinality"),
+            ];
+            let types = vec![
+                DataType::STRING,
+                DataType::LONG,
+                DataType::STRING,
+                DataType::STRING,
+                DataType::INTEGER,
+                DataType::INTEGER,
+                DataType::LONG,
+            ];
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        if getters.len() != 7 {
+            return Err(Error::internal_error(format!(
+                "Wrong number of UnexpiredTombstoneVisitor getters: {}",
+                getters.len()
+            )));
+        }
+        for i in 0..row_count {
+            let Some(path) = getters[0].get_opt(i, "remove.path")? else {
+                continue;
+            };
+            // removes without a deletion timestamp are treated as deleted long ago
+            let deletion_timestamp: Option<i64> =
+                getters[1].get_opt(i, "remove.deletionTimestamp")?;
+            if deletion_timestamp.unwrap_or(0) < self.cutoff_millis {
+                continue;
+            }
+            let deletion_vector = match getters[2]
+                .get_opt(i, "remove.deletionVector.storageType")?
+            {
+                Some(storage_type) => Some(DeletionVectorDescriptor {
+                    storage_type,
+                    path_or_inline_dv: getters[3].get(i, "remove.deletionVector.pathOrInlineDv")?,
+                    offset: getters[4].get_opt(i, "remove.deletionVector.offset")?,
+                    size_in_bytes: getters[5].get(i, "remove.deletionVector.sizeInBytes")?,
+                    cardinality: getters[6].get(i, "remove.deletionVector.cardinality")?,
+                }),
+                None => None,
+            };
+            self.tombstones.push((path, deletion_vector));
+        }
+        Ok(())
+    }
+}
+
+/// A [`ScanCallback`] that collects the path and deletion vector of each file of a scan.
+///
+/// [`ScanCallback`]: delta_kernel::scan::state::ScanCallback
+fn collect_scan_file(
+    files: &mut Vec<(String, DvInfo)>,
+    path: &str,
+    _: i64,
+    _: Option<Stats>,
+    dv_info: DvInfo,
+    _: Option<ExpressionRef>,
+    _: HashMap<String, String>,
+    _: HashMap<String, String>,
+) {
+    files.push((path.to_string(), dv_info));
+}
+
+/// Whether the file at `relative_path` (relative to the table root) is in a hidden directory, like
+/// `_delta_log`, which VACUUM leaves alone. Partition directories may start with an underscore.
+fn is_hidden(relative_path: &str) -> bool {
+    let mut directories = relative_path.split('/').rev().skip(1);
+    directories.any(|dir| (dir.starts_with('_') || dir.starts_with('.')) && !dir.contains('='))
+}
+
+/// List the files a VACUUM of `snapshot` would delete: the files in the table directory that are
+/// not hidden, that neither the snapshot nor an unexpired remove action references, and that were
+/// last modified before the deleted file retention (so that files of in-flight writes are kept).
+async fn vacuum_dry_run(
+    engine: &dyn Engine,
+    store: &DynObjectStore,
+    snapshot: SnapshotRef,
+) -> DeltaResult<()> {
+    let table_root = snapshot.table_root().clone();
+    let retention = snapshot
+        .table_properties()
+        .deleted_file_retention_duration
+        .unwrap_or(DEFAULT_DELETED_FILE_RETENTION);
+    let cutoff = SystemTime::now() - retention;
+    let cutoff_millis = cutoff
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::generic(format!("Invalid retention {retention:?}: {e}")))?
+        .as_millis() as i64;
+
+    // the files of the snapshot, and their deletion vectors
+    let scan = snapshot.clone().scan_builder().build()?;
+    let mut scan_files = vec![];
+    for scan_metadata in scan.scan_metadata(engine)? {
+        scan_files = scan_metadata?.visit_scan_files(scan_files, collect_scan_file)?;
+    }
+    // the files of unexpired removes, and their deletion vectors
+    let remove_schema = get_log_schema().project(&[REMOVE_NAME])?;
+    let actions =
+        snapshot
+            .log_segment()
+            .read_actions(engine, remove_schema.clone(), remove_schema, None)?;
+    let mut visitor = UnexpiredTombstoneVisitor {
+        cutoff_millis,
+        tombstones: vec![],
+    };
+    for actions in actions {
+        visitor.visit_rows_of(actions?.actions())?;
+    }
+
+    let scan_files = scan_files
+        .iter()
+        .map(|(path, dv_info)| (path, dv_info.deletion_vector()));
+    let tombstones = visitor
+        .tombstones
+        .iter()
+        .map(|(path, deletion_vector)| (path, deletion_vector.as_ref()));
+    let mut referenced = HashSet::new();
+    for (path, deletion_vector) in scan_files.chain(tombstones) {
+        let url = scan.resolve_file_path(path)?;
+        referenced.insert(Path::from_url_path(url.path())?);
+        if let Some(dv_url) = deletion_vector
+            .map(|dv| dv.absolute_path(&table_root))
+            .transpose()?
+            .flatten()
+        {
+            referenced.insert(Path::from_url_path(dv_url.path())?);
+        }
+    }
+
+    let prefix = Path::from_url_path(table_root.path())?;
+    let mut listing = store.list(Some(&prefix));
+    let (mut num_files, mut num_bytes) = (0, 0);
+    while let Some(file) = listing.try_next().await? {
+        let relative_path = file.location.as_ref().strip_prefix(prefix.as_ref());
+        let hidden = relative_path.is_none_or(|path| is_hidden(path.trim_start_matches('/')));
+        let expired = file.last_modified.timestamp_millis() < cutoff_millis;
+        if hidden || !expired || referenced.contains(&file.location) {
+            continue;
+        }
+        println!(
+            "{} ({} bytes)",
+            table_url(&table_root, &file.location),
+            file.size
+        );
+        num_files += 1;
+        num_bytes += file.size;
+    }
+    println!(
+        "✓ VACUUM would delete {num_files} files ({num_bytes} bytes), keeping files removed in the \
+         last {retention:?}"
+    );
+    Ok(())
+}
+
+/// The URL of the file at `location` in the object store of the table at `table_root`.
+fn table_url(table_root: &Url, location: &Path) -> Url {
+    let mut url = table_root.clone();
+    url.set_path(&format!("/{location}"));
+    url
+}
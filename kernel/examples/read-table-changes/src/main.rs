@@ -35,7 +35,7 @@ fn main() -> DeltaResult<()> {
         .execute(Arc::new(engine))?
         .map(|scan_result| -> DeltaResult<_> {
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch: RecordBatch = data
                 .into_any()
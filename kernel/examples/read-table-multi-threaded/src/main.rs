@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::process::ExitCode;
 use std::sync::mpsc::Sender;
 use std::sync::{mpsc, Arc};
@@ -7,26 +6,28 @@ use std::thread;
 use arrow::compute::filter_record_batch;
 use arrow::record_batch::RecordBatch;
 use arrow::util::pretty::print_batches;
-use common::{LocationArgs, ScanArgs};
+use common::{LocationArgs, ScanArgs, SnapshotArgs};
 use delta_kernel::actions::deletion_vector::split_vector;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
-use delta_kernel::scan::state::{transform_to_logical, DvInfo, Stats};
+use delta_kernel::scan::grouping::ScanFileGroup;
 use delta_kernel::schema::SchemaRef;
-use delta_kernel::{DeltaResult, Engine, EngineData, ExpressionRef, FileMeta, Snapshot};
+use delta_kernel::{DeltaResult, Engine, EngineData, FileMeta};
 
 use clap::Parser;
 use url::Url;
 
 /// An example program that reads a table using multiple threads. This shows the use of the
-/// scan_metadata method on a Scan, that can be used to partition work to either
+/// scan_file_groups method on a Scan, that can be used to partition work to either
 /// multiple threads, or workers (in the case of a distributed engine).
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
-#[command(propagate_version = true)]
+#[command(author, about, long_about = None)]
 struct Cli {
     #[command(flatten)]
     location_args: LocationArgs,
 
+    #[command(flatten)]
+    snapshot_args: SnapshotArgs,
+
     #[command(flatten)]
     scan_args: ScanArgs,
 
@@ -50,15 +51,6 @@ fn main() -> ExitCode {
     }
 }
 
-// the way we as a connector represent data to scan. this is computed from the raw data returned
-// from the scan, and could be any format the engine chooses to use to facilitate distributing work.
-struct ScanFile {
-    path: String,
-    size: i64,
-    transform: Option<ExpressionRef>,
-    dv_info: DvInfo,
-}
-
 // we know we're using arrow under the hood, so cast an EngineData into something we can work with
 fn to_arrow(data: Box<dyn EngineData>) -> DeltaResult<RecordBatch> {
     Ok(data
@@ -68,25 +60,6 @@ fn to_arrow(data: Box<dyn EngineData>) -> DeltaResult<RecordBatch> {
         .into())
 }
 
-// This is the callback that will be called for each valid scan row
-fn send_scan_file(
-    scan_tx: &mut spmc::Sender<ScanFile>,
-    path: &str,
-    size: i64,
-    _stats: Option<Stats>,
-    dv_info: DvInfo,
-    transform: Option<ExpressionRef>,
-    _: HashMap<String, String>,
-) {
-    let scan_file = ScanFile {
-        path: path.to_string(),
-        size,
-        transform,
-        dv_info,
-    };
-    scan_tx.send(scan_file).unwrap();
-}
-
 struct ScanState {
     table_root: Url,
     physical_schema: SchemaRef,
@@ -99,20 +72,17 @@ fn try_main() -> DeltaResult<()> {
     let url = delta_kernel::try_parse_uri(&cli.location_args.path)?;
     println!("Reading {url}");
     let engine = common::get_engine(&url, &cli.location_args)?;
-    let snapshot = Snapshot::builder_for(url).build(&engine)?;
+    let snapshot = common::get_snapshot(url, &engine, &cli.snapshot_args)?;
     let Some(scan) = common::get_scan(snapshot, &cli.scan_args)? else {
         return Ok(());
     };
 
-    // this gives us an iterator of (our engine data, selection vector). our engine data is just
-    // arrow data. The schema can be obtained by calling
-    // [`delta_kernel::scan::scan_row_schema`]. Generally engines will not need to interact with
-    // this data directly, and can just call [`visit_scan_files`] to get pre-parsed data back from
-    // the kernel.
-    let scan_metadata = scan.scan_metadata(&engine)?;
-
     if cli.metadata {
-        let (scan_metadata_batches, scan_metadata_rows) = scan_metadata
+        // this gives us an iterator of (our engine data, selection vector). our engine data is just
+        // arrow data. The schema can be obtained by calling
+        // [`delta_kernel::scan::scan_row_schema`].
+        let (scan_metadata_batches, scan_metadata_rows) = scan
+            .scan_metadata(&engine)?
             .map(|res| res.unwrap().scan_files.data.len())
             .fold((0, 0), |(batches, rows), len| (batches + 1, rows + len));
         println!("Scan metadata: {scan_metadata_batches} chunks, {scan_metadata_rows} files",);
@@ -122,8 +92,8 @@ fn try_main() -> DeltaResult<()> {
     // create the channels we'll use. record_batch_[t/r]x are used for the threads to send back the
     // processed RecordBatches to themain thread
     let (record_batch_tx, record_batch_rx) = mpsc::channel();
-    // scan_file_[t/r]x are used to send each scan file from the iterator out to the waiting threads
-    let (mut scan_file_tx, scan_file_rx) = spmc::channel();
+    // scan_group_[t/r]x are used to send each group of scan files out to the waiting threads
+    let (mut scan_group_tx, scan_group_rx) = spmc::channel();
 
     // fire up each thread. they will be automatically joined at the end due to the scope
     thread::scope(|s| {
@@ -135,9 +105,9 @@ fn try_main() -> DeltaResult<()> {
                 logical_schema: scan.logical_schema().clone(),
             });
             let rb_tx = record_batch_tx.clone();
-            let scan_file_rx = scan_file_rx.clone();
+            let scan_group_rx = scan_group_rx.clone();
             s.spawn(|| {
-                do_work(&engine, scan_state, rb_tx, scan_file_rx);
+                do_work(&engine, scan_state, rb_tx, scan_group_rx);
             });
         });
 
@@ -145,12 +115,15 @@ fn try_main() -> DeltaResult<()> {
         // done sending
         drop(record_batch_tx);
 
-        for res in scan_metadata {
-            let scan_metadata = res?;
-            scan_file_tx = scan_metadata.visit_scan_files(scan_file_tx, send_scan_file)?;
+        // bin the scan files into a few groups of similar size per thread, so that threads don't
+        // pay the overhead of a task for each (possibly tiny) file, but still finish around the
+        // same time
+        let num_groups = usize::try_from(cli.thread_count * 4).unwrap();
+        for group in scan.scan_file_groups(&engine, num_groups, false)? {
+            scan_group_tx.send(group).unwrap();
         }
 
-        drop(scan_file_tx);
+        drop(scan_group_tx);
 
         let batches = if let Some(limit) = cli.scan_args.limit {
             // gather batches while we need
@@ -183,11 +156,12 @@ fn do_work(
     engine: &dyn Engine,
     scan_state: Arc<ScanState>,
     record_batch_tx: Sender<RecordBatch>,
-    scan_file_rx: spmc::Receiver<ScanFile>,
+    scan_group_rx: spmc::Receiver<ScanFileGroup>,
 ) {
-    // in a loop, try and get a ScanFile. Note that `recv` will return an `Err` when the other side
-    // hangs up, which indicates there's no more data to process.
-    while let Ok(scan_file) = scan_file_rx.recv() {
+    // in a loop, try and get a group of scan files. Note that `recv` will return an `Err` when the
+    // other side hangs up, which indicates there's no more data to process.
+    let scan_files = std::iter::from_fn(|| scan_group_rx.recv().ok()).flat_map(|group| group.files);
+    for scan_file in scan_files {
         // we got a scan file, let's process it
         let root_url = &scan_state.table_root;
 
@@ -218,19 +192,22 @@ fn do_work(
             .read_parquet_files(&[meta], scan_state.physical_schema.clone(), None)
             .unwrap();
 
-        for read_result in read_results {
-            let read_result = read_result.unwrap();
-            let len = read_result.len();
-            // transform the physical data into the correct logical form
-            let logical = transform_to_logical(
-                engine,
-                read_result,
-                &scan_state.physical_schema,
-                &scan_state.logical_schema,
-                scan_file.transform.clone(),
-            )
-            .unwrap();
+        // transform the physical data into the correct logical form
+        let read_results = match scan_file.transform.clone() {
+            Some(transform) => engine
+                .evaluation_handler()
+                .new_expression_evaluator(
+                    scan_state.physical_schema.clone(),
+                    transform,
+                    scan_state.logical_schema.clone().into(),
+                )
+                .evaluate_batches(read_results),
+            None => read_results,
+        };
 
+        for logical in read_results {
+            let logical = logical.unwrap();
+            let len = logical.len();
             let record_batch = to_arrow(logical).unwrap();
 
             // need to split the dv_mask. what's left in dv_mask covers this result, and rest
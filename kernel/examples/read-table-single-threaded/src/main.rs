@@ -4,9 +4,9 @@ use std::sync::Arc;
 use arrow::compute::filter_record_batch;
 use arrow::record_batch::RecordBatch;
 use arrow::util::pretty::print_batches;
-use common::{LocationArgs, ScanArgs};
+use common::{LocationArgs, ScanArgs, SnapshotArgs};
 use delta_kernel::engine::arrow_data::ArrowEngineData;
-use delta_kernel::{DeltaResult, Snapshot};
+use delta_kernel::DeltaResult;
 
 use clap::Parser;
 use itertools::Itertools;
@@ -14,8 +14,7 @@ use itertools::Itertools;
 /// An example program that dumps out the data of a delta table. Struct and Map types are not
 /// supported.
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
-#[command(propagate_version = true)]
+#[command(author, about, long_about = None)]
 struct Cli {
     // today we don't have any args unique to this version, but we keep this as flattened this way
     // for consistency with the multi-threaded version and to make it easy to add unique options in
@@ -23,6 +22,9 @@ struct Cli {
     #[command(flatten)]
     location_args: LocationArgs,
 
+    #[command(flatten)]
+    snapshot_args: SnapshotArgs,
+
     #[command(flatten)]
     scan_args: ScanArgs,
 }
@@ -43,7 +45,7 @@ fn try_main() -> DeltaResult<()> {
     let url = delta_kernel::try_parse_uri(&cli.location_args.path)?;
     println!("Reading {url}");
     let engine = common::get_engine(&url, &cli.location_args)?;
-    let snapshot = Snapshot::builder_for(url).build(&engine)?;
+    let snapshot = common::get_snapshot(url, &engine, &cli.snapshot_args)?;
     let Some(scan) = common::get_scan(snapshot, &cli.scan_args)? else {
         return Ok(());
     };
@@ -54,7 +56,7 @@ fn try_main() -> DeltaResult<()> {
         .map(|scan_result| -> DeltaResult<_> {
             // extract the batches and filter them if they have deletion vectors
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch: RecordBatch = data
                 .into_any()
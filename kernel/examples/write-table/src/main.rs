@@ -1,10 +1,15 @@
 use std::collections::HashMap;
-use std::fs::{create_dir_all, write};
-use std::path::Path;
+use std::fs::{create_dir_all, write, File};
+use std::io::Seek as _;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::Arc;
 
 use arrow::array::{BooleanArray, Float64Array, Int32Array, RecordBatch, StringArray};
+use arrow::compute::cast;
+use arrow::csv::reader::Format;
+use arrow::csv::ReaderBuilder;
+use arrow::datatypes::Schema as ArrowSchema;
 use arrow::util::pretty::print_batches;
 use clap::Parser;
 use itertools::Itertools;
@@ -13,10 +18,11 @@ use url::Url;
 use uuid::Uuid;
 
 use delta_kernel::arrow::array::TimestampMicrosecondArray;
-use delta_kernel::engine::arrow_conversion::TryIntoArrow;
+use delta_kernel::engine::arrow_conversion::{TryFromArrow as _, TryIntoArrow};
 use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use delta_kernel::schema::{DataType, SchemaRef, StructField, StructType};
 use delta_kernel::transaction::CommitResult;
 use delta_kernel::{DeltaResult, Engine, Error, Snapshot, SnapshotRef};
@@ -30,7 +36,14 @@ struct Cli {
     #[arg(long, short = 'p')]
     path: String,
 
-    /// Comma-separated schema specification of the form `field_name:data_type`
+    /// A CSV (with a header row) or parquet file with the data to append to the table. If the
+    /// table does not exist yet, it is created with the schema of the file. Without an input,
+    /// sample data is generated.
+    #[arg(long, short = 'i')]
+    input: Option<PathBuf>,
+
+    /// Comma-separated schema specification of the form `field_name:data_type`, used to create
+    /// the table if it does not exist yet and no input is given
     #[arg(
         long,
         short = 's',
@@ -38,10 +51,9 @@ struct Cli {
     )]
     schema: String,
 
-    /// Number of rows to generate for the example data
+    /// Number of rows to generate for the example data, if no input is given
     #[arg(long, short, default_value = "10")]
     num_rows: usize,
-    // TODO: Support providing input data from a JSON file instead of generating random data
     // TODO: Support specifying whether the transaction should overwrite, append, or error if the table already exists
 }
 
@@ -78,11 +90,27 @@ async fn try_main() -> DeltaResult<()> {
         Arc::new(TokioBackgroundExecutor::new()),
     )?;
 
-    // Create or get the table
-    let snapshot = create_or_get_base_snapshot(&url, &engine, &cli.schema).await?;
+    // Read the input, if any, and use its schema for a new table
+    let input = cli.input.as_deref().map(read_input).transpose()?;
+    let schema = match &input {
+        Some((schema, _)) => Arc::new(StructType::try_from_arrow(schema)?),
+        None => parse_schema(&cli.schema)?,
+    };
 
-    // Create sample data based on the schema
-    let sample_data = create_sample_data(&snapshot.schema(), cli.num_rows)?;
+    // Create or get the table
+    let snapshot = create_or_get_base_snapshot(&url, &engine, &schema).await?;
+
+    // Conform the input to the table schema, or create sample data based on it
+    let batches = match input {
+        Some((_, batches)) => {
+            let table_schema: ArrowSchema = snapshot.schema().as_ref().try_into_arrow()?;
+            batches
+                .iter()
+                .map(|batch| conform_to_schema(batch, &table_schema))
+                .try_collect()?
+        }
+        None => vec![create_sample_data(&snapshot.schema(), cli.num_rows)?],
+    };
 
     // Write sample data to the table
     let mut txn = snapshot
@@ -90,20 +118,28 @@ async fn try_main() -> DeltaResult<()> {
         .with_operation("INSERT".to_string())
         .with_engine_info("default_engine/write-table-example");
 
-    // Write the data using the engine
+    // Write each batch to a data file using the engine, and add the file metadata to the
+    // transaction
     let write_context = Arc::new(txn.get_write_context());
-    let file_metadata = engine
-        .write_parquet(&sample_data, write_context.as_ref(), HashMap::new(), true)
-        .await?;
-
-    // Add the file metadata to the transaction
-    txn.add_files(file_metadata);
+    let mut num_rows = 0;
+    for batch in batches {
+        num_rows += batch.num_rows();
+        let file_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(batch),
+                write_context.as_ref(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(file_metadata);
+    }
 
     // Commit the transaction
     match txn.commit(&engine)? {
         CommitResult::Committed { version, .. } => {
             println!("✓ Committed transaction at version {version}");
-            println!("✓ Successfully wrote {} rows to the table", cli.num_rows);
+            println!("✓ Successfully wrote {num_rows} rows to the table");
 
             // Read and display the data
             read_and_display_data(&url, engine).await?;
@@ -122,7 +158,7 @@ async fn try_main() -> DeltaResult<()> {
 async fn create_or_get_base_snapshot(
     url: &Url,
     engine: &dyn Engine,
-    schema_str: &str,
+    schema: &SchemaRef,
 ) -> DeltaResult<SnapshotRef> {
     // Check if table already exists
     match Snapshot::builder_for(url.clone()).build(engine) {
@@ -133,13 +169,57 @@ async fn create_or_get_base_snapshot(
         Err(_) => {
             // Create new table
             println!("Creating new Delta table...");
-            let schema = parse_schema(schema_str)?;
-            create_table(url, &schema).await?;
+            create_table(url, schema).await?;
             Snapshot::builder_for(url.clone()).build(engine)
         }
     }
 }
 
+/// Read the batches of a CSV or parquet file, depending on its extension, and their schema.
+fn read_input(path: &Path) -> DeltaResult<(ArrowSchema, Vec<RecordBatch>)> {
+    let mut file = File::open(path)
+        .map_err(|e| Error::generic(format!("Failed to open {}: {e}", path.display())))?;
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    match extension {
+        Some("csv") => {
+            // infer the schema from the whole file, then read it again from the start
+            let format = Format::default().with_header(true);
+            let (schema, _) = format.infer_schema(&mut file, None)?;
+            file.rewind()
+                .map_err(|e| Error::generic(format!("Failed to read {}: {e}", path.display())))?;
+            let reader = ReaderBuilder::new(Arc::new(schema.clone()))
+                .with_format(format)
+                .build(file)?;
+            Ok((schema, reader.try_collect()?))
+        }
+        Some("parquet") => {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+            let schema = builder.schema().as_ref().clone();
+            Ok((schema, builder.build()?.try_collect()?))
+        }
+        _ => Err(Error::generic(format!(
+            "Unsupported input file {}: expected a .csv or .parquet file",
+            path.display()
+        ))),
+    }
+}
+
+/// Select the columns of `schema` from `batch` by name, and cast them to their type in `schema`,
+/// e.g. the `long` columns inferred from a CSV file to the `integer` columns of the table.
+fn conform_to_schema(batch: &RecordBatch, schema: &ArrowSchema) -> DeltaResult<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = batch.column_by_name(field.name()).ok_or_else(|| {
+                Error::generic(format!("Input is missing column {}", field.name()))
+            })?;
+            Ok(cast(column, field.data_type())?)
+        })
+        .collect::<DeltaResult<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(Arc::new(schema.clone()), columns)?)
+}
+
 /// Parse a schema string into a SchemaRef.
 fn parse_schema(schema_str: &str) -> DeltaResult<SchemaRef> {
     let fields = schema_str
@@ -240,7 +320,7 @@ async fn create_table(table_url: &Url, schema: &SchemaRef) -> DeltaResult<()> {
 }
 
 /// Create sample data based on the schema.
-fn create_sample_data(schema: &SchemaRef, num_rows: usize) -> DeltaResult<ArrowEngineData> {
+fn create_sample_data(schema: &SchemaRef, num_rows: usize) -> DeltaResult<RecordBatch> {
     let fields = schema.fields();
     let mut columns = Vec::new();
 
@@ -284,9 +364,7 @@ fn create_sample_data(schema: &SchemaRef, num_rows: usize) -> DeltaResult<ArrowE
     }
 
     let arrow_schema = schema.as_ref().try_into_arrow()?;
-    let record_batch = RecordBatch::try_new(Arc::new(arrow_schema), columns)?;
-
-    Ok(ArrowEngineData::new(record_batch))
+    Ok(RecordBatch::try_new(Arc::new(arrow_schema), columns)?)
 }
 
 /// Read and display data from the table.
@@ -301,7 +379,7 @@ async fn read_and_display_data(
         .execute(Arc::new(engine))?
         .map(|scan_result| -> DeltaResult<_> {
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch: RecordBatch = data
                 .into_any()
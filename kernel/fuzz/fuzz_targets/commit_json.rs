@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Arbitrary (mostly malformed, truncated or non-UTF-8) content of a commit file
+fuzz_target!(|data: &[u8]| {
+    let _ = delta_kernel_fuzz::replay_commit(&String::from_utf8_lossy(data));
+});
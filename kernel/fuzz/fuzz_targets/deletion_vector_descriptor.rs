@@ -0,0 +1,33 @@
+#![no_main]
+
+use delta_kernel::actions::deletion_vector::DeletionVectorDescriptor;
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::Engine as _;
+use libfuzzer_sys::fuzz_target;
+use object_store::memory::InMemory;
+use url::Url;
+
+// Arbitrary deletion vector descriptors of an add action, which are resolved (and, if inline,
+// decoded) without any validation beyond that of the log's schema
+fuzz_target!(|input: (String, String, Option<i32>)| {
+    let (storage_type, path_or_inline_dv, offset) = input;
+    let descriptor = DeletionVectorDescriptor {
+        storage_type,
+        path_or_inline_dv,
+        offset,
+        size_in_bytes: 0,
+        cardinality: 0,
+    };
+    let parent = Url::parse("memory:///table/").unwrap();
+    // only inline DVs are read, since they don't need storage
+    if let Ok(None) = descriptor.absolute_path(&parent) {
+        let engine = DefaultEngine::new(
+            Arc::new(InMemory::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        );
+        let _ = descriptor.read(engine.storage_handler(), &parent);
+    }
+});
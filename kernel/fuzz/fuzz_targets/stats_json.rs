@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_json::json;
+
+// Arbitrary stats (and paths, e.g. with exotic unicode) of an otherwise valid add action
+fuzz_target!(|input: (String, String)| {
+    let (path, stats) = input;
+    let add = json!({"add": {
+        "path": path,
+        "partitionValues": {},
+        "size": 1,
+        "modificationTime": 0,
+        "dataChange": true,
+        "stats": stats,
+    }});
+    let _ = delta_kernel_fuzz::replay_commit(&add.to_string());
+});
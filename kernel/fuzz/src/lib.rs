@@ -0,0 +1,80 @@
+//! Shared setup of the fuzz targets: replaying a (possibly corrupted) commit with the default
+//! engine, the way a scan of a table with that commit in its log would.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+use delta_kernel::scan::state::{DvInfo, Stats};
+use delta_kernel::{DeltaResult, ExpressionRef, Snapshot};
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::ObjectStore as _;
+use serde_json::json;
+use url::Url;
+
+/// A valid first commit of a table with an `id` and a `value` column.
+fn create_table_commit() -> String {
+    let schema = json!({
+        "type": "struct",
+        "fields": [
+            {"name": "id", "type": "long", "nullable": true, "metadata": {}},
+            {"name": "value", "type": "string", "nullable": true, "metadata": {}},
+        ],
+    });
+    [
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+        json!({"metaData": {
+            "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+            "format": {"provider": "parquet", "options": {}},
+            "schemaString": schema.to_string(),
+            "partitionColumns": [],
+            "configuration": {},
+            "createdTime": 1677811175819u64,
+        }}),
+    ]
+    .map(|action| action.to_string())
+    .join("\n")
+}
+
+fn collect_scan_file(
+    paths: &mut Vec<String>,
+    path: &str,
+    _: i64,
+    _: Option<Stats>,
+    _: DvInfo,
+    _: Option<ExpressionRef>,
+    _: HashMap<String, String>,
+    _: HashMap<String, String>,
+) {
+    paths.push(path.to_string());
+}
+
+/// Replay a table whose second commit is `commit`, with a predicate on `id` so that the stats of
+/// its add actions are parsed too. Corrupted commits must fail with an error, never panic.
+pub fn replay_commit(commit: &str) -> DeltaResult<Vec<String>> {
+    let store = Arc::new(InMemory::new());
+    for (version, commit) in [create_table_commit().as_str(), commit]
+        .into_iter()
+        .enumerate()
+    {
+        let path = Path::from(format!("_delta_log/{version:020}.json"));
+        futures::executor::block_on(store.put(&path, commit.to_string().into()))?;
+    }
+    let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let predicate = Pred::gt(column_expr!("id"), Expr::literal(5i64));
+    let scan = snapshot
+        .scan_builder()
+        .with_predicate(Arc::new(predicate))
+        .build()?;
+    let mut paths = vec![];
+    for scan_metadata in scan.scan_metadata(&engine)? {
+        paths = scan_metadata?.visit_scan_files(paths, collect_scan_file)?;
+    }
+    Ok(paths)
+}
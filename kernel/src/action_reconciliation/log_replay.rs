@@ -32,7 +32,7 @@
 //!
 use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
 use crate::log_replay::{
-    ActionsBatch, FileActionDeduplicator, FileActionKey, HasSelectionVector, LogReplayProcessor,
+    ActionsBatch, FileActionDeduplicator, HasSelectionVector, LogReplayProcessor, SeenFileKeys,
 };
 use crate::scan::data_skipping::DataSkippingFilter;
 use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
@@ -46,8 +46,8 @@ use std::sync::LazyLock;
 /// trait that filters log segment actions.
 pub(crate) struct ActionReconciliationProcessor {
     /// Tracks file actions that have been seen during log replay to avoid duplicates.
-    /// Contains (data file path, dv_unique_id) pairs as `FileActionKey` instances.
-    seen_file_keys: HashSet<FileActionKey>,
+    /// Contains (hashed) (data file path, dv_unique_id) pairs, see [`SeenFileKeys`].
+    seen_file_keys: SeenFileKeys,
     /// Indicates whether a protocol action has been seen in the log.
     seen_protocol: bool,
     /// Indicates whether a metadata action has been seen in the log.
@@ -71,6 +71,8 @@ pub(crate) struct ActionReconciliationBatch {
     pub(crate) actions_count: i64,
     /// The number of add actions in the batch.
     pub(crate) add_actions_count: i64,
+    /// The number of expired remove actions (tombstones) dropped from the batch.
+    pub(crate) expired_tombstones_count: u64,
 }
 
 impl HasSelectionVector for ActionReconciliationBatch {
@@ -94,6 +96,7 @@ impl LogReplayProcessor for ActionReconciliationProcessor {
         let ActionsBatch {
             actions,
             is_log_batch,
+            ..
         } = actions_batch;
         let selection_vector = vec![true; actions.len()];
 
@@ -123,6 +126,7 @@ impl LogReplayProcessor for ActionReconciliationProcessor {
             filtered_data,
             actions_count: visitor.actions_count,
             add_actions_count: visitor.add_actions_count,
+            expired_tombstones_count: visitor.expired_tombstones_count,
         })
     }
 
@@ -200,6 +204,7 @@ pub(crate) struct ActionReconciliationVisitor<'seen> {
     actions_count: i64,
     // i64 to match the `_last_checkpoint` file schema
     add_actions_count: i64,
+    expired_tombstones_count: u64,
     // i64 for comparison with remove.deletionTimestamp
     minimum_file_retention_timestamp: i64,
     // Flag to track if we've seen a protocol action so we can keep only the first protocol action
@@ -230,7 +235,7 @@ impl ActionReconciliationVisitor<'_> {
 
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new<'seen>(
-        seen_file_keys: &'seen mut HashSet<FileActionKey>,
+        seen_file_keys: &'seen mut SeenFileKeys,
         is_log_batch: bool,
         selection_vector: Vec<bool>,
         minimum_file_retention_timestamp: i64,
@@ -251,6 +256,7 @@ impl ActionReconciliationVisitor<'_> {
             selection_vector,
             actions_count: 0,
             add_actions_count: 0,
+            expired_tombstones_count: 0,
             minimum_file_retention_timestamp,
             seen_protocol,
             seen_metadata,
@@ -306,9 +312,12 @@ impl ActionReconciliationVisitor<'_> {
         } else if is_add {
             self.add_actions_count += 1;
             true
-        } else {
+        } else if self.is_expired_tombstone(i, getters[Self::REMOVE_DELETION_TIMESTAMP_INDEX])? {
             // Expired remove actions are not valid
-            !self.is_expired_tombstone(i, getters[Self::REMOVE_DELETION_TIMESTAMP_INDEX])?
+            self.expired_tombstones_count += 1;
+            false
+        } else {
+            true
         };
         Ok(Some(is_valid))
     }
@@ -514,7 +523,7 @@ mod tests {
     #[test]
     fn test_action_reconciliation_visitor() -> DeltaResult<()> {
         let data = action_batch();
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -570,7 +579,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -590,6 +599,7 @@ mod tests {
         assert_eq!(visitor.selection_vector, expected);
         assert_eq!(visitor.actions_count, 1);
         assert_eq!(visitor.add_actions_count, 0);
+        assert_eq!(visitor.expired_tombstones_count, 3);
         Ok(())
     }
 
@@ -601,7 +611,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -640,7 +650,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -673,7 +683,7 @@ mod tests {
         let batch = parse_json_batch(json_strings);
 
         // Pre-populate with txn app1
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         seen_txns.insert("app1".to_string());
 
@@ -713,7 +723,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -868,7 +878,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -1021,7 +1031,7 @@ mod tests {
 
     /// Helper function to create a standard action reconciliation visitor for error testing
     fn create_test_visitor<'a>(
-        seen_file_keys: &'a mut HashSet<FileActionKey>,
+        seen_file_keys: &'a mut SeenFileKeys,
         seen_txns: &'a mut HashSet<String>,
         txn_expiration_timestamp: Option<i64>,
     ) -> ActionReconciliationVisitor<'a> {
@@ -1057,7 +1067,7 @@ mod tests {
     #[test]
     fn test_action_reconciliation_visitor_validation_and_type_errors() {
         // Test 1: Wrong getter count validation
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, None);
         let getter = MockErrorGetData::default();
@@ -1083,7 +1093,7 @@ mod tests {
         ];
 
         for (getter_index, field_name, error_type, expected_error_text) in test_cases {
-            let mut seen_file_keys = HashSet::new();
+            let mut seen_file_keys = SeenFileKeys::default();
             let mut seen_txns = HashSet::new();
             let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, None);
             let getters = create_getters_with_error_at_index(getter_index, field_name, error_type);
@@ -1101,7 +1111,7 @@ mod tests {
     #[test]
     fn test_action_reconciliation_visitor_complex_field_errors() {
         // Test txn.lastUpdated with retention enabled
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, Some(1000));
         let defaults = (0..11)
@@ -1122,7 +1132,7 @@ mod tests {
             .contains("lastUpdated is not of type i64"));
 
         // Test remove.deletionTimestamp
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, None);
         let defaults = (0..4)
@@ -1,14 +1,21 @@
 //! CRC (version checksum) file
-use std::sync::LazyLock;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
 
 use super::visitors::{visit_metadata_at, visit_protocol_at};
 use super::{Add, DomainMetadata, Metadata, Protocol, SetTransaction};
 use crate::actions::PROTOCOL_NAME;
-use crate::engine_data::GetData;
+use crate::engine_data::{GetData, TypedGetData as _};
+use crate::expressions::{ExpressionRef, Scalar};
+use crate::path::ParsedLogPath;
+use crate::scan::state::{DvInfo, Stats};
 use crate::schema::ToSchema as _;
-use crate::schema::{ColumnName, ColumnNamesAndTypes, DataType};
+use crate::schema::{
+    column_name, ColumnName, ColumnNamesAndTypes, DataType, SchemaRef, StructField, StructType,
+};
+use crate::snapshot::SnapshotRef;
 use crate::utils::require;
-use crate::{DeltaResult, Error, RowVisitor};
+use crate::{DeltaResult, Engine, Error, EvaluationHandlerExtension as _, RowVisitor};
 use delta_kernel_derive::ToSchema;
 
 /// Though technically not an action, we include the CRC (version checksum) file here. A [CRC file]
@@ -138,6 +145,109 @@ impl RowVisitor for CrcProtocolMetadataVisitor {
     }
 }
 
+/// Visitor for the number of live files recorded in a CRC file, which tells whether a table
+/// version is empty without replaying its log.
+#[derive(Debug, Default)]
+pub(crate) struct CrcNumFilesVisitor {
+    pub(crate) num_files: Option<i64>,
+}
+
+impl RowVisitor for CrcNumFilesVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("numFiles")], vec![DataType::LONG]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of CrcNumFilesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        require!(
+            row_count == 1,
+            Error::InternalError(format!("Expected 1 row for CRC file, but got {row_count}"))
+        );
+        self.num_files = Some(getters[0].get(0, "numFiles")?);
+        Ok(())
+    }
+}
+
+/// The schema of the CRC files the kernel writes, which only have the required fields of [`Crc`].
+static CRC_WRITE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new_unchecked([
+        StructField::not_null("tableSizeBytes", DataType::LONG),
+        StructField::not_null("numFiles", DataType::LONG),
+        StructField::not_null("numMetadata", DataType::LONG),
+        StructField::not_null("numProtocol", DataType::LONG),
+        StructField::not_null("metadata", Metadata::to_schema()),
+        StructField::not_null("protocol", Protocol::to_schema()),
+    ]))
+});
+
+/// Writes the CRC file of `snapshot`'s version, `{version}.crc`. The number and total size of the
+/// table's live files are computed by replaying its log. Fails with [`Error::FileAlreadyExists`] if
+/// the version already has a CRC file.
+///
+/// The CRC files of tables with in-commit timestamps must record the timestamp of their version,
+/// which the kernel doesn't track, so writing them is unsupported.
+pub(crate) fn write_crc(engine: &dyn Engine, snapshot: SnapshotRef) -> DeltaResult<()> {
+    let table_configuration = snapshot.table_configuration();
+    if table_configuration.is_in_commit_timestamps_enabled() {
+        return Err(Error::unsupported(
+            "Cannot write the CRC file of a table with in-commit timestamps",
+        ));
+    }
+    let path = ParsedLogPath::new_crc(snapshot.table_root(), snapshot.version())?;
+    let metadata = table_configuration.metadata().clone();
+    let protocol = table_configuration.protocol().clone();
+    let (num_files, table_size_bytes) = live_files(engine, snapshot)?;
+    let values: Vec<Scalar> = [
+        table_size_bytes.into(),
+        num_files.into(),
+        1i64.into(),
+        1i64.into(),
+    ]
+    .into_iter()
+    .chain(metadata.into_leaf_values()?)
+    .chain(protocol.into_leaf_values()?)
+    .collect();
+    let crc = engine
+        .evaluation_handler()
+        .create_one(CRC_WRITE_SCHEMA.clone(), &values)?;
+    engine
+        .json_handler()
+        .write_json_file(&path.location, Box::new(std::iter::once(Ok(crc))), false)
+}
+
+/// The number and total size of the live files of `snapshot`.
+fn live_files(engine: &dyn Engine, snapshot: SnapshotRef) -> DeltaResult<(i64, i64)> {
+    #[allow(clippy::too_many_arguments)]
+    fn add_file(
+        (num_files, total_size): &mut (i64, i64),
+        _path: &str,
+        size: i64,
+        _stats: Option<Stats>,
+        _dv_info: DvInfo,
+        _transform: Option<ExpressionRef>,
+        _partition_values: HashMap<String, String>,
+        _tags: HashMap<String, String>,
+    ) {
+        *num_files += 1;
+        *total_size += size;
+    }
+
+    let scan = snapshot.scan_builder().build()?;
+    let mut totals = (0, 0);
+    for scan_metadata in scan.scan_metadata(engine)? {
+        totals = scan_metadata?.visit_scan_files(totals, add_file)?;
+    }
+    Ok(totals)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,16 +68,17 @@ impl DeletionVectorDescriptor {
                     path_len >= 20,
                     Error::DeletionVector(format!("Invalid length {path_len}, must be >= 20"))
                 );
-                let prefix_len = path_len - 20;
-                let decoded = z85::decode(&self.path_or_inline_dv[prefix_len..])
+                // the uuid is z85 (i.e. ASCII) encoded, so a non-ASCII path can't contain one
+                let (prefix, encoded_uuid) = self
+                    .path_or_inline_dv
+                    .split_at_checked(path_len - 20)
+                    .ok_or_else(|| Error::deletion_vector("Failed to decode DV uuid"))?;
+                let decoded = z85::decode(encoded_uuid)
                     .map_err(|_| Error::deletion_vector("Failed to decode DV uuid"))?;
                 let uuid = uuid::Uuid::from_slice(&decoded)
                     .map_err(|err| Error::DeletionVector(err.to_string()))?;
-                let dv_suffix = if prefix_len > 0 {
-                    format!(
-                        "{}/deletion_vector_{uuid}.bin",
-                        &self.path_or_inline_dv[..prefix_len]
-                    )
+                let dv_suffix = if !prefix.is_empty() {
+                    format!("{prefix}/deletion_vector_{uuid}.bin")
                 } else {
                     format!("deletion_vector_{uuid}.bin")
                 };
@@ -111,14 +112,18 @@ impl DeletionVectorDescriptor {
             None => {
                 let byte_slice = z85::decode(&self.path_or_inline_dv)
                     .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
-                let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
-                match magic {
-                    1681511377 => RoaringTreemap::deserialize_from(&byte_slice[4..])
+                require!(
+                    byte_slice.len() >= 4,
+                    Error::deletion_vector("Inline DV is too short")
+                );
+                let (magic, bitmap) = byte_slice.split_at(4);
+                match slice_to_u32(magic, Endian::Little)? {
+                    1681511377 => RoaringTreemap::deserialize_from(bitmap)
                         .map_err(|err| Error::DeletionVector(err.to_string())),
-                    1681511376 => {
-                        todo!("Don't support native serialization in inline bitmaps yet");
-                    }
-                    _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
+                    1681511376 => Err(Error::deletion_vector(
+                        "Native serialization in inline bitmaps is not supported yet",
+                    )),
+                    magic => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
                 }
             }
             Some(path) => {
@@ -286,6 +291,7 @@ pub fn split_vector(
 mod tests {
     use std::path::PathBuf;
 
+    use proptest::prelude::*;
     use roaring::RoaringTreemap;
 
     use crate::{engine::sync::SyncEngine, Engine};
@@ -396,6 +402,63 @@ mod tests {
         assert_eq!(found, expected)
     }
 
+    #[test]
+    fn test_non_ascii_relative_path() {
+        // 21 bytes, whose last 20 don't start on a char boundary
+        let dv = DeletionVectorDescriptor {
+            path_or_inline_dv: format!("{}a", "é".repeat(10)),
+            ..dv_example()
+        };
+        let parent = Url::parse("s3://mytable/").unwrap();
+        assert!(matches!(
+            dv.absolute_path(&parent),
+            Err(Error::DeletionVector(_))
+        ));
+    }
+
+    #[test]
+    fn test_short_inline_dv() {
+        let dv = DeletionVectorDescriptor {
+            path_or_inline_dv: String::new(),
+            ..dv_inline()
+        };
+        let storage = SyncEngine::new().storage_handler();
+        let parent = Url::parse("http://not.used").unwrap();
+        assert!(matches!(
+            dv.read(storage, &parent),
+            Err(Error::DeletionVector(_))
+        ));
+    }
+
+    proptest! {
+        // Corrupted descriptors in the log must surface as errors instead of panics
+        #[test]
+        fn test_absolute_path_never_panics(
+            storage_type in "u|i|p|\\PC{0,2}",
+            path_or_inline_dv in "\\PC{0,64}",
+        ) {
+            let dv = DeletionVectorDescriptor {
+                storage_type,
+                path_or_inline_dv,
+                ..dv_example()
+            };
+            let _ = dv.absolute_path(&Url::parse("s3://mytable/").unwrap());
+        }
+
+        #[test]
+        fn test_inline_read_never_panics(
+            // the z85 alphabet
+            path_or_inline_dv in r"[0-9a-zA-Z.\-:+=^!/*?&<>()\[\]{}@%$#]{0,80}",
+        ) {
+            let dv = DeletionVectorDescriptor {
+                path_or_inline_dv,
+                ..dv_inline()
+            };
+            let storage = SyncEngine::new().storage_handler();
+            let _ = dv.read(storage, &Url::parse("http://not.used").unwrap());
+        }
+    }
+
     // this test is ignored by default as it's expensive to allocate such big vecs full of `true`. you can run it via:
     // cargo test actions::deletion_vector::tests::test_dv_to_bools -- --ignored
     #[test]
@@ -2,18 +2,19 @@
 //! specification](https://github.com/delta-io/delta/blob/master/PROTOCOL.md)
 
 use std::collections::HashMap;
-use std::fmt::{Debug, Display};
-use std::hash::Hash;
+use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::{Arc, LazyLock};
 
 use self::deletion_vector::DeletionVectorDescriptor;
 use crate::expressions::{ArrayData, MapData, Scalar, StructData};
+use crate::redact::{Redacted, RedactedValues};
 use crate::schema::{
-    ArrayType, DataType, MapType, SchemaRef, StructField, StructType, ToSchema as _,
+    ArrayType, DataType, MapType, SchemaLimits, SchemaRef, StructField, StructType, ToSchema as _,
 };
 use crate::table_features::{
-    ReaderFeature, WriterFeature, SUPPORTED_READER_FEATURES, SUPPORTED_WRITER_FEATURES,
+    FeatureOperation, KernelCapability, ReaderFeature, TableFeature, UnsupportedFeature,
+    WriterFeature, SUPPORTED_READER_FEATURES, SUPPORTED_WRITER_FEATURES,
 };
 use crate::table_properties::TableProperties;
 use crate::utils::require;
@@ -26,7 +27,6 @@ use url::Url;
 use visitors::{MetadataVisitor, ProtocolVisitor};
 
 use delta_kernel_derive::{internal_api, IntoEngineData, ToSchema};
-use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 const KERNEL_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -64,6 +64,8 @@ pub(crate) const SIDECAR_NAME: &str = "sidecar";
 pub(crate) const CHECKPOINT_METADATA_NAME: &str = "checkpointMetadata";
 #[internal_api]
 pub(crate) const DOMAIN_METADATA_NAME: &str = "domainMetadata";
+/// The name of the struct-typed copy of `add.stats` that parquet checkpoints may contain.
+pub(crate) const STATS_PARSED_NAME: &str = "stats_parsed";
 
 pub(crate) const INTERNAL_DOMAIN_PREFIX: &str = "delta.";
 
@@ -142,12 +144,8 @@ pub(crate) fn as_log_add_schema(schema: SchemaRef) -> SchemaRef {
     )]))
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(
-    any(test, feature = "internal-api"),
-    derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
-)]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Format {
     /// Name of the encoding for files in this table
@@ -182,12 +180,8 @@ impl TryFrom<Format> for Scalar {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(
-    any(test, feature = "internal-api"),
-    derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
-)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Metadata {
     // TODO: Make the struct fields private to force using the try_new function.
@@ -239,7 +233,7 @@ impl Metadata {
             // both for legacy reasons and to enable possible support for other formats in the
             // future (See delta-io/delta#87).
             format: Format::default(),
-            schema_string: serde_json::to_string(&schema)?,
+            schema_string: schema.to_json()?,
             partition_columns,
             created_time: Some(created_time),
             configuration,
@@ -284,8 +278,17 @@ impl Metadata {
     }
 
     #[internal_api]
+    #[allow(dead_code)]
     pub(crate) fn parse_schema(&self) -> DeltaResult<StructType> {
-        Ok(serde_json::from_str(&self.schema_string)?)
+        StructType::from_json(&self.schema_string)
+    }
+
+    /// Parse the schema, returning an error if it exceeds `limits`.
+    pub(crate) fn parse_schema_with_limits(
+        &self,
+        limits: &SchemaLimits,
+    ) -> DeltaResult<StructType> {
+        StructType::from_json_with_limits(&self.schema_string, limits)
     }
 
     #[internal_api]
@@ -310,8 +313,18 @@ impl IntoEngineData for Metadata {
         schema: SchemaRef,
         engine: &dyn Engine,
     ) -> DeltaResult<Box<dyn EngineData>> {
+        engine
+            .evaluation_handler()
+            .create_one(schema, &self.into_leaf_values()?)
+    }
+}
+
+impl Metadata {
+    /// The values of the leaf fields of [`Metadata::to_schema`], e.g. to create a row that nests
+    /// the metadata.
+    pub(crate) fn into_leaf_values(self) -> DeltaResult<[Scalar; 9]> {
         // For format, we need to provide individual scalars for provider and options
-        let values = [
+        Ok([
             self.id.into(),
             self.name.into(),
             self.description.into(),
@@ -321,9 +334,7 @@ impl IntoEngineData for Metadata {
             self.partition_columns.try_into()?,
             self.created_time.into(),
             self.configuration.try_into()?,
-        ];
-
-        engine.evaluation_handler().create_one(schema, &values)
+        ])
     }
 }
 
@@ -469,10 +480,11 @@ impl Protocol {
                 ))
             }
             // any other min_reader_version is not supported
-            _ => Err(Error::Unsupported(format!(
-                "Unsupported minimum reader version {}",
-                self.min_reader_version
-            ))),
+            _ => Err(Error::UnsupportedFeatures(vec![UnsupportedFeature::new(
+                "minReaderVersion",
+                FeatureOperation::Read,
+                KernelCapability::ReaderVersion(self.min_reader_version),
+            )])),
         }
     }
 
@@ -505,9 +517,11 @@ impl Protocol {
                 // no features, we currently only support version 1 or 2 in this case
                 require!(
                     self.min_writer_version == 1 || self.min_writer_version == 2,
-                    Error::unsupported(
-                        "Currently delta-kernel-rs can only write to tables with protocol.minWriterVersion = 1, 2, or 7"
-                    )
+                    Error::UnsupportedFeatures(vec![UnsupportedFeature::new(
+                        "minWriterVersion",
+                        FeatureOperation::Write,
+                        KernelCapability::WriterVersion(self.min_writer_version),
+                    )])
                 );
                 Ok(())
             }
@@ -522,6 +536,16 @@ impl IntoEngineData for Protocol {
         schema: SchemaRef,
         engine: &dyn Engine,
     ) -> DeltaResult<Box<dyn EngineData>> {
+        engine
+            .evaluation_handler()
+            .create_one(schema, &self.into_leaf_values()?)
+    }
+}
+
+impl Protocol {
+    /// The values of the leaf fields of [`Protocol::to_schema`], e.g. to create a row that nests
+    /// the protocol.
+    pub(crate) fn into_leaf_values(self) -> DeltaResult<[Scalar; 4]> {
         fn features_to_scalar<T>(
             features: Option<impl IntoIterator<Item = T>>,
         ) -> DeltaResult<Scalar>
@@ -543,52 +567,38 @@ impl IntoEngineData for Protocol {
             }
         }
 
-        let values = [
+        Ok([
             self.min_reader_version.into(),
             self.min_writer_version.into(),
             features_to_scalar(self.reader_features)?,
             features_to_scalar(self.writer_features)?,
-        ];
-
-        engine.evaluation_handler().create_one(schema, &values)
+        ])
     }
 }
 
 // given `table_features`, check if they are subset of `supported_features`
-pub(crate) fn ensure_supported_features<T>(
+pub(crate) fn ensure_supported_features<T: TableFeature>(
     table_features: &[T],
     supported_features: &[T],
-) -> DeltaResult<()>
-where
-    T: Display + FromStr + Hash + Eq,
-    <T as FromStr>::Err: Display,
-{
-    // first check if all features are supported, else we proceed to craft an error message
-    if table_features
+) -> DeltaResult<()> {
+    // NB: an empty Vec does not allocate, so the common case stays cheap
+    let unsupported: Vec<_> = table_features
         .iter()
-        .all(|feature| supported_features.contains(feature))
-    {
-        return Ok(());
-    }
-
-    // we get the type name (ReaderFeature/WriterFeature) for better error messages
-    let features_type = std::any::type_name::<T>()
-        .rsplit("::")
-        .next()
-        .unwrap_or("table feature");
-
-    // NB: we didn't do this above to avoid allocation in the common case
-    let mut unsupported = table_features
-        .iter()
-        .filter(|feature| !supported_features.contains(*feature));
-
-    Err(Error::Unsupported(format!(
-        "Unknown {}s: \"{}\". Supported {}s: \"{}\"",
-        features_type,
-        unsupported.join("\", \""),
-        features_type,
-        supported_features.iter().join("\", \""),
-    )))
+        .filter(|feature| !supported_features.contains(feature))
+        .map(|feature| {
+            let required_capability = if feature.is_unknown() {
+                KernelCapability::UnknownFeature
+            } else {
+                KernelCapability::FeatureImplementation
+            };
+            UnsupportedFeature::new(feature, T::BLOCKS, required_capability)
+        })
+        .collect();
+    require!(
+        unsupported.is_empty(),
+        Error::UnsupportedFeatures(unsupported)
+    );
+    Ok(())
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
@@ -608,7 +618,8 @@ pub(crate) struct CommitInfo {
     /// specified by the engine. Read: optional, write: required (that is, kernel alwarys writes).
     pub(crate) operation: Option<String>,
     /// Map of arbitrary string key-value pairs that provide additional information about the
-    /// operation. This is specified by the engine. For now this is always empty on write.
+    /// operation. This is specified by the engine. Kernel writes an empty map if the engine
+    /// specifies no parameters.
     pub(crate) operation_parameters: Option<HashMap<String, String>>,
     /// The version of the delta_kernel crate used to write this commit. The kernel will always
     /// write this field, but it is optional since many tables will not have this field (i.e. any
@@ -618,22 +629,27 @@ pub(crate) struct CommitInfo {
     pub(crate) engine_info: Option<String>,
     /// A unique transaction identifier for this commit.
     pub(crate) txn_id: Option<String>,
+    /// Arbitrary metadata that the user attached to this commit, e.g. to tag it.
+    pub(crate) user_metadata: Option<String>,
 }
 
 impl CommitInfo {
     pub(crate) fn new(
         timestamp: i64,
         operation: Option<String>,
+        operation_parameters: Option<HashMap<String, String>>,
         engine_info: Option<String>,
+        user_metadata: Option<String>,
     ) -> Self {
         Self {
             timestamp: Some(timestamp),
             in_commit_timestamp: None,
             operation: Some(operation.unwrap_or_else(|| UNKNOWN_OPERATION.to_string())),
-            operation_parameters: None,
+            operation_parameters,
             kernel_version: Some(format!("v{KERNEL_VERSION}")),
             engine_info,
             txn_id: Some(uuid::Uuid::new_v4().to_string()),
+            user_metadata,
         }
     }
 }
@@ -653,13 +669,14 @@ impl IntoEngineData for CommitInfo {
             self.kernel_version.into(),
             self.engine_info.into(),
             self.txn_id.into(),
+            self.user_metadata.into(),
         ];
 
         engine.evaluation_handler().create_one(schema, &values)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Clone, PartialEq, Eq, ToSchema)]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
 #[internal_api]
 pub(crate) struct Add {
@@ -719,6 +736,28 @@ pub(crate) struct Add {
     pub clustering_provider: Option<String>,
 }
 
+// partition values and stats (which hold min/max values) may contain sensitive data
+impl Debug for Add {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Add")
+            .field("path", &self.path)
+            .field("partition_values", &RedactedValues(&self.partition_values))
+            .field("size", &self.size)
+            .field("modification_time", &self.modification_time)
+            .field("data_change", &self.data_change)
+            .field("stats", &self.stats.as_ref().map(Redacted))
+            .field("tags", &self.tags)
+            .field("deletion_vector", &self.deletion_vector)
+            .field("base_row_id", &self.base_row_id)
+            .field(
+                "default_row_commit_version",
+                &self.default_row_commit_version,
+            )
+            .field("clustering_provider", &self.clustering_provider)
+            .finish()
+    }
+}
+
 impl Add {
     #[internal_api]
     #[allow(dead_code)]
@@ -727,7 +766,7 @@ impl Add {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Clone, PartialEq, Eq, ToSchema)]
 #[internal_api]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
 pub(crate) struct Remove {
@@ -777,7 +816,30 @@ pub(crate) struct Remove {
     pub(crate) default_row_commit_version: Option<i64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+impl Debug for Remove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Remove")
+            .field("path", &self.path)
+            .field("deletion_timestamp", &self.deletion_timestamp)
+            .field("data_change", &self.data_change)
+            .field("extended_file_metadata", &self.extended_file_metadata)
+            .field(
+                "partition_values",
+                &self.partition_values.as_ref().map(RedactedValues),
+            )
+            .field("size", &self.size)
+            .field("tags", &self.tags)
+            .field("deletion_vector", &self.deletion_vector)
+            .field("base_row_id", &self.base_row_id)
+            .field(
+                "default_row_commit_version",
+                &self.default_row_commit_version,
+            )
+            .finish()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, ToSchema)]
 #[internal_api]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
 pub(crate) struct Cdc {
@@ -812,6 +874,18 @@ pub(crate) struct Cdc {
     pub tags: Option<HashMap<String, String>>,
 }
 
+impl Debug for Cdc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cdc")
+            .field("path", &self.path)
+            .field("partition_values", &RedactedValues(&self.partition_values))
+            .field("size", &self.size)
+            .field("data_change", &self.data_change)
+            .field("tags", &self.tags)
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema, IntoEngineData)]
 #[internal_api]
 pub(crate) struct SetTransaction {
@@ -925,10 +999,17 @@ impl DomainMetadata {
         }
     }
 
+    pub(crate) fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub(crate) fn is_removed(&self) -> bool {
+        self.removed
+    }
+
     // returns true if the domain metadata is an system-controlled domain (all domains that start
     // with "delta.")
-    #[allow(unused)]
-    fn is_internal(&self) -> bool {
+    pub(crate) fn is_internal(&self) -> bool {
         self.domain.starts_with(INTERNAL_DOMAIN_PREFIX)
     }
 }
@@ -1200,6 +1281,7 @@ mod tests {
                 StructField::nullable("kernelVersion", DataType::STRING),
                 StructField::nullable("engineInfo", DataType::STRING),
                 StructField::nullable("txnId", DataType::STRING),
+                StructField::nullable("userMetadata", DataType::STRING),
             ]),
         )]));
         assert_eq!(schema, expected);
@@ -1357,7 +1439,7 @@ mod tests {
         .unwrap();
         assert_result_error_with_message(
             protocol.ensure_write_supported(),
-            r#"Unsupported: Unknown WriterFeatures: "identityColumns". Supported WriterFeatures: "appendOnly", "deletionVectors", "domainMetadata", "invariants", "rowTracking", "timestampNtz", "variantType", "variantType-preview", "variantShredding-preview""#,
+            "Unsupported table features: identityColumns (blocks writes, requires a kernel that implements the feature)",
         );
 
         // Unknown writer features should cause an error
@@ -1370,7 +1452,21 @@ mod tests {
         .unwrap();
         assert_result_error_with_message(
             protocol.ensure_write_supported(),
-            r#"Unsupported: Unknown WriterFeatures: "unsupported writer". Supported WriterFeatures: "appendOnly", "deletionVectors", "domainMetadata", "invariants", "rowTracking", "timestampNtz", "variantType", "variantType-preview", "variantShredding-preview""#,
+            "Unsupported table features: unsupported writer (blocks writes, requires a kernel that knows the feature)",
+        );
+
+        // Writer versions without table features other than 1 and 2 are not supported
+        let protocol = Protocol::try_new(1, 4, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let Err(Error::UnsupportedFeatures(features)) = protocol.ensure_write_supported() else {
+            panic!("Expected unsupported features error");
+        };
+        assert_eq!(
+            features,
+            [UnsupportedFeature::new(
+                "minWriterVersion",
+                FeatureOperation::Write,
+                KernelCapability::WriterVersion(4),
+            )]
         );
     }
 
@@ -1399,15 +1495,31 @@ mod tests {
         let table_features = vec![ReaderFeature::ColumnMapping];
         ensure_supported_features(&table_features, &supported_features).unwrap();
 
-        // test unknown features
-        let table_features = vec![ReaderFeature::ColumnMapping, ReaderFeature::unknown("idk")];
+        // test unknown and unsupported features
+        let table_features = vec![
+            ReaderFeature::ColumnMapping,
+            ReaderFeature::unknown("idk"),
+            ReaderFeature::V2Checkpoint,
+        ];
         let error = ensure_supported_features(&table_features, &supported_features).unwrap_err();
-        match error {
-            Error::Unsupported(e) if e ==
-                "Unknown ReaderFeatures: \"idk\". Supported ReaderFeatures: \"columnMapping\", \"deletionVectors\""
-            => {},
-            _ => panic!("Expected unsupported error, got: {error}"),
-        }
+        let Error::UnsupportedFeatures(features) = error else {
+            panic!("Expected unsupported features error, got: {error}");
+        };
+        assert_eq!(
+            features,
+            [
+                UnsupportedFeature::new(
+                    "idk",
+                    FeatureOperation::Read,
+                    KernelCapability::UnknownFeature
+                ),
+                UnsupportedFeature::new(
+                    "v2Checkpoint",
+                    FeatureOperation::Read,
+                    KernelCapability::FeatureImplementation
+                ),
+            ]
+        );
     }
 
     #[test]
@@ -1469,7 +1581,7 @@ mod tests {
     fn test_commit_info_into_engine_data() {
         let engine = ExprEngine::new();
 
-        let commit_info = CommitInfo::new(0, None, None);
+        let commit_info = CommitInfo::new(0, None, None, None, Some("tag".to_string()));
         let commit_info_txn_id = commit_info.txn_id.clone();
 
         let engine_data = commit_info.into_engine_data(CommitInfo::to_schema().into(), &engine);
@@ -1495,6 +1607,7 @@ mod tests {
                 Arc::new(StringArray::from(vec![Some(format!("v{KERNEL_VERSION}"))])),
                 Arc::new(StringArray::from(vec![None::<String>])),
                 Arc::new(StringArray::from(vec![commit_info_txn_id])),
+                Arc::new(StringArray::from(vec![Some("tag")])),
             ],
         )
         .unwrap();
@@ -1929,4 +2042,30 @@ mod tests {
         assert!(record_batch.column(2).is_null(0));
         assert!(record_batch.column(3).is_null(0));
     }
+
+    #[test]
+    fn test_debug_redacts_partition_values_and_stats() {
+        let _lock = crate::redact::tests::REDACTION_LOCK.lock().unwrap();
+        let add = Add {
+            path: "part-0.parquet".to_string(),
+            partition_values: HashMap::from([("email".to_string(), "a@b.c".to_string())]),
+            stats: Some(r#"{"minValues":{"name":"alice"}}"#.to_string()),
+            ..Default::default()
+        };
+        let debug = format!("{add:?}");
+        assert!(debug.contains("part-0.parquet"), "{debug}");
+        assert!(debug.contains(r#"{"email": <redacted>}"#), "{debug}");
+        assert!(debug.contains("stats: Some(<redacted>)"), "{debug}");
+        assert!(
+            !debug.contains("a@b.c") && !debug.contains("alice"),
+            "{debug}"
+        );
+
+        let remove = Remove {
+            partition_values: Some(add.partition_values.clone()),
+            ..Default::default()
+        };
+        let debug = format!("{remove:?}");
+        assert!(debug.contains(r#"Some({"email": <redacted>})"#), "{debug}");
+    }
 }
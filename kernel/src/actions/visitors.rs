@@ -117,7 +117,7 @@ impl AddVisitor {
         let data_change: bool = getters[4].get(row_index, "add.dataChange")?;
         let stats: Option<String> = getters[5].get_opt(row_index, "add.stats")?;
 
-        // TODO(nick) extract tags if we ever need them at getters[6]
+        let tags: Option<HashMap<_, _>> = getters[6].get_opt(row_index, "add.tags")?;
 
         let deletion_vector = visit_deletion_vector_at(row_index, &getters[7..])?;
 
@@ -134,7 +134,7 @@ impl AddVisitor {
             modification_time,
             data_change,
             stats,
-            tags: None,
+            tags,
             deletion_vector,
             base_row_id,
             default_row_commit_version,
@@ -195,7 +195,7 @@ impl RemoveVisitor {
 
         let size: Option<i64> = getters[5].get_opt(row_index, "remove.size")?;
 
-        // TODO(nick) tags are skipped in getters[6]
+        let tags: Option<HashMap<_, _>> = getters[6].get_opt(row_index, "remove.tags")?;
 
         let deletion_vector = visit_deletion_vector_at(row_index, &getters[7..])?;
 
@@ -210,7 +210,7 @@ impl RemoveVisitor {
             extended_file_metadata,
             partition_values,
             size,
-            tags: None,
+            tags,
             deletion_vector,
             base_row_id,
             default_row_commit_version,
@@ -783,7 +783,7 @@ mod tests {
             r#"{"metaData":{"id":"aff5cb91-8cd9-4195-aef9-446908507302","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"c1\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"c2\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"c3\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["c1","c2"],"configuration":{},"createdTime":1670892997849}}"#,
             r#"{"add":{"path":"c1=4/c2=c/part-00003-f525f459-34f9-46f5-82d6-d42121d883fd.c000.snappy.parquet","partitionValues":{"c1":"4","c2":"c"},"size":452,"modificationTime":1670892998135,"dataChange":true,"stats":"{\"numRecords\":1,\"minValues\":{\"c3\":5},\"maxValues\":{\"c3\":5},\"nullCount\":{\"c3\":0}}"}}"#,
             r#"{"add":{"path":"c1=5/c2=b/part-00007-4e73fa3b-2c88-424a-8051-f8b54328ffdb.c000.snappy.parquet","partitionValues":{"c1":"5","c2":"b"},"size":452,"modificationTime":1670892998136,"dataChange":true,"stats":"{\"numRecords\":1,\"minValues\":{\"c3\":6},\"maxValues\":{\"c3\":6},\"nullCount\":{\"c3\":0}}"}}"#,
-            r#"{"add":{"path":"c1=6/c2=a/part-00011-10619b10-b691-4fd0-acc4-2a9608499d7c.c000.snappy.parquet","partitionValues":{"c1":"6","c2":"a"},"size":452,"modificationTime":1670892998137,"dataChange":true,"stats":"{\"numRecords\":1,\"minValues\":{\"c3\":4},\"maxValues\":{\"c3\":4},\"nullCount\":{\"c3\":0}}"}}"#,
+            r#"{"add":{"path":"c1=6/c2=a/part-00011-10619b10-b691-4fd0-acc4-2a9608499d7c.c000.snappy.parquet","partitionValues":{"c1":"6","c2":"a"},"size":452,"modificationTime":1670892998137,"dataChange":true,"stats":"{\"numRecords\":1,\"minValues\":{\"c3\":4},\"maxValues\":{\"c3\":4},\"nullCount\":{\"c3\":0}}","tags":{"INSERTION_TIME":"1670892998137000"}}}"#,
         ]
         .into();
         let batch = parse_json_batch(json_strings);
@@ -819,6 +819,10 @@ mod tests {
             ]),
             modification_time: 1670892998137,
             stats: Some("{\"numRecords\":1,\"minValues\":{\"c3\":4},\"maxValues\":{\"c3\":4},\"nullCount\":{\"c3\":0}}".into()),
+            tags: Some(HashMap::from([(
+                "INSERTION_TIME".to_string(),
+                "1670892998137000".to_string(),
+            )])),
             ..add1.clone()
         };
         let expected = vec![add1, add2, add3];
@@ -833,7 +837,7 @@ mod tests {
         let json_strings: StringArray = vec![
             r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
             r#"{"metaData":{"id":"aff5cb91-8cd9-4195-aef9-446908507302","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"c1\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"c2\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"c3\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["c1","c2"],"configuration":{},"createdTime":1670892997849}}"#,
-            r#"{"remove":{"path":"c1=4/c2=c/part-00003-f525f459-34f9-46f5-82d6-d42121d883fd.c000.snappy.parquet","deletionTimestamp":1670892998135,"dataChange":true,"partitionValues":{"c1":"4","c2":"c"},"size":452}}"#,
+            r#"{"remove":{"path":"c1=4/c2=c/part-00003-f525f459-34f9-46f5-82d6-d42121d883fd.c000.snappy.parquet","deletionTimestamp":1670892998135,"dataChange":true,"partitionValues":{"c1":"4","c2":"c"},"size":452,"tags":{"ZCUBE_ID":"zc-1"}}}"#,
         ]
         .into();
         let batch = parse_json_batch(json_strings);
@@ -849,6 +853,10 @@ mod tests {
                 ("c2".to_string(), "c".to_string()),
             ])),
             size: Some(452),
+            tags: Some(HashMap::from([(
+                "ZCUBE_ID".to_string(),
+                "zc-1".to_string(),
+            )])),
             ..Default::default()
         };
         assert_eq!(
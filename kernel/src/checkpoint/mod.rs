@@ -17,6 +17,7 @@
 //!
 //! - [`CheckpointWriter`] - Core component that manages the checkpoint creation workflow
 //! - [`CheckpointDataIterator`] - Iterator over the checkpoint data to be written
+//! - [`CheckpointActionFilter`] - Engine hook to filter or augment the checkpoint actions
 //!
 //! ## Usage
 //!
@@ -68,6 +69,14 @@
 //! # Ok::<_, Error>(())
 //! ```
 //!
+//! ## Custom checkpoint policies
+//!
+//! Engines can register [`CheckpointActionFilter`]s with [`CheckpointWriter::with_action_filter`]
+//! to customize the actions of the checkpoint before they are returned by
+//! [`CheckpointWriter::checkpoint_data`], e.g. to drop remove tombstones earlier than the table's
+//! retention requires, or to add engine-specific columns. The counts written to the
+//! `_last_checkpoint` file reflect the actions the filters keep.
+//!
 //! ## Warning
 //! Multi-part (V1) checkpoints are DEPRECATED and UNSAFE.
 //!
@@ -84,26 +93,41 @@
 // Future extensions:
 // - TODO(#837): Multi-file V2 checkpoints are not supported yet. The API is designed to be extensible for future
 //   multi-file support, but the current implementation only supports single-file checkpoints.
+use std::borrow::Cow;
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use crate::action_reconciliation::log_replay::{
     ActionReconciliationBatch, ActionReconciliationProcessor,
 };
-use crate::action_reconciliation::RetentionCalculator;
+use crate::action_reconciliation::{
+    deleted_file_retention_timestamp_with_time, RetentionCalculator,
+};
 use crate::actions::{
     Add, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME, CHECKPOINT_METADATA_NAME,
     METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME, SET_TRANSACTION_NAME, SIDECAR_NAME,
 };
-use crate::engine_data::FilteredEngineData;
-use crate::expressions::Scalar;
+use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{
+    column_expr, Expression, Scalar, Transform, UnaryExpressionOp::ParseJson,
+};
 use crate::last_checkpoint_hint::LastCheckpointHint;
 use crate::log_replay::LogReplayProcessor;
 use crate::path::ParsedLogPath;
-use crate::schema::{DataType, SchemaRef, StructField, StructType, ToSchema as _};
+use crate::scan::data_skipping::{stats_schema, with_stats_parsed};
+use crate::schema::{
+    column_name, ArrayType, ColumnName, ColumnNamesAndTypes, DataType, MapType, SchemaRef,
+    SchemaTransform, StructField, StructType, ToSchema as _,
+};
 use crate::snapshot::SnapshotRef;
 use crate::table_properties::TableProperties;
-use crate::{DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension, FileMeta};
+use crate::utils::{current_time_duration, require};
+use crate::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension, ExpressionEvaluator,
+    FileMeta,
+};
 
+use tracing::info;
 use url::Url;
 
 #[cfg(test)]
@@ -162,6 +186,18 @@ pub struct CheckpointDataIterator {
     actions_count: i64,
     /// Running total of add actions included in the checkpoint
     add_actions_count: i64,
+    /// Running total of expired remove actions (tombstones) dropped from the checkpoint
+    expired_tombstones_count: u64,
+}
+
+impl CheckpointDataIterator {
+    /// The number of expired remove actions (tombstones) dropped from the checkpoint so far,
+    /// i.e. those deleted before the retention of [`CheckpointWriter::with_deleted_file_retention`]
+    /// (or the table's `delta.deletedFileRetentionDuration`). Complete once the iterator is
+    /// exhausted.
+    pub fn expired_tombstones_count(&self) -> u64 {
+        self.expired_tombstones_count
+    }
 }
 
 impl Iterator for CheckpointDataIterator {
@@ -177,11 +213,100 @@ impl Iterator for CheckpointDataIterator {
         Some(self.checkpoint_batch_iterator.next()?.map(|batch| {
             self.actions_count += batch.actions_count;
             self.add_actions_count += batch.add_actions_count;
+            self.expired_tombstones_count += batch.expired_tombstones_count;
             batch.filtered_data
         }))
     }
 }
 
+/// A hook that lets engines customize the actions of a checkpoint, registered with
+/// [`CheckpointWriter::with_action_filter`].
+///
+/// Filters see each batch of reconciled actions before it is returned by the
+/// [`CheckpointDataIterator`], in the order they were registered. The data of a batch has one
+/// action per row, with the `add`, `remove`, `metaData`, `protocol`, `txn` and `sidecar` columns
+/// of the checkpoint schema (and `add.stats_parsed` if the table writes stats as a struct).
+/// Filters never see the `checkpointMetadata` action of V2 checkpoints.
+pub trait CheckpointActionFilter: Send + Sync {
+    /// Filters or augments a batch of checkpoint actions.
+    ///
+    /// Implementations may deselect actions in the selection vector, e.g. remove tombstones past
+    /// a custom retention, or replace the data with data that has additional columns, e.g. an
+    /// engine-computed `add.stats_parsed`. The returned data must keep the rows and the action
+    /// columns of the input. Rows that were not selected stay deselected whatever the filter
+    /// returns, since the kernel already dropped them as duplicate, stale or expired actions, and
+    /// deselecting the protocol or metadata action fails the checkpoint.
+    fn filter_actions(&self, actions: FilteredEngineData) -> DeltaResult<FilteredEngineData>;
+}
+
+/// Counts the selected actions, and the selected add actions among them, of a batch of
+/// checkpoint actions, and checks that the action filters did not deselect the protocol or
+/// metadata action: a checkpoint without them cannot be read.
+struct SelectedActionsCounter<'sv> {
+    /// The rows the kernel selected, before applying the action filters
+    kernel_selection_vector: &'sv [bool],
+    selection_vector: &'sv [bool],
+    actions_count: i64,
+    add_actions_count: i64,
+}
+
+impl RowVisitor for SelectedActionsCounter<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![
+                    column_name!("add.path"),
+                    column_name!("protocol.minReaderVersion"),
+                    column_name!("metaData.id"),
+                ],
+                vec![DataType::STRING, DataType::INTEGER, DataType::STRING],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 3,
+            Error::InternalError(format!(
+                "Wrong number of SelectedActionsCounter getters: {}",
+                getters.len()
+            ))
+        );
+        require!(
+            self.selection_vector.len() == row_count,
+            Error::checkpoint_write(format!(
+                "Checkpoint action filter returned a selection vector of length {} for {} actions",
+                self.selection_vector.len(),
+                row_count
+            ))
+        );
+        for i in 0..row_count {
+            if !self.selection_vector[i] {
+                if self.kernel_selection_vector[i] {
+                    let protocol: Option<i32> =
+                        getters[1].get_opt(i, "protocol.minReaderVersion")?;
+                    let metadata: Option<String> = getters[2].get_opt(i, "metaData.id")?;
+                    require!(
+                        protocol.is_none() && metadata.is_none(),
+                        Error::checkpoint_write(
+                            "Checkpoint action filter deselected the protocol or metadata action"
+                        )
+                    );
+                }
+                continue;
+            }
+            self.actions_count += 1;
+            let path: Option<String> = getters[0].get_opt(i, "add.path")?;
+            if path.is_some() {
+                self.add_actions_count += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Orchestrates the process of creating a checkpoint for a table.
 ///
 /// The [`CheckpointWriter`] is the entry point for generating checkpoint data for a Delta table.
@@ -202,6 +327,12 @@ pub struct CheckpointWriter {
     /// Note: Although the version is stored as a u64 in the snapshot, it is stored as an i64
     /// field here to avoid multiple type conversions.
     version: i64,
+
+    /// The engine's filters for the checkpoint actions, applied in order
+    action_filters: Vec<Arc<dyn CheckpointActionFilter>>,
+
+    /// Overrides the table's `delta.deletedFileRetentionDuration` for dropping tombstones
+    deleted_file_retention: Option<Duration>,
 }
 
 impl RetentionCalculator for CheckpointWriter {
@@ -221,8 +352,33 @@ impl CheckpointWriter {
             ))
         })?;
 
-        Ok(Self { snapshot, version })
+        Ok(Self {
+            snapshot,
+            version,
+            action_filters: vec![],
+            deleted_file_retention: None,
+        })
+    }
+
+    /// Registers a [`CheckpointActionFilter`] to apply to the checkpoint actions, after the
+    /// filters registered before it.
+    pub fn with_action_filter(mut self, filter: Arc<dyn CheckpointActionFilter>) -> Self {
+        self.action_filters.push(filter);
+        self
+    }
+
+    /// Overrides the table's `delta.deletedFileRetentionDuration` (7 days by default) when
+    /// dropping remove actions (tombstones) from the checkpoint: tombstones deleted more than
+    /// `retention` ago are expired and not written.
+    ///
+    /// Tombstones must outlive any reader of the versions that still reference their files, and
+    /// VACUUM relies on them to delete the files, so the override should not be shorter than the
+    /// longest running reader.
+    pub fn with_deleted_file_retention(mut self, retention: Duration) -> Self {
+        self.deleted_file_retention = Some(retention);
+        self
     }
+
     /// Returns the URL where the checkpoint file should be written.
     ///
     /// This method generates the checkpoint path based on the table's root and the version
@@ -254,9 +410,9 @@ impl CheckpointWriter {
     //    `v2Checkpoints` feature support
     // 2. Reads actions from the log segment using the checkpoint read schema
     // 3. Filters and deduplicates actions for the checkpoint
-    // 4. Chains the checkpoint metadata action if writing a V2 spec checkpoint
+    // 4. Applies the engine's action filters, recounting the actions they keep
+    // 5. Chains the checkpoint metadata action if writing a V2 spec checkpoint
     //    (i.e., if `v2Checkpoints` feature is supported by table)
-    // 5. Generates the appropriate checkpoint path
     pub fn checkpoint_data(&self, engine: &dyn Engine) -> DeltaResult<CheckpointDataIterator> {
         let is_v2_checkpoints_supported = self
             .snapshot
@@ -271,12 +427,65 @@ impl CheckpointWriter {
         )?;
 
         // Create iterator over actions for checkpoint data
+        let minimum_file_retention_timestamp = match self.deleted_file_retention {
+            Some(retention) => deleted_file_retention_timestamp_with_time(
+                Some(retention),
+                current_time_duration()?,
+            )?,
+            None => self.deleted_file_retention_timestamp()?,
+        };
         let checkpoint_data = ActionReconciliationProcessor::new(
-            self.deleted_file_retention_timestamp()?,
+            minimum_file_retention_timestamp,
             self.get_transaction_expiration_timestamp()?,
         )
         .process_actions_iter(actions);
 
+        // Add the struct-typed `add.stats_parsed` column if the table asks for it
+        let stats_parsed_evaluator = self.stats_parsed_evaluator(engine);
+        let checkpoint_data = checkpoint_data.map(move |batch| match &stats_parsed_evaluator {
+            Some(evaluator) => {
+                let mut batch = batch?;
+                batch.filtered_data.data = evaluator.evaluate(batch.filtered_data.data.as_ref())?;
+                Ok(batch)
+            }
+            None => batch,
+        });
+
+        let action_filters = self.action_filters.clone();
+        let checkpoint_data = checkpoint_data.map(move |batch| {
+            if action_filters.is_empty() {
+                return batch;
+            }
+            let mut batch = batch?;
+            let kernel_selection_vector = batch.filtered_data.selection_vector.clone();
+            for filter in &action_filters {
+                batch.filtered_data = filter.filter_actions(batch.filtered_data)?;
+            }
+            let selection_vector = &mut batch.filtered_data.selection_vector;
+            require!(
+                selection_vector.len() == kernel_selection_vector.len(),
+                Error::checkpoint_write(format!(
+                    "Checkpoint action filter returned a selection vector of length {} for {} actions",
+                    selection_vector.len(),
+                    kernel_selection_vector.len()
+                ))
+            );
+            // Filters may only deselect actions, never select ones the kernel dropped
+            for (selected, kernel_selected) in selection_vector.iter_mut().zip(&kernel_selection_vector) {
+                *selected &= *kernel_selected;
+            }
+            let mut counter = SelectedActionsCounter {
+                kernel_selection_vector: &kernel_selection_vector,
+                selection_vector: &batch.filtered_data.selection_vector,
+                actions_count: 0,
+                add_actions_count: 0,
+            };
+            counter.visit_rows_of(batch.filtered_data.data.as_ref())?;
+            batch.actions_count = counter.actions_count;
+            batch.add_actions_count = counter.add_actions_count;
+            Ok(batch)
+        });
+
         let checkpoint_metadata =
             is_v2_checkpoints_supported.then(|| self.create_checkpoint_metadata_batch(engine));
 
@@ -285,9 +494,22 @@ impl CheckpointWriter {
             checkpoint_batch_iterator: Box::new(checkpoint_data.chain(checkpoint_metadata)),
             actions_count: 0,
             add_actions_count: 0,
+            expired_tombstones_count: 0,
         })
     }
 
+    /// The schema of the action that [`Self::checkpoint_data`] yields after all other actions, if
+    /// any: the [`CheckpointMetadata`] action of V2 checkpoints. Writers that fix the columns of
+    /// the checkpoint file when they see its first batch need it to include the action.
+    ///
+    /// [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+    pub(crate) fn trailing_action_schema(&self) -> Option<SchemaRef> {
+        self.snapshot
+            .table_configuration()
+            .is_v2_checkpoint_write_supported()
+            .then(|| CHECKPOINT_METADATA_ACTION_SCHEMA.clone())
+    }
+
     /// Finalizes checkpoint creation by saving metadata about the checkpoint.
     ///
     /// # Important
@@ -333,6 +555,10 @@ impl CheckpointWriter {
             size_in_bytes,
         );
 
+        info!(
+            "Finalizing checkpoint of version {} with {} actions, dropped {} expired tombstones",
+            self.version, checkpoint_data.actions_count, checkpoint_data.expired_tombstones_count
+        );
         let last_checkpoint_path = LastCheckpointHint::path(&self.snapshot.log_segment().log_root)?;
 
         // Write the `_last_checkpoint` file to `table/_delta_log/_last_checkpoint`
@@ -345,6 +571,58 @@ impl CheckpointWriter {
         Ok(())
     }
 
+    /// Returns an evaluator that parses the JSON `add.stats` of checkpoint batches into an
+    /// additional struct-typed `add.stats_parsed` column, or None if the table does not enable
+    /// `delta.checkpoint.writeStatsAsStruct`.
+    ///
+    /// Readers with a predicate can then evaluate data skipping directly over `stats_parsed`,
+    /// without parsing the JSON stats of the files the checkpoint covers.
+    fn stats_parsed_evaluator(&self, engine: &dyn Engine) -> Option<Arc<dyn ExpressionEvaluator>> {
+        // Stats are only collected for primitive (and nested struct) data columns
+        struct DropNonStatsColumns;
+        impl<'a> SchemaTransform<'a> for DropNonStatsColumns {
+            fn transform_array(&mut self, _: &'a ArrayType) -> Option<Cow<'a, ArrayType>> {
+                None
+            }
+            fn transform_map(&mut self, _: &'a MapType) -> Option<Cow<'a, MapType>> {
+                None
+            }
+            fn transform_variant(&mut self, _: &'a StructType) -> Option<Cow<'a, StructType>> {
+                None
+            }
+        }
+
+        if self.table_properties().checkpoint_write_stats_as_struct != Some(true) {
+            return None;
+        }
+        let table_configuration = self.snapshot.table_configuration();
+        let partition_columns = table_configuration.metadata().partition_columns();
+        let data_schema = StructType::new_unchecked(
+            self.snapshot
+                .schema()
+                .fields()
+                .filter(|field| !partition_columns.contains(field.name()))
+                .cloned(),
+        )
+        .make_physical(table_configuration.column_mapping_mode());
+        let stats_schema =
+            stats_schema(DropNonStatsColumns.transform_struct(&data_schema)?.as_ref())?;
+
+        let add_schema = Add::to_schema();
+        let last_add_field = add_schema.fields().last().map(|field| field.name().clone());
+        let stats_parsed = Expression::unary(ParseJson, column_expr!("add.stats"));
+        let add_transform = Transform::new_nested([ADD_NAME])
+            .with_inserted_field(last_add_field, stats_parsed.into());
+        let transform = Transform::new_top_level()
+            .with_replaced_field(ADD_NAME, Expression::transform(add_transform).into());
+        let output_schema = with_stats_parsed(&CHECKPOINT_ACTIONS_SCHEMA, &stats_schema);
+        Some(engine.evaluation_handler().new_expression_evaluator(
+            CHECKPOINT_ACTIONS_SCHEMA.clone(),
+            Arc::new(Expression::transform(transform)),
+            output_schema.into(),
+        ))
+    }
+
     /// Creates the checkpoint metadata action for V2 checkpoints.
     ///
     /// This function generates the [`CheckpointMetadata`] action that must be included in the
@@ -379,6 +657,7 @@ impl CheckpointWriter {
             filtered_data,
             actions_count: 1,
             add_actions_count: 0,
+            expired_tombstones_count: 0,
         })
     }
 }
@@ -1,16 +1,18 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use crate::action_reconciliation::{
     deleted_file_retention_timestamp_with_time, DEFAULT_RETENTION_SECS,
 };
 use crate::actions::{Add, Metadata, Protocol, Remove};
-use crate::arrow::array::{ArrayRef, StructArray};
-use crate::arrow::datatypes::{DataType, Schema};
-use crate::checkpoint::create_last_checkpoint_data;
+use crate::arrow::array::{ArrayRef, AsArray as _, StructArray};
+use crate::arrow::datatypes::{DataType, Int32Type, Int64Type, Schema};
+use crate::checkpoint::{create_last_checkpoint_data, CheckpointActionFilter};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::engine_data::FilteredEngineData;
+use crate::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use crate::utils::test_utils::Action;
-use crate::{DeltaResult, FileMeta, Snapshot};
+use crate::{DeltaResult, Error, FileMeta, Snapshot};
 
 use arrow_56::{
     array::{create_array, RecordBatch},
@@ -511,3 +513,373 @@ fn test_v2_checkpoint_supported_table() -> DeltaResult<()> {
 
     Ok(())
 }
+
+/// Tests that checkpoints of tables with `delta.checkpoint.writeStatsAsStruct` enabled carry the
+/// parsed file stats in `add.stats_parsed`
+#[test]
+fn test_checkpoint_writes_stats_parsed() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    // 1st commit: adds `fake_path_1` with stats
+    let add = Action::Add(Add {
+        path: "fake_path_1".into(),
+        data_change: true,
+        stats: Some(
+            r#"{"numRecords":3,"minValues":{"value":1},"maxValues":{"value":5},"nullCount":{"value":0}}"#
+                .into(),
+        ),
+        ..Default::default()
+    });
+    write_commit_to_store(&store, vec![add], 0)?;
+
+    // 2nd commit: metadata & protocol actions
+    let metadata = Action::Metadata(Metadata {
+        id: "test-table".into(),
+        schema_string: "{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}".to_string(),
+        configuration: HashMap::from([(
+            "delta.checkpoint.writeStatsAsStruct".to_string(),
+            "true".to_string(),
+        )]),
+        ..Default::default()
+    });
+    write_commit_to_store(&store, vec![metadata, create_basic_protocol_action()], 1)?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let writer = snapshot.checkpoint()?;
+    let mut batches = writer
+        .checkpoint_data(&engine)?
+        .collect::<DeltaResult<Vec<_>>>()?;
+    assert_eq!(batches.len(), 2);
+
+    // The first batch holds the metadata and protocol actions, which must not get an add action
+    let batch = ArrowEngineData::try_from_engine_data(batches.remove(0).data)?;
+    let add = batch.record_batch().column_by_name("add").unwrap();
+    assert_eq!(add.null_count(), add.len());
+
+    // The (now first) batch holds the add action
+    let batch = ArrowEngineData::try_from_engine_data(batches.remove(0).data)?;
+    let add = batch
+        .record_batch()
+        .column_by_name("add")
+        .unwrap()
+        .as_struct();
+    let stats_parsed = add.column_by_name("stats_parsed").unwrap().as_struct();
+    let num_records = stats_parsed.column_by_name("numRecords").unwrap();
+    assert_eq!(num_records.as_primitive::<Int64Type>().value(0), 3);
+    for (stat, expected) in [("minValues", 1), ("maxValues", 5)] {
+        let values = stats_parsed.column_by_name(stat).unwrap().as_struct();
+        let value = values.column_by_name("value").unwrap();
+        assert_eq!(value.as_primitive::<Int32Type>().value(0), expected);
+    }
+    Ok(())
+}
+
+/// Drops all remove actions from the checkpoint
+struct DropRemoves;
+
+impl CheckpointActionFilter for DropRemoves {
+    fn filter_actions(&self, actions: FilteredEngineData) -> DeltaResult<FilteredEngineData> {
+        let data = ArrowEngineData::try_from_engine_data(actions.data)?;
+        let removes = data.record_batch().column_by_name("remove").unwrap();
+        let selection_vector = actions
+            .selection_vector
+            .iter()
+            .enumerate()
+            .map(|(i, selected)| *selected && removes.is_null(i))
+            .collect();
+        Ok(FilteredEngineData {
+            data,
+            selection_vector,
+        })
+    }
+}
+
+/// Tests that checkpoint action filters apply to the checkpoint data and its action counts
+#[test]
+fn test_checkpoint_action_filter() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    // 1st commit: adds `fake_path_1`
+    write_commit_to_store(&store, vec![create_add_action("fake_path_1")], 0)?;
+
+    // 2nd commit: adds `fake_path_2` & removes `fake_path_1`
+    write_commit_to_store(
+        &store,
+        vec![
+            create_add_action("fake_path_2"),
+            create_remove_action("fake_path_1"),
+        ],
+        1,
+    )?;
+
+    // 3rd commit: metadata & protocol actions
+    write_commit_to_store(
+        &store,
+        vec![create_metadata_action(), create_basic_protocol_action()],
+        2,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let writer = snapshot
+        .checkpoint()?
+        .with_action_filter(Arc::new(DropRemoves));
+
+    let mut data_iter = writer.checkpoint_data(&engine)?;
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true, true]);
+
+    // The remove action of the second batch is dropped by the filter
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true, false]);
+    assert!(data_iter.next().is_none());
+
+    let size_in_bytes = 10;
+    let metadata = FileMeta {
+        location: Url::parse("memory:///fake_path_2")?,
+        last_modified: 0,
+        size: size_in_bytes,
+    };
+    writer.finalize(&engine, &metadata, data_iter)?;
+    // - size: 1 metadata + 1 protocol + 1 add action
+    // - numOfAddFiles: 1 add file from 2nd commit (fake_path_2)
+    assert_last_checkpoint_contents(&store, 2, 3, 1, size_in_bytes)?;
+
+    Ok(())
+}
+
+/// Selects every action of the checkpoint
+struct SelectAll;
+
+impl CheckpointActionFilter for SelectAll {
+    fn filter_actions(&self, actions: FilteredEngineData) -> DeltaResult<FilteredEngineData> {
+        let selection_vector = vec![true; actions.selection_vector.len()];
+        Ok(FilteredEngineData {
+            data: actions.data,
+            selection_vector,
+        })
+    }
+}
+
+/// Tests that checkpoint action filters cannot select actions the kernel dropped
+#[test]
+fn test_checkpoint_action_filter_cannot_select() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    // 1st commit: adds `fake_path_1` & `fake_path_2`
+    write_commit_to_store(
+        &store,
+        vec![
+            create_add_action("fake_path_1"),
+            create_add_action("fake_path_2"),
+        ],
+        0,
+    )?;
+
+    // 2nd commit: re-adds `fake_path_1`
+    write_commit_to_store(&store, vec![create_add_action("fake_path_1")], 1)?;
+
+    // 3rd commit: metadata & protocol actions
+    write_commit_to_store(
+        &store,
+        vec![create_metadata_action(), create_basic_protocol_action()],
+        2,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let writer = snapshot
+        .checkpoint()?
+        .with_action_filter(Arc::new(SelectAll));
+
+    let mut data_iter = writer.checkpoint_data(&engine)?;
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true, true]);
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true]);
+
+    // The first add of `fake_path_1` stays deselected as a duplicate
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [false, true]);
+    assert!(data_iter.next().is_none());
+
+    let size_in_bytes = 10;
+    let metadata = FileMeta {
+        location: Url::parse("memory:///fake_path_2")?,
+        last_modified: 0,
+        size: size_in_bytes,
+    };
+    writer.finalize(&engine, &metadata, data_iter)?;
+    // - size: 1 metadata + 1 protocol + 2 add actions
+    // - numOfAddFiles: `fake_path_1` & `fake_path_2`
+    assert_last_checkpoint_contents(&store, 2, 4, 2, size_in_bytes)?;
+
+    Ok(())
+}
+
+/// Drops the protocol action from the checkpoint
+struct DropProtocol;
+
+impl CheckpointActionFilter for DropProtocol {
+    fn filter_actions(&self, actions: FilteredEngineData) -> DeltaResult<FilteredEngineData> {
+        let data = ArrowEngineData::try_from_engine_data(actions.data)?;
+        let protocols = data.record_batch().column_by_name("protocol").unwrap();
+        let selection_vector = actions
+            .selection_vector
+            .iter()
+            .enumerate()
+            .map(|(i, selected)| *selected && protocols.is_null(i))
+            .collect();
+        Ok(FilteredEngineData {
+            data,
+            selection_vector,
+        })
+    }
+}
+
+/// Tests that checkpoint action filters cannot drop the protocol action
+#[test]
+fn test_checkpoint_action_filter_cannot_drop_protocol() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    write_commit_to_store(
+        &store,
+        vec![create_metadata_action(), create_basic_protocol_action()],
+        0,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let writer = snapshot
+        .checkpoint()?
+        .with_action_filter(Arc::new(DropProtocol));
+
+    let err = writer
+        .checkpoint_data(&engine)?
+        .collect::<DeltaResult<Vec<_>>>()
+        .unwrap_err();
+    assert!(matches!(err, Error::CheckpointWrite(_)));
+
+    Ok(())
+}
+
+/// Tests that the default engine streams the actions of a V2 checkpoint, including the trailing
+/// checkpointMetadata action, into a single parquet file and finalizes it
+#[test]
+fn test_default_engine_write_checkpoint() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()))
+        .with_max_row_group_size(1);
+
+    write_commit_to_store(
+        &store,
+        vec![
+            create_add_action("fake_path_2"),
+            create_remove_action("fake_path_1"),
+        ],
+        0,
+    )?;
+    write_commit_to_store(
+        &store,
+        vec![
+            create_metadata_action(),
+            create_v2_checkpoint_protocol_action(),
+        ],
+        1,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let rt = tokio::runtime::Runtime::new().expect("create tokio runtime");
+    let metadata = rt.block_on(engine.write_checkpoint(snapshot))?;
+    assert_eq!(
+        metadata.location,
+        Url::parse("memory:///_delta_log/00000000000000000001.checkpoint.parquet")?
+    );
+
+    let bytes = rt.block_on(async {
+        let path = Path::from("_delta_log/00000000000000000001.checkpoint.parquet");
+        store.get(&path).await?.bytes().await
+    })?;
+    assert_eq!(bytes.len() as u64, metadata.size);
+    // every action lands in its own row group: metadata, protocol, add, remove and the
+    // checkpointMetadata action, whose columns are null in the other rows
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)?;
+    assert_eq!(reader.metadata().num_row_groups(), 5);
+    assert!(reader
+        .schema()
+        .field_with_name("checkpointMetadata")
+        .is_ok());
+
+    assert_last_checkpoint_contents(&store, 1, 5, 1, metadata.size)?;
+    Ok(())
+}
+
+/// Tests that tombstones older than the deleted file retention are dropped from the checkpoint
+/// and counted, with both the table's default retention and an override
+#[test]
+fn test_checkpoint_drops_expired_tombstones() -> DeltaResult<()> {
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    let now_ms = crate::utils::current_time_ms()?;
+    let remove = |path: &str, deletion_timestamp: i64| {
+        Action::Remove(Remove {
+            path: path.into(),
+            data_change: true,
+            deletion_timestamp: Some(deletion_timestamp),
+            ..Default::default()
+        })
+    };
+
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), create_metadata_action()],
+        0,
+    )?;
+    write_commit_to_store(
+        &store,
+        vec![
+            create_add_action("file1.parquet"),
+            remove("two_days_old.parquet", now_ms - 48 * HOUR_MS),
+            remove("one_hour_old.parquet", now_ms - HOUR_MS),
+        ],
+        1,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let metadata = FileMeta {
+        location: Url::parse("memory:///fake_path")?,
+        last_modified: 0,
+        size: 10,
+    };
+
+    // the default retention of 7 days keeps both tombstones
+    let writer = snapshot.clone().checkpoint()?;
+    let mut data_iter = writer.checkpoint_data(&engine)?;
+    for batch in data_iter.by_ref() {
+        batch?;
+    }
+    assert_eq!(data_iter.expired_tombstones_count(), 0);
+    writer.finalize(&engine, &metadata, data_iter)?;
+    assert_last_checkpoint_contents(&store, 1, 5, 1, 10)?;
+
+    // a retention of 1 day drops the older one
+    let writer = snapshot
+        .checkpoint()?
+        .with_deleted_file_retention(Duration::from_secs(24 * 60 * 60));
+    let mut data_iter = writer.checkpoint_data(&engine)?;
+    for batch in data_iter.by_ref() {
+        batch?;
+    }
+    assert_eq!(data_iter.expired_tombstones_count(), 1);
+    writer.finalize(&engine, &metadata, data_iter)?;
+    assert_last_checkpoint_contents(&store, 1, 4, 1, 10)?;
+
+    Ok(())
+}
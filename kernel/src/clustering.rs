@@ -0,0 +1,339 @@
+//! Clustering keys, which order the rows of data files along a space-filling curve over a set of
+//! columns, e.g. for `OPTIMIZE ... ZORDER BY`.
+//!
+//! Sorting rows by their clustering key keeps rows that are close in all of the columns close
+//! together, so that the files written from the sorted rows have tight min/max statistics for each
+//! of the columns, and data skipping can prune more files for predicates on any of them.
+//!
+//! Engines get the key of each row by evaluating [`clustering_key_expression`], whose result is a
+//! `BINARY` value that sorts (byte-wise) in curve order. The default engine evaluates it natively;
+//! other engines can recognize its [`ClusteringKeyOp`] and compute the keys with
+//! [`clustering_key`], so that all engines construct the same keys.
+
+use crate::expressions::{
+    ColumnName, Expression, OpaqueExpressionOp, Scalar, ScalarExpressionEvaluator,
+};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+use itertools::Itertools;
+
+/// The space-filling curve that clustering keys follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusteringCurve {
+    /// The Z-order (Morton) curve, which interleaves the bits of the columns' values.
+    ZOrder,
+    /// The Hilbert curve, which keeps consecutive keys adjacent in all columns and so clusters
+    /// rows better than the Z-order curve, at a slightly higher cost to compute.
+    Hilbert,
+}
+
+/// The opaque operation of [`clustering_key_expression`], which computes the [`clustering_key`] of
+/// its input expressions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusteringKeyOp {
+    curve: ClusteringCurve,
+}
+
+impl ClusteringKeyOp {
+    /// The curve that the keys computed by this operation follow.
+    pub fn curve(&self) -> ClusteringCurve {
+        self.curve
+    }
+}
+
+impl OpaqueExpressionOp for ClusteringKeyOp {
+    fn name(&self) -> &str {
+        match self.curve {
+            ClusteringCurve::ZOrder => "zorder_key",
+            ClusteringCurve::Hilbert => "hilbert_key",
+        }
+    }
+
+    fn eval_expr_scalar(
+        &self,
+        eval_expr: &ScalarExpressionEvaluator<'_>,
+        exprs: &[Expression],
+    ) -> DeltaResult<Scalar> {
+        let values: Vec<_> = exprs
+            .iter()
+            .map(|expr| {
+                eval_expr(expr).ok_or_else(|| {
+                    Error::generic(format!("Cannot evaluate clustering key input {expr}"))
+                })
+            })
+            .try_collect()?;
+        Ok(Scalar::Binary(clustering_key(self.curve, &values)?))
+    }
+}
+
+/// Returns an expression that computes the `BINARY` clustering key of each row over `columns`,
+/// following `curve`. The order of the columns matters: earlier columns are more significant.
+///
+/// Fails if no columns are given.
+pub fn clustering_key_expression(
+    curve: ClusteringCurve,
+    columns: impl IntoIterator<Item = ColumnName>,
+) -> DeltaResult<Expression> {
+    let columns: Vec<_> = columns.into_iter().map(Expression::Column).collect();
+    require!(
+        !columns.is_empty(),
+        Error::generic("Clustering keys need at least one column")
+    );
+    Ok(Expression::opaque(ClusteringKeyOp { curve }, columns))
+}
+
+/// Computes the clustering key of a row with the given `values` (one per clustering column),
+/// following `curve`. Keys of rows with the same number of values sort byte-wise in curve order.
+///
+/// Each value is first mapped to 64 bits that preserve its order, see [`order_preserving_bits`].
+pub fn clustering_key(curve: ClusteringCurve, values: &[Scalar]) -> DeltaResult<Vec<u8>> {
+    let mut bits: Vec<u64> = values.iter().map(order_preserving_bits).try_collect()?;
+    if curve == ClusteringCurve::Hilbert && bits.len() > 1 {
+        hilbert_transpose(&mut bits);
+    }
+    Ok(interleave(&bits))
+}
+
+/// Maps `value` to 64 bits whose unsigned order matches the order of the values of its type:
+///
+/// - Integers, dates and timestamps keep their value, with the sign bit flipped.
+/// - Floating point numbers keep their IEEE 754 bits, ordered as a total order (NaN is largest).
+/// - Decimals keep their unscaled value, saturated to 64 bits.
+/// - Strings and binary values keep their first 8 bytes, so longer common prefixes don't cluster.
+/// - Nulls are smallest, and `false` is smaller than `true`.
+///
+/// Nested values (structs, arrays and maps) have no such order and are not supported.
+pub fn order_preserving_bits(value: &Scalar) -> DeltaResult<u64> {
+    const SIGN: u64 = 1 << 63;
+    let signed = |value: i64| value as u64 ^ SIGN;
+    let bits = match value {
+        Scalar::Null(_) => 0,
+        Scalar::Boolean(value) => u64::from(*value),
+        Scalar::Byte(value) => signed(i64::from(*value)),
+        Scalar::Short(value) => signed(i64::from(*value)),
+        Scalar::Integer(value) | Scalar::Date(value) => signed(i64::from(*value)),
+        Scalar::Long(value)
+        | Scalar::Timestamp(value)
+        | Scalar::TimestampNtz(value)
+        | Scalar::TimestampWithOffset(value, _) => signed(*value),
+        Scalar::Float(value) => float_bits(f64::from(*value)),
+        Scalar::Double(value) => float_bits(*value),
+        Scalar::Decimal(value) => {
+            let bits = value.bits();
+            signed(i64::try_from(bits).unwrap_or(if bits < 0 { i64::MIN } else { i64::MAX }))
+        }
+        Scalar::String(value) => prefix_bits(value.as_bytes()),
+        Scalar::Binary(value) => prefix_bits(value),
+        Scalar::Struct(_) | Scalar::Array(_) | Scalar::Map(_) => {
+            return Err(Error::unsupported(format!(
+                "Cannot cluster by nested value {value}"
+            )))
+        }
+    };
+    Ok(bits)
+}
+
+fn float_bits(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    }
+}
+
+fn prefix_bits(bytes: &[u8]) -> u64 {
+    let mut prefix = [0; 8];
+    let len = bytes.len().min(8);
+    prefix[..len].copy_from_slice(&bytes[..len]);
+    u64::from_be_bytes(prefix)
+}
+
+/// Interleaves the bits of `values`, from the most significant bit of the first value to the least
+/// significant bit of the last one, into big-endian bytes.
+fn interleave(values: &[u64]) -> Vec<u8> {
+    let mut key = vec![0u8; values.len() * 8];
+    let mut position = 0;
+    for bit in (0..64).rev() {
+        for value in values {
+            if (value >> bit) & 1 == 1 {
+                key[position / 8] |= 0x80 >> (position % 8);
+            }
+            position += 1;
+        }
+    }
+    key
+}
+
+/// Transforms the coordinates of a point into the "transposed" form of its Hilbert index, whose
+/// interleaved bits are the index. This is the `AxestoTranspose` algorithm of J. Skilling,
+/// "Programming the Hilbert curve" (AIP Conference Proceedings 707, 2004).
+fn hilbert_transpose(x: &mut [u64]) {
+    let n = x.len();
+    // inverse undo excess work
+    let mut q: u64 = 1 << 63;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+    // gray encode
+    for i in 1..n {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q: u64 = 1 << 63;
+    while q > 1 {
+        if x[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for value in x.iter_mut() {
+        *value ^= t;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::column_name;
+    use crate::schema::DataType;
+
+    #[test]
+    fn test_order_preserving_bits() {
+        let ordered = [
+            vec![
+                Scalar::Null(DataType::LONG),
+                Scalar::Long(i64::MIN),
+                Scalar::Long(-1),
+                Scalar::Long(0),
+                Scalar::Long(1),
+                Scalar::Long(i64::MAX),
+            ],
+            vec![
+                Scalar::Double(f64::NEG_INFINITY),
+                Scalar::Double(-1.5),
+                Scalar::Double(-0.0),
+                Scalar::Double(0.0),
+                Scalar::Double(1e-300),
+                Scalar::Double(2.5),
+                Scalar::Double(f64::INFINITY),
+            ],
+            vec![
+                Scalar::from(""),
+                Scalar::from("a"),
+                Scalar::from("ab"),
+                Scalar::from("b"),
+            ],
+            vec![Scalar::Boolean(false), Scalar::Boolean(true)],
+        ];
+        for values in ordered {
+            let bits: Vec<_> = values
+                .iter()
+                .map(|value| order_preserving_bits(value).unwrap())
+                .collect();
+            assert!(bits.is_sorted_by(|a, b| a < b), "{values:?}");
+        }
+        assert_eq!(
+            order_preserving_bits(&Scalar::Integer(-1)).unwrap(),
+            order_preserving_bits(&Scalar::Long(-1)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_zorder_key() {
+        let key = clustering_key(
+            ClusteringCurve::ZOrder,
+            &[Scalar::Long(-1), Scalar::Long(i64::MIN)],
+        )
+        .unwrap();
+        // -1 and i64::MIN map to all ones and all zeros
+        assert_eq!(key, vec![0xAA; 16]);
+
+        // the keys of a 4x4 grid follow the Z-order curve, with the first column most significant
+        let grid: Vec<_> = (0..4).cartesian_product(0..4).collect();
+        let sorted = sorted_by_key(ClusteringCurve::ZOrder, &grid);
+        assert_eq!(
+            &sorted[..8],
+            [
+                (0, 0),
+                (0, 1),
+                (1, 0),
+                (1, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (1, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hilbert_key() {
+        // sorted by their keys, the points of an aligned grid form a path through adjacent points
+        let grid: Vec<_> = (0..8).cartesian_product(0..8).collect();
+        let sorted = sorted_by_key(ClusteringCurve::Hilbert, &grid);
+        for ((x1, y1), (x2, y2)) in sorted.iter().tuple_windows() {
+            assert_eq!(
+                (x1 - x2).abs() + (y1 - y2).abs(),
+                1,
+                "({x1}, {y1}) ({x2}, {y2})"
+            );
+        }
+
+        // a single column keeps its order
+        let key = |value| clustering_key(ClusteringCurve::Hilbert, &[Scalar::Integer(value)]);
+        assert!(key(-5).unwrap() < key(3).unwrap());
+    }
+
+    fn sorted_by_key(curve: ClusteringCurve, points: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut sorted = points.to_vec();
+        sorted.sort_by_cached_key(|&(x, y)| {
+            clustering_key(curve, &[Scalar::Integer(x), Scalar::Integer(y)]).unwrap()
+        });
+        sorted
+    }
+
+    #[test]
+    fn test_clustering_key_expression() {
+        let expr = clustering_key_expression(
+            ClusteringCurve::Hilbert,
+            [column_name!("a"), column_name!("b")],
+        )
+        .unwrap();
+        let Expression::Opaque(opaque) = &expr else {
+            panic!("Expected an opaque expression, got {expr}");
+        };
+        let op = opaque
+            .op
+            .any_ref()
+            .downcast_ref::<ClusteringKeyOp>()
+            .unwrap();
+        assert_eq!(op.curve(), ClusteringCurve::Hilbert);
+
+        let values = [Scalar::Integer(3), Scalar::from("x")];
+        let eval_expr = |expr: &Expression| match expr {
+            Expression::Column(name) if *name == column_name!("a") => Some(values[0].clone()),
+            Expression::Column(_) => Some(values[1].clone()),
+            _ => None,
+        };
+        let key = opaque
+            .op
+            .eval_expr_scalar(&eval_expr, &opaque.exprs)
+            .unwrap();
+        let expected = clustering_key(ClusteringCurve::Hilbert, &values).unwrap();
+        assert_eq!(key, Scalar::Binary(expected));
+
+        assert!(clustering_key_expression(ClusteringCurve::ZOrder, []).is_err());
+    }
+}
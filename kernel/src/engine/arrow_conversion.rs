@@ -11,7 +11,8 @@ use itertools::Itertools;
 
 use crate::error::Error;
 use crate::schema::{
-    ArrayType, DataType, MapType, MetadataValue, PrimitiveType, StructField, StructType,
+    ArrayType, ColumnMetadataKey, DataType, MapType, MetadataValue, PrimitiveType, StructField,
+    StructType,
 };
 
 pub(crate) const LIST_ARRAY_ROOT: &str = "element";
@@ -68,6 +69,55 @@ impl TryFromKernel<&StructType> for ArrowSchema {
     }
 }
 
+/// Field metadata keys whose values are JSON numbers in a Delta schema. Arrow metadata values are
+/// always strings, so these are parsed back into numbers when converting from Arrow.
+const NUMBER_METADATA_KEYS: &[ColumnMetadataKey] = &[
+    ColumnMetadataKey::ColumnMappingId,
+    ColumnMetadataKey::ParquetFieldId,
+    ColumnMetadataKey::IdentityStart,
+    ColumnMetadataKey::IdentityStep,
+    ColumnMetadataKey::IdentityHighWaterMark,
+];
+
+/// Field metadata keys whose values are JSON booleans in a Delta schema.
+const BOOLEAN_METADATA_KEYS: &[ColumnMetadataKey] = &[
+    ColumnMetadataKey::IdentityAllowExplicitInsert,
+    ColumnMetadataKey::InternalColumn,
+];
+
+/// Field metadata keys whose values are strings in a Delta schema, even if they look like JSON.
+const STRING_METADATA_KEYS: &[ColumnMetadataKey] = &[
+    ColumnMetadataKey::ColumnMappingPhysicalName,
+    ColumnMetadataKey::GenerationExpression,
+    ColumnMetadataKey::Invariants,
+    ColumnMetadataKey::MetadataSpec,
+    ColumnMetadataKey::CurrentDefault,
+];
+
+fn is_one_of(key: &str, keys: &[ColumnMetadataKey]) -> bool {
+    keys.iter().any(|k| k.as_ref() == key)
+}
+
+/// Recovers the Delta metadata value of an Arrow field metadata entry, undoing the conversion done
+/// by `TryFromKernel<&StructField> for ArrowField`. Values of well-known Delta keys are restored
+/// to their Delta types. Values of other keys are kept as strings, unless they are JSON objects or
+/// arrays (e.g. collations), which are always serialized as JSON.
+fn metadata_value_from_arrow(key: &str, value: &str) -> MetadataValue {
+    let parsed = if is_one_of(key, NUMBER_METADATA_KEYS) {
+        value.parse().ok().map(MetadataValue::Number)
+    } else if is_one_of(key, BOOLEAN_METADATA_KEYS) {
+        value.parse().ok().map(MetadataValue::Boolean)
+    } else if is_one_of(key, STRING_METADATA_KEYS) {
+        None
+    } else {
+        serde_json::from_str(value)
+            .ok()
+            .filter(|v: &serde_json::Value| v.is_object() || v.is_array())
+            .map(MetadataValue::Other)
+    };
+    parsed.unwrap_or_else(|| MetadataValue::String(value.to_string()))
+}
+
 impl TryFromKernel<&StructField> for ArrowField {
     fn try_from_kernel(f: &StructField) -> Result<Self, ArrowError> {
         let metadata = f
@@ -199,12 +249,16 @@ impl TryFromArrow<ArrowSchemaRef> for StructType {
 
 impl TryFromArrow<&ArrowField> for StructField {
     fn try_from_arrow(arrow_field: &ArrowField) -> Result<Self, ArrowError> {
+        let metadata = arrow_field
+            .metadata()
+            .iter()
+            .map(|(k, v)| (k.clone(), metadata_value_from_arrow(k, v)));
         Ok(StructField::new(
             arrow_field.name().clone(),
             DataType::try_from_arrow(arrow_field.data_type())?,
             arrow_field.is_nullable(),
         )
-        .with_metadata(arrow_field.metadata().iter().map(|(k, v)| (k.clone(), v))))
+        .with_metadata(metadata))
     }
 }
 
@@ -324,6 +378,78 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_metadata_roundtrip() -> DeltaResult<()> {
+        let field = StructField::nullable("name", DataType::STRING).with_metadata([
+            (
+                ColumnMetadataKey::ColumnMappingId.as_ref(),
+                MetadataValue::Number(5),
+            ),
+            (
+                ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+                MetadataValue::from("col-5"),
+            ),
+            (
+                ColumnMetadataKey::ParquetFieldId.as_ref(),
+                MetadataValue::Number(5),
+            ),
+            (
+                ColumnMetadataKey::GenerationExpression.as_ref(),
+                MetadataValue::from("upper(other)"),
+            ),
+            (
+                ColumnMetadataKey::InternalColumn.as_ref(),
+                MetadataValue::Boolean(true),
+            ),
+            (
+                "__COLLATIONS",
+                MetadataValue::Other(serde_json::json!({"name": "ICU.en_US"})),
+            ),
+            ("comment", MetadataValue::from("5")),
+        ]);
+        let schema = StructType::try_new([field])?;
+
+        let arrow_schema = ArrowSchema::try_from_kernel(&schema)?;
+        assert_eq!(
+            arrow_schema
+                .field(0)
+                .metadata()
+                .get("delta.columnMapping.id"),
+            Some(&"5".to_string())
+        );
+
+        let roundtrip = StructType::try_from_arrow(&arrow_schema)?;
+        assert_eq!(roundtrip, schema);
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_from_arrow() -> DeltaResult<()> {
+        // Metadata of arrow fields that did not originate from kernel is recovered as well
+        let arrow_field =
+            ArrowField::new("a", ArrowDataType::Int32, true).with_metadata(HashMap::from([
+                ("delta.columnMapping.id".to_string(), "3".to_string()),
+                (
+                    "delta.invariants".to_string(),
+                    r#"{"expression":"a > 0"}"#.to_string(),
+                ),
+                ("PARQUET:field_id".to_string(), "3".to_string()),
+                ("tags".to_string(), r#"["a","b"]"#.to_string()),
+            ]));
+        let field = StructField::try_from_arrow(&arrow_field)?;
+        let expected = StructField::nullable("a", DataType::INTEGER).with_metadata([
+            ("delta.columnMapping.id", MetadataValue::Number(3)),
+            (
+                "delta.invariants",
+                MetadataValue::from(r#"{"expression":"a > 0"}"#),
+            ),
+            ("PARQUET:field_id", MetadataValue::from("3")),
+            ("tags", MetadataValue::Other(serde_json::json!(["a", "b"]))),
+        ]);
+        assert_eq!(field, expected);
+        Ok(())
+    }
+
     #[test]
     fn test_variant_shredded_type_fail() -> DeltaResult<()> {
         let unshredded_variant = DataType::unshredded_variant();
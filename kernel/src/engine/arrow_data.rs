@@ -7,13 +7,17 @@ use tracing::debug;
 use crate::arrow::array::cast::AsArray;
 use crate::arrow::array::types::{Int32Type, Int64Type};
 use crate::arrow::array::{
-    Array, ArrayRef, GenericListArray, MapArray, OffsetSizeTrait, RecordBatch, StructArray,
+    Array, ArrayRef, BooleanArray, GenericListArray, MapArray, OffsetSizeTrait, RecordBatch,
+    StructArray,
 };
+use crate::arrow::compute::filter_record_batch;
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, FieldRef, Schema as ArrowSchema,
 };
 use crate::engine::arrow_conversion::TryIntoArrow as _;
-use crate::engine_data::{EngineData, EngineList, EngineMap, GetData, RowVisitor};
+use crate::engine_data::{
+    EngineData, EngineList, EngineMap, FilteredEngineData, GetData, RowVisitor,
+};
 use crate::expressions::ArrayData;
 use crate::schema::{ColumnName, DataType, SchemaRef};
 use crate::{DeltaResult, Error};
@@ -66,6 +70,20 @@ impl ArrowEngineData {
     }
 }
 
+impl FilteredEngineData {
+    /// The rows of this data that its selection vector selects, as a [`RecordBatch`]. Rows past the
+    /// end of the selection vector are selected. Fails if the data is not [`ArrowEngineData`].
+    pub fn try_into_selected_record_batch(self) -> DeltaResult<RecordBatch> {
+        let batch: RecordBatch = ArrowEngineData::try_from_engine_data(self.data)?.into();
+        let mut selection_vector = self.selection_vector;
+        selection_vector.resize(batch.num_rows(), true);
+        Ok(filter_record_batch(
+            &batch,
+            &BooleanArray::from(selection_vector),
+        )?)
+    }
+}
+
 impl From<RecordBatch> for ArrowEngineData {
     fn from(value: RecordBatch) -> Self {
         ArrowEngineData::new(value)
@@ -237,6 +255,22 @@ impl EngineData for ArrowEngineData {
         let data = RecordBatch::try_new(combined_schema, combined_columns)?;
         Ok(Box::new(ArrowEngineData { data }))
     }
+
+    fn apply_selection_vector(
+        &self,
+        selection_vector: &[bool],
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        if selection_vector.len() != self.len() {
+            return Err(Error::generic(format!(
+                "Selection vector has {} rows, but the data has {}",
+                selection_vector.len(),
+                self.len()
+            )));
+        }
+        let selection_vector = BooleanArray::from(selection_vector.to_vec());
+        let data = filter_record_batch(&self.data, &selection_vector)?;
+        Ok(Box::new(ArrowEngineData { data }))
+    }
 }
 
 impl ArrowEngineData {
@@ -724,4 +758,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_apply_selection_vector() -> DeltaResult<()> {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "id",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2, 3]))])?;
+        let arrow_data = ArrowEngineData::new(batch);
+
+        let selected = arrow_data.apply_selection_vector(&[true, false, true])?;
+        let selected = extract_record_batch(selected.as_ref())?;
+        let ids = selected.column(0).as_primitive::<Int32Type>();
+        assert_eq!(ids, &Int32Array::from(vec![1, 3]));
+
+        let result = arrow_data.apply_selection_vector(&[true, false]);
+        assert_result_error_with_message(result, "Selection vector has 2 rows, but the data has 3");
+        Ok(())
+    }
 }
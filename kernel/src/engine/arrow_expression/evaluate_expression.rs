@@ -6,25 +6,27 @@ use itertools::Itertools;
 
 use crate::arrow::array::types::*;
 use crate::arrow::array::{
-    make_array, Array, ArrayData, ArrayRef, AsArray, BooleanArray, Datum, MutableArrayData,
-    NullBufferBuilder, RecordBatch, StringArray, StructArray,
+    make_array, make_comparator, Array, ArrayData, ArrayRef, AsArray, BinaryBuilder, BooleanArray,
+    Datum, MutableArrayData, NullBufferBuilder, RecordBatch, StringArray, StructArray,
 };
 use crate::arrow::buffer::OffsetBuffer;
 use crate::arrow::compute::kernels::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq, not_distinct};
 use crate::arrow::compute::kernels::comparison::in_list_utf8;
 use crate::arrow::compute::kernels::numeric::{add, div, mul, sub};
-use crate::arrow::compute::{and_kleene, is_not_null, is_null, not, or_kleene};
+use crate::arrow::compute::{and_kleene, is_not_null, is_null, not, or_kleene, SortOptions};
 use crate::arrow::datatypes::{
-    DataType as ArrowDataType, Field as ArrowField, Fields as ArrowFields, IntervalUnit, TimeUnit,
+    DataType as ArrowDataType, Field as ArrowField, Fields as ArrowFields, IntervalUnit,
+    Schema as ArrowSchema, TimeUnit,
 };
 use crate::arrow::error::ArrowError;
 use crate::arrow::json::writer::{make_encoder, EncoderOptions};
 use crate::arrow::json::StructMode;
+use crate::clustering::{clustering_key, ClusteringCurve, ClusteringKeyOp};
 use crate::engine::arrow_conversion::TryIntoArrow;
 use crate::engine::arrow_expression::opaque::{
     ArrowOpaqueExpressionOpAdaptor, ArrowOpaquePredicateOpAdaptor,
 };
-use crate::engine::arrow_utils::prim_array_cmp;
+use crate::engine::arrow_utils::{parse_json_impl, prim_array_cmp};
 use crate::error::{DeltaResult, Error};
 use crate::expressions::{
     BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, Expression,
@@ -33,6 +35,7 @@ use crate::expressions::{
     UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
 };
 use crate::schema::{DataType, StructType};
+use crate::utils::require;
 
 pub(super) trait ProvidesColumnByName {
     fn schema_fields(&self) -> &ArrowFields;
@@ -153,6 +156,9 @@ fn evaluate_transform_expression(
         .map(|path| extract_column(batch, path))
         .transpose()?;
 
+    // A nested transform's output is null wherever its input struct is null.
+    let nulls = source_data.as_ref().and_then(|array| array.logical_nulls());
+
     let source_data: &dyn ProvidesColumnByName = match source_data {
         Some(ref array) => array
             .as_any()
@@ -205,7 +211,7 @@ fn evaluate_transform_expression(
             )
         })
         .collect();
-    let data = StructArray::try_new(output_fields.into(), output_cols, None)?;
+    let data = StructArray::try_new(output_fields.into(), output_cols, nulls)?;
     Ok(Arc::new(data))
 }
 
@@ -250,6 +256,21 @@ pub fn evaluate_expression(
                 "ToJson operator requires STRING output, but got {data_type:?}"
             ))),
         },
+        (
+            Unary(UnaryExpression {
+                op: ParseJson,
+                expr,
+            }),
+            result_type,
+        ) => match result_type {
+            Some(DataType::Struct(output_schema)) => {
+                let input = evaluate_expression(expr, batch, Some(&DataType::STRING))?;
+                parse_json(&input, output_schema)
+            }
+            data_type => Err(Error::generic(format!(
+                "ParseJson operator requires STRUCT output, but got {data_type:?}"
+            ))),
+        },
         (Binary(BinaryExpression { op, left, right }), _) => {
             let left_arr = evaluate_expression(left.as_ref(), batch, None)?;
             let right_arr = evaluate_expression(right.as_ref(), batch, None)?;
@@ -278,20 +299,52 @@ pub fn evaluate_expression(
             Ok(coalesce_arrays(&arrays, result_type)?)
         }
         (Opaque(OpaqueExpression { op, exprs }), _) => {
-            match op
+            if let Some(op) = op
                 .any_ref()
                 .downcast_ref::<ArrowOpaqueExpressionOpAdaptor>()
             {
-                Some(op) => op.eval_expr(exprs, batch, result_type),
-                None => Err(Error::unsupported(format!(
+                op.eval_expr(exprs, batch, result_type)
+            } else if let Some(op) = op.any_ref().downcast_ref::<ClusteringKeyOp>() {
+                require!(
+                    matches!(result_type, None | Some(&DataType::BINARY)),
+                    Error::generic(format!(
+                        "Clustering keys are BINARY, but caller expects {result_type:?}"
+                    ))
+                );
+                clustering_keys(op.curve(), exprs, batch)
+            } else {
+                Err(Error::unsupported(format!(
                     "Unsupported opaque expression: {op:?}"
-                ))),
+                )))
             }
         }
         (Unknown(name), _) => Err(Error::unsupported(format!("Unknown expression: {name:?}"))),
     }
 }
 
+/// Computes the clustering key of each row of `batch` over the values of `exprs`, see
+/// [`crate::clustering`].
+fn clustering_keys(
+    curve: ClusteringCurve,
+    exprs: &[Expression],
+    batch: &RecordBatch,
+) -> DeltaResult<ArrayRef> {
+    let arrays: Vec<ArrayRef> = exprs
+        .iter()
+        .map(|expr| evaluate_expression(expr, batch, None))
+        .try_collect()?;
+    let num_rows = batch.num_rows();
+    let mut keys = BinaryBuilder::with_capacity(num_rows, num_rows * 8 * arrays.len());
+    for row in 0..num_rows {
+        let values: Vec<_> = arrays
+            .iter()
+            .map(|array| Scalar::try_from_array(array.as_ref(), row))
+            .try_collect()?;
+        keys.append_value(clustering_key(curve, &values)?);
+    }
+    Ok(Arc::new(keys.finish()))
+}
+
 /// Evaluates a (possibly inverted) kernel predicate over a record batch
 pub fn evaluate_predicate(
     predicate: &Predicate,
@@ -384,6 +437,10 @@ pub fn evaluate_predicate(
                     let exists = ad.array_elements().contains(lit);
                     Ok(BooleanArray::from(vec![exists]))
                 }
+                (_, Expression::Literal(list @ Scalar::Array(_))) => {
+                    let left = evaluate_expression(left, batch, None)?;
+                    in_literal_list(left.as_ref(), list)
+                }
                 (l, r) => Err(Error::invalid_expression(format!(
                     "Invalid right value for (NOT) IN comparison, left is: {l} right is: {r}"
                 ))),
@@ -436,6 +493,46 @@ pub fn evaluate_predicate(
     }
 }
 
+/// Evaluates `values IN list` for a literal array `list`. Like in SQL, the result is null if the
+/// value is null, or if it matches no element of a list that contains nulls. Values of any type,
+/// including structs and arrays, can be compared; like in Spark, nulls nested in them are equal.
+fn in_literal_list(values: &dyn Array, list: &Scalar) -> DeltaResult<BooleanArray> {
+    let list = list.to_array(1)?;
+    let elements = list
+        .as_list_opt::<i32>()
+        .ok_or_else(|| Error::internal_error("Array literal didn't produce a list array"))?
+        .values();
+    let cmp = make_comparator(values, elements.as_ref(), SortOptions::default())?;
+    let list_has_nulls = elements.null_count() > 0;
+    let result = (0..values.len())
+        .map(|i| {
+            if values.is_null(i) {
+                None
+            } else if (0..elements.len()).any(|j| elements.is_valid(j) && cmp(i, j).is_eq()) {
+                Some(true)
+            } else if list_has_nulls {
+                None
+            } else {
+                Some(false)
+            }
+        })
+        .collect();
+    Ok(result)
+}
+
+/// Parses JSON-encoded strings into a StructArray of the given `schema`. Null strings produce
+/// null structs.
+fn parse_json(input: &ArrayRef, schema: &StructType) -> DeltaResult<ArrayRef> {
+    let json_strings = input
+        .as_string_opt::<i32>()
+        .ok_or_else(|| Error::generic("ParseJson operator requires STRING input"))?;
+    let arrow_schema: ArrowSchema = schema.try_into_arrow()?;
+    let parsed = parse_json_impl(json_strings, Arc::new(arrow_schema))?;
+    let (fields, columns, _) = StructArray::from(parsed).into_parts();
+    let result = StructArray::try_new(fields, columns, json_strings.nulls().cloned())?;
+    Ok(Arc::new(result))
+}
+
 /// Converts a StructArray to JSON-encoded strings
 pub fn to_json(input: &dyn Datum) -> Result<ArrayRef, ArrowError> {
     let (array_ref, _is_scalar) = input.get();
@@ -1099,4 +1196,45 @@ mod tests {
         validate_i32_column(nested_struct_result, 0, &[1, 2, 3]);
         validate_i32_column(nested_struct_result, 1, &[10, 20, 30]);
     }
+    #[test]
+    fn test_nested_transform_propagates_nulls() {
+        use crate::arrow::buffer::NullBuffer;
+
+        let x_field = Arc::new(ArrowField::new("x", ArrowDataType::Int32, true));
+        let nested = StructArray::try_new(
+            vec![x_field.clone()].into(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+            Some(NullBuffer::from(vec![true, false, true])),
+        )
+        .unwrap();
+        let schema = ArrowSchema::new(vec![ArrowField::new(
+            "nested",
+            nested.data_type().clone(),
+            true,
+        )]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(nested)]).unwrap();
+
+        // The transform of a null input struct is null, not a struct of nulls
+        let nested_transform =
+            Transform::new_nested(["nested"]).with_replaced_field("x", Expr::literal(999).into());
+        let transform = Transform::new_top_level()
+            .with_replaced_field("nested", Expr::Transform(nested_transform).into());
+        let nested_output_schema =
+            StructType::new_unchecked([StructField::nullable("x", DataType::INTEGER)]);
+        let output_schema =
+            StructType::new_unchecked([StructField::nullable("nested", nested_output_schema)]);
+        let result = evaluate_expression(
+            &Expr::Transform(transform),
+            &batch,
+            Some(&DataType::Struct(Box::new(output_schema))),
+        )
+        .unwrap();
+
+        let nested_result = result.as_struct().column(0).as_struct();
+        assert_eq!(
+            (0..3).map(|i| nested_result.is_null(i)).collect::<Vec<_>>(),
+            [false, true, false]
+        );
+        validate_i32_column(nested_result, 0, &[999, 999, 999]);
+    }
 }
@@ -1,18 +1,26 @@
 //! Expression handling based on arrow-rs compute kernels.
+use std::borrow::Cow;
 use std::sync::Arc;
 
-use crate::arrow::array::{self, ArrayBuilder, ArrayRef, RecordBatch, StructArray};
+use crate::arrow::array::{
+    self, new_null_array, Array as _, ArrayBuilder, ArrayRef, AsArray as _, RecordBatch,
+    RecordBatchOptions, StructArray,
+};
 use crate::arrow::datatypes::{
-    DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema,
+    DataType as ArrowDataType, Field as ArrowField, FieldRef, Fields, Schema as ArrowSchema,
 };
 
 use super::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
 use crate::engine::arrow_data::{extract_record_batch, ArrowEngineData};
 use crate::error::{DeltaResult, Error};
 use crate::expressions::{ArrayData, Expression, ExpressionRef, PredicateRef, Scalar};
-use crate::schema::{DataType, PrimitiveType, SchemaRef};
+use crate::scan::predicate_binding::normalize_timestamp_literals;
+use crate::schema::{DataType, PrimitiveType, SchemaRef, StructType};
 use crate::utils::require;
-use crate::{EngineData, EvaluationHandler, ExpressionEvaluator, PredicateEvaluator};
+use crate::{
+    EngineData, EvaluationHandler, ExpressionEvaluator, FileDataReadResultIterator,
+    PredicateEvaluator,
+};
 
 use itertools::Itertools;
 use tracing::debug;
@@ -23,6 +31,7 @@ use evaluate_expression::{evaluate_expression, evaluate_predicate, extract_colum
 mod apply_schema;
 pub mod evaluate_expression;
 pub mod opaque;
+mod scalar;
 
 #[cfg(test)]
 mod tests;
@@ -88,7 +97,7 @@ impl Scalar {
             Double(val) => append_val_as!(array::Float64Builder, *val),
             String(val) => append_val_as!(array::StringBuilder, val),
             Boolean(val) => append_val_as!(array::BooleanBuilder, *val),
-            Timestamp(val) | TimestampNtz(val) => {
+            Timestamp(val) | TimestampNtz(val) | TimestampWithOffset(val, _) => {
                 // timezone was already set at builder construction time
                 append_val_as!(array::TimestampMicrosecondBuilder, *val)
             }
@@ -243,14 +252,36 @@ impl EvaluationHandler for ArrowEvaluationHandler {
             input_schema: schema,
             expression,
             output_type,
+            missing_columns_as_null: false,
         })
     }
 
+    fn new_missing_column_tolerant_evaluator(
+        &self,
+        schema: SchemaRef,
+        expression: ExpressionRef,
+        output_type: DataType,
+    ) -> DeltaResult<Arc<dyn ExpressionEvaluator>> {
+        Ok(Arc::new(DefaultExpressionEvaluator {
+            input_schema: schema,
+            expression,
+            output_type,
+            missing_columns_as_null: true,
+        }))
+    }
+
     fn new_predicate_evaluator(
         &self,
         schema: SchemaRef,
         predicate: PredicateRef,
     ) -> Arc<dyn PredicateEvaluator> {
+        // timestamp literals with UTC offsets compare by instant or by local time, depending on
+        // the type of the columns they are compared with
+        let normalized = match normalize_timestamp_literals(&predicate, &schema) {
+            Cow::Owned(normalized) => Some(Arc::new(normalized)),
+            Cow::Borrowed(_) => None,
+        };
+        let predicate = normalized.unwrap_or(predicate);
         Arc::new(DefaultPredicateEvaluator {
             input_schema: schema,
             predicate,
@@ -269,6 +300,12 @@ impl EvaluationHandler for ArrowEvaluationHandler {
             RecordBatch::try_new(Arc::new(output_schema.as_ref().try_into_arrow()?), arrays)?;
         Ok(Box::new(ArrowEngineData::new(record_batch)))
     }
+
+    fn empty_batch(&self, output_schema: SchemaRef) -> DeltaResult<Box<dyn EngineData>> {
+        let schema: ArrowSchema = output_schema.as_ref().try_into_arrow()?;
+        let record_batch = RecordBatch::new_empty(Arc::new(schema));
+        Ok(Box::new(ArrowEngineData::new(record_batch)))
+    }
 }
 
 #[derive(Debug)]
@@ -276,22 +313,29 @@ pub struct DefaultExpressionEvaluator {
     input_schema: SchemaRef,
     expression: ExpressionRef,
     output_type: DataType,
+    /// Whether columns of the input schema that a batch lacks evaluate to null
+    missing_columns_as_null: bool,
 }
 
-impl ExpressionEvaluator for DefaultExpressionEvaluator {
-    fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>> {
-        debug!("Arrow evaluator evaluating: {:#?}", self.expression);
-        let batch = extract_record_batch(batch)?;
-        let _input_schema: ArrowSchema = self.input_schema.as_ref().try_into_arrow()?;
-        // TODO: make sure we have matching schemas for validation
-        // if batch.schema().as_ref() != &input_schema {
-        //     return Err(Error::Generic(format!(
-        //         "input schema does not match batch schema: {:?} != {:?}",
-        //         input_schema,
-        //         batch.schema()
-        //     )));
-        // };
+impl DefaultExpressionEvaluator {
+    /// The schema of the output of expressions that don't produce a struct: a single column named
+    /// "output" of the output type.
+    fn scalar_output_schema(&self) -> DeltaResult<Option<Arc<ArrowSchema>>> {
+        if matches!(self.output_type, DataType::Struct(_)) {
+            return Ok(None);
+        }
+        let arrow_type = ArrowDataType::try_from_kernel(&self.output_type)?;
+        let field = ArrowField::new("output", arrow_type, true);
+        Ok(Some(Arc::new(ArrowSchema::new(vec![field]))))
+    }
 
+    /// Evaluates the expression on a batch that has all columns of the input schema.
+    fn evaluate_record_batch(
+        &self,
+        batch: &RecordBatch,
+        scalar_output_schema: Option<&Arc<ArrowSchema>>,
+    ) -> DeltaResult<RecordBatch> {
+        // TODO: make sure we have matching schemas for validation
         let batch = match (self.expression.as_ref(), &self.output_type) {
             (Expression::Transform(transform), DataType::Struct(_)) if transform.is_identity() => {
                 // Empty transform optimization: Skip expression evaluation and directly apply the
@@ -310,14 +354,124 @@ impl ExpressionEvaluator for DefaultExpressionEvaluator {
             (expr, output_type) => {
                 let array_ref = evaluate_expression(expr, batch, Some(output_type))?;
                 let array_ref = apply_schema_to(&array_ref, output_type)?;
-                let arrow_type = ArrowDataType::try_from_kernel(output_type)?;
-                let schema = ArrowSchema::new(vec![ArrowField::new("output", arrow_type, true)]);
-                RecordBatch::try_new(Arc::new(schema), vec![array_ref])?
+                let schema = scalar_output_schema.ok_or_else(|| {
+                    Error::internal_error("Missing the output schema of a scalar expression")
+                })?;
+                RecordBatch::try_new(schema.clone(), vec![array_ref])?
             }
         };
+        Ok(batch)
+    }
+}
 
+impl ExpressionEvaluator for DefaultExpressionEvaluator {
+    fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>> {
+        debug!("Arrow evaluator evaluating: {:#?}", self.expression);
+        let batch = extract_record_batch(batch)?;
+        let filled_batch;
+        let batch = if self.missing_columns_as_null {
+            filled_batch = fill_missing_columns(batch, &self.input_schema)?;
+            &filled_batch
+        } else {
+            batch
+        };
+        let scalar_output_schema = self.scalar_output_schema()?;
+        let batch = self.evaluate_record_batch(batch, scalar_output_schema.as_ref())?;
         Ok(Box::new(ArrowEngineData::new(batch)))
     }
+
+    /// Evaluates the batches like [`Self::evaluate`], except that the output schema of scalar
+    /// expressions is built once per stream, and batches are only checked for missing columns
+    /// when their schema differs from the previous complete batch's. Column references are still
+    /// resolved by name for every batch.
+    fn evaluate_batches(
+        self: Arc<Self>,
+        batches: FileDataReadResultIterator,
+    ) -> FileDataReadResultIterator {
+        debug!("Arrow evaluator evaluating batches: {:#?}", self.expression);
+        let scalar_output_schema = match self.scalar_output_schema() {
+            Ok(schema) => schema,
+            Err(err) => return Box::new(std::iter::once(Err(err))),
+        };
+        // The schema of the last batch that had all columns of the input schema. Batches with the
+        // same schema need not be checked for missing columns again.
+        let mut complete_schema = None;
+        Box::new(
+            batches.map(move |batch| -> DeltaResult<Box<dyn EngineData>> {
+                let batch = batch?;
+                let batch = extract_record_batch(batch.as_ref())?;
+                let filled_batch;
+                let batch = if self.missing_columns_as_null
+                    && complete_schema.as_ref() != Some(&batch.schema())
+                {
+                    filled_batch = fill_missing_columns(batch, &self.input_schema)?;
+                    if filled_batch.schema() == batch.schema() {
+                        complete_schema = Some(batch.schema());
+                    }
+                    &filled_batch
+                } else {
+                    batch
+                };
+                let batch = self.evaluate_record_batch(batch, scalar_output_schema.as_ref())?;
+                Ok(Box::new(ArrowEngineData::new(batch)))
+            }),
+        )
+    }
+}
+
+/// Adds an all-null column to `batch` for each field of `schema` it lacks, recursing into struct
+/// columns.
+fn fill_missing_columns(batch: &RecordBatch, schema: &StructType) -> DeltaResult<RecordBatch> {
+    let num_rows = batch.num_rows();
+    let (fields, columns) =
+        fill_missing_fields(batch.schema().fields(), batch.columns(), num_rows, schema)?;
+    let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+    let schema = Arc::new(ArrowSchema::new(fields));
+    Ok(RecordBatch::try_new_with_options(
+        schema, columns, &options,
+    )?)
+}
+
+fn fill_missing_fields(
+    fields: &Fields,
+    columns: &[ArrayRef],
+    num_rows: usize,
+    schema: &StructType,
+) -> DeltaResult<(Vec<FieldRef>, Vec<ArrayRef>)> {
+    let mut fields: Vec<FieldRef> = fields.iter().cloned().collect();
+    let mut columns = columns.to_vec();
+    for (field, column) in fields.iter_mut().zip(columns.iter_mut()) {
+        let Some(DataType::Struct(child_schema)) =
+            schema.field(field.name()).map(|f| f.data_type())
+        else {
+            continue;
+        };
+        let Some(child) = column.as_struct_opt() else {
+            continue;
+        };
+        let (child_fields, child_columns) =
+            fill_missing_fields(child.fields(), child.columns(), child.len(), child_schema)?;
+        let child =
+            StructArray::try_new(child_fields.into(), child_columns, child.nulls().cloned())?;
+        *field = Arc::new(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(child.data_type().clone()),
+        );
+        *column = Arc::new(child);
+    }
+    for kernel_field in schema.fields() {
+        if fields
+            .iter()
+            .all(|field| field.name() != kernel_field.name())
+        {
+            let field = ArrowField::try_from_kernel(kernel_field)?.with_nullable(true);
+            columns.push(new_null_array(field.data_type(), num_rows));
+            fields.push(Arc::new(field));
+        }
+    }
+    Ok((fields, columns))
 }
 
 #[derive(Debug)]
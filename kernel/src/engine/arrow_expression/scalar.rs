@@ -0,0 +1,214 @@
+//! Conversions between kernel [`Scalar`]s and arrow scalars, i.e. single values of arrow arrays.
+//!
+//! Engines can use them to turn the literals of their query plans into kernel predicates (and back)
+//! without hand-rolling conversions that lose e.g. the precision of decimals or the timezone of
+//! timestamps.
+
+use crate::arrow::array::{self, Array, ArrayRef, AsArray as _, Datum as _};
+use crate::arrow::datatypes::{
+    DataType as ArrowDataType, Date32Type, Date64Type, Decimal128Type, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+};
+use crate::engine::arrow_conversion::TryFromArrow as _;
+use crate::error::{DeltaResult, Error};
+use crate::expressions::{ArrayData, DecimalData, MapData, Scalar, StructData};
+use crate::schema::{ArrayType, DataType, DecimalType, MapType, StructField};
+use crate::utils::require;
+
+use itertools::Itertools;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+impl Scalar {
+    /// Convert scalar to an arrow [`Scalar`](array::Scalar), e.g. to compare arrow arrays with it.
+    pub fn to_arrow_scalar(&self) -> DeltaResult<array::Scalar<ArrayRef>> {
+        Ok(array::Scalar::new(self.to_array(1)?))
+    }
+
+    /// Convert an arrow [`Scalar`](array::Scalar) to a kernel scalar, see
+    /// [`Scalar::try_from_array`].
+    pub fn try_from_arrow_scalar(scalar: &array::Scalar<ArrayRef>) -> DeltaResult<Self> {
+        let (array, _) = scalar.get();
+        Self::try_from_array(array, 0)
+    }
+
+    /// Convert the value at `index` of an arrow array to a scalar. A null value becomes a
+    /// [`Scalar::Null`] of the array's type.
+    ///
+    /// Timestamps with a timezone become [`Scalar::Timestamp`]s (arrow stores them adjusted to
+    /// UTC, whatever their timezone), and timestamps without one [`Scalar::TimestampNtz`]s.
+    /// Timestamps in seconds, milliseconds or nanoseconds are converted to microseconds, and fail
+    /// to convert if that loses precision. Unsigned integers, dictionaries and other arrow types
+    /// that have no kernel equivalent are not supported.
+    pub fn try_from_array(array: &dyn Array, index: usize) -> DeltaResult<Self> {
+        require!(
+            index < array.len(),
+            Error::generic(format!(
+                "Index {index} is out of bounds for an array of length {}",
+                array.len()
+            ))
+        );
+        if array.is_null(index) {
+            return Ok(Self::Null(kernel_data_type(array.data_type())?));
+        }
+        let scalar = match array.data_type() {
+            ArrowDataType::Boolean => Self::Boolean(array.as_boolean().value(index)),
+            ArrowDataType::Int8 => Self::Byte(array.as_primitive::<Int8Type>().value(index)),
+            ArrowDataType::Int16 => Self::Short(array.as_primitive::<Int16Type>().value(index)),
+            ArrowDataType::Int32 => Self::Integer(array.as_primitive::<Int32Type>().value(index)),
+            ArrowDataType::Int64 => Self::Long(array.as_primitive::<Int64Type>().value(index)),
+            ArrowDataType::Float32 => Self::Float(array.as_primitive::<Float32Type>().value(index)),
+            ArrowDataType::Float64 => {
+                Self::Double(array.as_primitive::<Float64Type>().value(index))
+            }
+            ArrowDataType::Utf8 => Self::String(array.as_string::<i32>().value(index).into()),
+            ArrowDataType::LargeUtf8 => Self::String(array.as_string::<i64>().value(index).into()),
+            ArrowDataType::Utf8View => Self::String(array.as_string_view().value(index).into()),
+            ArrowDataType::Binary => Self::Binary(array.as_binary::<i32>().value(index).into()),
+            ArrowDataType::LargeBinary => {
+                Self::Binary(array.as_binary::<i64>().value(index).into())
+            }
+            ArrowDataType::BinaryView => Self::Binary(array.as_binary_view().value(index).into()),
+            ArrowDataType::FixedSizeBinary(_) => {
+                Self::Binary(array.as_fixed_size_binary().value(index).into())
+            }
+            ArrowDataType::Date32 => Self::Date(array.as_primitive::<Date32Type>().value(index)),
+            ArrowDataType::Date64 => {
+                let millis = array.as_primitive::<Date64Type>().value(index);
+                let days = i32::try_from(millis.div_euclid(MILLIS_PER_DAY))
+                    .map_err(|_| Error::generic(format!("Date {millis}ms is out of range")))?;
+                Self::Date(days)
+            }
+            ArrowDataType::Timestamp(unit, tz) => {
+                let micros = timestamp_micros(array, *unit, index)?;
+                match tz {
+                    Some(_) => Self::Timestamp(micros),
+                    None => Self::TimestampNtz(micros),
+                }
+            }
+            ArrowDataType::Decimal128(precision, scale) => {
+                let bits = array.as_primitive::<Decimal128Type>().value(index);
+                let ty = DecimalType::try_new(*precision, decimal_scale(*scale)?)?;
+                Self::Decimal(DecimalData::try_new(bits, ty)?)
+            }
+            ArrowDataType::Struct(fields) => {
+                let array = array.as_struct();
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|field| kernel_field(field.name(), field.data_type(), field.is_nullable()))
+                    .try_collect()?;
+                let values = array
+                    .columns()
+                    .iter()
+                    .map(|column| Self::try_from_array(column.as_ref(), index))
+                    .try_collect()?;
+                Self::Struct(StructData::try_new(fields, values)?)
+            }
+            ArrowDataType::List(field) | ArrowDataType::LargeList(field) => {
+                let values = match array.data_type() {
+                    ArrowDataType::List(_) => array.as_list::<i32>().value(index),
+                    _ => array.as_list::<i64>().value(index),
+                };
+                let element_type = kernel_data_type(field.data_type())?;
+                let array_type = ArrayType::new(element_type, field.is_nullable());
+                Self::Array(ArrayData::try_new(array_type, scalars(values.as_ref())?)?)
+            }
+            ArrowDataType::Map(..) => {
+                let DataType::Map(map_type) = kernel_data_type(array.data_type())? else {
+                    return Err(Error::internal_error("Map array has a non-map kernel type"));
+                };
+                let entries = array.as_map().value(index);
+                let keys = scalars(entries.column(0).as_ref())?;
+                let values = scalars(entries.column(1).as_ref())?;
+                Self::Map(MapData::try_new(*map_type, keys.into_iter().zip(values))?)
+            }
+            data_type => {
+                return Err(Error::unsupported(format!(
+                    "Arrow values of type {data_type} cannot be converted to a scalar"
+                )))
+            }
+        };
+        Ok(scalar)
+    }
+}
+
+/// Converts every value of `array` to a scalar.
+fn scalars(array: &dyn Array) -> DeltaResult<Vec<Scalar>> {
+    (0..array.len())
+        .map(|index| Scalar::try_from_array(array, index))
+        .collect()
+}
+
+fn timestamp_micros(array: &dyn Array, unit: TimeUnit, index: usize) -> DeltaResult<i64> {
+    let micros = match unit {
+        TimeUnit::Second => array
+            .as_primitive::<TimestampSecondType>()
+            .value(index)
+            .checked_mul(1_000_000),
+        TimeUnit::Millisecond => array
+            .as_primitive::<TimestampMillisecondType>()
+            .value(index)
+            .checked_mul(1_000),
+        TimeUnit::Microsecond => Some(
+            array
+                .as_primitive::<TimestampMicrosecondType>()
+                .value(index),
+        ),
+        TimeUnit::Nanosecond => {
+            let nanos = array.as_primitive::<TimestampNanosecondType>().value(index);
+            (nanos % 1_000 == 0).then_some(nanos / 1_000)
+        }
+    };
+    micros.ok_or_else(|| {
+        Error::generic(format!(
+            "Timestamp at index {index} cannot be represented in microseconds"
+        ))
+    })
+}
+
+fn decimal_scale(scale: i8) -> DeltaResult<u8> {
+    u8::try_from(scale)
+        .map_err(|_| Error::invalid_decimal("Negative scales are not supported in Delta"))
+}
+
+fn kernel_field(name: &str, data_type: &ArrowDataType, nullable: bool) -> DeltaResult<StructField> {
+    Ok(StructField::new(
+        name,
+        kernel_data_type(data_type)?,
+        nullable,
+    ))
+}
+
+/// The kernel type of the scalars converted from arrow values of type `data_type`. Unlike the
+/// conversion of arrow schemas, this accepts timestamps of any unit and timezone, and ignores field
+/// metadata, so that it matches the types of the converted scalars.
+fn kernel_data_type(data_type: &ArrowDataType) -> DeltaResult<DataType> {
+    let data_type = match data_type {
+        ArrowDataType::Timestamp(_, Some(_)) => DataType::TIMESTAMP,
+        ArrowDataType::Timestamp(_, None) => DataType::TIMESTAMP_NTZ,
+        ArrowDataType::Struct(fields) => DataType::try_struct_type(
+            fields
+                .iter()
+                .map(|field| kernel_field(field.name(), field.data_type(), field.is_nullable()))
+                .try_collect::<_, Vec<_>, _>()?,
+        )?,
+        ArrowDataType::List(field) | ArrowDataType::LargeList(field) => {
+            ArrayType::new(kernel_data_type(field.data_type())?, field.is_nullable()).into()
+        }
+        ArrowDataType::Map(field, _) => {
+            let ArrowDataType::Struct(entries) = field.data_type() else {
+                return Err(Error::generic("Map array entries must be a struct"));
+            };
+            require!(
+                entries.len() == 2,
+                Error::generic("Map array entries must have a key and a value")
+            );
+            let key_type = kernel_data_type(entries[0].data_type())?;
+            let value_type = kernel_data_type(entries[1].data_type())?;
+            MapType::new(key_type, value_type, entries[1].is_nullable()).into()
+        }
+        data_type => DataType::try_from_arrow(data_type)?,
+    };
+    Ok(data_type)
+}
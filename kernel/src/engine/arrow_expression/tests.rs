@@ -115,6 +115,90 @@ fn test_literal_type_array() {
     assert_eq!(result, in_expected);
 }
 
+#[test]
+fn test_column_in_literal_list() {
+    let values = Int32Array::from(vec![Some(1), Some(2), None]);
+    let field = Arc::new(Field::new("item", DataType::Int32, true));
+    let schema = Schema::new([field]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+
+    let list = |elements: Vec<Option<i32>>| Scalar::try_from(elements).unwrap();
+    let in_op = Pred::binary(
+        BinaryPredicateOp::In,
+        column_expr!("item"),
+        list(vec![Some(1), Some(3)]),
+    );
+    let result = evaluate_predicate(&in_op, &batch, false).unwrap();
+    assert_eq!(
+        result,
+        BooleanArray::from(vec![Some(true), Some(false), None])
+    );
+    let result = evaluate_predicate(&in_op, &batch, true).unwrap();
+    assert_eq!(
+        result,
+        BooleanArray::from(vec![Some(false), Some(true), None])
+    );
+
+    // a value that matches no element of a list with nulls might be one of them
+    let in_op = Pred::binary(
+        BinaryPredicateOp::In,
+        column_expr!("item"),
+        list(vec![Some(1), None]),
+    );
+    let result = evaluate_predicate(&in_op, &batch, false).unwrap();
+    assert_eq!(result, BooleanArray::from(vec![Some(true), None, None]));
+}
+
+#[test]
+fn test_struct_column_in_literal_list() {
+    let fields = vec![
+        StructField::nullable("a", KernelDataType::INTEGER),
+        StructField::nullable("b", KernelDataType::STRING),
+    ];
+    let make_struct = |a: i32, b: Option<&str>| {
+        let b = b.map_or(Scalar::Null(KernelDataType::STRING), Scalar::from);
+        Scalar::from(StructData::try_new(fields.clone(), vec![a.into(), b]).unwrap())
+    };
+    let arrow_fields = Fields::from(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Utf8, true),
+    ]);
+    let column = StructArray::new(
+        arrow_fields.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec![Some("x"), Some("y"), None])),
+        ],
+        None,
+    );
+    let schema = Schema::new([Field::new("s", DataType::Struct(arrow_fields), true)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(column)]).unwrap();
+
+    let list = ArrayData::try_new(
+        ArrayType::new(StructType::new_unchecked(fields.clone()).into(), false),
+        [
+            make_struct(1, Some("x")),
+            make_struct(2, Some("z")),
+            make_struct(3, None),
+        ],
+    )
+    .unwrap();
+    let list = Scalar::from(list);
+    let in_op = Pred::binary(BinaryPredicateOp::In, column_expr!("s"), list.clone());
+    let result = evaluate_predicate(&in_op, &batch, false).unwrap();
+    // nested nulls are equal
+    assert_eq!(result, BooleanArray::from(vec![true, false, true]));
+
+    // the same holds for literal values
+    let in_op = Pred::binary(
+        BinaryPredicateOp::In,
+        Expr::literal(make_struct(3, None)),
+        list,
+    );
+    let result = evaluate_predicate(&in_op, &batch, false).unwrap();
+    assert_eq!(result, BooleanArray::from(vec![true]));
+}
+
 #[test]
 fn test_literal_complex_type_array() {
     use crate::arrow::array::{Array as _, AsArray as _};
@@ -1108,3 +1192,256 @@ fn test_to_json_with_nested_struct() {
         r#"{"outer_int":200,"nested_struct":{"inner_string":"value"}}"#
     );
 }
+
+#[test]
+fn test_missing_column_tolerant_evaluator() {
+    let input_schema = Arc::new(StructType::new_unchecked([
+        StructField::nullable("a", KernelDataType::INTEGER),
+        StructField::nullable(
+            "b",
+            StructType::new_unchecked([
+                StructField::nullable("c", KernelDataType::INTEGER),
+                StructField::nullable("d", KernelDataType::STRING),
+            ]),
+        ),
+        StructField::nullable("e", KernelDataType::LONG),
+    ]));
+    // the batch predates the addition of `b.d` and `e`
+    let inner = StructArray::from(vec![(
+        Arc::new(Field::new("c", DataType::Int32, true)),
+        create_array!(Int32, [Some(10), None]) as ArrayRef,
+    )]);
+    let batch = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", inner.data_type().clone(), true),
+        ])),
+        vec![create_array!(Int32, [1, 2]), Arc::new(inner)],
+    )
+    .unwrap();
+    let batch = ArrowEngineData::new(batch);
+
+    let output_schema = Arc::new(StructType::new_unchecked([
+        StructField::nullable("a", KernelDataType::INTEGER),
+        StructField::nullable("d", KernelDataType::STRING),
+        StructField::nullable("e", KernelDataType::LONG),
+    ]));
+    let expression = Arc::new(Expr::struct_from([
+        column_expr!("a"),
+        column_expr!("b.d"),
+        column_expr!("e"),
+    ]));
+    let output_type = KernelDataType::Struct(Box::new(output_schema.as_ref().clone()));
+
+    let handler = ArrowEvaluationHandler;
+    let evaluator = handler.new_expression_evaluator(
+        input_schema.clone(),
+        expression.clone(),
+        output_type.clone(),
+    );
+    assert_result_error_with_message(evaluator.evaluate(&batch), "No such field: d");
+
+    let evaluator = handler
+        .new_missing_column_tolerant_evaluator(input_schema, expression, output_type)
+        .unwrap();
+    let result: RecordBatch = evaluator
+        .evaluate(&batch)
+        .unwrap()
+        .into_any()
+        .downcast::<ArrowEngineData>()
+        .unwrap()
+        .into();
+    let expected = RecordBatch::try_new(
+        Arc::new(output_schema.as_ref().try_into_arrow().unwrap()),
+        vec![
+            create_array!(Int32, [1, 2]),
+            create_array!(Utf8, [None::<String>, None]),
+            create_array!(Int64, [None::<i64>, None]),
+        ],
+    )
+    .unwrap();
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_evaluate_batches() {
+    let input_schema = Arc::new(StructType::new_unchecked([
+        StructField::nullable("a", KernelDataType::INTEGER),
+        StructField::nullable("b", KernelDataType::INTEGER),
+    ]));
+    let batch = |columns: Vec<(&str, ArrayRef)>| -> DeltaResult<Box<dyn EngineData>> {
+        Ok(Box::new(ArrowEngineData::new(
+            RecordBatch::try_from_iter(columns).unwrap(),
+        )))
+    };
+    let complete = || {
+        batch(vec![
+            ("a", create_array!(Int32, [1, 2])),
+            ("b", create_array!(Int32, [3, 4])),
+        ])
+    };
+    // a batch of a file that predates the addition of `b`
+    let incomplete = || batch(vec![("a", create_array!(Int32, [5]))]);
+    let batches = || -> FileDataReadResultIterator {
+        Box::new(
+            [
+                complete(),
+                incomplete(),
+                complete(),
+                Err(Error::generic("failed to read batch")),
+                incomplete(),
+            ]
+            .into_iter(),
+        )
+    };
+    let into_record_batch = |data: Box<dyn EngineData>| -> RecordBatch {
+        data.into_any()
+            .downcast::<ArrowEngineData>()
+            .unwrap()
+            .into()
+    };
+
+    let handler = ArrowEvaluationHandler;
+    // struct-typed and scalar outputs
+    let expressions = [
+        (
+            Arc::new(Expr::struct_from([column_expr!("b"), column_expr!("a")])),
+            KernelDataType::struct_type_unchecked([
+                StructField::nullable("b", KernelDataType::INTEGER),
+                StructField::nullable("a", KernelDataType::INTEGER),
+            ]),
+        ),
+        (Arc::new(column_expr!("b")), KernelDataType::INTEGER),
+    ];
+    for (expression, output_type) in expressions {
+        let evaluator = handler
+            .new_missing_column_tolerant_evaluator(input_schema.clone(), expression, output_type)
+            .unwrap();
+        let results: Vec<_> = evaluator.clone().evaluate_batches(batches()).collect();
+        assert_eq!(results.len(), 5);
+        for (result, input) in results.into_iter().zip(batches()) {
+            match input {
+                Ok(input) => {
+                    let expected = evaluator.evaluate(input.as_ref()).unwrap();
+                    assert_eq!(
+                        into_record_batch(result.unwrap()),
+                        into_record_batch(expected)
+                    );
+                }
+                Err(_) => assert_result_error_with_message(result, "failed to read batch"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_scalar_arrow_round_trip() {
+    let struct_fields = vec![
+        StructField::nullable("a", KernelDataType::INTEGER),
+        StructField::not_null("b", KernelDataType::STRING),
+    ];
+    let struct_values = vec![Scalar::Null(KernelDataType::INTEGER), Scalar::from("b")];
+    let array_type = ArrayType::new(KernelDataType::INTEGER, true);
+    let map_type = MapType::new(KernelDataType::STRING, KernelDataType::INTEGER, true);
+    let decimal_type = crate::schema::DecimalType::try_new(10, 2).unwrap();
+    let scalars = [
+        Scalar::Integer(1),
+        Scalar::Long(2),
+        Scalar::Short(3),
+        Scalar::Byte(4),
+        Scalar::Float(1.5),
+        Scalar::Double(2.5),
+        Scalar::from("a"),
+        Scalar::Boolean(true),
+        Scalar::Timestamp(1_000),
+        Scalar::TimestampNtz(2_000),
+        Scalar::Date(3),
+        Scalar::Binary(vec![1, 2]),
+        Scalar::Decimal(DecimalData::try_new(12345, decimal_type).unwrap()),
+        Scalar::Null(KernelDataType::LONG),
+        Scalar::Struct(StructData::try_new(struct_fields, struct_values).unwrap()),
+        Scalar::Array(ArrayData::try_new(array_type, [Some(1), None]).unwrap()),
+        Scalar::Map(
+            MapData::try_new(map_type, [("k", Scalar::Null(KernelDataType::INTEGER))]).unwrap(),
+        ),
+    ];
+    for scalar in scalars {
+        let arrow_scalar = scalar.to_arrow_scalar().unwrap();
+        assert_eq!(
+            Scalar::try_from_arrow_scalar(&arrow_scalar).unwrap(),
+            scalar
+        );
+    }
+}
+
+#[test]
+fn test_scalar_from_arrow_array() {
+    use crate::arrow::array::{
+        Date64Array, LargeStringArray, TimestampNanosecondArray, TimestampSecondArray, UInt8Array,
+    };
+
+    // timestamps with any timezone are stored adjusted to UTC
+    let timestamps = TimestampSecondArray::from(vec![1]).with_timezone("America/New_York");
+    let scalar = Scalar::try_from_array(&timestamps, 0).unwrap();
+    assert_eq!(scalar, Scalar::Timestamp(1_000_000));
+
+    // nanoseconds are only converted if that doesn't lose precision
+    let timestamps = TimestampNanosecondArray::from(vec![1_000, 1_001]);
+    let scalar = Scalar::try_from_array(&timestamps, 0).unwrap();
+    assert_eq!(scalar, Scalar::TimestampNtz(1));
+    assert!(Scalar::try_from_array(&timestamps, 1).is_err());
+
+    let dates = Date64Array::from(vec![2 * 24 * 60 * 60 * 1000]);
+    assert_eq!(Scalar::try_from_array(&dates, 0).unwrap(), Scalar::Date(2));
+
+    let strings = LargeStringArray::from(vec![Some("a"), None]);
+    assert_eq!(
+        Scalar::try_from_array(&strings, 0).unwrap(),
+        Scalar::from("a")
+    );
+    let scalar = Scalar::try_from_array(&strings, 1).unwrap();
+    assert_eq!(scalar, Scalar::Null(KernelDataType::STRING));
+    assert!(Scalar::try_from_array(&strings, 2).is_err());
+
+    let unsigned = UInt8Array::from(vec![1]);
+    assert_result_error_with_message(
+        Scalar::try_from_array(&unsigned, 0),
+        "cannot be converted to a scalar",
+    );
+}
+
+#[test]
+fn test_clustering_keys() {
+    use crate::arrow::array::AsArray as _;
+    use crate::clustering::{clustering_key, clustering_key_expression, ClusteringCurve};
+
+    let schema = Schema::new([
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            create_array!(Int32, [Some(3), None, Some(-7)]),
+            create_array!(Utf8, [Some("x"), Some("y"), None]),
+        ],
+    )
+    .unwrap();
+    for curve in [ClusteringCurve::ZOrder, ClusteringCurve::Hilbert] {
+        let expr =
+            clustering_key_expression(curve, [column_name!("a"), column_name!("b")]).unwrap();
+        let keys = evaluate_expression(&expr, &batch, Some(&KernelDataType::BINARY)).unwrap();
+        let keys = keys.as_binary::<i32>();
+        let rows = [
+            [Scalar::Integer(3), Scalar::from("x")],
+            [Scalar::Null(KernelDataType::INTEGER), Scalar::from("y")],
+            [Scalar::Integer(-7), Scalar::Null(KernelDataType::STRING)],
+        ];
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(keys.value(i), clustering_key(curve, row).unwrap());
+        }
+
+        let result = evaluate_expression(&expr, &batch, Some(&KernelDataType::STRING));
+        assert_result_error_with_message(result, "Clustering keys are BINARY");
+    }
+}
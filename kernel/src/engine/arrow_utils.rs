@@ -10,14 +10,17 @@ use crate::engine::ensure_data_types::DataTypeCompat;
 use crate::schema::{ColumnMetadataKey, MetadataValue};
 use crate::{
     engine::arrow_data::ArrowEngineData,
-    schema::{DataType, MetadataColumnSpec, Schema, SchemaRef, StructField, StructType},
+    expressions::{ArrayData, DecimalData, MapData, Scalar, StructData},
+    schema::{
+        DataType, MetadataColumnSpec, PrimitiveType, Schema, SchemaRef, StructField, StructType,
+    },
     utils::require,
     DeltaResult, EngineData, Error,
 };
 
 use crate::arrow::array::{
-    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray, MapArray,
-    OffsetSizeTrait, PrimitiveArray, RecordBatch, StringArray, StructArray,
+    cast::AsArray, make_array, new_null_array, Array as ArrowArray, ArrayRef, GenericListArray,
+    MapArray, OffsetSizeTrait, PrimitiveArray, RecordBatch, StringArray, StructArray,
 };
 use crate::arrow::buffer::NullBuffer;
 use crate::arrow::compute::concat_batches;
@@ -31,7 +34,7 @@ use crate::parquet::file::metadata::RowGroupMetaData;
 use crate::parquet::{arrow::ProjectionMask, schema::types::SchemaDescriptor};
 use delta_kernel_derive::internal_api;
 use itertools::Itertools;
-use tracing::debug;
+use tracing::{debug, warn};
 
 macro_rules! prim_array_cmp {
     ( $left_arr: ident, $right_arr: ident, $(($data_ty: pat, $prim_ty: ty)),+ ) => {
@@ -278,7 +281,8 @@ pub(crate) struct ReorderIndex {
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum ReorderIndexTransform {
-    /// For a non-nested type, indicates that we need to cast to the contained type
+    /// For a non-nested type, or a list of them, indicates that we need to cast to the contained
+    /// type
     Cast(ArrowDataType),
     /// Used for struct/list/map. Potentially transform child fields using contained reordering
     Nested(Vec<ReorderIndex>),
@@ -286,6 +290,8 @@ pub(crate) enum ReorderIndexTransform {
     Identity,
     /// Data is missing, fill in with a null column
     Missing(ArrowFieldRef),
+    /// Data is missing, fill in with a column of the field's default value
+    Default(ArrowFieldRef, Scalar),
     /// Row index column requested, compute it
     RowIndex(ArrowFieldRef),
 }
@@ -311,6 +317,10 @@ impl ReorderIndex {
         ReorderIndex::new(index, ReorderIndexTransform::Missing(field))
     }
 
+    fn default_value(index: usize, field: ArrowFieldRef, default: Scalar) -> Self {
+        ReorderIndex::new(index, ReorderIndexTransform::Default(field, default))
+    }
+
     fn row_index(index: usize, field: ArrowFieldRef) -> Self {
         ReorderIndex::new(index, ReorderIndexTransform::RowIndex(field))
     }
@@ -319,9 +329,11 @@ impl ReorderIndex {
     /// [`ordering_needs_transform`] to understand why this is needed.
     fn needs_transform(&self) -> bool {
         match self.transform {
-            // if we're casting, inserting null, or generating row index, we need to transform
+            // if we're casting, inserting null or defaults, or generating row index, we need to
+            // transform
             ReorderIndexTransform::Cast(_)
             | ReorderIndexTransform::Missing(_)
+            | ReorderIndexTransform::Default(..)
             | ReorderIndexTransform::RowIndex(_) => true,
             // if our nested ordering needs a transform, we need a transform
             ReorderIndexTransform::Nested(ref children) => ordering_needs_transform(children),
@@ -378,6 +390,30 @@ fn validate_parquet_variant(field: &ArrowField) -> DeltaResult<()> {
     }
 }
 
+/// The key of the arrow field metadata that flags a column as missing from the parquet file it was
+/// read from, see [`MissingColumnPolicy::FillNull`].
+pub const MISSING_COLUMN_METADATA_KEY: &str = "delta.kernel.missingColumn";
+
+/// How parquet reads fill a requested column that is not nullable but missing from the file, e.g.
+/// because a buggy writer omitted it. Missing nullable columns are always read as nulls, and
+/// missing columns with a default value (see [`StructField::current_default_value`]) as their
+/// default, whatever the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingColumnPolicy {
+    /// Fail the read of the file.
+    #[default]
+    Error,
+    /// Read the column as nulls. The column is flagged in the read data: its arrow field is
+    /// nullable and has the [`MISSING_COLUMN_METADATA_KEY`] metadata key, so that engines can find
+    /// (and e.g. reject or repair) the null values.
+    FillNull,
+    /// Read the zero value of the column's type: zero, false, an empty string or binary, the Unix
+    /// epoch for dates and timestamps, an empty array or map, or a struct of the zero values of its
+    /// non-nullable fields (and nulls for its nullable ones). Fails for variant columns, which have
+    /// no zero value.
+    UseDefault,
+}
+
 /// helper function, does the same as `get_requested_indices` but at an offset. used to recurse into
 /// structs, lists, and maps. `parquet_offset` is how many parquet fields exist before processing
 /// this potentially nested schema. returns the number of parquet fields in `fields` (regardless of
@@ -387,6 +423,7 @@ fn get_indices(
     requested_schema: &Schema,
     fields: &ArrowFields,
     mask_indices: &mut Vec<usize>,
+    policy: MissingColumnPolicy,
 ) -> DeltaResult<(usize, Vec<ReorderIndex>)> {
     let mut found_fields = HashSet::with_capacity(requested_schema.num_fields());
     let mut reorder_indices = Vec::with_capacity(requested_schema.num_fields());
@@ -426,6 +463,7 @@ fn get_indices(
                             requested_schema.as_ref(),
                             fields,
                             mask_indices,
+                            policy,
                         )?;
                         // advance the number of parquet fields, but subtract 1 because the
                         // struct will be counted by the `enumerate` call but doesn't count as
@@ -455,6 +493,7 @@ fn get_indices(
                             &requested_schema,
                             &[list_field.clone()].into(),
                             mask_indices,
+                            policy,
                         )?;
                         // see comment above in struct match arm
                         parquet_offset += parquet_advance - 1;
@@ -469,6 +508,23 @@ fn get_indices(
                         // the index is wrong, as it's the index from the inner schema. Adjust
                         // it to be our index
                         children.index = index;
+                        // a cast of the elements (e.g. to rescale decimals that were written with
+                        // a narrower type before the column was widened) is a cast of the list
+                        if let ReorderIndexTransform::Cast(target) = &children.transform {
+                            let element_field = Arc::new(
+                                list_field.as_ref().clone().with_data_type(target.clone()),
+                            );
+                            let target = match field.data_type() {
+                                ArrowDataType::LargeList(_) => {
+                                    ArrowDataType::LargeList(element_field)
+                                }
+                                ArrowDataType::ListView(_) => {
+                                    ArrowDataType::ListView(element_field)
+                                }
+                                _ => ArrowDataType::List(element_field),
+                            };
+                            children = ReorderIndex::cast(index, target);
+                        }
                         reorder_indices.push(children);
                     } else {
                         return Err(Error::unexpected_column_type(list_field.name()));
@@ -494,6 +550,7 @@ fn get_indices(
                                 &inner_schema,
                                 inner_fields,
                                 mask_indices,
+                                policy,
                             )?;
 
                             // advance the number of parquet fields, but subtract 1 because the
@@ -534,10 +591,11 @@ fn get_indices(
                     // as the final argument. These can differ between the delta schema and the
                     // parquet schema without causing issues in reading the data. We fix them up in
                     // expression evaluation later.
-                    match super::ensure_data_types::ensure_data_types(
+                    // parquet files of legacy writers may also need casts, e.g. from unannotated
+                    // binary to string.
+                    match super::ensure_data_types::ensure_parquet_data_types(
                         &requested_field.data_type,
                         field.data_type(),
-                        false,
                     )? {
                         DataTypeCompat::Identical => {
                             reorder_indices.push(ReorderIndex::identity(index))
@@ -569,32 +627,80 @@ fn get_indices(
         // some fields are missing, but they might be nullable or metadata columns, need to insert them into the reorder_indices
         for (requested_position, field) in requested_schema.fields().enumerate() {
             if !found_fields.contains(field.name()) {
-                match field.get_metadata_column_spec() {
-                    Some(MetadataColumnSpec::RowIndex) => {
+                match (
+                    field.get_metadata_column_spec(),
+                    missing_field_default(field),
+                ) {
+                    (Some(MetadataColumnSpec::RowIndex), _) => {
                         debug!("Inserting a row index column: {}", field.name());
                         reorder_indices.push(ReorderIndex::row_index(
                             requested_position,
                             Arc::new(field.try_into_arrow()?),
                         ));
                     }
-                    Some(metadata_spec) => {
+                    (Some(metadata_spec), _) => {
                         return Err(Error::Generic(format!(
                             "Metadata column {metadata_spec:?} is not supported by the default parquet reader"
                         )));
                     }
-                    None if field.nullable => {
+                    (None, Some(default)) => {
+                        debug!(
+                            "Inserting the default value of missing field: {}",
+                            field.name()
+                        );
+                        reorder_indices.push(ReorderIndex::default_value(
+                            requested_position,
+                            Arc::new(field.try_into_arrow()?),
+                            default,
+                        ));
+                    }
+                    (None, None) if field.nullable => {
                         debug!("Inserting missing and nullable field: {}", field.name());
                         reorder_indices.push(ReorderIndex::missing(
                             requested_position,
                             Arc::new(field.try_into_arrow()?),
                         ));
                     }
-                    None => {
-                        return Err(Error::Generic(format!(
-                            "Requested field not found in parquet schema, and field is not nullable: {}",
-                            field.name()
-                        )));
-                    }
+                    (None, None) => match policy {
+                        MissingColumnPolicy::Error => {
+                            return Err(Error::Generic(format!(
+                                "Requested field not found in parquet schema, and field is not nullable: {}",
+                                field.name()
+                            )));
+                        }
+                        MissingColumnPolicy::FillNull => {
+                            warn!(
+                                "Reading missing non-nullable field as null: {}",
+                                field.name()
+                            );
+                            let field = ArrowField::try_from_kernel(field)?;
+                            let mut metadata = field.metadata().clone();
+                            metadata.insert(
+                                MISSING_COLUMN_METADATA_KEY.to_string(),
+                                "true".to_string(),
+                            );
+                            let field = field.with_nullable(true).with_metadata(metadata);
+                            reorder_indices
+                                .push(ReorderIndex::missing(requested_position, Arc::new(field)));
+                        }
+                        MissingColumnPolicy::UseDefault => {
+                            let zero = zero_value(field.data_type()).ok_or_else(|| {
+                                Error::Generic(format!(
+                                    "Requested field not found in parquet schema, and field is not nullable and has no zero value: {}",
+                                    field.name()
+                                ))
+                            })?;
+                            warn!(
+                                "Reading missing non-nullable field as {zero}: {}",
+                                field.name()
+                            );
+                            reorder_indices.push(ReorderIndex::default_value(
+                                requested_position,
+                                Arc::new(field.try_into_arrow()?),
+                                zero,
+                            ));
+                        }
+                    },
                 }
             }
         }
@@ -605,6 +711,61 @@ fn get_indices(
     ))
 }
 
+/// The value to read for `field` from a file that doesn't contain it: the field's default if it
+/// has one, since the field must have been added with that default after the file was written.
+/// Defaults that aren't supported literals are read as null, like fields without defaults.
+fn missing_field_default(field: &StructField) -> Option<Scalar> {
+    field.current_default_value().unwrap_or_else(|e| {
+        warn!("Reading missing field {} as null: {e}", field.name());
+        None
+    })
+}
+
+/// The value [`MissingColumnPolicy::UseDefault`] reads for a missing column of type `data_type`, or
+/// `None` if the type has no zero value.
+fn zero_value(data_type: &DataType) -> Option<Scalar> {
+    let value = match data_type {
+        DataType::Primitive(primitive) => match primitive {
+            PrimitiveType::String => Scalar::String(String::new()),
+            PrimitiveType::Long => Scalar::Long(0),
+            PrimitiveType::Integer => Scalar::Integer(0),
+            PrimitiveType::Short => Scalar::Short(0),
+            PrimitiveType::Byte => Scalar::Byte(0),
+            PrimitiveType::Float => Scalar::Float(0.0),
+            PrimitiveType::Double => Scalar::Double(0.0),
+            PrimitiveType::Boolean => Scalar::Boolean(false),
+            PrimitiveType::Binary => Scalar::Binary(vec![]),
+            PrimitiveType::Date => Scalar::Date(0),
+            PrimitiveType::Timestamp => Scalar::Timestamp(0),
+            PrimitiveType::TimestampNtz => Scalar::TimestampNtz(0),
+            PrimitiveType::Decimal(decimal_type) => {
+                Scalar::Decimal(DecimalData::try_new(0, *decimal_type).ok()?)
+            }
+        },
+        DataType::Array(array_type) => {
+            let elements: [Scalar; 0] = [];
+            Scalar::Array(ArrayData::try_new(array_type.as_ref().clone(), elements).ok()?)
+        }
+        DataType::Map(map_type) => {
+            let pairs: [(Scalar, Scalar); 0] = [];
+            Scalar::Map(MapData::try_new(map_type.as_ref().clone(), pairs).ok()?)
+        }
+        DataType::Struct(struct_type) => {
+            let values = struct_type
+                .fields()
+                .map(|field| match field.nullable {
+                    true => Some(Scalar::Null(field.data_type().clone())),
+                    false => zero_value(field.data_type()),
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let fields = struct_type.fields().cloned().collect();
+            Scalar::Struct(StructData::try_new(fields, values).ok()?)
+        }
+        DataType::Variant(_) => return None,
+    };
+    Some(value)
+}
+
 /// Constructs an iterator where each parquet Field in `fields` is matched
 /// with a a kernel `KernelFieldInfo` representing a StructField.
 ///
@@ -681,6 +842,20 @@ fn match_parquet_fields<'k, 'p>(
 pub(crate) fn get_requested_indices(
     requested_schema: &SchemaRef,
     parquet_schema: &ArrowSchemaRef,
+) -> DeltaResult<(Vec<usize>, Vec<ReorderIndex>)> {
+    get_requested_indices_with_policy(
+        requested_schema,
+        parquet_schema,
+        MissingColumnPolicy::default(),
+    )
+}
+
+/// Like [`get_requested_indices`], but fills requested columns that are not nullable and missing
+/// from `parquet_schema` according to `policy`.
+pub(crate) fn get_requested_indices_with_policy(
+    requested_schema: &SchemaRef,
+    parquet_schema: &ArrowSchemaRef,
+    policy: MissingColumnPolicy,
 ) -> DeltaResult<(Vec<usize>, Vec<ReorderIndex>)> {
     let mut mask_indices = vec![];
     let (_, reorder_indexes) = get_indices(
@@ -688,6 +863,7 @@ pub(crate) fn get_requested_indices(
         requested_schema,
         parquet_schema.fields(),
         &mut mask_indices,
+        policy,
     )?;
     Ok((mask_indices, reorder_indexes))
 }
@@ -831,6 +1007,14 @@ pub(crate) fn reorder_struct_array(
                     let field = field.clone(); // cheap Arc clone
                     final_fields_cols[reorder_index.index] = Some((field, null_array));
                 }
+                ReorderIndexTransform::Default(field, default) => {
+                    let default_array = default.to_array(num_rows)?;
+                    let default_array = match default_array.data_type() == field.data_type() {
+                        true => default_array,
+                        false => crate::arrow::compute::cast(&default_array, field.data_type())?,
+                    };
+                    final_fields_cols[reorder_index.index] = Some((field.clone(), default_array));
+                }
                 ReorderIndexTransform::RowIndex(field) => {
                     let Some(ref mut row_index_iter) = row_indexes else {
                         return Err(Error::generic(
@@ -1011,7 +1195,10 @@ pub(crate) fn parse_json(
 // NOTE: This code is really inefficient because arrow lacks the native capability to perform robust
 // StringArray -> StructArray JSON parsing. See https://github.com/apache/arrow-rs/issues/6522. If
 // that shortcoming gets fixed upstream, this method can simplify or hopefully even disappear.
-fn parse_json_impl(json_strings: &StringArray, schema: ArrowSchemaRef) -> DeltaResult<RecordBatch> {
+pub(crate) fn parse_json_impl(
+    json_strings: &StringArray,
+    schema: ArrowSchemaRef,
+) -> DeltaResult<RecordBatch> {
     if json_strings.is_empty() {
         return Ok(RecordBatch::new_empty(schema));
     }
@@ -1056,6 +1243,94 @@ fn parse_json_impl(json_strings: &StringArray, schema: ArrowSchemaRef) -> DeltaR
     Ok(concat_batches(&schema, output.iter())?)
 }
 
+/// Returns an error if any value of a `CHAR`/`VARCHAR` column in `batch` exceeds the column's
+/// length limit. Columns are matched to `schema` by name, recursing into nested structs, arrays and
+/// maps. The limit of a column applies to all of its strings, including array elements and map
+/// keys and values.
+pub(crate) fn ensure_char_varchar_lengths(
+    batch: &RecordBatch,
+    schema: &StructType,
+) -> DeltaResult<()> {
+    ensure_struct_char_varchar_lengths(batch.columns(), batch.schema_ref().fields(), schema)
+}
+
+fn ensure_struct_char_varchar_lengths(
+    columns: &[ArrayRef],
+    arrow_fields: &ArrowFields,
+    schema: &StructType,
+) -> DeltaResult<()> {
+    for (column, arrow_field) in columns.iter().zip(arrow_fields) {
+        if let Some(field) = schema.field(arrow_field.name()) {
+            ensure_column_char_varchar_lengths(field, field.data_type(), column)?;
+        }
+    }
+    Ok(())
+}
+
+// Check the strings of `column`, which holds values of type `data_type` stored in `field` (either
+// directly, or as array elements or map keys/values)
+fn ensure_column_char_varchar_lengths(
+    field: &StructField,
+    data_type: &DataType,
+    column: &ArrayRef,
+) -> DeltaResult<()> {
+    // Only the values referenced by the offsets of a (possibly sliced) list or map are checked
+    fn referenced<O: OffsetSizeTrait>(values: &ArrayRef, offsets: &[O]) -> ArrayRef {
+        let start = offsets.first().map_or(0, |offset| offset.as_usize());
+        let end = offsets.last().map_or(0, |offset| offset.as_usize());
+        values.slice(start, end - start)
+    }
+    match data_type {
+        DataType::Struct(inner) => {
+            if let Some(array) = column.as_struct_opt() {
+                ensure_struct_char_varchar_lengths(array.columns(), array.fields(), inner)?;
+            }
+        }
+        DataType::Array(array_type) => {
+            let elements = match column.data_type() {
+                ArrowDataType::List(_) => column
+                    .as_list_opt::<i32>()
+                    .map(|list| referenced(list.values(), list.value_offsets())),
+                ArrowDataType::LargeList(_) => column
+                    .as_list_opt::<i64>()
+                    .map(|list| referenced(list.values(), list.value_offsets())),
+                _ => None,
+            };
+            if let Some(elements) = elements {
+                ensure_column_char_varchar_lengths(field, &array_type.element_type, &elements)?;
+            }
+        }
+        DataType::Map(map_type) => {
+            if let Some(map) = column.as_map_opt() {
+                let keys = referenced(map.keys(), map.value_offsets());
+                let values = referenced(map.values(), map.value_offsets());
+                ensure_column_char_varchar_lengths(field, &map_type.key_type, &keys)?;
+                ensure_column_char_varchar_lengths(field, &map_type.value_type, &values)?;
+            }
+        }
+        _ => {
+            let Some(char_varchar_type) = field.char_varchar_type() else {
+                return Ok(());
+            };
+            let too_long = |value: Option<&str>| value.is_some_and(|v| !char_varchar_type.fits(v));
+            let exceeded = match column.data_type() {
+                ArrowDataType::Utf8 => column.as_string::<i32>().iter().any(too_long),
+                ArrowDataType::LargeUtf8 => column.as_string::<i64>().iter().any(too_long),
+                ArrowDataType::Utf8View => column.as_string_view().iter().any(too_long),
+                _ => false,
+            };
+            require!(
+                !exceeded,
+                Error::generic(format!(
+                    "Column '{}' has a value exceeding the length limit of its type {char_varchar_type}",
+                    field.name()
+                ))
+            );
+        }
+    }
+    Ok(())
+}
+
 /// serialize an arrow RecordBatch to a JSON string by appending to a buffer.
 // TODO (zach): this should stream data to the JSON writer and output an iterator.
 #[internal_api]
@@ -1077,8 +1352,9 @@ mod tests {
     use std::sync::Arc;
 
     use crate::arrow::array::{
-        Array, ArrayRef as ArrowArrayRef, BooleanArray, GenericListArray, Int32Array, Int32Builder,
-        MapArray, MapBuilder, StructArray, StructBuilder,
+        Array, ArrayRef as ArrowArrayRef, BinaryArray, BooleanArray, Decimal128Array,
+        GenericListArray, Int32Array, Int32Builder, MapArray, MapBuilder, StructArray,
+        StructBuilder, UInt32Array,
     };
     use crate::arrow::datatypes::{
         DataType as ArrowDataType, Field as ArrowField, Fields as ArrowFields,
@@ -1089,12 +1365,17 @@ mod tests {
         buffer::{OffsetBuffer, ScalarBuffer},
     };
 
+    use crate::actions::get_log_schema;
+    use crate::arrow::datatypes::{Int32Type, Int64Type};
     use crate::schema::{
         ArrayType, ColumnMetadataKey, DataType, MapType, MetadataValue, StructField, StructType,
     };
     use crate::table_features::ColumnMappingMode;
     use crate::utils::test_utils::assert_result_error_with_message;
 
+    use proptest::prelude::*;
+    use serde_json::json;
+
     use super::*;
 
     fn column_mapping_cases() -> [ColumnMappingMode; 3] {
@@ -1224,6 +1505,69 @@ mod tests {
         assert_eq!(long_col.value(0), long_string);
     }
 
+    fn log_arrow_schema() -> ArrowSchemaRef {
+        Arc::new(ArrowSchema::try_from_kernel(get_log_schema().as_ref()).unwrap())
+    }
+
+    fn stats_arrow_schema() -> ArrowSchemaRef {
+        Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("numRecords", ArrowDataType::Int64, true),
+            ArrowField::new(
+                "minValues",
+                ArrowDataType::Struct(
+                    vec![ArrowField::new("id", ArrowDataType::Int64, true)].into(),
+                ),
+                true,
+            ),
+        ]))
+    }
+
+    proptest! {
+        // Corrupted commit lines and stats strings must fail to parse instead of panicking
+        #[test]
+        fn test_parse_json_never_panics(json in "\\PC*") {
+            for schema in [log_arrow_schema(), stats_arrow_schema()] {
+                let input: Vec<Option<&str>> = vec![Some(&json)];
+                let _ = parse_json_impl(&input.into(), schema);
+            }
+        }
+
+        #[test]
+        fn test_parse_truncated_add_action(
+            path in "\\PC{1,32}",
+            num_records in any::<i64>(),
+            cut in any::<prop::sample::Index>(),
+        ) {
+            let stats = json!({"numRecords": num_records, "minValues": {"id": 0}}).to_string();
+            let add = json!({"add": {
+                "path": path,
+                "partitionValues": {},
+                "size": 1,
+                "modificationTime": 0,
+                "dataChange": true,
+                "stats": stats,
+            }})
+            .to_string();
+
+            let input: Vec<Option<&str>> = vec![Some(&add)];
+            let batch = parse_json_impl(&input.into(), log_arrow_schema()).unwrap();
+            let add_action = batch.column_by_name("add").unwrap().as_struct();
+            let parsed_path = add_action.column_by_name("path").unwrap().as_string::<i32>();
+            prop_assert_eq!(parsed_path.value(0), path.as_str());
+            let parsed_stats = add_action.column_by_name("stats").unwrap().as_string::<i32>();
+            let input: Vec<Option<&str>> = vec![Some(parsed_stats.value(0))];
+            let stats = parse_json_impl(&input.into(), stats_arrow_schema()).unwrap();
+            let parsed_num_records = stats.column(0).as_primitive::<Int64Type>().value(0);
+            prop_assert_eq!(parsed_num_records, num_records);
+
+            // every proper prefix of the line, e.g. of a commit file cut short by a crashed
+            // writer, is malformed
+            let cut = add.char_indices().nth(cut.index(add.chars().count())).unwrap().0;
+            let input: Vec<Option<&str>> = vec![Some(&add[..cut])];
+            prop_assert!(parse_json_impl(&input.into(), log_arrow_schema()).is_err());
+        }
+    }
+
     #[test]
     fn simple_mask_indices() {
         column_mapping_cases().into_iter().for_each(|mode| {
@@ -1508,6 +1852,106 @@ mod tests {
         })
     }
 
+    #[test]
+    fn legacy_parquet_types_reorder() {
+        let requested_schema = Arc::new(StructType::new_unchecked([
+            StructField::nullable("name", DataType::STRING),
+            StructField::nullable("count", DataType::LONG),
+        ]));
+        // a legacy writer stored `name` as unannotated binary and `count` as UINT_32
+        let parquet_fields = vec![
+            ArrowField::new("name", ArrowDataType::Binary, true),
+            ArrowField::new("count", ArrowDataType::UInt32, true),
+        ];
+        let parquet_schema = Arc::new(ArrowSchema::new(parquet_fields.clone()));
+        let (mask_indices, reorder_indices) =
+            get_requested_indices(&requested_schema, &parquet_schema).unwrap();
+        assert_eq!(mask_indices, vec![0, 1]);
+        assert_eq!(
+            reorder_indices,
+            vec![
+                ReorderIndex::cast(0, ArrowDataType::Utf8),
+                ReorderIndex::cast(1, ArrowDataType::Int64),
+            ]
+        );
+
+        let input = StructArray::new(
+            parquet_fields.into(),
+            vec![
+                Arc::new(BinaryArray::from(vec![Some(b"a".as_ref()), None])),
+                Arc::new(UInt32Array::from(vec![Some(u32::MAX), Some(0)])),
+            ],
+            None,
+        );
+        let ordered = reorder_struct_array(input, &reorder_indices, None).unwrap();
+        let names = ordered.column(0).as_string::<i32>();
+        assert_eq!(names.value(0), "a");
+        assert!(names.is_null(1));
+        let counts = ordered.column(1).as_primitive::<Int64Type>();
+        assert_eq!(counts.values(), &[u32::MAX as i64, 0]);
+    }
+
+    #[test]
+    fn rescale_decimals_reorder() {
+        // the columns were widened from decimal(5, 2) to decimal(10, 4) after the file was written
+        let requested_schema = Arc::new(StructType::new_unchecked([
+            StructField::nullable("d", DataType::decimal(10, 4).unwrap()),
+            StructField::nullable(
+                "list",
+                ArrayType::new(DataType::decimal(10, 4).unwrap(), true),
+            ),
+        ]));
+        let element_field = Arc::new(ArrowField::new(
+            "element",
+            ArrowDataType::Decimal128(5, 2),
+            true,
+        ));
+        let parquet_fields = vec![
+            ArrowField::new("d", ArrowDataType::Decimal128(5, 2), true),
+            ArrowField::new("list", ArrowDataType::List(element_field.clone()), true),
+        ];
+        let parquet_schema = Arc::new(ArrowSchema::new(parquet_fields.clone()));
+        let (mask_indices, reorder_indices) =
+            get_requested_indices(&requested_schema, &parquet_schema).unwrap();
+        assert_eq!(mask_indices, vec![0, 1]);
+        let target_element_field = Arc::new(ArrowField::new(
+            "element",
+            ArrowDataType::Decimal128(10, 4),
+            true,
+        ));
+        assert_eq!(
+            reorder_indices,
+            vec![
+                ReorderIndex::cast(0, ArrowDataType::Decimal128(10, 4)),
+                ReorderIndex::cast(1, ArrowDataType::List(target_element_field)),
+            ]
+        );
+
+        let decimals = || {
+            Decimal128Array::from(vec![Some(12345), None])
+                .with_precision_and_scale(5, 2)
+                .unwrap()
+        };
+        let list = GenericListArray::<i32>::new(
+            element_field,
+            OffsetBuffer::new(ScalarBuffer::from(vec![0, 1, 2])),
+            Arc::new(decimals()),
+            None,
+        );
+        let input = StructArray::new(
+            parquet_fields.into(),
+            vec![Arc::new(decimals()), Arc::new(list)],
+            None,
+        );
+        let ordered = reorder_struct_array(input, &reorder_indices, None).unwrap();
+        let expected = Decimal128Array::from(vec![Some(1234500), None])
+            .with_precision_and_scale(10, 4)
+            .unwrap();
+        assert_eq!(ordered.column(0).as_ref(), &expected as &dyn Array);
+        let list = ordered.column(1).as_list::<i32>();
+        assert_eq!(list.values().as_ref(), &expected as &dyn Array);
+    }
+
     #[test]
     fn simple_nullable_field_missing() {
         column_mapping_cases().into_iter().for_each(|mode| {
@@ -1550,6 +1994,114 @@ mod tests {
         });
     }
 
+    #[test]
+    fn missing_field_with_default() {
+        let with_default = |default: &str| {
+            HashMap::from([(
+                ColumnMetadataKey::CurrentDefault.as_ref().to_string(),
+                MetadataValue::String(default.to_string()),
+            )])
+        };
+        let requested_schema: SchemaRef = StructType::new_unchecked([
+            StructField::not_null("a", DataType::INTEGER),
+            StructField::not_null("b", DataType::INTEGER).with_metadata(with_default("42")),
+            StructField::nullable("c", DataType::STRING)
+                .with_metadata(with_default("current_user()")),
+        ])
+        .into();
+        let parquet_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "a",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let (mask_indices, reorder_indices) =
+            get_requested_indices(&requested_schema, &parquet_schema).unwrap();
+        let arrow_field = |name: &str, data_type, nullable| {
+            let metadata = requested_schema
+                .field(name)
+                .unwrap()
+                .metadata_with_string_values();
+            Arc::new(ArrowField::new(name, data_type, nullable).with_metadata(metadata))
+        };
+        let expect_reorder = vec![
+            ReorderIndex::identity(0),
+            ReorderIndex::default_value(
+                1,
+                arrow_field("b", ArrowDataType::Int32, false),
+                Scalar::Integer(42),
+            ),
+            // unsupported defaults read as null
+            ReorderIndex::missing(2, arrow_field("c", ArrowDataType::Utf8, true)),
+        ];
+        assert_eq!(mask_indices, vec![0]);
+        assert_eq!(reorder_indices, expect_reorder);
+
+        let data = StructArray::from(vec![(
+            Arc::new(ArrowField::new("a", ArrowDataType::Int32, false)),
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrowArrayRef,
+        )]);
+        let ordered = reorder_struct_array(data, &reorder_indices, None).unwrap();
+        assert_eq!(ordered.column_names(), vec!["a", "b", "c"]);
+        let b = ordered.column(1).as_primitive::<Int32Type>();
+        assert_eq!(b.values(), &[42, 42, 42]);
+        assert_eq!(ordered.column(2).null_count(), 3);
+    }
+
+    #[test]
+    fn missing_non_nullable_field_policies() {
+        let requested_schema: SchemaRef = StructType::new_unchecked([
+            StructField::not_null("a", DataType::INTEGER),
+            StructField::not_null("b", DataType::INTEGER),
+            StructField::not_null(
+                "s",
+                StructType::new_unchecked([
+                    StructField::not_null("x", DataType::STRING),
+                    StructField::nullable("y", DataType::LONG),
+                ]),
+            ),
+        ])
+        .into();
+        let parquet_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "a",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let data = || {
+            StructArray::from(vec![(
+                Arc::new(ArrowField::new("a", ArrowDataType::Int32, false)),
+                Arc::new(Int32Array::from(vec![1, 2])) as ArrowArrayRef,
+            )])
+        };
+        let read = |policy| -> DeltaResult<StructArray> {
+            let (_, reorder_indices) =
+                get_requested_indices_with_policy(&requested_schema, &parquet_schema, policy)?;
+            reorder_struct_array(data(), &reorder_indices, None)
+        };
+
+        let result = read(MissingColumnPolicy::Error);
+        assert_result_error_with_message(result, "field is not nullable: b");
+
+        let ordered = read(MissingColumnPolicy::FillNull).unwrap();
+        for (field, column) in ordered.fields().iter().zip(ordered.columns()).skip(1) {
+            assert!(field.is_nullable());
+            assert_eq!(
+                field.metadata().get(MISSING_COLUMN_METADATA_KEY),
+                Some(&"true".to_string())
+            );
+            assert_eq!(column.null_count(), 2);
+        }
+
+        let ordered = read(MissingColumnPolicy::UseDefault).unwrap();
+        assert_eq!(ordered.column_names(), vec!["a", "b", "s"]);
+        let b = ordered.column(1).as_primitive::<Int32Type>();
+        assert_eq!(b.values(), &[0, 0]);
+        let s = ordered.column(2).as_struct();
+        assert_eq!(s.null_count(), 0);
+        let x = s.column(0).as_string::<i32>();
+        assert_eq!(x.iter().collect_vec(), vec![Some(""), Some("")]);
+        assert_eq!(s.column(1).null_count(), 2);
+    }
+
     #[test]
     fn get_requested_indices_by_id_only() {
         let requested_schema = StructType::new_unchecked([
@@ -2727,4 +3279,91 @@ mod tests {
         let non_null_leaf_nullable_2 = inner_non_null_2.column(1);
         assert_eq!(non_null_leaf_nullable_2, non_null_leaf_nullable_1);
     }
+
+    #[test]
+    fn test_ensure_char_varchar_lengths() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("c", DataType::STRING)
+                .with_char_varchar_type(crate::schema::CharVarcharType::Char(2)),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([StructField::nullable("v", DataType::STRING)
+                    .with_char_varchar_type(crate::schema::CharVarcharType::Varchar(3))]),
+            ),
+        ]);
+        let make_batch = |c: &str, v: &str| {
+            let inner = StructArray::from(vec![(
+                Arc::new(ArrowField::new("v", ArrowDataType::Utf8, true)),
+                Arc::new(StringArray::from(vec![Some(v), None])) as ArrowArrayRef,
+            )]);
+            RecordBatch::try_from_iter(vec![
+                (
+                    "c",
+                    Arc::new(StringArray::from(vec![Some(c), None])) as ArrowArrayRef,
+                ),
+                ("s", Arc::new(inner) as ArrowArrayRef),
+            ])
+            .unwrap()
+        };
+
+        ensure_char_varchar_lengths(&make_batch("ab", "abc"), &schema).unwrap();
+        ensure_char_varchar_lengths(&make_batch("ab   ", "abc "), &schema).unwrap();
+        assert_result_error_with_message(
+            ensure_char_varchar_lengths(&make_batch("abc", "abc"), &schema),
+            "Column 'c' has a value exceeding the length limit of its type char(2)",
+        );
+        assert_result_error_with_message(
+            ensure_char_varchar_lengths(&make_batch("ab", "abcd"), &schema),
+            "Column 'v' has a value exceeding the length limit of its type varchar(3)",
+        );
+    }
+
+    #[test]
+    fn test_ensure_char_varchar_lengths_in_arrays_and_maps() {
+        use crate::arrow::array::{ListBuilder, StringBuilder};
+        use crate::schema::CharVarcharType;
+
+        let schema = StructType::new_unchecked([
+            StructField::nullable("a", ArrayType::new(DataType::STRING, true))
+                .with_char_varchar_type(CharVarcharType::Varchar(3)),
+            StructField::nullable("m", MapType::new(DataType::STRING, DataType::STRING, true))
+                .with_char_varchar_type(CharVarcharType::Char(2)),
+        ]);
+        let make_batch = |rows: &[(&[&str], (&str, &str))]| {
+            let mut list_builder = ListBuilder::new(StringBuilder::new());
+            let mut map_builder = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+            for (elements, (key, value)) in rows {
+                for element in *elements {
+                    list_builder.values().append_value(element);
+                }
+                list_builder.append(true);
+                map_builder.keys().append_value(key);
+                map_builder.values().append_value(value);
+                map_builder.append(true).unwrap();
+            }
+            RecordBatch::try_from_iter(vec![
+                ("a", Arc::new(list_builder.finish()) as ArrowArrayRef),
+                ("m", Arc::new(map_builder.finish()) as ArrowArrayRef),
+            ])
+            .unwrap()
+        };
+
+        ensure_char_varchar_lengths(&make_batch(&[(&["a", "abc"], ("k", "v"))]), &schema).unwrap();
+        assert_result_error_with_message(
+            ensure_char_varchar_lengths(&make_batch(&[(&["a", "abcd"], ("k", "v"))]), &schema),
+            "Column 'a' has a value exceeding the length limit of its type varchar(3)",
+        );
+        assert_result_error_with_message(
+            ensure_char_varchar_lengths(&make_batch(&[(&["a"], ("key", "v"))]), &schema),
+            "Column 'm' has a value exceeding the length limit of its type char(2)",
+        );
+        assert_result_error_with_message(
+            ensure_char_varchar_lengths(&make_batch(&[(&["a"], ("k", "value"))]), &schema),
+            "Column 'm' has a value exceeding the length limit of its type char(2)",
+        );
+
+        // values of rows sliced away are not checked
+        let batch = make_batch(&[(&["abcd"], ("key", "v")), (&["abc"], ("k", "v"))]);
+        ensure_char_varchar_lengths(&batch.slice(1, 1), &schema).unwrap();
+    }
 }
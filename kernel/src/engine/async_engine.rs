@@ -0,0 +1,433 @@
+//! An object-safe async variant of the [`Engine`] trait.
+//!
+//! The kernel APIs are synchronous, so engines whose IO is natively async otherwise have to block
+//! a thread inside every handler call. An [`AsyncEngine`] instead exposes the IO operations of the
+//! storage, JSON and Parquet handlers as futures and streams. [`AsyncEngineAdapter`] turns an
+//! [`AsyncEngine`] into an [`Engine`] the kernel can use, driving its futures on a
+//! [`TaskExecutor`], and [`SyncEngineAdapter`] goes the other way, running the handlers of an
+//! [`Engine`] on blocking threads.
+//!
+//! Parsing JSON and evaluating expressions don't do IO, so they are synchronous in both traits.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, StreamExt as _};
+use url::Url;
+
+use super::default::executor::TaskExecutor;
+use crate::schema::SchemaRef;
+use crate::{
+    AsAny, DeltaResult, Engine, EngineData, Error, EvaluationHandler, FileDataReadResultIterator,
+    FileMeta, FileSlice, JsonHandler, ParquetHandler, PredicateRef, StorageHandler,
+};
+
+/// A stream of data read from specified files, the async counterpart of
+/// [`FileDataReadResultIterator`].
+pub type FileDataReadResultStream = BoxStream<'static, DeltaResult<Box<dyn EngineData>>>;
+
+/// The async counterpart of [`Engine`]. Each method has the same contract as the handler method
+/// of the same name, e.g. [`read_parquet_files`] must emit data in the order that `files` is
+/// given, see [`ParquetHandler::read_parquet_files`].
+///
+/// [`read_parquet_files`]: Self::read_parquet_files
+pub trait AsyncEngine: AsAny {
+    /// Get the connector provided [`EvaluationHandler`].
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler>;
+
+    /// See [`StorageHandler::list_from`].
+    fn list_from<'a>(
+        &'a self,
+        path: &'a Url,
+    ) -> BoxFuture<'a, DeltaResult<BoxStream<'static, DeltaResult<FileMeta>>>>;
+
+    /// See [`StorageHandler::read_files`].
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> BoxFuture<'_, DeltaResult<BoxStream<'static, DeltaResult<Bytes>>>>;
+
+    /// See [`StorageHandler::delete_files`].
+    fn delete_files(&self, files: Vec<Url>) -> BoxFuture<'_, DeltaResult<()>> {
+        let _ = files;
+        Box::pin(async {
+            Err(Error::unsupported(
+                "This engine does not support deleting files",
+            ))
+        })
+    }
+
+    /// See [`JsonHandler::parse_json`].
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>>;
+
+    /// See [`JsonHandler::read_json_files`].
+    fn read_json_files<'a>(
+        &'a self,
+        files: &'a [FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> BoxFuture<'a, DeltaResult<FileDataReadResultStream>>;
+
+    /// See [`JsonHandler::write_json_file`].
+    fn write_json_file<'a>(
+        &'a self,
+        path: &'a Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a>,
+        overwrite: bool,
+    ) -> BoxFuture<'a, DeltaResult<()>>;
+
+    /// See [`ParquetHandler::read_parquet_files`].
+    fn read_parquet_files<'a>(
+        &'a self,
+        files: &'a [FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> BoxFuture<'a, DeltaResult<FileDataReadResultStream>>;
+}
+
+/// An [`Engine`] backed by an [`AsyncEngine`], whose futures and streams are driven by blocking on
+/// the given [`TaskExecutor`].
+pub struct AsyncEngineAdapter<E: TaskExecutor> {
+    engine: Arc<dyn AsyncEngine>,
+    executor: Arc<E>,
+}
+
+impl<E: TaskExecutor> AsyncEngineAdapter<E> {
+    pub fn new(engine: Arc<dyn AsyncEngine>, executor: Arc<E>) -> Self {
+        Self { engine, executor }
+    }
+
+    /// The wrapped async engine.
+    pub fn async_engine(&self) -> &Arc<dyn AsyncEngine> {
+        &self.engine
+    }
+
+    fn handler(&self) -> Arc<AsyncHandler<E>> {
+        Arc::new(AsyncHandler {
+            engine: self.engine.clone(),
+            executor: self.executor.clone(),
+        })
+    }
+}
+
+impl<E: TaskExecutor> Engine for AsyncEngineAdapter<E> {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.engine.evaluation_handler()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+        self.handler()
+    }
+
+    fn json_handler(&self) -> Arc<dyn JsonHandler> {
+        self.handler()
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+        self.handler()
+    }
+}
+
+// Implements the sync handlers on top of an async engine.
+struct AsyncHandler<E: TaskExecutor> {
+    engine: Arc<dyn AsyncEngine>,
+    executor: Arc<E>,
+}
+
+impl<E: TaskExecutor> AsyncHandler<E> {
+    // Returns an iterator that blocks on the executor for each item of the stream.
+    fn blocking_iter<T: Send + 'static>(
+        &self,
+        stream: BoxStream<'static, T>,
+    ) -> impl Iterator<Item = T> + Send + 'static {
+        let executor = self.executor.clone();
+        let mut stream = Some(stream);
+        std::iter::from_fn(move || {
+            let mut current = stream.take()?;
+            let (item, current) = executor.block_on(async move {
+                let item = current.next().await;
+                (item, current)
+            });
+            if item.is_some() {
+                stream = Some(current);
+            }
+            item
+        })
+    }
+}
+
+impl<E: TaskExecutor> StorageHandler for AsyncHandler<E> {
+    fn list_from(
+        &self,
+        path: &Url,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
+        let (engine, path) = (self.engine.clone(), path.clone());
+        let stream = self
+            .executor
+            .block_on(async move { engine.list_from(&path).await })?;
+        Ok(Box::new(self.blocking_iter(stream)))
+    }
+
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+        let engine = self.engine.clone();
+        let stream = self
+            .executor
+            .block_on(async move { engine.read_files(files).await })?;
+        Ok(Box::new(self.blocking_iter(stream)))
+    }
+
+    fn delete_files(&self, files: Vec<Url>) -> DeltaResult<()> {
+        let engine = self.engine.clone();
+        self.executor
+            .block_on(async move { engine.delete_files(files).await })
+    }
+}
+
+impl<E: TaskExecutor> JsonHandler for AsyncHandler<E> {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.engine.parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let (engine, files) = (self.engine.clone(), files.to_vec());
+        let stream = self.executor.block_on(async move {
+            engine
+                .read_json_files(&files, physical_schema, predicate)
+                .await
+        })?;
+        Ok(Box::new(self.blocking_iter(stream)))
+    }
+
+    fn write_json_file(
+        &self,
+        path: &Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+        overwrite: bool,
+    ) -> DeltaResult<()> {
+        // the future must own its data, so collect it first (commits are small)
+        let data = data.collect::<DeltaResult<Vec<_>>>()?;
+        let (engine, path) = (self.engine.clone(), path.clone());
+        self.executor.block_on(async move {
+            let data = Box::new(data.into_iter().map(Ok));
+            engine.write_json_file(&path, data, overwrite).await
+        })
+    }
+}
+
+impl<E: TaskExecutor> ParquetHandler for AsyncHandler<E> {
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let (engine, files) = (self.engine.clone(), files.to_vec());
+        let stream = self.executor.block_on(async move {
+            engine
+                .read_parquet_files(&files, physical_schema, predicate)
+                .await
+        })?;
+        Ok(Box::new(self.blocking_iter(stream)))
+    }
+}
+
+/// An [`AsyncEngine`] backed by an [`Engine`], whose handlers are called on blocking threads of
+/// the given [`TaskExecutor`].
+///
+/// The iterators returned by [`StorageHandler`]s are not `Send`, so listings and file reads are
+/// collected on the blocking thread before being streamed. Data read by the JSON and Parquet
+/// handlers is streamed lazily.
+pub struct SyncEngineAdapter<E: TaskExecutor> {
+    engine: Arc<dyn Engine>,
+    executor: Arc<E>,
+}
+
+impl<E: TaskExecutor> SyncEngineAdapter<E> {
+    pub fn new(engine: Arc<dyn Engine>, executor: Arc<E>) -> Self {
+        Self { engine, executor }
+    }
+
+    /// The wrapped sync engine.
+    pub fn engine(&self) -> &Arc<dyn Engine> {
+        &self.engine
+    }
+
+    // Returns a stream that calls `next` on the iterator on a blocking thread for each item.
+    fn stream(&self, iter: FileDataReadResultIterator) -> FileDataReadResultStream {
+        let executor = self.executor.clone();
+        stream::unfold(Some(iter), move |iter| {
+            let executor = executor.clone();
+            async move {
+                let mut iter = iter?;
+                let next = executor.spawn_blocking(move || {
+                    let item = iter.next();
+                    (item, iter)
+                });
+                match next.await {
+                    Ok((item, iter)) => Some((item?, Some(iter))),
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+impl<E: TaskExecutor> AsyncEngine for SyncEngineAdapter<E> {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.engine.evaluation_handler()
+    }
+
+    fn list_from<'a>(
+        &'a self,
+        path: &'a Url,
+    ) -> BoxFuture<'a, DeltaResult<BoxStream<'static, DeltaResult<FileMeta>>>> {
+        let (storage, path) = (self.engine.storage_handler(), path.clone());
+        Box::pin(async move {
+            let files = self
+                .executor
+                .spawn_blocking(move || storage.list_from(&path)?.collect::<DeltaResult<Vec<_>>>())
+                .await??;
+            Ok(stream::iter(files).map(Ok).boxed())
+        })
+    }
+
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> BoxFuture<'_, DeltaResult<BoxStream<'static, DeltaResult<Bytes>>>> {
+        let storage = self.engine.storage_handler();
+        Box::pin(async move {
+            let contents = self
+                .executor
+                .spawn_blocking(move || storage.read_files(files)?.collect::<DeltaResult<Vec<_>>>())
+                .await??;
+            Ok(stream::iter(contents).map(Ok).boxed())
+        })
+    }
+
+    fn delete_files(&self, files: Vec<Url>) -> BoxFuture<'_, DeltaResult<()>> {
+        let storage = self.engine.storage_handler();
+        Box::pin(async move {
+            self.executor
+                .spawn_blocking(move || storage.delete_files(files))
+                .await?
+        })
+    }
+
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.engine
+            .json_handler()
+            .parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files<'a>(
+        &'a self,
+        files: &'a [FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> BoxFuture<'a, DeltaResult<FileDataReadResultStream>> {
+        let (handler, files) = (self.engine.json_handler(), files.to_vec());
+        Box::pin(async move {
+            let iter = self
+                .executor
+                .spawn_blocking(move || handler.read_json_files(&files, physical_schema, predicate))
+                .await??;
+            Ok(self.stream(iter))
+        })
+    }
+
+    fn write_json_file<'a>(
+        &'a self,
+        path: &'a Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a>,
+        overwrite: bool,
+    ) -> BoxFuture<'a, DeltaResult<()>> {
+        let (handler, path) = (self.engine.json_handler(), path.clone());
+        // the blocking task must own its data, so collect it first (commits are small)
+        let data = data.collect::<DeltaResult<Vec<_>>>();
+        Box::pin(async move {
+            let data = data?;
+            self.executor
+                .spawn_blocking(move || {
+                    handler.write_json_file(&path, Box::new(data.into_iter().map(Ok)), overwrite)
+                })
+                .await?
+        })
+    }
+
+    fn read_parquet_files<'a>(
+        &'a self,
+        files: &'a [FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> BoxFuture<'a, DeltaResult<FileDataReadResultStream>> {
+        let (handler, files) = (self.engine.parquet_handler(), files.to_vec());
+        Box::pin(async move {
+            let iter = self
+                .executor
+                .spawn_blocking(move || {
+                    handler.read_parquet_files(&files, physical_schema, predicate)
+                })
+                .await??;
+            Ok(self.stream(iter))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use object_store::local::LocalFileSystem;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::Snapshot;
+
+    #[test]
+    fn test_round_trip_through_async_engine() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/"));
+        let url = Url::from_directory_path(path.unwrap()).unwrap();
+
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let engine: Arc<dyn Engine> = Arc::new(DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            executor.clone(),
+        ));
+        let async_engine = Arc::new(SyncEngineAdapter::new(engine, executor.clone()));
+        let engine: Arc<dyn Engine> = Arc::new(AsyncEngineAdapter::new(async_engine, executor));
+
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+        assert_eq!(snapshot.version(), 0);
+        let scan = snapshot.scan_builder().build().unwrap();
+        let rows: usize = scan
+            .execute(engine)
+            .unwrap()
+            .map(|result| result.unwrap().raw_data.unwrap().len())
+            .sum();
+        assert_eq!(rows, 10);
+    }
+}
@@ -0,0 +1,391 @@
+//! A DataFusion [`TableProvider`] backed by kernel scans.
+//!
+//! [`DeltaTableProvider`] exposes a [`Snapshot`] as a DataFusion table. Projections are pushed down
+//! as the scan schema, and filters are converted to a kernel [`Predicate`] where possible so that
+//! files can be skipped based on their stats and partition values. Since skipping is not exact,
+//! filters are pushed down as [`TableProviderFilterPushDown::Inexact`], i.e. DataFusion still
+//! applies them to the scanned rows.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::catalog::{Session, TableProvider};
+use datafusion::common::{DataFusionError, Result as DataFusionResult, ScalarValue};
+use datafusion::datasource::TableType;
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{BinaryExpr, Expr, Operator, TableProviderFilterPushDown};
+use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties,
+    SendableRecordBatchStream,
+};
+use futures::SinkExt;
+
+use crate::arrow::array::{BooleanArray, RecordBatch, RecordBatchOptions};
+use crate::arrow::compute::filter_record_batch;
+use crate::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use crate::engine::arrow_conversion::TryIntoArrow as _;
+use crate::engine::arrow_data::ArrowEngineData;
+use crate::expressions::{ColumnName, Expression, Predicate, Scalar};
+use crate::scan::{Scan, ScanResult};
+use crate::schema::StructType;
+use crate::{DeltaResult, Engine, Error, Snapshot, SnapshotRef};
+
+/// A DataFusion [`TableProvider`] that scans a [`Snapshot`] with the kernel.
+#[derive(Debug)]
+pub struct DeltaTableProvider {
+    snapshot: SnapshotRef,
+    engine: Arc<dyn Engine>,
+    schema: ArrowSchemaRef,
+}
+
+impl DeltaTableProvider {
+    /// Creates a provider for the given snapshot, which is scanned with the given engine.
+    pub fn try_new(snapshot: SnapshotRef, engine: Arc<dyn Engine>) -> DeltaResult<Self> {
+        let schema: ArrowSchema = snapshot.schema().as_ref().try_into_arrow()?;
+        Ok(Self {
+            snapshot,
+            engine,
+            schema: Arc::new(schema),
+        })
+    }
+}
+
+#[async_trait]
+impl TableProvider for DeltaTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> ArrowSchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        Ok(filters
+            .iter()
+            .map(|filter| match to_kernel_predicate(filter) {
+                Some(_) => TableProviderFilterPushDown::Inexact,
+                None => TableProviderFilterPushDown::Unsupported,
+            })
+            .collect())
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let output_schema = match projection {
+            Some(projection) => Arc::new(self.schema.project(projection)?),
+            None => self.schema.clone(),
+        };
+
+        // The kernel can't scan zero columns, so queries that only need row counts (e.g. `SELECT
+        // count(*)`) scan the first column and drop it afterwards.
+        let table_schema = self.snapshot.schema();
+        let read_fields: Vec<_> = match projection {
+            Some(projection) if projection.is_empty() => table_schema.fields().take(1).collect(),
+            Some(projection) => projection
+                .iter()
+                .filter_map(|&i| table_schema.fields().nth(i))
+                .collect(),
+            None => table_schema.fields().collect(),
+        };
+        let read_schema =
+            StructType::try_new(read_fields.into_iter().cloned()).map_err(to_datafusion_error)?;
+
+        let predicate = filters.iter().filter_map(to_kernel_predicate);
+        let predicate = Predicate::and_from(predicate);
+        let scan = self
+            .snapshot
+            .clone()
+            .scan_builder()
+            .with_schema(Arc::new(read_schema))
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .map_err(to_datafusion_error)?;
+
+        Ok(Arc::new(DeltaScanExec::new(
+            Arc::new(scan),
+            self.engine.clone(),
+            output_schema,
+            limit,
+        )))
+    }
+}
+
+/// The [`ExecutionPlan`] of a [`DeltaTableProvider`] scan. It executes the kernel scan on a
+/// blocking thread and streams the resulting batches, with deleted rows filtered out.
+#[derive(Debug)]
+pub struct DeltaScanExec {
+    scan: Arc<Scan>,
+    engine: Arc<dyn Engine>,
+    schema: ArrowSchemaRef,
+    limit: Option<usize>,
+    properties: PlanProperties,
+}
+
+impl DeltaScanExec {
+    fn new(
+        scan: Arc<Scan>,
+        engine: Arc<dyn Engine>,
+        schema: ArrowSchemaRef,
+        limit: Option<usize>,
+    ) -> Self {
+        let properties = PlanProperties::new(
+            EquivalenceProperties::new(schema.clone()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        );
+        Self {
+            scan,
+            engine,
+            schema,
+            limit,
+            properties,
+        }
+    }
+}
+
+impl DisplayAs for DeltaScanExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DeltaScanExec: table={}", self.scan.table_root())?;
+        if let Some(predicate) = self.scan.physical_predicate() {
+            write!(f, ", predicate={predicate}")?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, ", limit={limit}")?;
+        }
+        Ok(())
+    }
+}
+
+impl ExecutionPlan for DeltaScanExec {
+    fn name(&self) -> &str {
+        "DeltaScanExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "DeltaScanExec has a single partition, but partition {partition} was requested"
+            )));
+        }
+        let (mut tx, rx) = futures::channel::mpsc::channel(2);
+        let (scan, engine) = (self.scan.clone(), self.engine.clone());
+        let (schema, limit) = (self.schema.clone(), self.limit);
+        // the kernel scan does blocking IO, so run it on a blocking thread
+        tokio::task::spawn_blocking(move || {
+            let results = match scan.execute(engine) {
+                Ok(results) => results,
+                Err(e) => {
+                    let _ = futures::executor::block_on(tx.send(Err(to_datafusion_error(e))));
+                    return;
+                }
+            };
+            let mut rows = 0;
+            for result in results {
+                let batch = result
+                    .and_then(|result| to_record_batch(result, &schema))
+                    .map_err(to_datafusion_error);
+                rows += batch.as_ref().map_or(0, RecordBatch::num_rows);
+                // stop once the consumer is gone, or enough rows were produced
+                if futures::executor::block_on(tx.send(batch)).is_err()
+                    || limit.is_some_and(|limit| rows >= limit)
+                {
+                    return;
+                }
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            rx,
+        )))
+    }
+}
+
+// Converts a scan result to a record batch of the given schema, filtering out deleted rows.
+fn to_record_batch(result: ScanResult, schema: &ArrowSchemaRef) -> DeltaResult<RecordBatch> {
+    let mask = result.full_mask_buffer();
+    let batch: RecordBatch = ArrowEngineData::try_from_engine_data(result.raw_data?)?.into();
+    let batch = match mask {
+        Some(mask) => filter_record_batch(&batch, &BooleanArray::new(mask, None))?,
+        None => batch,
+    };
+    if schema.fields().is_empty() {
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        return Ok(RecordBatch::try_new_with_options(
+            schema.clone(),
+            vec![],
+            &options,
+        )?);
+    }
+    Ok(RecordBatch::try_new(
+        schema.clone(),
+        batch.columns().to_vec(),
+    )?)
+}
+
+fn to_datafusion_error(error: Error) -> DataFusionError {
+    DataFusionError::External(Box::new(error))
+}
+
+/// Converts a DataFusion filter to a kernel [`Predicate`], or returns `None` if the filter (or
+/// any part of it) has no kernel equivalent.
+fn to_kernel_predicate(expr: &Expr) -> Option<Predicate> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+            Operator::And => Some(Predicate::and(
+                to_kernel_predicate(left)?,
+                to_kernel_predicate(right)?,
+            )),
+            Operator::Or => Some(Predicate::or(
+                to_kernel_predicate(left)?,
+                to_kernel_predicate(right)?,
+            )),
+            _ => {
+                let (left, right) = (to_kernel_expression(left)?, to_kernel_expression(right)?);
+                match op {
+                    Operator::Eq => Some(Predicate::eq(left, right)),
+                    Operator::NotEq => Some(Predicate::ne(left, right)),
+                    Operator::Lt => Some(Predicate::lt(left, right)),
+                    Operator::LtEq => Some(Predicate::le(left, right)),
+                    Operator::Gt => Some(Predicate::gt(left, right)),
+                    Operator::GtEq => Some(Predicate::ge(left, right)),
+                    _ => None,
+                }
+            }
+        },
+        Expr::Not(expr) => Some(Predicate::not(to_kernel_predicate(expr)?)),
+        Expr::IsNull(expr) => Some(Predicate::is_null(to_kernel_expression(expr)?)),
+        Expr::IsNotNull(expr) => Some(Predicate::is_not_null(to_kernel_expression(expr)?)),
+        _ => None,
+    }
+}
+
+fn to_kernel_expression(expr: &Expr) -> Option<Expression> {
+    match expr {
+        Expr::Column(column) => Some(Expression::Column(ColumnName::new([column.name()]))),
+        Expr::Literal(value, _) => Some(Expression::Literal(to_kernel_scalar(value)?)),
+        _ => None,
+    }
+}
+
+fn to_kernel_scalar(value: &ScalarValue) -> Option<Scalar> {
+    // comparisons with NULL are never true, so they are not worth pushing down
+    if value.is_null() {
+        return None;
+    }
+    let array = value.to_array().ok()?;
+    Scalar::try_from_array(array.as_ref(), 0).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use datafusion::prelude::SessionContext;
+    use object_store::local::LocalFileSystem;
+    use url::Url;
+
+    use super::*;
+    use crate::arrow::array::AsArray;
+    use crate::arrow::datatypes::Int64Type;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    fn table_context(table: &str) -> SessionContext {
+        let path = std::fs::canonicalize(PathBuf::from(table)).unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine: Arc<dyn Engine> = Arc::new(DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+        let provider = DeltaTableProvider::try_new(snapshot, engine).unwrap();
+        let context = SessionContext::new();
+        context.register_table("t", Arc::new(provider)).unwrap();
+        context
+    }
+
+    async fn query(context: &SessionContext, sql: &str) -> Vec<i64> {
+        let batches = context.sql(sql).await.unwrap().collect().await.unwrap();
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_table_provider() {
+        let context = table_context("./tests/data/table-without-dv-small/");
+        assert_eq!(query(&context, "SELECT count(*) FROM t").await, vec![10]);
+        let filtered = "SELECT value FROM t WHERE value > 6 ORDER BY value";
+        assert_eq!(query(&context, filtered).await, vec![7, 8, 9]);
+
+        // rows removed by deletion vectors are filtered out
+        let context = table_context("./tests/data/table-with-dv-small/");
+        assert_eq!(query(&context, "SELECT count(*) FROM t").await, vec![8]);
+    }
+
+    #[test]
+    fn test_to_kernel_predicate() {
+        use crate::expressions::column_expr;
+        use datafusion::prelude::{col, lit};
+
+        let filter = col("a").gt(lit(5i64)).and(col("b").is_not_null());
+        let expected = Predicate::and(
+            Predicate::gt(column_expr!("a"), Scalar::Long(5)),
+            Predicate::is_not_null(column_expr!("b")),
+        );
+        assert_eq!(to_kernel_predicate(&filter), Some(expected));
+
+        // filters without a kernel equivalent can't be pushed down, even partially
+        let filter = col("a").gt(lit(5i64)).or(col("b").like(lit("x%")));
+        assert_eq!(to_kernel_predicate(&filter), None);
+    }
+}
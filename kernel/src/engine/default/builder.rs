@@ -0,0 +1,395 @@
+//! A typed builder for [`DefaultEngine`], see [`DefaultEngineBuilder`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use object_store::{DynObjectStore, RetryConfig};
+use url::Url;
+
+use super::executor::TaskExecutor;
+use super::parquet::MissingColumnPolicy;
+use super::storage::{parse_url_opts, parse_url_opts_with_retry, validate_options};
+use super::DefaultEngine;
+use crate::redact::RedactedValues;
+use crate::DeltaResult;
+
+/// The credentials a [`DefaultEngine`] authenticates its requests to the object store with.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum Credentials {
+    /// Send unsigned requests, e.g. to read public buckets
+    Anonymous,
+    /// AWS access keys, optionally with a session token for temporary credentials
+    Aws {
+        access_key_id: String,
+        secret_access_key: String,
+        session_token: Option<String>,
+    },
+    /// An Azure storage account and its access key
+    AzureAccountKey {
+        account_name: String,
+        account_key: String,
+    },
+    /// An Azure storage account and a shared access signature (SAS) for it
+    AzureSas {
+        account_name: String,
+        sas_key: String,
+    },
+    /// The JSON key of a Google Cloud service account
+    GoogleServiceAccountKey(String),
+}
+
+// don't leak secrets into logs
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::Anonymous => "Anonymous",
+            Self::Aws { .. } => "Aws",
+            Self::AzureAccountKey { .. } => "AzureAccountKey",
+            Self::AzureSas { .. } => "AzureSas",
+            Self::GoogleServiceAccountKey(_) => "GoogleServiceAccountKey",
+        };
+        write!(f, "Credentials::{kind}(..)")
+    }
+}
+
+impl Credentials {
+    /// The object store options that configure these credentials.
+    fn options(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Anonymous => vec![("skip_signature", "true".to_string())],
+            Self::Aws {
+                access_key_id,
+                secret_access_key,
+                session_token,
+            } => {
+                let mut options = vec![
+                    ("aws_access_key_id", access_key_id.clone()),
+                    ("aws_secret_access_key", secret_access_key.clone()),
+                ];
+                if let Some(session_token) = session_token {
+                    options.push(("aws_session_token", session_token.clone()));
+                }
+                options
+            }
+            Self::AzureAccountKey {
+                account_name,
+                account_key,
+            } => vec![
+                ("azure_storage_account_name", account_name.clone()),
+                ("azure_storage_account_key", account_key.clone()),
+            ],
+            Self::AzureSas {
+                account_name,
+                sas_key,
+            } => vec![
+                ("azure_storage_account_name", account_name.clone()),
+                ("azure_storage_sas_key", sas_key.clone()),
+            ],
+            Self::GoogleServiceAccountKey(key) => vec![("google_service_account_key", key.clone())],
+        }
+    }
+}
+
+/// Builds a [`DefaultEngine`] for a table, with typed settings instead of the string options of
+/// [`DefaultEngine::try_new`].
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+/// # use delta_kernel::engine::default::{Credentials, DefaultEngine};
+/// # fn main() -> delta_kernel::DeltaResult<()> {
+/// let table_root = url::Url::parse("s3://bucket/table/")?;
+/// let engine = DefaultEngine::builder(table_root, Arc::new(TokioBackgroundExecutor::new()))
+///     .with_region("us-west-2")
+///     .with_credentials(Credentials::Anonymous)
+///     .with_batch_size(8192)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DefaultEngineBuilder<E: TaskExecutor> {
+    table_root: Url,
+    task_executor: Arc<E>,
+    region: Option<String>,
+    credentials: Option<Credentials>,
+    retry: Option<RetryConfig>,
+    options: HashMap<String, String>,
+    storage_roots: Vec<(Url, Credentials)>,
+    batch_size: Option<usize>,
+    io_concurrency: Option<usize>,
+    memory_budget: Option<usize>,
+    row_group_parallelism: Option<usize>,
+    mmap_local_files: bool,
+    missing_column_policy: MissingColumnPolicy,
+    listing_cache_max_age: Option<Duration>,
+}
+
+// option values may hold secrets, see [`crate::redact`]
+impl<E: TaskExecutor + std::fmt::Debug> std::fmt::Debug for DefaultEngineBuilder<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultEngineBuilder")
+            .field("table_root", &self.table_root)
+            .field("task_executor", &self.task_executor)
+            .field("region", &self.region)
+            .field("credentials", &self.credentials)
+            .field("retry", &self.retry)
+            .field("options", &RedactedValues(&self.options))
+            .field("storage_roots", &self.storage_roots)
+            .field("batch_size", &self.batch_size)
+            .field("io_concurrency", &self.io_concurrency)
+            .field("memory_budget", &self.memory_budget)
+            .field("row_group_parallelism", &self.row_group_parallelism)
+            .field("mmap_local_files", &self.mmap_local_files)
+            .field("missing_column_policy", &self.missing_column_policy)
+            .field("listing_cache_max_age", &self.listing_cache_max_age)
+            .finish()
+    }
+}
+
+impl<E: TaskExecutor> DefaultEngineBuilder<E> {
+    /// Creates a builder for an engine that reads the table at `table_root`, and runs its async
+    /// IO tasks on `task_executor`.
+    pub fn new(table_root: Url, task_executor: Arc<E>) -> Self {
+        Self {
+            table_root,
+            task_executor,
+            region: None,
+            credentials: None,
+            retry: None,
+            options: HashMap::new(),
+            storage_roots: vec![],
+            batch_size: None,
+            io_concurrency: None,
+            memory_budget: None,
+            row_group_parallelism: None,
+            mmap_local_files: false,
+            missing_column_policy: MissingColumnPolicy::default(),
+            listing_cache_max_age: None,
+        }
+    }
+
+    /// Set the AWS region of the table's bucket.
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Set the credentials to authenticate requests to the object store with. By default, the
+    /// object store looks for credentials in its environment (e.g. instance metadata).
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Set the policy for retrying failed requests to the object store. Only supported for cloud
+    /// object stores, not for local or in-memory ones.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Set an object store option that has no typed setting, as [`DefaultEngine::try_new`] takes
+    /// them. Options are validated when the engine is built.
+    pub fn with_option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.insert(key.into(), value.into());
+        self
+    }
+
+    /// Allow reading files under `root`, which is in another bucket or account than the table
+    /// (e.g. the source table of a shallow clone), authenticating with `credentials`. Only files
+    /// under allowed roots are read from other stores than the table's, see
+    /// [`DefaultEngine::with_storage_root`].
+    pub fn with_storage_root(mut self, root: Url, credentials: Credentials) -> Self {
+        self.storage_roots.push((root, credentials));
+        self
+    }
+
+    /// See [`DefaultEngine::with_batch_size`].
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// See [`DefaultEngine::with_io_concurrency`].
+    pub fn with_io_concurrency(mut self, io_concurrency: usize) -> Self {
+        self.io_concurrency = Some(io_concurrency);
+        self
+    }
+
+    /// See [`DefaultEngine::with_memory_budget`].
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// See [`DefaultEngine::with_row_group_parallelism`].
+    pub fn with_row_group_parallelism(mut self, parallelism: usize) -> Self {
+        self.row_group_parallelism = Some(parallelism);
+        self
+    }
+
+    /// See [`DefaultEngine::with_mmap_local_files`].
+    ///
+    /// # Safety
+    ///
+    /// If `mmap_local_files` is true, the local files the engine reads must not be modified or
+    /// truncated while they are mapped, see [`DefaultEngine::with_mmap_local_files`].
+    pub unsafe fn with_mmap_local_files(mut self, mmap_local_files: bool) -> Self {
+        self.mmap_local_files = mmap_local_files;
+        self
+    }
+
+    /// See [`DefaultEngine::with_missing_column_policy`].
+    pub fn with_missing_column_policy(mut self, policy: MissingColumnPolicy) -> Self {
+        self.missing_column_policy = policy;
+        self
+    }
+
+    /// See [`DefaultEngine::with_listing_cache`].
+    pub fn with_listing_cache(mut self, max_age: Duration) -> Self {
+        self.listing_cache_max_age = Some(max_age);
+        self
+    }
+
+    /// Builds the engine, failing if its settings are invalid for the table's object store, see
+    /// [`validate_options`].
+    ///
+    /// [`validate_options`]: super::storage::validate_options
+    pub fn build(self) -> DeltaResult<DefaultEngine<E>> {
+        let mut options = self.options;
+        if let Some(region) = self.region {
+            options.insert("aws_region".to_string(), region);
+        }
+        if let Some(credentials) = &self.credentials {
+            for (key, value) in credentials.options() {
+                options.insert(key.to_string(), value);
+            }
+        }
+        validate_options(&self.table_root, &options)?;
+        let object_store: Arc<DynObjectStore> = match self.retry {
+            Some(retry) => parse_url_opts_with_retry(&self.table_root, options, retry)?,
+            None => Arc::new(parse_url_opts(&self.table_root, options)?.0),
+        };
+
+        let mut engine = DefaultEngine::new(object_store, self.task_executor)
+            .with_table_root(self.table_root)
+            .with_missing_column_policy(self.missing_column_policy);
+        // SAFETY: the caller of `Self::with_mmap_local_files` guarantees the files aren't modified
+        engine = unsafe { engine.with_mmap_local_files(self.mmap_local_files) };
+        for (root, credentials) in self.storage_roots {
+            let options: HashMap<String, String> = credentials
+                .options()
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect();
+            validate_options(&root, &options)?;
+            let (store, _) = parse_url_opts(&root, options)?;
+            engine = engine.with_storage_root(root, store.into());
+        }
+        if let Some(batch_size) = self.batch_size {
+            engine = engine.with_batch_size(batch_size);
+        }
+        if let Some(io_concurrency) = self.io_concurrency {
+            engine = engine.with_io_concurrency(io_concurrency);
+        }
+        if let Some(bytes) = self.memory_budget {
+            engine = engine.with_memory_budget(bytes);
+        }
+        if let Some(parallelism) = self.row_group_parallelism {
+            engine = engine.with_row_group_parallelism(parallelism);
+        }
+        if let Some(max_age) = self.listing_cache_max_age {
+            engine = engine.with_listing_cache(max_age);
+        }
+        Ok(engine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn builder(table_root: &str) -> DefaultEngineBuilder<TokioBackgroundExecutor> {
+        let table_root = Url::parse(table_root).unwrap();
+        DefaultEngineBuilder::new(table_root, Arc::new(TokioBackgroundExecutor::new()))
+    }
+
+    #[test]
+    fn test_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let engine_builder = builder(table_root.as_str())
+            .with_batch_size(10)
+            .with_io_concurrency(2)
+            .with_row_group_parallelism(4);
+        // SAFETY: the engine reads no files
+        unsafe { engine_builder.with_mmap_local_files(true) }
+            .with_missing_column_policy(MissingColumnPolicy::FillNull)
+            .build()
+            .unwrap();
+        // e.g. the examples pass a region for every table, including local ones
+        builder(table_root.as_str())
+            .with_region("us-west-2")
+            .with_option("skip_signature", "true")
+            .build()
+            .unwrap();
+
+        builder("s3://bucket/table/")
+            .with_region("us-west-2")
+            .with_credentials(Credentials::Aws {
+                access_key_id: "id".to_string(),
+                secret_access_key: "secret".to_string(),
+                session_token: None,
+            })
+            .with_retry(RetryConfig::default())
+            .build()
+            .unwrap();
+
+        let source_root = Url::parse("s3://source-bucket/table/").unwrap();
+        builder("s3://bucket/table/")
+            .with_storage_root(source_root, Credentials::Anonymous)
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_build_invalid() {
+        let result = builder("gs://bucket/table/")
+            .with_region("us-west-2")
+            .build();
+        assert_result_error_with_message(result, "unknown option `aws_region`");
+
+        let result = builder("s3://bucket/table/")
+            .with_credentials(Credentials::Anonymous)
+            .with_option("access_key_id", "id")
+            .build();
+        assert_result_error_with_message(result, "conflict");
+
+        let result = builder("memory:///")
+            .with_retry(RetryConfig::default())
+            .build();
+        assert_result_error_with_message(result, "Retry policies are not supported");
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let _lock = crate::redact::tests::REDACTION_LOCK.lock().unwrap();
+        let builder = builder("s3://bucket/table/")
+            .with_credentials(Credentials::AzureSas {
+                account_name: "account".to_string(),
+                sas_key: "sas-secret".to_string(),
+            })
+            .with_option("aws_secret_access_key", "option-secret");
+        let debug = format!("{builder:?}");
+        assert!(!debug.contains("sas-secret"), "{debug}");
+        assert!(!debug.contains("option-secret"), "{debug}");
+        assert!(
+            debug.contains(r#""aws_secret_access_key": <redacted>"#),
+            "{debug}"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Coalescing of small record batches.
+//!
+//! Readers may produce many small batches, e.g. for files with tiny row groups, or once heavy
+//! filtering has been applied. Since engines usually pay a fixed overhead per batch,
+//! [`coalesce_batches`] merges consecutive small batches until they reach a target row count.
+
+use std::mem;
+
+use crate::arrow::array::RecordBatch;
+use crate::arrow::compute::concat_batches;
+use crate::{DeltaResult, Error};
+
+/// Merges consecutive batches of `batches` into batches of at least `target_rows` rows. Batches
+/// are never split, so a merged batch may exceed `target_rows` rows. Only the last batch may have
+/// fewer rows. Batches without any rows are dropped. All batches must have the same schema.
+///
+/// An error from `batches` is returned after the batches that preceded it.
+pub fn coalesce_batches(
+    batches: impl Iterator<Item = DeltaResult<RecordBatch>>,
+    target_rows: usize,
+) -> impl Iterator<Item = DeltaResult<RecordBatch>> {
+    CoalesceBatches {
+        batches,
+        target_rows,
+        pending: Vec::new(),
+        pending_rows: 0,
+        pending_error: None,
+    }
+}
+
+struct CoalesceBatches<I> {
+    batches: I,
+    target_rows: usize,
+    pending: Vec<RecordBatch>,
+    pending_rows: usize,
+    pending_error: Option<Error>,
+}
+
+impl<I> CoalesceBatches<I> {
+    fn flush(&mut self) -> Option<DeltaResult<RecordBatch>> {
+        self.pending_rows = 0;
+        let mut pending = mem::take(&mut self.pending);
+        match pending.len() {
+            0 => None,
+            1 => pending.pop().map(Ok),
+            _ => Some(concat_batches(&pending[0].schema(), &pending).map_err(Into::into)),
+        }
+    }
+}
+
+impl<I: Iterator<Item = DeltaResult<RecordBatch>>> Iterator for CoalesceBatches<I> {
+    type Item = DeltaResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.pending_error.take() {
+            return Some(Err(error));
+        }
+        loop {
+            match self.batches.next() {
+                Some(Ok(batch)) if batch.num_rows() == 0 => {}
+                Some(Ok(batch)) => {
+                    self.pending_rows += batch.num_rows();
+                    self.pending.push(batch);
+                    if self.pending_rows >= self.target_rows {
+                        return self.flush();
+                    }
+                }
+                Some(Err(error)) => {
+                    let Some(flushed) = self.flush() else {
+                        return Some(Err(error));
+                    };
+                    self.pending_error = Some(error);
+                    return Some(flushed);
+                }
+                None => return self.flush(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::arrow::array::{Array, Int32Array};
+    use crate::arrow::datatypes::{DataType, Field, Schema};
+
+    use super::*;
+
+    fn batch(values: impl IntoIterator<Item = i32>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let values = Int32Array::from_iter_values(values);
+        RecordBatch::try_new(schema, vec![Arc::new(values)]).unwrap()
+    }
+
+    fn values(batch: &RecordBatch) -> Vec<i32> {
+        let column = batch.column(0).as_any().downcast_ref::<Int32Array>();
+        column.unwrap().values().to_vec()
+    }
+
+    #[test]
+    fn test_coalesce_batches() {
+        let batches = vec![
+            Ok(batch([1])),
+            Ok(batch([])),
+            Ok(batch([2, 3])),
+            Ok(batch([4, 5, 6, 7])),
+            Ok(batch([8])),
+        ];
+        let coalesced: Vec<_> = coalesce_batches(batches.into_iter(), 3)
+            .map(|batch| values(&batch.unwrap()))
+            .collect();
+        assert_eq!(coalesced, vec![vec![1, 2, 3], vec![4, 5, 6, 7], vec![8]]);
+    }
+
+    #[test]
+    fn test_coalesce_batches_error() {
+        let batches = vec![Ok(batch([1])), Err(Error::generic("boom")), Ok(batch([2]))];
+        let mut coalesced = coalesce_batches(batches.into_iter(), 10);
+        assert_eq!(values(&coalesced.next().unwrap().unwrap()), vec![1]);
+        assert!(coalesced.next().unwrap().is_err());
+        assert_eq!(values(&coalesced.next().unwrap().unwrap()), vec![2]);
+        assert!(coalesced.next().is_none());
+    }
+}
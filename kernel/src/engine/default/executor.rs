@@ -151,10 +151,13 @@ pub mod tokio {
 
     /// A [`TaskExecutor`] that uses the tokio multi-threaded runtime. You can
     /// create one based on a handle to an existing runtime, so it can share
-    /// the runtime with other parts of your application.
+    /// the runtime with other parts of your application, or let it own a
+    /// dedicated runtime with a fixed number of worker threads.
     #[derive(Debug)]
     pub struct TokioMultiThreadExecutor {
         handle: tokio::runtime::Handle,
+        // Only set if the executor owns its runtime, see `with_worker_threads`
+        runtime: Option<tokio::runtime::Runtime>,
     }
 
     impl TokioMultiThreadExecutor {
@@ -164,7 +167,38 @@ pub mod tokio {
                 RuntimeFlavor::MultiThread,
                 "TokioExecutor must be created with a multi-threaded runtime"
             );
-            Self { handle }
+            Self {
+                handle,
+                runtime: None,
+            }
+        }
+
+        /// Create an executor that owns a dedicated multi-threaded runtime with
+        /// `worker_threads` worker threads (and at most as many blocking
+        /// threads). Sharing one such executor between all handlers of an
+        /// engine bounds the threads the engine uses, no matter how many scans
+        /// run concurrently. Panics if `worker_threads` is zero.
+        pub fn with_worker_threads(worker_threads: usize) -> DeltaResult<Self> {
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(worker_threads)
+                .max_blocking_threads(worker_threads)
+                .thread_name("delta-kernel-io")
+                .enable_all()
+                .build()?;
+            Ok(Self {
+                handle: runtime.handle().clone(),
+                runtime: Some(runtime),
+            })
+        }
+    }
+
+    impl Drop for TokioMultiThreadExecutor {
+        fn drop(&mut self) {
+            // Dropping a runtime panics if done from an async context, e.g. when
+            // the last engine reference goes away inside a task.
+            if let Some(runtime) = self.runtime.take() {
+                runtime.shutdown_background();
+            }
         }
     }
 
@@ -246,5 +280,11 @@ pub mod tokio {
             let executor = TokioMultiThreadExecutor::new(tokio::runtime::Handle::current());
             test_executor(executor).await;
         }
+
+        #[tokio::test]
+        async fn test_tokio_multi_thread_executor_with_worker_threads() {
+            let executor = TokioMultiThreadExecutor::with_worker_threads(2).unwrap();
+            test_executor(executor).await;
+        }
     }
 }
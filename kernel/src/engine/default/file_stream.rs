@@ -2,7 +2,8 @@ use std::collections::VecDeque;
 use std::mem;
 use std::ops::Range;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
 use std::task::{ready, Context, Poll};
 
 use crate::arrow::array::RecordBatch;
@@ -14,7 +15,7 @@ use tracing::error;
 
 use super::executor::TaskExecutor;
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::{DeltaResult, FileDataReadResultIterator, FileMeta};
+use crate::{DeltaResult, EngineData, FileDataReadResultIterator, FileMeta};
 
 /// A fallible future that resolves to a stream of [`RecordBatch`]
 /// cbindgen:ignore
@@ -78,6 +79,88 @@ enum FileStreamState {
     Error,
 }
 
+/// Limits the total (in-memory) size of the decoded batches that a read stream buffers ahead of
+/// its consumer, so that reading wide tables can't exhaust memory. The producer blocks until the
+/// consumer received enough batches to make room for the next one.
+#[derive(Debug)]
+struct MemoryBudget {
+    limit: usize,
+    state: Mutex<MemoryBudgetState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct MemoryBudgetState {
+    used: usize,
+    // Set once the consumer went away, so the producer never waits for it again
+    closed: bool,
+}
+
+impl MemoryBudget {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: Mutex::default(),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MemoryBudgetState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Blocks until `bytes` fit into the budget, then reserves and returns them. A batch larger
+    /// than the whole budget is admitted once nothing else is reserved, so it can't block forever.
+    fn reserve(&self, bytes: usize) -> usize {
+        let mut state = self.lock();
+        while !state.closed && state.used > 0 && state.used + bytes > self.limit {
+            state = self
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        state.used += bytes;
+        bytes
+    }
+
+    fn release(&self, bytes: usize) {
+        self.lock().used -= bytes;
+        self.changed.notify_all();
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.changed.notify_all();
+    }
+}
+
+/// The consuming end of a read stream, which returns the memory of each batch it receives to the
+/// stream's [`MemoryBudget`] (if any).
+struct BudgetedReceiver {
+    receiver: Receiver<(DeltaResult<RecordBatch>, usize)>,
+    budget: Option<Arc<MemoryBudget>>,
+}
+
+impl Iterator for BudgetedReceiver {
+    type Item = DeltaResult<Box<dyn EngineData>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (result, reserved) = self.receiver.recv().ok()?;
+        if let Some(budget) = &self.budget {
+            budget.release(reserved);
+        }
+        Some(result.map(|rb| Box::new(ArrowEngineData::new(rb)) as _))
+    }
+}
+
+impl Drop for BudgetedReceiver {
+    fn drop(&mut self) {
+        if let Some(budget) = &self.budget {
+            budget.close();
+        }
+    }
+}
+
 /// A stream that iterates record batch by record batch, file over file.
 #[allow(missing_debug_implementations)]
 pub struct FileStream {
@@ -108,6 +191,7 @@ impl FileStream {
         file_opener: Box<dyn FileOpener>,
         files: &[FileMeta],
         readahead: usize,
+        memory_budget: Option<usize>,
     ) -> DeltaResult<FileDataReadResultIterator> {
         let mut stream = FileStream::new(files.to_vec(), schema, file_opener)?;
 
@@ -115,13 +199,25 @@ impl FileStream {
         // The stream will execute in the background, and we allow up to `readahead`
         // batches to be buffered in the channel.
         let (sender, receiver) = std::sync::mpsc::sync_channel(readahead);
+        let budget = memory_budget.map(|limit| Arc::new(MemoryBudget::new(limit)));
 
         let executor_for_block = task_executor.clone();
+        let producer_budget = budget.clone();
         task_executor.spawn(async move {
             while let Some(res) = stream.next().await {
                 let sender_clone = sender.clone();
+                let budget = producer_budget.clone();
                 let join_res = executor_for_block
-                    .spawn_blocking(move || sender_clone.send(res))
+                    .spawn_blocking(move || {
+                        // Wait for the consumer to catch up if the batch would exceed the budget
+                        let reserved = match (&budget, &res) {
+                            (Some(budget), Ok(batch)) => {
+                                budget.reserve(batch.get_array_memory_size())
+                            }
+                            _ => 0,
+                        };
+                        sender_clone.send((res, reserved))
+                    })
                     .await;
                 match join_res {
                     Ok(send_res) => match send_res {
@@ -131,18 +227,19 @@ impl FileStream {
                     Err(je) => {
                         error!("Couldn't join spawned task, runtime is likely in bad state: {je}");
                         // Send an error through the channel to be handled by the receiver
-                        let _ = sender.send(Err(crate::Error::JoinFailure(format!(
-                            "Failed to join spawned task: {je}",
-                        ))));
+                        let _ = sender.send((
+                            Err(crate::Error::JoinFailure(format!(
+                                "Failed to join spawned task: {je}",
+                            ))),
+                            0,
+                        ));
                         break;
                     }
                 }
             }
         });
 
-        Ok(Box::new(receiver.into_iter().map(|rbr| {
-            rbr.map(|rb| Box::new(ArrowEngineData::new(rb)) as _)
-        })))
+        Ok(Box::new(BudgetedReceiver { receiver, budget }))
     }
 
     /// Create a new `FileStream` using the given `FileOpener` to scan underlying files
@@ -1,39 +1,189 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use delta_kernel_derive::internal_api;
-use futures::stream::StreamExt;
+use futures::stream::{StreamExt, TryStreamExt};
 use itertools::Itertools;
 use object_store::path::Path;
 use object_store::{DynObjectStore, ObjectStore};
+use tracing::debug;
 use url::Url;
 
+use super::storage::ObjectStoreRouter;
 use super::UrlExt;
 use crate::engine::default::executor::TaskExecutor;
 use crate::{DeltaResult, Error, FileMeta, FileSlice, StorageHandler};
 
 #[derive(Debug)]
 pub struct ObjectStoreStorageHandler<E: TaskExecutor> {
-    inner: Arc<DynObjectStore>,
+    stores: ObjectStoreRouter,
     task_executor: Arc<E>,
     readahead: usize,
+    listing_cache: Option<ListingCache>,
+}
+
+/// Listings of the directories the storage handler listed, see
+/// [`ObjectStoreStorageHandler::with_listing_cache`].
+#[derive(Debug)]
+struct ListingCache {
+    max_age: Duration,
+    listings: Mutex<HashMap<Path, CachedListing>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedListing {
+    /// Every file under the directory, sorted by path
+    files: Vec<(Path, FileMeta)>,
+    /// When the directory was last listed in full
+    listed_at: Instant,
 }
 
 impl<E: TaskExecutor> ObjectStoreStorageHandler<E> {
     #[internal_api]
     pub(crate) fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
         Self {
-            inner: store,
+            stores: ObjectStoreRouter::new(store),
             task_executor,
             readahead: 10,
+            listing_cache: None,
         }
     }
 
+    /// Read the files under `root` from `store` instead of the table's store, see
+    /// [`DefaultParquetHandler::with_storage_root`]. Listing and deleting files always uses the
+    /// table's store.
+    ///
+    /// [`DefaultParquetHandler::with_storage_root`]: super::parquet::DefaultParquetHandler::with_storage_root
+    pub fn with_storage_root(mut self, root: Url, store: Arc<DynObjectStore>) -> Self {
+        self.stores = self.stores.with_root(root, store);
+        self
+    }
+
+    /// Only read the files in the bucket (or container) of `table_root` from the table's store,
+    /// see [`DefaultParquetHandler::with_table_root`].
+    ///
+    /// [`DefaultParquetHandler::with_table_root`]: super::parquet::DefaultParquetHandler::with_table_root
+    pub fn with_table_root(mut self, table_root: Url) -> Self {
+        self.stores = self.stores.with_table_root(table_root);
+        self
+    }
+
     /// Set the maximum number of files to read in parallel.
     pub fn with_readahead(mut self, readahead: usize) -> Self {
         self.readahead = readahead;
         self
     }
+
+    /// Cache the listing of each directory listed, so that frequent polls for the latest version
+    /// of a table don't list its whole `_delta_log` directory on every call.
+    ///
+    /// Object stores have no conditional requests for listings, so a cached listing is instead
+    /// revalidated by only listing the files after the newest commit it contains (which stores
+    /// like S3 serve natively, see [`ObjectStore::list_with_offset`]): new commits are thus
+    /// always seen, as are checkpoints and other files written for the newest version or later.
+    /// Files written for older versions (e.g. a checkpoint of an older version) and deleted files
+    /// are only noticed when the directory is listed in full again, once its listing is older than
+    /// `max_age`. Directories without commits are listed in full on every call.
+    pub fn with_listing_cache(mut self, max_age: Duration) -> Self {
+        self.listing_cache = Some(ListingCache {
+            max_age,
+            listings: Mutex::default(),
+        });
+        self
+    }
+
+    /// Lists every file under `prefix` (after `offset`, if given), sorted by path. The locations
+    /// of the files are relative to `url`.
+    fn list_sorted(
+        &self,
+        prefix: &Path,
+        offset: Option<&Path>,
+        url: &Url,
+    ) -> DeltaResult<Vec<(Path, FileMeta)>> {
+        let store = self.stores.table_store().clone();
+        let (prefix, offset, url) = (prefix.clone(), offset.cloned(), url.clone());
+        let mut files: Vec<(Path, FileMeta)> = self.task_executor.block_on(async move {
+            let stream = match &offset {
+                Some(offset) => store.list_with_offset(Some(&prefix), offset),
+                None => store.list(Some(&prefix)),
+            };
+            stream
+                .map_ok(|meta| {
+                    let mut location = url.clone();
+                    location.set_path(&format!("/{}", meta.location.as_ref()));
+                    let file = FileMeta {
+                        location,
+                        last_modified: meta.last_modified.timestamp_millis(),
+                        size: meta.size,
+                    };
+                    (meta.location, file)
+                })
+                .try_collect::<Vec<_>>()
+                .await
+        })?;
+        files.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(files)
+    }
+
+    /// Lists every file under `prefix` through the listing cache, revalidating or refreshing the
+    /// cached listing of `prefix` as needed.
+    fn list_cached(
+        &self,
+        cache: &ListingCache,
+        prefix: &Path,
+        url: &Url,
+    ) -> DeltaResult<Vec<(Path, FileMeta)>> {
+        let cached = cache
+            .listings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(prefix)
+            .filter(|cached| cached.listed_at.elapsed() < cache.max_age)
+            .cloned();
+        let revalidation = cached.and_then(|cached| {
+            let offset = revalidation_offset(&cached.files)?;
+            Some((cached, offset))
+        });
+        let listing = match revalidation {
+            Some((mut cached, offset)) => {
+                let new_files = self.list_sorted(prefix, Some(&offset), url)?;
+                debug!(
+                    "Revalidated listing of {prefix} after {offset}: {} new files",
+                    new_files.len()
+                );
+                cached.files.retain(|(path, _)| *path <= offset);
+                cached.files.extend(new_files);
+                cached
+            }
+            None => {
+                let listed_at = Instant::now();
+                let files = self.list_sorted(prefix, None, url)?;
+                CachedListing { files, listed_at }
+            }
+        };
+        cache
+            .listings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(prefix.clone(), listing.clone());
+        Ok(listing.files)
+    }
+}
+
+/// The offset to list a cached directory listing after to revalidate it: the path of its newest
+/// commit without the `.json` extension, so that the files of the newest commit's version (e.g. a
+/// checkpoint written after the listing) are listed again.
+fn revalidation_offset(files: &[(Path, FileMeta)]) -> Option<Path> {
+    files.iter().rev().find_map(|(path, _)| {
+        let version = path.filename()?.strip_suffix(".json")?;
+        if version.len() != 20 || !version.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let path = path.as_ref();
+        Path::parse(&path[..path.len() - ".json".len()]).ok()
+    })
 }
 
 impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
@@ -58,7 +208,17 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
             Path::from_iter(parts)
         };
 
-        let store = self.inner.clone();
+        if let Some(cache) = &self.listing_cache {
+            let files = self.list_cached(cache, &prefix, path)?;
+            return Ok(Box::new(
+                files
+                    .into_iter()
+                    .filter(move |(path, _)| *path > offset)
+                    .map(|(_, file)| Ok(file)),
+            ));
+        }
+
+        let store = self.stores.table_store().clone();
 
         // HACK to check if we're using a LocalFileSystem from ObjectStore. We need this because
         // local filesystem doesn't return a sorted list by default. Although the `object_store`
@@ -126,7 +286,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
-        let store = self.inner.clone();
+        let stores = self.stores.clone();
 
         // This channel will become the output iterator.
         // Because there will already be buffering in the stream, we set the
@@ -136,7 +296,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         self.task_executor.spawn(
             futures::stream::iter(files)
                 .map(move |(url, range)| {
-                    let store = store.clone();
+                    let store = stores.store_for(&url).cloned();
                     async move {
                         // Wasn't checking the scheme before calling to_file_path causing the url path to
                         // be eaten in a strange way. Now, if not a file scheme, just blindly convert to a path.
@@ -156,9 +316,9 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
                             // have to annotate type here or rustc can't figure it out
                             Ok::<bytes::Bytes, Error>(reqwest::get(url).await?.bytes().await?)
                         } else if let Some(rng) = range {
-                            Ok(store.get_range(&path, rng).await?)
+                            Ok(store?.get_range(&path, rng).await?)
                         } else {
-                            let result = store.get(&path).await?;
+                            let result = store?.get(&path).await?;
                             Ok(result.bytes().await?)
                         }
                     }
@@ -175,6 +335,35 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
 
         Ok(Box::new(receiver.into_iter()))
     }
+
+    fn delete_files(&self, files: Vec<Url>) -> DeltaResult<()> {
+        let store = self.stores.table_store().clone();
+        let mut deleted = self.task_executor.block_on(async move {
+            let mut deleted = vec![];
+            for url in files {
+                let path = Path::from_url_path(url.path())?;
+                match store.delete(&path).await {
+                    Err(object_store::Error::NotFound { .. }) | Ok(()) => deleted.push(path),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok::<_, Error>(deleted)
+        })?;
+        deleted.sort_unstable();
+        // Deleted files would otherwise stay in cached listings until they are listed in full
+        if let Some(cache) = &self.listing_cache {
+            let mut listings = cache
+                .listings
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for listing in listings.values_mut() {
+                listing
+                    .files
+                    .retain(|(path, _)| deleted.binary_search(path).is_err());
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -300,4 +489,59 @@ mod tests {
         }
         assert_eq!(len, 10, "list_from should have returned 10 files");
     }
+
+    #[tokio::test]
+    async fn test_listing_cache() {
+        let store = Arc::new(InMemory::new());
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let storage = ObjectStoreStorageHandler::new(store.clone(), executor)
+            .with_listing_cache(Duration::from_secs(3600));
+        let log_root = Url::parse("memory:///_delta_log/").unwrap();
+        let list = || -> Vec<String> {
+            storage
+                .list_from(&log_root)
+                .unwrap()
+                .map_ok(|file| file.location.path().to_string())
+                .try_collect()
+                .unwrap()
+        };
+        let path = |version, suffix| format!("/{}", delta_path_for_version(version, suffix));
+        let data = Bytes::from("kernel-data");
+
+        for version in 0..2 {
+            let name = delta_path_for_version(version, "json");
+            store.put(&name, data.clone().into()).await.unwrap();
+        }
+        assert_eq!(list(), [path(0, "json"), path(1, "json")]);
+
+        // revalidating the listing finds new commits and the files of the newest commit's version
+        for (version, suffix) in [(1, "checkpoint.parquet"), (2, "json")] {
+            let name = delta_path_for_version(version, suffix);
+            store.put(&name, data.clone().into()).await.unwrap();
+        }
+        let expected = [
+            path(0, "json"),
+            path(1, "checkpoint.parquet"),
+            path(1, "json"),
+            path(2, "json"),
+        ];
+        assert_eq!(list(), expected);
+
+        // but files of older versions are only found when the directory is listed in full
+        let name = delta_path_for_version(0, "crc");
+        store.put(&name, data.clone().into()).await.unwrap();
+        assert_eq!(list(), expected);
+
+        // deleted files are dropped from the cached listing
+        let deleted = log_root.join(&path(0, "json")).unwrap();
+        storage.delete_files(vec![deleted]).unwrap();
+        assert_eq!(list(), expected[1..]);
+
+        // a listing older than the maximum age is listed in full
+        let storage =
+            ObjectStoreStorageHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+                .with_listing_cache(Duration::ZERO);
+        let files: Vec<_> = storage.list_from(&log_root).unwrap().try_collect().unwrap();
+        assert_eq!(files.len(), 4);
+    }
 }
@@ -1,6 +1,6 @@
 //! Default Json handler implementation
 
-use std::io::BufReader;
+use std::io::{BufReader, Cursor};
 use std::ops::Range;
 use std::sync::{mpsc, Arc};
 use std::task::Poll;
@@ -17,6 +17,7 @@ use tracing::warn;
 use url::Url;
 
 use super::executor::TaskExecutor;
+use super::mmap::mmap_local_file;
 use crate::engine::arrow_conversion::TryFromKernel as _;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::parse_json as arrow_parse_json;
@@ -42,6 +43,8 @@ pub struct DefaultJsonHandler<E: TaskExecutor> {
     /// Limit the number of rows per batch. That is, for batch_size = N, then each RecordBatch
     /// yielded by the stream will have at most N rows.
     batch_size: usize,
+    /// Whether to read local (`file://`) files through memory maps instead of the object store.
+    mmap_local_files: bool,
 }
 
 impl<E: TaskExecutor> DefaultJsonHandler<E> {
@@ -51,6 +54,7 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
             task_executor,
             buffer_size: DEFAULT_BUFFER_SIZE,
             batch_size: DEFAULT_BATCH_SIZE,
+            mmap_local_files: false,
         }
     }
 
@@ -81,6 +85,20 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
         self.batch_size = batch_size;
         self
     }
+
+    /// Read local (`file://`) files through memory maps instead of the object store, which avoids
+    /// copying their content and speeds up reading local tables. Disabled by default.
+    ///
+    /// # Safety
+    ///
+    /// If `mmap_local_files` is true, the local files this handler reads must not be modified or
+    /// truncated while they are mapped, see [`DefaultParquetHandler::with_mmap_local_files`].
+    ///
+    /// [`DefaultParquetHandler::with_mmap_local_files`]: super::parquet::DefaultParquetHandler::with_mmap_local_files
+    pub unsafe fn with_mmap_local_files(mut self, mmap_local_files: bool) -> Self {
+        self.mmap_local_files = mmap_local_files;
+        self
+    }
 }
 
 impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
@@ -104,6 +122,8 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
 
         let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
         let file_opener = JsonOpener::new(self.batch_size, schema.clone(), self.store.clone());
+        // SAFETY: the caller of `Self::with_mmap_local_files` guarantees the files aren't modified
+        let file_opener = unsafe { file_opener.with_mmap_local_files(self.mmap_local_files) };
 
         let (tx, rx) = mpsc::sync_channel(self.buffer_size);
         let files = files.to_vec();
@@ -165,6 +185,7 @@ pub struct JsonOpener {
     batch_size: usize,
     projected_schema: ArrowSchemaRef,
     object_store: Arc<DynObjectStore>,
+    mmap_local_files: bool,
 }
 
 impl JsonOpener {
@@ -178,8 +199,19 @@ impl JsonOpener {
             batch_size,
             projected_schema,
             object_store,
+            mmap_local_files: false,
         }
     }
+
+    /// Read local (`file://`) files through memory maps instead of the object store.
+    ///
+    /// # Safety
+    ///
+    /// See [`DefaultJsonHandler::with_mmap_local_files`].
+    pub unsafe fn with_mmap_local_files(mut self, mmap_local_files: bool) -> Self {
+        self.mmap_local_files = mmap_local_files;
+        self
+    }
 }
 
 impl JsonOpener {
@@ -192,6 +224,15 @@ impl JsonOpener {
         let schema = self.projected_schema.clone();
         let batch_size = self.batch_size;
 
+        if self.mmap_local_files {
+            if let Some(bytes) = mmap_local_file(&file_meta.location)? {
+                let reader = ReaderBuilder::new(schema)
+                    .with_batch_size(batch_size)
+                    .build(Cursor::new(bytes))?;
+                return Ok(futures::stream::iter(reader).map_err(Error::from).boxed());
+            }
+        }
+
         let path = Path::from_url_path(file_meta.location.path())?;
         match store.get(&path).await?.payload {
             GetResultPayload::File(file, _) => {
@@ -562,6 +603,20 @@ mod tests {
         assert_eq!(data.len(), 2);
         assert_eq!(data[0].num_rows(), 2);
         assert_eq!(data[1].num_rows(), 2);
+
+        // read through a memory map
+        // SAFETY: nothing modifies the test's files while they are read
+        let handler = unsafe { handler.with_mmap_local_files(true) };
+        let data: Vec<RecordBatch> = handler
+            .read_json_files(files, get_log_schema().clone(), None)
+            .unwrap()
+            .map_ok(into_record_batch)
+            .try_collect()
+            .unwrap();
+
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].num_rows(), 2);
+        assert_eq!(data[1].num_rows(), 2);
     }
 
     #[tokio::test]
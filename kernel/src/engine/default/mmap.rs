@@ -0,0 +1,53 @@
+//! Memory-mapped reads of local files, which bypass the object store for `file://` URLs.
+
+use std::fs::File;
+
+use bytes::Bytes;
+use memmap2::Mmap;
+use url::Url;
+
+use crate::{DeltaResult, Error};
+
+/// Memory-maps the file at `location` if it is a local (`file://`) file, returning `None`
+/// otherwise. The returned [`Bytes`] keep the mapping alive.
+///
+/// Callers must only map files that are not modified while the returned bytes are alive, as the
+/// callers of the unsafe [`DefaultParquetHandler::with_mmap_local_files`] guarantee.
+///
+/// [`DefaultParquetHandler::with_mmap_local_files`]: super::parquet::DefaultParquetHandler::with_mmap_local_files
+pub(crate) fn mmap_local_file(location: &Url) -> DeltaResult<Option<Bytes>> {
+    if location.scheme() != "file" {
+        return Ok(None);
+    }
+    let path = location
+        .to_file_path()
+        .map_err(|_| Error::generic(format!("Invalid local file URL: {location}")))?;
+    let file = File::open(path)?;
+    // SAFETY: The mapping is only sound as long as no process modifies or truncates the file while
+    // it is mapped. The kernel never rewrites data or log files, and neither do Delta writers, but
+    // nothing prevents another process from doing so. That's why mapping is only enabled through
+    // the unsafe `with_mmap_local_files`, whose callers guarantee the files aren't modified.
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(Some(Bytes::from_owner(mmap)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_mmap_local_file() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/_delta_log/00000000000000000000.json",
+        ))
+        .unwrap();
+        let url = Url::from_file_path(&path).unwrap();
+        let bytes = mmap_local_file(&url).unwrap().unwrap();
+        assert_eq!(bytes.as_ref(), std::fs::read(path).unwrap());
+
+        let url = Url::parse("memory:///foo.json").unwrap();
+        assert!(mmap_local_file(&url).unwrap().is_none());
+    }
+}
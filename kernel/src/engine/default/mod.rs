@@ -8,34 +8,57 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
-use self::storage::parse_url_opts;
+use self::storage::{parse_url_opts, validate_options, ObjectStoreRouter};
 use object_store::DynObjectStore;
 use url::Url;
 
 use self::executor::TaskExecutor;
 use self::filesystem::ObjectStoreStorageHandler;
 use self::json::DefaultJsonHandler;
-use self::parquet::DefaultParquetHandler;
-use super::arrow_conversion::TryFromArrow as _;
+use self::parquet::{DefaultParquetHandler, MissingColumnPolicy};
+use super::arrow_conversion::{TryFromArrow as _, TryIntoArrow as _};
 use super::arrow_data::ArrowEngineData;
 use super::arrow_expression::ArrowEvaluationHandler;
+use super::arrow_utils::ensure_char_varchar_lengths;
+use crate::arrow::datatypes::{FieldRef, Schema as ArrowSchema};
 use crate::schema::Schema;
+use crate::snapshot::SnapshotRef;
 use crate::transaction::WriteContext;
 use crate::{
-    DeltaResult, Engine, EngineData, EvaluationHandler, JsonHandler, ParquetHandler, StorageHandler,
+    DeltaResult, Engine, EngineData, EvaluationHandler, FileMeta, JsonHandler, ParquetHandler,
+    StorageHandler,
 };
 
+mod builder;
+pub mod coalesce;
 pub mod executor;
 pub mod file_stream;
 pub mod filesystem;
 pub mod json;
+pub(crate) mod mmap;
 pub mod parquet;
 pub mod storage;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+
+pub use builder::{Credentials, DefaultEngineBuilder};
 
 #[derive(Debug)]
 pub struct DefaultEngine<E: TaskExecutor> {
     object_store: Arc<DynObjectStore>,
+    table_root: Option<Url>,
+    storage_roots: Vec<(Url, Arc<DynObjectStore>)>,
+    task_executor: Arc<E>,
+    io_concurrency: Option<usize>,
+    memory_budget: Option<usize>,
+    batch_size: Option<usize>,
+    row_group_parallelism: Option<usize>,
+    max_row_group_size: Option<usize>,
+    mmap_local_files: bool,
+    missing_column_policy: MissingColumnPolicy,
+    listing_cache_max_age: Option<Duration>,
     storage: Arc<ObjectStoreStorageHandler<E>>,
     json: Arc<DefaultJsonHandler<E>>,
     parquet: Arc<DefaultParquetHandler<E>>,
@@ -43,12 +66,19 @@ pub struct DefaultEngine<E: TaskExecutor> {
 }
 
 impl<E: TaskExecutor> DefaultEngine<E> {
+    /// Create a [`DefaultEngineBuilder`] for the table at `table_root`, whose async IO tasks run
+    /// on `task_executor`.
+    pub fn builder(table_root: Url, task_executor: Arc<E>) -> DefaultEngineBuilder<E> {
+        DefaultEngineBuilder::new(table_root, task_executor)
+    }
+
     /// Create a new [`DefaultEngine`] instance
     ///
     /// # Parameters
     ///
     /// - `table_root`: The URL of the table within storage.
-    /// - `options`: key/value pairs of options to pass to the object store.
+    /// - `options`: key/value pairs of options to pass to the object store. Unknown or conflicting
+    ///   options are rejected, see [storage::validate_options].
     /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
     pub fn try_new<K, V>(
         table_root: &Url,
@@ -59,9 +89,14 @@ impl<E: TaskExecutor> DefaultEngine<E> {
         K: AsRef<str>,
         V: Into<String>,
     {
+        let options: HashMap<String, String> = options
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.into()))
+            .collect();
+        validate_options(table_root, &options)?;
         // table root is the path of the table in the ObjectStore
         let (object_store, _table_root) = parse_url_opts(table_root, options)?;
-        Ok(Self::new(Arc::new(object_store), task_executor))
+        Ok(Self::new(Arc::new(object_store), task_executor).with_table_root(table_root.clone()))
     }
 
     /// Create a new [`DefaultEngine`] instance
@@ -69,7 +104,8 @@ impl<E: TaskExecutor> DefaultEngine<E> {
     /// # Parameters
     ///
     /// - `object_store`: The object store to use.
-    /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
+    /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor]. It is shared
+    ///   by all handlers of the engine, so its size bounds the threads the engine uses.
     pub fn new(object_store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
         Self {
             storage: Arc::new(ObjectStoreStorageHandler::new(
@@ -82,15 +118,171 @@ impl<E: TaskExecutor> DefaultEngine<E> {
             )),
             parquet: Arc::new(DefaultParquetHandler::new(
                 object_store.clone(),
-                task_executor,
+                task_executor.clone(),
             )),
             object_store,
+            table_root: None,
+            storage_roots: vec![],
+            task_executor,
+            io_concurrency: None,
+            memory_budget: None,
+            batch_size: None,
+            row_group_parallelism: None,
+            max_row_group_size: None,
+            mmap_local_files: false,
+            missing_column_policy: MissingColumnPolicy::default(),
+            listing_cache_max_age: None,
             evaluation: Arc::new(ArrowEvaluationHandler {}),
         }
     }
 
-    pub fn get_object_store_for_url(&self, _url: &Url) -> Option<Arc<DynObjectStore>> {
-        Some(self.object_store.clone())
+    /// Set the maximum number of concurrent IO requests (files or batches read ahead) that any
+    /// single read by the storage, JSON, or parquet handler may have in flight. Together with the
+    /// size of the shared [`TaskExecutor`], this makes the resources used by the engine under
+    /// concurrent scans predictable.
+    pub fn with_io_concurrency(mut self, io_concurrency: usize) -> Self {
+        self.io_concurrency = Some(io_concurrency);
+        self.rebuild_handlers()
+    }
+
+    /// Limit the total size (in bytes) of decoded batches that any single parquet read (e.g. the
+    /// read of a scan file) buffers ahead of its consumer, so that a scan of a wide table can't
+    /// exhaust memory. See [`DefaultParquetHandler::with_memory_budget`].
+    ///
+    /// NOTE: The budget applies to each read separately, not to the engine as a whole. Engines
+    /// that read several files concurrently (e.g. from multiple threads) may buffer up to the
+    /// budget for every read in flight.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self.rebuild_handlers()
+    }
+
+    /// Limit the number of rows per batch read by the JSON and parquet handlers. See
+    /// [`DefaultJsonHandler::with_batch_size`] and [`DefaultParquetHandler::with_batch_size`].
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self.rebuild_handlers()
+    }
+
+    /// Decode the row groups of each parquet file (e.g. of a large checkpoint) with up to
+    /// `parallelism` concurrent tasks. See [`DefaultParquetHandler::with_row_group_parallelism`].
+    pub fn with_row_group_parallelism(mut self, parallelism: usize) -> Self {
+        self.row_group_parallelism = Some(parallelism);
+        self.rebuild_handlers()
+    }
+
+    /// Limit the number of rows per row group of written parquet files, which bounds the memory
+    /// used to write them. See [`DefaultParquetHandler::with_max_row_group_size`].
+    pub fn with_max_row_group_size(mut self, rows: usize) -> Self {
+        self.max_row_group_size = Some(rows);
+        self.rebuild_handlers()
+    }
+
+    /// Read local (`file://`) JSON and parquet files through memory maps instead of the object
+    /// store. Disabled by default.
+    ///
+    /// # Safety
+    ///
+    /// If `mmap_local_files` is true, the local files the engine reads must not be modified or
+    /// truncated while they are mapped, see [`DefaultParquetHandler::with_mmap_local_files`].
+    pub unsafe fn with_mmap_local_files(mut self, mmap_local_files: bool) -> Self {
+        self.mmap_local_files = mmap_local_files;
+        self.rebuild_handlers()
+    }
+
+    /// How to read requested columns that are not nullable but missing from a parquet file. See
+    /// [`DefaultParquetHandler::with_missing_column_policy`].
+    pub fn with_missing_column_policy(mut self, policy: MissingColumnPolicy) -> Self {
+        self.missing_column_policy = policy;
+        self.rebuild_handlers()
+    }
+
+    /// Read the data files and deletion vectors under `root` from `store`, e.g. to read a shallow
+    /// clone whose files are in another bucket or account than the table, with credentials of
+    /// their own. Files are only read from the stores of registered roots, all other files are
+    /// read from the table's store, which fails for files outside the bucket of a table whose
+    /// engine was created from its URL. See [`DefaultParquetHandler::with_storage_root`].
+    pub fn with_storage_root(mut self, root: Url, store: Arc<DynObjectStore>) -> Self {
+        self.storage_roots.push((root, store));
+        self.rebuild_handlers()
+    }
+
+    /// Only read the files in the bucket (or container) of `table_root` from the engine's store,
+    /// see [`DefaultParquetHandler::with_table_root`]. Engines created from a table URL do this.
+    pub(crate) fn with_table_root(mut self, table_root: Url) -> Self {
+        self.table_root = Some(table_root);
+        self.rebuild_handlers()
+    }
+
+    /// Cache the listings of the `_delta_log` directories the engine lists, so that frequent polls
+    /// for the latest snapshot of a table only list the files added since the last poll. Cached
+    /// listings are listed in full again once they are older than `max_age`. See
+    /// [`ObjectStoreStorageHandler::with_listing_cache`].
+    pub fn with_listing_cache(mut self, max_age: Duration) -> Self {
+        self.listing_cache_max_age = Some(max_age);
+        self.rebuild_handlers()
+    }
+
+    fn rebuild_handlers(mut self) -> Self {
+        let (store, executor) = (&self.object_store, &self.task_executor);
+        let mut storage = ObjectStoreStorageHandler::new(store.clone(), executor.clone());
+        let mut json = DefaultJsonHandler::new(store.clone(), executor.clone());
+        let mut parquet = DefaultParquetHandler::new(store.clone(), executor.clone())
+            .with_missing_column_policy(self.missing_column_policy);
+        // SAFETY: the caller of `Self::with_mmap_local_files` guarantees the files aren't modified
+        unsafe {
+            json = json.with_mmap_local_files(self.mmap_local_files);
+            parquet = parquet.with_mmap_local_files(self.mmap_local_files);
+        }
+        if let Some(max_age) = self.listing_cache_max_age {
+            storage = storage.with_listing_cache(max_age);
+        }
+        if let Some(io_concurrency) = self.io_concurrency {
+            storage = storage.with_readahead(io_concurrency);
+            json = json.with_buffer_size(io_concurrency);
+            parquet = parquet.with_readahead(io_concurrency);
+        }
+        if let Some(bytes) = self.memory_budget {
+            parquet = parquet.with_memory_budget(bytes);
+        }
+        if let Some(batch_size) = self.batch_size {
+            json = json.with_batch_size(batch_size);
+            parquet = parquet.with_batch_size(batch_size);
+        }
+        if let Some(parallelism) = self.row_group_parallelism {
+            parquet = parquet.with_row_group_parallelism(parallelism);
+        }
+        if let Some(rows) = self.max_row_group_size {
+            parquet = parquet.with_max_row_group_size(rows);
+        }
+        if let Some(table_root) = &self.table_root {
+            storage = storage.with_table_root(table_root.clone());
+            parquet = parquet.with_table_root(table_root.clone());
+        }
+        for (root, store) in &self.storage_roots {
+            storage = storage.with_storage_root(root.clone(), store.clone());
+            parquet = parquet.with_storage_root(root.clone(), store.clone());
+        }
+        self.storage = Arc::new(storage);
+        self.json = Arc::new(json);
+        self.parquet = Arc::new(parquet);
+        self
+    }
+
+    /// The [`TaskExecutor`] shared by all handlers of this engine.
+    pub fn task_executor(&self) -> &Arc<E> {
+        &self.task_executor
+    }
+
+    pub fn get_object_store_for_url(&self, url: &Url) -> Option<Arc<DynObjectStore>> {
+        let mut stores = ObjectStoreRouter::new(self.object_store.clone());
+        if let Some(table_root) = &self.table_root {
+            stores = stores.with_table_root(table_root.clone());
+        }
+        for (root, store) in &self.storage_roots {
+            stores = stores.with_root(root.clone(), store.clone());
+        }
+        stores.store_for(url).ok().cloned()
     }
 
     pub async fn write_parquet(
@@ -100,6 +292,9 @@ impl<E: TaskExecutor> DefaultEngine<E> {
         partition_values: HashMap<String, String>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
+        if write_context.enforce_char_varchar_lengths() {
+            ensure_char_varchar_lengths(data.record_batch(), write_context.schema())?;
+        }
         let transform = write_context.logical_to_physical();
         let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
         let output_schema = write_context.schema();
@@ -118,6 +313,31 @@ impl<E: TaskExecutor> DefaultEngine<E> {
             )
             .await
     }
+
+    /// Write a single-file checkpoint of `snapshot` and finalize it, which updates the
+    /// `_last_checkpoint` file. Returns the metadata of the checkpoint file.
+    ///
+    /// The actions of the checkpoint are streamed from log replay into the parquet file, so memory
+    /// use is bounded by the row group size (see [`Self::with_max_row_group_size`]) rather than
+    /// growing with the number of files in the table.
+    pub async fn write_checkpoint(&self, snapshot: SnapshotRef) -> DeltaResult<FileMeta> {
+        let writer = snapshot.checkpoint()?;
+        let path = writer.checkpoint_path()?;
+        let trailing_fields: Vec<FieldRef> = match writer.trailing_action_schema() {
+            Some(schema) => {
+                let schema: ArrowSchema = schema.as_ref().try_into_arrow()?;
+                schema.fields().iter().cloned().collect()
+            }
+            None => vec![],
+        };
+        let mut data = writer.checkpoint_data(self)?;
+        let metadata = self
+            .parquet
+            .write_checkpoint(&path, &mut data, &trailing_fields)
+            .await?;
+        writer.finalize(self, &metadata, data)?;
+        Ok(metadata)
+    }
 }
 
 impl<E: TaskExecutor> Engine for DefaultEngine<E> {
@@ -169,7 +389,7 @@ impl UrlExt for Url {
 
 #[cfg(test)]
 mod tests {
-    use super::executor::tokio::TokioBackgroundExecutor;
+    use super::executor::tokio::{TokioBackgroundExecutor, TokioMultiThreadExecutor};
     use super::*;
     use crate::engine::tests::test_arrow_engine;
     use object_store::local::LocalFileSystem;
@@ -183,6 +403,19 @@ mod tests {
         test_arrow_engine(&engine, &url);
     }
 
+    #[test]
+    fn test_default_engine_with_shared_pool() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(tmp.path()).unwrap();
+        let object_store = Arc::new(LocalFileSystem::new());
+        let executor = Arc::new(TokioMultiThreadExecutor::with_worker_threads(2).unwrap());
+        let engine = DefaultEngine::new(object_store, executor.clone())
+            .with_io_concurrency(2)
+            .with_memory_budget(1 << 20);
+        assert!(Arc::ptr_eq(engine.task_executor(), &executor));
+        test_arrow_engine(&engine, &url);
+    }
+
     #[test]
     fn test_pre_signed_url() {
         let url = Url::parse("https://example.com?X-Amz-Signature=foo").unwrap();
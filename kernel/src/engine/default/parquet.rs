@@ -5,28 +5,44 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use crate::arrow::array::builder::{MapBuilder, MapFieldNames, StringBuilder};
-use crate::arrow::array::{BooleanArray, Int64Array, RecordBatch, StringArray, StructArray};
-use crate::arrow::datatypes::{DataType, Field};
+use crate::arrow::array::{
+    new_null_array, BooleanArray, Int64Array, RecordBatch, StringArray, StructArray,
+};
+use crate::arrow::datatypes::{DataType, Field, FieldRef, Schema as ArrowSchema};
 use crate::parquet::arrow::arrow_reader::{
     ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
 };
 use crate::parquet::arrow::arrow_writer::ArrowWriter;
 use crate::parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
-use futures::StreamExt;
+use crate::parquet::arrow::async_writer::{AsyncArrowWriter, ParquetObjectWriter};
+use crate::parquet::arrow::ProjectionMask;
+use crate::parquet::file::properties::WriterProperties;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{SinkExt as _, StreamExt};
+use itertools::Itertools as _;
 use object_store::path::Path;
 use object_store::DynObjectStore;
+use url::Url;
 use uuid::Uuid;
 
+use super::coalesce::coalesce_batches;
 use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
+use super::mmap::mmap_local_file;
+use super::storage::ObjectStoreRouter;
 use super::UrlExt;
+use crate::checkpoint::CheckpointDataIterator;
 use crate::engine::arrow_conversion::TryIntoArrow as _;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::{
-    fixup_parquet_read, generate_mask, get_requested_indices, ordering_needs_row_indexes,
-    RowIndexBuilder,
+    fixup_parquet_read, generate_mask, get_requested_indices_with_policy,
+    ordering_needs_row_indexes, ReorderIndex, RowIndexBuilder,
 };
 use crate::engine::default::executor::TaskExecutor;
-use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
+use crate::engine::parquet_row_group_skipping::{filter_row_groups, ParquetRowGroupSkipping};
+use crate::scan::skipping_trace::SkippingTrace;
 use crate::schema::SchemaRef;
 use crate::transaction::add_files_schema;
 use crate::{
@@ -34,11 +50,23 @@ use crate::{
     PredicateRef,
 };
 
+pub use crate::engine::arrow_utils::{MissingColumnPolicy, MISSING_COLUMN_METADATA_KEY};
+
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
 #[derive(Debug)]
 pub struct DefaultParquetHandler<E: TaskExecutor> {
-    store: Arc<DynObjectStore>,
+    stores: ObjectStoreRouter,
     task_executor: Arc<E>,
     readahead: usize,
+    batch_size: usize,
+    skipping_trace: Option<Arc<SkippingTrace>>,
+    coalesce_target_rows: Option<usize>,
+    memory_budget: Option<usize>,
+    mmap_local_files: bool,
+    row_group_parallelism: usize,
+    missing_column_policy: MissingColumnPolicy,
+    max_row_group_size: Option<usize>,
 }
 
 /// Metadata of a data file (typically a parquet file).
@@ -125,12 +153,39 @@ impl DataFileMetadata {
 impl<E: TaskExecutor> DefaultParquetHandler<E> {
     pub fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
         Self {
-            store,
+            stores: ObjectStoreRouter::new(store),
             task_executor,
             readahead: 10,
+            batch_size: DEFAULT_BATCH_SIZE,
+            skipping_trace: None,
+            coalesce_target_rows: None,
+            memory_budget: None,
+            mmap_local_files: false,
+            row_group_parallelism: 1,
+            missing_column_policy: MissingColumnPolicy::default(),
+            max_row_group_size: None,
         }
     }
 
+    /// Read the files under `root` (e.g. the absolute paths of a shallow clone's source table)
+    /// from `store` instead of the table's store. `store` must serve the whole bucket of `root`,
+    /// like the stores created by [`parse_url_opts`] do. All other files are read from the
+    /// table's store.
+    ///
+    /// [`parse_url_opts`]: super::storage::parse_url_opts
+    pub fn with_storage_root(mut self, root: Url, store: Arc<DynObjectStore>) -> Self {
+        self.stores = self.stores.with_root(root, store);
+        self
+    }
+
+    /// Only read the files in the bucket (or container) of `table_root` from the table's store,
+    /// and fail to read files that are neither there nor under a storage root, instead of reading
+    /// whatever the table's store has at their path.
+    pub fn with_table_root(mut self, table_root: Url) -> Self {
+        self.stores = self.stores.with_table_root(table_root);
+        self
+    }
+
     /// Max number of batches to read ahead while executing [Self::read_parquet_files()].
     ///
     /// Defaults to 10.
@@ -139,6 +194,99 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         self
     }
 
+    /// Limit the number of rows per batch returned by [Self::read_parquet_files()].
+    ///
+    /// Defaults to 1024.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Merge consecutive batches returned by [Self::read_parquet_files()] until they have at least
+    /// `target_rows` rows, see [`coalesce_batches`]. Disabled by default.
+    pub fn with_batch_coalescing(mut self, target_rows: usize) -> Self {
+        self.coalesce_target_rows = Some(target_rows);
+        self
+    }
+
+    /// Limit the total size (in bytes) of the decoded batches that each call to
+    /// [Self::read_parquet_files()] buffers ahead of its consumer. Reading pauses until the consumer
+    /// has received enough batches to make room for the next one. A single batch larger than the
+    /// budget is still returned. Unlimited by default, in which case only [Self::with_readahead()]
+    /// bounds the number of buffered batches.
+    ///
+    /// Each read has its own budget, so concurrent reads may together buffer a multiple of it.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Read local (`file://`) files through memory maps instead of the object store, which avoids
+    /// copying their content and speeds up reading local tables. Disabled by default.
+    ///
+    /// # Safety
+    ///
+    /// If `mmap_local_files` is true, the local files this handler reads must not be modified or
+    /// truncated while they are mapped, i.e. while they are read or batches read from them are
+    /// alive. Delta writers never modify data or log files once written, but a process that
+    /// truncates or rewrites a file in place while it is mapped causes undefined behavior: the
+    /// reader can crash (e.g. with `SIGBUS`) or read corrupted data.
+    pub unsafe fn with_mmap_local_files(mut self, mmap_local_files: bool) -> Self {
+        self.mmap_local_files = mmap_local_files;
+        self
+    }
+
+    /// Decode the row groups of each file read by [Self::read_parquet_files()] with up to
+    /// `parallelism` concurrent tasks on the [`TaskExecutor`], each reading a contiguous range of
+    /// the file's row groups. This speeds up reading large files (e.g. multi-gigabyte checkpoints)
+    /// when the executor runs tasks on multiple threads, like [`TokioMultiThreadExecutor`] does.
+    /// Batches are still returned in file order, and each task buffers up to
+    /// [Self::with_readahead()] batches ahead of its consumer. Files read through memory maps or
+    /// presigned URLs are always decoded sequentially.
+    ///
+    /// Defaults to 1, i.e. row groups are decoded sequentially.
+    ///
+    /// [`TokioMultiThreadExecutor`]: super::executor::tokio::TokioMultiThreadExecutor
+    pub fn with_row_group_parallelism(mut self, parallelism: usize) -> Self {
+        self.row_group_parallelism = parallelism;
+        self
+    }
+
+    /// How to read requested columns that are not nullable but missing from a file, see
+    /// [`MissingColumnPolicy`].
+    ///
+    /// Defaults to [`MissingColumnPolicy::Error`].
+    pub fn with_missing_column_policy(mut self, policy: MissingColumnPolicy) -> Self {
+        self.missing_column_policy = policy;
+        self
+    }
+
+    /// Limit the number of rows per row group of the parquet files written by this handler. The
+    /// writer buffers a whole row group before writing it, so this bounds the memory used to
+    /// write large files, see [Self::write_checkpoint()].
+    ///
+    /// Defaults to the parquet writer's default (1024 * 1024 rows).
+    pub fn with_max_row_group_size(mut self, rows: usize) -> Self {
+        self.max_row_group_size = Some(rows);
+        self
+    }
+
+    fn writer_properties(&self) -> Option<WriterProperties> {
+        let rows = self.max_row_group_size?;
+        Some(
+            WriterProperties::builder()
+                .set_max_row_group_size(rows)
+                .build(),
+        )
+    }
+
+    /// Record the outcome of row group skipping for every file read by
+    /// [Self::read_parquet_files()] in `trace`. Meant for debugging, see [`SkippingTrace`].
+    pub fn with_skipping_trace(mut self, trace: Arc<SkippingTrace>) -> Self {
+        self.skipping_trace = Some(trace);
+        self
+    }
+
     // Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
     // metadata (where `<uuid>` is a generated UUIDv4).
     //
@@ -154,7 +302,8 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         let num_records = record_batch.num_rows();
 
         let mut buffer = vec![];
-        let mut writer = ArrowWriter::try_new(&mut buffer, record_batch.schema(), None)?;
+        let mut writer =
+            ArrowWriter::try_new(&mut buffer, record_batch.schema(), self.writer_properties())?;
         writer.write(record_batch)?;
         writer.close()?; // writer must be closed to write footer
 
@@ -171,11 +320,12 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         }
         let path = path.join(&name)?;
 
-        self.store
+        let store = self.stores.table_store();
+        store
             .put(&Path::from_url_path(path.path())?, buffer.into())
             .await?;
 
-        let metadata = self.store.head(&Path::from_url_path(path.path())?).await?;
+        let metadata = store.head(&Path::from_url_path(path.path())?).await?;
         let modification_time = metadata.last_modified.timestamp_millis();
         if size != metadata.size {
             return Err(Error::generic(format!(
@@ -203,6 +353,68 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         let parquet_metadata = self.write_parquet(path, data).await?;
         parquet_metadata.as_record_batch(&partition_values, data_change)
     }
+
+    /// Write the checkpoint `data` to the parquet file at `path`, and return the metadata of the
+    /// written file. Batches are written as log replay produces them, and the file is uploaded in
+    /// parts as row groups fill up, so memory use is bounded by the row group size (see
+    /// [Self::with_max_row_group_size()]) rather than by the size of the checkpoint.
+    ///
+    /// The file has the columns of the first batch, followed by `trailing_fields`: the columns
+    /// that only later batches have, like the `checkpointMetadata` action of V2 checkpoints.
+    /// Columns missing from a batch are written as nulls.
+    pub(crate) async fn write_checkpoint(
+        &self,
+        path: &Url,
+        data: &mut CheckpointDataIterator,
+        trailing_fields: &[FieldRef],
+    ) -> DeltaResult<FileMeta> {
+        let store = self.stores.store_for(path)?.clone();
+        let location = Path::from_url_path(path.path())?;
+        let mut batches = data.map(|data| data?.try_into_selected_record_batch());
+        let first = batches
+            .next()
+            .ok_or_else(|| Error::checkpoint_write("The checkpoint has no actions"))??;
+        let fields = first.schema().fields().iter().chain(trailing_fields);
+        let schema = Arc::new(ArrowSchema::new(fields.cloned().collect_vec()));
+        let object_writer = ParquetObjectWriter::new(store.clone(), location.clone());
+        let mut writer =
+            AsyncArrowWriter::try_new(object_writer, schema.clone(), self.writer_properties())?;
+        for batch in std::iter::once(Ok(first)).chain(batches) {
+            writer.write(&align_to_schema(batch?, &schema)?).await?;
+        }
+        writer.close().await?;
+
+        let metadata = store.head(&location).await?;
+        Ok(FileMeta::new(
+            path.clone(),
+            metadata.last_modified.timestamp_millis(),
+            metadata.size,
+        ))
+    }
+}
+
+/// Arranges the columns of `batch` by the fields of `schema`, with nulls for missing columns.
+fn align_to_schema(batch: RecordBatch, schema: &Arc<ArrowSchema>) -> DeltaResult<RecordBatch> {
+    if let Some(field) = batch
+        .schema()
+        .fields()
+        .iter()
+        .find(|field| schema.field_with_name(field.name()).is_err())
+    {
+        return Err(Error::checkpoint_write(format!(
+            "Checkpoint batch has unexpected column {}",
+            field.name()
+        )));
+    }
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(column) => column.clone(),
+            None => new_null_array(field.data_type(), batch.num_rows()),
+        })
+        .collect();
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
 }
 
 impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
@@ -226,28 +438,62 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
         // SAFETY: we did is_empty check above, this is ok.
         let file_opener: Box<dyn FileOpener> = if files[0].location.is_presigned() {
             Box::new(PresignedUrlOpener::new(
-                1024,
+                self.batch_size,
                 physical_schema.clone(),
                 predicate,
+                self.skipping_trace.clone(),
+                self.missing_column_policy,
             ))
         } else {
+            let parallel_decode = (self.row_group_parallelism > 1).then(|| {
+                let task_executor = self.task_executor.clone();
+                ParallelDecode {
+                    parallelism: self.row_group_parallelism,
+                    buffer: self.readahead,
+                    spawn: Arc::new(move |task| task_executor.spawn(task)),
+                }
+            });
             Box::new(ParquetOpener::new(
-                1024,
+                self.batch_size,
                 physical_schema.clone(),
                 predicate,
-                self.store.clone(),
+                self.stores.clone(),
+                self.skipping_trace.clone(),
+                self.mmap_local_files,
+                parallel_decode,
+                self.missing_column_policy,
             ))
         };
-        FileStream::new_async_read_iterator(
+        let batches = FileStream::new_async_read_iterator(
             self.task_executor.clone(),
             Arc::new(physical_schema.as_ref().try_into_arrow()?),
             file_opener,
             files,
             self.readahead,
-        )
+            self.memory_budget,
+        )?;
+        let Some(target_rows) = self.coalesce_target_rows else {
+            return Ok(batches);
+        };
+        let batches =
+            batches.map(|data| ArrowEngineData::try_from_engine_data(data?).map(RecordBatch::from));
+        Ok(Box::new(coalesce_batches(batches, target_rows).map_ok(
+            |batch| -> Box<dyn EngineData> { Box::new(ArrowEngineData::new(batch)) },
+        )))
     }
 }
 
+/// How [`ParquetOpener`] decodes the row groups of a file concurrently, see
+/// [`DefaultParquetHandler::with_row_group_parallelism`].
+#[derive(Clone)]
+struct ParallelDecode {
+    parallelism: usize,
+    /// The number of batches each task buffers ahead of the consumer
+    buffer: usize,
+    /// Runs a task on the handler's [`TaskExecutor`]
+    spawn: Arc<dyn Fn(BoxFuture<'static, ()>) + Send + Sync>,
+}
+
 /// Implements [`FileOpener`] for a parquet file
 struct ParquetOpener {
     // projection: Arc<[usize]>,
@@ -255,22 +501,35 @@ struct ParquetOpener {
     table_schema: SchemaRef,
     predicate: Option<PredicateRef>,
     limit: Option<usize>,
-    store: Arc<DynObjectStore>,
+    stores: ObjectStoreRouter,
+    skipping_trace: Option<Arc<SkippingTrace>>,
+    mmap_local_files: bool,
+    parallel_decode: Option<ParallelDecode>,
+    missing_column_policy: MissingColumnPolicy,
 }
 
 impl ParquetOpener {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         batch_size: usize,
         table_schema: SchemaRef,
         predicate: Option<PredicateRef>,
-        store: Arc<DynObjectStore>,
+        stores: ObjectStoreRouter,
+        skipping_trace: Option<Arc<SkippingTrace>>,
+        mmap_local_files: bool,
+        parallel_decode: Option<ParallelDecode>,
+        missing_column_policy: MissingColumnPolicy,
     ) -> Self {
         Self {
             batch_size,
             table_schema,
             predicate,
             limit: None,
-            store,
+            stores,
+            skipping_trace,
+            mmap_local_files,
+            parallel_decode,
+            missing_column_policy,
         }
     }
 }
@@ -278,16 +537,34 @@ impl ParquetOpener {
 impl FileOpener for ParquetOpener {
     fn open(&self, file_meta: FileMeta, _range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture> {
         let path = Path::from_url_path(file_meta.location.path())?;
-        let store = self.store.clone();
+        let store = self.stores.store_for(&file_meta.location)?.clone();
 
         let batch_size = self.batch_size;
         // let projection = self.projection.clone();
         let table_schema = self.table_schema.clone();
         let predicate = self.predicate.clone();
         let limit = self.limit;
+        let skipping_trace = self.skipping_trace.clone();
+        let mmap_local_files = self.mmap_local_files;
+        let parallel_decode = self.parallel_decode.clone();
+        let missing_column_policy = self.missing_column_policy;
 
         Ok(Box::pin(async move {
-            let mut reader = {
+            if mmap_local_files {
+                if let Some(reader) = mmap_local_file(&file_meta.location)? {
+                    return read_parquet_bytes(
+                        reader,
+                        &table_schema,
+                        predicate.as_ref(),
+                        limit,
+                        batch_size,
+                        skipping_trace.as_deref(),
+                        &file_meta.location,
+                        missing_column_policy,
+                    );
+                }
+            }
+            let file_size = {
                 use object_store::ObjectStoreScheme;
                 // HACK: unfortunately, `ParquetObjectReader` under the hood does a suffix range
                 // request which isn't supported by Azure. For now we just detect if the URL is
@@ -303,17 +580,55 @@ impl FileOpener for ParquetOpener {
                 {
                     // also note doing HEAD then actual GET isn't atomic, and leaves us vulnerable
                     // to file changing between the two calls.
-                    let meta = store.head(&path).await?;
-                    ParquetObjectReader::new(store, path).with_file_size(meta.size)
+                    Some(store.head(&path).await?.size)
                 } else {
-                    ParquetObjectReader::new(store, path)
+                    None
+                }
+            };
+            let make_reader = || {
+                let reader = ParquetObjectReader::new(store.clone(), path.clone());
+                match file_size {
+                    Some(file_size) => reader.with_file_size(file_size),
+                    None => reader,
                 }
             };
+            let mut reader = make_reader();
 
             let metadata = ArrowReaderMetadata::load_async(&mut reader, Default::default()).await?;
             let parquet_schema = metadata.schema();
-            let (indices, requested_ordering) =
-                get_requested_indices(&table_schema, parquet_schema)?;
+            let (indices, requested_ordering) = get_requested_indices_with_policy(
+                &table_schema,
+                parquet_schema,
+                missing_column_policy,
+            )?;
+            if let Some(parallel_decode) = parallel_decode
+                .filter(|_| limit.is_none() && metadata.metadata().num_row_groups() > 1)
+            {
+                let row_groups = match predicate {
+                    Some(ref predicate) => {
+                        let trace = skipping_trace
+                            .as_deref()
+                            .map(|trace| (trace, &file_meta.location));
+                        filter_row_groups(metadata.metadata(), predicate, trace)
+                    }
+                    None => (0..metadata.metadata().num_row_groups()).collect(),
+                };
+                let mask = generate_mask(
+                    &table_schema,
+                    parquet_schema,
+                    metadata.metadata().file_metadata().schema_descr(),
+                    &indices,
+                );
+                return decode_row_groups_in_parallel(
+                    make_reader,
+                    metadata,
+                    row_groups,
+                    mask,
+                    requested_ordering,
+                    batch_size,
+                    &parallel_decode,
+                );
+            }
             let options = ArrowReaderOptions::new(); //.with_page_index(enable_page_index);
             let mut builder =
                 ParquetRecordBatchStreamBuilder::new_with_options(reader, options).await?;
@@ -332,7 +647,10 @@ impl FileOpener for ParquetOpener {
 
             // Filter row groups and row indexes if a predicate is provided
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
+                let trace = skipping_trace
+                    .as_deref()
+                    .map(|trace| (trace, &file_meta.location));
+                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut(), trace);
             }
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
@@ -349,6 +667,60 @@ impl FileOpener for ParquetOpener {
     }
 }
 
+/// Decodes `row_groups` of a parquet file in up to `parallel_decode.parallelism` contiguous ranges,
+/// each by its own task, and returns their batches in file order. Every task reads through its own
+/// reader from `make_reader`.
+fn decode_row_groups_in_parallel(
+    make_reader: impl Fn() -> ParquetObjectReader,
+    metadata: ArrowReaderMetadata,
+    row_groups: Vec<usize>,
+    mask: Option<ProjectionMask>,
+    requested_ordering: Vec<ReorderIndex>,
+    batch_size: usize,
+    parallel_decode: &ParallelDecode,
+) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
+    let requested_ordering = Arc::new(requested_ordering);
+    let needs_row_indexes = ordering_needs_row_indexes(&requested_ordering);
+    let chunk_size = row_groups
+        .len()
+        .div_ceil(parallel_decode.parallelism)
+        .max(1);
+    let mut receivers = vec![];
+    for chunk in row_groups.chunks(chunk_size) {
+        let mut builder =
+            ParquetRecordBatchStreamBuilder::new_with_metadata(make_reader(), metadata.clone())
+                .with_row_groups(chunk.to_vec())
+                .with_batch_size(batch_size);
+        if let Some(mask) = &mask {
+            builder = builder.with_projection(mask.clone());
+        }
+        let mut row_indexes = needs_row_indexes.then(|| {
+            let mut row_indexes = RowIndexBuilder::new(metadata.metadata().row_groups());
+            row_indexes.select_row_groups(chunk);
+            row_indexes.into_iter()
+        });
+        let requested_ordering = requested_ordering.clone();
+        let mut batches = builder
+            .build()?
+            .map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut()))
+            .boxed();
+
+        let (mut sender, receiver) =
+            mpsc::channel::<DeltaResult<RecordBatch>>(parallel_decode.buffer);
+        (parallel_decode.spawn)(Box::pin(async move {
+            while let Some(batch) = batches.next().await {
+                let failed = batch.is_err();
+                // stop once the consumer is gone, or after forwarding the first error
+                if sender.send(batch).await.is_err() || failed {
+                    break;
+                }
+            }
+        }));
+        receivers.push(receiver);
+    }
+    Ok(futures::stream::iter(receivers).flatten().boxed())
+}
+
 /// Implements [`FileOpener`] for a opening a parquet file from a presigned URL
 struct PresignedUrlOpener {
     batch_size: usize,
@@ -356,6 +728,8 @@ struct PresignedUrlOpener {
     limit: Option<usize>,
     table_schema: SchemaRef,
     client: reqwest::Client,
+    skipping_trace: Option<Arc<SkippingTrace>>,
+    missing_column_policy: MissingColumnPolicy,
 }
 
 impl PresignedUrlOpener {
@@ -363,6 +737,8 @@ impl PresignedUrlOpener {
         batch_size: usize,
         schema: SchemaRef,
         predicate: Option<PredicateRef>,
+        skipping_trace: Option<Arc<SkippingTrace>>,
+        missing_column_policy: MissingColumnPolicy,
     ) -> Self {
         Self {
             batch_size,
@@ -370,6 +746,8 @@ impl PresignedUrlOpener {
             predicate,
             limit: None,
             client: reqwest::Client::new(),
+            skipping_trace,
+            missing_column_policy,
         }
     }
 }
@@ -381,49 +759,75 @@ impl FileOpener for PresignedUrlOpener {
         let predicate = self.predicate.clone();
         let limit = self.limit;
         let client = self.client.clone(); // uses Arc internally according to reqwest docs
+        let skipping_trace = self.skipping_trace.clone();
+        let missing_column_policy = self.missing_column_policy;
 
         Ok(Box::pin(async move {
             // fetch the file from the interweb
-            let reader = client.get(file_meta.location).send().await?.bytes().await?;
-            let metadata = ArrowReaderMetadata::load(&reader, Default::default())?;
-            let parquet_schema = metadata.schema();
-            let (indices, requested_ordering) =
-                get_requested_indices(&table_schema, parquet_schema)?;
-
-            let options = ArrowReaderOptions::new();
-            let mut builder =
-                ParquetRecordBatchReaderBuilder::try_new_with_options(reader, options)?;
-            if let Some(mask) = generate_mask(
+            let location = file_meta.location;
+            let reader = client.get(location.clone()).send().await?.bytes().await?;
+            read_parquet_bytes(
+                reader,
                 &table_schema,
-                parquet_schema,
-                builder.parquet_schema(),
-                &indices,
-            ) {
-                builder = builder.with_projection(mask)
-            }
-
-            // Only create RowIndexBuilder if row indexes are actually needed
-            let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
-                .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
+                predicate.as_ref(),
+                limit,
+                batch_size,
+                skipping_trace.as_deref(),
+                &location,
+                missing_column_policy,
+            )
+        }))
+    }
+}
 
-            // Filter row groups and row indexes if a predicate is provided
-            if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
-            }
-            if let Some(limit) = limit {
-                builder = builder.with_limit(limit)
-            }
+/// Reads a parquet file whose content is already in memory (e.g. downloaded or memory-mapped).
+#[allow(clippy::too_many_arguments)]
+fn read_parquet_bytes(
+    reader: Bytes,
+    table_schema: &SchemaRef,
+    predicate: Option<&PredicateRef>,
+    limit: Option<usize>,
+    batch_size: usize,
+    skipping_trace: Option<&SkippingTrace>,
+    location: &Url,
+    missing_column_policy: MissingColumnPolicy,
+) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
+    let metadata = ArrowReaderMetadata::load(&reader, Default::default())?;
+    let parquet_schema = metadata.schema();
+    let (indices, requested_ordering) =
+        get_requested_indices_with_policy(table_schema, parquet_schema, missing_column_policy)?;
+
+    let options = ArrowReaderOptions::new();
+    let mut builder = ParquetRecordBatchReaderBuilder::try_new_with_options(reader, options)?;
+    if let Some(mask) = generate_mask(
+        table_schema,
+        parquet_schema,
+        builder.parquet_schema(),
+        &indices,
+    ) {
+        builder = builder.with_projection(mask)
+    }
 
-            let reader = builder.with_batch_size(batch_size).build()?;
+    // Only create RowIndexBuilder if row indexes are actually needed
+    let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
+        .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
 
-            let mut row_indexes = row_indexes.map(|rb| rb.into_iter());
-            let stream = futures::stream::iter(reader);
-            let stream = stream.map(move |rbr| {
-                fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
-            });
-            Ok(stream.boxed())
-        }))
+    // Filter row groups and row indexes if a predicate is provided
+    if let Some(predicate) = predicate {
+        let trace = skipping_trace.map(|trace| (trace, location));
+        builder = builder.with_row_group_filter(predicate, row_indexes.as_mut(), trace);
+    }
+    if let Some(limit) = limit {
+        builder = builder.with_limit(limit)
     }
+
+    let reader = builder.with_batch_size(batch_size).build()?;
+
+    let mut row_indexes = row_indexes.map(|rb| rb.into_iter());
+    let stream = futures::stream::iter(reader);
+    let stream =
+        stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut()));
+    Ok(stream.boxed())
 }
 
 #[cfg(test)]
@@ -479,13 +883,10 @@ mod tests {
             size: meta.size,
         }];
 
+        let schema = Arc::new(physical_schema.try_into_kernel().unwrap());
         let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
         let data: Vec<RecordBatch> = handler
-            .read_parquet_files(
-                files,
-                Arc::new(physical_schema.try_into_kernel().unwrap()),
-                None,
-            )
+            .read_parquet_files(files, schema.clone(), None)
             .unwrap()
             .map(into_record_batch)
             .try_collect()
@@ -493,6 +894,155 @@ mod tests {
 
         assert_eq!(data.len(), 1);
         assert_eq!(data[0].num_rows(), 10);
+
+        // read through a memory map
+        // SAFETY: nothing modifies the test's files while they are read
+        let handler = unsafe { handler.with_mmap_local_files(true) };
+        let mapped: Vec<RecordBatch> = handler
+            .read_parquet_files(files, schema, None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+        assert_eq!(mapped, data);
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_files_with_batch_coalescing() {
+        let store = Arc::new(LocalFileSystem::new());
+
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
+        )).unwrap();
+        let url = url::Url::from_file_path(path).unwrap();
+        let location = Path::from_url_path(url.path()).unwrap();
+        let meta = store.head(&location).await.unwrap();
+        let file = FileMeta {
+            location: url.clone(),
+            last_modified: meta.last_modified.timestamp(),
+            size: meta.size,
+        };
+        let files = &[file.clone(), file.clone(), file];
+
+        let reader = ParquetObjectReader::new(store.clone(), location);
+        let physical_schema = ParquetRecordBatchStreamBuilder::new(reader)
+            .await
+            .unwrap()
+            .schema()
+            .clone();
+        let schema = Arc::new(physical_schema.try_into_kernel().unwrap());
+
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+            .with_batch_coalescing(15);
+        let data: Vec<RecordBatch> = handler
+            .read_parquet_files(files, schema, None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+
+        // Each file has a single batch of 10 rows
+        let num_rows: Vec<_> = data.iter().map(|batch| batch.num_rows()).collect();
+        assert_eq!(num_rows, vec![20, 10]);
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_files_with_memory_budget() {
+        let store = Arc::new(LocalFileSystem::new());
+
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
+        )).unwrap();
+        let url = url::Url::from_file_path(path).unwrap();
+        let location = Path::from_url_path(url.path()).unwrap();
+        let meta = store.head(&location).await.unwrap();
+        let file = FileMeta {
+            location: url.clone(),
+            last_modified: meta.last_modified.timestamp(),
+            size: meta.size,
+        };
+        let files = &[file.clone(), file.clone(), file];
+
+        let reader = ParquetObjectReader::new(store.clone(), location);
+        let physical_schema = ParquetRecordBatchStreamBuilder::new(reader)
+            .await
+            .unwrap()
+            .schema()
+            .clone();
+        let schema = Arc::new(physical_schema.try_into_kernel().unwrap());
+
+        // Every batch exceeds the budget on its own, so they are read one at a time
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+            .with_memory_budget(1);
+        let data: Vec<RecordBatch> = handler
+            .read_parquet_files(files, schema, None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+        let num_rows: Vec<_> = data.iter().map(|batch| batch.num_rows()).collect();
+        assert_eq!(num_rows, vec![10, 10, 10]);
+    }
+
+    #[test]
+    fn test_read_parquet_files_with_row_group_parallelism() {
+        use crate::arrow::array::Int64Array;
+        use crate::arrow::datatypes::Schema as ArrowSchema;
+        use crate::engine::default::executor::tokio::TokioMultiThreadExecutor;
+        use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+        use crate::parquet::file::properties::WriterProperties;
+        use crate::schema::{DataType as KernelDataType, StructField, StructType};
+
+        // write 100 rows in 10 row groups
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.parquet");
+        let ids = Int64Array::from_iter_values(0..100);
+        let values = Int64Array::from_iter_values((0..100).map(|i| i * 10));
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![
+                Field::new("id", DataType::Int64, false),
+                Field::new("value", DataType::Int64, false),
+            ])),
+            vec![Arc::new(ids), Arc::new(values)],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .build();
+        let file = std::fs::File::create(&path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let files = &[FileMeta {
+            location: Url::from_file_path(&path).unwrap(),
+            last_modified: 0,
+            size: std::fs::metadata(&path).unwrap().len(),
+        }];
+
+        // read the columns in reverse order, skipping the first three row groups
+        let schema = Arc::new(StructType::new_unchecked([
+            StructField::not_null("value", KernelDataType::LONG),
+            StructField::not_null("id", KernelDataType::LONG),
+        ]));
+        let predicate = Arc::new(Pred::ge(column_expr!("id"), Expr::literal(30i64)));
+        let executor = Arc::new(TokioMultiThreadExecutor::with_worker_threads(4).unwrap());
+        let read = |parallelism| -> Vec<RecordBatch> {
+            let handler =
+                DefaultParquetHandler::new(Arc::new(LocalFileSystem::new()), executor.clone())
+                    .with_batch_size(4)
+                    .with_row_group_parallelism(parallelism);
+            handler
+                .read_parquet_files(files, schema.clone(), Some(predicate.clone()))
+                .unwrap()
+                .map(into_record_batch)
+                .try_collect()
+                .unwrap()
+        };
+        let sequential = read(1);
+        let parallel = read(4);
+        assert_eq!(parallel, sequential);
+        let num_rows: usize = parallel.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(num_rows, 70);
     }
 
     #[test]
@@ -623,6 +1173,63 @@ mod tests {
         assert_eq!(data[0].num_rows(), 3);
     }
 
+    #[tokio::test]
+    async fn test_read_from_storage_root() {
+        let table_store = Arc::new(InMemory::new());
+        let source_store = Arc::new(InMemory::new());
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+
+        // write a file to the store of another bucket, e.g. the source table of a shallow clone
+        let data = Box::new(ArrowEngineData::new(
+            RecordBatch::try_from_iter(vec![(
+                "a",
+                Arc::new(Int64Array::from(vec![1, 2, 3])) as Arc<dyn Array>,
+            )])
+            .unwrap(),
+        ));
+        let write_metadata = DefaultParquetHandler::new(source_store.clone(), executor.clone())
+            .write_parquet(&Url::parse("memory:///data/").unwrap(), data)
+            .await
+            .unwrap();
+        let path = Path::from_url_path(write_metadata.file_meta.location.path()).unwrap();
+        let reader = ParquetObjectReader::new(source_store.clone(), path);
+        let physical_schema: SchemaRef = Arc::new(
+            ParquetRecordBatchStreamBuilder::new(reader)
+                .await
+                .unwrap()
+                .schema()
+                .as_ref()
+                .try_into_kernel()
+                .unwrap(),
+        );
+        let source_root = Url::parse("memory://source/").unwrap();
+        let file = FileMeta {
+            location: source_root
+                .join(write_metadata.file_meta.location.path())
+                .unwrap(),
+            ..write_metadata.file_meta
+        };
+
+        // without the storage root, the file is read from the table's store
+        let handler = DefaultParquetHandler::new(table_store.clone(), executor.clone());
+        let result: DeltaResult<Vec<RecordBatch>> = handler
+            .read_parquet_files(slice::from_ref(&file), physical_schema.clone(), None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect();
+        assert!(result.is_err());
+
+        let handler = handler.with_storage_root(source_root, source_store);
+        let data: Vec<RecordBatch> = handler
+            .read_parquet_files(slice::from_ref(&file), physical_schema, None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].num_rows(), 3);
+    }
+
     #[tokio::test]
     async fn test_disallow_non_trailing_slash() {
         let store = Arc::new(InMemory::new());
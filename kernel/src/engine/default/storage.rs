@@ -1,9 +1,16 @@
+use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
+use object_store::azure::{AzureConfigKey, MicrosoftAzureBuilder};
+use object_store::gcp::{GoogleCloudStorageBuilder, GoogleConfigKey};
+use object_store::http::HttpBuilder;
 use object_store::parse_url_opts as parse_url_opts_object_store;
 use object_store::path::Path;
-use object_store::{Error, ObjectStore};
+use object_store::{
+    ClientConfigKey, DynObjectStore, Error, ObjectStore, ObjectStoreScheme, RetryConfig,
+};
+use tracing::warn;
 use url::Url;
 
-use crate::Error as DeltaError;
+use crate::{DeltaResult, Error as DeltaError};
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock, RwLock};
 
@@ -60,11 +67,422 @@ where
     parse_url_opts_object_store(url, options)
 }
 
+/// Creates a builder of type `$builder` for `$url`, configured with the given `$options`. Like
+/// [object_store::parse_url_opts], this ignores unknown options.
+macro_rules! configured_builder {
+    ($builder:ty, $url:expr, $options:expr) => {
+        $options.into_iter().fold(
+            <$builder>::new().with_url($url.as_str()),
+            |builder, (key, value)| match key.parse() {
+                Ok(key) => builder.with_config(key, value),
+                Err(_) => builder,
+            },
+        )
+    };
+}
+
+/// Like [parse_url_opts], but the created [ObjectStore] retries failed requests according to
+/// `retry`. Local and in-memory stores do not retry, and neither do stores created by handlers
+/// registered via [insert_url_handler], so those are rejected.
+pub(crate) fn parse_url_opts_with_retry(
+    url: &Url,
+    options: HashMap<String, String>,
+    retry: RetryConfig,
+) -> DeltaResult<Arc<DynObjectStore>> {
+    let has_handler = URL_REGISTRY
+        .read()
+        .is_ok_and(|handlers| handlers.contains_key(url.scheme()));
+    let scheme = match ObjectStoreScheme::parse(url) {
+        Ok((scheme, _)) if !has_handler => scheme,
+        _ => {
+            return Err(DeltaError::unsupported(format!(
+                "Retry policies are not supported for {url}"
+            )))
+        }
+    };
+    let store: Arc<DynObjectStore> = match scheme {
+        ObjectStoreScheme::AmazonS3 => Arc::new(
+            configured_builder!(AmazonS3Builder, url, options)
+                .with_retry(retry)
+                .build()?,
+        ),
+        ObjectStoreScheme::MicrosoftAzure => Arc::new(
+            configured_builder!(MicrosoftAzureBuilder, url, options)
+                .with_retry(retry)
+                .build()?,
+        ),
+        ObjectStoreScheme::GoogleCloudStorage => Arc::new(
+            configured_builder!(GoogleCloudStorageBuilder, url, options)
+                .with_retry(retry)
+                .build()?,
+        ),
+        ObjectStoreScheme::Http => Arc::new(
+            configured_builder!(HttpBuilder, url, options)
+                .with_retry(retry)
+                .build()?,
+        ),
+        _ => {
+            return Err(DeltaError::unsupported(format!(
+                "Retry policies are not supported for {url}"
+            )))
+        }
+    };
+    Ok(store)
+}
+
+/// The options accepted by one kind of [ObjectStore], see [validate_options]. Options are referred to
+/// by any of their names (e.g. `region` or `aws_region`), and compared by their canonical name.
+struct StoreOptions {
+    /// Returns the canonical name of an option, or `None` if the store does not accept it
+    canonical_name: fn(&str) -> Option<String>,
+    /// Names to suggest in place of misspelled options
+    names: &'static [&'static str],
+    /// Pairs of options that must not be enabled together
+    exclusive: &'static [(&'static str, &'static str)],
+    /// Pairs of options where enabling the first requires enabling the second
+    requires: &'static [(&'static str, &'static str)],
+}
+
+const CLIENT_NAMES: &[&str] = &[
+    "allow_http",
+    "allow_invalid_certificates",
+    "connect_timeout",
+    "default_content_type",
+    "http1_only",
+    "http2_only",
+    "http2_keep_alive_interval",
+    "http2_keep_alive_timeout",
+    "http2_keep_alive_while_idle",
+    "http2_max_frame_size",
+    "pool_idle_timeout",
+    "pool_max_idle_per_host",
+    "proxy_url",
+    "proxy_ca_certificate",
+    "proxy_excludes",
+    "timeout",
+    "user_agent",
+];
+
+const CLIENT_EXCLUSIVE: &[(&str, &str)] = &[("http1_only", "http2_only")];
+
+static S3_OPTIONS: StoreOptions = StoreOptions {
+    canonical_name: |name| Some(name.parse::<AmazonS3ConfigKey>().ok()?.as_ref().to_string()),
+    names: &[
+        "access_key_id",
+        "secret_access_key",
+        "session_token",
+        "region",
+        "bucket",
+        "endpoint",
+        "imdsv1_fallback",
+        "virtual_hosted_style_request",
+        "checksum_algorithm",
+        "metadata_endpoint",
+        "copy_if_not_exists",
+        "conditional_put",
+        "skip_signature",
+        "disable_tagging",
+        "s3_express",
+        "unsigned_payload",
+        "request_payer",
+        "aws_access_key_id",
+        "aws_secret_access_key",
+        "aws_session_token",
+        "aws_region",
+        "aws_default_region",
+        "aws_bucket",
+        "aws_endpoint",
+        "aws_container_credentials_relative_uri",
+        "aws_skip_signature",
+        "aws_server_side_encryption",
+        "aws_sse_kms_key_id",
+        "aws_sse_bucket_key_enabled",
+    ],
+    exclusive: &[
+        ("skip_signature", "access_key_id"),
+        ("skip_signature", "secret_access_key"),
+        ("skip_signature", "session_token"),
+    ],
+    requires: &[
+        ("access_key_id", "secret_access_key"),
+        ("secret_access_key", "access_key_id"),
+        ("session_token", "access_key_id"),
+    ],
+};
+
+static AZURE_OPTIONS: StoreOptions = StoreOptions {
+    canonical_name: |name| Some(name.parse::<AzureConfigKey>().ok()?.as_ref().to_string()),
+    names: &[
+        "account_name",
+        "account_key",
+        "access_key",
+        "client_id",
+        "client_secret",
+        "tenant_id",
+        "authority_host",
+        "sas_key",
+        "sas_token",
+        "bearer_token",
+        "use_emulator",
+        "endpoint",
+        "msi_endpoint",
+        "object_id",
+        "msi_resource_id",
+        "federated_token_file",
+        "use_fabric_endpoint",
+        "use_azure_cli",
+        "skip_signature",
+        "container_name",
+        "disable_tagging",
+        "azure_storage_account_name",
+        "azure_storage_account_key",
+        "azure_client_id",
+        "azure_client_secret",
+        "azure_tenant_id",
+        "azure_storage_sas_key",
+        "azure_storage_token",
+        "azure_storage_use_emulator",
+        "azure_storage_endpoint",
+        "azure_skip_signature",
+        "azure_container_name",
+    ],
+    exclusive: &[
+        ("skip_signature", "account_key"),
+        ("skip_signature", "sas_key"),
+        ("skip_signature", "bearer_token"),
+        ("account_key", "sas_key"),
+    ],
+    requires: &[
+        ("client_secret", "client_id"),
+        ("client_secret", "tenant_id"),
+    ],
+};
+
+static GCS_OPTIONS: StoreOptions = StoreOptions {
+    canonical_name: |name| Some(name.parse::<GoogleConfigKey>().ok()?.as_ref().to_string()),
+    names: &[
+        "service_account",
+        "service_account_path",
+        "service_account_key",
+        "bucket",
+        "bucket_name",
+        "skip_signature",
+        "google_service_account",
+        "google_service_account_key",
+        "google_bucket",
+        "google_application_credentials",
+        "google_skip_signature",
+    ],
+    exclusive: &[
+        ("service_account", "service_account_key"),
+        ("skip_signature", "service_account"),
+        ("skip_signature", "service_account_key"),
+    ],
+    requires: &[],
+};
+
+static HTTP_OPTIONS: StoreOptions = StoreOptions {
+    canonical_name: |name| Some(name.parse::<ClientConfigKey>().ok()?.as_ref().to_string()),
+    names: &[],
+    exclusive: &[],
+    requires: &[],
+};
+
+/// Validate the `options` for the [ObjectStore] of `url`, as [crate::engine::default::DefaultEngine]
+/// does before creating the store. Unlike [parse_url_opts], which silently ignores options the store
+/// does not know, this rejects unknown options (suggesting the closest known option, if any), options
+/// that are given more than once under different names, and conflicting combinations of options
+/// (e.g. skipping request signing while also passing credentials).
+///
+/// Options for schemes with a handler registered via [insert_url_handler] are not validated. Local
+/// and in-memory stores take no options, so any options given for them (e.g. a default cloud
+/// region an application sets for every table) are ignored with a warning.
+pub fn validate_options(url: &Url, options: &HashMap<String, String>) -> DeltaResult<()> {
+    if let Ok(handlers) = URL_REGISTRY.read() {
+        if handlers.contains_key(url.scheme()) {
+            return Ok(());
+        }
+    }
+    // unsupported URLs fail when the store is created
+    let Ok((scheme, _)) = ObjectStoreScheme::parse(url) else {
+        return Ok(());
+    };
+    let store = match scheme {
+        ObjectStoreScheme::AmazonS3 => &S3_OPTIONS,
+        ObjectStoreScheme::MicrosoftAzure => &AZURE_OPTIONS,
+        ObjectStoreScheme::GoogleCloudStorage => &GCS_OPTIONS,
+        ObjectStoreScheme::Http => &HTTP_OPTIONS,
+        ObjectStoreScheme::Local | ObjectStoreScheme::Memory => {
+            if !options.is_empty() {
+                let mut names: Vec<_> = options.keys().collect();
+                names.sort();
+                warn!("Ignoring options {names:?}, which the store of {url} does not take");
+            }
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    let mut problems = vec![];
+    // canonical name => (name, value) of each known option
+    let mut known: HashMap<String, (&str, &str)> = HashMap::new();
+    let mut names: Vec<_> = options.keys().collect();
+    names.sort();
+    for name in names {
+        let Some(canonical) = (store.canonical_name)(name) else {
+            let candidates = store.names.iter().chain(CLIENT_NAMES);
+            let known_candidates =
+                candidates.filter(|candidate| (store.canonical_name)(candidate).is_some());
+            problems.push(match closest_name(name, known_candidates) {
+                Some(suggestion) => {
+                    format!("unknown option `{name}` (did you mean `{suggestion}`?)")
+                }
+                None => format!("unknown option `{name}`"),
+            });
+            continue;
+        };
+        let value = options[name].as_str();
+        if let Some((other, _)) = known.insert(canonical, (name.as_str(), value)) {
+            problems.push(format!(
+                "options `{other}` and `{name}` set the same option"
+            ));
+        }
+    }
+
+    // the option named `name`, if it is given and not disabled
+    let enabled = |name: &str| {
+        let (name, value) = known.get(&(store.canonical_name)(name)?)?;
+        let disabled = ["false", "0", "no", "off"]
+            .iter()
+            .any(|falsy| value.eq_ignore_ascii_case(falsy));
+        (!disabled).then_some(*name)
+    };
+    for &(a, b) in store.exclusive.iter().chain(CLIENT_EXCLUSIVE) {
+        if let (Some(a), Some(b)) = (enabled(a), enabled(b)) {
+            problems.push(format!("options `{a}` and `{b}` conflict"));
+        }
+    }
+    for &(a, b) in store.requires {
+        if let (Some(a), None) = (enabled(a), enabled(b)) {
+            problems.push(format!("option `{a}` requires option `{b}`"));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(DeltaError::generic(format!(
+        "Invalid options for the {scheme:?} object store of {url}: {}",
+        problems.join("; ")
+    )))
+}
+
+/// The candidate closest to `name` by edit distance, if it is close enough to be a likely typo.
+fn closest_name<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a &'static str>,
+) -> Option<&'static str> {
+    let max_distance = (name.len() / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Routes the URLs of files to the [ObjectStore] that serves them: the store of the first
+/// registered storage root that contains the URL, or else the table's store.
+///
+/// Storage roots let an engine read tables whose files live in other buckets or accounts than the
+/// table itself (e.g. shallow clones, whose adds hold absolute paths to the source table), each
+/// with its own credentials. Only the registered roots are ever routed to another store, so they
+/// act as an allowlist. If the table root is known, all other URLs must be in the bucket (or
+/// container) of the table to be read through the table's store; otherwise all of them are.
+#[derive(Debug, Clone)]
+pub(crate) struct ObjectStoreRouter {
+    table_store: Arc<DynObjectStore>,
+    table_root: Option<Url>,
+    roots: Vec<(Url, Arc<DynObjectStore>)>,
+}
+
+impl ObjectStoreRouter {
+    pub(crate) fn new(table_store: Arc<DynObjectStore>) -> Self {
+        Self {
+            table_store,
+            table_root: None,
+            roots: vec![],
+        }
+    }
+
+    /// Only serve the URLs in the bucket (or container) of `table_root` from the table's store.
+    pub(crate) fn with_table_root(mut self, table_root: Url) -> Self {
+        self.table_root = Some(table_root);
+        self
+    }
+
+    /// Serve the URLs under `root` from `store`. URLs are resolved against the store like against
+    /// the table's store, so `store` must serve the whole bucket (or container) of `root`, as the
+    /// stores created by [parse_url_opts] do.
+    pub(crate) fn with_root(mut self, mut root: Url, store: Arc<DynObjectStore>) -> Self {
+        if !root.path().ends_with('/') {
+            root.set_path(&format!("{}/", root.path()));
+        }
+        self.roots.push((root, store));
+        self
+    }
+
+    /// The store of the table, which also serves the URLs outside the registered roots.
+    pub(crate) fn table_store(&self) -> &Arc<DynObjectStore> {
+        &self.table_store
+    }
+
+    /// The store that serves `url`. Fails if `url` is neither under a registered root nor in the
+    /// bucket of the table, since the table's store would read some other file at its path.
+    pub(crate) fn store_for(&self, url: &Url) -> DeltaResult<&Arc<DynObjectStore>> {
+        let root = self
+            .roots
+            .iter()
+            .find(|(root, _)| same_bucket(root, url) && url.path().starts_with(root.path()));
+        match (root, &self.table_root) {
+            (Some((_, store)), _) => Ok(store),
+            (None, Some(table_root)) if !same_bucket(table_root, url) => {
+                Err(DeltaError::generic(format!(
+                    "Cannot read {url}: it is outside the table at {table_root} and every \
+                     registered storage root"
+                )))
+            }
+            (None, _) => Ok(&self.table_store),
+        }
+    }
+}
+
+/// Whether `a` and `b` have the same scheme, host and port.
+fn same_bucket(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme() && a.host_str() == b.host_str() && a.port() == b.port()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::utils::test_utils::assert_result_error_with_message;
     use hdfs_native_object_store::HdfsObjectStoreBuilder;
+    use object_store::memory::InMemory;
     use object_store::{self, path::Path};
 
     /// Example funciton of doing testing of a custom [HdfsObjectStore] construction
@@ -121,4 +539,110 @@ mod tests {
             panic!("Expected to get an error when constructing an HdfsObjectStore, but something didn't work as expected! Either the parse_url_opts_hdfs_native function didn't get called, or the hdfs-native-object-store no longer errors when it cannot connect to HDFS");
         }
     }
+
+    fn options(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let s3 = Url::parse("s3://bucket/table/").unwrap();
+        validate_options(&s3, &options(&[])).unwrap();
+        let valid = options(&[
+            ("region", "us-east-1"),
+            ("aws_access_key_id", "id"),
+            ("secret_access_key", "secret"),
+            ("skip_signature", "false"),
+            ("timeout", "30s"),
+        ]);
+        validate_options(&s3, &valid).unwrap();
+
+        let result = validate_options(&s3, &options(&[("skip_signatures", "true")]));
+        assert_result_error_with_message(
+            result,
+            "unknown option `skip_signatures` (did you mean `skip_signature`?)",
+        );
+        let result = validate_options(&s3, &options(&[("foo", "bar")]));
+        assert_result_error_with_message(result, "unknown option `foo`");
+        let result = validate_options(&s3, &options(&[("aws_region", "a"), ("region", "b")]));
+        assert_result_error_with_message(result, "options `aws_region` and `region` set the same");
+        let result = validate_options(
+            &s3,
+            &options(&[("skip_signature", "true"), ("access_key_id", "id")]),
+        );
+        assert_result_error_with_message(
+            result,
+            "options `skip_signature` and `access_key_id` conflict",
+        );
+        let result = validate_options(&s3, &options(&[("access_key_id", "id")]));
+        assert_result_error_with_message(result, "option `access_key_id` requires option");
+
+        let local = Url::parse("file:///tmp/table/").unwrap();
+        validate_options(&local, &options(&[])).unwrap();
+        // local and in-memory stores ignore the options of cloud stores
+        validate_options(&local, &options(&[("aws_region", "us-east-1")])).unwrap();
+        let memory = Url::parse("memory:///").unwrap();
+        validate_options(&memory, &options(&[("skip_signature", "true")])).unwrap();
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("skip_signatures", "skip_signature"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_object_store_router() {
+        let table_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let source_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let router = ObjectStoreRouter::new(table_store.clone()).with_root(
+            Url::parse("s3://source-bucket/tables").unwrap(),
+            source_store.clone(),
+        );
+        let store_for = |router: &ObjectStoreRouter, url: &str| {
+            router.store_for(&Url::parse(url).unwrap()).cloned()
+        };
+
+        assert!(Arc::ptr_eq(
+            &store_for(&router, "s3://source-bucket/tables/t/part-0.parquet").unwrap(),
+            &source_store
+        ));
+        // only URLs under the root are routed to its store
+        let outside_roots = [
+            "s3://source-bucket/tables-old/part-0.parquet",
+            "s3://other-bucket/tables/part-0.parquet",
+            "gs://source-bucket/tables/part-0.parquet",
+            "s3://table-bucket/table/part-0.parquet",
+        ];
+        for url in outside_roots {
+            assert!(
+                Arc::ptr_eq(&store_for(&router, url).unwrap(), &table_store),
+                "{url}"
+            );
+        }
+        assert!(Arc::ptr_eq(router.table_store(), &table_store));
+
+        // once the table root is known, only the URLs in its bucket are read from the table store
+        let router = router.with_table_root(Url::parse("s3://table-bucket/table/").unwrap());
+        for url in [
+            "s3://table-bucket/table/part-0.parquet",
+            "s3://table-bucket/other-table/part-0.parquet",
+        ] {
+            assert!(
+                Arc::ptr_eq(&store_for(&router, url).unwrap(), &table_store),
+                "{url}"
+            );
+        }
+        for url in &outside_roots[..3] {
+            let result = store_for(&router, url);
+            assert_result_error_with_message(
+                result,
+                "outside the table at s3://table-bucket/table/",
+            );
+        }
+    }
 }
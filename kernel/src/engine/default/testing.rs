@@ -0,0 +1,346 @@
+//! An in-memory object store with fault injection, see [`FaultInjectingStore`]. Requires the
+//! `test-utils` feature.
+//!
+//! Code paths like commit conflict resolution or log listing only misbehave under conditions that
+//! real cloud storage produces rarely and unpredictably: slow requests, concurrent writers racing
+//! for the same commit file, or listings that lag behind recent writes. A [`FaultInjectingStore`]
+//! produces them on demand, so engines (and the kernel's own tests) can exercise these paths
+//! deterministically.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use delta_kernel::engine::default::DefaultEngine;
+//! # use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+//! # use delta_kernel::engine::default::testing::FaultInjectingStore;
+//! # use object_store::path::Path;
+//! let store = Arc::new(FaultInjectingStore::new());
+//! let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+//! // the next attempt to commit version 1 loses the race against a (simulated) concurrent writer
+//! store.inject_conflict(Path::from("table/_delta_log/00000000000000000001.json"));
+//! // listings miss the most recent write, like an eventually consistent store
+//! store.set_listing_lag(1);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::stream::{self, BoxStream};
+use futures::{future, StreamExt as _, TryStreamExt as _};
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMode,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
+};
+
+/// An in-memory [`ObjectStore`] that injects faults into the requests made to it:
+///
+/// - latency, added to every request, see [`Self::set_latency`]
+/// - conflicts, where a create-only write fails as if another writer created the object first,
+///   see [`Self::inject_conflict`]
+/// - stale listings, which miss the most recently written objects, see [`Self::set_listing_lag`]
+/// - a manual clock for the modification times of written objects, see [`Self::set_clock`]
+///
+/// Faults can be changed at any time, also while requests are in flight. Without any faults, the
+/// store behaves like an [`InMemory`] store.
+#[derive(Debug, Default)]
+pub struct FaultInjectingStore {
+    inner: InMemory,
+    faults: Mutex<Faults>,
+}
+
+#[derive(Debug, Default)]
+struct Faults {
+    latency: Duration,
+    conflicts: HashSet<Path>,
+    listing_lag: usize,
+    // The written objects, oldest first
+    writes: Vec<Path>,
+    clock: Option<DateTime<Utc>>,
+    // The modification times of the objects written while the clock was set
+    modified: HashMap<Path, DateTime<Utc>>,
+}
+
+/// What listings see of the store, captured when the listing starts.
+struct ListingView {
+    hidden: HashSet<Path>,
+    modified: HashMap<Path, DateTime<Utc>>,
+}
+
+impl ListingView {
+    fn apply(&self, mut meta: ObjectMeta) -> Option<ObjectMeta> {
+        if self.hidden.contains(&meta.location) {
+            return None;
+        }
+        if let Some(modified) = self.modified.get(&meta.location) {
+            meta.last_modified = *modified;
+        }
+        Some(meta)
+    }
+}
+
+impl FaultInjectingStore {
+    /// Creates an empty store without any faults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every request by `latency`. Requires a tokio runtime, like the one of the default
+    /// engine's executors.
+    pub fn set_latency(&self, latency: Duration) {
+        self.faults().latency = latency;
+    }
+
+    /// Fail the next create-only write (e.g. of a commit file) to `location` with
+    /// [`object_store::Error::AlreadyExists`], as if a concurrent writer had won the race to create
+    /// it. The object itself is not created, so a retry can succeed.
+    pub fn inject_conflict(&self, location: Path) {
+        self.faults().conflicts.insert(location);
+    }
+
+    /// Make listings miss the `lag` most recently written objects, like an eventually consistent
+    /// store whose listings have not caught up with recent writes. Reading these objects directly
+    /// still succeeds. Set the lag to 0 to make listings consistent again.
+    pub fn set_listing_lag(&self, lag: usize) {
+        self.faults().listing_lag = lag;
+    }
+
+    /// Set the clock that determines the modification time of the objects written from now on.
+    /// Without a clock, objects get the current (wall clock) time as their modification time.
+    pub fn set_clock(&self, now: DateTime<Utc>) {
+        self.faults().clock = Some(now);
+    }
+
+    /// Advance the clock set by [`Self::set_clock`] by `delta`. Starts the clock at the Unix
+    /// epoch if it was not set.
+    pub fn advance_clock(&self, delta: TimeDelta) {
+        let mut faults = self.faults();
+        let now = faults.clock.unwrap_or(DateTime::UNIX_EPOCH);
+        faults.clock = Some(now + delta);
+    }
+
+    fn faults(&self) -> MutexGuard<'_, Faults> {
+        self.faults.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn delay(&self) {
+        let latency = self.faults().latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+
+    fn delayed<T: Send + 'static>(&self, stream: BoxStream<'static, T>) -> BoxStream<'static, T> {
+        let latency = self.faults().latency;
+        if latency.is_zero() {
+            return stream;
+        }
+        stream::once(async move {
+            tokio::time::sleep(latency).await;
+            stream
+        })
+        .flatten()
+        .boxed()
+    }
+
+    /// Returns true (and consumes the conflict) if a conflict was injected for `location`.
+    fn take_conflict(&self, location: &Path) -> bool {
+        self.faults().conflicts.remove(location)
+    }
+
+    fn record_write(&self, location: &Path) {
+        let mut faults = self.faults();
+        faults.writes.retain(|write| write != location);
+        faults.writes.push(location.clone());
+        match faults.clock {
+            Some(now) => faults.modified.insert(location.clone(), now),
+            None => faults.modified.remove(location),
+        };
+    }
+
+    fn listing_view(&self) -> ListingView {
+        let faults = self.faults();
+        let hidden = faults.writes.iter().rev().take(faults.listing_lag);
+        ListingView {
+            hidden: hidden.cloned().collect(),
+            modified: faults.modified.clone(),
+        }
+    }
+
+    fn conflict_error(location: &Path) -> object_store::Error {
+        object_store::Error::AlreadyExists {
+            path: location.to_string(),
+            source: "injected conflict".into(),
+        }
+    }
+}
+
+impl fmt::Display for FaultInjectingStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FaultInjectingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for FaultInjectingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.delay().await;
+        if matches!(opts.mode, PutMode::Create) && self.take_conflict(location) {
+            return Err(Self::conflict_error(location));
+        }
+        let result = self.inner.put_opts(location, payload, opts).await?;
+        self.record_write(location);
+        Ok(result)
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.delay().await;
+        // the modification time is only known once the upload completes, so leave it to the
+        // inner store
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.delay().await;
+        let mut result = self.inner.get_opts(location, options).await?;
+        if let Some(modified) = self.faults().modified.get(location) {
+            result.meta.last_modified = *modified;
+        }
+        Ok(result)
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.delay().await;
+        self.inner.delete(location).await?;
+        let mut faults = self.faults();
+        faults.writes.retain(|write| write != location);
+        faults.modified.remove(location);
+        Ok(())
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let view = self.listing_view();
+        let stream = self
+            .inner
+            .list(prefix)
+            .try_filter_map(move |meta| future::ready(Ok(view.apply(meta))));
+        self.delayed(stream.boxed())
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.delay().await;
+        let view = self.listing_view();
+        let mut result = self.inner.list_with_delimiter(prefix).await?;
+        result.objects = result
+            .objects
+            .into_iter()
+            .filter_map(|meta| view.apply(meta))
+            .collect();
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.delay().await;
+        self.inner.copy(from, to).await?;
+        self.record_write(to);
+        Ok(())
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.delay().await;
+        if self.take_conflict(to) {
+            return Err(Self::conflict_error(to));
+        }
+        self.inner.copy_if_not_exists(from, to).await?;
+        self.record_write(to);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt as _;
+
+    use super::*;
+
+    async fn list(store: &FaultInjectingStore) -> Vec<ObjectMeta> {
+        store.list(None).try_collect().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_conflict() {
+        let store = FaultInjectingStore::new();
+        let path = Path::from("a");
+        store.inject_conflict(path.clone());
+
+        // overwrites are unaffected
+        store.put(&path, "x".into()).await.unwrap();
+        store.delete(&path).await.unwrap();
+
+        let result = store
+            .put_opts(&path, "y".into(), PutMode::Create.into())
+            .await;
+        assert!(matches!(
+            result,
+            Err(object_store::Error::AlreadyExists { .. })
+        ));
+        assert!(matches!(
+            store.get(&path).await,
+            Err(object_store::Error::NotFound { .. })
+        ));
+        // the conflict only happens once
+        store
+            .put_opts(&path, "y".into(), PutMode::Create.into())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_listing_lag() {
+        let store = FaultInjectingStore::new();
+        store.set_listing_lag(2);
+        for name in ["a", "b", "c"] {
+            store.put(&Path::from(name), name.into()).await.unwrap();
+        }
+        let listed: Vec<_> = list(&store).await.into_iter().map(|m| m.location).collect();
+        assert_eq!(listed, [Path::from("a")]);
+        let listed = store.list_with_delimiter(None).await.unwrap().objects;
+        assert_eq!(listed.len(), 1);
+        // reads are consistent
+        store.get(&Path::from("c")).await.unwrap();
+
+        store.set_listing_lag(0);
+        assert_eq!(list(&store).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_clock_and_latency() {
+        let store = FaultInjectingStore::new();
+        store.set_latency(Duration::from_millis(1));
+        store.advance_clock(TimeDelta::seconds(10));
+        store.put(&Path::from("a"), "a".into()).await.unwrap();
+        store.advance_clock(TimeDelta::seconds(10));
+        store.put(&Path::from("b"), "b".into()).await.unwrap();
+
+        let modified: Vec<_> = list(&store)
+            .await
+            .into_iter()
+            .map(|meta| meta.last_modified.timestamp())
+            .collect();
+        assert_eq!(modified, [10, 20]);
+        let meta = store.head(&Path::from("b")).await.unwrap();
+        assert_eq!(meta.last_modified.timestamp(), 20);
+    }
+}
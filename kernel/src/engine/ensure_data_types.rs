@@ -11,7 +11,7 @@ use itertools::Itertools;
 use super::arrow_conversion::TryIntoArrow as _;
 use crate::{
     engine::arrow_utils::make_arrow_error,
-    schema::{DataType, MetadataValue, StructField},
+    schema::{DataType, MetadataValue, PrimitiveType, StructField},
     utils::require,
     DeltaResult, Error,
 };
@@ -39,6 +39,65 @@ pub(crate) fn ensure_data_types(
     check.ensure_data_types(kernel_type, arrow_type)
 }
 
+/// Like [`ensure_data_types`] without nullability and metadata checks, but for data read from
+/// parquet files: on top of the types `ensure_data_types` accepts, this accepts the arrow types that
+/// the parquet reader produces for files of legacy writers (e.g. older Hive, Impala and Spark
+/// versions), and returns the cast to the requested type for them:
+/// - strings stored as `BYTE_ARRAY` without the `UTF8` annotation are read as binary. Values that
+///   are not valid UTF-8 are cast to null.
+/// - binary stored as `FIXED_LEN_BYTE_ARRAY` is read as fixed size binary
+/// - the deprecated `UINT_8`, `UINT_16`, `UINT_32` and `UINT_64` converted types are read as
+///   unsigned integers, and cast to signed integer or decimal types that hold all their values
+///
+/// `INT96` timestamps and timestamps without `isAdjustedToUTC` are read as timestamps without time
+/// zone, which (like any other timestamp) are cast to the requested timestamp type already. Note
+/// that the parquet reader reads `INT96` timestamps as nanoseconds, so only those between the years
+/// 1677 and 2262 read correctly.
+pub(crate) fn ensure_parquet_data_types(
+    kernel_type: &DataType,
+    arrow_type: &ArrowDataType,
+) -> DeltaResult<DataTypeCompat> {
+    match legacy_parquet_cast(kernel_type, arrow_type)? {
+        Some(target) => Ok(DataTypeCompat::NeedsCast(target)),
+        None => ensure_data_types(kernel_type, arrow_type, false),
+    }
+}
+
+/// The cast from `arrow_type`, as read from a parquet file of a legacy writer, to `kernel_type`, see
+/// [`ensure_parquet_data_types`]. `None` if `arrow_type` is not a legacy type for `kernel_type`.
+fn legacy_parquet_cast(
+    kernel_type: &DataType,
+    arrow_type: &ArrowDataType,
+) -> DeltaResult<Option<ArrowDataType>> {
+    use ArrowDataType::*;
+
+    let DataType::Primitive(primitive) = kernel_type else {
+        return Ok(None);
+    };
+    let is_legacy = match (primitive, arrow_type) {
+        (PrimitiveType::String, Binary | LargeBinary | BinaryView) => true,
+        (PrimitiveType::Binary, FixedSizeBinary(_)) => true,
+        (PrimitiveType::Short | PrimitiveType::Integer | PrimitiveType::Long, UInt8) => true,
+        (PrimitiveType::Integer | PrimitiveType::Long, UInt16) => true,
+        (PrimitiveType::Long, UInt32) => true,
+        (PrimitiveType::Decimal(decimal), UInt8 | UInt16 | UInt32 | UInt64) => {
+            // the number of digits of the largest value of the unsigned type
+            let digits = match arrow_type {
+                UInt8 => 3,
+                UInt16 => 5,
+                UInt32 => 10,
+                _ => 20,
+            };
+            decimal.precision() - decimal.scale() >= digits
+        }
+        _ => false,
+    };
+    match is_legacy {
+        true => Ok(Some(kernel_type.try_into_arrow()?)),
+        false => Ok(None),
+    }
+}
+
 struct EnsureDataTypes {
     check_nullability_and_metadata: bool,
 }
@@ -265,7 +324,9 @@ fn metadata_eq(
 mod tests {
     use std::sync::Arc;
 
-    use crate::arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Fields};
+    use crate::arrow::datatypes::{
+        DataType as ArrowDataType, Field as ArrowField, Fields, TimeUnit,
+    };
 
     use crate::engine::arrow_conversion::TryFromKernel as _;
     use crate::engine::arrow_data::unshredded_variant_arrow_type;
@@ -554,6 +615,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ensure_legacy_parquet_types() {
+        let needs_cast = |kernel_type: &DataType, arrow_type: ArrowDataType| {
+            ensure_parquet_data_types(kernel_type, &arrow_type).unwrap()
+        };
+        assert_eq!(
+            needs_cast(&DataType::STRING, ArrowDataType::Binary),
+            DataTypeCompat::NeedsCast(ArrowDataType::Utf8)
+        );
+        assert_eq!(
+            needs_cast(&DataType::BINARY, ArrowDataType::FixedSizeBinary(16)),
+            DataTypeCompat::NeedsCast(ArrowDataType::Binary)
+        );
+        assert_eq!(
+            needs_cast(&DataType::SHORT, ArrowDataType::UInt8),
+            DataTypeCompat::NeedsCast(ArrowDataType::Int16)
+        );
+        assert_eq!(
+            needs_cast(&DataType::LONG, ArrowDataType::UInt32),
+            DataTypeCompat::NeedsCast(ArrowDataType::Int64)
+        );
+        assert_eq!(
+            needs_cast(&DataType::decimal(20, 0).unwrap(), ArrowDataType::UInt64),
+            DataTypeCompat::NeedsCast(ArrowDataType::Decimal128(20, 0))
+        );
+        // INT96 timestamps are read as nanoseconds without time zone
+        assert_eq!(
+            needs_cast(
+                &DataType::TIMESTAMP,
+                ArrowDataType::Timestamp(TimeUnit::Nanosecond, None)
+            ),
+            DataTypeCompat::NeedsCast(ArrowDataType::Timestamp(
+                TimeUnit::Microsecond,
+                Some("UTC".into())
+            ))
+        );
+        // types that don't hold all values of the unsigned type are still rejected
+        assert!(ensure_parquet_data_types(&DataType::INTEGER, &ArrowDataType::UInt32).is_err());
+        assert!(ensure_parquet_data_types(
+            &DataType::decimal(20, 1).unwrap(),
+            &ArrowDataType::UInt64
+        )
+        .is_err());
+        // the legacy types are only accepted for parquet reads
+        assert!(ensure_data_types(&DataType::STRING, &ArrowDataType::Binary, false).is_err());
+    }
+
     #[test]
     fn ensure_large_strings_and_binary() {
         assert_eq!(
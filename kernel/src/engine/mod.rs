@@ -15,6 +15,9 @@ pub use self::arrow_utils::{parse_json, to_json_bytes};
 #[cfg(feature = "default-engine-base")]
 pub mod default;
 
+#[cfg(feature = "default-engine-base")]
+pub mod async_engine;
+
 #[cfg(test)]
 pub(crate) mod sync;
 
@@ -27,6 +30,9 @@ pub(crate) mod ensure_data_types;
 #[cfg(feature = "default-engine-base")]
 pub mod parquet_row_group_skipping;
 
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -3,13 +3,19 @@ use crate::engine::arrow_utils::RowIndexBuilder;
 use crate::expressions::{ColumnName, DecimalData, Predicate, Scalar};
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
 use crate::parquet::arrow::arrow_reader::ArrowReaderBuilder;
-use crate::parquet::file::metadata::RowGroupMetaData;
+use crate::parquet::data_type::Int96;
+use crate::parquet::file::metadata::{FileMetaData, ParquetMetaData, RowGroupMetaData};
 use crate::parquet::file::statistics::Statistics;
 use crate::parquet::schema::types::ColumnDescPtr;
+use crate::scan::skipping_trace::{
+    ClauseResult, SkippingTarget, SkippingTrace, SkippingTraceEntry,
+};
 use crate::schema::{DataType, DecimalType, PrimitiveType};
 use chrono::{DateTime, Days};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use tracing::debug;
+use url::Url;
 
 #[cfg(test)]
 mod tests;
@@ -21,10 +27,14 @@ pub(crate) trait ParquetRowGroupSkipping {
     ///
     /// If a [`RowIndexBuilder`] is provided, it will be updated to only include row indices of the
     /// row groups that survived the filter.
+    ///
+    /// If a [`SkippingTrace`] is provided, the decision for each row group of the file at the given
+    /// location is recorded in it.
     fn with_row_group_filter(
         self,
         predicate: &Predicate,
         row_indexes: Option<&mut RowIndexBuilder>,
+        trace: Option<(&SkippingTrace, &Url)>,
     ) -> Self;
 }
 impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
@@ -32,18 +42,9 @@ impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
         self,
         predicate: &Predicate,
         row_indexes: Option<&mut RowIndexBuilder>,
+        trace: Option<(&SkippingTrace, &Url)>,
     ) -> Self {
-        let ordinals: Vec<_> = self
-            .metadata()
-            .row_groups()
-            .iter()
-            .enumerate()
-            .filter_map(|(ordinal, row_group)| {
-                // If the group survives the filter, return Some(ordinal) so filter_map keeps it.
-                RowGroupFilter::apply(row_group, predicate).then_some(ordinal)
-            })
-            .collect();
-        debug!("with_row_group_filter({predicate:#?}) = {ordinals:?})");
+        let ordinals = filter_row_groups(self.metadata(), predicate, trace);
         if let Some(row_indexes) = row_indexes {
             row_indexes.select_row_groups(&ordinals);
         }
@@ -51,45 +52,172 @@ impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
     }
 }
 
+/// Returns the ordinals of the row groups in `metadata` whose stats do not prove that none of their
+/// rows can satisfy `predicate`. See [`ParquetRowGroupSkipping::with_row_group_filter`].
+pub(crate) fn filter_row_groups(
+    metadata: &ParquetMetaData,
+    predicate: &Predicate,
+    trace: Option<(&SkippingTrace, &Url)>,
+) -> Vec<usize> {
+    // all row groups share the file's schema, so the predicate's columns are bound only once
+    let file_metadata = metadata.file_metadata();
+    let columns = BoundColumns::new(file_metadata.schema_descr().columns(), predicate);
+    let ordinals: Vec<_> = metadata
+        .row_groups()
+        .iter()
+        .enumerate()
+        .filter_map(|(ordinal, row_group)| {
+            let filter = RowGroupFilter::with_columns(file_metadata, row_group, &columns);
+            let keep = filter.apply(predicate);
+            if let Some((trace, location)) = trace {
+                let target = SkippingTarget::RowGroup {
+                    location: location.clone(),
+                    ordinal,
+                };
+                trace.record([filter.trace_entry(predicate, target, keep)]);
+            }
+            // If the group survives the filter, return Some(ordinal) so filter_map keeps it.
+            keep.then_some(ordinal)
+        })
+        .collect();
+    debug!("with_row_group_filter({predicate:#?}) = {ordinals:?})");
+    ordinals
+}
+
+/// The parquet columns a predicate references, bound to their field indices, for O(1) stats
+/// lookups. All row groups of a file share its schema, so this is computed once per file.
+#[derive(Debug, Clone)]
+struct BoundColumns {
+    field_indices: HashMap<ColumnName, usize>,
+    struct_leaf_indices: HashMap<ColumnName, Vec<usize>>,
+}
+
+impl BoundColumns {
+    fn new(fields: &[ColumnDescPtr], predicate: &Predicate) -> Self {
+        Self {
+            field_indices: compute_field_indices(fields, predicate),
+            struct_leaf_indices: compute_struct_leaf_indices(fields, predicate),
+        }
+    }
+}
+
 /// A ParquetStatsSkippingFilter for row group skipping. It obtains stats from a parquet
-/// [`RowGroupMetaData`] and pre-computes the mapping of each referenced column path to its
-/// corresponding field index, for O(1) stats lookups.
+/// [`RowGroupMetaData`], using the predicate's [`BoundColumns`] to look them up.
 struct RowGroupFilter<'a> {
+    file_metadata: &'a FileMetaData,
     row_group: &'a RowGroupMetaData,
-    field_indices: HashMap<ColumnName, usize>,
+    columns: Cow<'a, BoundColumns>,
 }
 
 impl<'a> RowGroupFilter<'a> {
     /// Creates a new row group filter for the given row group and predicate.
-    fn new(row_group: &'a RowGroupMetaData, predicate: &Predicate) -> Self {
+    #[cfg(test)]
+    fn new(
+        file_metadata: &'a FileMetaData,
+        row_group: &'a RowGroupMetaData,
+        predicate: &Predicate,
+    ) -> Self {
+        let columns = BoundColumns::new(row_group.schema_descr().columns(), predicate);
         Self {
+            file_metadata,
             row_group,
-            field_indices: compute_field_indices(row_group.schema_descr().columns(), predicate),
+            columns: Cow::Owned(columns),
         }
     }
 
-    /// Applies a filtering predicate to a row group. Return value false means to skip it.
-    fn apply(row_group: &'a RowGroupMetaData, predicate: &Predicate) -> bool {
+    /// Creates a new row group filter for the given row group, with the columns of the predicate
+    /// bound to the schema of its file.
+    fn with_columns(
+        file_metadata: &'a FileMetaData,
+        row_group: &'a RowGroupMetaData,
+        columns: &'a BoundColumns,
+    ) -> Self {
+        Self {
+            file_metadata,
+            row_group,
+            columns: Cow::Borrowed(columns),
+        }
+    }
+
+    /// Applies a filtering predicate to the row group. Return value false means to skip it.
+    fn apply(&self, predicate: &Predicate) -> bool {
         use crate::kernel_predicates::KernelPredicateEvaluator as _;
-        RowGroupFilter::new(row_group, predicate).eval_sql_where(predicate) != Some(false)
+        self.eval_sql_where(predicate) != Some(false)
+    }
+
+    /// Explains the outcome of [`Self::apply`], by evaluating each clause of the predicate on its
+    /// own.
+    fn trace_entry(
+        &self,
+        predicate: &Predicate,
+        target: SkippingTarget,
+        keep: bool,
+    ) -> SkippingTraceEntry {
+        use crate::kernel_predicates::KernelPredicateEvaluator as _;
+        let clauses = predicate
+            .conjuncts()
+            .into_iter()
+            .map(|clause| ClauseResult {
+                clause: clause.clone(),
+                result: self.eval_sql_where(clause),
+            })
+            .collect();
+        SkippingTraceEntry {
+            target,
+            pruned: !keep,
+            clauses,
+        }
     }
 
     /// Returns `None` if the column doesn't exist and `Some(None)` if the column has no stats.
     fn get_stats(&self, col: &ColumnName) -> Option<Option<&Statistics>> {
-        self.field_indices
+        self.columns
+            .field_indices
             .get(col)
             .map(|&i| self.row_group.column(i).statistics())
     }
 
-    fn decimal_from_bytes(bytes: Option<&[u8]>, dtype: DecimalType) -> Option<Scalar> {
+    fn nullcount_from_stats(stats: &Statistics) -> Option<u64> {
+        // WARNING: [`Statistics::null_count_opt`] returns Some(0) when the underlying stat is
+        // missing, causing an IS NULL predicate to wrongly skip the file if it contains any NULL
+        // values. Manually drill into each arm's [`ValueStatistics`] for the stat's true.
+        match stats {
+            Statistics::Boolean(s) => s.null_count_opt(),
+            Statistics::Int32(s) => s.null_count_opt(),
+            Statistics::Int64(s) => s.null_count_opt(),
+            Statistics::Int96(s) => s.null_count_opt(),
+            Statistics::Float(s) => s.null_count_opt(),
+            Statistics::Double(s) => s.null_count_opt(),
+            Statistics::ByteArray(s) => s.null_count_opt(),
+            Statistics::FixedLenByteArray(s) => s.null_count_opt(),
+        }
+    }
+
+    fn decimal_from_bytes(bytes: Option<&[u8]>) -> Option<i128> {
         // WARNING: The bytes are stored in big-endian order; reverse and then 0-pad to 16 bytes.
         let bytes = bytes.filter(|b| b.len() <= 16)?;
         let mut bytes = Vec::from(bytes);
         bytes.reverse();
         bytes.resize(16, 0u8);
         let bytes: [u8; 16] = bytes.try_into().ok()?;
-        let value = DecimalData::try_new(i128::from_le_bytes(bytes), dtype).ok()?;
-        Some(value.into())
+        Some(i128::from_le_bytes(bytes))
+    }
+
+    /// Interprets an unscaled decimal (or plain integer) stat as a value of `dtype`. Type widening
+    /// may have increased the scale since the file was written, in which case the stat is rescaled
+    /// from the scale recorded in the file's own column descriptor.
+    fn decimal_from_unscaled(
+        &self,
+        col: &ColumnName,
+        unscaled: i128,
+        dtype: DecimalType,
+    ) -> Option<Scalar> {
+        let i = *self.columns.field_indices.get(col)?;
+        // Columns without a decimal annotation report a negative scale; treat them as integers.
+        let file_scale = self.row_group.column(i).column_descr().type_scale().max(0);
+        let shift = u32::try_from(i32::from(dtype.scale()) - file_scale).ok()?;
+        let value = unscaled.checked_mul(10i128.checked_pow(shift)?)?;
+        Some(DecimalData::try_new(value, dtype).ok()?.into())
     }
 
     fn timestamp_from_date(days: Option<&i32>) -> Option<Scalar> {
@@ -98,8 +226,51 @@ impl<'a> RowGroupFilter<'a> {
         let timestamp = timestamp.signed_duration_since(DateTime::UNIX_EPOCH);
         Some(Scalar::TimestampNtz(timestamp.num_microseconds()?))
     }
+
+    /// Decodes a legacy INT96 timestamp (nanos within the day, followed by the julian day) to
+    /// microseconds since the epoch. Sub-microsecond precision is truncated, same as when reading
+    /// the column itself.
+    fn timestamp_from_int96(value: &Int96) -> Option<i64> {
+        const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
+        const MICROS_PER_DAY: i64 = 86_400_000_000;
+        let &[nanos_lo, nanos_hi, julian_day] = value.data() else {
+            return None;
+        };
+        let nanos = (u64::from(nanos_hi) << 32) | u64::from(nanos_lo);
+        let days = i64::from(julian_day) - JULIAN_DAY_OF_EPOCH;
+        days.checked_mul(MICROS_PER_DAY)?
+            .checked_add(i64::try_from(nanos / 1000).ok()?)
+    }
+
+    /// Returns the (min, max) stats of an INT96 timestamp column, as microseconds since the epoch.
+    ///
+    /// The parquet spec leaves the sort order of INT96 undefined (parquet-rs reports an undefined
+    /// column order for every INT96 column), and some writers compare the raw values, which does
+    /// not order timestamps correctly. So we only trust INT96 stats written by one of
+    /// the [`INT96_TIMESTAMP_STATS_WRITERS`], and only if the decoded values are consistent.
+    fn int96_timestamp_stats(&self, col: &ColumnName) -> Option<(i64, i64)> {
+        let created_by = self.file_metadata.created_by()?;
+        if !INT96_TIMESTAMP_STATS_WRITERS
+            .iter()
+            .any(|writer| created_by.starts_with(writer))
+        {
+            return None;
+        }
+        let i = *self.columns.field_indices.get(col)?;
+        let Statistics::Int96(s) = self.row_group.column(i).statistics()? else {
+            return None;
+        };
+        let min = Self::timestamp_from_int96(s.min_opt()?)?;
+        let max = Self::timestamp_from_int96(s.max_opt()?)?;
+        (min <= max).then_some((min, max))
+    }
 }
 
+/// Prefixes of the `created_by` of parquet writers known to order INT96 stats as timestamps.
+/// parquet-mr and parquet-cpp don't write INT96 stats at all, and parquet-rs orders them by the
+/// raw values.
+const INT96_TIMESTAMP_STATS_WRITERS: &[&str] = &["impala version"];
+
 impl ParquetStatsProvider for RowGroupFilter<'_> {
     // Extracts a stat value, converting from its physical type to the requested logical type.
     //
@@ -125,6 +296,7 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
             (Float, _) => return None,
             (Double, Statistics::Double(s)) => s.min_opt()?.into(),
             (Double, Statistics::Float(s)) => (*s.min_opt()? as f64).into(),
+            (Double, Statistics::Int32(s)) => (*s.min_opt()? as f64).into(),
             (Double, _) => return None,
             (Boolean, Statistics::Boolean(s)) => s.min_opt()?.into(),
             (Boolean, _) => return None,
@@ -134,18 +306,25 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
             (Date, Statistics::Int32(s)) => Scalar::Date(*s.min_opt()?),
             (Date, _) => return None,
             (Timestamp, Statistics::Int64(s)) => Scalar::Timestamp(*s.min_opt()?),
-            (Timestamp, _) => return None, // TODO: Int96 timestamps
+            (Timestamp, Statistics::Int96(_)) => {
+                Scalar::Timestamp(self.int96_timestamp_stats(col)?.0)
+            }
+            (Timestamp, _) => return None,
             (TimestampNtz, Statistics::Int64(s)) => Scalar::TimestampNtz(*s.min_opt()?),
             (TimestampNtz, Statistics::Int32(s)) => Self::timestamp_from_date(s.min_opt())?,
-            (TimestampNtz, _) => return None, // TODO: Int96 timestamps
+            (TimestampNtz, Statistics::Int96(_)) => {
+                Scalar::TimestampNtz(self.int96_timestamp_stats(col)?.0)
+            }
+            (TimestampNtz, _) => return None,
             (Decimal(d), Statistics::Int32(i)) => {
-                DecimalData::try_new(*i.min_opt()?, *d).ok()?.into()
+                self.decimal_from_unscaled(col, (*i.min_opt()?).into(), *d)?
             }
             (Decimal(d), Statistics::Int64(i)) => {
-                DecimalData::try_new(*i.min_opt()?, *d).ok()?.into()
+                self.decimal_from_unscaled(col, (*i.min_opt()?).into(), *d)?
             }
             (Decimal(d), Statistics::FixedLenByteArray(b)) => {
-                Self::decimal_from_bytes(b.min_bytes_opt(), *d)?
+                let unscaled = Self::decimal_from_bytes(b.min_bytes_opt())?;
+                self.decimal_from_unscaled(col, unscaled, *d)?
             }
             (Decimal(..), _) => return None,
         };
@@ -171,6 +350,7 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
             (Float, _) => return None,
             (Double, Statistics::Double(s)) => s.max_opt()?.into(),
             (Double, Statistics::Float(s)) => (*s.max_opt()? as f64).into(),
+            (Double, Statistics::Int32(s)) => (*s.max_opt()? as f64).into(),
             (Double, _) => return None,
             (Boolean, Statistics::Boolean(s)) => s.max_opt()?.into(),
             (Boolean, _) => return None,
@@ -180,18 +360,25 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
             (Date, Statistics::Int32(s)) => Scalar::Date(*s.max_opt()?),
             (Date, _) => return None,
             (Timestamp, Statistics::Int64(s)) => Scalar::Timestamp(*s.max_opt()?),
-            (Timestamp, _) => return None, // TODO: Int96 timestamps
+            (Timestamp, Statistics::Int96(_)) => {
+                Scalar::Timestamp(self.int96_timestamp_stats(col)?.1)
+            }
+            (Timestamp, _) => return None,
             (TimestampNtz, Statistics::Int64(s)) => Scalar::TimestampNtz(*s.max_opt()?),
             (TimestampNtz, Statistics::Int32(s)) => Self::timestamp_from_date(s.max_opt())?,
-            (TimestampNtz, _) => return None, // TODO: Int96 timestamps
+            (TimestampNtz, Statistics::Int96(_)) => {
+                Scalar::TimestampNtz(self.int96_timestamp_stats(col)?.1)
+            }
+            (TimestampNtz, _) => return None,
             (Decimal(d), Statistics::Int32(i)) => {
-                DecimalData::try_new(*i.max_opt()?, *d).ok()?.into()
+                self.decimal_from_unscaled(col, (*i.max_opt()?).into(), *d)?
             }
             (Decimal(d), Statistics::Int64(i)) => {
-                DecimalData::try_new(*i.max_opt()?, *d).ok()?.into()
+                self.decimal_from_unscaled(col, (*i.max_opt()?).into(), *d)?
             }
             (Decimal(d), Statistics::FixedLenByteArray(b)) => {
-                Self::decimal_from_bytes(b.max_bytes_opt(), *d)?
+                let unscaled = Self::decimal_from_bytes(b.max_bytes_opt())?;
+                self.decimal_from_unscaled(col, unscaled, *d)?
             }
             (Decimal(..), _) => return None,
         };
@@ -199,6 +386,17 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
     }
 
     fn get_parquet_nullcount_stat(&self, col: &ColumnName) -> Option<i64> {
+        // A struct column has no stats of its own, but a null struct makes all of its leaves null.
+        // So the struct has no nulls if any of its leaves has none, and otherwise its nullcount is
+        // unknown (even all-null leaves don't prove that the struct itself is null).
+        if let Some(leaves) = self.columns.struct_leaf_indices.get(col) {
+            let no_nulls = leaves.iter().any(|&i| {
+                let stats = self.row_group.column(i).statistics();
+                stats.and_then(Self::nullcount_from_stats) == Some(0)
+            });
+            return no_nulls.then_some(0);
+        }
+
         // NOTE: Stats for any given column are optional, which may produce a NULL nullcount. But if
         // the column itself is missing, then we know all values are implied to be NULL.
         //
@@ -211,24 +409,10 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
             return Some(self.get_parquet_rowcount_stat()).filter(|_| false);
         };
 
-        // WARNING: [`Statistics::null_count_opt`] returns Some(0) when the underlying stat is
-        // missing, causing an IS NULL predicate to wrongly skip the file if it contains any NULL
-        // values. Manually drill into each arm's [`ValueStatistics`] for the stat's true.
-        let nullcount = match stats? {
-            Statistics::Boolean(s) => s.null_count_opt(),
-            Statistics::Int32(s) => s.null_count_opt(),
-            Statistics::Int64(s) => s.null_count_opt(),
-            Statistics::Int96(s) => s.null_count_opt(),
-            Statistics::Float(s) => s.null_count_opt(),
-            Statistics::Double(s) => s.null_count_opt(),
-            Statistics::ByteArray(s) => s.null_count_opt(),
-            Statistics::FixedLenByteArray(s) => s.null_count_opt(),
-        };
-
         // Parquet nullcount stats are always u64, so we can directly return the value instead of
         // wrapping it in a Scalar. We can safely cast it from u64 to i64 because the nullcount can
         // never be larger than the rowcount and the parquet rowcount stat is i64.
-        Some(nullcount? as i64)
+        Some(Self::nullcount_from_stats(stats?)? as i64)
     }
 
     fn get_parquet_rowcount_stat(&self) -> i64 {
@@ -259,3 +443,27 @@ pub(crate) fn compute_field_indices(
         })
         .collect()
 }
+
+/// Given a predicate of interest and a set of parquet column descriptors, map each (struct) column
+/// the predicate references to the indices of the leaf columns nested below it.
+pub(crate) fn compute_struct_leaf_indices(
+    fields: &[ColumnDescPtr],
+    predicate: &Predicate,
+) -> HashMap<ColumnName, Vec<usize>> {
+    predicate
+        .references()
+        .into_iter()
+        .filter_map(|col| {
+            let leaves: Vec<_> = fields
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| {
+                    let parts = f.path().parts();
+                    parts.len() > col.len() && parts.starts_with(col)
+                })
+                .map(|(i, _)| i)
+                .collect();
+            (!leaves.is_empty()).then(|| (col.clone(), leaves))
+        })
+        .collect()
+}
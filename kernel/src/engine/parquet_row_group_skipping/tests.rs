@@ -57,7 +57,11 @@ fn test_get_stat_values() {
         column_pred!("chrono.timestamp"),
         column_pred!("chrono.timestamp_ntz"),
     ]);
-    let filter = RowGroupFilter::new(metadata.metadata().row_group(0), &columns);
+    let filter = RowGroupFilter::new(
+        metadata.metadata().file_metadata(),
+        metadata.metadata().row_group(0),
+        &columns,
+    );
 
     assert_eq!(filter.get_rowcount_stat(), Some(5i64.into()));
 
@@ -435,3 +439,231 @@ fn test_get_stat_values() {
         )
     );
 }
+
+/// Stats for columns whose type was widened after the file was written must be converted to the
+/// widened type, including rescaling decimals whose scale changed.
+#[test]
+fn test_get_stat_values_type_widening() {
+    let file = File::open("./tests/data/parquet_row_group_skipping/part-00000-b92e017a-50ba-4676-8322-48fc371c2b59-c000.snappy.parquet").unwrap();
+    let metadata = ArrowReaderMetadata::load(&file, Default::default()).unwrap();
+
+    let columns = Predicate::and_from(vec![
+        column_pred!("numeric.ints.int32"),
+        column_pred!("numeric.ints.int64"),
+        column_pred!("numeric.decimals.decimal32"),
+        column_pred!("numeric.decimals.decimal128"),
+    ]);
+    let filter = RowGroupFilter::new(
+        metadata.metadata().file_metadata(),
+        metadata.metadata().row_group(0),
+        &columns,
+    );
+
+    // int -> double
+    assert_eq!(
+        filter.get_min_stat(&column_name!("numeric.ints.int32"), &DataType::DOUBLE),
+        Some(1000000f64.into())
+    );
+    assert_eq!(
+        filter.get_max_stat(&column_name!("numeric.ints.int32"), &DataType::DOUBLE),
+        Some(1000004f64.into())
+    );
+
+    // int -> decimal: the stat is an integer, not an unscaled decimal
+    assert_eq!(
+        filter.get_min_stat(
+            &column_name!("numeric.ints.int32"),
+            &DataType::decimal(12, 2).unwrap()
+        ),
+        Some(Scalar::decimal(100000000, 12, 2).unwrap())
+    );
+    assert_eq!(
+        filter.get_max_stat(
+            &column_name!("numeric.ints.int64"),
+            &DataType::decimal(22, 2).unwrap()
+        ),
+        Some(Scalar::decimal(100000000400, 22, 2).unwrap())
+    );
+
+    // decimal scale increase
+    assert_eq!(
+        filter.get_min_stat(
+            &column_name!("numeric.decimals.decimal32"),
+            &DataType::decimal(10, 5).unwrap()
+        ),
+        Some(Scalar::decimal(1103200, 10, 5).unwrap())
+    );
+    assert_eq!(
+        filter.get_max_stat(
+            &column_name!("numeric.decimals.decimal128"),
+            &DataType::decimal(34, 5).unwrap()
+        ),
+        Some(Scalar::decimal(1512800, 34, 5).unwrap())
+    );
+
+    // scale decreases are not valid widenings, so no stats are returned
+    assert_eq!(
+        filter.get_min_stat(
+            &column_name!("numeric.decimals.decimal32"),
+            &DataType::decimal(8, 2).unwrap()
+        ),
+        None
+    );
+}
+
+/// INT96 timestamp stats are decoded to microseconds, but only trusted if they were written by a
+/// writer known to order them as timestamps, and the decoded values are consistent. The file has a
+/// single INT96 column `ts` with the values 1970-01-02 00:00:00.0000015, 1970-01-03 00:00:00 and
+/// 1970-01-02 01:00:00, and the footer of an Impala-written file, whose stats order the values as
+/// timestamps:
+///
+/// ```text
+/// created_by: impala version 4.1.0-RELEASE (build 0000000000000000000000000000000000000000)
+/// Row group 0:  count: 3
+/// --------------------------------------------------------------------------------
+///     type      nulls   min / max
+/// ts  INT96     0       "1970-01-02T00:00:00.0000015" / "1970-01-03T00:00:00"
+/// ```
+#[test]
+fn test_get_stat_values_int96() {
+    use crate::parquet::basic::{ColumnOrder, SortOrder};
+    use crate::parquet::file::metadata::ColumnChunkMetaData;
+
+    let file = File::open("./tests/data/int96_timestamp_stats/impala.parquet").unwrap();
+    let metadata = ArrowReaderMetadata::load(&file, Default::default()).unwrap();
+    let file_metadata = metadata.metadata().file_metadata();
+    let row_group = metadata.metadata().row_group(0);
+    let col = column_name!("ts");
+    let columns = column_pred!("ts");
+
+    // The reader doesn't know any sort order for INT96, so the writer has to be trusted
+    assert_eq!(
+        file_metadata.column_order(0),
+        ColumnOrder::TYPE_DEFINED_ORDER(SortOrder::UNDEFINED)
+    );
+    let filter = RowGroupFilter::new(file_metadata, row_group, &columns);
+    assert_eq!(
+        filter.get_min_stat(&col, &DataType::TIMESTAMP),
+        Some(Scalar::Timestamp(86_400_000_001))
+    );
+    assert_eq!(
+        filter.get_max_stat(&col, &DataType::TIMESTAMP),
+        Some(Scalar::Timestamp(172_800_000_000))
+    );
+    assert_eq!(
+        filter.get_max_stat(&col, &DataType::TIMESTAMP_NTZ),
+        Some(Scalar::TimestampNtz(172_800_000_000))
+    );
+    assert_eq!(filter.get_max_stat(&col, &DataType::LONG), None);
+
+    // The same stats are not trusted from other writers
+    let with_created_by = |created_by: &str| {
+        FileMetaData::new(
+            file_metadata.version(),
+            file_metadata.num_rows(),
+            Some(created_by.to_string()),
+            None,
+            file_metadata.schema_descr_ptr(),
+            file_metadata.column_orders().cloned(),
+        )
+    };
+    let untrusted = with_created_by("parquet-rs version 54.3.1");
+    let filter = RowGroupFilter::new(&untrusted, row_group, &columns);
+    assert_eq!(filter.get_min_stat(&col, &DataType::TIMESTAMP), None);
+    assert_eq!(filter.get_max_stat(&col, &DataType::TIMESTAMP), None);
+
+    // Stats that decode to min > max were not produced by timestamp ordering
+    let Some(Statistics::Int96(stats)) = row_group.column(0).statistics() else {
+        panic!("expected INT96 stats");
+    };
+    let swapped = Statistics::int96(
+        stats.max_opt().cloned(),
+        stats.min_opt().cloned(),
+        None,
+        Some(0),
+        false,
+    );
+    let column = ColumnChunkMetaData::builder(row_group.column(0).column_descr_ptr())
+        .set_statistics(swapped)
+        .build()
+        .unwrap();
+    let invalid = RowGroupMetaData::builder(file_metadata.schema_descr_ptr())
+        .set_num_rows(3)
+        .set_column_metadata(vec![column])
+        .build()
+        .unwrap();
+    let filter = RowGroupFilter::new(file_metadata, &invalid, &columns);
+    assert_eq!(filter.get_min_stat(&col, &DataType::TIMESTAMP), None);
+    assert_eq!(filter.get_max_stat(&col, &DataType::TIMESTAMP), None);
+}
+
+#[test]
+fn test_nested_is_null() {
+    use crate::expressions::column_expr;
+
+    let file = File::open("./tests/data/parquet_row_group_skipping/part-00000-b92e017a-50ba-4676-8322-48fc371c2b59-c000.snappy.parquet").unwrap();
+    let metadata = ArrowReaderMetadata::load(&file, Default::default()).unwrap();
+    let row_group = metadata.metadata().row_group(0);
+    let filter = |predicate: &Predicate| {
+        RowGroupFilter::new(metadata.metadata().file_metadata(), row_group, predicate)
+    };
+
+    // Nested leaf columns use their own nullcount stats
+    let pred = Predicate::is_null(column_expr!("numeric.ints.int32"));
+    assert_eq!(
+        filter(&pred).get_nullcount_stat(&column_name!("numeric.ints.int32")),
+        Some(0i64.into())
+    );
+    assert!(!filter(&pred).apply(&pred));
+    let pred = Predicate::is_not_null(column_expr!("numeric.ints.int32"));
+    assert!(filter(&pred).apply(&pred));
+
+    // None of the leaves of the `numeric` struct has nulls, so neither does the struct
+    let pred = Predicate::is_null(column_expr!("numeric"));
+    assert_eq!(
+        filter(&pred).get_nullcount_stat(&column_name!("numeric")),
+        Some(0i64.into())
+    );
+    assert!(!filter(&pred).apply(&pred));
+    let pred = Predicate::is_not_null(column_expr!("numeric"));
+    assert!(filter(&pred).apply(&pred));
+}
+
+#[test]
+fn test_row_group_filter_trace() {
+    use crate::expressions::{column_expr, Expression as Expr};
+    use crate::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let file = File::open("./tests/data/parquet_row_group_skipping/part-00000-b92e017a-50ba-4676-8322-48fc371c2b59-c000.snappy.parquet").unwrap();
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let location = Url::parse("file:///part-00000.parquet").unwrap();
+
+    // The int32 values are 1000000..=1000004, and the INT96 timestamps have no stats
+    let clauses = [
+        Predicate::gt(column_expr!("numeric.ints.int32"), Expr::literal(1000010)),
+        Predicate::gt(column_expr!("numeric.ints.int64"), Expr::literal(0i64)),
+        Predicate::gt(
+            column_expr!("chrono.timestamp"),
+            Expr::literal(Scalar::Timestamp(0)),
+        ),
+    ];
+    let predicate = Predicate::and_from(clauses.clone());
+    let trace = SkippingTrace::new();
+    let builder = builder.with_row_group_filter(&predicate, None, Some((&trace, &location)));
+    assert!(builder.build().unwrap().next().is_none());
+
+    let expected_results = [Some(false), Some(true), None];
+    let expected = SkippingTraceEntry {
+        target: SkippingTarget::RowGroup {
+            location,
+            ordinal: 0,
+        },
+        pruned: true,
+        clauses: clauses
+            .into_iter()
+            .zip(expected_results)
+            .map(|(clause, result)| ClauseResult { clause, result })
+            .collect(),
+    };
+    assert_eq!(trace.entries(), [expected]);
+}
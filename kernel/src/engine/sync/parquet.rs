@@ -35,7 +35,7 @@ fn try_create_from_parquet(
 
     // Filter row groups and row indexes if a predicate is provided
     if let Some(predicate) = predicate {
-        builder = builder.with_row_group_filter(predicate.as_ref(), row_indexes.as_mut());
+        builder = builder.with_row_group_filter(predicate.as_ref(), row_indexes.as_mut(), None);
     }
 
     let mut row_indexes = row_indexes.map(|rb| rb.into_iter());
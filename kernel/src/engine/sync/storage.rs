@@ -70,6 +70,20 @@ impl StorageHandler for SyncStorageHandler {
         });
         Ok(Box::new(iter))
     }
+
+    fn delete_files(&self, files: Vec<Url>) -> DeltaResult<()> {
+        for url in files {
+            let path = url
+                .to_file_path()
+                .map_err(|_| Error::generic("Can only delete from local filesystem"))?;
+            if let Err(e) = std::fs::remove_file(path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
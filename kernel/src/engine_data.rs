@@ -269,6 +269,9 @@ pub trait RowVisitor {
 ///   fn append_columns(&self, schema: SchemaRef, columns: Vec<ArrayData>) -> DeltaResult<Box<dyn EngineData>> {
 ///     todo!() // convert `SchemaRef` and `ArrayData` into local representation and append them
 ///   }
+///   fn apply_selection_vector(&self, selection_vector: &[bool]) -> DeltaResult<Box<dyn EngineData>> {
+///     todo!() // keep only the selected rows
+///   }
 /// }
 /// ```
 pub trait EngineData: AsAny {
@@ -314,4 +317,13 @@ pub trait EngineData: AsAny {
         schema: SchemaRef,
         columns: Vec<ArrayData>,
     ) -> DeltaResult<Box<dyn EngineData>>;
+
+    /// Return a new [`EngineData`] with only the rows of this data that `selection_vector`
+    /// selects (`true`), in their original order. The kernel uses this e.g. to only parse the
+    /// stats of the files that data skipping still has to consider.
+    ///
+    /// # Errors
+    /// Returns an error if the length of `selection_vector` doesn't match the number of rows.
+    fn apply_selection_vector(&self, selection_vector: &[bool])
+        -> DeltaResult<Box<dyn EngineData>>;
 }
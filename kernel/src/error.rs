@@ -2,11 +2,19 @@
 
 use std::{
     backtrace::{Backtrace, BacktraceStatus},
+    fmt,
     num::ParseIntError,
     str::Utf8Error,
 };
 
+use itertools::Itertools;
+use strum::{AsRefStr, Display as StrumDisplay};
+use url::Url;
+
+use crate::redact::Redacted;
+use crate::scan::predicate_binding::PredicateBindingError;
 use crate::schema::{DataType, StructType};
+use crate::table_features::UnsupportedFeature;
 use crate::table_properties::ParseIntervalError;
 use crate::Version;
 
@@ -32,6 +40,14 @@ pub enum Error {
         backtrace: Box<Backtrace>,
     },
 
+    /// An error annotated with structured context about where it occurred, see
+    /// [`Error::with_context`]
+    #[error("{source} ({context})")]
+    WithContext {
+        source: Box<Self>,
+        context: Box<ErrorContext>,
+    },
+
     /// An error performing operations on arrow data
     #[cfg(feature = "default-engine-base")]
     #[error(transparent)]
@@ -136,8 +152,9 @@ pub enum Error {
     #[error("No table metadata or protocol found in delta log.")]
     MissingMetadataAndProtocol,
 
-    /// A string failed to parse as the specified data type
-    #[error("Failed to parse value '{0}' as '{1}'")]
+    /// A string failed to parse as the specified data type. The value is redacted in the message,
+    /// see [`crate::redact`].
+    #[error("Failed to parse value '{}' as '{}'", Redacted(.0), .1)]
     ParseError(String, DataType),
 
     /// A tokio executor failed to join a task
@@ -206,6 +223,274 @@ pub enum Error {
     /// Schema mismatch has occurred or invalid schema used somewhere
     #[error("Schema error: {0}")]
     Schema(String),
+
+    /// The log violates an invariant of the Delta protocol (only detected by strict validation)
+    #[error("Invalid table state: {0}")]
+    InvalidTableState(String),
+
+    /// The table uses features that the kernel does not support, and that block the requested
+    /// operation
+    #[error("Unsupported table features: {}", .0.iter().join(", "))]
+    UnsupportedFeatures(Vec<UnsupportedFeature>),
+
+    /// A scan predicate references columns that do not exist in the scan's schema, or compares
+    /// them with values of incompatible types
+    #[error("Predicate does not bind to the scan schema: {}", .0.iter().join(", "))]
+    PredicateBinding(Vec<PredicateBindingError>),
+}
+
+/// A stable, machine-readable code for each kind of [`Error`], see [`Error::code`]. Codes serialize
+/// (via `Display` and `AsRef<str>`) as `SCREAMING_SNAKE_CASE` strings, e.g. `MISSING_VERSION`. New
+/// codes may be added over time, but existing codes keep their meaning.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, StrumDisplay, AsRefStr)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    Arrow,
+    CheckpointWrite,
+    EngineDataType,
+    Extract,
+    Generic,
+    #[strum(serialize = "IO")]
+    Io,
+    Internal,
+    Parquet,
+    ObjectStore,
+    ObjectStorePath,
+    Reqwest,
+    FileNotFound,
+    MissingColumn,
+    UnexpectedColumnType,
+    MissingData,
+    MissingVersion,
+    DeletionVector,
+    InvalidUrl,
+    MalformedJson,
+    MissingMetadata,
+    MissingProtocol,
+    InvalidProtocol,
+    MissingMetadataAndProtocol,
+    Parse,
+    JoinFailure,
+    #[strum(serialize = "UTF8")]
+    Utf8,
+    ParseInt,
+    InvalidColumnMappingMode,
+    InvalidTableLocation,
+    InvalidDecimal,
+    InvalidStructData,
+    InvalidExpression,
+    InvalidLogPath,
+    FileAlreadyExists,
+    Unsupported,
+    ParseInterval,
+    ChangeDataFeedUnsupported,
+    ChangeDataFeedIncompatibleSchema,
+    InvalidCheckpoint,
+    LiteralExpressionTransform,
+    Schema,
+    InvalidTableState,
+    UnsupportedFeatures,
+    PredicateBinding,
+}
+
+/// Structured context about where an [`Error`] occurred, see [`Error::with_context`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The root of the table the failed operation accessed
+    pub table_uri: Option<Url>,
+    /// The version of the table the failed operation accessed
+    pub version: Option<Version>,
+    /// The path of the file the failed operation accessed
+    pub file_path: Option<String>,
+}
+
+impl ErrorContext {
+    pub fn with_table_uri(mut self, table_uri: Url) -> Self {
+        self.table_uri = Some(table_uri);
+        self
+    }
+
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn with_file_path(mut self, file_path: impl ToString) -> Self {
+        self.file_path = Some(file_path.to_string());
+        self
+    }
+
+    /// Fills in the fields of this context that are not set from `other`.
+    fn merge(&mut self, other: ErrorContext) {
+        self.table_uri = self.table_uri.take().or(other.table_uri);
+        self.version = self.version.or(other.version);
+        self.file_path = self.file_path.take().or(other.file_path);
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table_uri = self.table_uri.as_ref().map(|uri| format!("table: {uri}"));
+        let version = self.version.map(|version| format!("version: {version}"));
+        let file_path = self.file_path.as_ref().map(|path| format!("file: {path}"));
+        write!(
+            f,
+            "{}",
+            [table_uri, version, file_path]
+                .into_iter()
+                .flatten()
+                .join(", ")
+        )
+    }
+}
+
+impl Error {
+    /// The underlying error, without the [`Error::Backtraced`] and [`Error::WithContext`] wrappers.
+    /// Match on this rather than on the error itself to handle specific kinds of errors.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Backtraced { source, .. } | Self::WithContext { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+
+    /// Like [`Error::root`], but takes ownership of the error, dropping its backtrace and context.
+    pub fn into_root(self) -> Self {
+        match self {
+            Self::Backtraced { source, .. } | Self::WithContext { source, .. } => {
+                source.into_root()
+            }
+            source => source,
+        }
+    }
+
+    /// The structured context attached to this error with [`Error::with_context`], if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::WithContext { context, .. } => Some(context),
+            Self::Backtraced { source, .. } => source.context(),
+            _ => None,
+        }
+    }
+
+    /// Annotates this error with structured context about where it occurred. If the error already
+    /// has context, only the fields it does not set yet are taken from `context`, because context
+    /// attached closer to the failure is more specific.
+    ///
+    /// The result is always an [`Error::WithContext`], so code that matches on error variants
+    /// should match on [`Error::root`] (or [`Error::into_root`]) instead.
+    #[must_use]
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Self::WithContext {
+                source,
+                context: mut existing,
+            } => {
+                existing.merge(context);
+                Self::WithContext {
+                    source,
+                    context: existing,
+                }
+            }
+            source => Self::WithContext {
+                source: Box::new(source),
+                context: Box::new(context),
+            },
+        }
+    }
+
+    /// The stable, machine-readable [`ErrorCode`] of this error.
+    pub fn code(&self) -> ErrorCode {
+        match self.root() {
+            Self::Backtraced { .. } | Self::WithContext { .. } => ErrorCode::Internal, // unreachable
+            #[cfg(feature = "default-engine-base")]
+            Self::Arrow(_) => ErrorCode::Arrow,
+            Self::CheckpointWrite(_) => ErrorCode::CheckpointWrite,
+            Self::EngineDataType(_) => ErrorCode::EngineDataType,
+            Self::Extract(..) => ErrorCode::Extract,
+            Self::Generic(_) | Self::GenericError { .. } => ErrorCode::Generic,
+            Self::IOError(_) => ErrorCode::Io,
+            Self::InternalError(_) => ErrorCode::Internal,
+            #[cfg(feature = "default-engine-base")]
+            Self::Parquet(_) => ErrorCode::Parquet,
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStore(_) => ErrorCode::ObjectStore,
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStorePath(_) => ErrorCode::ObjectStorePath,
+            #[cfg(feature = "default-engine-base")]
+            Self::Reqwest(_) => ErrorCode::Reqwest,
+            Self::FileNotFound(_) => ErrorCode::FileNotFound,
+            Self::MissingColumn(_) => ErrorCode::MissingColumn,
+            Self::UnexpectedColumnType(_) => ErrorCode::UnexpectedColumnType,
+            Self::MissingData(_) => ErrorCode::MissingData,
+            Self::MissingVersion => ErrorCode::MissingVersion,
+            Self::DeletionVector(_) => ErrorCode::DeletionVector,
+            Self::InvalidUrl(_) => ErrorCode::InvalidUrl,
+            Self::MalformedJson(_) => ErrorCode::MalformedJson,
+            Self::MissingMetadata => ErrorCode::MissingMetadata,
+            Self::MissingProtocol => ErrorCode::MissingProtocol,
+            Self::InvalidProtocol(_) => ErrorCode::InvalidProtocol,
+            Self::MissingMetadataAndProtocol => ErrorCode::MissingMetadataAndProtocol,
+            Self::ParseError(..) => ErrorCode::Parse,
+            Self::JoinFailure(_) => ErrorCode::JoinFailure,
+            Self::Utf8Error(_) => ErrorCode::Utf8,
+            Self::ParseIntError(_) => ErrorCode::ParseInt,
+            Self::InvalidColumnMappingMode(_) => ErrorCode::InvalidColumnMappingMode,
+            Self::InvalidTableLocation(_) => ErrorCode::InvalidTableLocation,
+            Self::InvalidDecimal(_) => ErrorCode::InvalidDecimal,
+            Self::InvalidStructData(_) => ErrorCode::InvalidStructData,
+            Self::InvalidExpressionEvaluation(_) => ErrorCode::InvalidExpression,
+            Self::InvalidLogPath(_) => ErrorCode::InvalidLogPath,
+            Self::FileAlreadyExists(_) => ErrorCode::FileAlreadyExists,
+            Self::Unsupported(_) => ErrorCode::Unsupported,
+            Self::ParseIntervalError(_) => ErrorCode::ParseInterval,
+            Self::ChangeDataFeedUnsupported(_) => ErrorCode::ChangeDataFeedUnsupported,
+            Self::ChangeDataFeedIncompatibleSchema(..) => {
+                ErrorCode::ChangeDataFeedIncompatibleSchema
+            }
+            Self::InvalidCheckpoint(_) => ErrorCode::InvalidCheckpoint,
+            Self::LiteralExpressionTransformError(_) => ErrorCode::LiteralExpressionTransform,
+            Self::Schema(_) => ErrorCode::Schema,
+            Self::InvalidTableState(_) => ErrorCode::InvalidTableState,
+            Self::UnsupportedFeatures(_) => ErrorCode::UnsupportedFeatures,
+            Self::PredicateBinding(_) => ErrorCode::PredicateBinding,
+        }
+    }
+
+    /// Whether the failed operation may succeed if retried as is, because the error is likely
+    /// transient: I/O errors such as timeouts and connection resets, generic object store errors
+    /// (which the object store raises once its own retries are exhausted), and HTTP timeouts,
+    /// connection errors, server errors and throttling.
+    ///
+    /// NOTE: A commit that fails with [`Error::FileAlreadyExists`] is not retryable as is: it
+    /// conflicts with a concurrent commit, and must be rebased onto it first.
+    pub fn is_retryable(&self) -> bool {
+        use std::io::ErrorKind;
+        match self.root() {
+            Self::IOError(e) => matches!(
+                e.kind(),
+                ErrorKind::TimedOut
+                    | ErrorKind::Interrupted
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::ConnectionRefused
+                    | ErrorKind::BrokenPipe
+            ),
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStore(e) => matches!(e, object_store::Error::Generic { .. }),
+            #[cfg(feature = "default-engine-base")]
+            Self::Reqwest(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|status| {
+                        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            _ => false,
+        }
+    }
 }
 
 // Convenience constructors for Error types that take a String argument
@@ -291,6 +576,10 @@ impl Error {
         Self::Schema(msg.to_string())
     }
 
+    pub fn invalid_table_state(msg: impl ToString) -> Self {
+        Self::InvalidTableState(msg.to_string())
+    }
+
     // Capture a backtrace when the error is constructed.
     #[must_use]
     pub fn with_backtrace(self) -> Self {
@@ -338,3 +627,57 @@ impl From<object_store::Error> for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_and_retryability() {
+        let timed_out = Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert_eq!(timed_out.code(), ErrorCode::Io);
+        assert!(timed_out.is_retryable());
+
+        let not_found = Error::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(!not_found.is_retryable());
+        assert!(!Error::FileAlreadyExists("00.json".to_string()).is_retryable());
+
+        assert_eq!(Error::MissingVersion.code().as_ref(), "MISSING_VERSION");
+        assert_eq!(ErrorCode::Io.to_string(), "IO");
+    }
+
+    #[test]
+    fn test_with_context() {
+        let uri = Url::parse("memory:///table/").unwrap();
+        let error = Error::generic("boom")
+            .with_context(ErrorContext::default().with_file_path("a.parquet"))
+            .with_context(
+                ErrorContext::default()
+                    .with_table_uri(uri.clone())
+                    .with_file_path("b.parquet"),
+            );
+
+        // the innermost context wins, and the wrapper is transparent to code and root
+        let context = error.context().unwrap();
+        assert_eq!(context.table_uri, Some(uri));
+        assert_eq!(context.version, None);
+        assert_eq!(context.file_path.as_deref(), Some("a.parquet"));
+        assert_eq!(error.code(), ErrorCode::Generic);
+        assert!(matches!(error.root(), Error::Generic(msg) if msg == "boom"));
+        assert_eq!(
+            error.to_string(),
+            "Generic delta kernel error: boom (table: memory:///table/, file: a.parquet)"
+        );
+        assert!(matches!(error.into_root(), Error::Generic(msg) if msg == "boom"));
+    }
+
+    #[test]
+    fn test_parse_error_redacts_value() {
+        let _lock = crate::redact::tests::REDACTION_LOCK.lock().unwrap();
+        let error = Error::ParseError("alice".to_string(), DataType::INTEGER);
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse value '<redacted>' as 'integer'"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{DeltaResult, Error};
 
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::iter::Peekable;
@@ -36,10 +36,57 @@ impl ColumnName {
     ///     ColumnName::new([" \"a", "b\" ", " c "])
     /// );
     /// ```
+    ///
+    /// Use [`ColumnName::parse`] instead to unambiguously reference nested fields, including field
+    /// names that contain periods.
     pub fn from_naive_str_split(name: impl AsRef<str>) -> Self {
         Self::new(name.as_ref().split(FIELD_SEPARATOR))
     }
 
+    /// Parses a column name, properly accounting for escapes and special characters. Fields are
+    /// separated by periods, and field names containing special characters must be enclosed in
+    /// backticks, following the same rules as Delta Spark (see [`FromStr`](#impl-FromStr-for-ColumnName)
+    /// for details):
+    ///
+    /// ```
+    /// # use delta_kernel::expressions::ColumnName;
+    /// assert_eq!(
+    ///     ColumnName::parse("a.`b.c`.`d``e`").unwrap(),
+    ///     ColumnName::new(["a", "b.c", "d`e"])
+    /// );
+    /// assert!(ColumnName::parse("a.b c").is_err());
+    /// ```
+    pub fn parse(name: impl AsRef<str>) -> DeltaResult<Self> {
+        name.as_ref().parse()
+    }
+
+    /// Quotes a single field name for use in a column name string, enclosing it in backticks (and
+    /// doubling any backticks it contains) unless it is a simple field name that can be used as-is.
+    /// The [`Display`] implementation of `ColumnName` quotes each of its fields this way:
+    ///
+    /// ```
+    /// # use delta_kernel::expressions::ColumnName;
+    /// assert_eq!(ColumnName::quote_field_name("a_1"), "a_1");
+    /// assert_eq!(ColumnName::quote_field_name("b.c"), "`b.c`");
+    /// assert_eq!(ColumnName::quote_field_name("d`e"), "`d``e`");
+    /// assert_eq!(ColumnName::quote_field_name("1a"), "`1a`");
+    /// ```
+    pub fn quote_field_name(name: &str) -> Cow<'_, str> {
+        if !needs_escape(name) {
+            return Cow::Borrowed(name);
+        }
+        let mut quoted = String::with_capacity(name.len() + 2);
+        quoted.push(FIELD_ESCAPE_CHAR);
+        for c in name.chars() {
+            quoted.push(c);
+            if c == FIELD_ESCAPE_CHAR {
+                quoted.push(c); // escape the escape by doubling
+            }
+        }
+        quoted.push(FIELD_ESCAPE_CHAR);
+        Cow::Owned(quoted)
+    }
+
     /// Parses a comma-separated list of column names, properly accounting for escapes and special
     /// characters, e.g.:
     ///
@@ -194,22 +241,7 @@ impl Display for ColumnName {
                 f.write_char(FIELD_SEPARATOR)?;
             }
 
-            let digit_char = |c: char| c.is_ascii_digit();
-            if s.is_empty() || s.starts_with(digit_char) || s.contains(|c| !is_simple_char(c)) {
-                // Special situation detected. For safety, surround the field name with backticks
-                // (with proper escaping if the field name itself contains backticks).
-                f.write_char(FIELD_ESCAPE_CHAR)?;
-                for c in s.chars() {
-                    f.write_char(c)?;
-                    if c == FIELD_ESCAPE_CHAR {
-                        f.write_char(c)?; // escape the escape by doubling
-                    }
-                }
-                f.write_char(FIELD_ESCAPE_CHAR)?;
-            } else {
-                // Simple field name -- emit it as-is
-                f.write_str(s)?;
-            }
+            f.write_str(&Self::quote_field_name(s))?;
         }
         Ok(())
     }
@@ -220,6 +252,13 @@ fn is_simple_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_'
 }
 
+// Field names that are empty, start with a digit, or contain non-simple chars must be escaped.
+fn needs_escape(name: &str) -> bool {
+    name.is_empty()
+        || name.starts_with(|c: char| c.is_ascii_digit())
+        || !name.chars().all(is_simple_char)
+}
+
 fn drop_leading_whitespace(iter: &mut Peekable<impl Iterator<Item = char>>) {
     while iter.next_if(|c| c.is_whitespace()).is_some() {}
 }
@@ -636,6 +675,32 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_quote_field_name() {
+        let cases = [
+            ("a", "a"),
+            ("a_0", "a_0"),
+            ("", "``"),
+            ("0a", "`0a`"),
+            ("a b", "`a b`"),
+            ("a.b", "`a.b`"),
+            ("a`b", "`a``b`"),
+            ("ä", "`ä`"),
+        ];
+        for (field, expected) in cases {
+            let quoted = ColumnName::quote_field_name(field);
+            assert_eq!(quoted, expected);
+            assert_eq!(
+                ColumnName::parse(&quoted).unwrap(),
+                ColumnName::new([field])
+            );
+        }
+        assert!(matches!(
+            ColumnName::quote_field_name("a"),
+            Cow::Borrowed("a")
+        ));
+    }
+
     #[test]
     fn test_parse_column_name_list() {
         let cases = [
@@ -57,6 +57,9 @@ pub enum BinaryPredicateOp {
 pub enum UnaryExpressionOp {
     /// Convert struct data to JSON-encoded strings
     ToJson,
+    /// Parse JSON-encoded strings into struct data. The struct type to parse is the type expected
+    /// of the expression's result, and null strings produce null structs.
+    ParseJson,
 }
 
 /// A binary expression operator.
@@ -294,7 +297,8 @@ pub struct FieldTransform {
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Transform {
     /// The path to the nested input struct this transform operates on (if any). If no path is
-    /// given, the transform operates directly on top-level columns.
+    /// given, the transform operates directly on top-level columns. The output of a nested
+    /// transform is null wherever its input struct is null.
     pub input_path: Option<ColumnName>,
     /// A mapping from named input fields to the transform to be performed on each field.
     pub field_transforms: HashMap<String, FieldTransform>,
@@ -654,6 +658,23 @@ impl Predicate {
         references.into_inner()
     }
 
+    /// Returns the top-level conjuncts of this predicate, flattening any nested AND. A predicate
+    /// that is not an AND is its own (only) conjunct.
+    pub(crate) fn conjuncts(&self) -> Vec<&Predicate> {
+        fn collect<'a>(predicate: &'a Predicate, conjuncts: &mut Vec<&'a Predicate>) {
+            match predicate {
+                Predicate::Junction(JunctionPredicate {
+                    op: JunctionPredicateOp::And,
+                    preds,
+                }) => preds.iter().for_each(|pred| collect(pred, conjuncts)),
+                _ => conjuncts.push(predicate),
+            }
+        }
+        let mut conjuncts = vec![];
+        collect(self, &mut conjuncts);
+        conjuncts
+    }
+
     /// Creates a new boolean column reference. See also [`Expression::column`].
     pub fn column<A>(field_names: impl IntoIterator<Item = A>) -> Predicate
     where
@@ -807,6 +828,7 @@ impl Display for UnaryExpressionOp {
         use UnaryExpressionOp::*;
         match self {
             ToJson => write!(f, "TO_JSON"),
+            ParseJson => write!(f, "PARSE_JSON"),
         }
     }
 }
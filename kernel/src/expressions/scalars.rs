@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
 use itertools::Itertools;
 
 use crate::schema::derive_macro_utils::ToDataType;
@@ -240,6 +240,12 @@ pub enum Scalar {
     Timestamp(i64),
     /// Microsecond precision timestamp, with no timezone.
     TimestampNtz(i64),
+    /// Microsecond precision timestamp, adjusted to UTC, of a literal that was given with an
+    /// explicit UTC offset (in seconds), e.g. by an engine in a non-UTC session. Its type is
+    /// `TIMESTAMP`. When a scan predicate compares it with a `TIMESTAMP` column, it denotes the
+    /// instant (like a [`Scalar::Timestamp`]); when it compares it with a `TIMESTAMP_NTZ` column,
+    /// it denotes its local time at the offset (like a [`Scalar::TimestampNtz`]).
+    TimestampWithOffset(i64, i32),
     /// Date stored as a signed 32bit int days since UNIX epoch 1970-01-01
     Date(i32),
     /// Binary data
@@ -269,6 +275,7 @@ impl Scalar {
             Self::Boolean(_) => DataType::BOOLEAN,
             Self::Timestamp(_) => DataType::TIMESTAMP,
             Self::TimestampNtz(_) => DataType::TIMESTAMP_NTZ,
+            Self::TimestampWithOffset(..) => DataType::TIMESTAMP,
             Self::Date(_) => DataType::DATE,
             Self::Binary(_) => DataType::BINARY,
             Self::Decimal(d) => DataType::from(*d.ty()),
@@ -301,6 +308,13 @@ impl Scalar {
         Ok(Self::Timestamp(timestamp.timestamp_micros()))
     }
 
+    /// Constructs a timestamp that keeps the UTC offset of `timestamp` in the time zone it was
+    /// given in, see [`Scalar::TimestampWithOffset`].
+    pub fn timestamp_with_offset<Tz: TimeZone>(timestamp: &DateTime<Tz>) -> Self {
+        let offset = timestamp.offset().fix().local_minus_utc();
+        Self::TimestampWithOffset(timestamp.timestamp_micros(), offset)
+    }
+
     /// Attempts to add two scalars, returning None if they were incompatible.
     pub fn try_add(&self, other: &Scalar) -> Option<Scalar> {
         use Scalar::*;
@@ -367,6 +381,10 @@ impl Display for Scalar {
             Self::Boolean(b) => write!(f, "{b}"),
             Self::Timestamp(ts) => write!(f, "{ts}"),
             Self::TimestampNtz(ts) => write!(f, "{ts}"),
+            Self::TimestampWithOffset(ts, offset) => match FixedOffset::east_opt(*offset) {
+                Some(offset) => write!(f, "{ts}{offset}"),
+                None => write!(f, "{ts}"),
+            },
             Self::Date(d) => write!(f, "{d}"),
             Self::Binary(b) => write!(f, "{b:?}"),
             Self::Decimal(d) => match d.scale().cmp(&0) {
@@ -458,6 +476,11 @@ impl PartialOrd for Scalar {
             (Timestamp(_), _) => None,
             (TimestampNtz(a), TimestampNtz(b)) => a.partial_cmp(b),
             (TimestampNtz(_), _) => None,
+            // timestamps with different offsets denote different local times
+            (TimestampWithOffset(a, o1), TimestampWithOffset(b, o2)) if o1 == o2 => {
+                a.partial_cmp(b)
+            }
+            (TimestampWithOffset(..), _) => None,
             (Date(a), Date(b)) => a.partial_cmp(b),
             (Date(_), _) => None,
             (Binary(a), Binary(b)) => a.partial_cmp(b),
@@ -467,13 +490,47 @@ impl PartialOrd for Scalar {
                 .flatten(),
             (Decimal(_), _) => None,
             (Null(_), _) => None, // NOTE: NULL values are incomparable by definition
-            (Struct(_), _) => None, // TODO: Support Struct?
-            (Array(_), _) => None, // TODO: Support Array?
-            (Map(_), _) => None,  // TODO: Support Map?
+            (Struct(a), Struct(b)) => {
+                if a.fields.len() != b.fields.len() {
+                    return None;
+                }
+                let fields = a.fields.iter().zip(&b.fields);
+                if fields
+                    .clone()
+                    .any(|(f1, f2)| f1.data_type() != f2.data_type())
+                {
+                    return None;
+                }
+                cmp_nested_values(&a.values, &b.values)
+            }
+            (Struct(_), _) => None,
+            (Array(a), Array(b)) if a.tpe.element_type() == b.tpe.element_type() => {
+                cmp_nested_values(&a.elements, &b.elements)
+            }
+            (Array(_), _) => None,
+            (Map(_), _) => None, // TODO: Support Map?
         }
     }
 }
 
+/// Compares the fields of two structs or the elements of two arrays lexicographically, like Spark
+/// does: nested nulls compare equal to each other and less than any other value, so that e.g.
+/// `{a: 1, b: null}` equals itself (while a top-level null is incomparable).
+fn cmp_nested_values(a: &[Scalar], b: &[Scalar]) -> Option<Ordering> {
+    for (a, b) in a.iter().zip(b) {
+        let ord = match (a, b) {
+            (Scalar::Null(_), Scalar::Null(_)) => Ordering::Equal,
+            (Scalar::Null(_), _) => Ordering::Less,
+            (_, Scalar::Null(_)) => Ordering::Greater,
+            (a, b) => a.partial_cmp(b)?,
+        };
+        if ord != Ordering::Equal {
+            return Some(ord);
+        }
+    }
+    Some(a.len().cmp(&b.len()))
+}
+
 impl From<i8> for Scalar {
     fn from(i: i8) -> Self {
         Self::Byte(i)
@@ -610,6 +667,12 @@ impl<T: Into<Scalar> + ToDataType> From<Option<T>> for Scalar {
     }
 }
 
+impl From<StructData> for Scalar {
+    fn from(struct_data: StructData) -> Self {
+        Self::Struct(struct_data)
+    }
+}
+
 impl From<ArrayData> for Scalar {
     fn from(array_data: ArrayData) -> Self {
         Self::Array(array_data)
@@ -679,6 +742,12 @@ impl PrimitiveType {
                     // Note: `%+` specifies the ISO 8601 / RFC 3339 format
                     timestamp = NaiveDateTime::parse_from_str(raw, "%+");
                 }
+                if timestamp.is_err() && *self == TimestampNtz {
+                    // A date column widened to timestampNtz keeps its old partition values, which
+                    // are serialized as plain dates.
+                    timestamp = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                        .map(|date| date.and_time(NaiveTime::MIN));
+                }
                 let timestamp = timestamp.map_err(|_| self.parse_error(raw))?;
                 let timestamp = Utc.from_utc_datetime(&timestamp);
                 let micros = timestamp
@@ -745,11 +814,17 @@ impl PrimitiveType {
         // most i128::MAX, and 0-i128::MAX doesn't underflow
         let scale = frac_digits - exp;
         let scale: u8 = scale.try_into().map_err(|_| parse_error())?;
-        require!(scale == dtype.scale(), parse_error());
+        // A value with fewer fractional digits than the type (e.g. a partition value written
+        // before type widening increased the scale) is padded to the type's scale.
+        require!(scale <= dtype.scale(), parse_error());
         let int: i128 = match frac_part {
             None => int_part.parse()?,
             Some(frac_part) => format!("{int_part}{frac_part}").parse()?,
         };
+        let int = 10i128
+            .checked_pow((dtype.scale() - scale).into())
+            .and_then(|factor| int.checked_mul(factor))
+            .ok_or_else(parse_error)?;
         Ok(Scalar::Decimal(DecimalData::try_new(int, dtype)?))
     }
 }
@@ -879,6 +954,9 @@ mod tests {
         assert_decimal("1234.5E-4", 12345, 5, 5)?;
         assert_decimal("-0", 0, 1, 0)?;
         assert_decimal("12.000000000000000000", 12000000000000000000, 38, 18)?;
+        // fewer fractional digits than the type's scale, e.g. after type widening
+        assert_decimal("123", 12300, 5, 2)?;
+        assert_decimal("12.3", 12300, 5, 3)?;
         Ok(())
     }
 
@@ -1017,6 +1095,8 @@ mod tests {
         assert_timestamp_eq("2011-01-11 13:06:07", 1294751167000000);
         assert_timestamp_eq("2011-01-11 13:06:07.123456", 1294751167123456);
         assert_timestamp_eq("1970-01-01 00:00:00", 0);
+        // date partition values of a column widened from date to timestamp_ntz
+        assert_timestamp_eq("2011-01-11", 1294704000000000);
     }
 
     #[test]
@@ -1029,12 +1109,32 @@ mod tests {
         let p_type = PrimitiveType::TimestampNtz;
         assert_timestamp_fails(&p_type, "1971-07-22T03:06:40.678910Z");
         assert_timestamp_fails(&p_type, "1971-07-22T03:06:40Z");
-        assert_timestamp_fails(&p_type, "1971-07-22");
 
         let p_type = PrimitiveType::Timestamp;
         assert_timestamp_fails(&p_type, "1971-07-22");
     }
 
+    #[test]
+    fn test_timestamp_with_offset() {
+        let offset = FixedOffset::east_opt(2 * 60 * 60).unwrap();
+        let timestamp = offset.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let scalar = Scalar::timestamp_with_offset(&timestamp);
+        // the value is adjusted to UTC, i.e. 2024-01-01 08:00:00
+        assert_eq!(
+            scalar,
+            Scalar::TimestampWithOffset(1_704_096_000_000_000, 7200)
+        );
+        assert_eq!(scalar.data_type(), DataType::TIMESTAMP);
+        assert_eq!(
+            Scalar::timestamp_with_offset(&timestamp.with_timezone(&Utc)),
+            Scalar::TimestampWithOffset(1_704_096_000_000_000, 0)
+        );
+        // only timestamps with the same offset compare
+        let later = Scalar::TimestampWithOffset(1_704_096_000_000_001, 7200);
+        assert!(scalar < later);
+        assert_eq!(scalar.partial_cmp(&Scalar::Timestamp(0)), None);
+    }
+
     #[test]
     fn test_partial_cmp() {
         let a = Scalar::Integer(1);
@@ -1052,6 +1152,48 @@ mod tests {
         assert_eq!(null.partial_cmp(&null), None);
     }
 
+    #[test]
+    fn test_partial_cmp_nested() {
+        let fields = vec![
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::STRING),
+        ];
+        let make_struct = |a: Option<i32>, b: Option<&str>| {
+            Scalar::from(
+                StructData::try_new(fields.clone(), vec![a.into(), b.map(String::from).into()])
+                    .unwrap(),
+            )
+        };
+        let s1 = make_struct(Some(1), Some("x"));
+        let s2 = make_struct(Some(1), Some("y"));
+        let s3 = make_struct(Some(1), None);
+        assert_eq!(s1.partial_cmp(&s1), Some(Ordering::Equal));
+        assert_eq!(s1.partial_cmp(&s2), Some(Ordering::Less));
+        assert_eq!(s2.partial_cmp(&s1), Some(Ordering::Greater));
+        // nested nulls are equal to each other, and less than any other value
+        assert_eq!(s3, s3.clone());
+        assert_eq!(s3.partial_cmp(&s1), Some(Ordering::Less));
+        // structs of different types are incomparable
+        let other = StructData::try_new(
+            vec![StructField::nullable("a", DataType::LONG)],
+            vec![1i64.into()],
+        )
+        .unwrap();
+        assert_eq!(s1.partial_cmp(&other.into()), None);
+
+        let a1 = Scalar::try_from(vec![1, 2]).unwrap();
+        let a2 = Scalar::try_from(vec![1, 3]).unwrap();
+        let a3 = Scalar::try_from(vec![1, 2, 0]).unwrap();
+        assert_eq!(a1, a1.clone());
+        assert_eq!(a1.partial_cmp(&a2), Some(Ordering::Less));
+        // a prefix is less than the longer array
+        assert_eq!(a1.partial_cmp(&a3), Some(Ordering::Less));
+        // the nullability of the elements doesn't matter
+        let a4 = Scalar::try_from(vec![Some(1), Some(2)]).unwrap();
+        assert_eq!(a1, a4);
+        assert_eq!(a1.partial_cmp(&Scalar::try_from(vec![1i64]).unwrap()), None);
+    }
+
     #[test]
     fn test_partial_eq() {
         let a = Scalar::Integer(1);
@@ -523,6 +523,28 @@ impl KernelPredicateEvaluatorDefaults {
         Some(matched != inverted)
     }
 
+    /// Directly evaluates a (possibly inverted) IN-list check of a scalar against an array scalar.
+    /// Like in SQL, the result is NULL if the value is NULL, or if it matches no element of a list
+    /// that contains NULLs. Struct and array values are compared like [`Scalar`]'s `PartialOrd`.
+    pub fn eval_pred_in_scalars(val: &Scalar, list: &Scalar, inverted: bool) -> Option<bool> {
+        let Scalar::Array(list) = list else {
+            debug!("Unsupported right value for IN: {list:?}");
+            return None;
+        };
+        if val.is_null() {
+            return None;
+        }
+        #[allow(deprecated)]
+        let elements = list.array_elements();
+        if elements.iter().any(|element| val == element) {
+            Some(!inverted)
+        } else if elements.iter().any(Scalar::is_null) {
+            None
+        } else {
+            Some(inverted)
+        }
+    }
+
     /// Directly evaluates a boolean comparison. See [`KernelPredicateEvaluator::eval_pred_binary_scalars`].
     pub fn eval_pred_binary_scalars(
         op: BinaryPredicateOp,
@@ -535,7 +557,8 @@ impl KernelPredicateEvaluatorDefaults {
             Equal => Self::partial_cmp_scalars(Ordering::Equal, left, right, inverted),
             LessThan => Self::partial_cmp_scalars(Ordering::Less, left, right, inverted),
             GreaterThan => Self::partial_cmp_scalars(Ordering::Greater, left, right, inverted),
-            Distinct | In => {
+            In => Self::eval_pred_in_scalars(left, right, inverted),
+            Distinct => {
                 debug!("Unsupported binary operator: {left:?} {op:?} {right:?}");
                 None
             }
@@ -678,6 +701,11 @@ impl<R: ResolveColumnAsScalar> KernelPredicateEvaluator for DefaultKernelPredica
         self.eval_pred_binary_scalars(BinaryPredicateOp::Equal, &col, val, inverted)
     }
 
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<bool> {
+        let col = self.resolve_column(col)?;
+        self.eval_pred_binary_scalars(BinaryPredicateOp::In, &col, val, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
@@ -780,10 +808,33 @@ pub trait DataSkippingPredicateEvaluator {
     ///
     /// NOTE: When deletion vectors are enabled, they could produce a file that is logically
     /// all-null or logically no-null, even tho the physical stats indicate a mix of null and
-    /// non-null values. They cannot invalidate a file's physical all-null or non-null status,
-    /// however, so the worst that can happen is we fail to skip an unnecessary file.
+    /// non-null values. Stats with wide bounds (see [`Self::eval_wide_bounds`]) make no promises
+    /// about null counts, so implementations should guard their result with
+    /// [`Self::unless_wide_bounds`].
     fn eval_pred_is_null(&self, col: &ColumnName, inverted: bool) -> Option<Self::Output>;
 
+    /// Evaluates to TRUE if the stats being evaluated are known to have wide bounds, i.e. the
+    /// file has a deletion vector and its stats were not recomputed after the delete
+    /// (`tightBounds = false`). Such stats still bound the remaining values, but null counts and
+    /// exact-value conclusions (e.g. `min = max = val`) derived from them are unsound.
+    ///
+    /// Returns None (the default) if the stats are always tight.
+    fn eval_wide_bounds(&self) -> Option<Self::Output> {
+        None
+    }
+
+    /// Guards a skipping conclusion that is only valid for tight bounds, so that it becomes
+    /// `output OR wide_bounds` -- i.e. we always keep files whose stats have wide bounds.
+    fn unless_wide_bounds(&self, output: Option<Self::Output>) -> Option<Self::Output> {
+        match self.eval_wide_bounds() {
+            Some(wide_bounds) => {
+                let mut preds = [output, Some(wide_bounds)].into_iter();
+                self.finish_eval_pred_junction(JunctionPredicateOp::Or, &mut preds, false)
+            }
+            None => output,
+        }
+    }
+
     /// See [`KernelPredicateEvaluator::eval_pred_binary_scalars`]
     fn eval_pred_binary_scalars(
         &self,
@@ -891,22 +942,27 @@ pub trait DataSkippingPredicateEvaluator {
 
     /// See [`KernelPredicateEvaluator::eval_pred_eq`]
     fn eval_pred_eq(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
-        let (op, preds) = if inverted {
+        if inverted {
             // Column could compare not-equal if min or max value differs from the literal.
+            // NOTE: Equality-only pruning (min = max = val) is unsound for wide bounds.
             let preds = [
                 self.partial_cmp_min_stat(col, val, Ordering::Equal, true),
                 self.partial_cmp_max_stat(col, val, Ordering::Equal, true),
             ];
-            (JunctionPredicateOp::Or, preds)
+            let output = self.finish_eval_pred_junction(
+                JunctionPredicateOp::Or,
+                &mut preds.into_iter(),
+                false,
+            );
+            self.unless_wide_bounds(output)
         } else {
             // Column could compare equal if its min/max values bracket the literal.
             let preds = [
                 self.partial_cmp_min_stat(col, val, Ordering::Greater, true),
                 self.partial_cmp_max_stat(col, val, Ordering::Less, true),
             ];
-            (JunctionPredicateOp::And, preds)
-        };
-        self.finish_eval_pred_junction(op, &mut preds.into_iter(), false)
+            self.finish_eval_pred_junction(JunctionPredicateOp::And, &mut preds.into_iter(), false)
+        }
     }
 }
 
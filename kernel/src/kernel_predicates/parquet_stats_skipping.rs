@@ -79,6 +79,12 @@ impl<T: ParquetStatsProvider> DataSkippingPredicateEvaluator for T {
         Some(self.get_nullcount_stat(col)? != safe_to_skip)
     }
 
+    // Footer stats always describe exactly the physical rows of the row group (deletion vectors
+    // are applied after the read), so their bounds are always tight.
+    fn eval_wide_bounds(&self) -> Option<bool> {
+        None
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
@@ -5,7 +5,7 @@ use crate::expressions::{
 };
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
 use crate::scan::data_skipping::as_data_skipping_predicate;
-use crate::schema::ArrayType;
+use crate::schema::{ArrayType, StructField};
 use crate::DataType;
 use crate::DeltaResult;
 
@@ -356,6 +356,63 @@ fn test_eval_binary_scalars() {
     }
 }
 
+#[test]
+fn test_eval_in() {
+    let fields = vec![StructField::nullable("a", DataType::INTEGER)];
+    let make_struct =
+        |a: Option<i32>| Scalar::from(StructData::try_new(fields.clone(), vec![a.into()]).unwrap());
+    let list = ArrayData::try_new(
+        ArrayType::new(DataType::struct_type_unchecked(fields.clone()), true),
+        [
+            make_struct(Some(1)),
+            make_struct(None),
+            Scalar::Null(DataType::struct_type_unchecked(fields.clone())),
+        ],
+    )
+    .unwrap();
+    let filter = DefaultKernelPredicateEvaluator::from(HashMap::from_iter([
+        (column_name!("x"), make_struct(Some(1))),
+        (column_name!("y"), make_struct(None)),
+        (column_name!("z"), make_struct(Some(2))),
+    ]));
+    for inverted in [true, false] {
+        let eval_in = |col: &str| {
+            filter.eval_pred_in(&ColumnName::new([col]), &list.clone().into(), inverted)
+        };
+        expect_eq!(
+            eval_in("x"),
+            Some(!inverted),
+            "x IN list (inverted: {inverted})"
+        );
+        // nested nulls are equal
+        expect_eq!(
+            eval_in("y"),
+            Some(!inverted),
+            "y IN list (inverted: {inverted})"
+        );
+        // z might be the null element
+        expect_eq!(eval_in("z"), None, "z IN list (inverted: {inverted})");
+    }
+
+    let list = Scalar::try_from(vec![1, 2]).unwrap();
+    let compare = KernelPredicateEvaluatorDefaults::eval_pred_in_scalars;
+    expect_eq!(
+        compare(&Scalar::from(2), &list, false),
+        Some(true),
+        "2 IN (1, 2)"
+    );
+    expect_eq!(
+        compare(&Scalar::from(3), &list, false),
+        Some(false),
+        "3 IN (1, 2)"
+    );
+    expect_eq!(
+        compare(&Scalar::Null(DataType::INTEGER), &list, false),
+        None,
+        "NULL IN (1, 2)"
+    );
+}
+
 // NOTE: We're testing routing here -- the actual comparisons are already validated by test_eval_binary_scalars.
 #[test]
 fn test_eval_binary_columns() {
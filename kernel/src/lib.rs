@@ -87,19 +87,27 @@ use self::schema::{DataType, SchemaRef};
 mod action_reconciliation;
 pub mod actions;
 pub mod checkpoint;
+pub mod clustering;
 pub mod engine_data;
 pub mod error;
 pub mod expressions;
+mod log_cleanup;
 mod log_compaction;
+pub mod optimize;
+pub mod partition_values;
+pub mod redact;
 pub mod scan;
 pub mod schema;
 pub mod snapshot;
+pub mod stats;
 pub mod table_changes;
 pub mod table_configuration;
 pub mod table_features;
 pub mod table_properties;
 pub mod transaction;
 pub(crate) mod transforms;
+pub mod uri;
+pub mod verify;
 
 mod row_tracking;
 
@@ -146,7 +154,7 @@ pub(crate) mod history_manager;
 
 pub use delta_kernel_derive;
 pub use engine_data::{EngineData, RowVisitor};
-pub use error::{DeltaResult, Error};
+pub use error::{DeltaResult, Error, ErrorCode, ErrorContext};
 pub use expressions::{Expression, ExpressionRef, Predicate, PredicateRef};
 pub use log_compaction::{should_compact, LogCompactionDataIterator, LogCompactionWriter};
 pub use snapshot::Snapshot;
@@ -380,6 +388,20 @@ pub trait ExpressionEvaluator: AsAny {
     /// Produces one value for each row of the input.
     /// The data type of the output is same as the type output of the expression this evaluator is using.
     fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>>;
+
+    /// Evaluate the expression on each batch of `batches`, lazily producing one output for each
+    /// input batch (or the error reading it).
+    ///
+    /// This is a streaming convenience: by default, each batch is evaluated with
+    /// [`Self::evaluate`]. Since batches of the same stream (e.g. the batches read from one file)
+    /// usually share a schema, connectors can override it to do per-schema work once per stream
+    /// rather than once per batch.
+    fn evaluate_batches(
+        self: Arc<Self>,
+        batches: FileDataReadResultIterator,
+    ) -> FileDataReadResultIterator {
+        Box::new(batches.map(move |batch| self.evaluate(batch?.as_ref())))
+    }
 }
 
 /// Trait for implementing a Predicate evaluator.
@@ -423,6 +445,24 @@ pub trait EvaluationHandler: AsAny {
         output_type: DataType,
     ) -> Arc<dyn ExpressionEvaluator>;
 
+    /// Like [`Self::new_expression_evaluator`], but columns of `input_schema` (including nested
+    /// fields) that a batch lacks are treated as all-null columns of the type `input_schema`
+    /// declares, instead of failing the evaluation. This allows evaluating an expression over
+    /// batches whose schema is older than `input_schema`, e.g. when the table schema evolved.
+    ///
+    /// The default implementation returns an [`Error::Unsupported`].
+    fn new_missing_column_tolerant_evaluator(
+        &self,
+        input_schema: SchemaRef,
+        expression: ExpressionRef,
+        output_type: DataType,
+    ) -> DeltaResult<Arc<dyn ExpressionEvaluator>> {
+        let _ = (input_schema, expression, output_type);
+        Err(Error::unsupported(
+            "This engine does not support evaluating expressions with missing input columns",
+        ))
+    }
+
     /// Create a [`PredicateEvaluator`] that can evaluate the given [`Predicate`] on columnar
     /// batches with the given [`Schema`] to produce a column of boolean results.
     ///
@@ -445,6 +485,18 @@ pub trait EvaluationHandler: AsAny {
     // NOTE: we should probably allow DataType instead of SchemaRef, but can expand that in the
     // future.
     fn null_row(&self, output_schema: SchemaRef) -> DeltaResult<Box<dyn EngineData>>;
+
+    /// Create a zero-row [`EngineData`] with the schema specified by `output_schema`. The kernel
+    /// uses it to return a correctly-typed (empty) result when scanning an empty table.
+    ///
+    /// The default implementation returns [`Error::Unsupported`], in which case scans of empty
+    /// tables return no data at all.
+    fn empty_batch(&self, output_schema: SchemaRef) -> DeltaResult<Box<dyn EngineData>> {
+        let _ = output_schema;
+        Err(Error::unsupported(
+            "This engine does not support creating empty batches",
+        ))
+    }
 }
 
 /// Internal trait to allow us to have a private `create_one` API that's implemented for all
@@ -529,6 +581,15 @@ pub trait StorageHandler: AsAny {
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>>;
+
+    /// Delete the given files, in order. Files that do not exist are ignored. Kernel only deletes
+    /// files to clean up expired log files, so the default implementation does not support it.
+    fn delete_files(&self, files: Vec<Url>) -> DeltaResult<()> {
+        let _ = files;
+        Err(Error::unsupported(
+            "This storage handler does not support deleting files",
+        ))
+    }
 }
 
 /// Provides JSON handling functionality to Delta Kernel.
@@ -625,8 +686,10 @@ pub trait ParquetHandler: AsAny {
     /// 2. **Field Name**: If no field ID is present in the `physical_schema`'s [`StructField`] or no matching parquet field ID is found,
     ///    fall back to matching by column name
     ///
-    ///  If no matching Parquet column is found, `NULL` values are returned
-    ///  for nullable columns in `physical_schema`. For non-nullable columns, an error is returned.
+    ///  If no matching Parquet column is found, the column was added to the table after the file
+    ///  was written. If it has a default value (see [`StructField::current_default_value`]), that
+    ///  value is returned for every row. Otherwise `NULL` values are returned for nullable columns
+    ///  in `physical_schema`, and an error for non-nullable columns.
     ///
     ///
     /// ## Examples
@@ -239,17 +239,29 @@ impl ListedLogFiles {
 
     /// List all commit and checkpoint files with versions above the provided `start_version` (inclusive).
     /// If successful, this returns a `ListedLogFiles`.
+    pub(crate) fn list(
+        storage: &dyn StorageHandler,
+        log_root: &Url,
+        start_version: Option<Version>,
+        end_version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        Self::list_with_log_tail(storage, log_root, vec![], start_version, end_version)
+    }
+
+    /// Like [`list`], but the given `log_tail` of commits (e.g. as returned from a catalog for a
+    /// catalog-managed table) takes precedence over the listed commits of the same versions.
+    ///
+    /// [`list`]: Self::list
     // TODO: encode some of these guarantees in the output types. e.g. we could have:
     // - SortedCommitFiles: Vec<ParsedLogPath>, is_ascending: bool, end_version: Version
     // - CheckpointParts: Vec<ParsedLogPath>, checkpoint_version: Version (guarantee all same version)
-    pub(crate) fn list(
+    pub(crate) fn list_with_log_tail(
         storage: &dyn StorageHandler,
         log_root: &Url,
+        log_tail: Vec<ParsedLogPath>,
         start_version: Option<Version>,
         end_version: Option<Version>,
     ) -> DeltaResult<Self> {
-        // TODO: plumb through a log_tail provided by our caller
-        let log_tail = vec![];
         let log_files = list_log_files(storage, log_root, log_tail, start_version, end_version)?;
 
         log_files.process_results(|iter| {
@@ -318,11 +330,13 @@ impl ListedLogFiles {
         checkpoint_metadata: &LastCheckpointHint,
         storage: &dyn StorageHandler,
         log_root: &Url,
+        log_tail: Vec<ParsedLogPath>,
         end_version: Option<Version>,
     ) -> DeltaResult<Self> {
-        let listed_files = Self::list(
+        let listed_files = Self::list_with_log_tail(
             storage,
             log_root,
+            log_tail,
             Some(checkpoint_metadata.version),
             end_version,
         )?;
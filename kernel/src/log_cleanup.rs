@@ -0,0 +1,212 @@
+//! Cleanup of expired log files, see [`Snapshot::cleanup_expired_logs`].
+//!
+//! The Delta protocol allows deleting commits and checkpoints once they are older than the table's
+//! log retention (`delta.logRetentionDuration`, 30 days by default), as long as the table can still
+//! be reconstructed without them. Every version after the newest checkpoint that is itself older
+//! than the log retention can be, so cleanup deletes the (old enough) log files of the versions
+//! before that checkpoint, and never the checkpoint itself or anything after it.
+//!
+//! [`Snapshot::cleanup_expired_logs`]: crate::snapshot::Snapshot::cleanup_expired_logs
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use tracing::info;
+use url::Url;
+
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::utils::current_time_duration;
+use crate::{DeltaResult, Engine, Error, FileMeta, Version};
+
+/// The log retention of tables that do not set `delta.logRetentionDuration`.
+pub(crate) const DEFAULT_LOG_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Deletes the expired log files of the table at `table_root`, oldest first, and returns them.
+pub(crate) fn cleanup_expired_logs(
+    engine: &dyn Engine,
+    table_root: &Url,
+    log_retention: Option<Duration>,
+) -> DeltaResult<Vec<Url>> {
+    let retention = log_retention.unwrap_or(DEFAULT_LOG_RETENTION);
+    let cutoff = current_time_duration()?.saturating_sub(retention);
+    let cutoff_ms = i64::try_from(cutoff.as_millis())
+        .map_err(|_| Error::generic("Log retention cutoff exceeds i64 millisecond range"))?;
+
+    let log_root = table_root.join("_delta_log/")?;
+    let mut log_files = vec![];
+    for file in engine.storage_handler().list_from(&log_root)? {
+        if let Some(log_file) = ParsedLogPath::try_from(file?)? {
+            log_files.push(log_file);
+        }
+    }
+    let expired: Vec<_> = expired_log_files(log_files, cutoff_ms)
+        .into_iter()
+        .map(|file| file.location)
+        .collect();
+    if !expired.is_empty() {
+        info!(
+            "deleting {} expired log files of {table_root}",
+            expired.len()
+        );
+        engine.storage_handler().delete_files(expired.clone())?;
+    }
+    Ok(expired)
+}
+
+/// Selects the log files last modified at or before `cutoff_ms` (in milliseconds since epoch)
+/// whose versions precede the newest complete checkpoint that was also written by then. The
+/// selected files keep the (version) order of `log_files`.
+fn expired_log_files(log_files: Vec<ParsedLogPath>, cutoff_ms: i64) -> Vec<FileMeta> {
+    let is_old = |file: &ParsedLogPath| file.location.last_modified <= cutoff_ms;
+
+    // a multi-part checkpoint is only complete once all its parts are present
+    let mut checkpoint_parts: HashMap<(Version, u32), HashSet<u32>> = HashMap::new();
+    let mut checkpoint_version = None;
+    for file in log_files.iter().filter(|file| is_old(file)) {
+        let is_complete = match file.file_type {
+            LogPathFileType::SinglePartCheckpoint | LogPathFileType::UuidCheckpoint(_) => true,
+            LogPathFileType::MultiPartCheckpoint {
+                part_num,
+                num_parts,
+            } => {
+                let parts = checkpoint_parts
+                    .entry((file.version, num_parts))
+                    .or_default();
+                parts.insert(part_num);
+                parts.len() == num_parts as usize
+            }
+            _ => false,
+        };
+        if is_complete {
+            checkpoint_version = checkpoint_version.max(Some(file.version));
+        }
+    }
+    let Some(checkpoint_version) = checkpoint_version else {
+        return vec![];
+    };
+
+    log_files
+        .into_iter()
+        .filter(|file| {
+            let precedes_checkpoint = match file.file_type {
+                LogPathFileType::Commit
+                | LogPathFileType::SinglePartCheckpoint
+                | LogPathFileType::UuidCheckpoint(_)
+                | LogPathFileType::MultiPartCheckpoint { .. }
+                | LogPathFileType::Crc => file.version < checkpoint_version,
+                LogPathFileType::CompactedCommit { hi } => hi < checkpoint_version,
+                // staged commits are managed by the catalog, and unknown files are left alone
+                LogPathFileType::StagedCommit | LogPathFileType::Unknown => false,
+            };
+            precedes_checkpoint && is_old(file)
+        })
+        .map(|file| file.location)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::engine::sync::SyncEngine;
+
+    fn log_file(name: &str, last_modified: i64) -> ParsedLogPath {
+        let location = Url::parse("memory:///table/_delta_log/")
+            .unwrap()
+            .join(name)
+            .unwrap();
+        ParsedLogPath::try_from(FileMeta::new(location, last_modified, 1))
+            .unwrap()
+            .unwrap()
+    }
+
+    fn file_name(location: &Url) -> String {
+        location.path().rsplit('/').next().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_expired_log_files() {
+        let log_files = vec![
+            log_file("00000000000000000000.crc", 100),
+            log_file("00000000000000000000.json", 100),
+            log_file(
+                "00000000000000000000.00000000000000000001.compacted.json",
+                100,
+            ),
+            log_file("00000000000000000001.json", 100),
+            log_file("00000000000000000002.checkpoint.parquet", 100),
+            log_file("00000000000000000002.json", 100),
+            // an incomplete multi-part checkpoint does not count
+            log_file(
+                "00000000000000000003.checkpoint.0000000001.0000000002.parquet",
+                100,
+            ),
+            log_file("00000000000000000003.json", 200),
+            log_file("00000000000000000004.checkpoint.parquet", 200),
+        ];
+
+        // only the checkpoint at version 2 is old enough
+        let expired = expired_log_files(log_files.clone(), 150);
+        let names: Vec<_> = expired
+            .iter()
+            .map(|file| file_name(&file.location))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "00000000000000000000.crc",
+                "00000000000000000000.json",
+                "00000000000000000000.00000000000000000001.compacted.json",
+                "00000000000000000001.json",
+            ]
+        );
+
+        // nothing expires without a checkpoint older than the cutoff
+        assert!(expired_log_files(log_files.clone(), 50).is_empty());
+
+        // once the checkpoint at version 4 is old enough, all files before it expire
+        let expired = expired_log_files(log_files, 250);
+        assert_eq!(expired.len(), 8);
+    }
+
+    #[test]
+    fn test_cleanup_expired_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        fs::create_dir(&log_dir).unwrap();
+        for name in [
+            "00000000000000000000.json",
+            "00000000000000000001.json",
+            "00000000000000000001.checkpoint.parquet",
+            "00000000000000000002.json",
+            "_last_checkpoint",
+        ] {
+            fs::write(log_dir.join(name), "{}").unwrap();
+        }
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let engine = SyncEngine::new();
+
+        // with the default retention, the files are far too recent to expire
+        let deleted = cleanup_expired_logs(&engine, &table_root, None).unwrap();
+        assert!(deleted.is_empty());
+
+        let deleted = cleanup_expired_logs(&engine, &table_root, Some(Duration::ZERO)).unwrap();
+        let deleted: Vec<_> = deleted.iter().map(file_name).collect();
+        assert_eq!(deleted, ["00000000000000000000.json"]);
+        let mut remaining: Vec<_> = fs::read_dir(&log_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                "00000000000000000001.checkpoint.parquet",
+                "00000000000000000001.json",
+                "00000000000000000002.json",
+                "_last_checkpoint",
+            ]
+        );
+    }
+}
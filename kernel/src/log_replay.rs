@@ -20,6 +20,7 @@ use crate::{DeltaResult, EngineData};
 
 use delta_kernel_derive::internal_api;
 
+use std::collections::hash_map::RandomState;
 use std::collections::HashSet;
 
 use tracing::debug;
@@ -39,6 +40,41 @@ impl FileActionKey {
     }
 }
 
+/// The set of [`FileActionKey`]s seen so far during log replay.
+///
+/// Tables with many files would need a lot of memory to keep the full path (and DV id) strings of
+/// every key. Instead, only a 128-bit hash of each key is kept, which also makes lookups cheaper.
+/// The hash is keyed randomly per set, so collisions are negligible (about n^2 / 2^129 for n keys)
+/// and cannot be crafted by whoever writes the log.
+#[derive(Debug, Default)]
+pub(crate) struct SeenFileKeys {
+    hashers: [RandomState; 2],
+    hashes: HashSet<u128>,
+}
+
+impl SeenFileKeys {
+    fn hash(&self, key: &FileActionKey) -> u128 {
+        let [lo, hi] = &self.hashers;
+        (u128::from(hi.hash_one(key)) << 64) | u128::from(lo.hash_one(key))
+    }
+
+    /// Returns whether the key was seen before.
+    pub(crate) fn contains(&self, key: &FileActionKey) -> bool {
+        self.hashes.contains(&self.hash(key))
+    }
+
+    /// Records the key as seen. Returns whether the key was newly recorded.
+    pub(crate) fn insert(&mut self, key: &FileActionKey) -> bool {
+        self.hashes.insert(self.hash(key))
+    }
+
+    /// Returns whether no key was seen so far.
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
 /// Maintains state and provides functionality for deduplicating file actions during log replay.
 ///
 /// This struct is embedded in visitors to track which files have been seen across multiple
@@ -53,7 +89,7 @@ pub(crate) struct FileActionDeduplicator<'seen> {
     /// A set of (data file path, dv_unique_id) pairs that have been seen thus
     /// far in the log for deduplication. This is a mutable reference to the set
     /// of seen file keys that persists across multiple log batches.
-    seen_file_keys: &'seen mut HashSet<FileActionKey>,
+    seen_file_keys: &'seen mut SeenFileKeys,
     // TODO: Consider renaming to `is_commit_batch`, `deduplicate_batch`, or `save_batch`
     // to better reflect its role in deduplication logic.
     /// Whether we're processing a log batch (as opposed to a checkpoint)
@@ -70,7 +106,7 @@ pub(crate) struct FileActionDeduplicator<'seen> {
 
 impl<'seen> FileActionDeduplicator<'seen> {
     pub(crate) fn new(
-        seen_file_keys: &'seen mut HashSet<FileActionKey>,
+        seen_file_keys: &'seen mut SeenFileKeys,
         is_log_batch: bool,
         add_path_index: usize,
         remove_path_index: usize,
@@ -111,7 +147,7 @@ impl<'seen> FileActionDeduplicator<'seen> {
                 // Remember file actions from this batch so we can ignore duplicates as we process
                 // batches from older commit and/or checkpoint files. We don't track checkpoint
                 // batches because they are already the oldest actions and never replace anything.
-                self.seen_file_keys.insert(key);
+                self.seen_file_keys.insert(&key);
             }
             false
         }
@@ -201,6 +237,9 @@ pub(crate) struct ActionsBatch {
     pub actions: Box<dyn EngineData>,
     /// Whether the batch is from a commit log (=true) or a checkpoint/CRC/elsewhere (=false).
     pub is_log_batch: bool,
+    /// Whether the batch was read with an `add.stats_parsed` column, which holds the stats of
+    /// add actions in struct form (if the checkpoint provides them).
+    pub has_stats_parsed: bool,
 }
 
 impl ActionsBatch {
@@ -215,9 +254,16 @@ impl ActionsBatch {
         Self {
             actions,
             is_log_batch,
+            has_stats_parsed: false,
         }
     }
 
+    /// Marks whether the batch was read with an `add.stats_parsed` column.
+    pub(crate) fn with_stats_parsed(mut self, has_stats_parsed: bool) -> Self {
+        self.has_stats_parsed = has_stats_parsed;
+        self
+    }
+
     /// HACK: a duplication of the pub(crate) field `actions` to allow us to export as
     /// 'internal-api' and let inspect-table example use it.
     #[allow(unused)]
@@ -324,29 +370,33 @@ pub(crate) trait LogReplayProcessor: Sized {
             })
     }
 
-    /// Builds the initial selection vector for the action batch, used to filter out rows that
+    /// Applies the processor's optional [`DataSkippingFilter`] to a selection vector that the
+    /// processor already computed for the action batch, deselecting rows whose stats prove they
     /// are not relevant to the current processor's purpose (e.g., checkpointing, scanning).
-    /// This method performs a first pass of filtering using an optional [`DataSkippingFilter`].
-    /// If no filter is provided, it assumes that all rows should be selected.
     ///
-    /// The selection vector is further updated based on the processor's logic in the
-    /// `process_actions_batch` method.
+    /// Processors should call this after their own (cheaper) filtering, so that stats are only
+    /// parsed when some rows remain selected. If no filter is provided, the selection vector is
+    /// left unchanged.
     ///
     /// # Parameters
     /// - `batch`: A reference to the batch of actions to be processed.
-    ///
-    /// # Returns
-    /// A `DeltaResult<Vec<bool>>`, where each boolean indicates if the corresponding row should be included.
-    /// If no filter is provided, all rows are selected.
-    fn build_selection_vector(&self, batch: &dyn EngineData) -> DeltaResult<Vec<bool>> {
+    /// - `selection_vector`: The selection vector to update, with one entry per row of `batch`.
+    /// - `has_stats_parsed`: Whether `batch` has an `add.stats_parsed` column, see
+    ///   [`ActionsBatch::has_stats_parsed`].
+    fn apply_data_skipping(
+        &self,
+        batch: &dyn EngineData,
+        selection_vector: &mut [bool],
+        has_stats_parsed: bool,
+    ) -> DeltaResult<()> {
         match self.data_skipping_filter() {
-            Some(filter) => filter.apply(batch),
-            None => Ok(vec![true; batch.len()]), // If no filter is provided, select all rows
+            Some(filter) => filter.apply_to_selection(batch, selection_vector, has_stats_parsed),
+            None => Ok(()), // If no filter is provided, keep all selected rows
         }
     }
 
     /// Returns an optional reference to the [`DataSkippingFilter`] used to filter rows
-    /// when building the initial selection vector in `build_selection_vector`.
+    /// in `apply_data_skipping`.
     /// If `None` is returned, no filter is applied, and all rows are selected.
     fn data_skipping_filter(&self) -> Option<&DataSkippingFilter>;
 }
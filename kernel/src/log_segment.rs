@@ -3,16 +3,17 @@
 use std::num::NonZero;
 use std::sync::{Arc, LazyLock};
 
+use crate::actions::crc::CrcNumFilesVisitor;
 use crate::actions::visitors::SidecarVisitor;
 use crate::actions::{
     get_log_schema, Metadata, Protocol, ADD_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
-    SIDECAR_NAME,
+    SIDECAR_NAME, STATS_PARSED_NAME,
 };
 use crate::last_checkpoint_hint::LastCheckpointHint;
 use crate::log_replay::ActionsBatch;
 use crate::path::{LogPathFileType, ParsedLogPath};
-use crate::schema::SchemaRef;
-use crate::utils::require;
+use crate::schema::{DataType, SchemaRef, StructField, StructType};
+use crate::utils::{require, SpanIteratorExt as _};
 use crate::{
     DeltaResult, Engine, EngineData, Error, Expression, FileMeta, ParquetHandler, Predicate,
     PredicateRef, RowVisitor, StorageHandler, Version,
@@ -25,7 +26,7 @@ pub use crate::listed_log_files::ListedLogFiles;
 use crate::listed_log_files::ListedLogFiles;
 
 use itertools::Itertools;
-use tracing::{debug, warn};
+use tracing::{debug, debug_span, info_span, warn};
 use url::Url;
 
 #[cfg(test)]
@@ -137,6 +138,8 @@ impl LogSegment {
     ///
     /// The options for constructing a LogSegment for Snapshot are as follows:
     /// - `checkpoint_hint`: a `LastCheckpointHint` to start the log segment from (e.g. from reading the `last_checkpoint` file).
+    /// - `log_tail`: the latest commits as provided by the catalog of a catalog-managed table.
+    ///   These take precedence over the commits of the same versions found by listing.
     /// - `time_travel_version`: The version of the log that the Snapshot will be at.
     ///
     /// [`Snapshot`]: crate::snapshot::Snapshot
@@ -144,33 +147,48 @@ impl LogSegment {
     pub(crate) fn for_snapshot(
         storage: &dyn StorageHandler,
         log_root: Url,
+        log_tail: Vec<ParsedLogPath>,
         time_travel_version: impl Into<Option<Version>>,
     ) -> DeltaResult<Self> {
         let time_travel_version = time_travel_version.into();
         let checkpoint_hint = LastCheckpointHint::try_read(storage, &log_root)?;
-        Self::for_snapshot_impl(storage, log_root, checkpoint_hint, time_travel_version)
+        Self::for_snapshot_impl(
+            storage,
+            log_root,
+            log_tail,
+            checkpoint_hint,
+            time_travel_version,
+        )
     }
 
     // factored out for testing
     pub(crate) fn for_snapshot_impl(
         storage: &dyn StorageHandler,
         log_root: Url,
+        log_tail: Vec<ParsedLogPath>,
         checkpoint_hint: Option<LastCheckpointHint>,
         time_travel_version: Option<Version>,
     ) -> DeltaResult<Self> {
         let listed_files = match (checkpoint_hint, time_travel_version) {
             (Some(cp), None) => {
-                ListedLogFiles::list_with_checkpoint_hint(&cp, storage, &log_root, None)?
+                ListedLogFiles::list_with_checkpoint_hint(&cp, storage, &log_root, log_tail, None)?
             }
             (Some(cp), Some(end_version)) if cp.version <= end_version => {
                 ListedLogFiles::list_with_checkpoint_hint(
                     &cp,
                     storage,
                     &log_root,
+                    log_tail,
                     Some(end_version),
                 )?
             }
-            _ => ListedLogFiles::list(storage, &log_root, None, time_travel_version)?,
+            _ => ListedLogFiles::list_with_log_tail(
+                storage,
+                &log_root,
+                log_tail,
+                None,
+                time_travel_version,
+            )?,
         };
 
         LogSegment::try_new(listed_files, log_root, time_travel_version)
@@ -280,6 +298,13 @@ impl LogSegment {
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
         // `replay` expects commit files to be sorted in descending order, so the return value here is correct
         let commits_and_compactions = self.find_commit_cover();
+        let span = info_span!(
+            "log_replay",
+            version = self.end_version,
+            checkpoint_version = self.checkpoint_version,
+            commit_files = commits_and_compactions.len(),
+        );
+        let _entered = span.enter();
         let commit_stream = engine
             .json_handler()
             .read_json_files(
@@ -292,7 +317,7 @@ impl LogSegment {
         let checkpoint_stream =
             self.create_checkpoint_stream(engine, checkpoint_read_schema, meta_predicate)?;
 
-        Ok(commit_stream.chain(checkpoint_stream))
+        Ok(commit_stream.chain(checkpoint_stream).in_span(span.clone()))
     }
 
     /// find a minimal set to cover the range of commits we want. This is greedy so not always
@@ -300,7 +325,7 @@ impl LogSegment {
     /// returns files is DESCENDING ORDER, as that's what `replay` expects. This function assumes
     /// that all files in `self.ascending_commit_files` and `self.ascending_compaction_files` are in
     /// range for this log segment. This invariant is maintained by our listing code.
-    fn find_commit_cover(&self) -> Vec<FileMeta> {
+    pub(crate) fn find_commit_cover(&self) -> Vec<FileMeta> {
         // Create an iterator sorted in ascending order by (initial version, end version), e.g.
         // [00.json, 00.09.compacted.json, 00.99.compacted.json, 01.json, 02.json, ..., 10.json,
         //  10.19.compacted.json, 11.json, ...]
@@ -357,6 +382,9 @@ impl LogSegment {
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
         let need_file_actions = checkpoint_read_schema.contains(ADD_NAME)
             || checkpoint_read_schema.contains(REMOVE_NAME);
+        let has_stats_parsed = checkpoint_read_schema.field(ADD_NAME).is_some_and(|add| {
+            matches!(add.data_type(), DataType::Struct(add) if add.contains(STATS_PARSED_NAME))
+        });
 
         // Only validate sidecar requirement if we actually have checkpoint files
         if !self.checkpoint_parts.is_empty() {
@@ -368,6 +396,13 @@ impl LogSegment {
             );
         }
 
+        let span = debug_span!(
+            "checkpoint_read",
+            checkpoint_version = self.checkpoint_version,
+            parts = self.checkpoint_parts.len(),
+        );
+        let _entered = span.enter();
+
         let checkpoint_file_meta: Vec<_> = self
             .checkpoint_parts
             .iter()
@@ -434,12 +469,15 @@ impl LogSegment {
                     .chain(sidecar_content.into_iter().flatten())
                     // The boolean flag indicates whether the batch originated from a commit file
                     // (true) or a checkpoint file (false).
-                    .map_ok(|sidecar_batch| ActionsBatch::new(sidecar_batch, false));
+                    .map_ok(move |sidecar_batch| {
+                        ActionsBatch::new(sidecar_batch, false).with_stats_parsed(has_stats_parsed)
+                    });
 
                 Ok(combined_batches)
             })
             .flatten_ok()
-            .map(|result| result?); // result-result to result
+            .map(|result| result?) // result-result to result
+            .in_span(span.clone());
 
         Ok(actions_iter)
     }
@@ -529,6 +567,40 @@ impl LogSegment {
         self.read_actions(engine, schema.clone(), schema, META_PREDICATE.clone())
     }
 
+    /// The number of live files at the end version of this log segment, if the log segment has a
+    /// CRC file at its end version. CRC files are optional, so failing to read one is not an error.
+    pub(crate) fn num_files_from_crc(&self, engine: &dyn Engine) -> Option<i64> {
+        let crc_file = self
+            .latest_crc_file
+            .as_ref()
+            .filter(|crc| crc.version == self.end_version)?;
+        let read_num_files = || -> DeltaResult<Option<i64>> {
+            let mut visitor = CrcNumFilesVisitor::default();
+            let schema = Arc::new(StructType::new_unchecked([StructField::not_null(
+                "numFiles",
+                DataType::LONG,
+            )]));
+            let batches = engine.json_handler().read_json_files(
+                std::slice::from_ref(&crc_file.location),
+                schema,
+                None,
+            )?;
+            for batch in batches {
+                visitor.visit_rows_of(batch?.as_ref())?;
+            }
+            Ok(visitor.num_files)
+        };
+        read_num_files()
+            .inspect_err(|e| {
+                warn!(
+                    "Failed to read CRC file {}: {e}",
+                    crc_file.location.location
+                )
+            })
+            .ok()
+            .flatten()
+    }
+
     /// How many commits since a checkpoint, according to this log segment
     pub(crate) fn commits_since_checkpoint(&self) -> u64 {
         // we can use 0 as the checkpoint version if there is no checkpoint since `end_version - 0`
@@ -0,0 +1,344 @@
+//! Planning of `OPTIMIZE`, which compacts the small files of a table into fewer, larger ones and
+//! optionally clusters their rows, see [`OptimizePlanner`].
+//!
+//! Like a scan, `OPTIMIZE` is split between the kernel and the engine: the kernel replays the log
+//! and decides which files to rewrite together, and the engine executes the resulting
+//! [`OptimizePlan`]. For each [`RewriteBin`], the engine reads the bin's files (applying their
+//! deletion vectors and transforms, as for a scan), sorts the rows by the plan's clustering key if
+//! there is one, writes them into new files of about the target size, and finally commits the
+//! removal of the bins' files and the addition of the new ones (with `dataChange = false`) in a
+//! transaction read at the plan's [`read_version`].
+//!
+//! [`read_version`]: OptimizePlan::read_version
+
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools as _;
+
+use crate::clustering::{clustering_key_expression, ClusteringCurve};
+use crate::expressions::{ColumnName, ExpressionRef};
+use crate::scan::grouping::ScanFile;
+use crate::schema::{DataType, StructType};
+use crate::snapshot::SnapshotRef;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, Version};
+
+/// The default target size of the files written by `OPTIMIZE`, in bytes (1 GiB).
+pub const DEFAULT_TARGET_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Plans an `OPTIMIZE` of a snapshot, see [`Snapshot::optimize_planner`].
+///
+/// Without clustering columns, the files of each partition that are smaller than the target file
+/// size (or have a deletion vector, whose deleted rows a rewrite purges) are packed into bins of at
+/// most the target size. Bins of a single file without deletion vector are not worth rewriting and
+/// are left out of the plan.
+///
+/// With clustering columns, the rows of each partition must be sorted together, so all files of
+/// every partition are rewritten as one bin.
+///
+/// [`Snapshot::optimize_planner`]: crate::Snapshot::optimize_planner
+#[derive(Debug)]
+pub struct OptimizePlanner {
+    snapshot: SnapshotRef,
+    target_file_size: u64,
+    clustering: Option<(ClusteringCurve, Vec<ColumnName>)>,
+}
+
+impl OptimizePlanner {
+    /// Create a new planner for `snapshot`, with the [`DEFAULT_TARGET_FILE_SIZE`] and no
+    /// clustering.
+    pub fn new(snapshot: SnapshotRef) -> Self {
+        Self {
+            snapshot,
+            target_file_size: DEFAULT_TARGET_FILE_SIZE,
+            clustering: None,
+        }
+    }
+
+    /// Set the target size of the files to write, in bytes.
+    pub fn with_target_file_size(mut self, target_file_size: u64) -> Self {
+        self.target_file_size = target_file_size;
+        self
+    }
+
+    /// Cluster the rewritten rows by the given (logical) columns along `curve`, e.g. for
+    /// `OPTIMIZE ... ZORDER BY`. Partition columns cannot be clustering columns, since all rows of
+    /// a partition have the same value for them.
+    pub fn with_clustering_columns(
+        mut self,
+        curve: ClusteringCurve,
+        columns: impl IntoIterator<Item = ColumnName>,
+    ) -> Self {
+        self.clustering = Some((curve, columns.into_iter().collect()));
+        self
+    }
+
+    /// Plan the `OPTIMIZE` by replaying the log of the snapshot.
+    pub fn plan(self, engine: &dyn Engine) -> DeltaResult<OptimizePlan> {
+        require!(
+            self.target_file_size > 0,
+            Error::generic("The target file size of OPTIMIZE must be positive")
+        );
+        let clustering_key = match &self.clustering {
+            Some((curve, columns)) => {
+                for column in columns {
+                    self.validate_clustering_column(column)?;
+                }
+                let expr = clustering_key_expression(*curve, columns.iter().cloned())?;
+                Some(ExpressionRef::new(expr))
+            }
+            None => None,
+        };
+
+        let scan = self.snapshot.clone().scan_builder().build()?;
+        let mut files = vec![];
+        for scan_metadata in scan.scan_metadata(engine)? {
+            files = scan_metadata?.visit_scan_files(files, ScanFile::collect)?;
+        }
+
+        // order the partitions by their values, so that the plan is deterministic
+        let mut partitions: BTreeMap<Vec<(String, String)>, Vec<ScanFile>> = BTreeMap::new();
+        for file in files {
+            let key = file.partition_values.clone().into_iter().sorted().collect();
+            partitions.entry(key).or_default().push(file);
+        }
+        let bins = partitions
+            .into_values()
+            .flat_map(|files| match clustering_key {
+                Some(_) => vec![RewriteBin::new(files)],
+                None => bin_files(files, self.target_file_size),
+            })
+            .collect();
+        Ok(OptimizePlan {
+            read_version: self.snapshot.version(),
+            target_file_size: self.target_file_size,
+            clustering_key,
+            bins,
+        })
+    }
+
+    fn validate_clustering_column(&self, column: &ColumnName) -> DeltaResult<()> {
+        let schema = self.snapshot.schema();
+        let mut data_type: Option<&DataType> = None;
+        for name in column.path() {
+            let parent: &StructType = match data_type {
+                None => schema.as_ref(),
+                Some(DataType::Struct(inner)) => inner.as_ref(),
+                Some(_) => return Err(Error::missing_column(column.to_string())),
+            };
+            let field = parent
+                .field(name)
+                .ok_or_else(|| Error::missing_column(column.to_string()))?;
+            data_type = Some(field.data_type());
+        }
+        require!(
+            matches!(data_type, Some(DataType::Primitive(_))),
+            Error::generic(format!(
+                "Cannot cluster by column {column}, which is not of a primitive type"
+            ))
+        );
+        let partition_columns = &self.snapshot.metadata().partition_columns;
+        require!(
+            !matches!(column.path(), [name] if partition_columns.contains(name)),
+            Error::generic(format!("Cannot cluster by partition column {column}"))
+        );
+        Ok(())
+    }
+}
+
+/// The plan of an `OPTIMIZE`, which the engine executes as described in the [module docs].
+///
+/// [module docs]: self
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizePlan {
+    /// The version of the table the plan was made at, which the rewrite must be committed against
+    pub read_version: Version,
+    /// The target size of the files to write, in bytes
+    pub target_file_size: u64,
+    /// The expression whose (`BINARY`) values the rows of each bin must be sorted by before they
+    /// are written, if the rewrite clusters them. It references logical columns, so it applies to
+    /// the rows after their scan transform.
+    pub clustering_key: Option<ExpressionRef>,
+    /// The groups of files to rewrite together, ordered by partition
+    pub bins: Vec<RewriteBin>,
+}
+
+impl OptimizePlan {
+    /// Whether there is nothing to rewrite.
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+}
+
+/// A group of files of one partition whose rows are rewritten together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteBin {
+    /// The partition values shared by the files, keyed by physical column name
+    pub partition_values: HashMap<String, String>,
+    /// The files to rewrite, smallest first
+    pub files: Vec<ScanFile>,
+    /// The total size of the files in bytes
+    pub size: u64,
+}
+
+impl RewriteBin {
+    fn new(mut files: Vec<ScanFile>) -> Self {
+        files.sort_by(|a, b| a.size.cmp(&b.size).then_with(|| a.path.cmp(&b.path)));
+        let partition_values = files
+            .first()
+            .map(|file| file.partition_values.clone())
+            .unwrap_or_default();
+        let size = files.iter().map(file_size).sum();
+        Self {
+            partition_values,
+            files,
+            size,
+        }
+    }
+
+    fn is_worth_rewriting(&self) -> bool {
+        match self.files.as_slice() {
+            [file] => file.dv_info.has_vector(),
+            files => !files.is_empty(),
+        }
+    }
+}
+
+fn file_size(file: &ScanFile) -> u64 {
+    u64::try_from(file.size).unwrap_or(0)
+}
+
+/// Packs the files of one partition that need compaction into bins of at most `target_file_size`
+/// bytes, smallest files first. A file larger than the target only ends up in a bin (of its own) if
+/// it has a deletion vector.
+fn bin_files(files: Vec<ScanFile>, target_file_size: u64) -> Vec<RewriteBin> {
+    let candidates = files
+        .into_iter()
+        .filter(|file| file_size(file) < target_file_size || file.dv_info.has_vector())
+        .sorted_by(|a, b| a.size.cmp(&b.size).then_with(|| a.path.cmp(&b.path)));
+    let mut bins: Vec<Vec<ScanFile>> = vec![];
+    let mut bin_size = 0;
+    for file in candidates {
+        let size = file_size(&file);
+        match bins.last_mut() {
+            Some(bin) if bin_size + size <= target_file_size => {
+                bin_size += size;
+                bin.push(file);
+            }
+            _ => {
+                bin_size = size;
+                bins.push(vec![file]);
+            }
+        }
+    }
+    bins.into_iter()
+        .map(RewriteBin::new)
+        .filter(RewriteBin::is_worth_rewriting)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::expressions::column_name;
+    use crate::scan::state::DvInfo;
+    use crate::utils::test_utils::assert_result_error_with_message;
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn optimize_planner(table: &str) -> (OptimizePlanner, SyncEngine) {
+        let path = std::fs::canonicalize(PathBuf::from(table)).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        (snapshot.optimize_planner(), engine)
+    }
+
+    fn file(path: &str, size: i64) -> ScanFile {
+        ScanFile {
+            path: path.to_string(),
+            size,
+            stats: None,
+            dv_info: DvInfo::default(),
+            transform: None,
+            partition_values: HashMap::new(),
+            tags: HashMap::new(),
+        }
+    }
+
+    fn paths(bin: &RewriteBin) -> Vec<&str> {
+        bin.files.iter().map(|file| file.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_bin_files() {
+        let files = vec![
+            file("a", 60),
+            file("b", 10),
+            file("c", 30),
+            file("d", 100),
+            file("e", 50),
+            file("f", 95),
+        ];
+        let bins = bin_files(files, 100);
+        // d is not smaller than the target, and a and f don't fit into the first bin but are alone
+        // in their own bins
+        let bins: Vec<_> = bins.iter().map(paths).collect();
+        assert_eq!(bins, vec![vec!["b", "c", "e"]]);
+        assert!(bin_files(vec![file("a", 10)], 100).is_empty());
+    }
+
+    #[test]
+    fn test_plan_compaction() {
+        let (planner, engine) = optimize_planner("./tests/data/basic_partitioned/");
+        let plan = planner.with_target_file_size(2000).plan(&engine).unwrap();
+        assert_eq!(plan.read_version, 1);
+        assert_eq!(plan.clustering_key, None);
+        // only partition a has more than one file
+        assert_eq!(plan.bins.len(), 1);
+        let bin = &plan.bins[0];
+        assert_eq!(
+            bin.partition_values,
+            HashMap::from([("letter".to_string(), "a".to_string())])
+        );
+        assert_eq!(bin.files.len(), 2);
+        assert_eq!(bin.size, 1502);
+
+        // the files of partition a don't fit together into a smaller target
+        let (planner, engine) = optimize_planner("./tests/data/basic_partitioned/");
+        let plan = planner.with_target_file_size(1000).plan(&engine).unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_plan_clustering() {
+        let (planner, engine) = optimize_planner("./tests/data/basic_partitioned/");
+        let plan = planner
+            .with_clustering_columns(ClusteringCurve::ZOrder, [column_name!("number")])
+            .plan(&engine)
+            .unwrap();
+        let expected =
+            clustering_key_expression(ClusteringCurve::ZOrder, [column_name!("number")]).unwrap();
+        assert_eq!(plan.clustering_key, Some(Arc::new(expected)));
+        // every partition is rewritten as a whole
+        let sizes: Vec<_> = plan.bins.iter().map(|bin| bin.files.len()).collect();
+        assert_eq!(sizes.iter().sum::<usize>(), 6);
+        assert!(sizes.contains(&2));
+
+        let (planner, engine) = optimize_planner("./tests/data/basic_partitioned/");
+        let result = planner
+            .with_clustering_columns(ClusteringCurve::ZOrder, [column_name!("letter")])
+            .plan(&engine);
+        assert_result_error_with_message(result, "Cannot cluster by partition column letter");
+
+        let (planner, engine) = optimize_planner("./tests/data/basic_partitioned/");
+        let result = planner
+            .with_clustering_columns(ClusteringCurve::Hilbert, [column_name!("nope")])
+            .plan(&engine);
+        assert_result_error_with_message(result, "nope");
+    }
+}
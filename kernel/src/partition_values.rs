@@ -0,0 +1,253 @@
+//! Conversion between the string `partitionValues` of add actions and typed [`Scalar`]s, following
+//! the Delta protocol's [partition value serialization] rules.
+//!
+//! Engines that read partition values (e.g. to prune files or materialize partition columns) or
+//! write them (e.g. to build add actions) should use these functions rather than re-implementing
+//! the rules: they handle null values (empty strings and `__HIVE_DEFAULT_PARTITION__`), dates and
+//! timestamps, decimals and binary values consistently with the rest of the kernel.
+//!
+//! [partition value serialization]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#partition-value-serialization
+
+use std::collections::HashMap;
+
+use chrono::DateTime;
+
+use crate::expressions::{DecimalData, Scalar};
+use crate::schema::{DataType, StructType};
+use crate::{DeltaResult, Error};
+
+/// The partition value Hive (and thus Spark) uses for null values in partition directory names,
+/// which some writers also store in `partitionValues`.
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Parses a serialized partition value of type `data_type`. Missing values, empty strings and
+/// [`HIVE_DEFAULT_PARTITION`] are null.
+pub fn parse_partition_value(raw: Option<&str>, data_type: &DataType) -> DeltaResult<Scalar> {
+    match (raw, data_type.as_primitive_opt()) {
+        (None | Some("" | HIVE_DEFAULT_PARTITION), _) => Ok(Scalar::Null(data_type.clone())),
+        (Some(raw), Some(primitive)) => primitive.parse_scalar(raw),
+        (Some(_), None) => Err(Error::generic(format!(
+            "Unexpected partition column type: {data_type:?}"
+        ))),
+    }
+}
+
+/// Parses the `partitionValues` of an add action into a typed value for each partition column of
+/// `partition_schema`, keyed by the column's name. The `partitionValues` map is keyed by the
+/// physical names of the partition columns, see [`StructField::physical_name`]. Columns without a
+/// value are null.
+///
+/// [`StructField::physical_name`]: crate::schema::StructField
+pub fn parse_partition_values(
+    partition_schema: &StructType,
+    partition_values: &HashMap<String, String>,
+) -> DeltaResult<HashMap<String, Scalar>> {
+    partition_schema
+        .fields()
+        .map(|field| {
+            let raw = partition_values
+                .get(field.physical_name())
+                .map(String::as_str);
+            let value = parse_partition_value(raw, field.data_type())?;
+            Ok((field.name().clone(), value))
+        })
+        .collect()
+}
+
+/// Serializes a partition value, or returns `None` if it is null. Serializing a value and parsing
+/// the result with [`parse_partition_value`] returns the original value.
+///
+/// - Timestamps are serialized in ISO 8601 format with microsecond precision, in UTC, e.g.
+///   `1970-01-01T00:00:00.000000Z`, and timestamps without time zone as e.g.
+///   `1970-01-01 00:00:00.000000`.
+/// - Decimals are serialized without exponent, with as many fractional digits as their scale.
+/// - Binary values are serialized as the string they encode, which must be valid UTF-8.
+/// - Empty strings cannot be serialized: the protocol reads them back as null.
+pub fn serialize_partition_value(value: &Scalar) -> DeltaResult<Option<String>> {
+    let serialized = match value {
+        Scalar::Null(_) => return Ok(None),
+        Scalar::String(s) if s.is_empty() => {
+            return Err(Error::generic(
+                "Empty strings cannot be serialized as partition values, they read back as null",
+            ))
+        }
+        Scalar::String(s) => s.clone(),
+        Scalar::Boolean(b) => b.to_string(),
+        Scalar::Byte(i) => i.to_string(),
+        Scalar::Short(i) => i.to_string(),
+        Scalar::Integer(i) => i.to_string(),
+        Scalar::Long(i) => i.to_string(),
+        Scalar::Float(f) => serialize_float(f64::from(*f), f.to_string()),
+        Scalar::Double(f) => serialize_float(*f, f.to_string()),
+        Scalar::Decimal(d) => serialize_decimal(d),
+        Scalar::Date(days) => {
+            let date = DateTime::from_timestamp(i64::from(*days) * 24 * 60 * 60, 0)
+                .ok_or_else(|| Error::generic(format!("Date out of range: {days} days")))?;
+            date.format("%Y-%m-%d").to_string()
+        }
+        Scalar::Timestamp(micros) | Scalar::TimestampWithOffset(micros, _) => timestamp(*micros)?
+            .format("%Y-%m-%dT%H:%M:%S%.6fZ")
+            .to_string(),
+        Scalar::TimestampNtz(micros) => timestamp(*micros)?
+            .format("%Y-%m-%d %H:%M:%S%.6f")
+            .to_string(),
+        Scalar::Binary(bytes) => String::from_utf8(bytes.clone()).map_err(|_| {
+            Error::generic("Binary partition values must be valid UTF-8 to be serialized")
+        })?,
+        Scalar::Struct(_) | Scalar::Array(_) | Scalar::Map(_) => {
+            return Err(Error::generic(format!(
+                "Partition values must be primitive, got {:?}",
+                value.data_type()
+            )))
+        }
+    };
+    Ok(Some(serialized))
+}
+
+/// Serializes the value of each partition column of `partition_schema` into the `partitionValues`
+/// of an add action, keyed by the column's physical name (see [`parse_partition_values`]). `values`
+/// is keyed by the columns' names, and must hold a value of the column's type (or null) for every
+/// partition column. Null values are serialized as empty strings.
+pub fn serialize_partition_values(
+    partition_schema: &StructType,
+    values: &HashMap<String, Scalar>,
+) -> DeltaResult<HashMap<String, String>> {
+    partition_schema
+        .fields()
+        .map(|field| {
+            let value = values.get(field.name()).ok_or_else(|| {
+                Error::generic(format!(
+                    "Missing value for partition column {}",
+                    field.name()
+                ))
+            })?;
+            if !value.is_null() && value.data_type() != *field.data_type() {
+                return Err(Error::generic(format!(
+                    "Partition column {} has type {:?}, but its value has type {:?}",
+                    field.name(),
+                    field.data_type(),
+                    value.data_type()
+                )));
+            }
+            let serialized = serialize_partition_value(value)?.unwrap_or_default();
+            Ok((field.physical_name().to_string(), serialized))
+        })
+        .collect()
+}
+
+fn timestamp(micros: i64) -> DeltaResult<DateTime<chrono::Utc>> {
+    DateTime::from_timestamp_micros(micros)
+        .ok_or_else(|| Error::generic(format!("Timestamp out of range: {micros} microseconds")))
+}
+
+// Infinities are serialized the way Java does, which Rust also parses.
+fn serialize_float(value: f64, display: String) -> String {
+    if value == f64::INFINITY {
+        "Infinity".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Infinity".to_string()
+    } else {
+        display
+    }
+}
+
+fn serialize_decimal(decimal: &DecimalData) -> String {
+    let sign = if decimal.bits() < 0 { "-" } else { "" };
+    let digits = decimal.bits().unsigned_abs().to_string();
+    let scale = decimal.scale() as usize;
+    if scale == 0 {
+        return format!("{sign}{digits}");
+    }
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+    format!("{sign}{int_part}.{frac_part}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{DecimalType, StructField};
+
+    #[test]
+    fn test_parse_partition_value_nulls() {
+        for raw in [None, Some(""), Some(HIVE_DEFAULT_PARTITION)] {
+            let value = parse_partition_value(raw, &DataType::INTEGER).unwrap();
+            assert_eq!(value, Scalar::Null(DataType::INTEGER));
+        }
+        let result = parse_partition_value(Some("x"), &DataType::INTEGER);
+        assert!(matches!(result, Err(Error::ParseError(..))));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let decimal = |bits, precision, scale| {
+            Scalar::Decimal(
+                DecimalData::try_new(bits, DecimalType::try_new(precision, scale).unwrap())
+                    .unwrap(),
+            )
+        };
+        let cases = [
+            (Scalar::String("a b/c".into()), "a b/c"),
+            (Scalar::Boolean(true), "true"),
+            (Scalar::Byte(-1), "-1"),
+            (Scalar::Long(i64::MAX), "9223372036854775807"),
+            (Scalar::Double(1.5), "1.5"),
+            (Scalar::Float(f32::NEG_INFINITY), "-Infinity"),
+            (decimal(-5, 5, 2), "-0.05"),
+            (decimal(12345, 5, 2), "123.45"),
+            (decimal(7, 3, 0), "7"),
+            (Scalar::Date(19723), "2024-01-01"),
+            (
+                Scalar::Timestamp(1_704_067_200_000_001),
+                "2024-01-01T00:00:00.000001Z",
+            ),
+            (Scalar::TimestampNtz(-1), "1969-12-31 23:59:59.999999"),
+            (Scalar::Binary(b"\x01\x02".to_vec()), "\u{1}\u{2}"),
+        ];
+        for (value, expected) in cases {
+            let serialized = serialize_partition_value(&value).unwrap().unwrap();
+            assert_eq!(serialized, expected);
+            let parsed = parse_partition_value(Some(&serialized), &value.data_type()).unwrap();
+            assert_eq!(parsed, value, "{serialized}");
+        }
+
+        assert_eq!(
+            serialize_partition_value(&Scalar::Null(DataType::DATE)).unwrap(),
+            None
+        );
+        assert!(serialize_partition_value(&Scalar::String(String::new())).is_err());
+        assert!(serialize_partition_value(&Scalar::Binary(vec![0xff])).is_err());
+    }
+
+    #[test]
+    fn test_partition_values() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("date", DataType::DATE),
+            StructField::nullable("id", DataType::LONG),
+        ]);
+        let values = HashMap::from([
+            ("date".to_string(), Scalar::Date(0)),
+            ("id".to_string(), Scalar::Null(DataType::LONG)),
+        ]);
+        let serialized = serialize_partition_values(&schema, &values).unwrap();
+        assert_eq!(
+            serialized,
+            HashMap::from([
+                ("date".to_string(), "1970-01-01".to_string()),
+                ("id".to_string(), String::new()),
+            ])
+        );
+        assert_eq!(
+            parse_partition_values(&schema, &serialized).unwrap(),
+            values
+        );
+
+        let wrong_type = HashMap::from([
+            ("date".to_string(), Scalar::Integer(0)),
+            ("id".to_string(), Scalar::Long(1)),
+        ]);
+        assert!(serialize_partition_values(&schema, &wrong_type).is_err());
+        let missing = HashMap::from([("date".to_string(), Scalar::Date(0))]);
+        assert!(serialize_partition_values(&schema, &missing).is_err());
+    }
+}
@@ -270,8 +270,6 @@ impl ParsedLogPath<Url> {
         Ok(path)
     }
 
-    // TODO: remove after support for writing CRC files
-    #[allow(unused)]
     /// Create a new ParsedCommitPath<Url> for a new CRC file
     pub(crate) fn new_crc(table_root: &Url, version: Version) -> DeltaResult<Self> {
         let filename = format!("{version:020}.crc");
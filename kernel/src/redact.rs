@@ -0,0 +1,96 @@
+//! Redaction of sensitive values in the `Debug` and `Display` output of kernel types.
+//!
+//! Partition values, file statistics (which hold the min/max values of columns) and the values of
+//! object store options frequently contain personal data or secrets, and easily end up in engine
+//! logs, e.g. via the [`Display`] of an [`Error`] or the [`Debug`] of an action. The kernel
+//! therefore prints them as [`REDACTED`] by default. Engines that need the actual values (e.g. to
+//! debug a table in a development environment) can opt out with [`set_redaction_enabled`].
+//!
+//! The setting only affects how values are formatted, never the values themselves.
+//!
+//! [`Error`]: crate::Error
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What redacted values are printed as.
+pub const REDACTED: &str = "<redacted>";
+
+static REDACTION_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables the redaction of sensitive values process-wide. Redaction is enabled by
+/// default.
+pub fn set_redaction_enabled(enabled: bool) {
+    REDACTION_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether sensitive values are redacted, see [`set_redaction_enabled`].
+pub fn redaction_enabled() -> bool {
+    REDACTION_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Formats the wrapped value as [`REDACTED`] while redaction is enabled, and like the value itself
+/// otherwise.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Redacted<T>(pub T);
+
+impl<T: Debug> Debug for Redacted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match redaction_enabled() {
+            true => f.write_str(REDACTED),
+            false => self.0.fmt(f),
+        }
+    }
+}
+
+impl<T: Display> Display for Redacted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match redaction_enabled() {
+            true => f.write_str(REDACTED),
+            false => self.0.fmt(f),
+        }
+    }
+}
+
+/// Formats a map with its keys, but with each value [`Redacted`].
+pub(crate) struct RedactedValues<'a, K, V>(pub(crate) &'a HashMap<K, V>);
+
+impl<K: Debug, V: Debug> Debug for RedactedValues<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(k, v)| (k, Redacted(v))))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Serializes the tests that change the process-wide redaction setting.
+    pub(crate) static REDACTION_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_redacted() {
+        let _lock = REDACTION_LOCK.lock().unwrap();
+        let options = HashMap::from([("aws_secret_access_key", "secret")]);
+        assert_eq!(format!("{}", Redacted("x")), REDACTED);
+        assert_eq!(format!("{:?}", Redacted("x")), REDACTED);
+        assert_eq!(
+            format!("{:?}", RedactedValues(&options)),
+            r#"{"aws_secret_access_key": <redacted>}"#
+        );
+
+        set_redaction_enabled(false);
+        assert_eq!(format!("{}", Redacted("x")), "x");
+        assert_eq!(format!("{:?}", Redacted("x")), r#""x""#);
+        assert_eq!(
+            format!("{:?}", RedactedValues(&options)),
+            r#"{"aws_secret_access_key": "secret"}"#
+        );
+        set_redaction_enabled(true);
+    }
+}
@@ -0,0 +1,484 @@
+//! Answers simple aggregates (`COUNT(*)`, `MIN(col)`, `MAX(col)`) over a snapshot directly from
+//! the file statistics recorded in the Delta log, without reading any data.
+//!
+//! Stats are only used when they are known to be exact. Whenever any file of the snapshot lacks
+//! the stats an aggregate needs, or its stats may be inexact (e.g. truncated string bounds, or
+//! loose bounds left behind by a deletion vector), the aggregate's result is `None` and the engine
+//! must fall back to reading the data.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use itertools::Itertools as _;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::actions::visitors::visit_deletion_vector_at;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{ColumnName, Scalar};
+use crate::schema::{ColumnNamesAndTypes, DataType, PrimitiveType, StructField, StructType};
+use crate::snapshot::SnapshotRef;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error};
+
+use super::log_replay::SCAN_ROW_SCHEMA;
+
+/// A simple aggregate that may be answerable from file statistics alone. See
+/// [`Snapshot::aggregate_from_stats`].
+///
+/// [`Snapshot::aggregate_from_stats`]: crate::Snapshot::aggregate_from_stats
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregate {
+    /// `COUNT(*)`: the number of rows in the snapshot.
+    Count,
+    /// `MIN(col)`: the smallest non-null value of the (logical) column.
+    Min(ColumnName),
+    /// `MAX(col)`: the largest non-null value of the (logical) column.
+    Max(ColumnName),
+}
+
+/// Where the values of a MIN/MAX column come from.
+#[derive(Debug)]
+pub(super) enum ValueSource {
+    /// A partition column, whose values are keyed by physical name in `partitionValues`.
+    Partition(String),
+    /// A data column, whose bounds are found at this physical path in the min/max stats.
+    Stats(Vec<String>),
+}
+
+/// The running state of one aggregate. A `None` result means the aggregate can no longer be
+/// answered exactly from stats.
+#[derive(Debug)]
+enum AggregateState {
+    Count {
+        result: Option<i64>,
+    },
+    Bound {
+        is_max: bool,
+        data_type: PrimitiveType,
+        source: ValueSource,
+        result: Option<Option<Scalar>>,
+    },
+}
+
+/// The parts of a scan file that the aggregates need.
+pub(super) struct FileInfo<'a> {
+    pub(super) stats: Option<Value>,
+    pub(super) deleted_rows: i64,
+    pub(super) has_deletion_vector: bool,
+    pub(super) partition_values: &'a HashMap<String, String>,
+}
+
+impl FileInfo<'_> {
+    pub(super) fn num_records(&self) -> Option<i64> {
+        self.stats.as_ref()?.get("numRecords")?.as_i64()
+    }
+
+    // Bounds are tight unless a deletion vector says otherwise; without a DV, the spec requires
+    // `tightBounds` to be absent or true.
+    pub(super) fn tight_bounds(&self) -> bool {
+        let tight_bounds = self
+            .stats
+            .as_ref()
+            .and_then(|stats| stats.get("tightBounds"))
+            .and_then(Value::as_bool);
+        tight_bounds.unwrap_or(!self.has_deletion_vector)
+    }
+
+    pub(super) fn stat(&self, kind: &str, path: &[String]) -> Option<&Value> {
+        let stat = self.stats.as_ref()?.get(kind)?;
+        path.iter().try_fold(stat, |value, name| value.get(name))
+    }
+}
+
+impl AggregateState {
+    fn try_new(snapshot: &SnapshotRef, aggregate: &Aggregate) -> DeltaResult<Self> {
+        let (column, is_max) = match aggregate {
+            Aggregate::Count => return Ok(Self::Count { result: Some(0) }),
+            Aggregate::Min(column) => (column, false),
+            Aggregate::Max(column) => (column, true),
+        };
+        let schema = snapshot.schema();
+        let mut fields: Vec<&StructField> = Vec::with_capacity(column.path().len());
+        for name in column.path() {
+            let parent: &StructType = match fields.last().map(|field| field.data_type()) {
+                None => schema.as_ref(),
+                Some(DataType::Struct(inner)) => inner.as_ref(),
+                Some(_) => return Err(Error::missing_column(column.to_string())),
+            };
+            let field = parent
+                .field(name)
+                .ok_or_else(|| Error::missing_column(column.to_string()))?;
+            fields.push(field);
+        }
+        let Some(field) = fields.last() else {
+            return Err(Error::generic("Cannot aggregate an empty column name"));
+        };
+        let is_partition_column = fields.len() == 1
+            && snapshot
+                .metadata()
+                .partition_columns
+                .iter()
+                .any(|partition_column| partition_column == field.name());
+        let source = if is_partition_column {
+            ValueSource::Partition(field.physical_name().to_string())
+        } else {
+            let path = fields.iter().map(|f| f.physical_name().to_string());
+            ValueSource::Stats(path.collect())
+        };
+
+        // Partition values are always exact, but only these stats types are never truncated or
+        // rounded by writers. Floating point types are excluded everywhere because of NaN.
+        let data_type = match field.data_type() {
+            DataType::Primitive(data_type) => match (data_type, &source) {
+                (PrimitiveType::Float | PrimitiveType::Double, _) => None,
+                (_, ValueSource::Partition(_)) => Some(data_type.clone()),
+                (
+                    PrimitiveType::Byte
+                    | PrimitiveType::Short
+                    | PrimitiveType::Integer
+                    | PrimitiveType::Long
+                    | PrimitiveType::Date,
+                    ValueSource::Stats(_),
+                ) => Some(data_type.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        let (data_type, result) = match data_type {
+            Some(data_type) => (data_type, Some(None)),
+            // The result is never exact, but keep some type around for the sake of simplicity
+            None => (PrimitiveType::Long, None),
+        };
+        Ok(Self::Bound {
+            is_max,
+            data_type,
+            source,
+            result,
+        })
+    }
+
+    fn update(&mut self, file: &FileInfo<'_>) {
+        match self {
+            Self::Count { result } => {
+                *result = result.and_then(|count| {
+                    let live_rows = file.num_records()?.checked_sub(file.deleted_rows)?;
+                    count.checked_add(live_rows)
+                });
+            }
+            Self::Bound {
+                is_max,
+                data_type,
+                source,
+                result,
+            } => {
+                let Some(current) = result else {
+                    return;
+                };
+                match Self::file_bound(*is_max, data_type, source, file) {
+                    Some(Some(bound)) => {
+                        let replace = current.as_ref().is_none_or(|current| match *is_max {
+                            true => bound > *current,
+                            false => bound < *current,
+                        });
+                        if replace {
+                            *current = Some(bound);
+                        }
+                    }
+                    Some(None) => {}
+                    None => *result = None,
+                }
+            }
+        }
+    }
+
+    /// Returns the file's exact bound for the column, `Some(None)` if the file contributes no
+    /// value (no live rows, or only nulls), or `None` if its stats are insufficient.
+    fn file_bound(
+        is_max: bool,
+        data_type: &PrimitiveType,
+        source: &ValueSource,
+        file: &FileInfo<'_>,
+    ) -> Option<Option<Scalar>> {
+        let to_scalar = |raw: &str| match data_type.parse_scalar(raw) {
+            Ok(Scalar::Null(_)) => Some(None),
+            Ok(scalar) => Some(Some(scalar)),
+            Err(err) => {
+                warn!("Cannot parse {raw:?} as {data_type:?}: {err}");
+                None
+            }
+        };
+        match source {
+            ValueSource::Partition(key) => {
+                match file.num_records() {
+                    Some(num_records) if num_records <= file.deleted_rows => return Some(None),
+                    Some(_) => {}
+                    // Without a row count, a file with a DV may have no live rows left
+                    None if file.has_deletion_vector => return None,
+                    None => {}
+                }
+                match file.partition_values.get(key) {
+                    Some(raw) => to_scalar(raw),
+                    None => Some(None),
+                }
+            }
+            ValueSource::Stats(path) => {
+                let num_records = file.num_records()?;
+                if num_records <= file.deleted_rows {
+                    return Some(None);
+                }
+                if !file.tight_bounds() {
+                    return None;
+                }
+                let kind = if is_max { "maxValues" } else { "minValues" };
+                match file.stat(kind, path) {
+                    Some(Value::String(raw)) => to_scalar(raw),
+                    Some(Value::Number(raw)) => to_scalar(&raw.to_string()),
+                    Some(_) => None,
+                    // A missing bound is only exact if every row of the file is null
+                    None => {
+                        let null_count = file.stat("nullCount", path)?.as_i64()?;
+                        (null_count == num_records).then_some(None)
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> Option<Scalar> {
+        match self {
+            Self::Count { result } => result.map(Scalar::Long),
+            Self::Bound {
+                data_type, result, ..
+            } => {
+                let data_type = DataType::Primitive(data_type);
+                result.map(|bound| bound.unwrap_or(Scalar::Null(data_type)))
+            }
+        }
+    }
+}
+
+/// Visits the scan files of a [`ScanMetadata`], calling `on_file` with every selected file.
+///
+/// [`ScanMetadata`]: super::ScanMetadata
+struct ScanFileVisitor<'a, F> {
+    selection_vector: &'a [bool],
+    on_file: F,
+}
+
+impl<F> ScanFileVisitor<'_, F> {
+    // These index positions correspond to the leaves of `SCAN_ROW_SCHEMA`
+    const PATH_INDEX: usize = 0; // Position of "path" in getters
+    const STATS_INDEX: usize = 3; // Position of "stats" in getters
+    const DV_START_INDEX: usize = 5; // Start position of deletion vector columns
+    const PARTITION_VALUES_INDEX: usize = 10; // Position of "fileConstantValues.partitionValues"
+}
+
+impl<F: FnMut(&FileInfo<'_>)> RowVisitor for ScanFileVisitor<'_, F> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| SCAN_ROW_SCHEMA.leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 12,
+            Error::InternalError(format!(
+                "Wrong number of ScanFileVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for row_index in 0..row_count {
+            if !self
+                .selection_vector
+                .get(row_index)
+                .copied()
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            // Since path column is required, use it to detect presence of an Add action
+            let path: Option<String> =
+                getters[Self::PATH_INDEX].get_opt(row_index, "scanFile.path")?;
+            if path.is_none() {
+                continue;
+            }
+            let stats: Option<String> =
+                getters[Self::STATS_INDEX].get_opt(row_index, "scanFile.stats")?;
+            let stats = stats.and_then(|json| match serde_json::from_str(&json) {
+                Ok(stats) => Some(stats),
+                Err(e) => {
+                    warn!("Invalid stats string in Add file {json}: {e}");
+                    None
+                }
+            });
+            let deletion_vector =
+                visit_deletion_vector_at(row_index, &getters[Self::DV_START_INDEX..])?;
+            let partition_values: HashMap<String, String> =
+                getters[Self::PARTITION_VALUES_INDEX]
+                    .get(row_index, "scanFile.fileConstantValues.partitionValues")?;
+            let file = FileInfo {
+                stats,
+                deleted_rows: deletion_vector.as_ref().map_or(0, |dv| dv.cardinality),
+                has_deletion_vector: deletion_vector.is_some(),
+                partition_values: &partition_values,
+            };
+            (self.on_file)(&file);
+        }
+        Ok(())
+    }
+}
+
+/// Calls `on_file` with every live file of `snapshot`.
+pub(super) fn visit_scan_files(
+    snapshot: SnapshotRef,
+    engine: &dyn Engine,
+    mut on_file: impl FnMut(&FileInfo<'_>),
+) -> DeltaResult<()> {
+    let scan = snapshot.scan_builder().build()?;
+    for scan_metadata in scan.scan_metadata(engine)? {
+        let scan_metadata = scan_metadata?;
+        let mut visitor = ScanFileVisitor {
+            selection_vector: &scan_metadata.scan_files.selection_vector,
+            on_file: &mut on_file,
+        };
+        visitor.visit_rows_of(scan_metadata.scan_files.data.as_ref())?;
+    }
+    Ok(())
+}
+
+pub(crate) fn aggregate_from_stats(
+    snapshot: SnapshotRef,
+    engine: &dyn Engine,
+    aggregates: &[Aggregate],
+) -> DeltaResult<Vec<Option<Scalar>>> {
+    let mut states: Vec<_> = aggregates
+        .iter()
+        .map(|aggregate| AggregateState::try_new(&snapshot, aggregate))
+        .try_collect()?;
+    visit_scan_files(snapshot, engine, |file| {
+        for state in states.iter_mut() {
+            state.update(file);
+        }
+    })?;
+    Ok(states.into_iter().map(AggregateState::finish).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::expressions::{column_name, Scalar};
+    use crate::utils::test_utils::assert_result_error_with_message;
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn aggregate(table: &str, aggregates: &[Aggregate]) -> DeltaResult<Vec<Option<Scalar>>> {
+        let path = std::fs::canonicalize(PathBuf::from(table)).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        snapshot.aggregate_from_stats(&engine, aggregates)
+    }
+
+    #[test]
+    fn test_aggregate_without_dv() {
+        let result = aggregate(
+            "./tests/data/table-without-dv-small/",
+            &[
+                Aggregate::Count,
+                Aggregate::Min(column_name!("value")),
+                Aggregate::Max(column_name!("value")),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Some(Scalar::Long(10)),
+                Some(Scalar::Long(0)),
+                Some(Scalar::Long(9))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_with_dv() {
+        // The DV's cardinality is exact, but it leaves the min/max stats loose
+        let result = aggregate(
+            "./tests/data/table-with-dv-small/",
+            &[
+                Aggregate::Count,
+                Aggregate::Min(column_name!("value")),
+                Aggregate::Max(column_name!("value")),
+            ],
+        )
+        .unwrap();
+        assert_eq!(result, vec![Some(Scalar::Long(8)), None, None]);
+    }
+
+    #[test]
+    fn test_aggregate_partitioned() {
+        let result = aggregate(
+            "./tests/data/basic_partitioned/",
+            &[
+                Aggregate::Count,
+                Aggregate::Min(column_name!("number")),
+                Aggregate::Max(column_name!("number")),
+                Aggregate::Min(column_name!("letter")),
+                Aggregate::Max(column_name!("letter")),
+                Aggregate::Max(column_name!("a_float")),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Some(Scalar::Long(6)),
+                Some(Scalar::Long(1)),
+                Some(Scalar::Long(6)),
+                Some(Scalar::from("a")),
+                Some(Scalar::from("e")),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partition_bound_of_deleted_file() {
+        let partition_values = HashMap::from([("letter".to_string(), "a".to_string())]);
+        let file = |stats: Option<Value>, deleted_rows| FileInfo {
+            stats,
+            deleted_rows,
+            has_deletion_vector: deleted_rows > 0,
+            partition_values: &partition_values,
+        };
+        let bound = |file: &FileInfo<'_>| {
+            let source = ValueSource::Partition("letter".to_string());
+            AggregateState::file_bound(false, &PrimitiveType::String, &source, file)
+        };
+        let num_records = |n: i64| Some(serde_json::json!({ "numRecords": n }));
+
+        assert_eq!(
+            bound(&file(num_records(2), 1)),
+            Some(Some(Scalar::from("a")))
+        );
+        // a file whose rows are all deleted contributes no value
+        assert_eq!(bound(&file(num_records(2), 2)), Some(None));
+        // without a row count, a file with a DV may or may not have live rows
+        assert_eq!(bound(&file(None, 1)), None);
+        assert_eq!(bound(&file(None, 0)), Some(Some(Scalar::from("a"))));
+    }
+
+    #[test]
+    fn test_aggregate_missing_column() {
+        let result = aggregate(
+            "./tests/data/basic_partitioned/",
+            &[Aggregate::Min(column_name!("nope"))],
+        );
+        assert_result_error_with_message(result, "nope");
+    }
+}
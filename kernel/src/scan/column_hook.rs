@@ -0,0 +1,80 @@
+//! Hooks that transform the data of selected columns right after a scan reads it, e.g. to decrypt
+//! or mask columns, see [`ColumnHook`].
+//!
+//! Hooks are registered on a [`ScanBuilder`] for a field metadata key, and apply to every column
+//! the scan reads whose field metadata has that key. Security layers can thus tag the columns they
+//! manage in the table schema (e.g. with the key of their encryption scheme), and integrate with
+//! the scan without a custom parquet handler. The hooks of a scan are combined into a single
+//! physical transform, see [`Scan::physical_transform`], which [`Scan::execute`] applies to the
+//! data of every file before transforming it into the logical schema.
+//!
+//! [`ScanBuilder`]: super::ScanBuilder
+//! [`Scan::physical_transform`]: super::Scan::physical_transform
+//! [`Scan::execute`]: super::Scan::execute
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::expressions::{Expression, ExpressionRef, Transform};
+use crate::schema::{MetadataValue, Schema, StructField};
+use crate::transforms::ColumnType;
+use crate::{DeltaResult, Error};
+
+/// Transforms the data of the columns it is registered for, see the [module docs](self).
+pub trait ColumnHook: Debug + Send + Sync {
+    /// Returns the expression that computes the new data of the column described by `field` from
+    /// the data read from a file. `value` is the value of the field's metadata key that the hook
+    /// is registered for.
+    ///
+    /// The expression is evaluated against the physical data of the file (see
+    /// [`Scan::physical_schema`]), so it must reference columns by their physical name, passed as
+    /// `physical_name`. It must produce data of the column's physical type, e.g. by wrapping an
+    /// [opaque expression] the engine implements.
+    ///
+    /// [`Scan::physical_schema`]: super::Scan::physical_schema
+    /// [opaque expression]: crate::expressions::OpaqueExpressionOp
+    fn transform_column(
+        &self,
+        field: &StructField,
+        physical_name: &str,
+        value: &MetadataValue,
+    ) -> DeltaResult<ExpressionRef>;
+}
+
+/// Combines the transforms of `hooks` (keyed by field metadata key) for the columns of a scan into
+/// a single expression over its physical data, or returns `None` if no hook applies. Only
+/// top-level columns read from the data files can be transformed, and at most one hook may apply
+/// to each column.
+pub(crate) fn physical_transform(
+    hooks: &[(String, Arc<dyn ColumnHook>)],
+    logical_schema: &Schema,
+    all_fields: &[ColumnType],
+) -> DeltaResult<Option<ExpressionRef>> {
+    if hooks.is_empty() {
+        return Ok(None);
+    }
+    let mut transform = Transform::new_top_level();
+    let mut any_applied = false;
+    for (field, column) in logical_schema.fields().zip(all_fields) {
+        let ColumnType::Selected(physical_name) = column else {
+            continue; // partition columns are not read from the data files
+        };
+        let mut matching = hooks.iter().filter_map(|(key, hook)| {
+            let value = field.metadata().get(key)?;
+            Some((key, hook, value))
+        });
+        let Some((key, hook, value)) = matching.next() else {
+            continue;
+        };
+        if let Some((other_key, ..)) = matching.next() {
+            return Err(Error::generic(format!(
+                "Column {} matches the column hooks of both {key} and {other_key}",
+                field.name()
+            )));
+        }
+        let expr = hook.transform_column(field, physical_name, value)?;
+        transform = transform.with_replaced_field(physical_name.clone(), expr);
+        any_applied = true;
+    }
+    Ok(any_applied.then(|| Arc::new(Expression::Transform(transform))))
+}
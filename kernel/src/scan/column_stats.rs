@@ -0,0 +1,304 @@
+//! Table-level column statistics, aggregated from the file statistics recorded in the Delta log
+//! without reading any data. See [`Snapshot::column_stats`].
+//!
+//! Unlike [`aggregate_from_stats`], which only answers aggregates it can answer exactly, these
+//! statistics are meant for cost-based planning: they are computed from whatever stats the files
+//! have, and marked approximate whenever some file lacks stats or has inexact ones.
+//!
+//! [`Snapshot::column_stats`]: crate::Snapshot::column_stats
+//! [`aggregate_from_stats`]: crate::Snapshot::aggregate_from_stats
+
+use serde_json::Value;
+use tracing::warn;
+
+use crate::expressions::{ColumnName, Scalar};
+use crate::schema::{DataType, PrimitiveType, StructType};
+use crate::snapshot::SnapshotRef;
+use crate::{DeltaResult, Engine};
+
+use super::aggregate::{visit_scan_files, FileInfo, ValueSource};
+
+/// The statistics of a snapshot, see [`Snapshot::column_stats`].
+///
+/// [`Snapshot::column_stats`]: crate::Snapshot::column_stats
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableStats {
+    /// The number of data files in the snapshot.
+    pub num_files: u64,
+    /// The number of rows in the snapshot, accounting for deletion vectors. Only counts the rows
+    /// of files that record `numRecords`.
+    pub num_rows: i64,
+    /// Whether `num_rows` may be too low, because some file does not record `numRecords`.
+    pub num_rows_is_approximate: bool,
+    /// The statistics of every primitive (logical) column of the table schema, in schema order.
+    /// Columns nested in arrays or maps have no statistics and are left out.
+    pub columns: Vec<ColumnStats>,
+}
+
+/// The statistics of one column of a snapshot, see [`TableStats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// The logical name of the column.
+    pub column: ColumnName,
+    /// The smallest non-null value of the column, or `None` if no file records one.
+    pub min: Option<Scalar>,
+    /// The largest non-null value of the column, or `None` if no file records one.
+    pub max: Option<Scalar>,
+    /// The number of null values in the column, or `None` if no file records one.
+    pub null_count: Option<i64>,
+    /// Whether the statistics may be inexact: some file lacks stats for the column, or has stats
+    /// that a deletion vector left loose. Note that writers may also truncate string bounds.
+    pub is_approximate: bool,
+}
+
+/// The running statistics of one column.
+struct ColumnState {
+    data_type: PrimitiveType,
+    source: ValueSource,
+    stats: ColumnStats,
+}
+
+impl ColumnState {
+    fn new(column: ColumnName, data_type: PrimitiveType, source: ValueSource) -> Self {
+        Self {
+            data_type,
+            source,
+            stats: ColumnStats {
+                column,
+                min: None,
+                max: None,
+                null_count: None,
+                is_approximate: false,
+            },
+        }
+    }
+
+    fn update(&mut self, file: &FileInfo<'_>) {
+        let live_rows = file
+            .num_records()
+            .map(|num_records| num_records.saturating_sub(file.deleted_rows));
+        match &self.source {
+            ValueSource::Partition(key) => {
+                let value = file
+                    .partition_values
+                    .get(key)
+                    .map(|raw| self.parse(&Value::String(raw.clone())));
+                match value {
+                    Some(Some(Some(value))) => self.add_bounds(Some(value.clone()), Some(value)),
+                    // a null partition value, or no value at all
+                    Some(Some(None)) | None => match live_rows {
+                        Some(live_rows) => self.add_null_count(live_rows),
+                        None => self.stats.is_approximate = true,
+                    },
+                    Some(None) => self.stats.is_approximate = true,
+                }
+            }
+            ValueSource::Stats(path) => {
+                let Some(live_rows) = live_rows else {
+                    self.stats.is_approximate = true;
+                    return;
+                };
+                if live_rows == 0 {
+                    return;
+                }
+                if !file.tight_bounds() || file.has_deletion_vector {
+                    self.stats.is_approximate = true;
+                }
+                let null_count = file.stat("nullCount", path).and_then(Value::as_i64);
+                let all_null = null_count == file.num_records();
+                let bound = |kind| match file.stat(kind, path) {
+                    Some(raw) => self.parse(raw),
+                    // A missing bound is only expected if every row of the file is null
+                    None => all_null.then_some(None),
+                };
+                let (min, max) = (bound("minValues"), bound("maxValues"));
+                match (min, max) {
+                    (Some(min), Some(max)) => self.add_bounds(min, max),
+                    _ => self.stats.is_approximate = true,
+                }
+                match null_count {
+                    Some(null_count) => self.add_null_count(null_count),
+                    None => self.stats.is_approximate = true,
+                }
+            }
+        }
+    }
+
+    /// Parses a raw partition value or stats bound, returning `Some(None)` for null values, and
+    /// `None` if the value cannot be parsed.
+    fn parse(&self, raw: &Value) -> Option<Option<Scalar>> {
+        let parsed = match (raw, &self.data_type) {
+            // Strings are taken as they are, since `parse_scalar` reads empty strings as null
+            (Value::String(raw), PrimitiveType::String) => Ok(Scalar::String(raw.clone())),
+            (Value::String(raw), data_type) => data_type.parse_scalar(raw),
+            (Value::Number(raw), data_type) => data_type.parse_scalar(&raw.to_string()),
+            (Value::Bool(raw), data_type) => data_type.parse_scalar(&raw.to_string()),
+            (Value::Null, data_type) => Ok(Scalar::Null(DataType::Primitive(data_type.clone()))),
+            (raw, data_type) => {
+                warn!("Unexpected {data_type:?} stats value {raw}");
+                return None;
+            }
+        };
+        match parsed {
+            Ok(Scalar::Null(_)) => Some(None),
+            Ok(scalar) => Some(Some(scalar)),
+            Err(err) => {
+                warn!("Cannot parse {raw} as {:?}: {err}", self.data_type);
+                None
+            }
+        }
+    }
+
+    fn add_bounds(&mut self, min: Option<Scalar>, max: Option<Scalar>) {
+        if let Some(min) = min {
+            if self.stats.min.as_ref().is_none_or(|current| min < *current) {
+                self.stats.min = Some(min);
+            }
+        }
+        if let Some(max) = max {
+            if self.stats.max.as_ref().is_none_or(|current| max > *current) {
+                self.stats.max = Some(max);
+            }
+        }
+    }
+
+    fn add_null_count(&mut self, null_count: i64) {
+        let current = self.stats.null_count.unwrap_or(0);
+        self.stats.null_count = Some(current.saturating_add(null_count));
+    }
+}
+
+/// Collects a [`ColumnState`] for every primitive column nested in `schema` (whose logical and
+/// physical paths are `logical` and `physical`) into `columns`.
+fn collect_columns(
+    schema: &StructType,
+    logical: &[String],
+    physical: &[String],
+    partition_columns: &[String],
+    columns: &mut Vec<ColumnState>,
+) {
+    for field in schema.fields() {
+        let logical = [logical, &[field.name().clone()]].concat();
+        let physical = [physical, &[field.physical_name().to_string()]].concat();
+        match field.data_type() {
+            DataType::Primitive(data_type) => {
+                let is_partition_column =
+                    logical.len() == 1 && partition_columns.contains(field.name());
+                let source = match is_partition_column {
+                    true => ValueSource::Partition(field.physical_name().to_string()),
+                    false => ValueSource::Stats(physical),
+                };
+                let column = ColumnName::new(logical);
+                columns.push(ColumnState::new(column, data_type.clone(), source));
+            }
+            DataType::Struct(inner) => {
+                collect_columns(inner, &logical, &physical, partition_columns, columns)
+            }
+            DataType::Array(_) | DataType::Map(_) | DataType::Variant(_) => {}
+        }
+    }
+}
+
+pub(crate) fn column_stats(snapshot: SnapshotRef, engine: &dyn Engine) -> DeltaResult<TableStats> {
+    let mut columns = vec![];
+    collect_columns(
+        &snapshot.schema(),
+        &[],
+        &[],
+        &snapshot.metadata().partition_columns,
+        &mut columns,
+    );
+    let mut num_files = 0;
+    let mut num_rows: i64 = 0;
+    let mut num_rows_is_approximate = false;
+    visit_scan_files(snapshot, engine, |file| {
+        num_files += 1;
+        match file.num_records() {
+            Some(num_records) => {
+                let live_rows = num_records.saturating_sub(file.deleted_rows);
+                num_rows = num_rows.saturating_add(live_rows);
+            }
+            None => num_rows_is_approximate = true,
+        }
+        for column in columns.iter_mut() {
+            column.update(file);
+        }
+    })?;
+    Ok(TableStats {
+        num_files,
+        num_rows,
+        num_rows_is_approximate,
+        columns: columns.into_iter().map(|column| column.stats).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::expressions::column_name;
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn table_stats(table: &str) -> TableStats {
+        let path = std::fs::canonicalize(PathBuf::from(table)).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        snapshot.column_stats(&engine).unwrap()
+    }
+
+    fn column<'a>(stats: &'a TableStats, column: &ColumnName) -> &'a ColumnStats {
+        stats
+            .columns
+            .iter()
+            .find(|stats| stats.column == *column)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_column_stats_without_dv() {
+        let stats = table_stats("./tests/data/table-without-dv-small/");
+        assert_eq!(stats.num_files, 1);
+        assert_eq!(stats.num_rows, 10);
+        assert!(!stats.num_rows_is_approximate);
+        assert_eq!(
+            column(&stats, &column_name!("value")),
+            &ColumnStats {
+                column: column_name!("value"),
+                min: Some(Scalar::Long(0)),
+                max: Some(Scalar::Long(9)),
+                null_count: Some(0),
+                is_approximate: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_column_stats_with_dv() {
+        // The DV's cardinality is exact, but it leaves the column stats loose
+        let stats = table_stats("./tests/data/table-with-dv-small/");
+        assert_eq!(stats.num_rows, 8);
+        assert!(!stats.num_rows_is_approximate);
+        let value = column(&stats, &column_name!("value"));
+        assert_eq!(value.min, Some(Scalar::Long(0)));
+        assert_eq!(value.max, Some(Scalar::Long(9)));
+        assert!(value.is_approximate);
+    }
+
+    #[test]
+    fn test_column_stats_partitioned() {
+        let stats = table_stats("./tests/data/basic_partitioned/");
+        assert_eq!(stats.num_rows, 6);
+        let letter = column(&stats, &column_name!("letter"));
+        assert_eq!(letter.min, Some(Scalar::from("a")));
+        assert_eq!(letter.max, Some(Scalar::from("e")));
+        assert!(!letter.is_approximate);
+        let number = column(&stats, &column_name!("number"));
+        assert_eq!(number.min, Some(Scalar::Long(1)));
+        assert_eq!(number.max, Some(Scalar::Long(6)));
+        assert_eq!(number.null_count, Some(0));
+    }
+}
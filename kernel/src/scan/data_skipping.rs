@@ -2,11 +2,12 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::sync::{Arc, LazyLock};
 
-use tracing::debug;
+use tracing::{debug, debug_span};
 
-use crate::actions::get_log_add_schema;
 use crate::actions::visitors::SelectionVectorVisitor;
-use crate::error::DeltaResult;
+use crate::actions::{get_log_add_schema, ADD_NAME, STATS_PARSED_NAME};
+use crate::engine_data::{GetData, TypedGetData as _};
+use crate::error::{DeltaResult, Error};
 use crate::expressions::{
     column_expr, joined_column_expr, BinaryPredicateOp, ColumnName, Expression as Expr,
     ExpressionRef, JunctionPredicateOp, OpaquePredicateOpRef, Predicate as Pred, PredicateRef,
@@ -15,10 +16,15 @@ use crate::expressions::{
 use crate::kernel_predicates::{
     DataSkippingPredicateEvaluator, KernelPredicateEvaluator, KernelPredicateEvaluatorDefaults,
 };
-use crate::schema::{DataType, PrimitiveType, SchemaRef, SchemaTransform, StructField, StructType};
-use crate::{
-    Engine, EngineData, ExpressionEvaluator, JsonHandler, PredicateEvaluator, RowVisitor as _,
+use crate::scan::skipping_trace::{
+    ClauseResult, SkippingTarget, SkippingTrace, SkippingTraceEntry,
 };
+use crate::schema::{
+    column_name, ColumnNamesAndTypes, DataType, PrimitiveType, SchemaRef, SchemaTransform,
+    StructField, StructType,
+};
+use crate::utils::require;
+use crate::{Engine, EngineData, ExpressionEvaluator, JsonHandler, PredicateEvaluator, RowVisitor};
 
 #[cfg(test)]
 mod tests;
@@ -32,7 +38,9 @@ mod tests;
 ///
 /// For Unary `Not`, we push the Not down using De Morgan's Laws to invert everything below the Not.
 ///
-/// Unary `IsNull` checks if the null counts indicate that the column could contain a null.
+/// Unary `IsNull` checks if the null counts indicate that the column could contain a null. Null
+/// counts only exist for leaf columns, so a struct column can't contain a null if any of its leaves
+/// doesn't (a null struct makes all its leaves null).
 ///
 /// The junction operations are rewritten as follows:
 /// - `AND` is rewritten as a conjunction of the rewritten operands where we just skip operands that
@@ -41,21 +49,108 @@ mod tests;
 ///   predicate is dropped.
 #[cfg(test)]
 pub(crate) fn as_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
-    DataSkippingPredicateCreator.eval(pred)
+    DataSkippingPredicateCreator::default().eval(pred)
 }
 
 /// Like `as_data_skipping_predicate`, but invokes [`KernelPredicateEvaluator::eval_sql_where`]
-/// instead of [`KernelPredicateEvaluator::eval`].
-fn as_sql_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
-    DataSkippingPredicateCreator.eval_sql_where(pred)
+/// instead of [`KernelPredicateEvaluator::eval`]. The predicate's columns are looked up in `schema`.
+fn as_sql_data_skipping_predicate(pred: &Pred, schema: &StructType) -> Option<Pred> {
+    let creator = DataSkippingPredicateCreator {
+        schema: Some(schema),
+    };
+    creator.eval_sql_where(pred)
 }
 
 pub(crate) struct DataSkippingFilter {
     stats_schema: SchemaRef,
     select_stats_evaluator: Arc<dyn ExpressionEvaluator>,
+    select_stats_parsed_evaluator: Arc<dyn ExpressionEvaluator>,
     skipping_evaluator: Arc<dyn PredicateEvaluator>,
     filter_evaluator: Arc<dyn PredicateEvaluator>,
     json_handler: Arc<dyn JsonHandler>,
+    tracer: Option<SkippingTracer>,
+}
+
+/// Returns the schema of parsed file statistics (i.e. of `add.stats` or `add.stats_parsed`) for
+/// the columns of `data_schema`. Returns None if none of the columns can have stats.
+pub(crate) fn stats_schema(data_schema: &StructType) -> Option<SchemaRef> {
+    // Convert all fields into nullable, as stats may not be available for all columns
+    // (and usually aren't for partition columns).
+    struct NullableStatsTransform;
+    impl<'a> SchemaTransform<'a> for NullableStatsTransform {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            use Cow::*;
+            let field = match self.transform(&field.data_type)? {
+                Borrowed(_) if field.is_nullable() => Borrowed(field),
+                data_type => Owned(StructField {
+                    name: field.name.clone(),
+                    data_type: data_type.into_owned(),
+                    nullable: true,
+                    metadata: field.metadata.clone(),
+                }),
+            };
+            Some(field)
+        }
+    }
+
+    // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
+    struct NullCountStatsTransform;
+    impl<'a> SchemaTransform<'a> for NullCountStatsTransform {
+        fn transform_primitive(
+            &mut self,
+            _ptype: &'a PrimitiveType,
+        ) -> Option<Cow<'a, PrimitiveType>> {
+            Some(Cow::Owned(PrimitiveType::Long))
+        }
+    }
+
+    let stats_schema = NullableStatsTransform
+        .transform_struct(data_schema)?
+        .into_owned();
+
+    let nullcount_schema = NullCountStatsTransform
+        .transform_struct(&stats_schema)?
+        .into_owned();
+    Some(Arc::new(StructType::new_unchecked([
+        StructField::nullable("numRecords", DataType::LONG),
+        StructField::nullable("nullCount", nullcount_schema),
+        StructField::nullable("minValues", stats_schema.clone()),
+        StructField::nullable("maxValues", stats_schema),
+        StructField::nullable("tightBounds", DataType::BOOLEAN),
+    ])))
+}
+
+/// Returns a copy of the log `schema`, with a nullable `add.stats_parsed` field of type
+/// `stats_schema` appended to its `add` struct. Schemas without an `add` struct are unchanged.
+pub(crate) fn with_stats_parsed(schema: &StructType, stats_schema: &SchemaRef) -> StructType {
+    let fields = schema.fields().map(|field| match field.data_type() {
+        DataType::Struct(add) if field.name() == ADD_NAME => {
+            let stats_parsed =
+                StructField::nullable(STATS_PARSED_NAME, stats_schema.as_ref().clone());
+            let add = StructType::new_unchecked(add.fields().cloned().chain([stats_parsed]));
+            StructField {
+                data_type: add.into(),
+                ..field.clone()
+            }
+        }
+        _ => field.clone(),
+    });
+    StructType::new_unchecked(fields)
+}
+
+/// Returns a struct expression that selects the fields of `schema` (recursively) from the struct
+/// column at `path`, ignoring any other fields the column may have.
+fn select_fields(path: &ColumnName, schema: &StructType) -> Expr {
+    Expr::struct_from(schema.fields().map(|field| {
+        let path = path.join(&ColumnName::new([field.name()]));
+        match field.data_type() {
+            DataType::Struct(inner) => select_fields(&path, inner),
+            _ => Expr::from(path),
+        }
+    }))
 }
 
 impl DataSkippingFilter {
@@ -64,9 +159,12 @@ impl DataSkippingFilter {
     ///
     /// NOTE: None is equivalent to a trivial filter that always returns TRUE (= keeps all files),
     /// but using an Option lets the engine easily avoid the overhead of applying trivial filters.
+    ///
+    /// If a `trace` is provided, the per-clause outcome of skipping every file is recorded in it.
     pub(crate) fn new(
         engine: &dyn Engine,
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
+        trace: Option<Arc<SkippingTrace>>,
     ) -> Option<Self> {
         static STATS_EXPR: LazyLock<ExpressionRef> =
             LazyLock::new(|| Arc::new(column_expr!("add.stats")));
@@ -76,52 +174,7 @@ impl DataSkippingFilter {
         let (predicate, referenced_schema) = physical_predicate?;
         debug!("Creating a data skipping filter for {:#?}", predicate);
 
-        // Convert all fields into nullable, as stats may not be available for all columns
-        // (and usually aren't for partition columns).
-        struct NullableStatsTransform;
-        impl<'a> SchemaTransform<'a> for NullableStatsTransform {
-            fn transform_struct_field(
-                &mut self,
-                field: &'a StructField,
-            ) -> Option<Cow<'a, StructField>> {
-                use Cow::*;
-                let field = match self.transform(&field.data_type)? {
-                    Borrowed(_) if field.is_nullable() => Borrowed(field),
-                    data_type => Owned(StructField {
-                        name: field.name.clone(),
-                        data_type: data_type.into_owned(),
-                        nullable: true,
-                        metadata: field.metadata.clone(),
-                    }),
-                };
-                Some(field)
-            }
-        }
-
-        // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
-        struct NullCountStatsTransform;
-        impl<'a> SchemaTransform<'a> for NullCountStatsTransform {
-            fn transform_primitive(
-                &mut self,
-                _ptype: &'a PrimitiveType,
-            ) -> Option<Cow<'a, PrimitiveType>> {
-                Some(Cow::Owned(PrimitiveType::Long))
-            }
-        }
-
-        let stats_schema = NullableStatsTransform
-            .transform_struct(&referenced_schema)?
-            .into_owned();
-
-        let nullcount_schema = NullCountStatsTransform
-            .transform_struct(&stats_schema)?
-            .into_owned();
-        let stats_schema = Arc::new(StructType::new_unchecked([
-            StructField::nullable("numRecords", DataType::LONG),
-            StructField::nullable("nullCount", nullcount_schema),
-            StructField::nullable("minValues", stats_schema.clone()),
-            StructField::nullable("maxValues", stats_schema),
-        ]));
+        let stats_schema = stats_schema(&referenced_schema)?;
 
         // Skipping happens in several steps:
         //
@@ -141,60 +194,393 @@ impl DataSkippingFilter {
             DataType::STRING,
         );
 
+        // Parquet checkpoints may also carry the stats in struct form, which spares us step 1's
+        // JSON parsing for the files they cover. They may have been read with stats for more
+        // columns than this predicate needs, so only select the ones in our stats schema.
+        let select_stats_parsed_evaluator = engine.evaluation_handler().new_expression_evaluator(
+            Arc::new(with_stats_parsed(get_log_add_schema(), &stats_schema)),
+            Arc::new(select_fields(
+                &column_name!("add.stats_parsed"),
+                &stats_schema,
+            )),
+            stats_schema.as_ref().clone().into(),
+        );
+
         let skipping_evaluator = engine.evaluation_handler().new_predicate_evaluator(
             stats_schema.clone(),
-            Arc::new(as_sql_data_skipping_predicate(&predicate)?),
+            Arc::new(as_sql_data_skipping_predicate(
+                &predicate,
+                &referenced_schema,
+            )?),
         );
 
         let filter_evaluator = engine
             .evaluation_handler()
             .new_predicate_evaluator(stats_schema.clone(), FILTER_PRED.clone());
 
+        let tracer = trace.map(|trace| {
+            SkippingTracer::new(engine, trace, &predicate, &referenced_schema, &stats_schema)
+        });
+
         Some(Self {
             stats_schema,
             select_stats_evaluator,
+            select_stats_parsed_evaluator,
             skipping_evaluator,
             filter_evaluator,
             json_handler: engine.json_handler(),
+            tracer,
         })
     }
 
     /// Apply the DataSkippingFilter to an EngineData batch of actions. Returns a selection vector
     /// which can be applied to the actions to find those that passed data skipping.
     pub(crate) fn apply(&self, actions: &dyn EngineData) -> DeltaResult<Vec<bool>> {
-        // retrieve and parse stats from actions data
+        let parsed_stats = self.parse_stats(actions, &vec![true; actions.len()])?;
+        self.apply_to_stats(parsed_stats.as_ref())
+
+        // TODO(zach): add some debug info about data skipping that occurred
+        // let before_count = actions.length();
+        // debug!(
+        //     "number of actions before/after data skipping: {before_count} / {}",
+        //     filtered_actions.num_rows()
+        // );
+    }
+
+    /// Retrieves and parses the JSON stats of the `rows` of a batch of actions, returning one row
+    /// of parsed stats per row of `rows`.
+    fn parse_stats(
+        &self,
+        actions: &dyn EngineData,
+        rows: &[bool],
+    ) -> DeltaResult<Box<dyn EngineData>> {
         let stats = self.select_stats_evaluator.evaluate(actions)?;
         assert_eq!(stats.len(), actions.len());
+        // Parsing dominates the cost of data skipping, so don't parse the stats of rows that log
+        // replay already deselected (e.g. adds of files that a newer commit removed).
+        let stats = select_rows(stats, rows)?;
+        let num_rows = stats.len();
         let parsed_stats = self
             .json_handler
             .parse_json(stats, self.stats_schema.clone())?;
-        assert_eq!(parsed_stats.len(), actions.len());
+        assert_eq!(parsed_stats.len(), num_rows);
+        Ok(parsed_stats)
+    }
 
+    /// Evaluates the skipping predicate over a batch of parsed stats (one row per action), and
+    /// returns the resulting selection vector.
+    fn apply_to_stats(&self, parsed_stats: &dyn EngineData) -> DeltaResult<Vec<bool>> {
         // evaluate the predicate on the parsed stats, then convert to selection vector
-        let skipping_predicate = self.skipping_evaluator.evaluate(&*parsed_stats)?;
-        assert_eq!(skipping_predicate.len(), actions.len());
+        let skipping_predicate = self.skipping_evaluator.evaluate(parsed_stats)?;
+        assert_eq!(skipping_predicate.len(), parsed_stats.len());
         let selection_vector = self
             .filter_evaluator
             .evaluate(skipping_predicate.as_ref())?;
-        assert_eq!(selection_vector.len(), actions.len());
+        assert_eq!(selection_vector.len(), parsed_stats.len());
 
         // visit the engine's selection vector to produce a Vec<bool>
         let mut visitor = SelectionVectorVisitor::default();
         visitor.visit_rows_of(selection_vector.as_ref())?;
         Ok(visitor.selection_vector)
+    }
 
-        // TODO(zach): add some debug info about data skipping that occurred
-        // let before_count = actions.length();
-        // debug!(
-        //     "number of actions before/after data skipping: {before_count} / {}",
-        //     filtered_actions.num_rows()
-        // );
+    /// Apply the DataSkippingFilter to the rows of `actions` that are still selected by
+    /// `selection_vector`, deselecting those whose stats prove they can be skipped.
+    ///
+    /// Stats parsing is the most expensive part of data skipping, so it is skipped entirely when
+    /// no rows are selected. Because the stats schema only contains the columns referenced by the
+    /// predicate, the JSON parser also ignores the stats of all other columns.
+    ///
+    /// If `has_stats_parsed` is true, `actions` were read with an `add.stats_parsed` column (see
+    /// [`with_stats_parsed`]), whose struct-typed stats are used instead of parsing the JSON
+    /// stats. The JSON stats are then only parsed if some selected add lacks `stats_parsed`.
+    pub(crate) fn apply_to_selection(
+        &self,
+        actions: &dyn EngineData,
+        selection_vector: &mut [bool],
+        has_stats_parsed: bool,
+    ) -> DeltaResult<()> {
+        assert_eq!(selection_vector.len(), actions.len());
+        if !selection_vector.contains(&true) {
+            return Ok(());
+        }
+        let _span = debug_span!("data_skipping", rows = actions.len(), has_stats_parsed).entered();
+        if !has_stats_parsed {
+            let json_rows = selection_vector.to_vec();
+            let parsed_stats = self.parse_stats(actions, &json_rows)?;
+            return self.filter_rows(actions, parsed_stats.as_ref(), selection_vector, &json_rows);
+        }
+
+        let mut visitor = StatsSourceVisitor::default();
+        visitor.visit_rows_of(actions)?;
+        let parsed_rows: Vec<_> = selection_vector
+            .iter()
+            .zip(&visitor.has_stats_parsed)
+            .map(|(&selected, &has_stats_parsed)| selected && has_stats_parsed)
+            .collect();
+        // Rows without any stats can't be skipped, so we only need their (empty) JSON stats if we
+        // are tracing.
+        let json_rows: Vec<_> = selection_vector
+            .iter()
+            .zip(visitor.has_stats_parsed.iter().zip(&visitor.has_stats))
+            .map(|(&selected, (&has_stats_parsed, &has_stats))| {
+                selected && !has_stats_parsed && (has_stats || self.tracer.is_some())
+            })
+            .collect();
+        if parsed_rows.contains(&true) {
+            let stats_parsed = self.select_stats_parsed_evaluator.evaluate(actions)?;
+            assert_eq!(stats_parsed.len(), actions.len());
+            let stats_parsed = select_rows(stats_parsed, &parsed_rows)?;
+            self.filter_rows(
+                actions,
+                stats_parsed.as_ref(),
+                selection_vector,
+                &parsed_rows,
+            )?;
+        }
+        if json_rows.contains(&true) {
+            let parsed_stats = self.parse_stats(actions, &json_rows)?;
+            self.filter_rows(actions, parsed_stats.as_ref(), selection_vector, &json_rows)?;
+        }
+        Ok(())
+    }
+
+    /// Deselects those `rows` of `selection_vector` whose `parsed_stats` (one row per row of
+    /// `rows`) prove they can be skipped, and traces the outcome for each of them.
+    fn filter_rows(
+        &self,
+        actions: &dyn EngineData,
+        parsed_stats: &dyn EngineData,
+        selection_vector: &mut [bool],
+        rows: &[bool],
+    ) -> DeltaResult<()> {
+        let skipping_vector = self.apply_to_stats(parsed_stats)?;
+        if let Some(tracer) = &self.tracer {
+            tracer.record(actions, parsed_stats, &skipping_vector, rows)?;
+        }
+        let selected_rows = selection_vector
+            .iter_mut()
+            .zip(rows)
+            .filter_map(|(selected, &row)| row.then_some(selected));
+        for (selected, keep) in selected_rows.zip(skipping_vector) {
+            *selected &= keep;
+        }
+        Ok(())
+    }
+}
+
+/// The rows of `data` that `rows` selects, without filtering if it selects all of them.
+fn select_rows(data: Box<dyn EngineData>, rows: &[bool]) -> DeltaResult<Box<dyn EngineData>> {
+    if rows.contains(&false) {
+        data.apply_selection_vector(rows)
+    } else {
+        Ok(data)
+    }
+}
+
+/// Records which clauses of the predicate allowed (or failed) to skip each file in a
+/// [`SkippingTrace`]. Each clause is evaluated on its own, so this is only done if requested.
+struct SkippingTracer {
+    trace: Arc<SkippingTrace>,
+    /// Each clause of the predicate, with an evaluator for its data skipping predicate (if the
+    /// clause is eligible for data skipping at all).
+    clauses: Vec<(Pred, Option<Arc<dyn PredicateEvaluator>>)>,
+}
+
+impl SkippingTracer {
+    fn new(
+        engine: &dyn Engine,
+        trace: Arc<SkippingTrace>,
+        predicate: &Pred,
+        referenced_schema: &StructType,
+        stats_schema: &SchemaRef,
+    ) -> Self {
+        let clauses = predicate
+            .conjuncts()
+            .into_iter()
+            .map(|clause| {
+                let skipping_pred = as_sql_data_skipping_predicate(clause, referenced_schema);
+                let evaluator = skipping_pred.map(|skipping_pred| {
+                    engine
+                        .evaluation_handler()
+                        .new_predicate_evaluator(stats_schema.clone(), Arc::new(skipping_pred))
+                });
+                (clause.clone(), evaluator)
+            })
+            .collect();
+        Self { trace, clauses }
+    }
+
+    /// Traces the `rows` of `actions`, given their parsed stats and the skipping decision for each
+    /// of them (false = skipped), both with one row per row of `rows`.
+    fn record(
+        &self,
+        actions: &dyn EngineData,
+        parsed_stats: &dyn EngineData,
+        skipping_vector: &[bool],
+        rows: &[bool],
+    ) -> DeltaResult<()> {
+        let mut paths = AddPathVisitor::default();
+        paths.visit_rows_of(actions)?;
+        let results: Vec<_> = self
+            .clauses
+            .iter()
+            .map(|(_, evaluator)| match evaluator {
+                Some(evaluator) => {
+                    let output = evaluator.evaluate(parsed_stats)?;
+                    let mut visitor = ClauseOutputVisitor::default();
+                    visitor.visit_rows_of(output.as_ref())?;
+                    Ok(visitor.results)
+                }
+                None => Ok(vec![None; parsed_stats.len()]),
+            })
+            .collect::<DeltaResult<_>>()?;
+
+        let selected_paths = paths
+            .paths
+            .into_iter()
+            .zip(rows)
+            .filter_map(|(path, &row)| row.then_some(path));
+        let entries = selected_paths.enumerate().filter_map(|(i, path)| {
+            let path = path?;
+            let clauses = self
+                .clauses
+                .iter()
+                .zip(&results)
+                .map(|((clause, _), results)| ClauseResult {
+                    clause: clause.clone(),
+                    result: results[i],
+                })
+                .collect();
+            Some(SkippingTraceEntry {
+                target: SkippingTarget::File(path),
+                pruned: !skipping_vector[i],
+                clauses,
+            })
+        });
+        self.trace.record(entries);
+        Ok(())
+    }
+}
+
+/// Collects the path of each add action (None for other actions).
+#[derive(Default)]
+struct AddPathVisitor {
+    paths: Vec<Option<String>>,
+}
+
+impl RowVisitor for AddPathVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("add.path")], vec![DataType::STRING]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of AddPathVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.paths.push(getters[0].get_opt(i, "add.path")?);
+        }
+        Ok(())
     }
 }
 
-struct DataSkippingPredicateCreator;
+/// Collects the (nullable) output of a predicate evaluator.
+#[derive(Default)]
+struct ClauseOutputVisitor {
+    results: Vec<Option<bool>>,
+}
+
+impl RowVisitor for ClauseOutputVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("output")], vec![DataType::BOOLEAN]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of ClauseOutputVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.results.push(getters[0].get_opt(i, "output")?);
+        }
+        Ok(())
+    }
+}
+
+/// Records, for each add action, whether it has JSON stats and whether it has `stats_parsed`. The
+/// latter is missing e.g. if the checkpoint that contains the add was written without struct
+/// stats.
+#[derive(Default)]
+struct StatsSourceVisitor {
+    has_stats: Vec<bool>,
+    has_stats_parsed: Vec<bool>,
+}
+
+impl RowVisitor for StatsSourceVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let names = vec![
+                column_name!("add.stats"),
+                column_name!("add.stats_parsed.numRecords"),
+            ];
+            (names, vec![DataType::STRING, DataType::LONG]).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 2,
+            Error::InternalError(format!(
+                "Wrong number of StatsSourceVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let stats = getters[0].get_str(i, "add.stats")?;
+            let num_records = getters[1].get_long(i, "add.stats_parsed.numRecords")?;
+            self.has_stats.push(stats.is_some());
+            self.has_stats_parsed.push(num_records.is_some());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct DataSkippingPredicateCreator<'a> {
+    /// The schema of the columns the predicate references, if known. Null checks need it to tell
+    /// struct columns (whose null counts are stored per leaf) from leaf columns.
+    schema: Option<&'a StructType>,
+}
+
+impl DataSkippingPredicateCreator<'_> {
+    /// The type of the (possibly nested) column `col`, if the schema is known and contains it.
+    fn column_type(&self, col: &ColumnName) -> Option<&DataType> {
+        let (first, rest) = col.path().split_first()?;
+        let mut data_type = self.schema?.field(first)?.data_type();
+        for name in rest {
+            let DataType::Struct(fields) = data_type else {
+                return None;
+            };
+            data_type = fields.field(name)?.data_type();
+        }
+        Some(data_type)
+    }
+}
 
-impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
+impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator<'_> {
     type Output = Pred;
     type ColumnStat = Expr;
 
@@ -252,11 +638,45 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
     // NOTE: This is nearly identical to the impl for ParquetStatsProvider in
     // parquet_stats_skipping.rs, except it uses `Expression` and `Predicate` instead of `Scalar`.
     fn eval_pred_is_null(&self, col: &ColumnName, inverted: bool) -> Option<Pred> {
-        let safe_to_skip = match inverted {
-            true => self.get_rowcount_stat()?, // all-null
-            false => Expr::literal(0i64),      // no-null
-        };
-        Some(Pred::ne(self.get_nullcount_stat(col)?, safe_to_skip))
+        match self.column_type(col) {
+            // A struct has no null count of its own, but a null struct makes all of its leaves
+            // null. So the struct has no nulls if any of its leaves has none, while even all-null
+            // leaves don't prove that the struct itself is null.
+            Some(DataType::Struct(fields)) => {
+                if inverted {
+                    return None;
+                }
+                let leaves = fields.leaves(None);
+                let (names, types) = leaves.as_ref();
+                let no_nulls = names
+                    .iter()
+                    .zip(types)
+                    .filter(|(_, data_type)| matches!(data_type, DataType::Primitive(_)))
+                    .map(|(leaf, _)| {
+                        let nullcount = self.get_nullcount_stat(&col.join(leaf))?;
+                        Some(Pred::ne(nullcount, Expr::literal(0i64)))
+                    });
+                let output = Some(Pred::and_from(no_nulls.collect::<Option<Vec<_>>>()?));
+                self.unless_wide_bounds(output)
+            }
+            // Null counts of arrays, maps and variants are not supported
+            Some(DataType::Array(_) | DataType::Map(_) | DataType::Variant(_)) => None,
+            _ => {
+                let safe_to_skip = match inverted {
+                    true => self.get_rowcount_stat()?, // all-null
+                    false => Expr::literal(0i64),      // no-null
+                };
+                let output = Some(Pred::ne(self.get_nullcount_stat(col)?, safe_to_skip));
+                self.unless_wide_bounds(output)
+            }
+        }
+    }
+
+    /// Bounds are wide only if `tightBounds` is explicitly false; missing means tight.
+    fn eval_wide_bounds(&self) -> Option<Pred> {
+        Some(Pred::not(
+            column_expr!("tightBounds").distinct(Expr::literal(false)),
+        ))
     }
 
     fn eval_pred_binary_scalars(
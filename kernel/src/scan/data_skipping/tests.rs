@@ -31,6 +31,7 @@ fn test_eval_is_null() {
         let resolver = HashMap::from_iter([
             (column_name!("numRecords"), Scalar::from(2i64)),
             (column_name!("nullCount.x"), Scalar::from(nullcount)),
+            (column_name!("tightBounds"), Scalar::from(true)),
         ]);
         let filter = DefaultKernelPredicateEvaluator::from(resolver);
         for (pred, expect) in predicates.iter().zip(expected) {
@@ -53,6 +54,63 @@ fn test_eval_is_null() {
     do_test(2, &[TRUE, FALSE]);
 }
 
+#[test]
+fn test_eval_nested_is_null() {
+    let schema = StructType::new_unchecked([StructField::nullable(
+        "s",
+        StructType::new_unchecked([
+            StructField::nullable("x", DataType::INTEGER),
+            StructField::nullable("y", DataType::STRING),
+            StructField::nullable("arr", ArrayType::new(DataType::INTEGER, true)),
+        ]),
+    )]);
+    let creator = DataSkippingPredicateCreator {
+        schema: Some(&schema),
+    };
+
+    let do_test = |x_nulls: i64, y_nulls: Option<i64>, pred: &Pred, expect: Option<bool>| {
+        let mut resolver = HashMap::from_iter([
+            (column_name!("numRecords"), Scalar::from(2i64)),
+            (column_name!("nullCount.s.x"), Scalar::from(x_nulls)),
+            (column_name!("tightBounds"), Scalar::from(true)),
+        ]);
+        if let Some(y_nulls) = y_nulls {
+            resolver.insert(column_name!("nullCount.s.y"), Scalar::from(y_nulls));
+        }
+        let filter = DefaultKernelPredicateEvaluator::from(resolver);
+        let skipping_pred = creator.eval(pred).unwrap();
+        expect_eq!(
+            filter.eval(&skipping_pred),
+            expect,
+            "{pred:#?} became {skipping_pred:#?} ({x_nulls} and {y_nulls:?} nulls)"
+        );
+    };
+
+    // nested leaf columns use their own null counts
+    let pred = &Pred::is_null(column_expr!("s.x"));
+    do_test(0, Some(2), pred, FALSE);
+    do_test(1, Some(2), pred, TRUE);
+    let pred = &Pred::is_not_null(column_expr!("s.x"));
+    do_test(2, Some(0), pred, FALSE);
+    do_test(1, Some(0), pred, TRUE);
+
+    // a struct has no nulls if any of its leaves has none
+    let pred = &Pred::is_null(column_expr!("s"));
+    do_test(0, Some(2), pred, FALSE);
+    do_test(2, Some(0), pred, FALSE);
+    do_test(0, None, pred, FALSE);
+    do_test(1, Some(2), pred, TRUE);
+    do_test(1, None, pred, NULL);
+
+    // ... but all-null leaves don't prove that the struct is null
+    let pred = &Pred::is_not_null(column_expr!("s"));
+    assert_eq!(creator.eval(pred), None);
+
+    // null counts of arrays are not supported
+    let pred = &Pred::is_null(column_expr!("s.arr"));
+    assert_eq!(creator.eval(pred), None);
+}
+
 #[test]
 fn test_eval_binary_comparisons() {
     let col = &column_expr!("x");
@@ -74,6 +132,7 @@ fn test_eval_binary_comparisons() {
         let resolver = HashMap::from_iter([
             (column_name!("minValues.x"), min.clone()),
             (column_name!("maxValues.x"), max.clone()),
+            (column_name!("tightBounds"), Scalar::from(true)),
         ]);
         let filter = DefaultKernelPredicateEvaluator::from(resolver);
         for (pred, expect) in predicates.iter().zip(expected.iter()) {
@@ -209,6 +268,7 @@ fn test_eval_distinct() {
             (column_name!("nullCount.x"), Scalar::from(nullcount)),
             (column_name!("minValues.x"), min.clone()),
             (column_name!("maxValues.x"), max.clone()),
+            (column_name!("tightBounds"), Scalar::from(true)),
         ]);
         let filter = DefaultKernelPredicateEvaluator::from(resolver);
         for (pred, expect) in predicates.iter().zip(expected) {
@@ -280,6 +340,7 @@ fn test_sql_where() {
                     (column_name!("nullCount.x"), Scalar::from(nulls)),
                     (column_name!("minValues.x"), min.clone()),
                     (column_name!("maxValues.x"), max.clone()),
+                    (column_name!("tightBounds"), Scalar::from(true)),
                 ])
             };
             let filter = DefaultKernelPredicateEvaluator::from(resolver);
@@ -289,7 +350,9 @@ fn test_sql_where() {
                 expect,
                 "{pred:#?} became {skipping_pred:#?} ({min}..{max}, {nulls} nulls)"
             );
-            let skipping_sql_pred = as_sql_data_skipping_predicate(pred).unwrap();
+            let skipping_sql_pred = DataSkippingPredicateCreator::default()
+                .eval_sql_where(pred)
+                .unwrap();
             expect_eq!(
                 filter.eval(&skipping_sql_pred),
                 expect_sql,
@@ -340,11 +403,57 @@ fn test_sql_where() {
     do_test(ALL_NULL, pred, MISSING, None, None);
 }
 
+// Files with wide bounds (tightBounds = false) can still be skipped by range comparisons, but not
+// by nullcount-based or equality-only conclusions.
+#[test]
+fn test_eval_wide_bounds() {
+    let col = &column_expr!("x");
+    let ten = &Scalar::from(10);
+    let fifteen = &Scalar::from(15);
+
+    let predicates = [
+        Pred::is_null(col.clone()),
+        Pred::is_not_null(col.clone()),
+        Pred::ne(col.clone(), ten.clone()),
+        Pred::eq(col.clone(), ten.clone()),
+        Pred::gt(col.clone(), fifteen.clone()),
+    ];
+
+    let do_test = |nullcount: i64, tight_bounds: Option<bool>, expected: &[Option<bool>]| {
+        let mut resolver = HashMap::from_iter([
+            (column_name!("numRecords"), Scalar::from(2i64)),
+            (column_name!("nullCount.x"), Scalar::from(nullcount)),
+            (column_name!("minValues.x"), ten.clone()),
+            (column_name!("maxValues.x"), ten.clone()),
+        ]);
+        let tight_bounds = tight_bounds.map_or(Scalar::Null(DataType::BOOLEAN), Scalar::from);
+        resolver.insert(column_name!("tightBounds"), tight_bounds.clone());
+        let filter = DefaultKernelPredicateEvaluator::from(resolver);
+        for (pred, expect) in predicates.iter().zip(expected) {
+            let skipping_pred = as_data_skipping_predicate(pred).unwrap();
+            expect_eq!(
+                filter.eval(&skipping_pred),
+                *expect,
+                "{pred:#?} became {skipping_pred:#?} ({nullcount} nulls, tightBounds={tight_bounds})"
+            );
+        }
+    };
+
+    // Tight (or unknown) bounds allow skipping on nullcount and min = max = value
+    do_test(0, Some(true), &[FALSE, TRUE, FALSE, TRUE, FALSE]);
+    do_test(0, None, &[FALSE, TRUE, FALSE, TRUE, FALSE]);
+    do_test(2, Some(true), &[TRUE, FALSE, FALSE, TRUE, FALSE]);
+
+    // Wide bounds only allow skipping on range comparisons
+    do_test(0, Some(false), &[TRUE, TRUE, TRUE, TRUE, FALSE]);
+    do_test(2, Some(false), &[TRUE, TRUE, TRUE, TRUE, FALSE]);
+}
+
 // TODO(#1002): we currently don't support file skipping on timestamp columns' max stat since they
 // are truncated to milliseconds in add.stats.
 #[test]
 fn test_timestamp_skipping_disabled() {
-    let creator = DataSkippingPredicateCreator;
+    let creator = DataSkippingPredicateCreator::default();
     let col = &column_name!("timestamp_col");
 
     assert!(
@@ -402,7 +511,192 @@ fn test_timestamp_predicates_dont_data_skip() {
         let skipping_pred = as_data_skipping_predicate(&pred);
         assert_eq!(
             skipping_pred.unwrap().to_string(),
-            "OR(NOT(Column(minValues.ts_col) = 1000000), null)"
+            "OR(OR(NOT(Column(minValues.ts_col) = 1000000), null), \
+             NOT(DISTINCT(Column(tightBounds), false)))"
         );
     }
 }
+
+#[test]
+fn test_apply_to_selection_with_stats_parsed() {
+    use crate::arrow::array::StringArray;
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::string_array_to_engine_data;
+
+    let engine = SyncEngine::new();
+    let referenced_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
+        "x",
+        DataType::INTEGER,
+    )]));
+    let predicate = Arc::new(Pred::gt(column_expr!("x"), Expr::literal(10)));
+    let filter =
+        DataSkippingFilter::new(&engine, Some((predicate, referenced_schema.clone())), None)
+            .unwrap();
+    let stats_schema = stats_schema(&referenced_schema).unwrap();
+    let read_schema = Arc::new(with_stats_parsed(get_log_add_schema(), &stats_schema));
+
+    // Files a and b only have struct stats, c only has JSON stats, and d has no stats at all
+    let add = |path: &str, stats: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1,"dataChange":true{stats}}}}}"#
+        )
+    };
+    let json_strings: StringArray = vec![
+        add(
+            "a",
+            r#","stats_parsed":{"numRecords":1,"maxValues":{"x":5}}"#,
+        ),
+        add(
+            "b",
+            r#","stats_parsed":{"numRecords":1,"maxValues":{"x":50}}"#,
+        ),
+        add(
+            "c",
+            r#","stats":"{\"numRecords\":1,\"maxValues\":{\"x\":5}}""#,
+        ),
+        add("d", ""),
+    ]
+    .into();
+    let actions = engine
+        .json_handler()
+        .parse_json(string_array_to_engine_data(json_strings), read_schema)
+        .unwrap();
+
+    let mut selection_vector = vec![true; 4];
+    filter
+        .apply_to_selection(actions.as_ref(), &mut selection_vector, true)
+        .unwrap();
+    assert_eq!(selection_vector, [false, true, false, true]);
+
+    // Without stats_parsed, only the JSON stats can be used for skipping
+    let mut selection_vector = vec![true; 4];
+    filter
+        .apply_to_selection(actions.as_ref(), &mut selection_vector, false)
+        .unwrap();
+    assert_eq!(selection_vector, [true, true, false, true]);
+}
+
+#[test]
+fn test_apply_to_selection_parses_only_selected_stats() {
+    use crate::arrow::array::StringArray;
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::string_array_to_engine_data;
+
+    let engine = SyncEngine::new();
+    let referenced_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
+        "x",
+        DataType::INTEGER,
+    )]));
+    let predicate = Arc::new(Pred::gt(column_expr!("x"), Expr::literal(10)));
+    let filter =
+        DataSkippingFilter::new(&engine, Some((predicate, referenced_schema)), None).unwrap();
+
+    let add = |path: &str, stats: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1,"dataChange":true,"stats":"{stats}"}}}}"#
+        )
+    };
+    let json_strings: StringArray = vec![
+        add("a", r#"{\"numRecords\":1,\"maxValues\":{\"x\":5}}"#),
+        add("b", "not json"),
+        add("c", r#"{\"numRecords\":1,\"maxValues\":{\"x\":50}}"#),
+    ]
+    .into();
+    let actions = engine
+        .json_handler()
+        .parse_json(
+            string_array_to_engine_data(json_strings),
+            get_log_add_schema().clone(),
+        )
+        .unwrap();
+
+    // The invalid stats of file b are never parsed, since b was already deselected
+    let mut selection_vector = vec![true, false, true];
+    filter
+        .apply_to_selection(actions.as_ref(), &mut selection_vector, false)
+        .unwrap();
+    assert_eq!(selection_vector, [false, false, true]);
+
+    let mut selection_vector = vec![true, true, true];
+    let result = filter.apply_to_selection(actions.as_ref(), &mut selection_vector, false);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_apply_to_selection_with_trace() {
+    use crate::arrow::array::StringArray;
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::string_array_to_engine_data;
+
+    let engine = SyncEngine::new();
+    let referenced_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
+        "x",
+        DataType::INTEGER,
+    )]));
+    let above = Pred::gt(column_expr!("x"), Expr::literal(10));
+    let below = Pred::lt(column_expr!("x"), Expr::literal(100));
+    let predicate = Arc::new(Pred::and(above.clone(), below.clone()));
+    let trace = Arc::new(SkippingTrace::new());
+    let filter = DataSkippingFilter::new(
+        &engine,
+        Some((predicate, referenced_schema)),
+        Some(trace.clone()),
+    )
+    .unwrap();
+
+    let add = |path: &str, stats: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1,"dataChange":true{stats}}}}}"#
+        )
+    };
+    let stats = |min: i32, max: i32| {
+        format!(
+            r#","stats":"{{\"numRecords\":1,\"nullCount\":{{\"x\":0}},\"minValues\":{{\"x\":{min}}},\"maxValues\":{{\"x\":{max}}}}}""#
+        )
+    };
+    let json_strings: StringArray = vec![
+        add("a", &stats(1, 5)),
+        add("b", &stats(20, 50)),
+        add("c", ""),
+        add("d", &stats(200, 500)),
+    ]
+    .into();
+    let actions = engine
+        .json_handler()
+        .parse_json(
+            string_array_to_engine_data(json_strings),
+            get_log_add_schema().clone(),
+        )
+        .unwrap();
+
+    // File d was already deselected (e.g. by deduplication), so it is neither skipped nor traced
+    let mut selection_vector = vec![true, true, true, false];
+    filter
+        .apply_to_selection(actions.as_ref(), &mut selection_vector, false)
+        .unwrap();
+    assert_eq!(selection_vector, [false, true, true, false]);
+
+    let entry = |path: &str, pruned, results: [Option<bool>; 2]| SkippingTraceEntry {
+        target: SkippingTarget::File(path.to_string()),
+        pruned,
+        clauses: [&above, &below]
+            .into_iter()
+            .zip(results)
+            .map(|(clause, result)| ClauseResult {
+                clause: clause.clone(),
+                result,
+            })
+            .collect(),
+    };
+    let entries = trace.entries();
+    assert_eq!(
+        entries,
+        [
+            entry("a", true, [FALSE, TRUE]),
+            entry("b", false, [TRUE, TRUE]),
+            entry("c", false, [NULL, NULL]),
+        ]
+    );
+    assert_eq!(entries[0].pruning_clauses().collect::<Vec<_>>(), [&above]);
+    assert_eq!(entries[2].undecided_clauses().count(), 2);
+}
@@ -0,0 +1,202 @@
+//! Grouping of scan files into groups of roughly equal size, see [`group_scan_files`].
+//!
+//! Engines that distribute a scan one file per task spend most of each task on scheduling overhead
+//! when a table has many small files. Binning the files into one group per worker (or a small
+//! multiple of that) and sending each worker a group keeps tasks few and evenly sized instead.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use itertools::Itertools as _;
+
+use crate::expressions::ExpressionRef;
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+use super::state::{DvInfo, Stats};
+
+/// A file to read for a scan, with the same information [`ScanMetadata::visit_scan_files`] passes
+/// to its callback.
+///
+/// [`ScanMetadata::visit_scan_files`]: super::ScanMetadata::visit_scan_files
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanFile {
+    /// The path of the file, relative to the table root unless absolute
+    pub path: String,
+    /// The size of the file in bytes
+    pub size: i64,
+    /// The statistics of the file, if the log records them
+    pub stats: Option<Stats>,
+    /// The deletion vector of the file
+    pub dv_info: DvInfo,
+    /// The transform that must be applied to the physical data read from the file, if any
+    pub transform: Option<ExpressionRef>,
+    /// The partition values of the file, keyed by physical column name
+    pub partition_values: HashMap<String, String>,
+    /// The tags of the file's add action
+    pub tags: HashMap<String, String>,
+}
+
+impl ScanFile {
+    /// A [`ScanCallback`] that collects the visited files into a vector.
+    ///
+    /// [`ScanCallback`]: super::state::ScanCallback
+    pub fn collect(
+        files: &mut Vec<ScanFile>,
+        path: &str,
+        size: i64,
+        stats: Option<Stats>,
+        dv_info: DvInfo,
+        transform: Option<ExpressionRef>,
+        partition_values: HashMap<String, String>,
+        tags: HashMap<String, String>,
+    ) {
+        files.push(ScanFile {
+            path: path.to_string(),
+            size,
+            stats,
+            dv_info,
+            transform,
+            partition_values,
+            tags,
+        });
+    }
+
+    fn size_bytes(&self) -> u64 {
+        u64::try_from(self.size).unwrap_or(0)
+    }
+}
+
+/// A group of scan files to read together, e.g. by one worker.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanFileGroup {
+    /// The files of the group, largest first
+    pub files: Vec<ScanFile>,
+    /// The total size of the files in bytes
+    pub size: u64,
+}
+
+/// Bins `files` into at most `num_groups` groups of roughly equal total size. Groups are never
+/// empty, so there are fewer groups than requested if there are fewer files.
+///
+/// With `partition_aligned`, no group mixes files of different partitions, so that e.g. a worker
+/// only ever writes to one partition. Every partition then gets a number of groups proportional to
+/// its size, but at least one, so there can be more groups than requested (up to `num_groups` plus
+/// the number of partitions).
+pub fn group_scan_files(
+    files: impl IntoIterator<Item = ScanFile>,
+    num_groups: usize,
+    partition_aligned: bool,
+) -> DeltaResult<Vec<ScanFileGroup>> {
+    require!(
+        num_groups > 0,
+        Error::generic("Scan files must be grouped into at least one group")
+    );
+    let files: Vec<_> = files.into_iter().collect();
+    if !partition_aligned {
+        return Ok(bin_files(files, num_groups));
+    }
+
+    let total_size: u64 = files.iter().map(ScanFile::size_bytes).sum();
+    let target_size = total_size.div_ceil(num_groups as u64).max(1);
+    // order the partitions by their values, so that the grouping is deterministic
+    let mut partitions: BTreeMap<Vec<(String, String)>, Vec<ScanFile>> = BTreeMap::new();
+    for file in files {
+        let key = file.partition_values.clone().into_iter().sorted().collect();
+        partitions.entry(key).or_default().push(file);
+    }
+    let groups = partitions.into_values().flat_map(|files| {
+        let size: u64 = files.iter().map(ScanFile::size_bytes).sum();
+        let num_groups = usize::try_from(size.div_ceil(target_size)).unwrap_or(usize::MAX);
+        bin_files(files, num_groups.max(1))
+    });
+    Ok(groups.collect())
+}
+
+/// Greedily assigns the largest remaining file to the smallest group, which keeps the largest
+/// group within 4/3 of the optimum.
+fn bin_files(mut files: Vec<ScanFile>, num_groups: usize) -> Vec<ScanFileGroup> {
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    let mut groups = vec![ScanFileGroup::default(); num_groups.min(files.len())];
+    // (size, index) of each group, smallest size (then index) first
+    let mut smallest: BinaryHeap<_> = (0..groups.len()).map(|i| Reverse((0, i))).collect();
+    for file in files {
+        let Some(Reverse((_, index))) = smallest.pop() else {
+            break; // unreachable: there are groups whenever there are files
+        };
+        let group = &mut groups[index];
+        group.size += file.size_bytes();
+        group.files.push(file);
+        smallest.push(Reverse((group.size, index)));
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: i64, partition: &str) -> ScanFile {
+        ScanFile {
+            path: path.to_string(),
+            size,
+            stats: None,
+            dv_info: DvInfo::default(),
+            transform: None,
+            partition_values: HashMap::from([("p".to_string(), partition.to_string())]),
+            tags: HashMap::new(),
+        }
+    }
+
+    fn paths(group: &ScanFileGroup) -> Vec<&str> {
+        group.files.iter().map(|f| f.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_group_scan_files() {
+        let files = [
+            file("a", 50, "x"),
+            file("b", 40, "y"),
+            file("c", 30, "x"),
+            file("d", 20, "y"),
+            file("e", 10, "x"),
+            file("f", 10, "y"),
+        ];
+        let groups = group_scan_files(files.clone(), 2, false).unwrap();
+        let sizes: Vec<_> = groups.iter().map(|g| g.size).collect();
+        assert_eq!(sizes, [80, 80]);
+        assert_eq!(paths(&groups[0]), ["a", "d", "e"]);
+        assert_eq!(paths(&groups[1]), ["b", "c", "f"]);
+
+        // groups are never empty
+        let groups = group_scan_files(files.clone(), 10, false).unwrap();
+        assert_eq!(groups.len(), 6);
+        assert!(group_scan_files(Vec::new(), 3, false).unwrap().is_empty());
+
+        let result = group_scan_files(files, 0, false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_group_scan_files_partition_aligned() {
+        let files = [
+            file("a", 50, "x"),
+            file("b", 40, "y"),
+            file("c", 30, "x"),
+            file("d", 20, "y"),
+            file("e", 10, "x"),
+            file("f", 10, "y"),
+        ];
+        // partition x has 90 bytes, and y has 70 bytes, of a target of 80 bytes per group
+        let groups = group_scan_files(files, 2, true).unwrap();
+        let groups: Vec<_> = groups.iter().map(|g| (g.size, paths(g))).collect();
+        assert_eq!(
+            groups,
+            [
+                (50, vec!["a"]),
+                (40, vec!["c", "e"]),
+                (70, vec!["b", "d", "f"]),
+            ]
+        );
+    }
+}
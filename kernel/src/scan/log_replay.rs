@@ -3,17 +3,27 @@ use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, LazyLock};
 
 use super::data_skipping::DataSkippingFilter;
+use super::report::ScanMetrics;
+use super::skipping_trace::SkippingTrace;
+use super::strict::StrictValidator;
 use super::ScanMetadata;
 use crate::actions::deletion_vector::DeletionVectorDescriptor;
 use crate::actions::get_log_add_schema;
 use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
-use crate::expressions::{column_name, ColumnName, Expression, ExpressionRef, PredicateRef};
+use crate::expressions::{
+    column_expr, column_name, ColumnName, Expression, ExpressionRef, Predicate, PredicateRef,
+    UnaryExpressionOp,
+};
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
-use crate::log_replay::{ActionsBatch, FileActionDeduplicator, FileActionKey, LogReplayProcessor};
+use crate::log_replay::{
+    ActionsBatch, FileActionDeduplicator, FileActionKey, LogReplayProcessor, SeenFileKeys,
+};
 use crate::scan::Scalar;
 use crate::schema::ToSchema as _;
 use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType};
-use crate::transforms::{get_transform_expr, parse_partition_values, TransformSpec};
+use crate::transforms::{
+    get_transform_expr, parse_partition_values, FieldTransformSpec, TransformSpec,
+};
 use crate::utils::require;
 use crate::{DeltaResult, Engine, Error, ExpressionEvaluator};
 
@@ -22,12 +32,13 @@ use crate::{DeltaResult, Engine, Error, ExpressionEvaluator};
 /// During a table scan, the processor reads batches of log actions (in reverse chronological order)
 /// and performs the following steps:
 ///
-/// - Data Skipping: Applies a predicate-based filter (via [`DataSkippingFilter`]) to quickly skip
-///   files that are irrelevant for the query.
-/// - Partition Pruning: Uses an optional partition filter (extracted from a physical predicate)
-///   to exclude actions whose partition values do not meet the required criteria.
+/// - Partition Pruning: Uses an optional partition filter (the conjuncts of the physical predicate
+///   that only reference partition columns) to exclude actions whose partition values do not meet
+///   the required criteria. It is evaluated directly against each file's `partitionValues`.
 /// - Action Deduplication: Leverages the [`FileActionDeduplicator`] to ensure that for each unique file
 ///   (identified by its path and deletion vector unique ID), only the latest valid Add action is processed.
+/// - Data Skipping: Applies a predicate-based filter (via [`DataSkippingFilter`]) built from the
+///   remaining conjuncts to skip surviving files whose stats prove them irrelevant for the query.
 /// - Transformation: Applies a built-in transformation (`add_transform`) to convert selected Add actions
 ///   into [`ScanMetadata`], the intermediate format passed to the engine.
 /// - Row Transform Passthrough: Any user-provided row-level transformation expressions (e.g. those derived
@@ -48,7 +59,9 @@ pub(crate) struct ScanLogReplayProcessor {
     /// A set of (data file path, dv_unique_id) pairs that have been seen thus
     /// far in the log. This is used to filter out files with Remove actions as
     /// well as duplicate entries in the log.
-    seen_file_keys: HashSet<FileActionKey>,
+    seen_file_keys: SeenFileKeys,
+    metrics: Option<Arc<ScanMetrics>>,
+    strict_validator: Option<StrictValidator>,
 }
 
 impl ScanLogReplayProcessor {
@@ -58,10 +71,16 @@ impl ScanLogReplayProcessor {
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
         logical_schema: SchemaRef,
         transform_spec: Option<Arc<TransformSpec>>,
+        skipping_trace: Option<Arc<SkippingTrace>>,
+        metrics: Option<Arc<ScanMetrics>>,
+        strict_validator: Option<StrictValidator>,
     ) -> Self {
+        let partition_columns = partition_columns(&logical_schema, transform_spec.as_deref());
+        let (partition_filter, data_predicate) =
+            split_physical_predicate(physical_predicate, &partition_columns);
         Self {
-            partition_filter: physical_predicate.as_ref().map(|(e, _)| e.clone()),
-            data_skipping_filter: DataSkippingFilter::new(engine, physical_predicate),
+            partition_filter,
+            data_skipping_filter: DataSkippingFilter::new(engine, data_predicate, skipping_trace),
             add_transform: engine.evaluation_handler().new_expression_evaluator(
                 get_log_add_schema().clone(),
                 get_add_transform_expr(),
@@ -70,16 +89,93 @@ impl ScanLogReplayProcessor {
             seen_file_keys: Default::default(),
             logical_schema,
             transform_spec,
+            metrics,
+            strict_validator,
         }
     }
 }
 
+/// Returns the physical names of the partition columns in `transform_spec`.
+fn partition_columns(
+    logical_schema: &StructType,
+    transform_spec: Option<&TransformSpec>,
+) -> HashSet<ColumnName> {
+    transform_spec
+        .into_iter()
+        .flatten()
+        .filter_map(|field_transform| match field_transform {
+            FieldTransformSpec::PartitionColumn { field_index, .. } => {
+                logical_schema.field_at_index(*field_index)
+            }
+            _ => None,
+        })
+        .map(|field| ColumnName::new([field.physical_name()]))
+        .collect()
+}
+
+/// Splits the top-level conjuncts of a physical predicate into a partition filter and a data
+/// skipping predicate:
+///
+/// - Conjuncts that only reference partition columns go to the partition filter. Partition columns
+///   have no file stats, so these would be useless (and costly) to evaluate during data skipping.
+/// - Conjuncts that only reference data columns go to the data skipping predicate, whose schema
+///   only keeps the columns it still references.
+/// - Conjuncts that reference both go to both, since either may be able to prune a file.
+fn split_physical_predicate(
+    physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    partition_columns: &HashSet<ColumnName>,
+) -> (Option<PredicateRef>, Option<(PredicateRef, SchemaRef)>) {
+    let Some((predicate, referenced_schema)) = physical_predicate else {
+        return (None, None);
+    };
+    if partition_columns.is_empty() {
+        return (None, Some((predicate, referenced_schema)));
+    }
+
+    let mut partition_conjuncts = vec![];
+    let mut data_conjuncts = vec![];
+    for conjunct in predicate.conjuncts() {
+        let references = conjunct.references();
+        let (partition_refs, data_refs): (Vec<_>, Vec<_>) = references
+            .iter()
+            .partition(|column| partition_columns.contains(**column));
+        if !partition_refs.is_empty() || data_refs.is_empty() {
+            partition_conjuncts.push(conjunct.clone());
+        }
+        if !data_refs.is_empty() {
+            data_conjuncts.push(conjunct.clone());
+        }
+    }
+
+    let partition_filter = (!partition_conjuncts.is_empty())
+        .then(|| Arc::new(Predicate::and_from(partition_conjuncts)));
+    if data_conjuncts.is_empty() {
+        return (partition_filter, None);
+    }
+    let data_predicate = Predicate::and_from(data_conjuncts);
+    let data_references = data_predicate.references();
+    let data_schema = StructType::new_unchecked(
+        referenced_schema
+            .fields()
+            .filter(|field| {
+                let column = ColumnName::new([field.name()]);
+                !partition_columns.contains(&column) || data_references.contains(&&column)
+            })
+            .cloned(),
+    );
+    (
+        partition_filter,
+        Some((Arc::new(data_predicate), Arc::new(data_schema))),
+    )
+}
+
 /// A visitor that deduplicates a stream of add and remove actions into a stream of valid adds. Log
 /// replay visits actions newest-first, so once we've seen a file action for a given (path, dvId)
 /// pair, we should ignore all subsequent (older) actions for that same (path, dvId) pair. If the
 /// first action for a given file is a remove, then that file does not show up in the result at all.
 struct AddRemoveDedupVisitor<'seen> {
     deduplicator: FileActionDeduplicator<'seen>,
+    strict_validator: Option<&'seen mut StrictValidator>,
     selection_vector: Vec<bool>,
     logical_schema: SchemaRef,
     transform_spec: Option<Arc<TransformSpec>>,
@@ -87,7 +183,7 @@ struct AddRemoveDedupVisitor<'seen> {
     row_transform_exprs: Vec<Option<ExpressionRef>>,
 }
 
-impl AddRemoveDedupVisitor<'_> {
+impl<'seen> AddRemoveDedupVisitor<'seen> {
     // These index positions correspond to the order of columns defined in
     // `selected_column_names_and_types()`
     const ADD_PATH_INDEX: usize = 0; // Position of "add.path" in getters
@@ -95,15 +191,17 @@ impl AddRemoveDedupVisitor<'_> {
     const ADD_DV_START_INDEX: usize = 2; // Start position of add deletion vector columns
     const REMOVE_PATH_INDEX: usize = 5; // Position of "remove.path" in getters
     const REMOVE_DV_START_INDEX: usize = 6; // Start position of remove deletion vector columns
+    const ADD_DATA_CHANGE_INDEX: usize = 9; // Position of "add.dataChange" (log batches only)
 
     fn new(
-        seen: &mut HashSet<FileActionKey>,
+        seen: &'seen mut SeenFileKeys,
+        strict_validator: Option<&'seen mut StrictValidator>,
         selection_vector: Vec<bool>,
         logical_schema: SchemaRef,
         transform_spec: Option<Arc<TransformSpec>>,
         partition_filter: Option<PredicateRef>,
         is_log_batch: bool,
-    ) -> AddRemoveDedupVisitor<'_> {
+    ) -> AddRemoveDedupVisitor<'seen> {
         AddRemoveDedupVisitor {
             deduplicator: FileActionDeduplicator::new(
                 seen,
@@ -113,6 +211,7 @@ impl AddRemoveDedupVisitor<'_> {
                 Self::ADD_DV_START_INDEX,
                 Self::REMOVE_DV_START_INDEX,
             ),
+            strict_validator,
             selection_vector,
             logical_schema,
             transform_spec,
@@ -139,6 +238,49 @@ impl AddRemoveDedupVisitor<'_> {
         evaluator.eval_sql_where(partition_filter) == Some(false)
     }
 
+    /// Validates a file action in strict mode, see [`StrictValidator`]. Runs before partition
+    /// pruning and deduplication, so that every file action in the log is validated.
+    fn validate_file_action<'a>(
+        validator: &mut StrictValidator,
+        file_key: &FileActionKey,
+        is_add: bool,
+        is_log_batch: bool,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<()> {
+        // Checkpoint adds usually have dataChange = false, but each of them adds a file to the table
+        let data_change = if is_add && is_log_batch {
+            getters[Self::ADD_DATA_CHANGE_INDEX].get(i, "add.dataChange")?
+        } else {
+            true
+        };
+        validator.validate_file_action(file_key, is_add, data_change)?;
+        let dv_start_index = if is_add {
+            Self::ADD_DV_START_INDEX
+        } else {
+            Self::REMOVE_DV_START_INDEX
+        };
+        let storage_type: Option<String> =
+            getters[dv_start_index].get_opt(i, "deletionVector.storageType")?;
+        if let Some(storage_type) = storage_type {
+            let path_or_inline_dv: String =
+                getters[dv_start_index + 1].get(i, "deletionVector.pathOrInlineDv")?;
+            let offset = getters[dv_start_index + 2].get_opt(i, "deletionVector.offset")?;
+            validator.validate_deletion_vector(
+                &file_key.path,
+                &storage_type,
+                &path_or_inline_dv,
+                offset,
+            )?;
+        }
+        if is_add {
+            let partition_values: HashMap<String, String> =
+                getters[Self::ADD_PARTITION_VALUES_INDEX].get(i, "add.partitionValues")?;
+            validator.validate_partition_values(&file_key.path, &partition_values)?;
+        }
+        Ok(())
+    }
+
     /// True if this row contains an Add action that should survive log replay. Skip it if the row
     /// is not an Add action, or the file has already been seen previously.
     fn is_valid_add<'a>(&mut self, i: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<bool> {
@@ -156,6 +298,10 @@ impl AddRemoveDedupVisitor<'_> {
         else {
             return Ok(false);
         };
+        if let Some(validator) = self.strict_validator.as_deref_mut() {
+            let is_log_batch = self.deduplicator.is_log_batch();
+            Self::validate_file_action(validator, &file_key, is_add, is_log_batch, i, getters)?;
+        }
 
         // Apply partition pruning (to adds only) before deduplication, so that we don't waste memory
         // tracking pruned files. Removes don't get pruned and we'll still have to track them.
@@ -212,6 +358,7 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
                 (STRING, column_name!("remove.deletionVector.storageType")),
                 (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
                 (INTEGER, column_name!("remove.deletionVector.offset")),
+                (DataType::BOOLEAN, column_name!("add.dataChange")),
             ];
             let (types, names) = types_and_names.into_iter().unzip();
             (names, types).into()
@@ -228,7 +375,7 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         let is_log_batch = self.deduplicator.is_log_batch();
-        let expected_getters = if is_log_batch { 9 } else { 5 };
+        let expected_getters = if is_log_batch { 10 } else { 5 };
         require!(
             getters.len() == expected_getters,
             Error::InternalError(format!(
@@ -252,13 +399,20 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
 pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(|| {
     // Note that fields projected out of a nullable struct must be nullable
     let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
-    let file_constant_values =
-        StructType::new_unchecked([StructField::nullable("partitionValues", partition_values)]);
+    let tags = MapType::new(DataType::STRING, DataType::STRING, false);
+    let file_constant_values = StructType::new_unchecked([
+        StructField::nullable("partitionValues", partition_values),
+        StructField::nullable("tags", tags),
+    ]);
+    // the row count of the file, parsed once from `stats` so that engines need not parse it
+    let stats_parsed =
+        StructType::new_unchecked([StructField::nullable("numRecords", DataType::LONG)]);
     Arc::new(StructType::new_unchecked([
         StructField::nullable("path", DataType::STRING),
         StructField::nullable("size", DataType::LONG),
         StructField::nullable("modificationTime", DataType::LONG),
         StructField::nullable("stats", DataType::STRING),
+        StructField::nullable("statsParsed", stats_parsed),
         StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
         StructField::nullable("fileConstantValues", file_constant_values),
     ]))
@@ -275,10 +429,15 @@ fn get_add_transform_expr() -> ExpressionRef {
             column_expr_ref!("add.size"),
             column_expr_ref!("add.modificationTime"),
             column_expr_ref!("add.stats"),
+            Arc::new(Expression::unary(
+                UnaryExpressionOp::ParseJson,
+                column_expr!("add.stats"),
+            )),
             column_expr_ref!("add.deletionVector"),
-            Arc::new(Expression::Struct(vec![column_expr_ref!(
-                "add.partitionValues"
-            )])),
+            Arc::new(Expression::Struct(vec![
+                column_expr_ref!("add.partitionValues"),
+                column_expr_ref!("add.tags"),
+            ])),
         ]))
     });
     EXPR.clone()
@@ -297,6 +456,7 @@ pub(crate) fn get_scan_metadata_transform_expr() -> ExpressionRef {
                 column_expr_ref!("modificationTime"),
                 column_expr_ref!("stats"),
                 column_expr_ref!("deletionVector"),
+                column_expr_ref!("fileConstantValues.tags"),
             ],
         ))]))
     });
@@ -310,29 +470,47 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
         let ActionsBatch {
             actions,
             is_log_batch,
+            has_stats_parsed,
         } = actions_batch;
-        // Build an initial selection vector for the batch which has had the data skipping filter
-        // applied. The selection vector is further updated by the deduplication visitor to remove
-        // rows that are not valid adds.
-        let selection_vector = self.build_selection_vector(actions.as_ref())?;
-        assert_eq!(selection_vector.len(), actions.len());
-
+        // Deduplicate and partition-prune first: the visitor only reads a few narrow columns, and
+        // typically deselects most rows of a batch (removes, non-file actions, and files that a
+        // newer commit already replaced or removed). Data skipping runs afterwards, so add.stats
+        // is only parsed for batches where some valid add survived. This does not change the
+        // result: every add action for a given file carries the same stats, so a file skipped by
+        // its stats is skipped no matter which of its actions is the first one seen.
         let mut visitor = AddRemoveDedupVisitor::new(
             &mut self.seen_file_keys,
-            selection_vector,
+            self.strict_validator.as_mut(),
+            vec![true; actions.len()],
             self.logical_schema.clone(),
             self.transform_spec.clone(),
             self.partition_filter.clone(),
             is_log_batch,
         );
         visitor.visit_rows_of(actions.as_ref())?;
+        let AddRemoveDedupVisitor {
+            mut selection_vector,
+            row_transform_exprs,
+            ..
+        } = visitor;
+
+        let count_selected =
+            |selection_vector: &[bool]| selection_vector.iter().filter(|s| **s).count();
+        let considered = count_selected(&selection_vector);
+        self.apply_data_skipping(actions.as_ref(), &mut selection_vector, has_stats_parsed)?;
+        if let Some(metrics) = &self.metrics {
+            let selected = count_selected(&selection_vector);
+            ScanMetrics::add(&metrics.files_considered, considered);
+            ScanMetrics::add(&metrics.files_skipped, considered - selected);
+            ScanMetrics::add(&metrics.files_selected, selected);
+        }
 
         // TODO: Teach expression eval to respect the selection vector we just computed so carefully!
         let result = self.add_transform.evaluate(actions.as_ref())?;
         Ok(ScanMetadata::new(
             result,
-            visitor.selection_vector,
-            visitor.row_transform_exprs,
+            selection_vector,
+            row_transform_exprs,
         ))
     }
 
@@ -355,17 +533,30 @@ pub(crate) fn scan_action_iter(
     logical_schema: SchemaRef,
     transform_spec: Option<Arc<TransformSpec>>,
     physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    skipping_trace: Option<Arc<SkippingTrace>>,
+    metrics: Option<Arc<ScanMetrics>>,
+    strict_validator: Option<StrictValidator>,
 ) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
-    ScanLogReplayProcessor::new(engine, physical_predicate, logical_schema, transform_spec)
-        .process_actions_iter(action_iter)
+    ScanLogReplayProcessor::new(
+        engine,
+        physical_predicate,
+        logical_schema,
+        transform_spec,
+        skipping_trace,
+        metrics,
+        strict_validator,
+    )
+    .process_actions_iter(action_iter)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, sync::Arc};
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
 
     use crate::actions::get_log_schema;
     use crate::expressions::Scalar;
+    use crate::expressions::{column_expr, column_name};
     use crate::log_replay::ActionsBatch;
     use crate::scan::state::{DvInfo, Stats};
     use crate::scan::test_utils::{
@@ -375,13 +566,14 @@ mod tests {
     use crate::scan::{get_transform_spec, StateInfo};
     use crate::table_features::ColumnMappingMode;
     use crate::Expression as Expr;
+    use crate::Predicate as Pred;
     use crate::{
         engine::sync::SyncEngine,
         schema::{DataType, SchemaRef, StructField, StructType},
         ExpressionRef,
     };
 
-    use super::scan_action_iter;
+    use super::{scan_action_iter, split_physical_predicate};
 
     // dv-info is more complex to validate, we validate that works in the test for visit_scan_files
     // in state.rs
@@ -393,6 +585,7 @@ mod tests {
         _: DvInfo,
         _: Option<ExpressionRef>,
         part_vals: HashMap<String, String>,
+        _: HashMap<String, String>,
     ) {
         assert_eq!(
             path,
@@ -429,6 +622,110 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_split_physical_predicate() {
+        let schema: SchemaRef = Arc::new(StructType::new_unchecked([
+            StructField::nullable("value", DataType::INTEGER),
+            StructField::nullable("date", DataType::DATE),
+        ]));
+        let partition_columns = HashSet::from([column_name!("date")]);
+        let split = |predicate: Pred| {
+            split_physical_predicate(
+                Some((Arc::new(predicate), schema.clone())),
+                &partition_columns,
+            )
+        };
+        let date = Pred::gt(column_expr!("date"), Expr::literal(Scalar::Date(1)));
+        let value = Pred::lt(column_expr!("value"), Expr::literal(10));
+        let mixed = Pred::or(date.clone(), value.clone());
+
+        // Nested conjunctions are flattened, and mixed conjuncts go to both sides
+        let predicate = Pred::and_from([date.clone(), Pred::and(value.clone(), mixed.clone())]);
+        let (partition_filter, data_predicate) = split(predicate);
+        assert_eq!(
+            partition_filter.as_deref(),
+            Some(&Pred::and_from([date.clone(), mixed.clone()]))
+        );
+        let (data_predicate, data_schema) = data_predicate.unwrap();
+        assert_eq!(*data_predicate, Pred::and_from([value.clone(), mixed]));
+        assert_eq!(data_schema, schema);
+
+        // Partition columns the data predicate doesn't reference are dropped from its schema
+        let (partition_filter, data_predicate) = split(Pred::and(date.clone(), value.clone()));
+        assert_eq!(
+            partition_filter.as_deref(),
+            Some(&Pred::and_from([date.clone()]))
+        );
+        let (data_predicate, data_schema) = data_predicate.unwrap();
+        assert_eq!(*data_predicate, Pred::and_from([value]));
+        assert_eq!(data_schema.field_names().collect::<Vec<_>>(), ["value"]);
+
+        // Partition-only predicates don't need data skipping at all
+        let (partition_filter, data_predicate) = split(date.clone());
+        assert_eq!(partition_filter.as_deref(), Some(&Pred::and_from([date])));
+        assert!(data_predicate.is_none());
+    }
+
+    #[test]
+    fn test_data_skipping_after_dedup() {
+        let schema: SchemaRef = Arc::new(StructType::new_unchecked([StructField::nullable(
+            "value",
+            DataType::INTEGER,
+        )]));
+        let scan = |value: i32| -> Vec<Vec<bool>> {
+            let predicate = Arc::new(Pred::gt(column_expr!("value"), Expr::literal(value)));
+            let batch = add_batch_with_remove(get_log_schema().clone());
+            scan_action_iter(
+                &SyncEngine::new(),
+                std::iter::once(Ok(ActionsBatch::new(batch as _, true))),
+                schema.clone(),
+                None,
+                Some((predicate, schema.clone())),
+                None,
+                None,
+                None,
+            )
+            .map(|res| res.unwrap().scan_files.selection_vector)
+            .collect()
+        };
+
+        // The removed file is deduplicated away, and only the remaining add passes data skipping
+        assert_eq!(scan(5), vec![vec![false, false, true, false]]);
+        // Every file is skipped, so the batch yields no scan metadata at all
+        assert!(scan(100).is_empty());
+    }
+
+    #[test]
+    fn test_scan_action_iter_is_lazy() {
+        let pulled = std::cell::Cell::new(0);
+        let batches = [
+            add_batch_simple(get_log_schema().clone()),
+            add_batch_with_remove(get_log_schema().clone()),
+        ];
+        let action_iter = batches.into_iter().map(|batch| {
+            pulled.set(pulled.get() + 1);
+            Ok(ActionsBatch::new(batch as _, true))
+        });
+        let logical_schema = Arc::new(StructType::new_unchecked(vec![]));
+        let mut iter = scan_action_iter(
+            &SyncEngine::new(),
+            action_iter,
+            logical_schema,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // Each batch is reconciled and yielded before the next one is read
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(pulled.get(), 1);
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(pulled.get(), 2);
+        assert!(iter.next().is_none());
+    }
+
     #[test]
     fn test_no_transforms() {
         let batch = vec![add_batch_simple(get_log_schema().clone())];
@@ -441,6 +738,9 @@ mod tests {
             logical_schema,
             None,
             None,
+            None,
+            None,
+            None,
         );
         for res in iter {
             let scan_metadata = res.unwrap();
@@ -470,6 +770,9 @@ mod tests {
             schema,
             static_transform,
             None,
+            None,
+            None,
+            None,
         );
 
         fn validate_transform(transform: Option<&ExpressionRef>, expected_date_offset: i32) {
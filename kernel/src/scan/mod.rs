@@ -3,17 +3,19 @@
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, LazyLock};
+use std::time::Instant;
 
 use delta_kernel_derive::internal_api;
 use itertools::Itertools;
-use tracing::debug;
+use tracing::{debug, debug_span, info_span};
 use url::Url;
+use uuid::Uuid;
 
 use self::log_replay::get_scan_metadata_transform_expr;
 use crate::actions::deletion_vector::{
     deletion_treemap_to_bools, split_vector, DeletionVectorDescriptor,
 };
-use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
+use crate::actions::{get_log_schema, Add, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
 use crate::engine_data::FilteredEngineData;
 use crate::expressions::transforms::ExpressionTransform;
 use crate::expressions::{ColumnName, ExpressionRef, Predicate, PredicateRef, Scalar};
@@ -29,29 +31,98 @@ use crate::schema::{
 };
 use crate::snapshot::SnapshotRef;
 use crate::table_features::ColumnMappingMode;
-use crate::transforms::{get_transform_spec, ColumnType};
-use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Version};
+use crate::transforms::{
+    get_transform_expr, get_transform_spec, parse_partition_values, ColumnType,
+};
+use crate::uri::{resolve_file_path_with_policy, PathPolicy};
+use crate::utils::SpanIteratorExt as _;
+use crate::{DeltaResult, Engine, EngineData, Error, ErrorContext, FileMeta, Version};
 
+use self::column_hook::{physical_transform, ColumnHook};
+use self::data_skipping::{stats_schema, with_stats_parsed};
+use self::grouping::{group_scan_files, ScanFile, ScanFileGroup};
 use self::log_replay::scan_action_iter;
-
+use self::predicate_binding::BoundPredicate;
+use self::report::{ScanMetrics, ScanReport, Timed};
+use self::skipping_trace::SkippingTrace;
+use self::strict::StrictValidator;
+
+pub mod aggregate;
+pub mod column_hook;
+pub mod column_stats;
 pub(crate) mod data_skipping;
+pub mod grouping;
 pub mod log_replay;
+pub mod predicate_binding;
+pub mod report;
+pub mod skipping_trace;
 pub mod state;
+pub(crate) mod strict;
 
 // safety: we define get_log_schema() and _know_ it contains ADD_NAME and REMOVE_NAME
 #[allow(clippy::unwrap_used)]
 static COMMIT_READ_SCHEMA: LazyLock<SchemaRef> =
     LazyLock::new(|| get_log_schema().project(&[ADD_NAME, REMOVE_NAME]).unwrap());
-// safety: we define get_log_schema() and _know_ it contains ADD_NAME and SIDECAR_NAME
+/// The fields of add actions that scan log replay uses, see `get_add_transform_expr`. Checkpoints
+/// are read with only these fields, so that the parquet reader can skip decoding the others.
+const CHECKPOINT_ADD_FIELDS: [&str; 7] = [
+    "path",
+    "partitionValues",
+    "size",
+    "modificationTime",
+    "stats",
+    "tags",
+    "deletionVector",
+];
+// safety: we define get_log_schema() and _know_ it contains ADD_NAME and SIDECAR_NAME, and that
+// the add action schema contains CHECKPOINT_ADD_FIELDS
 #[allow(clippy::unwrap_used)]
-static CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> =
-    LazyLock::new(|| get_log_schema().project(&[ADD_NAME, SIDECAR_NAME]).unwrap());
+static CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let add = Add::to_schema()
+        .project_as_struct(&CHECKPOINT_ADD_FIELDS)
+        .unwrap();
+    let sidecar = get_log_schema().field(SIDECAR_NAME).unwrap().clone();
+    Arc::new(StructType::new_unchecked([
+        StructField::nullable(ADD_NAME, add),
+        sidecar,
+    ]))
+});
+
+/// The order of the columns a [`Scan`] returns, see [`ScanBuilder::with_column_order`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColumnOrder {
+    /// Columns are in the order of the schema passed to [`ScanBuilder::with_schema`].
+    #[default]
+    Requested,
+    /// Columns are in the order of the table schema. Columns that are not in the table schema
+    /// (i.e. metadata columns) come last, in their requested order.
+    Table,
+}
+
+/// The order of the results of [`Scan::execute`], see [`ScanBuilder::with_result_order`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultOrder {
+    /// No particular order, which leaves the scan free to read files as soon as log replay finds
+    /// them, or to read them concurrently.
+    #[default]
+    Unordered,
+    /// Files in the order of their paths, and the rows of each file in the order they are stored
+    /// in. The order only depends on the files of the snapshot, so that scans of the same snapshot
+    /// (or of snapshots with the same files) return the same rows in the same order.
+    FileOrder,
+}
 
 /// Builder to scan a snapshot of a table.
 pub struct ScanBuilder {
     snapshot: SnapshotRef,
     schema: Option<SchemaRef>,
     predicate: Option<PredicateRef>,
+    skipping_trace: Option<Arc<SkippingTrace>>,
+    strict_validation: bool,
+    column_hooks: Vec<(String, Arc<dyn ColumnHook>)>,
+    path_policy: Option<Arc<dyn PathPolicy>>,
+    column_order: ColumnOrder,
+    result_order: ResultOrder,
 }
 
 impl std::fmt::Debug for ScanBuilder {
@@ -59,6 +130,11 @@ impl std::fmt::Debug for ScanBuilder {
         f.debug_struct("ScanBuilder")
             .field("schema", &self.schema)
             .field("predicate", &self.predicate)
+            .field("strict_validation", &self.strict_validation)
+            .field("column_hooks", &self.column_hooks)
+            .field("path_policy", &self.path_policy)
+            .field("column_order", &self.column_order)
+            .field("result_order", &self.result_order)
             .finish()
     }
 }
@@ -70,6 +146,12 @@ impl ScanBuilder {
             snapshot: snapshot.into(),
             schema: None,
             predicate: None,
+            skipping_trace: None,
+            strict_validation: false,
+            column_hooks: vec![],
+            path_policy: None,
+            column_order: ColumnOrder::default(),
+            result_order: ResultOrder::default(),
         }
     }
 
@@ -107,6 +189,76 @@ impl ScanBuilder {
         self
     }
 
+    /// Record the outcome of data skipping for every file the scan considers in `trace`, i.e.
+    /// whether the file was pruned, and which clauses of the predicate allowed (or failed) to
+    /// prune it. This is meant for debugging, as each clause is evaluated separately for every
+    /// file. See [`skipping_trace`] for details.
+    ///
+    /// NOTE: Files are only traced if the scan's predicate is eligible for data skipping, and files
+    /// pruned by their partition values are not traced.
+    pub fn with_skipping_trace(mut self, trace: Arc<SkippingTrace>) -> Self {
+        self.skipping_trace = Some(trace);
+        self
+    }
+
+    /// Validate table invariants that the kernel otherwise tolerates, and fail the scan with an
+    /// [`Error::InvalidTableState`] or [`Error::InvalidProtocol`] on the first violation, instead
+    /// of reading the table on a best-effort basis. The protocol is validated when the scan is
+    /// built, and the following invariants of file actions during log replay:
+    /// - a file is not added again unless it was removed in between, except by adds with
+    ///   `dataChange = false` that only update its stats
+    /// - deletion vector descriptors have a known storage type, and their path or inline data
+    ///   decodes
+    /// - partition values have exactly the table's partition columns as keys, and each value
+    ///   parses as the type of its column
+    ///
+    /// NOTE: Strict validation inspects every file action in the log, including those that are
+    /// pruned by the predicate, and keeps track of every file seen during log replay. It is thus
+    /// slower and needs more memory than a regular scan. It also rejects tables whose writers
+    /// re-add files only to update their stats.
+    pub fn with_strict_validation(mut self, strict_validation: bool) -> Self {
+        self.strict_validation = strict_validation;
+        self
+    }
+
+    /// Transform the data of every column the scan reads whose field metadata has the key
+    /// `metadata_key` with `hook`, e.g. to decrypt or mask it, before the data is transformed into
+    /// the logical schema. See [`column_hook`] for details.
+    pub fn with_column_hook(
+        mut self,
+        metadata_key: impl Into<String>,
+        hook: Arc<dyn ColumnHook>,
+    ) -> Self {
+        self.column_hooks.push((metadata_key.into(), hook));
+        self
+    }
+
+    /// Check the files of the scan that are located outside of the table root (e.g. the files of a
+    /// shallow clone) against `policy` before reading them, see [`PathPolicy`]. By default, such
+    /// files are read like any other.
+    pub fn with_path_policy(mut self, policy: Arc<dyn PathPolicy>) -> Self {
+        self.path_policy = Some(policy);
+        self
+    }
+
+    /// Choose whether the scan returns columns (including the fields of nested structs) in the
+    /// order of the schema passed to [`ScanBuilder::with_schema`], which is the default, or in the
+    /// order of the table schema. The [`Scan::logical_schema`], and the data [`Scan::execute`]
+    /// returns or the transforms of [`Scan::scan_metadata`] produce, always follow that order.
+    pub fn with_column_order(mut self, column_order: ColumnOrder) -> Self {
+        self.column_order = column_order;
+        self
+    }
+
+    /// Choose the order of the results of [`Scan::execute`]. By default, results are in no
+    /// particular order; engines that rely on a deterministic order, e.g. to implement sampling or
+    /// `LIMIT` consistently, can request [`ResultOrder::FileOrder`]. Ordered results are only
+    /// returned once log replay has found all files of the scan.
+    pub fn with_result_order(mut self, result_order: ResultOrder) -> Self {
+        self.result_order = result_order;
+        self
+    }
+
     /// Build the [`Scan`].
     ///
     /// This does not scan the table at this point, but does do some work to ensure that the
@@ -115,7 +267,13 @@ impl ScanBuilder {
     /// perform actual data reads.
     pub fn build(self) -> DeltaResult<Scan> {
         // if no schema is provided, use snapshot's entire schema (e.g. SELECT *)
-        let logical_schema = self.schema.unwrap_or_else(|| self.snapshot.schema());
+        let logical_schema = match (self.schema, self.column_order) {
+            (Some(schema), ColumnOrder::Requested) => schema,
+            (Some(schema), ColumnOrder::Table) => {
+                Arc::new(order_like_table(&schema, &self.snapshot.schema())?)
+            }
+            (None, _) => self.snapshot.schema(),
+        };
         let state_info = StateInfo::try_new(
             logical_schema.as_ref(),
             &self.snapshot.metadata().partition_columns,
@@ -126,18 +284,60 @@ impl ScanBuilder {
             Some(predicate) => PhysicalPredicate::try_new(&predicate, &logical_schema)?,
             None => PhysicalPredicate::None,
         };
+        if self.strict_validation {
+            // fail fast on an invalid protocol or partition schema
+            StrictValidator::try_new(&self.snapshot)?;
+        }
+        let physical_transform =
+            physical_transform(&self.column_hooks, &logical_schema, &state_info.all_fields)?;
 
         Ok(Scan {
             snapshot: self.snapshot,
             logical_schema,
             physical_schema: Arc::new(StructType::try_new(state_info.read_fields)?),
             physical_predicate,
+            physical_transform,
+            path_policy: self.path_policy,
             all_fields: Arc::new(state_info.all_fields),
             have_partition_cols: state_info.have_partition_cols,
+            skipping_trace: self.skipping_trace,
+            strict_validation: self.strict_validation,
+            result_order: self.result_order,
+            operation_id: Uuid::new_v4(),
+            metrics: Default::default(),
         })
     }
 }
 
+/// Reorders the fields of `requested`, and of its nested structs, into their order in `table`.
+/// Fields that are not in `table` keep their relative order after those that are.
+fn order_like_table(requested: &StructType, table: &StructType) -> DeltaResult<StructType> {
+    let mut fields: Vec<_> = requested
+        .fields()
+        .map(|field| -> DeltaResult<_> {
+            let table_field = table.field_with_index(&field.name);
+            let data_type = match (&field.data_type, table_field) {
+                (DataType::Struct(requested), Some((_, table_field))) => {
+                    match &table_field.data_type {
+                        DataType::Struct(table) => order_like_table(requested, table)?.into(),
+                        _ => field.data_type.clone(),
+                    }
+                }
+                _ => field.data_type.clone(),
+            };
+            let index = table_field.map_or(usize::MAX, |(index, _)| index);
+            let field = StructField {
+                data_type,
+                ..field.clone()
+            };
+            Ok((index, field))
+        })
+        .try_collect()?;
+    // the sort is stable, so fields that are not in the table keep their order
+    fields.sort_by_key(|(index, _)| *index);
+    StructType::try_new(fields.into_iter().map(|(_, field)| field))
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum PhysicalPredicate {
     Some(PredicateRef, SchemaRef),
@@ -146,10 +346,13 @@ pub(crate) enum PhysicalPredicate {
 }
 
 impl PhysicalPredicate {
-    /// If we have a predicate, verify the columns it references and apply column mapping. First, get
-    /// the set of references; use that to filter the schema to only the columns of interest (and
-    /// verify that all referenced columns exist); then use the resulting logical/physical mappings
-    /// to rewrite the expression with physical column names.
+    /// If we have a predicate, verify the columns it references and apply column mapping. First,
+    /// bind the predicate to the schema (see [`BoundPredicate::try_bind`]), which normalizes its
+    /// timestamp literals with UTC offsets against the columns they are compared with, failing
+    /// with [`Error::PredicateBinding`] if it references unknown columns or compares columns
+    /// with values of incompatible types; then get the set of
+    /// references and use it to filter the schema to only the columns of interest; then use the
+    /// resulting logical/physical mappings to rewrite the expression with physical column names.
     ///
     /// NOTE: It is possible the predicate resolves to FALSE even ignoring column references,
     /// e.g. `col > 10 AND FALSE`. Such predicates can statically skip the whole query.
@@ -160,6 +363,8 @@ impl PhysicalPredicate {
         if can_statically_skip_all_files(predicate) {
             return Ok(PhysicalPredicate::StaticSkipAll);
         }
+        let bound = BoundPredicate::try_bind(predicate, logical_schema)?;
+        let predicate = bound.predicate().as_ref();
         let mut get_referenced_fields = GetReferencedFields {
             unresolved_references: predicate.references(),
             column_mappings: HashMap::new(),
@@ -170,13 +375,15 @@ impl PhysicalPredicate {
         let mut unresolved = get_referenced_fields.unresolved_references.into_iter();
         if let Some(unresolved) = unresolved.next() {
             // Schema traversal failed to resolve at least one column referenced by the predicate.
+            // Binding already verified that the column exists, so it must be an array, map or
+            // struct column, none of which data skipping supports.
             //
             // NOTE: It's a pretty serious engine bug if we got this far with a query whose WHERE
             // clause has invalid column references. Data skipping is best-effort and the predicate
             // anyway needs to be evaluated against every row of data -- which is impossible if the
             // columns are missing/invalid. Just blow up instead of trying to handle it gracefully.
             return Err(Error::missing_column(format!(
-                "Predicate references unknown column: {unresolved}"
+                "Predicate references non-primitive column: {unresolved}"
             )));
         }
         let Some(schema) = schema_opt else {
@@ -306,6 +513,26 @@ impl ScanResult {
         mask.resize(self.raw_data.as_ref().ok()?.len(), true);
         Some(mask)
     }
+
+    /// Like [`full_mask`], but returns the mask as a bit-packed arrow [`BooleanBuffer`], which
+    /// converts into the `BooleanArray` expected by arrow's `filter_record_batch` without a copy.
+    ///
+    /// Since arrow is optional, the kernel still computes the raw mask as a `Vec<bool>`, and this
+    /// packs it into bits in a single pass. That saves extending the raw mask to a full-length
+    /// `Vec<bool>` and converting that to a `BooleanArray`, but not the raw mask itself.
+    ///
+    /// [`full_mask`]: #method.full_mask
+    /// [`BooleanBuffer`]: crate::arrow::buffer::BooleanBuffer
+    #[cfg(any(feature = "arrow-55", feature = "arrow-56"))]
+    pub fn full_mask_buffer(&self) -> Option<crate::arrow::buffer::BooleanBuffer> {
+        let mask = self.raw_mask.as_ref()?;
+        let len = self.raw_data.as_ref().ok()?.len();
+        let mut buffer = crate::arrow::array::BooleanBufferBuilder::new(len);
+        let masked = mask.len().min(len);
+        buffer.append_slice(&mask[..masked]);
+        buffer.append_n(len - masked, true);
+        Some(buffer.finish())
+    }
 }
 
 /// utility method making it easy to get a transform for a particular row. If the requested row is
@@ -318,6 +545,34 @@ pub fn get_transform_for_row(
     transforms.get(row).cloned().flatten()
 }
 
+/// Computes the transform expression the kernel attaches to a scan file with the given
+/// `partition_values`, see [`ScanMetadata::scan_file_transforms`]. Engines that read data files
+/// with their own parquet readers can apply it to the physical data they read, to reconstruct the
+/// file's partition columns (with the values parsed into the columns' types) and the logical
+/// column names exactly as the kernel does.
+///
+/// `logical_schema` is the schema of the scan (see [`Scan::logical_schema`]),
+/// `partition_columns` and `column_mapping_mode` are those of the table, and `partition_values`
+/// are the raw `partitionValues` of the file's add action, keyed by physical column name. Returns
+/// `None` if the data needs no transform, because the scan selects no partition columns and the
+/// table does not use column mapping. See also [`Scan::transform_for_partition_values`].
+pub fn transform_for_partition_values(
+    logical_schema: &SchemaRef,
+    partition_columns: &[String],
+    column_mapping_mode: ColumnMappingMode,
+    partition_values: &HashMap<String, String>,
+) -> DeltaResult<Option<ExpressionRef>> {
+    let state_info = StateInfo::try_new(logical_schema, partition_columns, column_mapping_mode)?;
+    // must match the static transform of `Scan::scan_metadata`
+    if !state_info.have_partition_cols && column_mapping_mode == ColumnMappingMode::None {
+        return Ok(None);
+    }
+    let transform_spec = get_transform_spec(&state_info.all_fields);
+    let partition_values =
+        parse_partition_values(logical_schema, &transform_spec, partition_values)?;
+    get_transform_expr(&transform_spec, partition_values).map(Some)
+}
+
 /// [`ScanMetadata`] contains (1) a batch of [`FilteredEngineData`] specifying data files to be scanned
 /// and (2) a vector of transforms (one transform per scan file) that must be applied to the data read
 /// from those files.
@@ -369,13 +624,21 @@ pub struct Scan {
     logical_schema: SchemaRef,
     physical_schema: SchemaRef,
     physical_predicate: PhysicalPredicate,
+    physical_transform: Option<ExpressionRef>,
+    path_policy: Option<Arc<dyn PathPolicy>>,
     all_fields: Arc<Vec<ColumnType>>,
     have_partition_cols: bool,
+    skipping_trace: Option<Arc<SkippingTrace>>,
+    strict_validation: bool,
+    result_order: ResultOrder,
+    operation_id: Uuid,
+    metrics: Arc<ScanMetrics>,
 }
 
 impl std::fmt::Debug for Scan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         f.debug_struct("Scan")
+            .field("operation_id", &self.operation_id)
             .field("schema", &self.logical_schema)
             .field("predicate", &self.physical_predicate)
             .finish()
@@ -417,6 +680,43 @@ impl Scan {
         &self.physical_schema
     }
 
+    /// A unique id of this scan. All `tracing` spans of the scan carry it as their `operation_id`
+    /// field, so that the log replay, data skipping and file reads of a slow query can be
+    /// correlated.
+    pub fn operation_id(&self) -> Uuid {
+        self.operation_id
+    }
+
+    /// A [`ScanReport`] of the work this scan did so far, e.g. for surfacing in query profiles.
+    /// Its counters accumulate while the iterators returned by [`Scan::scan_metadata`] and
+    /// [`Scan::execute`] are consumed, so retrieve it once they are exhausted.
+    pub fn report(&self) -> ScanReport {
+        let log_segment = self.snapshot.log_segment();
+        let log_files_listed = log_segment.ascending_commit_files.len()
+            + log_segment.ascending_compaction_files.len()
+            + log_segment.checkpoint_parts.len();
+        self.metrics.report(log_files_listed as u64)
+    }
+
+    /// Get the transform that the scan's column hooks (see [`ScanBuilder::with_column_hook`])
+    /// apply to the data read from every file, if any. Its input and output schema is the
+    /// [physical schema] of the scan. [`Scan::execute`] applies it before the file's transform
+    /// (see [`ScanMetadata::scan_file_transforms`]), and engines that read the files themselves
+    /// must do the same.
+    ///
+    /// [physical schema]: Self::physical_schema
+    pub fn physical_transform(&self) -> Option<ExpressionRef> {
+        self.physical_transform.clone()
+    }
+
+    /// Resolve the `path` of a scan file (see [`ScanMetadata::visit_scan_files`]) into the URL to
+    /// read it from, checking files outside of the table root against the scan's path policy (see
+    /// [`ScanBuilder::with_path_policy`]). Engines that read the files themselves should use this
+    /// rather than joining the path to the table root.
+    pub fn resolve_file_path(&self, path: &str) -> DeltaResult<Url> {
+        resolve_file_path_with_policy(self.table_root(), path, self.path_policy.as_deref())
+    }
+
     /// Get the predicate [`PredicateRef`] of the scan.
     pub fn physical_predicate(&self) -> Option<PredicateRef> {
         if let PhysicalPredicate::Some(ref predicate, _) = self.physical_predicate {
@@ -426,6 +726,36 @@ impl Scan {
         }
     }
 
+    /// Computes the transform expression this scan attaches to a scan file with the given (raw)
+    /// `partition_values`, see [`transform_for_partition_values`].
+    pub fn transform_for_partition_values(
+        &self,
+        partition_values: &HashMap<String, String>,
+    ) -> DeltaResult<Option<ExpressionRef>> {
+        transform_for_partition_values(
+            &self.logical_schema,
+            &self.snapshot.metadata().partition_columns,
+            self.snapshot.column_mapping_mode(),
+            partition_values,
+        )
+    }
+
+    /// Get the files of this scan, binned into at most `num_groups` groups of roughly equal size
+    /// (optionally aligned to partitions) for distribution to workers, see [`group_scan_files`].
+    /// Unlike [`Self::scan_metadata`], this replays the whole log before returning.
+    pub fn scan_file_groups(
+        &self,
+        engine: &dyn Engine,
+        num_groups: usize,
+        partition_aligned: bool,
+    ) -> DeltaResult<Vec<ScanFileGroup>> {
+        let mut files = vec![];
+        for scan_metadata in self.scan_metadata(engine)? {
+            files = scan_metadata?.visit_scan_files(files, ScanFile::collect)?;
+        }
+        group_scan_files(files, num_groups, partition_aligned)
+    }
+
     /// Get an iterator of [`ScanMetadata`]s that should be used to facilitate a scan. This handles
     /// log-replay, reconciling Add and Remove actions, and applying data skipping (if possible).
     /// Each item in the returned iterator is a struct of:
@@ -445,11 +775,25 @@ impl Scan {
     ///   the item at index `i` in this `Vec` is `None`, or if the `Vec` contains fewer than `i`
     ///   elements, no expression need be applied and the data read from disk is already in the
     ///   correct logical state.
+    ///
+    /// The returned iterator is lazy: each batch of actions read from a commit or checkpoint file
+    /// is reconciled and yielded as soon as it is pulled, so the full set of files is never
+    /// buffered. Log replay only retains the (path, deletion vector) keys of file actions seen in
+    /// commit files, which is needed to drop files removed or replaced by newer commits.
     pub fn scan_metadata(
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
-        self.scan_metadata_inner(engine, self.replay_for_scan_metadata(engine)?)
+        let span = info_span!(
+            "scan_metadata",
+            operation_id = %self.operation_id,
+            version = self.snapshot.version(),
+        );
+        let _entered = span.enter();
+        let it = self.scan_metadata_inner(engine, self.replay_for_scan_metadata(engine)?)?;
+        let metrics = self.metrics.clone();
+        let it = Timed::new(it, move |elapsed| metrics.add_log_replay_time(elapsed));
+        Ok(it.in_span(span.clone()))
     }
 
     /// Get an updated iterator of [`ScanMetadata`]s based on an existing iterator of [`EngineData`]s.
@@ -504,6 +848,7 @@ impl Scan {
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<ScanMetadata>>>> {
         static RESTORED_ADD_SCHEMA: LazyLock<DataType> = LazyLock::new(|| {
             let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
+            let tags = MapType::new(DataType::STRING, DataType::STRING, false);
             DataType::struct_type_unchecked(vec![StructField::nullable(
                 "add",
                 DataType::struct_type_unchecked(vec![
@@ -513,6 +858,7 @@ impl Scan {
                     StructField::nullable("modificationTime", DataType::LONG),
                     StructField::nullable("stats", DataType::STRING),
                     StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+                    StructField::nullable("tags", tags),
                 ]),
             )])
         });
@@ -601,12 +947,24 @@ impl Scan {
             PhysicalPredicate::Some(predicate, schema) => Some((predicate, schema)),
             PhysicalPredicate::None => None,
         };
+        // an empty table has no files to scan, so skip replaying its log
+        if self.snapshot.is_known_empty(engine) {
+            return Ok(None.into_iter().flatten());
+        }
+        // each log replay tracks the file actions it sees in a fresh validator
+        let strict_validator = self
+            .strict_validation
+            .then(|| StrictValidator::try_new(&self.snapshot))
+            .transpose()?;
         let it = scan_action_iter(
             engine,
             action_batch_iter,
             self.logical_schema.clone(),
             static_transform,
             physical_predicate,
+            self.skipping_trace.clone(),
+            Some(self.metrics.clone()),
+            strict_validator,
         );
         Ok(Some(it).into_iter().flatten())
     }
@@ -616,12 +974,32 @@ impl Scan {
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
+        // If the scan does data skipping, also read the struct-typed stats that parquet checkpoints
+        // may contain, so the files they cover can be skipped without parsing their JSON stats.
+        let checkpoint_read_schema = match &self.physical_predicate {
+            PhysicalPredicate::Some(_, referenced_schema) => stats_schema(referenced_schema)
+                .map(|stats_schema| with_stats_parsed(&CHECKPOINT_READ_SCHEMA, &stats_schema)),
+            PhysicalPredicate::StaticSkipAll | PhysicalPredicate::None => None,
+        };
+        let checkpoint_read_schema =
+            checkpoint_read_schema.map_or_else(|| CHECKPOINT_READ_SCHEMA.clone(), Arc::new);
+
+        let log_segment = self.snapshot.log_segment();
+        let checkpoint_bytes: u64 = log_segment
+            .checkpoint_parts
+            .iter()
+            .map(|part| part.location.size)
+            .sum();
+        ScanMetrics::add(&self.metrics.checkpoint_bytes_read, checkpoint_bytes);
+        let commits = log_segment.find_commit_cover().len();
+        ScanMetrics::add(&self.metrics.commits_replayed, commits);
+
         // NOTE: We don't pass any meta-predicate because we expect no meaningful row group skipping
         // when ~every checkpoint file will contain the adds and removes we are looking for.
-        self.snapshot.log_segment().read_actions(
+        log_segment.read_actions(
             engine,
             COMMIT_READ_SCHEMA.clone(),
-            CHECKPOINT_READ_SCHEMA.clone(),
+            checkpoint_read_schema,
             None,
         )
     }
@@ -632,12 +1010,23 @@ impl Scan {
     /// the documentation for [`ScanResult`] for more details. Generally connectors/engines will
     /// want to use [`Scan::scan_metadata`] so they can have more control over the execution of the
     /// scan.
+    ///
+    /// If the scan reads no files (e.g. the table is empty), the iterator yields a single empty
+    /// batch with the scan's logical schema, created with [`EvaluationHandler::empty_batch`].
+    ///
+    /// Results are in no particular order, unless the scan was built with
+    /// [`ScanBuilder::with_result_order`].
+    ///
+    /// Errors reading a file are wrapped in an [`Error::WithContext`] that names the file, so
+    /// match on [`Error::root`] or [`Error::into_root`] to handle specific kinds of errors.
+    ///
+    /// [`EvaluationHandler::empty_batch`]: crate::EvaluationHandler::empty_batch
     // This calls [`Scan::scan_metadata`] to get an iterator of `ScanMetadata` actions for the scan,
     // and then uses the `engine`'s [`crate::ParquetHandler`] to read the actual table data.
     pub fn execute(
         &self,
         engine: Arc<dyn Engine>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<'_>> {
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>>> {
         struct ScanFile {
             path: String,
             size: i64,
@@ -652,6 +1041,7 @@ impl Scan {
             dv_info: DvInfo,
             transform: Option<ExpressionRef>,
             _: HashMap<String, String>,
+            _: HashMap<String, String>,
         ) {
             batches.push(ScanFile {
                 path: path.to_string(),
@@ -666,7 +1056,21 @@ impl Scan {
             self.logical_schema, self.physical_schema
         );
 
+        let span = info_span!(
+            "scan_execute",
+            operation_id = %self.operation_id,
+            version = self.snapshot.version(),
+        );
+        let _entered = span.enter();
+
         let table_root = self.snapshot.table_root().clone();
+        let version = self.snapshot.version();
+        let physical_schema = self.physical_schema.clone();
+        let logical_schema = self.logical_schema.clone();
+        let physical_transform = self.physical_transform.clone();
+        let path_policy = self.path_policy.clone();
+        let metrics = self.metrics.clone();
+        let engine_for_empty = engine.clone();
 
         let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
         let scan_files_iter = scan_metadata_iter
@@ -677,14 +1081,38 @@ impl Scan {
             })
             // Iterator<DeltaResult<Vec<ScanFile>>> to Iterator<DeltaResult<ScanFile>>
             .flatten_ok();
+        // to read files in order, log replay must have found all of them first
+        let scan_files_iter: Box<dyn Iterator<Item = DeltaResult<ScanFile>> + Send> =
+            match self.result_order {
+                ResultOrder::Unordered => Box::new(scan_files_iter),
+                ResultOrder::FileOrder => {
+                    let mut scan_files: Vec<_> = scan_files_iter.try_collect()?;
+                    scan_files.sort_by(|a, b| a.path.cmp(&b.path));
+                    Box::new(scan_files.into_iter().map(Ok))
+                }
+            };
 
         let result = scan_files_iter
             .map(move |scan_file| -> DeltaResult<_> {
                 let scan_file = scan_file?;
-                let file_path = table_root.join(&scan_file.path)?;
+                let file_path = resolve_file_path_with_policy(
+                    &table_root,
+                    &scan_file.path,
+                    path_policy.as_deref(),
+                )?;
+                let file_span = debug_span!("read_file", path = %file_path);
+                let _entered = file_span.enter();
+                // attached to errors reading this file, so engines can tell which file failed
+                let context = ErrorContext::default()
+                    .with_table_uri(table_root.clone())
+                    .with_version(version)
+                    .with_file_path(&file_path);
+                let start = Instant::now();
+                ScanMetrics::add(&metrics.data_files_read, 1u64);
                 let mut selection_vector = scan_file
                     .dv_info
-                    .get_selection_vector(engine.as_ref(), &table_root)?;
+                    .get_selection_vector(engine.as_ref(), &table_root)
+                    .map_err(|e| e.with_context(context.clone()))?;
                 let meta = FileMeta {
                     last_modified: 0,
                     size: scan_file.size.try_into().map_err(|_| {
@@ -699,23 +1127,36 @@ impl Scan {
                 // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
                 //
                 // TODO(#860): we disable predicate pushdown until we support row indexes.
-                let read_result_iter = engine.parquet_handler().read_parquet_files(
-                    &[meta],
-                    self.physical_schema().clone(),
-                    None,
-                )?;
-
-                let engine = engine.clone(); // Arc clone
-                Ok(read_result_iter.map(move |read_result| -> DeltaResult<_> {
-                    let read_result = read_result?;
-                    // transform the physical data into the correct logical form
-                    let logical = state::transform_to_logical(
-                        engine.as_ref(),
-                        read_result,
-                        self.physical_schema(),
-                        self.logical_schema(),
-                        scan_file.transform.clone(), // Arc clone
-                    );
+                let read_result_iter = engine
+                    .parquet_handler()
+                    .read_parquet_files(&[meta], physical_schema.clone(), None)
+                    .map_err(|e| e.with_context(context.clone()))?;
+
+                // apply the column hooks, then transform the physical data into the correct
+                // logical form. The evaluators are created once per file and evaluate all of its
+                // batches.
+                let evaluation = engine.evaluation_handler();
+                let mut data = read_result_iter;
+                if let Some(transform) = physical_transform.clone() {
+                    data = evaluation
+                        .new_expression_evaluator(
+                            physical_schema.clone(),
+                            transform,
+                            physical_schema.clone().into(),
+                        )
+                        .evaluate_batches(data);
+                }
+                if let Some(transform) = scan_file.transform {
+                    data = evaluation
+                        .new_expression_evaluator(
+                            physical_schema.clone(),
+                            transform,
+                            logical_schema.clone().into(),
+                        )
+                        .evaluate_batches(data);
+                }
+                let read_results = data.map(move |logical| -> DeltaResult<_> {
+                    let logical = logical.map_err(|e| e.with_context(context.clone()));
                     let len = logical.as_ref().map_or(0, |res| res.len());
                     // need to split the dv_mask. what's left in dv_mask covers this result, and rest
                     // will cover the following results. we `take()` out of `selection_vector` to avoid
@@ -729,18 +1170,50 @@ impl Scan {
                     };
                     selection_vector = rest;
                     Ok(result)
-                }))
+                });
+                metrics.add_data_read_time(start.elapsed());
+                let metrics = metrics.clone();
+                let read_results = Timed::new(read_results, move |elapsed| {
+                    metrics.add_data_read_time(elapsed)
+                });
+                Ok(read_results.in_span(file_span.clone()))
             })
             // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
             .flatten_ok()
             // Iterator<DeltaResult<DeltaResult<ScanResult>>> to Iterator<DeltaResult<ScanResult>>
             .map(|x| x?);
-        Ok(result)
+
+        // If the scan reads no files, return a single empty batch with the logical schema instead,
+        // so that engines need not special-case empty tables.
+        let mut result = result.fuse();
+        let mut empty_result = Some((engine_for_empty, self.logical_schema.clone()));
+        let result = std::iter::from_fn(move || match result.next() {
+            Some(res) => {
+                empty_result = None;
+                Some(res)
+            }
+            None => {
+                let (engine, logical_schema) = empty_result.take()?;
+                match engine.evaluation_handler().empty_batch(logical_schema) {
+                    Ok(data) => Some(Ok(ScanResult {
+                        raw_data: Ok(data),
+                        raw_mask: None,
+                    })),
+                    // the engine can't create an empty batch, so return no data at all
+                    Err(Error::Unsupported(_)) => None,
+                    Err(err) => Some(Err(err)),
+                }
+            }
+        });
+        Ok(result.in_span(span.clone()))
     }
 }
 
 /// Get the schema that scan rows (from [`Scan::scan_metadata`]) will be returned with.
 ///
+/// `statsParsed.numRecords` holds the `numRecords` statistic of `stats`, parsed during log replay,
+/// so engines that only need the row counts of files (e.g. for planning) need not parse the stats.
+///
 /// It is:
 /// ```ignored
 /// {
@@ -748,6 +1221,9 @@ impl Scan {
 ///    size: long,
 ///    modificationTime: long,
 ///    stats: string,
+///    statsParsed: {
+///      numRecords: long,
+///    },
 ///    deletionVector: {
 ///      storageType: string,
 ///      pathOrInlineDv: string,
@@ -756,7 +1232,8 @@ impl Scan {
 ///      cardinality: long,
 ///    },
 ///    fileConstantValues: {
-///      partitionValues: map<string, string>
+///      partitionValues: map<string, string>,
+///      tags: map<string, string>
 ///    }
 /// }
 /// ```
@@ -973,6 +1450,9 @@ pub(crate) mod test_utils {
             logical_schema,
             transform_spec,
             None,
+            None,
+            None,
+            None,
         );
         let mut batch_count = 0;
         for res in iter {
@@ -991,13 +1471,15 @@ pub(crate) mod test_utils {
 mod tests {
     use std::path::PathBuf;
 
-    use crate::arrow::array::BooleanArray;
+    use crate::arrow::array::{AsArray as _, BooleanArray};
     use crate::arrow::compute::filter_record_batch;
+    use crate::arrow::datatypes::Int64Type;
     use crate::arrow::record_batch::RecordBatch;
+    use crate::engine::arrow_conversion::TryIntoArrow as _;
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::sync::SyncEngine;
     use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
-    use crate::schema::{ColumnMetadataKey, PrimitiveType};
+    use crate::schema::{ColumnMetadataKey, MetadataValue, PrimitiveType};
     use crate::Snapshot;
 
     use super::*;
@@ -1190,6 +1672,7 @@ mod tests {
             dv_info: DvInfo,
             _transform: Option<ExpressionRef>,
             _partition_values: HashMap<String, String>,
+            _tags: HashMap<String, String>,
         ) {
             paths.push(path.to_string());
             assert!(dv_info.deletion_vector.is_none());
@@ -1202,6 +1685,215 @@ mod tests {
         Ok(files)
     }
 
+    #[test]
+    fn test_transform_for_partition_values() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+
+        fn scan_metadata_callback(
+            files: &mut Vec<(Option<ExpressionRef>, HashMap<String, String>)>,
+            _path: &str,
+            _size: i64,
+            _: Option<Stats>,
+            _dv_info: DvInfo,
+            transform: Option<ExpressionRef>,
+            partition_values: HashMap<String, String>,
+            _tags: HashMap<String, String>,
+        ) {
+            files.push((transform, partition_values));
+        }
+        let mut files = vec![];
+        for res in scan.scan_metadata(&engine).unwrap() {
+            files = res
+                .unwrap()
+                .visit_scan_files(files, scan_metadata_callback)
+                .unwrap();
+        }
+        assert_eq!(files.len(), 6);
+        // the helper reproduces the transform the kernel computed for every file
+        for (transform, partition_values) in files {
+            assert!(transform.is_some());
+            let computed = scan
+                .transform_for_partition_values(&partition_values)
+                .unwrap();
+            assert_eq!(computed, transform);
+        }
+
+        // without partition columns (or column mapping), no transform is needed
+        let schema = snapshot.schema().project(&["number"]).unwrap();
+        let scan = snapshot.scan_builder().with_schema(schema).build().unwrap();
+        let partition_values = HashMap::from([("letter".to_string(), "a".to_string())]);
+        let transform = scan
+            .transform_for_partition_values(&partition_values)
+            .unwrap();
+        assert_eq!(transform, None);
+    }
+
+    #[test]
+    fn test_scan_file_groups() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let scan = snapshot.scan_builder().build().unwrap();
+
+        let groups = scan.scan_file_groups(&engine, 2, false).unwrap();
+        assert_eq!(groups.len(), 2);
+        let num_files: usize = groups.iter().map(|group| group.files.len()).sum();
+        assert_eq!(num_files, 6);
+
+        // a single group per partition, as no partition is larger than the whole table
+        let groups = scan.scan_file_groups(&engine, 1, true).unwrap();
+        assert_eq!(groups.len(), 5);
+        for group in groups {
+            let partitions: HashSet<_> = group
+                .files
+                .iter()
+                .map(|file| file.partition_values.get("letter"))
+                .collect();
+            assert_eq!(partitions.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_scan_column_order() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+        let table_schema = snapshot.schema();
+        let requested =
+            ["a_float", "letter", "number"].map(|name| table_schema.field(name).unwrap().clone());
+        let requested = Arc::new(StructType::new_unchecked(requested));
+
+        for (column_order, expected) in [
+            (ColumnOrder::Requested, ["a_float", "letter", "number"]),
+            (ColumnOrder::Table, ["letter", "number", "a_float"]),
+        ] {
+            let scan = snapshot
+                .clone()
+                .scan_builder()
+                .with_schema(requested.clone())
+                .with_column_order(column_order)
+                .build()
+                .unwrap();
+            let names: Vec<_> = scan.logical_schema().field_names().cloned().collect();
+            assert_eq!(names, expected);
+
+            // the data follows the logical schema, including the (inserted) partition column
+            for result in scan.execute(engine.clone()).unwrap() {
+                let data = result.unwrap().raw_data.unwrap();
+                let batch: RecordBatch =
+                    ArrowEngineData::try_from_engine_data(data).unwrap().into();
+                let names: Vec<_> = batch
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| f.name().clone())
+                    .collect();
+                assert_eq!(names, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_result_order() {
+        use crate::arrow::array::AsArray as _;
+
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+        let scan = snapshot
+            .scan_builder()
+            .with_result_order(ResultOrder::FileOrder)
+            .build()
+            .unwrap();
+
+        // files are partitioned by letter, so their paths sort like their letters, nulls first
+        let mut letters = vec![];
+        for result in scan.execute(engine).unwrap() {
+            let data = result.unwrap().raw_data.unwrap();
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data).unwrap().into();
+            let column = batch.column_by_name("letter").unwrap().as_string::<i32>();
+            letters.extend(column.iter().map(|letter| letter.map(str::to_string)));
+        }
+        let mut sorted = letters.clone();
+        sorted.sort();
+        assert_eq!(letters.len(), 6);
+        assert_eq!(letters, sorted);
+        assert_eq!(letters[0], None);
+    }
+
+    #[test]
+    fn test_scan_path_policy() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url.clone())
+            .build(engine.as_ref())
+            .unwrap();
+        let scan = snapshot
+            .scan_builder()
+            .with_path_policy(Arc::new(crate::uri::DenyExternalFiles))
+            .build()
+            .unwrap();
+
+        // the table's own files can be read
+        let results: Vec<_> = scan.execute(engine).unwrap().try_collect().unwrap();
+        assert_eq!(results.len(), 1);
+        let file = scan.resolve_file_path("a%20b.parquet").unwrap();
+        assert_eq!(file, url.join("a%20b.parquet").unwrap());
+
+        let result = scan.resolve_file_path("s3://other-bucket/part-0.parquet");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_scan_empty_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        let protocol = r#"{"minReaderVersion":1,"minWriterVersion":2}"#;
+        let metadata = r#"{"id":"id","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":false,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1}"#;
+        let commit0 = format!("{{\"protocol\":{protocol}}}\n{{\"metaData\":{metadata}}}");
+        std::fs::write(log_dir.join(format!("{:020}.json", 0)), commit0).unwrap();
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+
+        let check_empty = |known_empty: bool| {
+            let snapshot = Snapshot::builder_for(url.clone())
+                .build(engine.as_ref())
+                .unwrap();
+            assert_eq!(snapshot.is_known_empty(engine.as_ref()), known_empty);
+            let scan = snapshot.scan_builder().build().unwrap();
+            assert_eq!(scan.scan_metadata(engine.as_ref()).unwrap().count(), 0);
+
+            // the scan returns a single empty batch with the logical schema
+            let results: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
+            assert_eq!(results.len(), 1);
+            assert!(results[0].raw_mask().is_none());
+            let data = results.into_iter().next().unwrap().raw_data.unwrap();
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data).unwrap().into();
+            assert_eq!(batch.num_rows(), 0);
+            let expected: crate::arrow::datatypes::Schema =
+                scan.logical_schema().as_ref().try_into_arrow().unwrap();
+            assert_eq!(batch.schema().as_ref(), &expected);
+        };
+        // without a CRC file, the log must be replayed to find that the table is empty
+        check_empty(false);
+
+        let crc = format!(
+            r#"{{"tableSizeBytes":0,"numFiles":0,"numMetadata":1,"numProtocol":1,"metadata":{metadata},"protocol":{protocol}}}"#
+        );
+        std::fs::write(log_dir.join(format!("{:020}.crc", 0)), crc).unwrap();
+        check_empty(true);
+    }
+
     #[test]
     fn test_scan_metadata_paths() {
         let path =
@@ -1235,6 +1927,252 @@ mod tests {
         assert_eq!(num_rows, 10)
     }
 
+    #[derive(Debug)]
+    struct MaskHook;
+
+    impl ColumnHook for MaskHook {
+        fn transform_column(
+            &self,
+            field: &StructField,
+            _physical_name: &str,
+            _value: &MetadataValue,
+        ) -> DeltaResult<ExpressionRef> {
+            Ok(Arc::new(Expr::null_literal(field.data_type().clone())))
+        }
+    }
+
+    #[test]
+    fn test_scan_with_column_hook() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+
+        // without tagged columns, the hook does not apply
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_column_hook("mask", Arc::new(MaskHook))
+            .build()
+            .unwrap();
+        assert!(scan.physical_transform().is_none());
+
+        let schema = Arc::new(StructType::new_unchecked(vec![StructField::nullable(
+            "value",
+            DataType::LONG,
+        )
+        .with_metadata([("mask", "all")])]));
+        let scan = snapshot
+            .scan_builder()
+            .with_schema(schema)
+            .with_column_hook("mask", Arc::new(MaskHook))
+            .build()
+            .unwrap();
+        assert!(scan.physical_transform().is_some());
+
+        let results: Vec<ScanResult> = scan.execute(engine).unwrap().try_collect().unwrap();
+        assert_eq!(results.len(), 1);
+        let data = results.into_iter().next().unwrap().raw_data.unwrap();
+        let batch: RecordBatch = ArrowEngineData::try_from_engine_data(data).unwrap().into();
+        assert_eq!(batch.num_rows(), 10);
+        assert_eq!(batch.column(0).null_count(), 10);
+    }
+
+    #[test]
+    fn test_scan_report() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+        let results: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
+        assert_eq!(results.len(), 1);
+        let report = scan.report();
+        assert_eq!(report.log_files_listed, 1);
+        assert_eq!(report.commits_replayed, 1);
+        assert_eq!(report.checkpoint_bytes_read, 0);
+        assert_eq!(report.files_considered, 1);
+        assert_eq!(report.files_selected, 1);
+        assert_eq!(report.data_files_read, 1);
+        assert_eq!(report.skipping_efficiency(), Some(0.0));
+
+        // the only file's stats prove that no row matches
+        let predicate = Pred::gt(column_expr!("value"), Expr::literal(10i64));
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .unwrap();
+        let files: Vec<_> = scan
+            .scan_metadata(engine.as_ref())
+            .unwrap()
+            .try_collect()
+            .unwrap();
+        assert!(files.is_empty());
+        let report = scan.report();
+        assert_eq!(report.files_considered, 1);
+        assert_eq!(report.files_skipped, 1);
+        assert_eq!(report.files_selected, 0);
+        assert_eq!(report.data_files_read, 0);
+        assert_eq!(report.skipping_efficiency(), Some(1.0));
+    }
+
+    #[test]
+    fn test_strict_validation() {
+        let engine = SyncEngine::new();
+        let count_files = |url: &url::Url, strict: bool| -> DeltaResult<usize> {
+            let snapshot = Snapshot::builder_for(url.clone()).build(&engine)?;
+            let scan = snapshot
+                .scan_builder()
+                .with_strict_validation(strict)
+                .build()?;
+            let mut count = 0;
+            for scan_metadata in scan.scan_metadata(&engine)? {
+                let selection_vector = scan_metadata?.scan_files.selection_vector;
+                count += selection_vector.into_iter().filter(|s| *s).count();
+            }
+            Ok(count)
+        };
+
+        for table in ["table-with-dv-small", "basic_partitioned"] {
+            let path = std::fs::canonicalize(PathBuf::from("./tests/data").join(table)).unwrap();
+            let url = url::Url::from_directory_path(path).unwrap();
+            assert_eq!(
+                count_files(&url, true).unwrap(),
+                count_files(&url, false).unwrap()
+            );
+        }
+
+        // a table with a single file
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        let add = r#"{"add":{"path":"a.parquet","partitionValues":{"date":"2024-01-01"},"size":1,"modificationTime":1,"dataChange":true}}"#;
+        let commit0 = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+            r#"{"metaData":{"id":"id","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"date\",\"type\":\"date\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["date"],"configuration":{},"createdTime":1}}"#,
+            add,
+        ];
+        std::fs::write(log_dir.join(format!("{:020}.json", 0)), commit0.join("\n")).unwrap();
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+
+        // re-adding the file just to update its stats is fine ...
+        let stats_update = add.replace(r#""dataChange":true"#, r#""dataChange":false"#);
+        std::fs::write(log_dir.join(format!("{:020}.json", 1)), stats_update).unwrap();
+        assert_eq!(count_files(&url, true).unwrap(), 1);
+
+        // ... but adding it again without removing it in between is not
+        std::fs::write(log_dir.join(format!("{:020}.json", 2)), add).unwrap();
+        assert_eq!(count_files(&url, false).unwrap(), 1);
+        let result = count_files(&url, true);
+        assert!(matches!(result, Err(Error::InvalidTableState(_))));
+    }
+
+    // Records the name and `operation_id` field of every span created while it is active.
+    #[derive(Default, Clone)]
+    struct SpanRecorder(Arc<std::sync::Mutex<Vec<(String, Option<String>)>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanRecorder {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            struct OperationId(Option<String>);
+            impl tracing::field::Visit for OperationId {
+                fn record_debug(
+                    &mut self,
+                    field: &tracing::field::Field,
+                    value: &dyn std::fmt::Debug,
+                ) {
+                    if field.name() == "operation_id" {
+                        self.0 = Some(format!("{value:?}"));
+                    }
+                }
+            }
+            let mut operation_id = OperationId(None);
+            attrs.record(&mut operation_id);
+            let name = attrs.metadata().name().to_string();
+            self.0.lock().unwrap().push((name, operation_id.0));
+        }
+    }
+
+    #[test]
+    fn test_scan_tracing_spans() {
+        use tracing_subscriber::layer::SubscriberExt as _;
+
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+
+        let recorder = SpanRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let operation_id = tracing::subscriber::with_default(subscriber, || {
+            let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+            let scan = snapshot.scan_builder().build().unwrap();
+            let results: Vec<_> = scan.execute(engine).unwrap().try_collect().unwrap();
+            assert_eq!(results.len(), 1);
+            scan.operation_id()
+        });
+
+        let spans = recorder.0.lock().unwrap();
+        let names: HashSet<_> = spans.iter().map(|(name, _)| name.as_str()).collect();
+        for name in [
+            "snapshot_build",
+            "log_replay",
+            "scan_execute",
+            "scan_metadata",
+            "read_file",
+        ] {
+            assert!(names.contains(name), "missing span {name}");
+        }
+        let operation_id = operation_id.to_string();
+        for (name, id) in spans.iter() {
+            if name.starts_with("scan_") {
+                assert_eq!(id.as_deref(), Some(operation_id.as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan_rows_have_num_records() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let scan = snapshot.scan_builder().build().unwrap();
+        let mut files = 0;
+        for scan_metadata in scan.scan_metadata(&engine).unwrap() {
+            let ScanMetadata { scan_files, .. } = scan_metadata.unwrap();
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(scan_files.data)
+                .unwrap()
+                .into();
+            let stats = batch.column_by_name("stats").unwrap().as_string::<i32>();
+            let num_records = batch
+                .column_by_name("statsParsed")
+                .unwrap()
+                .as_struct()
+                .column_by_name("numRecords")
+                .unwrap()
+                .as_primitive::<Int64Type>();
+            for (i, selected) in scan_files.selection_vector.iter().enumerate() {
+                if *selected {
+                    let stats: serde_json::Value = serde_json::from_str(stats.value(i)).unwrap();
+                    assert_eq!(Some(num_records.value(i)), stats["numRecords"].as_i64());
+                    files += 1;
+                }
+            }
+        }
+        assert!(files > 0);
+    }
+
     #[test_log::test]
     fn test_scan_metadata_from_same_version() {
         let path =
@@ -1322,6 +2260,69 @@ mod tests {
         assert_eq!(new_files[1].num_rows(), 3);
     }
 
+    #[test]
+    fn test_scan_metadata_from_keeps_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        let commit0 = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+            r#"{"metaData":{"id":"id","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1}}"#,
+            r#"{"add":{"path":"a.parquet","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true,"tags":{"ZCUBE_ID":"zc-1"}}}"#,
+            r#"{"add":{"path":"b.parquet","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true}}"#,
+        ]
+        .join("\n");
+        std::fs::write(log_dir.join(format!("{:020}.json", 0)), commit0).unwrap();
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let scan = snapshot.scan_builder().build().unwrap();
+        let mut files = vec![];
+        let mut existing_data = vec![];
+        for scan_metadata in scan.scan_metadata(&engine).unwrap() {
+            let scan_metadata = scan_metadata.unwrap();
+            files = scan_metadata
+                .visit_scan_files(files, ScanFile::collect)
+                .unwrap();
+            let ScanMetadata { scan_files, .. } = scan_metadata;
+            let batch: RecordBatch = ArrowEngineData::try_from_engine_data(scan_files.data)
+                .unwrap()
+                .into();
+            let batch =
+                filter_record_batch(&batch, &BooleanArray::from(scan_files.selection_vector))
+                    .unwrap();
+            existing_data.push(Box::new(ArrowEngineData::from(batch)) as Box<dyn EngineData>);
+        }
+        let tags_by_path = |files: Vec<ScanFile>| -> HashMap<_, _> {
+            files
+                .into_iter()
+                .map(|file| (file.path, file.tags))
+                .collect()
+        };
+        let expected = HashMap::from([
+            (
+                "a.parquet".to_string(),
+                HashMap::from([("ZCUBE_ID".to_string(), "zc-1".to_string())]),
+            ),
+            ("b.parquet".to_string(), HashMap::new()),
+        ]);
+        assert_eq!(tags_by_path(files), expected);
+
+        // restoring the scan metadata into add actions keeps their tags
+        let mut restored = vec![];
+        for scan_metadata in scan
+            .scan_metadata_from(&engine, 0, existing_data, None)
+            .unwrap()
+        {
+            restored = scan_metadata
+                .unwrap()
+                .visit_scan_files(restored, ScanFile::collect)
+                .unwrap();
+        }
+        assert_eq!(tags_by_path(restored), expected);
+    }
+
     #[test]
     fn test_get_partition_value() {
         let cases = [
@@ -1384,6 +2385,16 @@ mod tests {
         //
         // NOTE: Each checkpoint part is a single-row file -- guaranteed to produce one row group.
         assert_eq!(data.len(), 5);
+
+        // Checkpoint batches only have the add fields that log replay uses
+        let mut checkpoint_batches = 0;
+        for batch in data.into_iter().filter(|batch| !batch.is_log_batch) {
+            let batch = ArrowEngineData::try_from_engine_data(batch.actions).unwrap();
+            let add = batch.record_batch().column_by_name(ADD_NAME).unwrap();
+            assert_eq!(add.as_struct().column_names(), CHECKPOINT_ADD_FIELDS);
+            checkpoint_batches += 1;
+        }
+        assert!(checkpoint_batches > 0);
     }
 
     #[test]
@@ -1454,11 +2465,12 @@ mod tests {
 
         // Predicate over a logically missing column fails the scan
         let predicate = Arc::new(column_expr!("numeric.ints.invalid").lt(Expr::literal(1000)));
-        snapshot
+        let err = snapshot
             .scan_builder()
             .with_predicate(predicate)
             .build()
             .expect_err("unknown column");
+        assert_eq!(err.code(), crate::ErrorCode::PredicateBinding);
     }
 
     #[test_log::test]
@@ -1488,4 +2500,23 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_full_mask_buffer() -> DeltaResult<()> {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/"))?;
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref())?;
+        let scan = snapshot.scan_builder().build()?;
+        for scan_result in scan.execute(engine)? {
+            let scan_result = scan_result?;
+            let mask = scan_result.full_mask().unwrap();
+            let buffer = scan_result.full_mask_buffer().unwrap();
+            assert_eq!(buffer.len(), scan_result.raw_data?.len());
+            assert_eq!(buffer.iter().collect::<Vec<_>>(), mask);
+            assert_eq!(buffer.count_set_bits(), 8);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,565 @@
+//! Binding of the columns a scan predicate references to the scan's schema.
+//!
+//! A predicate whose columns do not exist in the schema, or whose literals cannot be compared to
+//! the columns they are compared with, could only fail once data is evaluated, with errors that
+//! say little about the predicate itself. Binding it when the scan is built instead reports every
+//! offending column at once, see [`Error::PredicateBinding`].
+//!
+//! Timestamp literals with an explicit UTC offset are normalized against the columns they are
+//! compared with before binding, see [`normalize_timestamp_literals`].
+//!
+//! [`BoundPredicate`] is the result of binding: the predicate with its literals coerced to the
+//! types of the columns they are compared with, so that evaluation need not widen either side.
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::expressions::transforms::ExpressionTransform;
+use crate::expressions::{
+    ArrayData, BinaryPredicate, BinaryPredicateOp, ColumnName, Expression, Predicate, PredicateRef,
+    Scalar,
+};
+use crate::schema::{ArrayType, DataType, PrimitiveType, StructType};
+use crate::{DeltaResult, Error};
+
+/// A column reference of a scan predicate that does not bind to the scan's schema, because the
+/// schema has no such column or the predicate expects it to have an incompatible type. See
+/// [`Error::PredicateBinding`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredicateBindingError {
+    /// The logical name of the referenced column.
+    pub column: ColumnName,
+    /// The type the predicate expects the column to have, if the predicate implies one, e.g. by
+    /// comparing the column to a literal.
+    pub expected_type: Option<DataType>,
+    /// The type of the column in the scan's schema, or `None` if the schema has no such column.
+    pub actual_type: Option<DataType>,
+}
+
+impl Display for PredicateBindingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.expected_type, &self.actual_type) {
+            (Some(expected), Some(actual)) => {
+                write!(f, "{} (expected {expected}, found {actual})", self.column)
+            }
+            (Some(expected), None) => {
+                write!(f, "{} (unknown column of type {expected})", self.column)
+            }
+            (None, _) => write!(f, "{} (unknown column)", self.column),
+        }
+    }
+}
+
+/// A predicate bound to a schema, see [`BoundPredicate::try_bind`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundPredicate {
+    predicate: PredicateRef,
+}
+
+impl BoundPredicate {
+    /// Binds `predicate` to `schema`. This
+    ///
+    /// 1. normalizes timestamp literals with a UTC offset, see [`normalize_timestamp_literals`],
+    /// 2. checks that every referenced column exists in `schema` with a type compatible to what
+    ///    the predicate expects of it, failing with an [`Error::PredicateBinding`] that lists all
+    ///    offending columns otherwise, and
+    /// 3. coerces numeric literals compared with a column to the column's type, if they convert
+    ///    losslessly, so that evaluation need not widen either side.
+    pub fn try_bind(predicate: &Predicate, schema: &StructType) -> DeltaResult<Self> {
+        let normalized = normalize_timestamp_literals(predicate, schema);
+        bind_predicate(&normalized, schema)?;
+        let coerced = CoerceNumericLiterals { schema }
+            .transform_pred(&normalized)
+            .map(Cow::into_owned);
+        let predicate = match coerced {
+            Some(coerced) => coerced,
+            None => normalized.into_owned(),
+        };
+        Ok(Self {
+            predicate: Arc::new(predicate),
+        })
+    }
+
+    /// The bound predicate, with normalized and coerced literals.
+    pub fn predicate(&self) -> &PredicateRef {
+        &self.predicate
+    }
+}
+
+/// Checks that every column `predicate` references exists in `schema`, with a type compatible
+/// to what the predicate expects of it. Fails with an [`Error::PredicateBinding`] that lists all
+/// offending columns otherwise.
+pub(crate) fn bind_predicate(predicate: &Predicate, schema: &StructType) -> DeltaResult<()> {
+    let mut expectations = vec![];
+    collect_expected_types(predicate, &mut expectations);
+
+    let mut errors = vec![];
+    // sort the references, so that errors are reported in a deterministic order
+    let references: BTreeSet<_> = predicate.references().into_iter().collect();
+    for column in references {
+        let expected = expectations
+            .iter()
+            .filter(|(name, _)| *name == column)
+            .map(|(_, data_type)| data_type);
+        match resolve_column(schema, column) {
+            Some(actual) => {
+                let mut mismatches: Vec<&DataType> = vec![];
+                for expected in expected.filter(|expected| !is_compatible(actual, expected)) {
+                    if !mismatches.contains(&expected) {
+                        mismatches.push(expected);
+                    }
+                }
+                errors.extend(
+                    mismatches
+                        .into_iter()
+                        .map(|expected| PredicateBindingError {
+                            column: column.clone(),
+                            expected_type: Some(expected.clone()),
+                            actual_type: Some(actual.clone()),
+                        }),
+                );
+            }
+            None => errors.push(PredicateBindingError {
+                column: column.clone(),
+                expected_type: expected.cloned().next(),
+                actual_type: None,
+            }),
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(Error::PredicateBinding(errors)),
+    }
+}
+
+/// Collects the types `predicate` expects the columns it compares to literals to have.
+fn collect_expected_types<'a>(
+    predicate: &'a Predicate,
+    expectations: &mut Vec<(&'a ColumnName, DataType)>,
+) {
+    match predicate {
+        Predicate::Not(predicate) => collect_expected_types(predicate, expectations),
+        Predicate::Junction(junction) => {
+            for predicate in &junction.preds {
+                collect_expected_types(predicate, expectations);
+            }
+        }
+        Predicate::Binary(BinaryPredicate { op, left, right }) => {
+            use Expression::{Column, Literal};
+            let expectation = match (op, left.as_ref(), right.as_ref()) {
+                // `col IN (...)` expects the column to have the type of the list's elements
+                (BinaryPredicateOp::In, Column(column), Literal(Scalar::Array(array))) => {
+                    Some((column, array.array_type().element_type().clone()))
+                }
+                (BinaryPredicateOp::In, _, _) => None,
+                (_, Column(column), Literal(literal)) | (_, Literal(literal), Column(column)) => {
+                    Some((column, literal.data_type()))
+                }
+                _ => None,
+            };
+            expectations.extend(expectation);
+        }
+        Predicate::BooleanExpression(_)
+        | Predicate::Unary(_)
+        | Predicate::Opaque(_)
+        | Predicate::Unknown(_) => {}
+    }
+}
+
+/// Replaces the timestamp literals with an explicit UTC offset ([`Scalar::TimestampWithOffset`])
+/// of `predicate` by literals of the type of the column they are compared with in `schema`:
+///
+/// * against a `TIMESTAMP_NTZ` column, by a [`Scalar::TimestampNtz`] of the literal's local time
+///   at its offset, e.g. `2024-01-01 10:00:00+02:00` becomes `2024-01-01 10:00:00`.
+/// * against a `TIMESTAMP` column (or anything else), by a [`Scalar::Timestamp`] of the instant
+///   it denotes, e.g. `2024-01-01 10:00:00+02:00` becomes `2024-01-01 08:00:00 UTC`.
+///
+/// Returns the predicate unchanged if it has no such literals.
+pub(crate) fn normalize_timestamp_literals<'p>(
+    predicate: &'p Predicate,
+    schema: &StructType,
+) -> Cow<'p, Predicate> {
+    NormalizeTimestampLiterals { schema }
+        .transform_pred(predicate)
+        .unwrap_or(Cow::Borrowed(predicate))
+}
+
+struct NormalizeTimestampLiterals<'s> {
+    schema: &'s StructType,
+}
+
+impl<'a> ExpressionTransform<'a> for NormalizeTimestampLiterals<'_> {
+    fn transform_expr_literal(&mut self, value: &'a Scalar) -> Option<Cow<'a, Scalar>> {
+        Some(normalize_literal(value, false).map_or(Cow::Borrowed(value), Cow::Owned))
+    }
+
+    fn transform_pred_binary(
+        &mut self,
+        pred: &'a BinaryPredicate,
+    ) -> Option<Cow<'a, BinaryPredicate>> {
+        use Expression::{Column, Literal};
+        let (column, literal, literal_first) = match (pred.left.as_ref(), pred.right.as_ref()) {
+            (Column(column), Literal(literal)) => (column, literal, false),
+            (Literal(literal), Column(column)) => (column, literal, true),
+            _ => return self.recurse_into_pred_binary(pred),
+        };
+        let ntz = resolve_column(self.schema, column) == Some(&DataType::TIMESTAMP_NTZ);
+        let Some(literal) = normalize_literal(literal, ntz) else {
+            return Some(Cow::Borrowed(pred));
+        };
+        let (column, literal) = (Box::new(Column(column.clone())), Box::new(Literal(literal)));
+        let (left, right) = match literal_first {
+            true => (literal, column),
+            false => (column, literal),
+        };
+        Some(Cow::Owned(BinaryPredicate {
+            op: pred.op,
+            left,
+            right,
+        }))
+    }
+}
+
+/// Converts numeric literals compared with a column to the column's type in `schema`, if they
+/// convert losslessly. Other literals keep their type, and evaluation widens them as needed.
+struct CoerceNumericLiterals<'s> {
+    schema: &'s StructType,
+}
+
+impl<'a> ExpressionTransform<'a> for CoerceNumericLiterals<'_> {
+    fn transform_pred_binary(
+        &mut self,
+        pred: &'a BinaryPredicate,
+    ) -> Option<Cow<'a, BinaryPredicate>> {
+        use Expression::{Column, Literal};
+        let (column, literal, literal_first) = match (pred.left.as_ref(), pred.right.as_ref()) {
+            _ if pred.op == BinaryPredicateOp::In => return Some(Cow::Borrowed(pred)),
+            (Column(column), Literal(literal)) => (column, literal, false),
+            (Literal(literal), Column(column)) => (column, literal, true),
+            _ => return self.recurse_into_pred_binary(pred),
+        };
+        let coerced = match resolve_column(self.schema, column) {
+            Some(DataType::Primitive(column_type)) => coerce_numeric_literal(literal, column_type),
+            _ => None,
+        };
+        let Some(literal) = coerced else {
+            return Some(Cow::Borrowed(pred));
+        };
+        let (column, literal) = (Box::new(Column(column.clone())), Box::new(Literal(literal)));
+        let (left, right) = match literal_first {
+            true => (literal, column),
+            false => (column, literal),
+        };
+        Some(Cow::Owned(BinaryPredicate {
+            op: pred.op,
+            left,
+            right,
+        }))
+    }
+}
+
+/// `literal` converted to the numeric type `to`, if it is a numeric literal of another type that
+/// converts losslessly.
+fn coerce_numeric_literal(literal: &Scalar, to: &PrimitiveType) -> Option<Scalar> {
+    use PrimitiveType::*;
+    let integral = match literal {
+        Scalar::Byte(value) => Some(i64::from(*value)),
+        Scalar::Short(value) => Some(i64::from(*value)),
+        Scalar::Integer(value) => Some(i64::from(*value)),
+        Scalar::Long(value) => Some(*value),
+        _ => None,
+    };
+    let coerced = match (integral, literal, to) {
+        (_, Scalar::Byte(_), Byte)
+        | (_, Scalar::Short(_), Short)
+        | (_, Scalar::Integer(_), Integer)
+        | (_, Scalar::Long(_), Long)
+        | (_, Scalar::Float(_), Float)
+        | (_, Scalar::Double(_), Double) => return None,
+        (Some(value), _, Byte) => Scalar::Byte(i8::try_from(value).ok()?),
+        (Some(value), _, Short) => Scalar::Short(i16::try_from(value).ok()?),
+        (Some(value), _, Integer) => Scalar::Integer(i32::try_from(value).ok()?),
+        (Some(value), _, Long) => Scalar::Long(value),
+        // integers up to 2^53 are exact doubles
+        (Some(value), _, Double) if value.unsigned_abs() <= 1 << 53 => Scalar::Double(value as f64),
+        (_, Scalar::Float(value), Double) => Scalar::Double(f64::from(*value)),
+        _ => return None,
+    };
+    Some(coerced)
+}
+
+/// The normalized form of `literal`, if it is a timestamp with an offset or a list of them, see
+/// [`normalize_timestamp_literals`].
+fn normalize_literal(literal: &Scalar, ntz: bool) -> Option<Scalar> {
+    match literal {
+        Scalar::TimestampWithOffset(micros, offset) => Some(match ntz {
+            true => Scalar::TimestampNtz(micros.saturating_add(i64::from(*offset) * 1_000_000)),
+            false => Scalar::Timestamp(*micros),
+        }),
+        Scalar::Array(array) => {
+            #[allow(deprecated)]
+            let elements = array.array_elements();
+            if !elements
+                .iter()
+                .any(|element| matches!(element, Scalar::TimestampWithOffset(..)))
+            {
+                return None;
+            }
+            let element_type = match ntz {
+                true => DataType::TIMESTAMP_NTZ,
+                false => DataType::TIMESTAMP,
+            };
+            let elements = elements.iter().map(|element| match element {
+                Scalar::Null(_) => Scalar::Null(element_type.clone()),
+                element => normalize_literal(element, ntz).unwrap_or_else(|| element.clone()),
+            });
+            let array_type =
+                ArrayType::new(element_type.clone(), array.array_type().contains_null());
+            // lists that mix in other timestamps stay as they are, and fail to bind
+            ArrayData::try_new(array_type, elements)
+                .ok()
+                .map(Scalar::Array)
+        }
+        _ => None,
+    }
+}
+
+/// The type of the (possibly nested) `column` in `schema`, if it exists.
+fn resolve_column<'s>(schema: &'s StructType, column: &ColumnName) -> Option<&'s DataType> {
+    let (first, rest) = column.path().split_first()?;
+    let mut data_type = schema.field(first)?.data_type();
+    for name in rest {
+        let DataType::Struct(inner) = data_type else {
+            return None;
+        };
+        data_type = inner.field(name)?.data_type();
+    }
+    Some(data_type)
+}
+
+/// Whether a column of type `actual` can be compared with a value of type `expected`. Numeric
+/// types are compatible with each other, since engines commonly compare e.g. an `int` column with
+/// a `long` literal, and widen one of them.
+fn is_compatible(actual: &DataType, expected: &DataType) -> bool {
+    fn is_numeric(data_type: &DataType) -> bool {
+        use PrimitiveType::*;
+        matches!(
+            data_type,
+            DataType::Primitive(Byte | Short | Integer | Long | Float | Double | Decimal(_))
+        )
+    }
+    actual == expected || (is_numeric(actual) && is_numeric(expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expressions::{column_expr, column_name, Expression as Expr, Predicate as Pred};
+    use crate::schema::StructField;
+
+    use super::*;
+
+    fn schema() -> StructType {
+        StructType::new_unchecked(vec![
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("name", DataType::STRING),
+            StructField::nullable(
+                "nested",
+                StructType::new_unchecked(vec![StructField::nullable("date", DataType::DATE)]),
+            ),
+        ])
+    }
+
+    fn bind(predicate: Pred) -> Vec<PredicateBindingError> {
+        match bind_predicate(&predicate, &schema()) {
+            Ok(()) => vec![],
+            Err(Error::PredicateBinding(errors)) => errors,
+            Err(err) => panic!("Unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn test_bind_valid_predicates() {
+        let predicates = [
+            column_expr!("id").lt(Expr::literal(10i64)),
+            // numeric types widen
+            column_expr!("id").lt(Expr::literal(10)),
+            Pred::or(
+                column_expr!("name").eq(Expr::literal("a")),
+                column_expr!("nested.date").is_null(),
+            ),
+            column_expr!("id").eq(column_expr!("name")),
+            Pred::not(Expr::literal(1.5).gt(column_expr!("id"))),
+        ];
+        for predicate in predicates {
+            assert_eq!(bind(predicate.clone()), vec![], "{predicate:?}");
+        }
+    }
+
+    #[test]
+    fn test_bind_invalid_predicates() {
+        let predicate = Pred::and_from([
+            column_expr!("name").gt(Expr::literal(1)),
+            column_expr!("missing").eq(Expr::literal("x")),
+            column_expr!("nested.missing").is_null(),
+            Expr::literal(false).eq(column_expr!("nested.date")),
+            column_expr!("id").is_not_null(),
+        ]);
+        let errors = bind(predicate);
+        assert_eq!(
+            errors,
+            [
+                PredicateBindingError {
+                    column: column_name!("missing"),
+                    expected_type: Some(DataType::STRING),
+                    actual_type: None,
+                },
+                PredicateBindingError {
+                    column: column_name!("name"),
+                    expected_type: Some(DataType::INTEGER),
+                    actual_type: Some(DataType::STRING),
+                },
+                PredicateBindingError {
+                    column: column_name!("nested.date"),
+                    expected_type: Some(DataType::BOOLEAN),
+                    actual_type: Some(DataType::DATE),
+                },
+                PredicateBindingError {
+                    column: column_name!("nested.missing"),
+                    expected_type: None,
+                    actual_type: None,
+                },
+            ]
+        );
+        let message = Error::PredicateBinding(errors).to_string();
+        assert!(
+            message.contains("name (expected integer, found string)"),
+            "{message}"
+        );
+        assert!(
+            message.contains("nested.missing (unknown column)"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_bound_predicate() {
+        let schema = StructType::new_unchecked(vec![
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("small", DataType::SHORT),
+            StructField::nullable("ratio", DataType::DOUBLE),
+            StructField::nullable(
+                "nested",
+                StructType::new_unchecked(vec![StructField::nullable("date", DataType::DATE)]),
+            ),
+        ]);
+        let predicate = Pred::and_from([
+            column_expr!("id").lt(Expr::literal(10)),
+            Expr::literal(7i64).le(column_expr!("small")),
+            // out of range for the column's type, so the literal keeps its type
+            column_expr!("small").gt(Expr::literal(100_000)),
+            column_expr!("ratio").ne(Expr::literal(1.5f32)),
+            column_expr!("nested.date").is_null(),
+        ]);
+        let bound = BoundPredicate::try_bind(&predicate, &schema).unwrap();
+        let expected = Pred::and_from([
+            column_expr!("id").lt(Expr::literal(10i64)),
+            Expr::literal(7i16).le(column_expr!("small")),
+            column_expr!("small").gt(Expr::literal(100_000)),
+            column_expr!("ratio").ne(Expr::literal(1.5f64)),
+            column_expr!("nested.date").is_null(),
+        ]);
+        assert_eq!(bound.predicate().as_ref(), &expected);
+
+        // binding errors surface when binding
+        let predicate = column_expr!("missing").eq(Expr::literal(1));
+        assert!(matches!(
+            BoundPredicate::try_bind(&predicate, &schema),
+            Err(Error::PredicateBinding(_))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_timestamp_literals() {
+        let schema = StructType::new_unchecked(vec![
+            StructField::nullable("ts", DataType::TIMESTAMP),
+            StructField::nullable("ts_ntz", DataType::TIMESTAMP_NTZ),
+        ]);
+        // 2024-01-01 10:00:00+02:00, i.e. 2024-01-01 08:00:00 UTC
+        let micros = 1_704_096_000_000_000;
+        let local_micros = 1_704_103_200_000_000;
+        let zoned = Scalar::TimestampWithOffset(micros, 7200);
+        let zoned_list = |contains_null| {
+            let array_type = ArrayType::new(DataType::TIMESTAMP, true);
+            let elements = [zoned.clone()]
+                .into_iter()
+                .chain(contains_null.then_some(Scalar::Null(DataType::TIMESTAMP)));
+            Scalar::Array(ArrayData::try_new(array_type, elements).unwrap())
+        };
+        let ntz_list = {
+            let array_type = ArrayType::new(DataType::TIMESTAMP_NTZ, true);
+            let elements = [
+                Scalar::TimestampNtz(local_micros),
+                Scalar::Null(DataType::TIMESTAMP_NTZ),
+            ];
+            Scalar::Array(ArrayData::try_new(array_type, elements).unwrap())
+        };
+        let cases = [
+            (
+                column_expr!("ts").lt(Expr::literal(zoned.clone())),
+                column_expr!("ts").lt(Expr::literal(Scalar::Timestamp(micros))),
+            ),
+            (
+                Expr::literal(zoned.clone()).eq(column_expr!("ts_ntz")),
+                Expr::literal(Scalar::TimestampNtz(local_micros)).eq(column_expr!("ts_ntz")),
+            ),
+            (
+                Pred::not(column_expr!("ts_ntz").gt(Expr::literal(zoned.clone()))),
+                Pred::not(
+                    column_expr!("ts_ntz").gt(Expr::literal(Scalar::TimestampNtz(local_micros))),
+                ),
+            ),
+            (
+                Pred::binary(
+                    BinaryPredicateOp::In,
+                    column_expr!("ts_ntz"),
+                    Expr::literal(zoned_list(true)),
+                ),
+                Pred::binary(
+                    BinaryPredicateOp::In,
+                    column_expr!("ts_ntz"),
+                    Expr::literal(ntz_list),
+                ),
+            ),
+            (
+                Pred::binary(
+                    BinaryPredicateOp::In,
+                    column_expr!("ts"),
+                    Expr::literal(zoned_list(false)),
+                ),
+                Pred::binary(
+                    BinaryPredicateOp::In,
+                    column_expr!("ts"),
+                    Expr::literal(Scalar::Array(
+                        ArrayData::try_new(
+                            ArrayType::new(DataType::TIMESTAMP, true),
+                            [Scalar::Timestamp(micros)],
+                        )
+                        .unwrap(),
+                    )),
+                ),
+            ),
+        ];
+        for (predicate, expected) in cases {
+            let normalized = normalize_timestamp_literals(&predicate, &schema);
+            assert!(matches!(normalized, Cow::Owned(_)), "{predicate:?}");
+            assert_eq!(normalized.as_ref(), &expected);
+            bind_predicate(&normalized, &schema).unwrap();
+        }
+
+        let predicate = column_expr!("ts_ntz").eq(Expr::literal(Scalar::TimestampNtz(0)));
+        let normalized = normalize_timestamp_literals(&predicate, &schema);
+        assert!(matches!(normalized, Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,137 @@
+//! Structured reports of the work done by a scan, e.g. for surfacing in engine query profiles.
+//!
+//! Every [`Scan`] collects [`ScanMetrics`] while its iterators are consumed, and exposes them as a
+//! [`ScanReport`] via [`Scan::report`].
+//!
+//! [`Scan`]: crate::scan::Scan
+//! [`Scan::report`]: crate::scan::Scan::report
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A report of the work done by a scan, accumulated over all calls to [`Scan::scan_metadata`] and
+/// [`Scan::execute`] whose iterators were consumed so far. Retrieve it once the scan completed to
+/// get the totals.
+///
+/// [`Scan::scan_metadata`]: crate::scan::Scan::scan_metadata
+/// [`Scan::execute`]: crate::scan::Scan::execute
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanReport {
+    /// Wall time spent replaying the log: reading commits and checkpoints, reconciling their
+    /// actions and skipping files.
+    pub log_replay_duration: Duration,
+    /// Wall time spent reading (and transforming) data files in [`Scan::execute`].
+    ///
+    /// [`Scan::execute`]: crate::scan::Scan::execute
+    pub data_read_duration: Duration,
+    /// The number of log files (commits, compacted commits and checkpoint parts) listed for the
+    /// snapshot that is scanned.
+    pub log_files_listed: u64,
+    /// The number of commit files (including compacted commits) replayed.
+    pub commits_replayed: u64,
+    /// The total size of the checkpoint parts read, in bytes. Sidecar files are not included.
+    pub checkpoint_bytes_read: u64,
+    /// The number of live files that were candidates for data skipping, i.e. were not pruned by
+    /// their partition values.
+    pub files_considered: u64,
+    /// The number of candidate files skipped based on their stats.
+    pub files_skipped: u64,
+    /// The number of files selected to be scanned.
+    pub files_selected: u64,
+    /// The number of data files read by [`Scan::execute`].
+    ///
+    /// [`Scan::execute`]: crate::scan::Scan::execute
+    pub data_files_read: u64,
+}
+
+impl ScanReport {
+    /// The fraction of candidate files that were skipped based on their stats, or `None` if no
+    /// files were candidates for data skipping.
+    pub fn skipping_efficiency(&self) -> Option<f64> {
+        (self.files_considered > 0)
+            .then(|| self.files_skipped as f64 / self.files_considered as f64)
+    }
+}
+
+/// The thread-safe counters a scan accumulates its [`ScanReport`] in.
+#[derive(Debug, Default)]
+pub(crate) struct ScanMetrics {
+    log_replay_nanos: AtomicU64,
+    data_read_nanos: AtomicU64,
+    pub(crate) commits_replayed: AtomicU64,
+    pub(crate) checkpoint_bytes_read: AtomicU64,
+    pub(crate) files_considered: AtomicU64,
+    pub(crate) files_skipped: AtomicU64,
+    pub(crate) files_selected: AtomicU64,
+    pub(crate) data_files_read: AtomicU64,
+}
+
+impl ScanMetrics {
+    pub(crate) fn add(counter: &AtomicU64, value: impl TryInto<u64>) {
+        counter.fetch_add(value.try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_log_replay_time(&self, elapsed: Duration) {
+        Self::add(&self.log_replay_nanos, elapsed.as_nanos());
+    }
+
+    pub(crate) fn add_data_read_time(&self, elapsed: Duration) {
+        Self::add(&self.data_read_nanos, elapsed.as_nanos());
+    }
+
+    pub(crate) fn report(&self, log_files_listed: u64) -> ScanReport {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        ScanReport {
+            log_replay_duration: Duration::from_nanos(load(&self.log_replay_nanos)),
+            data_read_duration: Duration::from_nanos(load(&self.data_read_nanos)),
+            log_files_listed,
+            commits_replayed: load(&self.commits_replayed),
+            checkpoint_bytes_read: load(&self.checkpoint_bytes_read),
+            files_considered: load(&self.files_considered),
+            files_skipped: load(&self.files_skipped),
+            files_selected: load(&self.files_selected),
+            data_files_read: load(&self.data_files_read),
+        }
+    }
+}
+
+/// An iterator that reports the wall time spent advancing the inner iterator.
+pub(crate) struct Timed<I, F> {
+    inner: I,
+    record: F,
+}
+
+impl<I, F: Fn(Duration)> Timed<I, F> {
+    pub(crate) fn new(inner: I, record: F) -> Self {
+        Self { inner, record }
+    }
+}
+
+impl<I: Iterator, F: Fn(Duration)> Iterator for Timed<I, F> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = Instant::now();
+        let item = self.inner.next();
+        (self.record)(start.elapsed());
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skipping_efficiency() {
+        let report = ScanReport::default();
+        assert_eq!(report.skipping_efficiency(), None);
+
+        let report = ScanReport {
+            files_considered: 4,
+            files_skipped: 3,
+            ..Default::default()
+        };
+        assert_eq!(report.skipping_efficiency(), Some(0.75));
+    }
+}
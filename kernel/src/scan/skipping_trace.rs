@@ -0,0 +1,106 @@
+//! Opt-in tracing of data skipping decisions, to help debug why a predicate does (or does not)
+//! prune the files and row groups one would expect.
+//!
+//! A [`SkippingTrace`] can be attached to a scan (see [`ScanBuilder::with_skipping_trace`]) to
+//! record the outcome of stats-based file skipping during log replay, and to the default engine's
+//! parquet handler to record the outcome of row group skipping. Each [`SkippingTraceEntry`]
+//! reports the result of every top-level conjunct ("clause") of the predicate, so a pruned file or
+//! row group can be attributed to the clause(s) that pruned it, and a retained one shows which
+//! clauses could not be decided from its stats.
+//!
+//! NOTE: Clauses are reported in their physical form, i.e. after column mapping was applied.
+//!
+//! [`ScanBuilder::with_skipping_trace`]: crate::scan::ScanBuilder::with_skipping_trace
+
+use std::sync::{Mutex, PoisonError};
+
+use url::Url;
+
+use crate::expressions::Predicate;
+
+/// The file or row group a [`SkippingTraceEntry`] refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkippingTarget {
+    /// A data file, identified by its path as recorded in the Delta log (usually relative to the
+    /// table root).
+    File(String),
+    /// A row group of a parquet file, identified by the file's location and the row group's
+    /// ordinal within the file.
+    RowGroup { location: Url, ordinal: usize },
+}
+
+/// The outcome of evaluating one predicate clause against the stats of a file or row group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClauseResult {
+    /// The clause, i.e. one top-level conjunct of the predicate.
+    pub clause: Predicate,
+    /// `Some(false)` if the stats prove that no row can satisfy the clause, `Some(true)` if some
+    /// rows might, and `None` if the stats could not decide (e.g. because they are missing, or the
+    /// clause is not eligible for data skipping).
+    pub result: Option<bool>,
+}
+
+/// The skipping decision for one file or row group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippingTraceEntry {
+    /// The file or row group this entry refers to.
+    pub target: SkippingTarget,
+    /// Whether the file or row group was pruned.
+    pub pruned: bool,
+    /// The outcome of each clause of the predicate.
+    pub clauses: Vec<ClauseResult>,
+}
+
+impl SkippingTraceEntry {
+    /// The clauses whose stats prove that no row of the target can satisfy them.
+    pub fn pruning_clauses(&self) -> impl Iterator<Item = &Predicate> {
+        self.clauses
+            .iter()
+            .filter(|clause| clause.result == Some(false))
+            .map(|clause| &clause.clause)
+    }
+
+    /// The clauses that could not be decided from the target's stats.
+    pub fn undecided_clauses(&self) -> impl Iterator<Item = &Predicate> {
+        self.clauses
+            .iter()
+            .filter(|clause| clause.result.is_none())
+            .map(|clause| &clause.clause)
+    }
+}
+
+/// A thread-safe collector of [`SkippingTraceEntry`]s. Share it (via `Arc`) with the scan and/or
+/// engine whose skipping decisions should be traced, then inspect its [`entries`] once the scan
+/// is done.
+///
+/// [`entries`]: Self::entries
+#[derive(Debug, Default)]
+pub struct SkippingTrace {
+    entries: Mutex<Vec<SkippingTraceEntry>>,
+}
+
+impl SkippingTrace {
+    /// Creates a new, empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of all entries recorded so far, in the order they were recorded.
+    pub fn entries(&self) -> Vec<SkippingTraceEntry> {
+        self.lock().clone()
+    }
+
+    /// Removes and returns all entries recorded so far.
+    pub fn take_entries(&self) -> Vec<SkippingTraceEntry> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    pub(crate) fn record(&self, entries: impl IntoIterator<Item = SkippingTraceEntry>) {
+        self.lock().extend(entries);
+    }
+
+    // A panic while recording can at worst lose some entries, so a poisoned lock is still usable.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<SkippingTraceEntry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -5,14 +5,13 @@ use std::sync::LazyLock;
 
 use crate::actions::deletion_vector::deletion_treemap_to_bools;
 use crate::scan::get_transform_for_row;
-use crate::schema::Schema;
 use crate::utils::require;
 use crate::ExpressionRef;
 use crate::{
     actions::{deletion_vector::DeletionVectorDescriptor, visitors::visit_deletion_vector_at},
     engine_data::{GetData, RowVisitor, TypedGetData as _},
-    schema::{ColumnName, ColumnNamesAndTypes, DataType, SchemaRef},
-    DeltaResult, Engine, EngineData, Error,
+    schema::{ColumnName, ColumnNamesAndTypes, DataType},
+    DeltaResult, Engine, Error,
 };
 use roaring::RoaringTreemap;
 use serde::Deserialize;
@@ -52,6 +51,14 @@ impl DvInfo {
         self.deletion_vector.is_some()
     }
 
+    /// The descriptor of this file's Deletion Vector, if it has one: where the DV is stored (see
+    /// [`DeletionVectorDescriptor::storage_type`]), its offset and size in that file, and the
+    /// number of rows it removes. Engines that apply DVs themselves can use it instead of reading
+    /// the DV through [`Self::get_selection_vector`] or [`Self::get_row_indexes`].
+    pub fn deletion_vector(&self) -> Option<&DeletionVectorDescriptor> {
+        self.deletion_vector.as_ref()
+    }
+
     pub(crate) fn get_treemap(
         &self,
         engine: &dyn Engine,
@@ -91,28 +98,6 @@ impl DvInfo {
     }
 }
 
-/// utility function for applying a transform expression to convert data from physical to logical
-/// format
-pub fn transform_to_logical(
-    engine: &dyn Engine,
-    physical_data: Box<dyn EngineData>,
-    physical_schema: &SchemaRef,
-    logical_schema: &Schema,
-    transform: Option<ExpressionRef>,
-) -> DeltaResult<Box<dyn EngineData>> {
-    match transform {
-        Some(transform) => engine
-            .evaluation_handler()
-            .new_expression_evaluator(
-                physical_schema.clone(),
-                transform,
-                logical_schema.clone().into(), // TODO: expensive deep clone!
-            )
-            .evaluate(physical_data.as_ref()),
-        None => Ok(physical_data),
-    }
-}
-
 pub type ScanCallback<T> = fn(
     context: &mut T,
     path: &str,
@@ -121,6 +106,7 @@ pub type ScanCallback<T> = fn(
     dv_info: DvInfo,
     transform: Option<ExpressionRef>,
     partition_values: HashMap<String, String>,
+    tags: HashMap<String, String>,
 );
 
 /// Request that the kernel call a callback on each valid file that needs to be read for the
@@ -131,10 +117,13 @@ pub type ScanCallback<T> = fn(
 ///   to each call
 /// * `path`: a `&str` which is the path to the file
 /// * `size`: an `i64` which is the size of the file
-/// * `dv_info`: a [`DvInfo`] struct, which allows getting the selection vector for this file
+/// * `dv_info`: a [`DvInfo`] struct, which allows getting the selection vector for this file, or
+///   the raw descriptor of its deletion vector (see [`DvInfo::deletion_vector`])
 /// * `transform`: An optional expression that, if present, _must_ be applied to physical data to
 ///   convert it to the correct logical format
 /// * `partition_values`: a `HashMap<String, String>` which are partition values
+/// * `tags`: a `HashMap<String, String>` of the tags of the file's add action, e.g. markers that
+///   an OPTIMIZE or ingestion job left on the file. Empty if the add action has no tags.
 ///
 /// ## Context
 /// A note on the `context`. This can be any value the engine wants. This function takes ownership
@@ -179,7 +168,7 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 10,
+            getters.len() == 12,
             Error::InternalError(format!(
                 "Wrong number of ScanFileVisitor getters: {}",
                 getters.len()
@@ -193,15 +182,15 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
             // Since path column is required, use it to detect presence of an Add action
             if let Some(path) = getters[0].get_opt(row_index, "scanFile.path")? {
                 let size = getters[1].get(row_index, "scanFile.size")?;
-                let stats: Option<String> = getters[3].get_opt(row_index, "scanFile.stats")?;
-                let stats: Option<Stats> =
-                    stats.and_then(|json| match serde_json::from_str(json.as_str()) {
-                        Ok(stats) => Some(stats),
-                        Err(e) => {
-                            warn!("Invalid stats string in Add file {json}: {}", e);
-                            None
-                        }
-                    });
+                let num_records: Option<i64> =
+                    getters[4].get_opt(row_index, "scanFile.statsParsed.numRecords")?;
+                let stats = num_records.and_then(|num_records| match u64::try_from(num_records) {
+                    Ok(num_records) => Some(Stats { num_records }),
+                    Err(_) => {
+                        warn!("Invalid numRecords in Add file {path}: {num_records}");
+                        None
+                    }
+                });
 
                 let dv_index = SCAN_ROW_SCHEMA
                     .index_of("deletionVector")
@@ -209,7 +198,9 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
                 let deletion_vector = visit_deletion_vector_at(row_index, &getters[dv_index..])?;
                 let dv_info = DvInfo { deletion_vector };
                 let partition_values =
-                    getters[9].get(row_index, "scanFile.fileConstantValues.partitionValues")?;
+                    getters[10].get(row_index, "scanFile.fileConstantValues.partitionValues")?;
+                let tags: Option<HashMap<_, _>> =
+                    getters[11].get_opt(row_index, "scanFile.fileConstantValues.tags")?;
                 (self.callback)(
                     &mut self.context,
                     path,
@@ -218,6 +209,7 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
                     dv_info,
                     get_transform_for_row(row_index, self.transforms),
                     partition_values,
+                    tags.unwrap_or_default(),
                 )
             }
         }
@@ -248,6 +240,7 @@ mod tests {
         dv_info: DvInfo,
         transform: Option<ExpressionRef>,
         part_vals: HashMap<String, String>,
+        tags: HashMap<String, String>,
     ) {
         assert_eq!(
             path,
@@ -258,9 +251,18 @@ mod tests {
         assert_eq!(stats.as_ref().unwrap().num_records, 10);
         assert_eq!(part_vals.get("date"), Some(&"2017-12-10".to_string()));
         assert_eq!(part_vals.get("non-existent"), None);
-        assert!(dv_info.deletion_vector.is_some());
-        let dv = dv_info.deletion_vector.unwrap();
+        assert_eq!(tags.len(), 4);
+        assert_eq!(
+            tags.get("OPTIMIZE_TARGET_SIZE"),
+            Some(&"268435456".to_string())
+        );
+        let dv = dv_info.deletion_vector().unwrap();
         assert_eq!(dv.unique_id(), "uvBn[lx{q8@P<9BNH/isA@1");
+        assert_eq!(dv.storage_type, "u");
+        assert_eq!(dv.path_or_inline_dv, "vBn[lx{q8@P<9BNH/isA");
+        assert_eq!(dv.offset, Some(1));
+        assert_eq!(dv.size_in_bytes, 36);
+        assert_eq!(dv.cardinality, 2);
         assert!(transform.is_none());
         assert_eq!(context.id, 2);
     }
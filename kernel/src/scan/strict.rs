@@ -0,0 +1,309 @@
+//! Opt-in strict validation of table invariants during scans, see
+//! [`ScanBuilder::with_strict_validation`].
+//!
+//! By default, the kernel reads tables on a best-effort basis, tolerating log states that violate
+//! the Delta protocol as long as they can be read unambiguously. Strict validation instead fails
+//! the scan with an [`Error::InvalidTableState`] (or [`Error::InvalidProtocol`]) as soon as such a
+//! violation is found.
+//!
+//! [`ScanBuilder::with_strict_validation`]: crate::scan::ScanBuilder::with_strict_validation
+
+use std::collections::{HashMap, HashSet};
+
+use url::Url;
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::Protocol;
+use crate::log_replay::FileActionKey;
+use crate::schema::{DataType, PrimitiveType};
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// Validates the invariants of a protocol that the kernel otherwise tolerates:
+/// - reader features are present if and only if the minimum reader version is 3
+/// - writer features are present if and only if the minimum writer version is 7
+/// - a table with reader version 3 has writer version 7, and all its reader features are also
+///   listed as writer features
+pub(crate) fn validate_protocol(protocol: &Protocol) -> DeltaResult<()> {
+    let reader_version = protocol.min_reader_version();
+    let writer_version = protocol.min_writer_version();
+    require!(
+        (reader_version == 3) == protocol.reader_features().is_some(),
+        Error::invalid_protocol(format!(
+            "Reader features must be present if and only if the minimum reader version is 3, \
+            but the minimum reader version is {reader_version}"
+        ))
+    );
+    require!(
+        (writer_version == 7) == protocol.writer_features().is_some(),
+        Error::invalid_protocol(format!(
+            "Writer features must be present if and only if the minimum writer version is 7, \
+            but the minimum writer version is {writer_version}"
+        ))
+    );
+    if let Some(reader_features) = protocol.reader_features() {
+        require!(
+            writer_version == 7,
+            Error::invalid_protocol(format!(
+                "Minimum reader version 3 requires minimum writer version 7, \
+                but the minimum writer version is {writer_version}"
+            ))
+        );
+        let writer_features: HashSet<_> = protocol
+            .writer_features()
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        for feature in reader_features {
+            require!(
+                writer_features.contains(&feature.to_string()),
+                Error::invalid_protocol(format!(
+                    "Reader feature {feature} is not listed as a writer feature"
+                ))
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The state of strict validation during the log replay of a scan.
+///
+/// NOTE: Detecting duplicate adds requires remembering every file action seen during log replay,
+/// so strict validation needs memory proportional to the number of files in the log.
+#[derive(Debug)]
+pub(crate) struct StrictValidator {
+    table_root: Url,
+    /// The types of the partition columns, by physical name
+    partition_columns: HashMap<String, PrimitiveType>,
+    /// Whether the newest action seen so far for each file that changed data was an add
+    newest_action_is_add: HashMap<FileActionKey, bool>,
+}
+
+impl StrictValidator {
+    /// Creates a validator for a scan of `snapshot`, failing if its protocol is invalid.
+    pub(crate) fn try_new(snapshot: &Snapshot) -> DeltaResult<Self> {
+        validate_protocol(snapshot.protocol())?;
+        let schema = snapshot.schema();
+        let partition_columns = snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .map(|name| {
+                let field = schema.field(name).ok_or_else(|| {
+                    Error::invalid_table_state(format!(
+                        "Partition column {name} is not in the table schema"
+                    ))
+                })?;
+                match field.data_type() {
+                    DataType::Primitive(ptype) => {
+                        Ok((field.physical_name().to_string(), ptype.clone()))
+                    }
+                    other => Err(Error::invalid_table_state(format!(
+                        "Partition column {name} has non-primitive type {other}"
+                    ))),
+                }
+            })
+            .collect::<DeltaResult<_>>()?;
+        Ok(Self {
+            table_root: snapshot.table_root().clone(),
+            partition_columns,
+            newest_action_is_add: HashMap::new(),
+        })
+    }
+
+    /// Validates a file action. Log replay visits actions newest-first, so an add that changes data
+    /// is invalid if the next newer action for the same file that changes data is also an add: the
+    /// file was added twice without being removed in between. Adds with `dataChange = false`, which
+    /// some writers use to update the stats of a file, don't change the files of the table and are
+    /// not checked.
+    pub(crate) fn validate_file_action(
+        &mut self,
+        key: &FileActionKey,
+        is_add: bool,
+        data_change: bool,
+    ) -> DeltaResult<()> {
+        if is_add && !data_change {
+            return Ok(());
+        }
+        let lookup_key = FileActionKey::new(key.path.clone(), key.dv_unique_id.clone());
+        let newer_is_add = self.newest_action_is_add.insert(lookup_key, is_add);
+        require!(
+            !(is_add && newer_is_add == Some(true)),
+            Error::invalid_table_state(format!(
+                "File {} (deletion vector {:?}) is added again without being removed first",
+                key.path, key.dv_unique_id
+            ))
+        );
+        Ok(())
+    }
+
+    /// Validates that the partition values of an add have exactly the table's partition columns
+    /// as keys, and that each value parses as the type of its column.
+    pub(crate) fn validate_partition_values(
+        &self,
+        path: &str,
+        partition_values: &HashMap<String, String>,
+    ) -> DeltaResult<()> {
+        for (column, ptype) in &self.partition_columns {
+            let value = partition_values.get(column).ok_or_else(|| {
+                Error::invalid_table_state(format!(
+                    "File {path} lacks a value for partition column {column}"
+                ))
+            })?;
+            ptype.parse_scalar(value).map_err(|_| {
+                Error::invalid_table_state(format!(
+                    "File {path} has invalid value {value:?} for partition column {column} \
+                    of type {ptype}"
+                ))
+            })?;
+        }
+        if let Some(column) = partition_values
+            .keys()
+            .find(|column| !self.partition_columns.contains_key(*column))
+        {
+            return Err(Error::invalid_table_state(format!(
+                "File {path} has a value for {column}, which is not a partition column"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validates that the deletion vector descriptor of a file action can be resolved, i.e. that
+    /// its storage type is known and its path or inline data decode.
+    pub(crate) fn validate_deletion_vector(
+        &self,
+        path: &str,
+        storage_type: &str,
+        path_or_inline_dv: &str,
+        offset: Option<i32>,
+    ) -> DeltaResult<()> {
+        let invalid = |reason: String| {
+            Error::invalid_table_state(format!(
+                "File {path} has an invalid deletion vector: {reason}"
+            ))
+        };
+        require!(
+            offset.is_none_or(|offset| offset >= 0),
+            invalid(format!("negative offset {offset:?}"))
+        );
+        if storage_type == "i" {
+            require!(
+                offset.is_none(),
+                invalid("inline deletion vectors must not have an offset".to_string())
+            );
+            z85::decode(path_or_inline_dv)
+                .map_err(|_| invalid("failed to decode the inline data".to_string()))?;
+            return Ok(());
+        }
+        let descriptor = DeletionVectorDescriptor {
+            storage_type: storage_type.to_string(),
+            path_or_inline_dv: path_or_inline_dv.to_string(),
+            offset,
+            size_in_bytes: 0,
+            cardinality: 0,
+        };
+        descriptor
+            .absolute_path(&self.table_root)
+            .map_err(|e| invalid(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn validator() -> StrictValidator {
+        StrictValidator {
+            table_root: Url::parse("memory:///table/").unwrap(),
+            partition_columns: HashMap::from([("date".to_string(), PrimitiveType::Date)]),
+            newest_action_is_add: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_protocol() {
+        let features = Some(["deletionVectors"]);
+        let valid = Protocol::try_new(3, 7, features, features).unwrap();
+        validate_protocol(&valid).unwrap();
+        validate_protocol(&Protocol::try_new(1, 2, None::<[&str; 0]>, None::<[&str; 0]>).unwrap())
+            .unwrap();
+
+        let protocol = Protocol::try_new(3, 7, features, Some(["appendOnly"])).unwrap();
+        let result = validate_protocol(&protocol);
+        assert_result_error_with_message(result, "is not listed as a writer feature");
+
+        let protocol = Protocol::try_new(1, 2, None::<[&str; 0]>, features).unwrap();
+        let result = validate_protocol(&protocol);
+        assert_result_error_with_message(result, "Writer features must be present if and only if");
+    }
+
+    #[test]
+    fn test_validate_file_action() {
+        let mut validator = validator();
+        let key = FileActionKey::new("a.parquet", None);
+        // newest first: the file was added, removed and added again
+        validator.validate_file_action(&key, true, true).unwrap();
+        validator.validate_file_action(&key, false, true).unwrap();
+        validator.validate_file_action(&key, true, true).unwrap();
+        // ... and its stats were updated by re-adding it without changing data
+        validator.validate_file_action(&key, true, false).unwrap();
+        // ... but an even older add has no remove in between
+        let result = validator.validate_file_action(&key, true, true);
+        assert_result_error_with_message(result, "is added again without being removed first");
+    }
+
+    #[test]
+    fn test_validate_partition_values() {
+        let validator = validator();
+        let values = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        validator
+            .validate_partition_values("a", &values(&[("date", "2024-01-01")]))
+            .unwrap();
+        // an empty value is null
+        validator
+            .validate_partition_values("a", &values(&[("date", "")]))
+            .unwrap();
+
+        let result = validator.validate_partition_values("a", &values(&[]));
+        assert_result_error_with_message(result, "lacks a value for partition column date");
+        let result = validator.validate_partition_values("a", &values(&[("date", "yesterday")]));
+        assert_result_error_with_message(result, "invalid value \"yesterday\"");
+        let result = validator
+            .validate_partition_values("a", &values(&[("date", "2024-01-01"), ("x", "1")]));
+        assert_result_error_with_message(result, "which is not a partition column");
+    }
+
+    #[test]
+    fn test_validate_deletion_vector() {
+        let validator = validator();
+        validator
+            .validate_deletion_vector("a", "u", "ab^-aqEH.-t@S}K{vb[*k^", Some(4))
+            .unwrap();
+        validator
+            .validate_deletion_vector(
+                "a",
+                "i",
+                "^Bg9^0rr910000000000iXQKl0rr91000f55c8Xg0@@D72lkbi5=-{L",
+                None,
+            )
+            .unwrap();
+
+        let result = validator.validate_deletion_vector("a", "x", "abc", None);
+        assert_result_error_with_message(result, "Unknown storage format");
+        let result = validator.validate_deletion_vector("a", "u", "short", None);
+        assert_result_error_with_message(result, "must be >= 20");
+        let result = validator.validate_deletion_vector("a", "i", "abc", Some(1));
+        assert_result_error_with_message(result, "must not have an offset");
+        let result = validator.validate_deletion_vector("a", "p", "s3://bucket/dv.bin", Some(-1));
+        assert_result_error_with_message(result, "negative offset");
+    }
+}
@@ -0,0 +1,402 @@
+//! Provides a human-readable, SQL DDL-style representation of schemas, e.g.
+//! `struct<id: long NOT NULL, tags: array<string>>`, along with a parser for it. This is also how
+//! [`DataType`] and [`StructType`] implement [`Display`].
+//!
+//! The format follows these rules:
+//! - Primitive types use the Delta type names (`integer`, `long`, `decimal(10,2)`, ...). The parser
+//!   additionally accepts common SQL aliases such as `int`, `bigint`, `smallint`, `tinyint` and
+//!   `bool`. All type names and keywords are case-insensitive.
+//! - Struct fields are written as `name: type`, followed by `NOT NULL` if the field is not
+//!   nullable. Names that are not plain identifiers are quoted with backticks, with embedded
+//!   backticks doubled.
+//! - `array<type NOT NULL>` and `map<key, value NOT NULL>` denote arrays and maps that cannot
+//!   contain null elements or values.
+//! - Field metadata is not represented, and variants are written as `variant`.
+//!
+//! # Examples
+//!  ```rust
+//!  # use delta_kernel::schema::{ArrayType, DataType, StructField, StructType};
+//!  # use delta_kernel::DeltaResult;
+//!  # fn main() -> DeltaResult<()> {
+//!  let schema = StructType::try_new([
+//!     StructField::not_null("id", DataType::LONG),
+//!     StructField::nullable("tags", ArrayType::new(DataType::STRING, true)),
+//!  ])?;
+//!  assert_eq!(schema.to_ddl(), "struct<id: long NOT NULL, tags: array<string>>");
+//!  assert_eq!(StructType::from_ddl(&schema.to_ddl())?, schema);
+//!  # Ok(())
+//!  # }
+//!  ```
+use std::fmt::{Display, Formatter};
+
+use super::{ArrayType, DataType, MapType, PrimitiveType, SchemaLimits, StructField, StructType};
+use crate::{DeltaResult, Error};
+
+impl DataType {
+    /// Formats this type as a DDL string. See the [module documentation](self) for the format.
+    /// This is the same as the type's [`Display`] output.
+    pub fn to_ddl(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses a type from a DDL string. See the [module documentation](self) for the format.
+    pub fn from_ddl(ddl: &str) -> DeltaResult<Self> {
+        let mut parser = DdlParser::new(ddl);
+        let data_type = parser.parse_type()?;
+        parser.expect_end()?;
+        Ok(data_type)
+    }
+}
+
+impl StructType {
+    /// Formats this schema as a DDL `struct<...>` string. See the [module documentation](self) for
+    /// the format. This is the same as the schema's [`Display`] output.
+    pub fn to_ddl(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses a schema from a DDL `struct<...>` string. See the [module documentation](self) for
+    /// the format.
+    pub fn from_ddl(ddl: &str) -> DeltaResult<Self> {
+        match DataType::from_ddl(ddl)? {
+            DataType::Struct(schema) => Ok(*schema),
+            other => Err(Error::schema(format!(
+                "Expected a struct type, got {other}"
+            ))),
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Primitive(p) => write!(f, "{p}"),
+            DataType::Array(a) => {
+                write!(f, "array<{}", a.element_type())?;
+                write_not_null(f, a.contains_null())?;
+                write!(f, ">")
+            }
+            DataType::Map(m) => {
+                write!(f, "map<{}, {}", m.key_type(), m.value_type())?;
+                write_not_null(f, m.value_contains_null())?;
+                write!(f, ">")
+            }
+            DataType::Struct(s) => write!(f, "{s}"),
+            DataType::Variant(_) => write!(f, "variant"),
+        }
+    }
+}
+
+impl Display for StructType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "struct<")?;
+        for (i, field) in self.fields().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write_name(f, field.name())?;
+            write!(f, ": {}", field.data_type())?;
+            write_not_null(f, field.is_nullable())?;
+        }
+        write!(f, ">")
+    }
+}
+
+fn write_not_null(f: &mut Formatter<'_>, nullable: bool) -> std::fmt::Result {
+    if nullable {
+        Ok(())
+    } else {
+        write!(f, " NOT NULL")
+    }
+}
+
+fn write_name(f: &mut Formatter<'_>, name: &str) -> std::fmt::Result {
+    let is_identifier = name.chars().next().is_some_and(is_identifier_start)
+        && name.chars().all(is_identifier_char);
+    if is_identifier {
+        write!(f, "{name}")
+    } else {
+        write!(f, "`{}`", name.replace('`', "``"))
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// A recursive descent parser over a DDL string. Nesting is bounded by
+/// [`SchemaLimits::DEFAULT_MAX_NESTING_DEPTH`], so hostile input can't overflow the stack.
+struct DdlParser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> DdlParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, msg: impl Display) -> Error {
+        Error::schema(format!(
+            "Invalid DDL type string at position {}: {msg}",
+            self.pos
+        ))
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `c` if it is the next non-whitespace character.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> DeltaResult<()> {
+        match self.eat(c) {
+            true => Ok(()),
+            false => Err(self.error(format!("expected '{c}'"))),
+        }
+    }
+
+    fn expect_end(&mut self) -> DeltaResult<()> {
+        self.skip_whitespace();
+        match self.rest().is_empty() {
+            true => Ok(()),
+            false => Err(self.error("unexpected trailing input")),
+        }
+    }
+
+    /// Consumes the next word (a run of identifier characters), which may be empty.
+    fn word(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !is_identifier_char(c))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Consumes `keyword` (case-insensitively) if it is the next word.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let start = self.pos;
+        if self.word().eq_ignore_ascii_case(keyword) {
+            true
+        } else {
+            self.pos = start;
+            false
+        }
+    }
+
+    fn parse_number<T: std::str::FromStr>(&mut self) -> DeltaResult<T> {
+        let word = self.word();
+        word.parse()
+            .map_err(|_| self.error(format!("expected a number, got '{word}'")))
+    }
+
+    fn parse_name(&mut self) -> DeltaResult<String> {
+        if !self.eat('`') {
+            return match self.word() {
+                "" => Err(self.error("expected a field name")),
+                name => Ok(name.to_string()),
+            };
+        }
+        let mut name = String::new();
+        loop {
+            let rest = self.rest();
+            let end = rest
+                .find('`')
+                .ok_or_else(|| self.error("unterminated quoted name"))?;
+            name.push_str(&rest[..end]);
+            self.pos += end + 1;
+            if !self.rest().starts_with('`') {
+                return Ok(name);
+            }
+            // a doubled backtick is an escaped backtick
+            name.push('`');
+            self.pos += 1;
+        }
+    }
+
+    /// Parses an optional trailing `NOT NULL`, returning whether the element is nullable.
+    fn parse_nullable(&mut self) -> DeltaResult<bool> {
+        if !self.eat_keyword("not") {
+            return Ok(true);
+        }
+        match self.eat_keyword("null") {
+            true => Ok(false),
+            false => Err(self.error("expected NULL after NOT")),
+        }
+    }
+
+    fn parse_type(&mut self) -> DeltaResult<DataType> {
+        self.depth += 1;
+        if self.depth > SchemaLimits::DEFAULT_MAX_NESTING_DEPTH {
+            return Err(self.error("type is nested too deeply"));
+        }
+        let data_type = self.parse_type_inner();
+        self.depth -= 1;
+        data_type
+    }
+
+    fn parse_type_inner(&mut self) -> DeltaResult<DataType> {
+        let start = self.pos;
+        let name = self.word().to_ascii_lowercase();
+        let primitive = match name.as_str() {
+            "string" => PrimitiveType::String,
+            "long" | "bigint" => PrimitiveType::Long,
+            "integer" | "int" => PrimitiveType::Integer,
+            "short" | "smallint" => PrimitiveType::Short,
+            "byte" | "tinyint" => PrimitiveType::Byte,
+            "float" | "real" => PrimitiveType::Float,
+            "double" => PrimitiveType::Double,
+            "boolean" | "bool" => PrimitiveType::Boolean,
+            "binary" => PrimitiveType::Binary,
+            "date" => PrimitiveType::Date,
+            "timestamp" => PrimitiveType::Timestamp,
+            "timestamp_ntz" => PrimitiveType::TimestampNtz,
+            "decimal" => {
+                self.expect('(')?;
+                let precision = self.parse_number()?;
+                self.expect(',')?;
+                let scale = self.parse_number()?;
+                self.expect(')')?;
+                PrimitiveType::decimal(precision, scale)?
+            }
+            "variant" => return Ok(DataType::unshredded_variant()),
+            "array" => {
+                self.expect('<')?;
+                let element_type = self.parse_type()?;
+                let contains_null = self.parse_nullable()?;
+                self.expect('>')?;
+                return Ok(ArrayType::new(element_type, contains_null).into());
+            }
+            "map" => {
+                self.expect('<')?;
+                let key_type = self.parse_type()?;
+                self.expect(',')?;
+                let value_type = self.parse_type()?;
+                let value_contains_null = self.parse_nullable()?;
+                self.expect('>')?;
+                return Ok(MapType::new(key_type, value_type, value_contains_null).into());
+            }
+            "struct" => {
+                self.expect('<')?;
+                let mut fields = vec![];
+                if !self.eat('>') {
+                    loop {
+                        let name = self.parse_name()?;
+                        // Like Spark DDL, the colon between name and type is optional
+                        self.eat(':');
+                        let data_type = self.parse_type()?;
+                        let nullable = self.parse_nullable()?;
+                        fields.push(StructField::new(name, data_type, nullable));
+                        if !self.eat(',') {
+                            break;
+                        }
+                    }
+                    self.expect('>')?;
+                }
+                return Ok(StructType::try_new(fields)?.into());
+            }
+            _ => {
+                self.pos = start;
+                return Err(self.error(format!("unknown type '{name}'")));
+            }
+        };
+        Ok(primitive.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ddl_round_trip() {
+        let schema = StructType::new_unchecked([
+            StructField::not_null("id", DataType::LONG),
+            StructField::nullable("price", DataType::decimal(10, 2).unwrap()),
+            StructField::nullable(
+                "tags",
+                MapType::new(
+                    DataType::STRING,
+                    ArrayType::new(DataType::INTEGER, false),
+                    true,
+                ),
+            ),
+            StructField::nullable(
+                "odd name`",
+                StructType::new_unchecked([StructField::not_null("ts", DataType::TIMESTAMP_NTZ)]),
+            ),
+            StructField::nullable("v", DataType::unshredded_variant()),
+        ]);
+        let ddl = schema.to_ddl();
+        assert_eq!(
+            ddl,
+            "struct<id: long NOT NULL, price: decimal(10,2), \
+             tags: map<string, array<integer NOT NULL>>, \
+             `odd name```: struct<ts: timestamp_ntz NOT NULL>, v: variant>"
+        );
+        assert_eq!(schema.to_string(), ddl);
+        assert_eq!(StructType::from_ddl(&ddl).unwrap(), schema);
+    }
+
+    #[test]
+    fn parse_aliases_and_spacing() {
+        let parsed =
+            DataType::from_ddl(" STRUCT < a INT not null , b:Array<BIGINT> , c: struct<> >")
+                .unwrap();
+        let expected: DataType = StructType::new_unchecked([
+            StructField::not_null("a", DataType::INTEGER),
+            StructField::nullable("b", ArrayType::new(DataType::LONG, true)),
+            StructField::nullable("c", StructType::new_unchecked([])),
+        ])
+        .into();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for ddl in [
+            "",
+            "int32",
+            "array<int",
+            "map<string>",
+            "decimal(10)",
+            "decimal(50,2)",
+            "struct<a: int, a: int>",
+            "struct<`a: int>",
+            "int NOT",
+            "int extra",
+        ] {
+            assert!(DataType::from_ddl(ddl).is_err(), "{ddl} should not parse");
+        }
+        assert!(StructType::from_ddl("int").is_err());
+
+        let deep = format!("{}int{}", "array<".repeat(100), ">".repeat(100));
+        let err = DataType::from_ddl(&deep).unwrap_err();
+        assert!(err.to_string().contains("nested too deeply"));
+    }
+}
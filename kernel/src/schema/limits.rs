@@ -0,0 +1,274 @@
+//! Provides [`SchemaLimits`], which bounds the size and nesting of schemas the kernel accepts. This
+//! protects against hostile or corrupted `schemaString`s, whose processing could otherwise
+//! overflow the stack or exhaust memory.
+use serde::{Deserialize, Serialize};
+
+use super::{DataType, StructType};
+use crate::{DeltaResult, Error};
+
+/// Limits on the shape of a schema, enforced when parsing a table's schema during log replay.
+///
+/// See [`SnapshotBuilder::with_schema_limits`] to configure the limits of a snapshot.
+///
+/// [`SnapshotBuilder::with_schema_limits`]: crate::snapshot::SnapshotBuilder::with_schema_limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaLimits {
+    /// The maximum number of nested struct, array and map types. A schema of primitive top-level
+    /// columns has depth 1, and each enclosing complex type adds one level.
+    pub max_nesting_depth: usize,
+    /// The maximum number of struct fields, counting the fields of nested structs.
+    pub max_field_count: usize,
+}
+
+impl SchemaLimits {
+    /// Default maximum nesting depth. Deeper schemas are vanishingly rare in practice.
+    pub const DEFAULT_MAX_NESTING_DEPTH: usize = 32;
+    /// Default maximum field count, far above the width of any reasonable table.
+    pub const DEFAULT_MAX_FIELD_COUNT: usize = 100_000;
+
+    /// Returns an error if `schema` exceeds any of these limits.
+    pub fn check(&self, schema: &StructType) -> DeltaResult<()> {
+        // Walk the schema with an explicit stack, so checking a deep schema can't itself overflow.
+        let mut field_count = schema.num_fields();
+        let top_level: Vec<&DataType> = schema.fields().map(|f| f.data_type()).collect();
+        let mut stack = vec![(top_level, 1)];
+        while let Some((data_types, depth)) = stack.pop() {
+            if depth > self.max_nesting_depth {
+                return Err(self.nesting_depth_error());
+            }
+            if field_count > self.max_field_count {
+                return Err(self.field_count_error());
+            }
+            for data_type in data_types {
+                let children: Vec<&DataType> = match data_type {
+                    DataType::Struct(inner) => {
+                        field_count += inner.num_fields();
+                        inner.fields().map(|f| f.data_type()).collect()
+                    }
+                    DataType::Array(inner) => vec![inner.element_type()],
+                    DataType::Map(inner) => vec![inner.key_type(), inner.value_type()],
+                    DataType::Primitive(_) | DataType::Variant(_) => continue,
+                };
+                stack.push((children, depth + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the schema serialized as `json` exceeds any of these limits. This only
+    /// scans the JSON text without deserializing it, so hostile schemas are rejected before they
+    /// are allocated. Nothing inside field metadata counts towards the limits.
+    fn check_json(&self, json: &str) -> DeltaResult<()> {
+        let bytes = json.as_bytes();
+        let mut stack = Vec::new();
+        let mut depth = 1;
+        let mut field_count = 0;
+        let mut last_string = None;
+        let mut key = None;
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => {
+                    let start = i + 1;
+                    i = start;
+                    while i < bytes.len() && bytes[i] != b'"' {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                    last_string = bytes.get(start..i);
+                }
+                b':' => key = last_string.take(),
+                b',' => key = None,
+                open @ (b'{' | b'[') => {
+                    let parent = stack.last().copied();
+                    let container = match (parent, key.take(), open) {
+                        (Some(JsonContainer::Metadata), _, _) | (_, Some(b"metadata"), _) => {
+                            JsonContainer::Metadata
+                        }
+                        (_, Some(b"type" | b"elementType" | b"keyType" | b"valueType"), b'{') => {
+                            JsonContainer::Type
+                        }
+                        (_, Some(b"fields"), b'[') => JsonContainer::Fields,
+                        _ => JsonContainer::Other,
+                    };
+                    if parent == Some(JsonContainer::Fields) && open == b'{' {
+                        field_count += 1;
+                        if field_count > self.max_field_count {
+                            return Err(self.field_count_error());
+                        }
+                    }
+                    if container == JsonContainer::Type {
+                        depth += 1;
+                        if depth > self.max_nesting_depth {
+                            return Err(self.nesting_depth_error());
+                        }
+                    }
+                    stack.push(container);
+                }
+                b'}' | b']' => {
+                    if stack.pop() == Some(JsonContainer::Type) {
+                        depth -= 1;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn nesting_depth_error(&self) -> Error {
+        Error::schema(format!(
+            "Schema nesting depth exceeds the limit of {}",
+            self.max_nesting_depth
+        ))
+    }
+
+    fn field_count_error(&self) -> Error {
+        Error::schema(format!(
+            "Schema field count exceeds the limit of {}",
+            self.max_field_count
+        ))
+    }
+}
+
+/// The kinds of JSON containers in a serialized schema that [`SchemaLimits::check_json`] tells
+/// apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsonContainer {
+    /// A nested struct, array or map type.
+    Type,
+    /// The fields of a struct.
+    Fields,
+    /// Field metadata, or anything inside it.
+    Metadata,
+    Other,
+}
+
+impl Default for SchemaLimits {
+    fn default() -> Self {
+        Self {
+            max_nesting_depth: Self::DEFAULT_MAX_NESTING_DEPTH,
+            max_field_count: Self::DEFAULT_MAX_FIELD_COUNT,
+        }
+    }
+}
+
+impl StructType {
+    /// Like [`StructType::from_json`], but also returns an error if the schema exceeds `limits`.
+    /// The limits are checked against the JSON text before it is parsed, so schemas that exceed
+    /// them are never deserialized.
+    pub fn from_json_with_limits(json: &str, limits: &SchemaLimits) -> DeltaResult<Self> {
+        limits.check_json(json)?;
+        let schema = Self::from_json(json)?;
+        // the JSON scan can't see through escaped keys, so check the parsed schema as well
+        limits.check(&schema)?;
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ArrayType, MapType, StructField};
+
+    fn nested_schema(depth: usize) -> StructType {
+        let mut data_type = DataType::INTEGER;
+        for i in 1..depth {
+            data_type = match i % 3 {
+                0 => ArrayType::new(data_type, true).into(),
+                1 => MapType::new(DataType::STRING, data_type, true).into(),
+                _ => StructType::new_unchecked([StructField::nullable("s", data_type)]).into(),
+            };
+        }
+        StructType::new_unchecked([StructField::nullable("c", data_type)])
+    }
+
+    #[test]
+    fn nesting_depth_limit() {
+        let limits = SchemaLimits {
+            max_nesting_depth: 5,
+            ..Default::default()
+        };
+        limits.check(&nested_schema(5)).unwrap();
+        let err = limits.check(&nested_schema(6)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("nesting depth exceeds the limit of 5"));
+    }
+
+    #[test]
+    fn field_count_limit() {
+        let limits = SchemaLimits {
+            max_field_count: 3,
+            ..Default::default()
+        };
+        let inner = StructType::new_unchecked([
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::INTEGER),
+        ]);
+        let schema = StructType::new_unchecked([StructField::nullable("s", inner.clone())]);
+        limits.check(&schema).unwrap();
+
+        let schema = StructType::new_unchecked([
+            StructField::nullable("s", inner),
+            StructField::nullable("c", DataType::INTEGER),
+        ]);
+        let err = limits.check(&schema).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("field count exceeds the limit of 3"));
+    }
+
+    #[test]
+    fn json_limits_match_schema_limits() {
+        let limits = SchemaLimits {
+            max_nesting_depth: 5,
+            max_field_count: 4,
+        };
+        for depth in 1..10 {
+            let schema = nested_schema(depth);
+            let json = schema.to_json().unwrap();
+            assert_eq!(
+                limits.check_json(&json).is_ok(),
+                limits.check(&schema).is_ok(),
+                "depth {depth}"
+            );
+        }
+    }
+
+    #[test]
+    fn json_limits_ignore_metadata() {
+        let json = r#"{"type":"struct","fields":[{"name":"c","type":"integer","nullable":true,
+            "metadata":{"fields":[{"type":{"a":[{"b":{}}]}},{},{}]}}]}"#;
+        let limits = SchemaLimits {
+            max_nesting_depth: 1,
+            max_field_count: 1,
+        };
+        limits.check_json(json).unwrap();
+        StructType::from_json_with_limits(json, &limits).unwrap();
+    }
+
+    #[test]
+    fn from_json_with_limits() {
+        let json = nested_schema(3).to_json().unwrap();
+        let limits = SchemaLimits {
+            max_nesting_depth: 2,
+            ..Default::default()
+        };
+        assert!(StructType::from_json_with_limits(&json, &limits).is_err());
+        assert_eq!(
+            StructType::from_json_with_limits(&json, &SchemaLimits::default()).unwrap(),
+            nested_schema(3)
+        );
+
+        // limits are checked before parsing, so a truncated schema fails on them rather than on
+        // its syntax
+        let json = nested_schema(10).to_json().unwrap();
+        let truncated = &json[..json.len() / 2];
+        let err = StructType::from_json_with_limits(truncated, &limits).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("nesting depth exceeds the limit of 2"));
+    }
+}
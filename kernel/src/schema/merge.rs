@@ -0,0 +1,285 @@
+//! Provides [`StructType::merge`], which reconciles two schemas into one that can represent data
+//! written with either of them, following Delta schema evolution rules.
+//!
+//! # Examples
+//!  ```rust
+//!  # use delta_kernel::schema::{DataType, StructField, StructType};
+//!  # use delta_kernel::DeltaResult;
+//!  # fn main() -> DeltaResult<()> {
+//!  let schema = StructType::try_new([
+//!     StructField::not_null("id", DataType::INTEGER),
+//!     StructField::nullable("value", DataType::STRING),
+//!  ])?;
+//!  let other = StructType::try_new([
+//!     StructField::not_null("id", DataType::LONG),
+//!     StructField::nullable("year", DataType::INTEGER),
+//!  ])?;
+//!  let merged = schema.merge(&other)?;
+//!  let expected = StructType::try_new([
+//!     StructField::not_null("id", DataType::LONG),
+//!     StructField::nullable("value", DataType::STRING),
+//!     StructField::nullable("year", DataType::INTEGER),
+//!  ])?;
+//!  assert_eq!(merged, expected);
+//!  # Ok(())
+//!  # }
+//!  ```
+use std::collections::HashMap;
+
+use super::{ArrayType, DataType, DecimalType, MapType, PrimitiveType, StructField, StructType};
+use crate::{DeltaResult, Error};
+
+impl StructType {
+    /// Merges `other` into this schema, returning a schema that can represent data of both.
+    ///
+    /// The merge is recursive (into nested structs, arrays and maps) and follows Delta schema
+    /// evolution rules:
+    /// - Fields are matched by name, case-insensitively. Matched fields keep the name, position
+    ///   and metadata of this schema; metadata keys only present in `other` are added.
+    /// - Fields only present in `other` are appended, in the order they appear in `other`.
+    /// - Nullability is unioned: a field (or array element, or map value) is nullable if it is
+    ///   nullable in either schema. Fields present in only one schema become nullable, because
+    ///   data written with the other schema has no values for them.
+    /// - Primitive types must either match or be reconcilable by an allowed type widening (e.g.
+    ///   `integer` and `long` merge to `long`, and decimals merge to a decimal that can hold
+    ///   both).
+    ///
+    /// Returns a schema error if the schemas are incompatible.
+    pub fn merge(&self, other: &StructType) -> DeltaResult<StructType> {
+        let mut other_fields: HashMap<String, &StructField> = other
+            .fields()
+            .map(|field| (field.name().to_lowercase(), field))
+            .collect();
+        let mut merged = Vec::with_capacity(self.num_fields() + other.num_fields());
+        for field in self.fields() {
+            let merged_field = match other_fields.remove(&field.name().to_lowercase()) {
+                Some(other_field) => field.merge(other_field)?,
+                None => field.clone().into_nullable(),
+            };
+            merged.push(merged_field);
+        }
+        let added_fields = other
+            .fields()
+            .filter(|field| other_fields.contains_key(&field.name().to_lowercase()))
+            .map(|field| field.clone().into_nullable());
+        StructType::try_new(merged.into_iter().chain(added_fields))
+    }
+}
+
+impl StructField {
+    fn merge(&self, other: &StructField) -> DeltaResult<StructField> {
+        let data_type = self
+            .data_type()
+            .merge(other.data_type())
+            .map_err(|err| Error::schema(format!("Cannot merge field '{}': {err}", self.name)))?;
+        let mut metadata = self.metadata.clone();
+        for (key, value) in &other.metadata {
+            metadata.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        Ok(StructField {
+            name: self.name.clone(),
+            data_type,
+            nullable: self.nullable || other.nullable,
+            metadata,
+        })
+    }
+
+    fn into_nullable(mut self) -> StructField {
+        self.nullable = true;
+        self
+    }
+}
+
+impl DataType {
+    fn merge(&self, other: &DataType) -> DeltaResult<DataType> {
+        let merged = match (self, other) {
+            (a, b) if a == b => a.clone(),
+            (DataType::Struct(a), DataType::Struct(b)) => a.merge(b)?.into(),
+            (DataType::Array(a), DataType::Array(b)) => ArrayType::new(
+                a.element_type().merge(b.element_type())?,
+                a.contains_null() || b.contains_null(),
+            )
+            .into(),
+            (DataType::Map(a), DataType::Map(b)) => MapType::new(
+                a.key_type().merge(b.key_type())?,
+                a.value_type().merge(b.value_type())?,
+                a.value_contains_null() || b.value_contains_null(),
+            )
+            .into(),
+            (DataType::Primitive(a), DataType::Primitive(b)) => merge_primitives(a, b)
+                .ok_or_else(|| Error::schema(format!("incompatible types {a} and {b}")))?
+                .into(),
+            (a, b) => return Err(Error::schema(format!("incompatible types {a} and {b}"))),
+        };
+        Ok(merged)
+    }
+}
+
+/// Returns the narrowest type both `a` and `b` can be widened to, if any.
+fn merge_primitives(a: &PrimitiveType, b: &PrimitiveType) -> Option<PrimitiveType> {
+    use PrimitiveType::*;
+    match (a, b) {
+        (Decimal(a), Decimal(b)) => {
+            let scale = a.scale().max(b.scale());
+            let integral_digits = (a.precision() - a.scale()).max(b.precision() - b.scale());
+            let precision = integral_digits.checked_add(scale)?;
+            DecimalType::try_new(precision, scale).ok().map(Decimal)
+        }
+        _ if can_widen(a, b) => Some(b.clone()),
+        _ if can_widen(b, a) => Some(a.clone()),
+        _ => None,
+    }
+}
+
+/// Returns true if values of type `from` can be losslessly read as type `to`, according to the
+/// type changes allowed by the Delta type widening feature.
+fn can_widen(from: &PrimitiveType, to: &PrimitiveType) -> bool {
+    use PrimitiveType::*;
+    match (from, to) {
+        (Byte, Short | Integer | Long | Double) => true,
+        (Short, Integer | Long | Double) => true,
+        (Integer, Long | Double) => true,
+        (Float, Double) => true,
+        (Date, TimestampNtz) => true,
+        (Byte | Short | Integer, Decimal(d)) => d.precision() - d.scale() >= 10,
+        (Long, Decimal(d)) => d.precision() - d.scale() >= 20,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{ArrayType, DataType, MapType, MetadataValue, StructField, StructType};
+
+    #[test]
+    fn merge_is_position_stable_and_appends_new_fields() {
+        let schema = StructType::new_unchecked([
+            StructField::not_null("a", DataType::INTEGER),
+            StructField::not_null("b", DataType::STRING),
+        ]);
+        let other = StructType::new_unchecked([
+            StructField::nullable("c", DataType::LONG),
+            StructField::not_null("A", DataType::INTEGER),
+        ]);
+        let expected = StructType::new_unchecked([
+            StructField::not_null("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::STRING),
+            StructField::nullable("c", DataType::LONG),
+        ]);
+        assert_eq!(schema.merge(&other).unwrap(), expected);
+    }
+
+    #[test]
+    fn merge_nested_types() {
+        let schema = StructType::new_unchecked([
+            StructField::not_null(
+                "s",
+                StructType::new_unchecked([StructField::not_null("x", DataType::SHORT)]),
+            ),
+            StructField::not_null("arr", ArrayType::new(DataType::INTEGER, false)),
+            StructField::not_null(
+                "map",
+                MapType::new(DataType::STRING, DataType::FLOAT, false),
+            ),
+        ]);
+        let other = StructType::new_unchecked([
+            StructField::not_null(
+                "s",
+                StructType::new_unchecked([
+                    StructField::nullable("x", DataType::INTEGER),
+                    StructField::nullable("y", DataType::DATE),
+                ]),
+            ),
+            StructField::not_null("arr", ArrayType::new(DataType::LONG, true)),
+            StructField::not_null(
+                "map",
+                MapType::new(DataType::STRING, DataType::DOUBLE, true),
+            ),
+        ]);
+        let expected = StructType::new_unchecked([
+            StructField::not_null(
+                "s",
+                StructType::new_unchecked([
+                    StructField::nullable("x", DataType::INTEGER),
+                    StructField::nullable("y", DataType::DATE),
+                ]),
+            ),
+            StructField::not_null("arr", ArrayType::new(DataType::LONG, true)),
+            StructField::not_null(
+                "map",
+                MapType::new(DataType::STRING, DataType::DOUBLE, true),
+            ),
+        ]);
+        assert_eq!(schema.merge(&other).unwrap(), expected);
+        assert_eq!(other.merge(&schema).unwrap(), expected);
+    }
+
+    #[test]
+    fn merge_widens_primitive_types() {
+        let cases = [
+            (DataType::BYTE, DataType::LONG, DataType::LONG),
+            (DataType::INTEGER, DataType::DOUBLE, DataType::DOUBLE),
+            (DataType::FLOAT, DataType::DOUBLE, DataType::DOUBLE),
+            (
+                DataType::DATE,
+                DataType::TIMESTAMP_NTZ,
+                DataType::TIMESTAMP_NTZ,
+            ),
+            (
+                DataType::decimal(10, 2).unwrap(),
+                DataType::decimal(12, 5).unwrap(),
+                DataType::decimal(13, 5).unwrap(),
+            ),
+            (
+                DataType::INTEGER,
+                DataType::decimal(12, 2).unwrap(),
+                DataType::decimal(12, 2).unwrap(),
+            ),
+        ];
+        for (a, b, expected) in cases {
+            let schema =
+                |t: &DataType| StructType::new_unchecked([StructField::nullable("f", t.clone())]);
+            assert_eq!(schema(&a).merge(&schema(&b)).unwrap(), schema(&expected));
+            assert_eq!(schema(&b).merge(&schema(&a)).unwrap(), schema(&expected));
+        }
+    }
+
+    #[test]
+    fn merge_rejects_incompatible_types() {
+        let cases = [
+            (DataType::INTEGER, DataType::STRING),
+            (DataType::LONG, DataType::FLOAT),
+            (DataType::TIMESTAMP, DataType::TIMESTAMP_NTZ),
+            (DataType::LONG, DataType::decimal(12, 2).unwrap()),
+            (
+                DataType::decimal(38, 0).unwrap(),
+                DataType::decimal(38, 2).unwrap(),
+            ),
+            (
+                DataType::INTEGER,
+                StructType::new_unchecked([StructField::nullable("x", DataType::INTEGER)]).into(),
+            ),
+        ];
+        for (a, b) in cases {
+            let schema = StructType::new_unchecked([StructField::nullable("f", a)]);
+            let other = StructType::new_unchecked([StructField::nullable("f", b)]);
+            let err = schema.merge(&other).unwrap_err().to_string();
+            assert!(err.contains("Cannot merge field 'f'"), "{err}");
+        }
+    }
+
+    #[test]
+    fn merge_keeps_metadata_of_this_schema() {
+        let schema = StructType::new_unchecked([StructField::nullable("f", DataType::INTEGER)
+            .with_metadata([("a", MetadataValue::from("self"))])]);
+        let other = StructType::new_unchecked([StructField::nullable("f", DataType::INTEGER)
+            .with_metadata([
+                ("a", MetadataValue::from("other")),
+                ("b", MetadataValue::from("other")),
+            ])]);
+        let merged = schema.merge(&other).unwrap();
+        let field = merged.field("f").unwrap();
+        assert_eq!(field.metadata.get("a"), Some(&MetadataValue::from("self")));
+        assert_eq!(field.metadata.get("b"), Some(&MetadataValue::from("other")));
+    }
+}
@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 // re-export because many call sites that use schemas do not necessarily use expressions
+use crate::expressions::Scalar;
 pub(crate) use crate::expressions::{column_name, ColumnName};
 use crate::table_features::ColumnMappingMode;
 use crate::utils::{require, CowExt as _};
@@ -20,6 +21,11 @@ use crate::{DeltaResult, Error};
 use delta_kernel_derive::internal_api;
 
 pub(crate) mod compare;
+mod ddl;
+mod limits;
+mod merge;
+
+pub use limits::SchemaLimits;
 
 #[cfg(feature = "internal-api")]
 pub mod derive_macro_utils;
@@ -104,6 +110,8 @@ pub enum ColumnMetadataKey {
     InternalColumn,
     Invariants,
     MetadataSpec,
+    CharVarcharType,
+    CurrentDefault,
 }
 
 impl AsRef<str> for ColumnMetadataKey {
@@ -120,6 +128,8 @@ impl AsRef<str> for ColumnMetadataKey {
             Self::InternalColumn => "delta.isInternalColumn",
             Self::Invariants => "delta.invariants",
             Self::MetadataSpec => "delta.metadataSpec",
+            Self::CharVarcharType => "__CHAR_VARCHAR_TYPE_STRING",
+            Self::CurrentDefault => "CURRENT_DEFAULT",
         }
     }
 }
@@ -176,6 +186,59 @@ impl FromStr for MetadataColumnSpec {
     }
 }
 
+/// A SQL `CHAR(n)` or `VARCHAR(n)` type. Delta stores such columns as plain strings, and records
+/// the original type and its length limit in the [`ColumnMetadataKey::CharVarcharType`] field
+/// metadata.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum CharVarcharType {
+    /// A fixed-length string of `n` characters, padded with trailing spaces
+    Char(usize),
+    /// A variable-length string of at most `n` characters
+    Varchar(usize),
+}
+
+impl CharVarcharType {
+    /// The maximum number of characters a value of this type may hold.
+    pub fn max_length(&self) -> usize {
+        match self {
+            Self::Char(n) | Self::Varchar(n) => *n,
+        }
+    }
+
+    /// Whether `value` fits this type. Like Spark, trailing spaces beyond the maximum length are
+    /// ignored.
+    pub fn fits(&self, value: &str) -> bool {
+        value.trim_end_matches(' ').chars().count() <= self.max_length()
+    }
+}
+
+impl Display for CharVarcharType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Char(n) => write!(f, "char({n})"),
+            Self::Varchar(n) => write!(f, "varchar({n})"),
+        }
+    }
+}
+
+impl FromStr for CharVarcharType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Schema(format!("Invalid char/varchar type: {s}"));
+        let (name, len) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(invalid)?;
+        let len = len.trim().parse().map_err(|_| invalid())?;
+        match name.trim().to_ascii_lowercase().as_str() {
+            "char" => Ok(Self::Char(len)),
+            "varchar" => Ok(Self::Varchar(len)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 pub struct StructField {
     /// Name of this (possibly nested) column
@@ -186,9 +249,50 @@ pub struct StructField {
     /// Denotes whether this Field can be null
     pub nullable: bool,
     /// A JSON map containing information about this column
+    #[serde(default, serialize_with = "serialize_metadata")]
     pub metadata: HashMap<String, MetadataValue>,
 }
 
+// Serialize field metadata with sorted keys, so that serializing a schema is deterministic.
+fn serialize_metadata<S: serde::Serializer>(
+    metadata: &HashMap<String, MetadataValue>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(metadata.iter().sorted_by_key(|(key, _)| *key))
+}
+
+/// Parse `default`, the SQL text of a column default, as a literal of type `data_type`.
+fn parse_default_literal(default: &str, data_type: &PrimitiveType) -> DeltaResult<Scalar> {
+    let default = default.trim();
+    if default.eq_ignore_ascii_case("null") {
+        return Ok(Scalar::Null(data_type.clone().into()));
+    }
+    // typed literals, e.g. TIMESTAMP '2024-01-01 00:00:00'
+    let unprefixed = default
+        .split_once(char::is_whitespace)
+        .filter(|(prefix, _)| {
+            ["DATE", "TIMESTAMP", "TIMESTAMP_NTZ", "TIMESTAMP_LTZ"]
+                .iter()
+                .any(|typ| prefix.eq_ignore_ascii_case(typ))
+        })
+        .map_or(default, |(_, literal)| literal.trim_start());
+    let quoted = ['\'', '"'].into_iter().find_map(|quote| {
+        unprefixed
+            .strip_prefix(quote)
+            .and_then(|s| s.strip_suffix(quote))
+            .map(|s| s.replace(&format!("{quote}{quote}"), &quote.to_string()))
+    });
+    match (quoted, data_type) {
+        // an empty string is a valid string default, but parses as null
+        (Some(s), PrimitiveType::String) => Ok(Scalar::String(s)),
+        (Some(s), _) => data_type.parse_scalar(&s),
+        (None, PrimitiveType::String | PrimitiveType::Binary) => {
+            Err(Error::generic("string defaults must be quoted"))
+        }
+        (None, _) => data_type.parse_scalar(unprefixed),
+    }
+}
+
 impl StructField {
     /// The name of the default row index metadata column.
     ///
@@ -289,6 +393,22 @@ impl StructField {
         }
     }
 
+    /// Returns the SQL `CHAR`/`VARCHAR` type of this string field, if its metadata records one.
+    pub fn char_varchar_type(&self) -> Option<CharVarcharType> {
+        match self.get_config_value(&ColumnMetadataKey::CharVarcharType)? {
+            MetadataValue::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Records a SQL `CHAR`/`VARCHAR` type for this field in its metadata.
+    pub fn with_char_varchar_type(self, char_varchar_type: CharVarcharType) -> Self {
+        self.add_metadata([(
+            ColumnMetadataKey::CharVarcharType.as_ref(),
+            char_varchar_type.to_string(),
+        )])
+    }
+
     /// Returns true if this field is an internal column added by Kernel.
     ///
     /// Internal columns must be removed before returning scan results to the user.
@@ -312,6 +432,39 @@ impl StructField {
         self.metadata.get(key.as_ref())
     }
 
+    /// The default value of this column, i.e. its `CURRENT_DEFAULT` metadata parsed as a literal of
+    /// the column's type, or `None` if the column has no default. Files written before a column
+    /// with a default was added read the default for it, rather than null.
+    ///
+    /// Defaults are SQL expressions, of which only literals are supported: `NULL`, numbers,
+    /// booleans, and quoted strings, optionally prefixed by a type for dates and timestamps (e.g.
+    /// `DATE '2024-01-01'`). Other expressions, such as function calls, fail to parse.
+    pub fn current_default_value(&self) -> DeltaResult<Option<Scalar>> {
+        let Some(default) = self.get_config_value(&ColumnMetadataKey::CurrentDefault) else {
+            return Ok(None);
+        };
+        let MetadataValue::String(default) = default else {
+            return Err(Error::generic(format!(
+                "Default value of column {} is not a string",
+                self.name
+            )));
+        };
+        let DataType::Primitive(data_type) = &self.data_type else {
+            return Err(Error::unsupported(format!(
+                "Default values of non-primitive column {} are not supported",
+                self.name
+            )));
+        };
+        parse_default_literal(default, data_type)
+            .map(Some)
+            .map_err(|e| {
+                Error::unsupported(format!(
+                    "Default value {default} of column {} is not a supported literal: {e}",
+                    self.name
+                ))
+            })
+    }
+
     /// Get the physical name for this field as it should be read from parquet.
     ///
     /// NOTE: Caller affirms that the schema was already validated by
@@ -705,6 +858,23 @@ impl StructType {
             .filter_map(|index| self.fields.get_index(*index).map(|(_, field)| field))
     }
 
+    /// Parses a schema from its Delta JSON representation, as stored in the `schemaString` field
+    /// of a table's `metaData` action.
+    ///
+    /// Field metadata (e.g. column mapping ids and physical names, or collations) is preserved
+    /// as-is, and the result is validated like [`StructType::try_new`]. Parsing the output of
+    /// [`StructType::to_json`] always returns a schema equal to the serialized one.
+    pub fn from_json(json: &str) -> DeltaResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serializes this schema to its Delta JSON representation, suitable for the `schemaString`
+    /// field of a table's `metaData` action. The output is deterministic: fields appear in schema
+    /// order and the keys of each field's metadata are sorted.
+    pub fn to_json(&self) -> DeltaResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
     /// Extracts the name and type of all leaf columns, in schema order. Caller should pass Some
     /// `own_name` if this schema is embedded in a larger struct (e.g. `add.*`) and None if the
     /// schema is a top-level result (e.g. `*`).
@@ -1398,27 +1568,6 @@ impl DataType {
     }
 }
 
-impl Display for DataType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DataType::Primitive(p) => write!(f, "{p}"),
-            DataType::Array(a) => write!(f, "array<{}>", a.element_type),
-            DataType::Struct(s) => {
-                write!(f, "struct<")?;
-                for (i, field) in s.fields().enumerate() {
-                    if i > 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}: {}", field.name, field.data_type)?;
-                }
-                write!(f, ">")
-            }
-            DataType::Map(m) => write!(f, "map<{}, {}>", m.key_type, m.value_type),
-            DataType::Variant(_) => write!(f, "variant"),
-        }
-    }
-}
-
 /// Generic framework for describing recursive bottom-up schema transforms. Transformations return
 /// `Option<Cow>` with the following semantics:
 /// * `Some(Cow::Owned)` -- The schema element was transformed and should propagate to its parent.
@@ -1811,6 +1960,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_schema_json_roundtrip() {
+        let json = r#"{"type":"struct","fields":[{"name":"a","type":"string","nullable":true,"metadata":{"__COLLATIONS":{"a":"ICU.en_US"},"delta.columnMapping.id":1,"delta.columnMapping.physicalName":"col-1"}},{"name":"b","type":{"type":"map","keyType":"string","valueType":{"type":"array","elementType":"decimal(10,2)","containsNull":false},"valueContainsNull":true},"nullable":false,"metadata":{"comment":"a map","delta.columnMapping.id":2,"delta.columnMapping.physicalName":"col-2"}}]}"#;
+        let schema = StructType::from_json(json).unwrap();
+        let field = schema.field("a").unwrap();
+        assert_eq!(
+            field.get_config_value(&ColumnMetadataKey::ColumnMappingId),
+            Some(&MetadataValue::Number(1))
+        );
+        assert_eq!(
+            field.metadata.get("__COLLATIONS"),
+            Some(&MetadataValue::Other(serde_json::json!({"a": "ICU.en_US"})))
+        );
+
+        // serialization is deterministic and exactly reproduces the (sorted) input
+        assert_eq!(schema.to_json().unwrap(), json);
+        assert_eq!(
+            StructType::from_json(&schema.to_json().unwrap()).unwrap(),
+            schema
+        );
+    }
+
+    #[test]
+    fn test_schema_from_json() {
+        // missing field metadata defaults to empty
+        let schema = StructType::from_json(
+            r#"{"type":"struct","fields":[{"name":"a","type":"long","nullable":true}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            schema,
+            StructType::new_unchecked([StructField::nullable("a", DataType::LONG)])
+        );
+
+        assert!(StructType::from_json("not json").is_err());
+        let duplicate = r#"{"type":"struct","fields":[{"name":"a","type":"long","nullable":true,"metadata":{}},{"name":"a","type":"long","nullable":true,"metadata":{}}]}"#;
+        assert!(StructType::from_json(duplicate).is_err());
+    }
+
     #[test]
     fn test_roundtrip_variant() {
         let data = r#"
@@ -2097,6 +2285,57 @@ mod tests {
         assert_eq!(check_with_call_count(8), (7, 32));
     }
 
+    #[test]
+    fn test_current_default_value() {
+        let field = |data_type: DataType, default: &str| {
+            StructField::nullable("col", data_type).with_metadata([(
+                ColumnMetadataKey::CurrentDefault.as_ref(),
+                MetadataValue::String(default.to_string()),
+            )])
+        };
+        let cases = [
+            (DataType::INTEGER, "42", Scalar::Integer(42)),
+            (DataType::LONG, " -7 ", Scalar::Long(-7)),
+            (DataType::BOOLEAN, "TRUE", Scalar::Boolean(true)),
+            (
+                DataType::STRING,
+                "'it''s'",
+                Scalar::String("it's".to_string()),
+            ),
+            (DataType::STRING, "''", Scalar::String("".to_string())),
+            (DataType::DATE, "DATE '1970-01-02'", Scalar::Date(1)),
+            (DataType::DATE, "'1970-01-02'", Scalar::Date(1)),
+            (
+                DataType::TIMESTAMP_NTZ,
+                "TIMESTAMP_NTZ '1970-01-01 00:00:01'",
+                Scalar::TimestampNtz(1_000_000),
+            ),
+            (DataType::INTEGER, "null", Scalar::Null(DataType::INTEGER)),
+        ];
+        for (data_type, default, expected) in cases {
+            let value = field(data_type, default).current_default_value().unwrap();
+            assert_eq!(value, Some(expected), "default {default}");
+        }
+
+        assert_eq!(
+            StructField::nullable("col", DataType::INTEGER)
+                .current_default_value()
+                .unwrap(),
+            None
+        );
+        for (data_type, default) in [
+            (DataType::STRING, "abc"),
+            (DataType::INTEGER, "1 + 1"),
+            (DataType::TIMESTAMP, "current_timestamp()"),
+        ] {
+            let res = field(data_type, default).current_default_value();
+            assert!(
+                matches!(res, Err(Error::Unsupported(_))),
+                "default {default}"
+            );
+        }
+    }
+
     #[test]
     fn test_metadata_value_to_string() {
         assert_eq!(MetadataValue::Number(0).to_string(), "0");
@@ -2887,4 +3126,33 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_char_varchar_type() {
+        let field = StructField::nullable("c", DataType::STRING)
+            .with_char_varchar_type(CharVarcharType::Varchar(3));
+        assert_eq!(
+            field.metadata().get("__CHAR_VARCHAR_TYPE_STRING"),
+            Some(&MetadataValue::String("varchar(3)".to_string()))
+        );
+        assert_eq!(field.char_varchar_type(), Some(CharVarcharType::Varchar(3)));
+        assert_eq!(
+            StructField::nullable("c", DataType::STRING).char_varchar_type(),
+            None
+        );
+
+        assert_eq!(
+            "CHAR(10)".parse::<CharVarcharType>().unwrap(),
+            CharVarcharType::Char(10)
+        );
+        assert!("varchar".parse::<CharVarcharType>().is_err());
+        assert!("text(3)".parse::<CharVarcharType>().is_err());
+        assert!("char(-1)".parse::<CharVarcharType>().is_err());
+
+        let varchar = CharVarcharType::Varchar(3);
+        assert!(varchar.fits("abc"));
+        assert!(varchar.fits("ab  "));
+        assert!(varchar.fits("äöü"));
+        assert!(!varchar.fits("abcd"));
+    }
 }
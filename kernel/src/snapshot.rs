@@ -8,12 +8,20 @@ use crate::actions::domain_metadata::domain_metadata_configuration;
 use crate::actions::set_transaction::SetTransactionScanner;
 use crate::actions::{Metadata, Protocol, INTERNAL_DOMAIN_PREFIX};
 use crate::checkpoint::CheckpointWriter;
+use crate::expressions::{ColumnName, Scalar};
 use crate::listed_log_files::ListedLogFiles;
+use crate::log_cleanup;
 use crate::log_segment::LogSegment;
+use crate::optimize::OptimizePlanner;
+use crate::scan::aggregate::{self, Aggregate};
+use crate::scan::column_stats::{self, TableStats};
 use crate::scan::ScanBuilder;
-use crate::schema::SchemaRef;
+use crate::schema::{SchemaLimits, SchemaRef};
 use crate::table_configuration::TableConfiguration;
-use crate::table_features::ColumnMappingMode;
+use crate::table_features::{
+    field_ids_to_logical_column, logical_column_field_ids, logical_to_physical_column,
+    physical_to_logical_column, ColumnMappingMode, WriterFeature,
+};
 use crate::table_properties::TableProperties;
 use crate::transaction::Transaction;
 use crate::LogCompactionWriter;
@@ -21,7 +29,17 @@ use crate::{DeltaResult, Engine, Error, Version};
 use delta_kernel_derive::internal_api;
 
 mod builder;
+mod cache;
+mod descriptor;
+mod log_segment_view;
+mod schema_history;
+mod watch;
 pub use builder::SnapshotBuilder;
+pub use cache::SnapshotCache;
+pub use descriptor::SnapshotDescriptor;
+pub use log_segment_view::{LogFile, LogFileKind, LogSegmentView};
+pub use schema_history::SchemaVersion;
+pub use watch::{ChangeNotifier, PollingNotifier};
 
 use tracing::debug;
 use url::Url;
@@ -86,6 +104,18 @@ impl Snapshot {
         SnapshotBuilder::new_from(existing_snapshot)
     }
 
+    /// Describe this snapshot, so that other processes can rebuild it with
+    /// [`Snapshot::from_descriptor`] without reading the log. See [`SnapshotDescriptor`].
+    pub fn descriptor(&self) -> SnapshotDescriptor {
+        SnapshotDescriptor::new(self)
+    }
+
+    /// Rebuild a snapshot from its [`SnapshotDescriptor`]. Unlike the [`SnapshotBuilder`], this
+    /// reads nothing from storage.
+    pub fn from_descriptor(descriptor: SnapshotDescriptor) -> DeltaResult<SnapshotRef> {
+        Ok(Arc::new(descriptor.into_snapshot()?))
+    }
+
     #[internal_api]
     pub(crate) fn new(log_segment: LogSegment, table_configuration: TableConfiguration) -> Self {
         Self {
@@ -175,6 +205,7 @@ impl Snapshot {
                 existing_snapshot.table_root().clone(),
                 new_log_segment,
                 engine,
+                existing_snapshot.table_configuration().schema_limits(),
             );
             return Ok(Arc::new(snapshot?));
         }
@@ -243,10 +274,16 @@ impl Snapshot {
         location: Url,
         log_segment: LogSegment,
         engine: &dyn Engine,
+        schema_limits: SchemaLimits,
     ) -> DeltaResult<Self> {
         let (metadata, protocol) = log_segment.read_metadata(engine)?;
-        let table_configuration =
-            TableConfiguration::try_new(metadata, protocol, location, log_segment.end_version)?;
+        let table_configuration = TableConfiguration::try_new_with_schema_limits(
+            metadata,
+            protocol,
+            location,
+            log_segment.end_version,
+            schema_limits,
+        )?;
         Ok(Self {
             log_segment,
             table_configuration,
@@ -280,6 +317,56 @@ impl Snapshot {
         LogCompactionWriter::try_new(self, start_version, end_version)
     }
 
+    /// Deletes the expired files of this table's log: the commits and checkpoints older than the
+    /// table's log retention (`delta.logRetentionDuration`, 30 days by default) that precede a
+    /// checkpoint which is itself older than the log retention. Versions since that checkpoint
+    /// remain readable. Files are deleted oldest first with [`StorageHandler::delete_files`].
+    ///
+    /// Returns the deleted files. See [`LogCleanupHook`] to clean up the log after commits.
+    ///
+    /// Fails without deleting anything if the kernel cannot write to the table, if the table
+    /// disables log cleanup (`delta.enableExpiredLogCleanup = false`), or if the table uses the
+    /// `checkpointProtection` feature, which the kernel does not support.
+    ///
+    /// [`StorageHandler::delete_files`]: crate::StorageHandler::delete_files
+    /// [`LogCleanupHook`]: crate::transaction::hooks::LogCleanupHook
+    pub fn cleanup_expired_logs(&self, engine: &dyn Engine) -> DeltaResult<Vec<Url>> {
+        let table_configuration = self.table_configuration();
+        if table_configuration
+            .protocol()
+            .has_writer_feature(&WriterFeature::CheckpointProtection)
+        {
+            return Err(Error::unsupported(
+                "Cannot clean up the log of a table with the checkpointProtection feature",
+            ));
+        }
+        table_configuration.ensure_write_supported()?;
+        if self.table_properties().enable_expired_log_cleanup == Some(false) {
+            return Err(Error::generic(
+                "Cannot clean up the log of a table with delta.enableExpiredLogCleanup = false",
+            ));
+        }
+        log_cleanup::cleanup_expired_logs(
+            engine,
+            self.table_root(),
+            self.table_properties().log_retention_duration,
+        )
+    }
+
+    /// Whether this snapshot is known to contain no files (e.g. a newly created table) without
+    /// replaying its log. This is the case if the snapshot's version has a checksum (CRC) file
+    /// recording zero live files. Returns `false` if there is no such file, in which case the
+    /// snapshot may or may not be empty.
+    pub fn is_known_empty(&self, engine: &dyn Engine) -> bool {
+        self.log_segment.num_files_from_crc(engine) == Some(0)
+    }
+
+    /// A read-only view of the log files this snapshot is built from, e.g. to implement caching,
+    /// prefetching or metrics around log IO. See [`LogSegmentView`].
+    pub fn log_segment_view(&self) -> LogSegmentView<'_> {
+        LogSegmentView::new(&self.log_segment)
+    }
+
     /// Log segment this snapshot uses
     #[internal_api]
     pub(crate) fn log_segment(&self) -> &LogSegment {
@@ -333,6 +420,30 @@ impl Snapshot {
         self.table_configuration.column_mapping_mode()
     }
 
+    /// Translate a logical (possibly nested) column name into the physical name used in the
+    /// table's parquet files. Without column mapping the two are identical.
+    pub fn physical_column_name(&self, column: &ColumnName) -> DeltaResult<ColumnName> {
+        logical_to_physical_column(&self.schema(), column)
+    }
+
+    /// Translate a physical (possibly nested) column name, as found in the table's parquet files,
+    /// into its logical name. Without column mapping the two are identical.
+    pub fn logical_column_name(&self, column: &ColumnName) -> DeltaResult<ColumnName> {
+        physical_to_logical_column(&self.schema(), column)
+    }
+
+    /// Get the column mapping field id of each element of a logical (possibly nested) column name.
+    /// Returns an error if column mapping is not enabled for this table.
+    pub fn column_field_ids(&self, column: &ColumnName) -> DeltaResult<Vec<i64>> {
+        logical_column_field_ids(&self.schema(), column, self.column_mapping_mode())
+    }
+
+    /// Translate a path of column mapping field ids into the logical (possibly nested) column name
+    /// it identifies. Returns an error if column mapping is not enabled for this table.
+    pub fn column_name_for_field_ids(&self, field_ids: &[i64]) -> DeltaResult<ColumnName> {
+        field_ids_to_logical_column(&self.schema(), field_ids, self.column_mapping_mode())
+    }
+
     /// Create a [`ScanBuilder`] for an `SnapshotRef`.
     pub fn scan_builder(self: Arc<Self>) -> ScanBuilder {
         ScanBuilder::new(self)
@@ -343,6 +454,37 @@ impl Snapshot {
         ScanBuilder::new(self)
     }
 
+    /// Create an [`OptimizePlanner`] to plan the compaction (and optionally clustering) of the
+    /// files of this snapshot.
+    pub fn optimize_planner(self: Arc<Self>) -> OptimizePlanner {
+        OptimizePlanner::new(self)
+    }
+
+    /// Answer simple aggregates over this snapshot from file statistics alone, without reading any
+    /// data. Returns one result per requested aggregate, in order; a result is `None` if the stats
+    /// are not sufficient to answer it exactly (e.g. some file lacks stats, or has a deletion
+    /// vector that leaves its min/max stats loose). `COUNT(*)` accounts for deletion vectors.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    pub fn aggregate_from_stats(
+        self: Arc<Self>,
+        engine: &dyn Engine,
+        aggregates: &[Aggregate],
+    ) -> DeltaResult<Vec<Option<Scalar>>> {
+        aggregate::aggregate_from_stats(self, engine, aggregates)
+    }
+
+    /// Aggregate the file statistics of this snapshot into table-level statistics (min, max and
+    /// null count of every column, and the number of rows) without reading any data. Unlike
+    /// [`Self::aggregate_from_stats`], the results are best-effort: they are marked approximate
+    /// when some file lacks stats or has inexact ones, which makes them suitable for cost-based
+    /// planning but not for answering queries.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    pub fn column_stats(self: Arc<Self>, engine: &dyn Engine) -> DeltaResult<TableStats> {
+        column_stats::column_stats(self, engine)
+    }
+
     /// Create a [`Transaction`] for this `SnapshotRef`.
     pub fn transaction(self: Arc<Self>) -> DeltaResult<Transaction> {
         Transaction::try_new(self)
@@ -923,6 +1065,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_log_segment_view() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/with_checkpoint_no_last_checkpoint/",
+        ))
+        .unwrap();
+        let location = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(location).build(&engine).unwrap();
+
+        let view = snapshot.log_segment_view();
+        assert_eq!(view.end_version(), 3);
+        assert_eq!(view.checkpoint_version(), Some(2));
+        assert_eq!(view.versions(), 2..=3);
+        assert!(view.log_root().as_str().ends_with("/_delta_log/"));
+
+        let checkpoint: Vec<_> = view
+            .checkpoint_parts()
+            .map(|file| (file.version(), file.kind()))
+            .collect();
+        let expected_kind = LogFileKind::Checkpoint {
+            part: 1,
+            num_parts: 1,
+        };
+        assert_eq!(checkpoint, [(2, expected_kind)]);
+        let commits: Vec<_> = view
+            .commit_files()
+            .map(|file| (file.version(), file.kind()))
+            .collect();
+        assert_eq!(commits, [(3, LogFileKind::Commit)]);
+        assert!(view
+            .commit_files()
+            .all(|file| file.file().location.as_str().ends_with(".json")));
+        assert_eq!(view.compaction_files().len(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_descriptor_round_trip() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/with_checkpoint_no_last_checkpoint/",
+        ))
+        .unwrap();
+        let location = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(location).build(&engine).unwrap();
+
+        let descriptor = snapshot.descriptor();
+        assert_eq!(descriptor.version(), 3);
+        let json = serde_json::to_string(&descriptor).unwrap();
+        let descriptor: SnapshotDescriptor = serde_json::from_str(&json).unwrap();
+        let rebuilt = Snapshot::from_descriptor(descriptor).unwrap();
+        assert_eq!(*rebuilt, *snapshot);
+
+        // a descriptor whose log segment does not reach its version is rejected
+        let mut json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        json["version"] = json!(4);
+        let descriptor: SnapshotDescriptor = serde_json::from_value(json).unwrap();
+        assert!(Snapshot::from_descriptor(descriptor).is_err());
+    }
+
     #[tokio::test]
     async fn test_domain_metadata() -> DeltaResult<()> {
         let url = Url::parse("memory:///")?;
@@ -1025,6 +1227,66 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_cleanup_expired_logs_refusals() {
+        async fn snapshot_with(
+            protocol: serde_json::Value,
+            configuration: serde_json::Value,
+        ) -> (DefaultEngine<TokioBackgroundExecutor>, SnapshotRef) {
+            let store = Arc::new(InMemory::new());
+            let commit = [
+                json!({ "protocol": protocol }),
+                json!({
+                    "metaData": {
+                        "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                        "format": { "provider": "parquet", "options": {} },
+                        "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                        "partitionColumns": [],
+                        "configuration": configuration,
+                        "createdTime": 1587968585495i64
+                    }
+                }),
+            ]
+            .map(|json| json.to_string())
+            .join("\n");
+            add_commit(store.as_ref(), 0, commit).await.unwrap();
+            let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+            let url = Url::parse("memory:///").unwrap();
+            let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+            (engine, snapshot)
+        }
+
+        // a table the kernel can write, whose log is too recent to expire
+        let protocol = json!({ "minReaderVersion": 1, "minWriterVersion": 2 });
+        let (engine, snapshot) = snapshot_with(protocol.clone(), json!({})).await;
+        assert!(snapshot.cleanup_expired_logs(&engine).unwrap().is_empty());
+
+        // the table disables log cleanup
+        let configuration = json!({ "delta.enableExpiredLogCleanup": "false" });
+        let (engine, snapshot) = snapshot_with(protocol, configuration).await;
+        let err = snapshot.cleanup_expired_logs(&engine).unwrap_err();
+        assert!(err.to_string().contains("delta.enableExpiredLogCleanup"));
+
+        // the table protects its checkpoints
+        let protocol = json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["checkpointProtection"]
+        });
+        let (engine, snapshot) = snapshot_with(protocol, json!({})).await;
+        let err = snapshot.cleanup_expired_logs(&engine).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(msg) if msg.contains("checkpointProtection")));
+
+        // the kernel cannot write to the table
+        let protocol = json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["someFutureFeature"]
+        });
+        let (engine, snapshot) = snapshot_with(protocol, json!({})).await;
+        assert!(snapshot.cleanup_expired_logs(&engine).is_err());
+    }
+
     #[test]
     fn test_log_compaction_writer() {
         let path =
@@ -1,9 +1,14 @@
 //! Builder for creating [`Snapshot`] instances.
 use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::schema::SchemaLimits;
 use crate::snapshot::SnapshotRef;
-use crate::{DeltaResult, Engine, Error, Snapshot, Version};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, FileMeta, Snapshot, Version};
 
+use tracing::{field, info_span};
 use url::Url;
+use uuid::Uuid;
 
 /// Builder for creating [`Snapshot`] instances.
 ///
@@ -32,6 +37,8 @@ pub struct SnapshotBuilder {
     table_root: Option<Url>,
     existing_snapshot: Option<SnapshotRef>,
     version: Option<Version>,
+    schema_limits: SchemaLimits,
+    log_tail: Vec<FileMeta>,
 }
 
 impl SnapshotBuilder {
@@ -40,6 +47,8 @@ impl SnapshotBuilder {
             table_root: Some(table_root),
             existing_snapshot: None,
             version: None,
+            schema_limits: SchemaLimits::default(),
+            log_tail: vec![],
         }
     }
 
@@ -48,6 +57,8 @@ impl SnapshotBuilder {
             table_root: None,
             existing_snapshot: Some(existing_snapshot),
             version: None,
+            schema_limits: SchemaLimits::default(),
+            log_tail: vec![],
         }
     }
 
@@ -58,6 +69,26 @@ impl SnapshotBuilder {
         self
     }
 
+    /// Set the [`SchemaLimits`] the table schema must respect, replacing the defaults. Building
+    /// the snapshot fails if the schema exceeds them. Snapshots built from an existing snapshot
+    /// (see [`Snapshot::builder_from`]) always inherit the limits of that snapshot.
+    pub fn with_schema_limits(mut self, schema_limits: SchemaLimits) -> Self {
+        self.schema_limits = schema_limits;
+        self
+    }
+
+    /// Set the log tail of a catalog-managed table: the latest commits of the table, as returned
+    /// by its catalog, in ascending version order and without gaps. These may be (unpublished)
+    /// staged commits in `_delta_log/_staged_commits/` and take precedence over the commits of the
+    /// same versions found by listing the log.
+    ///
+    /// Only supported when building a snapshot for a table root (see [`Snapshot::builder_for`]).
+    #[cfg(feature = "catalog-managed")]
+    pub fn with_log_tail(mut self, log_tail: Vec<FileMeta>) -> Self {
+        self.log_tail = log_tail;
+        self
+    }
+
     /// Create a new [`Snapshot`]. This returns a [`SnapshotRef`] (`Arc<Snapshot>`), perhaps
     /// returning a reference to an existing snapshot if the request to build a new snapshot
     /// matches the version of an existing snapshot.
@@ -66,14 +97,43 @@ impl SnapshotBuilder {
     ///
     /// - `engine`: Implementation of [`Engine`] apis.
     pub fn build(self, engine: &dyn Engine) -> DeltaResult<SnapshotRef> {
+        let span = info_span!(
+            "snapshot_build",
+            operation_id = %Uuid::new_v4(),
+            requested_version = self.version,
+            version = field::Empty,
+        );
+        let _entered = span.enter();
+        let snapshot = self.build_impl(engine)?;
+        span.record("version", snapshot.version());
+        Ok(snapshot)
+    }
+
+    fn build_impl(self, engine: &dyn Engine) -> DeltaResult<SnapshotRef> {
         if let Some(table_root) = self.table_root {
+            let log_tail = self
+                .log_tail
+                .into_iter()
+                .map(parse_log_tail_entry)
+                .collect::<DeltaResult<_>>()?;
             let log_segment = LogSegment::for_snapshot(
                 engine.storage_handler().as_ref(),
                 table_root.join("_delta_log/")?,
+                log_tail,
                 self.version,
             )?;
-            Ok(Snapshot::try_new_from_log_segment(table_root, log_segment, engine)?.into())
+            let snapshot = Snapshot::try_new_from_log_segment(
+                table_root,
+                log_segment,
+                engine,
+                self.schema_limits,
+            )?;
+            Ok(snapshot.into())
         } else {
+            require!(
+                self.log_tail.is_empty(),
+                Error::generic("A log tail can't be used to build a snapshot from an existing one")
+            );
             let existing_snapshot = self.existing_snapshot.ok_or_else(|| {
                 Error::internal_error(
                     "SnapshotBuilder should have either table_root or existing_snapshot",
@@ -84,6 +144,16 @@ impl SnapshotBuilder {
     }
 }
 
+fn parse_log_tail_entry(file: FileMeta) -> DeltaResult<ParsedLogPath> {
+    let location = file.location.clone();
+    match ParsedLogPath::try_from(file)? {
+        Some(path) if path.is_commit() => Ok(path),
+        _ => Err(Error::generic(format!(
+            "Log tail entries must be commits, but got {location}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -193,4 +263,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(feature = "catalog-managed")]
+    #[test]
+    fn test_snapshot_builder_with_log_tail() -> Result<(), Box<dyn std::error::Error>> {
+        let (engine, store, table_root) = setup_test();
+        let engine = engine.as_ref();
+        create_table(&store, &table_root)?;
+
+        // an unpublished commit 2, which can't be found by listing the log
+        let staged_commit = format!(
+            "_delta_log/_staged_commits/{:020}.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json",
+            2
+        );
+        let commit2 = json!({
+            "remove": {
+                "path": "part-00000-test.parquet",
+                "deletionTimestamp": 1587968587000i64,
+                "dataChange": true
+            }
+        });
+        let path = object_store::path::Path::from(staged_commit.as_str());
+        let meta = futures::executor::block_on(async {
+            store.put(&path, commit2.to_string().into()).await?;
+            store.head(&path).await
+        })?;
+        let log_tail = vec![FileMeta {
+            location: table_root.join(&staged_commit)?,
+            last_modified: meta.last_modified.timestamp_millis(),
+            size: meta.size,
+        }];
+
+        let snapshot = SnapshotBuilder::new_for(table_root.clone()).build(engine)?;
+        assert_eq!(snapshot.version(), 1);
+
+        let snapshot = SnapshotBuilder::new_for(table_root.clone())
+            .with_log_tail(log_tail.clone())
+            .build(engine)?;
+        assert_eq!(snapshot.version(), 2);
+
+        let snapshot = SnapshotBuilder::new_for(table_root.clone())
+            .with_log_tail(log_tail)
+            .at_version(1)
+            .build(engine)?;
+        assert_eq!(snapshot.version(), 1);
+
+        // only commits can be part of the log tail
+        let checkpoint = FileMeta {
+            location: table_root.join("_delta_log/00000000000000000001.checkpoint.parquet")?,
+            last_modified: 0,
+            size: 0,
+        };
+        let result = SnapshotBuilder::new_for(table_root)
+            .with_log_tail(vec![checkpoint])
+            .build(engine);
+        assert!(result.is_err());
+
+        Ok(())
+    }
 }
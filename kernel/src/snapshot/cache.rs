@@ -0,0 +1,301 @@
+//! A cache of [`Snapshot`]s shared across queries, see [`SnapshotCache`].
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+use url::Url;
+
+use crate::snapshot::SnapshotRef;
+use crate::{DeltaResult, Engine, Snapshot, Version};
+
+/// The default number of snapshots a [`SnapshotCache`] keeps, see
+/// [`SnapshotCache::with_capacity`].
+const DEFAULT_CAPACITY: usize = 64;
+
+/// A cache of [`Snapshot`]s keyed by table root and version, so that services which run many
+/// queries against the same tables can share snapshots instead of replaying the log for each query.
+///
+/// The snapshot of a given version never changes, so pinned versions are cached until they are
+/// evicted to respect the capacity of the cache. The latest version of a table is refreshed once
+/// the snapshot cached for it is older than the cache's time to live: queries for the latest
+/// version may thus see a snapshot that is up to one time to live stale. Snapshots are refreshed
+/// incrementally from the closest older cached snapshot of the same table, see
+/// [`Snapshot::builder_from`].
+///
+/// The cache is safe to share between threads. Snapshots are built without holding the cache's
+/// lock, so concurrent misses for the same snapshot may each build it.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use delta_kernel::snapshot::SnapshotCache;
+/// # use delta_kernel::Engine;
+/// # use url::Url;
+/// # fn example(engine: &dyn Engine) -> delta_kernel::DeltaResult<()> {
+/// let cache = SnapshotCache::new(Duration::from_secs(10));
+/// let table_root = Url::parse("file:///path/to/table/")?;
+///
+/// // the latest snapshot, rebuilt at most every 10 seconds
+/// let latest = cache.snapshot(engine, &table_root, None)?;
+/// // a pinned snapshot, built once
+/// let pinned = cache.snapshot(engine, &table_root, Some(5))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SnapshotCache {
+    latest_ttl: Duration,
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Every cached snapshot, by table root and version
+    snapshots: HashMap<(Url, Version), CachedSnapshot>,
+    /// The latest version of each table, and when it was found to be the latest
+    latest: HashMap<Url, (Version, Instant)>,
+    /// Incremented on every access, to find the least recently used snapshot
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct CachedSnapshot {
+    snapshot: SnapshotRef,
+    last_used: u64,
+}
+
+impl SnapshotCache {
+    /// Create a cache that refreshes the latest snapshot of a table once it is older than
+    /// `latest_ttl`. A zero time to live checks for new versions on every request for the latest
+    /// snapshot, though still updating the cached snapshot incrementally.
+    pub fn new(latest_ttl: Duration) -> Self {
+        Self {
+            latest_ttl,
+            capacity: DEFAULT_CAPACITY,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Set the maximum number of snapshots to keep, across all tables. The least recently used
+    /// snapshots are evicted first. Defaults to 64.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Get the snapshot of the table at `table_root` at `version`, or at its latest version if
+    /// `version` is `None`, building it if it isn't cached (or, for the latest version, if the
+    /// cached snapshot expired).
+    pub fn snapshot(
+        &self,
+        engine: &dyn Engine,
+        table_root: &Url,
+        version: Option<Version>,
+    ) -> DeltaResult<SnapshotRef> {
+        let base = {
+            let mut state = self.lock();
+            let cached_version = match version {
+                Some(version) => Some(version),
+                None => state
+                    .latest
+                    .get(table_root)
+                    .filter(|(_, refreshed)| refreshed.elapsed() < self.latest_ttl)
+                    .map(|(version, _)| *version),
+            };
+            if let Some(snapshot) = cached_version.and_then(|v| state.get(table_root, v)) {
+                return Ok(snapshot);
+            }
+            state.closest_older(table_root, version)
+        };
+
+        let builder = match base {
+            Some(base) => {
+                debug!(
+                    "Updating cached snapshot of {table_root} at version {}",
+                    base.version()
+                );
+                Snapshot::builder_from(base)
+            }
+            None => Snapshot::builder_for(table_root.clone()),
+        };
+        let snapshot = match version {
+            Some(version) => builder.at_version(version),
+            None => builder,
+        }
+        .build(engine)?;
+
+        let mut state = self.lock();
+        if version.is_none() {
+            state
+                .latest
+                .insert(table_root.clone(), (snapshot.version(), Instant::now()));
+        }
+        state.insert(table_root.clone(), snapshot.clone(), self.capacity);
+        Ok(snapshot)
+    }
+
+    /// Remove every cached snapshot of the table at `table_root`, e.g. after the table was
+    /// replaced.
+    pub fn invalidate(&self, table_root: &Url) {
+        let mut state = self.lock();
+        state.snapshots.retain(|(root, _), _| root != table_root);
+        state.latest.remove(table_root);
+    }
+
+    /// The number of cached snapshots.
+    pub fn len(&self) -> usize {
+        self.lock().snapshots.len()
+    }
+
+    /// Whether the cache holds no snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The cache's state is consistent between any two statements, so a panic of another thread
+    // holding the lock doesn't invalidate it
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl CacheState {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn get(&mut self, table_root: &Url, version: Version) -> Option<SnapshotRef> {
+        let now = self.tick();
+        let cached = self.snapshots.get_mut(&(table_root.clone(), version))?;
+        cached.last_used = now;
+        Some(cached.snapshot.clone())
+    }
+
+    /// The cached snapshot of the table with the greatest version less than `version` (or the
+    /// greatest version at all, if `version` is `None`), to incrementally update.
+    fn closest_older(&self, table_root: &Url, version: Option<Version>) -> Option<SnapshotRef> {
+        self.snapshots
+            .iter()
+            .filter(|((root, v), _)| {
+                root == table_root && version.is_none_or(|version| *v < version)
+            })
+            .max_by_key(|((_, v), _)| *v)
+            .map(|(_, cached)| cached.snapshot.clone())
+    }
+
+    fn insert(&mut self, table_root: Url, snapshot: SnapshotRef, capacity: usize) {
+        let last_used = self.tick();
+        let cached = CachedSnapshot {
+            snapshot: snapshot.clone(),
+            last_used,
+        };
+        self.snapshots
+            .insert((table_root, snapshot.version()), cached);
+        while self.snapshots.len() > capacity {
+            let Some(key) = self
+                .snapshots
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.snapshots.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use test_utils::{actions_to_string, add_commit, TestAction};
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    async fn setup() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let actions = vec![TestAction::Metadata, TestAction::Add("a.parquet".into())];
+        add_commit(store.as_ref(), 0, actions_to_string(actions))
+            .await
+            .unwrap();
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (store, engine, Url::parse("memory:///").unwrap())
+    }
+
+    async fn append(store: &InMemory, version: Version) {
+        let actions = vec![TestAction::Add(format!("{version}.parquet"))];
+        add_commit(store, version, actions_to_string(actions))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pinned_snapshots_are_shared() {
+        let (store, engine, table_root) = setup().await;
+        append(&store, 1).await;
+        let cache = SnapshotCache::new(Duration::ZERO);
+
+        let first = cache.snapshot(&engine, &table_root, Some(0)).unwrap();
+        let second = cache.snapshot(&engine, &table_root, Some(0)).unwrap();
+        assert_eq!(first.version(), 0);
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let newer = cache.snapshot(&engine, &table_root, Some(1)).unwrap();
+        assert_eq!(newer.version(), 1);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_latest_snapshot_refreshes_after_ttl() {
+        let (store, engine, table_root) = setup().await;
+
+        // a latest snapshot that never expires isn't refreshed
+        let cache = SnapshotCache::new(Duration::from_secs(3600));
+        let latest = cache.snapshot(&engine, &table_root, None).unwrap();
+        assert_eq!(latest.version(), 0);
+        append(&store, 1).await;
+        let cached = cache.snapshot(&engine, &table_root, None).unwrap();
+        assert!(Arc::ptr_eq(&latest, &cached));
+        // but the new version can still be pinned
+        let pinned = cache.snapshot(&engine, &table_root, Some(1)).unwrap();
+        assert_eq!(pinned.version(), 1);
+
+        // an expired latest snapshot is refreshed
+        let cache = SnapshotCache::new(Duration::ZERO);
+        let latest = cache.snapshot(&engine, &table_root, None).unwrap();
+        assert_eq!(latest.version(), 1);
+        append(&store, 2).await;
+        let latest = cache.snapshot(&engine, &table_root, None).unwrap();
+        assert_eq!(latest.version(), 2);
+        // the refreshed snapshot can be reused as a pinned one
+        let pinned = cache.snapshot(&engine, &table_root, Some(2)).unwrap();
+        assert!(Arc::ptr_eq(&latest, &pinned));
+    }
+
+    #[tokio::test]
+    async fn test_eviction_and_invalidation() {
+        let (store, engine, table_root) = setup().await;
+        append(&store, 1).await;
+        append(&store, 2).await;
+        let cache = SnapshotCache::new(Duration::ZERO).with_capacity(2);
+
+        let v0 = cache.snapshot(&engine, &table_root, Some(0)).unwrap();
+        cache.snapshot(&engine, &table_root, Some(1)).unwrap();
+        // use version 0 again so that version 1 is the least recently used
+        cache.snapshot(&engine, &table_root, Some(0)).unwrap();
+        cache.snapshot(&engine, &table_root, Some(2)).unwrap();
+        assert_eq!(cache.len(), 2);
+        let cached = cache.snapshot(&engine, &table_root, Some(0)).unwrap();
+        assert!(Arc::ptr_eq(&v0, &cached));
+
+        cache.invalidate(&table_root);
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! A serializable description of a [`Snapshot`], see [`SnapshotDescriptor`].
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::actions::{Metadata, Protocol};
+use crate::listed_log_files::ListedLogFiles;
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::schema::SchemaLimits;
+use crate::table_configuration::TableConfiguration;
+use crate::{DeltaResult, Error, FileMeta, FileSize, Version};
+
+use super::Snapshot;
+
+/// Everything needed to rebuild a [`Snapshot`] without reading the table's log: the files of its
+/// log segment, and its protocol and metadata.
+///
+/// Distributed engines can build a snapshot once on the driver, and ship its descriptor (in any
+/// [serde] format) to the executors, which rebuild the snapshot with
+/// [`Snapshot::from_descriptor`] instead of each listing and replaying the log themselves.
+///
+/// ```no_run
+/// # use delta_kernel::snapshot::SnapshotDescriptor;
+/// # use delta_kernel::{Engine, Snapshot};
+/// # use url::Url;
+/// # fn example(engine: &dyn Engine) -> delta_kernel::DeltaResult<()> {
+/// // on the driver
+/// let table_root = Url::parse("file:///path/to/table")?;
+/// let snapshot = Snapshot::builder_for(table_root).build(engine)?;
+/// let json = serde_json::to_string(&snapshot.descriptor())?;
+///
+/// // on an executor
+/// let descriptor: SnapshotDescriptor = serde_json::from_str(&json)?;
+/// let snapshot = Snapshot::from_descriptor(descriptor)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDescriptor {
+    table_root: String,
+    version: Version,
+    commit_files: Vec<LogFile>,
+    compaction_files: Vec<LogFile>,
+    checkpoint_parts: Vec<LogFile>,
+    latest_crc_file: Option<LogFile>,
+    protocol: Protocol,
+    metadata: Metadata,
+    schema_limits: SchemaLimits,
+}
+
+/// A file of a snapshot's log segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogFile {
+    location: String,
+    last_modified: i64,
+    size: FileSize,
+}
+
+impl LogFile {
+    fn new(path: &ParsedLogPath) -> Self {
+        Self {
+            location: path.location.location.to_string(),
+            last_modified: path.location.last_modified,
+            size: path.location.size,
+        }
+    }
+
+    fn parse(&self) -> DeltaResult<ParsedLogPath> {
+        let location = Url::parse(&self.location)?;
+        let file = FileMeta::new(location, self.last_modified, self.size);
+        ParsedLogPath::try_from(file)?
+            .ok_or_else(|| Error::invalid_log_path(self.location.as_str()))
+    }
+}
+
+fn log_files(paths: &[ParsedLogPath]) -> Vec<LogFile> {
+    paths.iter().map(LogFile::new).collect()
+}
+
+fn parse_log_files(files: &[LogFile]) -> DeltaResult<Vec<ParsedLogPath>> {
+    files.iter().map(LogFile::parse).collect()
+}
+
+impl SnapshotDescriptor {
+    /// The version of the described snapshot.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    pub(super) fn new(snapshot: &Snapshot) -> Self {
+        let log_segment = &snapshot.log_segment;
+        let table_configuration = &snapshot.table_configuration;
+        Self {
+            table_root: table_configuration.table_root().to_string(),
+            version: snapshot.version(),
+            commit_files: log_files(&log_segment.ascending_commit_files),
+            compaction_files: log_files(&log_segment.ascending_compaction_files),
+            checkpoint_parts: log_files(&log_segment.checkpoint_parts),
+            latest_crc_file: log_segment.latest_crc_file.as_ref().map(LogFile::new),
+            protocol: table_configuration.protocol().clone(),
+            metadata: table_configuration.metadata().clone(),
+            schema_limits: table_configuration.schema_limits(),
+        }
+    }
+
+    /// Rebuilds the described snapshot, validating it like a snapshot read from the log.
+    pub(super) fn into_snapshot(self) -> DeltaResult<Snapshot> {
+        let table_root = Url::parse(&self.table_root)?;
+        let listed_files = ListedLogFiles {
+            ascending_commit_files: parse_log_files(&self.commit_files)?,
+            ascending_compaction_files: parse_log_files(&self.compaction_files)?,
+            checkpoint_parts: parse_log_files(&self.checkpoint_parts)?,
+            latest_crc_file: self
+                .latest_crc_file
+                .as_ref()
+                .map(LogFile::parse)
+                .transpose()?,
+        };
+        let log_root = table_root.join("_delta_log/")?;
+        let log_segment = LogSegment::try_new(listed_files, log_root, Some(self.version))?;
+        let table_configuration = TableConfiguration::try_new_with_schema_limits(
+            self.metadata,
+            self.protocol,
+            table_root,
+            self.version,
+            self.schema_limits,
+        )?;
+        Ok(Snapshot::new(log_segment, table_configuration))
+    }
+}
@@ -0,0 +1,142 @@
+//! A read-only view of the log files a [`Snapshot`] is built from, see [`LogSegmentView`].
+//!
+//! [`Snapshot`]: super::Snapshot
+
+use std::ops::RangeInclusive;
+
+use url::Url;
+
+use crate::log_segment::LogSegment;
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::{FileMeta, Version};
+
+/// The kind of a file in a snapshot's log segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LogFileKind {
+    /// A published commit, e.g. `00000000000000000001.json`
+    Commit,
+    /// A staged commit of a catalog-managed table, in `_delta_log/_staged_commits/`
+    StagedCommit,
+    /// A part of a checkpoint. Classic and UUID-named (V2) checkpoints have a single part.
+    Checkpoint { part: u32, num_parts: u32 },
+    /// A log compaction file, which aggregates the commits from its version to `end_version`
+    CompactedCommit { end_version: Version },
+    /// A checksum (CRC) file
+    Crc,
+}
+
+/// A file of a snapshot's log segment.
+#[derive(Debug, Clone, Copy)]
+pub struct LogFile<'a> {
+    path: &'a ParsedLogPath,
+}
+
+impl<'a> LogFile<'a> {
+    /// The version of the file. For compaction files, this is the first version they cover.
+    pub fn version(&self) -> Version {
+        self.path.version
+    }
+
+    /// The kind of the file.
+    pub fn kind(&self) -> LogFileKind {
+        match &self.path.file_type {
+            LogPathFileType::Commit => LogFileKind::Commit,
+            LogPathFileType::StagedCommit => LogFileKind::StagedCommit,
+            LogPathFileType::MultiPartCheckpoint {
+                part_num,
+                num_parts,
+            } => LogFileKind::Checkpoint {
+                part: *part_num,
+                num_parts: *num_parts,
+            },
+            LogPathFileType::CompactedCommit { hi } => {
+                LogFileKind::CompactedCommit { end_version: *hi }
+            }
+            LogPathFileType::Crc => LogFileKind::Crc,
+            // log segments only ever contain the file types above
+            LogPathFileType::SinglePartCheckpoint
+            | LogPathFileType::UuidCheckpoint(_)
+            | LogPathFileType::Unknown => LogFileKind::Checkpoint {
+                part: 1,
+                num_parts: 1,
+            },
+        }
+    }
+
+    /// The location, size and modification time of the file.
+    pub fn file(&self) -> &'a FileMeta {
+        &self.path.location
+    }
+}
+
+/// A read-only view of the log segment of a [`Snapshot`]: the checkpoint, compaction and commit
+/// files that the kernel reads to build the snapshot's state, see [`Snapshot::log_segment_view`].
+///
+/// Engines can use it to implement caching, prefetching or metrics around the IO of log files.
+/// The view is stable across releases, unlike the kernel's internal representation of log
+/// segments.
+///
+/// [`Snapshot`]: super::Snapshot
+/// [`Snapshot::log_segment_view`]: super::Snapshot::log_segment_view
+#[derive(Debug, Clone, Copy)]
+pub struct LogSegmentView<'a> {
+    log_segment: &'a LogSegment,
+}
+
+impl<'a> LogSegmentView<'a> {
+    pub(super) fn new(log_segment: &'a LogSegment) -> Self {
+        Self { log_segment }
+    }
+
+    /// The URL of the table's `_delta_log` directory.
+    pub fn log_root(&self) -> &'a Url {
+        &self.log_segment.log_root
+    }
+
+    /// The version of the snapshot, i.e. the last version the log segment covers.
+    pub fn end_version(&self) -> Version {
+        self.log_segment.end_version
+    }
+
+    /// The version of the checkpoint the log segment starts from, if any.
+    pub fn checkpoint_version(&self) -> Option<Version> {
+        self.log_segment.checkpoint_version
+    }
+
+    /// The versions the log segment covers: from its checkpoint (or first commit) to its end
+    /// version.
+    pub fn versions(&self) -> RangeInclusive<Version> {
+        let first_commit = self.log_segment.ascending_commit_files.first();
+        let start = self
+            .checkpoint_version()
+            .or(first_commit.map(|commit| commit.version))
+            .unwrap_or(self.end_version());
+        start..=self.end_version()
+    }
+
+    /// The parts of the checkpoint the log segment starts from, if any, in part order.
+    pub fn checkpoint_parts(&self) -> impl ExactSizeIterator<Item = LogFile<'a>> {
+        files(&self.log_segment.checkpoint_parts)
+    }
+
+    /// The commit files after the checkpoint, in ascending version order.
+    pub fn commit_files(&self) -> impl ExactSizeIterator<Item = LogFile<'a>> {
+        files(&self.log_segment.ascending_commit_files)
+    }
+
+    /// The log compaction files after the checkpoint, in ascending order of their first version.
+    pub fn compaction_files(&self) -> impl ExactSizeIterator<Item = LogFile<'a>> {
+        files(&self.log_segment.ascending_compaction_files)
+    }
+
+    /// The latest checksum (CRC) file of the log segment, if any.
+    pub fn latest_crc_file(&self) -> Option<LogFile<'a>> {
+        let path = self.log_segment.latest_crc_file.as_ref()?;
+        Some(LogFile { path })
+    }
+}
+
+fn files(paths: &[ParsedLogPath]) -> impl ExactSizeIterator<Item = LogFile<'_>> {
+    paths.iter().map(|path| LogFile { path })
+}
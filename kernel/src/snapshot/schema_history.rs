@@ -0,0 +1,216 @@
+//! Access to the schemas a table had at past versions, see [`Snapshot::schema_at`] and
+//! [`Snapshot::schema_history`].
+
+use std::sync::{Arc, LazyLock};
+
+use url::Url;
+
+use crate::actions::{get_log_schema, Metadata, METADATA_NAME};
+use crate::log_segment::LogSegment;
+use crate::schema::SchemaRef;
+use crate::{DeltaResult, Engine, Error, Expression, PredicateRef, Version};
+
+use super::Snapshot;
+
+/// The schema of a table from `version` on, until the next [`SchemaVersion`] of a
+/// [`Snapshot::schema_history`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaVersion {
+    version: Version,
+    schema: SchemaRef,
+}
+
+impl SchemaVersion {
+    /// The first version with this schema.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The schema of the table.
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+}
+
+impl Snapshot {
+    /// The schema of the table at `table_root` at `version`.
+    ///
+    /// This only replays the log for the table's metadata, like building a snapshot does, but
+    /// skips validating the table's protocol and configuration, so it also works for versions of
+    /// the table that this kernel can't read.
+    pub fn schema_at(
+        engine: &dyn Engine,
+        table_root: Url,
+        version: Version,
+    ) -> DeltaResult<SchemaRef> {
+        let log_segment = LogSegment::for_snapshot(
+            engine.storage_handler().as_ref(),
+            table_root.join("_delta_log/")?,
+            vec![],
+            version,
+        )?;
+        match log_segment.protocol_and_metadata(engine)? {
+            (Some(metadata), _) => Ok(Arc::new(metadata.parse_schema()?)),
+            (None, _) => Err(Error::MissingMetadata),
+        }
+    }
+
+    /// The schemas of the table over the versions of this snapshot's log segment, in ascending
+    /// version order: each [`SchemaVersion`] gives the schema from its version on, until the next
+    /// one. The last schema is the schema of this snapshot.
+    ///
+    /// The history starts at the version of the log segment's checkpoint (or version 0 without
+    /// one), since the commits before it are not part of the log segment. Use
+    /// [`Snapshot::schema_at`] for older versions. Commits that change the table's metadata but
+    /// not its schema, e.g. to set a table property, are not part of the history.
+    pub fn schema_history(&self, engine: &dyn Engine) -> DeltaResult<Vec<SchemaVersion>> {
+        let log_segment = self.log_segment();
+        let mut history: Vec<SchemaVersion> = vec![];
+        if let Some(checkpoint_version) = log_segment.checkpoint_version {
+            let checkpoint = LogSegment {
+                end_version: checkpoint_version,
+                checkpoint_version: Some(checkpoint_version),
+                log_root: log_segment.log_root.clone(),
+                ascending_commit_files: vec![],
+                ascending_compaction_files: vec![],
+                checkpoint_parts: log_segment.checkpoint_parts.clone(),
+                latest_crc_file: None,
+            };
+            let (metadata, _) = checkpoint.protocol_and_metadata(engine)?;
+            let metadata = metadata.ok_or(Error::MissingMetadata)?;
+            history.push(SchemaVersion {
+                version: checkpoint_version,
+                schema: Arc::new(metadata.parse_schema()?),
+            });
+        }
+
+        // only read the metadata of the commits that have one
+        static METADATA_PREDICATE: LazyLock<Option<PredicateRef>> = LazyLock::new(|| {
+            Some(Arc::new(
+                Expression::column([METADATA_NAME, "id"]).is_not_null(),
+            ))
+        });
+        let read_schema = get_log_schema().project(&[METADATA_NAME])?;
+        for commit in &log_segment.ascending_commit_files {
+            let batches = engine.json_handler().read_json_files(
+                std::slice::from_ref(&commit.location),
+                read_schema.clone(),
+                METADATA_PREDICATE.clone(),
+            )?;
+            let mut metadata = None;
+            for batch in batches {
+                if let Some(m) = Metadata::try_new_from_data(batch?.as_ref())? {
+                    metadata = Some(m);
+                }
+            }
+            let Some(metadata) = metadata else {
+                continue;
+            };
+            let schema = Arc::new(metadata.parse_schema()?);
+            if history.last().is_none_or(|last| last.schema != schema) {
+                history.push(SchemaVersion {
+                    version: commit.version,
+                    schema,
+                });
+            }
+        }
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use object_store::memory::InMemory;
+    use serde_json::json;
+    use test_utils::add_commit;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::engine::sync::SyncEngine;
+    use crate::schema::{DataType, StructField, StructType};
+
+    fn metadata_action(schema: &StructType, configuration: serde_json::Value) -> String {
+        json!({
+            "metaData": {
+                "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(schema).unwrap(),
+                "partitionColumns": [],
+                "configuration": configuration,
+                "createdTime": 1587968585495i64
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_schema_history() {
+        let store = Arc::new(InMemory::new());
+        let v0_schema = StructType::new_unchecked([StructField::nullable("id", DataType::INTEGER)]);
+        let v2_schema = StructType::new_unchecked([
+            StructField::nullable("id", DataType::INTEGER),
+            StructField::nullable("name", DataType::STRING),
+        ]);
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let commits = [
+            format!("{protocol}\n{}", metadata_action(&v0_schema, json!({}))),
+            json!({"commitInfo": {"operation": "WRITE"}}).to_string(),
+            metadata_action(&v2_schema, json!({})),
+            // a metadata change that doesn't change the schema
+            metadata_action(&v2_schema, json!({"delta.appendOnly": "true"})),
+        ];
+        for (version, commit) in commits.into_iter().enumerate() {
+            add_commit(store.as_ref(), version as Version, commit)
+                .await
+                .unwrap();
+        }
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+
+        let snapshot = Snapshot::builder_for(table_root.clone())
+            .build(&engine)
+            .unwrap();
+        let history = snapshot.schema_history(&engine).unwrap();
+        let versions: Vec<_> = history.iter().map(SchemaVersion::version).collect();
+        assert_eq!(versions, vec![0, 2]);
+        assert_eq!(**history[0].schema(), v0_schema);
+        assert_eq!(history[1].schema(), &snapshot.schema());
+
+        let schema = Snapshot::schema_at(&engine, table_root.clone(), 1).unwrap();
+        assert_eq!(*schema, v0_schema);
+        let schema = Snapshot::schema_at(&engine, table_root, 3).unwrap();
+        assert_eq!(*schema, v2_schema);
+    }
+
+    #[test]
+    fn test_schema_history_from_checkpoint() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/with_checkpoint_no_last_checkpoint/",
+        ))
+        .unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        // the history starts at the checkpoint of version 2
+        let snapshot = Snapshot::builder_for(url.clone()).build(&engine).unwrap();
+        let history = snapshot.schema_history(&engine).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].version(), 2);
+        assert_eq!(history[0].schema(), &snapshot.schema());
+
+        let snapshot = Snapshot::builder_for(url.clone())
+            .at_version(1)
+            .build(&engine)
+            .unwrap();
+        let history = snapshot.schema_history(&engine).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].version(), 0);
+        assert_eq!(
+            Snapshot::schema_at(&engine, url, 1).unwrap(),
+            snapshot.schema()
+        );
+    }
+}
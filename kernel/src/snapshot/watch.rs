@@ -0,0 +1,198 @@
+//! Waiting for new versions of a table, see [`Snapshot::wait_for_version`].
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+use url::Url;
+
+use crate::{DeltaResult, Engine, Version};
+
+use super::{Snapshot, SnapshotRef};
+
+/// How often [`PollingNotifier::default`] checks for new versions.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Timeouts are capped to this, so that the deadline of a wait never overflows an [`Instant`].
+const MAX_TIMEOUT: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Tells [`Snapshot::wait_for_version_with`] when a table may have new versions.
+///
+/// The default [`PollingNotifier`] just waits a fixed interval between checks of the table.
+/// Engines whose stores can notify them of new objects (e.g. through bucket notifications) can
+/// implement this trait to check the table only once a new commit was written.
+pub trait ChangeNotifier: Send + Sync {
+    /// Blocks until the table at `table_root` may have a version newer than `version`, or until
+    /// `deadline`, whichever comes first. Returning early is harmless: the table is checked for new
+    /// versions after every return, and this is called again if there are none.
+    fn wait_for_change(
+        &self,
+        table_root: &Url,
+        version: Version,
+        deadline: Instant,
+    ) -> DeltaResult<()>;
+}
+
+/// A [`ChangeNotifier`] that checks the table for new versions at a fixed interval.
+#[derive(Debug, Clone)]
+pub struct PollingNotifier {
+    interval: Duration,
+}
+
+impl PollingNotifier {
+    /// Create a notifier that checks the table every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+/// Checks the table every second.
+impl Default for PollingNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_POLL_INTERVAL)
+    }
+}
+
+impl ChangeNotifier for PollingNotifier {
+    fn wait_for_change(&self, _: &Url, _: Version, deadline: Instant) -> DeltaResult<()> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        std::thread::sleep(self.interval.min(remaining));
+        Ok(())
+    }
+}
+
+impl Snapshot {
+    /// Wait until the table has reached at least `version`, for up to `timeout`. Returns the
+    /// snapshot of the latest version of the table once it has, or `None` if it didn't within the
+    /// timeout. The table is checked for new versions every second, see
+    /// [`Self::wait_for_version_with`] to check it differently.
+    ///
+    /// This lets streaming readers wait for the next version of a table without writing their own
+    /// polling loop:
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use delta_kernel::{DeltaResult, Engine, Snapshot};
+    /// # use url::Url;
+    /// # fn example(engine: &dyn Engine, table_root: Url) -> DeltaResult<()> {
+    /// let mut snapshot = Snapshot::builder_for(table_root).build(engine)?;
+    /// loop {
+    ///     let next_version = snapshot.version() + 1;
+    ///     let timeout = Duration::from_secs(60);
+    ///     if let Some(newer) = snapshot.clone().wait_for_version(engine, next_version, timeout)? {
+    ///         // process the new versions
+    ///         snapshot = newer;
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn wait_for_version(
+        self: Arc<Self>,
+        engine: &dyn Engine,
+        version: Version,
+        timeout: Duration,
+    ) -> DeltaResult<Option<SnapshotRef>> {
+        self.wait_for_version_with(engine, version, timeout, &PollingNotifier::default())
+    }
+
+    /// Like [`Self::wait_for_version`], but checks the table for new versions whenever `notifier`
+    /// says it may have changed. The snapshot is updated incrementally on every check, see
+    /// [`Snapshot::builder_from`].
+    pub fn wait_for_version_with(
+        self: Arc<Self>,
+        engine: &dyn Engine,
+        version: Version,
+        timeout: Duration,
+        notifier: &dyn ChangeNotifier,
+    ) -> DeltaResult<Option<SnapshotRef>> {
+        let deadline = Instant::now() + timeout.min(MAX_TIMEOUT);
+        let mut snapshot = self;
+        loop {
+            if snapshot.version() >= version {
+                return Ok(Some(snapshot));
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            notifier.wait_for_change(snapshot.table_root(), snapshot.version(), deadline)?;
+            debug!(
+                "Checking {} for versions after {}",
+                snapshot.table_root(),
+                snapshot.version()
+            );
+            snapshot = Snapshot::builder_from(snapshot).build(engine)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use object_store::memory::InMemory;
+    use test_utils::{actions_to_string, add_commit, TestAction};
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    async fn append(store: &InMemory, version: Version) {
+        let actions = match version {
+            0 => vec![TestAction::Metadata],
+            _ => vec![TestAction::Add(format!("{version}.parquet"))],
+        };
+        add_commit(store, version, actions_to_string(actions))
+            .await
+            .unwrap();
+    }
+
+    /// Commits the next version of the table whenever it is waited on.
+    struct CommitOnWait {
+        store: Arc<InMemory>,
+        waits: AtomicUsize,
+    }
+
+    impl ChangeNotifier for CommitOnWait {
+        fn wait_for_change(&self, _: &Url, version: Version, _: Instant) -> DeltaResult<()> {
+            self.waits.fetch_add(1, Ordering::Relaxed);
+            futures::executor::block_on(append(&self.store, version + 1));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_version() {
+        let store = Arc::new(InMemory::new());
+        append(&store, 0).await;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+
+        // a version the table already has is returned right away
+        let waited = snapshot
+            .clone()
+            .wait_for_version(&engine, 0, Duration::ZERO)
+            .unwrap();
+        assert!(waited.is_some_and(|waited| Arc::ptr_eq(&waited, &snapshot)));
+
+        // a version the table doesn't reach within the timeout isn't
+        let notifier = PollingNotifier::new(Duration::from_millis(10));
+        let waited = snapshot
+            .clone()
+            .wait_for_version_with(&engine, 1, Duration::from_millis(50), &notifier)
+            .unwrap();
+        assert!(waited.is_none());
+
+        // the table is checked whenever the notifier says it may have changed
+        let notifier = CommitOnWait {
+            store,
+            waits: AtomicUsize::new(0),
+        };
+        let waited = snapshot
+            .wait_for_version_with(&engine, 2, Duration::from_secs(60), &notifier)
+            .unwrap()
+            .unwrap();
+        assert_eq!(waited.version(), 2);
+        assert_eq!(notifier.waits.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,87 @@
+//! Utilities for engines that collect file statistics on write.
+//!
+//! Writers truncate the `minValues` and `maxValues` of string columns to a prefix of
+//! `delta.dataSkippingStringPrefixLength` characters (32 by default), so long values don't bloat
+//! the log. Truncation must keep the stats valid bounds: a truncated min is a prefix of the actual
+//! min and thus never greater than it, but a truncated max needs a tie-breaker to stay greater than
+//! or equal to the actual max. [`truncate_min_string`] and [`truncate_max_string`] implement the
+//! same rules as Delta Spark, see [`WriteContext::stats_string_prefix_length`] for the length to
+//! use when writing to a table.
+//!
+//! [`WriteContext::stats_string_prefix_length`]: crate::transaction::WriteContext::stats_string_prefix_length
+
+/// The number of characters string min/max stats are truncated to if the table does not set
+/// `delta.dataSkippingStringPrefixLength`.
+pub const DEFAULT_STRING_PREFIX_LENGTH: usize = 32;
+
+/// The character appended to a truncated max to keep it greater than the values it bounds. Like
+/// Delta Spark, we use the largest character of the basic multilingual plane that is not reserved.
+const MAX_TIE_BREAKER: char = '\u{FFFD}';
+
+/// Truncates the `minValues` stat of a string column to its first `prefix_length` characters.
+pub fn truncate_min_string(value: &str, prefix_length: usize) -> &str {
+    match value.char_indices().nth(prefix_length) {
+        Some((end, _)) => &value[..end],
+        None => value,
+    }
+}
+
+/// Truncates the `maxValues` stat of a string column to about `prefix_length` characters, or
+/// returns `None` if it cannot be truncated and the writer should omit the stat.
+///
+/// A truncated max is the prefix followed by U+FFFD, which is only greater than the actual max if
+/// the first character cut off is smaller than U+FFFD. Strings compare by their UTF-8 bytes, which
+/// order like their characters. If that character is U+FFFD or larger, the prefix is extended by up
+/// to `prefix_length` more characters to find a smaller one, and the max is dropped otherwise.
+pub fn truncate_max_string(value: &str, prefix_length: usize) -> Option<String> {
+    let mut chars = value.char_indices().skip(prefix_length).peekable();
+    if chars.peek().is_none() {
+        return Some(value.to_string());
+    }
+    chars
+        .take(prefix_length.max(1))
+        .find(|&(_, c)| c < MAX_TIE_BREAKER)
+        .map(|(end, _)| format!("{}{MAX_TIE_BREAKER}", &value[..end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_min_string() {
+        assert_eq!(truncate_min_string("abc", 5), "abc");
+        assert_eq!(truncate_min_string("abcdef", 3), "abc");
+        // truncates characters, not bytes
+        assert_eq!(truncate_min_string("äöüß", 2), "äö");
+        assert_eq!(truncate_min_string("abc", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_max_string() {
+        assert_eq!(truncate_max_string("abc", 3).as_deref(), Some("abc"));
+        assert_eq!(
+            truncate_max_string("abcdef", 3).as_deref(),
+            Some("abc\u{FFFD}")
+        );
+        assert_eq!(
+            truncate_max_string("äöüß", 2).as_deref(),
+            Some("äö\u{FFFD}")
+        );
+
+        // the prefix is extended past characters that are not smaller than the tie-breaker
+        let value = "ab\u{FFFD}cd";
+        assert_eq!(
+            truncate_max_string(value, 2).as_deref(),
+            Some("ab\u{FFFD}\u{FFFD}")
+        );
+        // up to twice the prefix length
+        assert_eq!(truncate_max_string("ab\u{FFFD}\u{1F600}", 2), None);
+        assert_eq!(truncate_max_string("ab\u{FFFD}\u{FFFD}c", 2), None);
+
+        for (value, prefix_length) in [("abcdef", 3), ("äöüß", 2), (value, 2), ("a", 0)] {
+            let max = truncate_max_string(value, prefix_length).unwrap();
+            assert!(max.as_str() >= value, "{max:?} < {value:?}");
+        }
+    }
+}
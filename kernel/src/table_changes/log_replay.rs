@@ -56,7 +56,7 @@ pub(crate) fn table_changes_action_iter(
     table_schema: SchemaRef,
     physical_predicate: Option<(PredicateRef, SchemaRef)>,
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
-    let filter = DataSkippingFilter::new(engine.as_ref(), physical_predicate).map(Arc::new);
+    let filter = DataSkippingFilter::new(engine.as_ref(), physical_predicate, None).map(Arc::new);
     let result = commit_files
         .into_iter()
         .map(move |commit_file| -> DeltaResult<_> {
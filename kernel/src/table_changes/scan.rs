@@ -10,6 +10,7 @@ use crate::actions::deletion_vector::split_vector;
 use crate::scan::{PhysicalPredicate, ScanResult};
 use crate::schema::{SchemaRef, StructType};
 use crate::transforms::ColumnType;
+use crate::uri::resolve_file_path;
 use crate::{DeltaResult, Engine, FileMeta, PredicateRef};
 
 use super::log_replay::{table_changes_action_iter, TableChangesScanMetadata};
@@ -299,7 +300,7 @@ fn read_scan_file(
     // Determine if the scan file was derived from a deletion vector pair
     let is_dv_resolved_pair = scan_file.remove_dv.is_some();
 
-    let location = table_root.join(&scan_file.path)?;
+    let location = resolve_file_path(table_root, &scan_file.path)?;
     let file = FileMeta {
         last_modified: 0,
         size: 0,
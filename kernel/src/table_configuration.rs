@@ -14,10 +14,11 @@ use url::Url;
 
 use crate::actions::{ensure_supported_features, Metadata, Protocol};
 use crate::schema::variant_utils::validate_variant_type_feature_support;
-use crate::schema::{InvariantChecker, SchemaRef};
+use crate::schema::{InvariantChecker, SchemaLimits, SchemaRef};
 use crate::table_features::{
     column_mapping_mode, validate_schema_column_mapping, validate_timestamp_ntz_feature_support,
-    ColumnMappingMode, ReaderFeature, WriterFeature,
+    ColumnMappingMode, FeatureOperation, KernelCapability, ReaderFeature, UnsupportedFeature,
+    WriterFeature,
 };
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error, Version};
@@ -43,6 +44,7 @@ pub(crate) struct TableConfiguration {
     column_mapping_mode: ColumnMappingMode,
     table_root: Url,
     version: Version,
+    schema_limits: SchemaLimits,
 }
 
 impl TableConfiguration {
@@ -71,10 +73,29 @@ impl TableConfiguration {
         protocol: Protocol,
         table_root: Url,
         version: Version,
+    ) -> DeltaResult<Self> {
+        Self::try_new_with_schema_limits(
+            metadata,
+            protocol,
+            table_root,
+            version,
+            SchemaLimits::default(),
+        )
+    }
+
+    /// Like [`TableConfiguration::try_new`], but returns an error if the table schema exceeds
+    /// `schema_limits`. The limits are retained, and also apply to configurations derived from
+    /// this one with [`TableConfiguration::try_new_from`].
+    pub(crate) fn try_new_with_schema_limits(
+        metadata: Metadata,
+        protocol: Protocol,
+        table_root: Url,
+        version: Version,
+        schema_limits: SchemaLimits,
     ) -> DeltaResult<Self> {
         protocol.ensure_read_supported()?;
 
-        let schema = Arc::new(metadata.parse_schema()?);
+        let schema = Arc::new(metadata.parse_schema_with_limits(&schema_limits)?);
         let table_properties = metadata.parse_table_properties();
         let column_mapping_mode = column_mapping_mode(&protocol, &table_properties);
 
@@ -93,6 +114,7 @@ impl TableConfiguration {
             column_mapping_mode,
             table_root,
             version,
+            schema_limits,
         })
     }
 
@@ -113,11 +135,12 @@ impl TableConfiguration {
         // note that while we could pick apart the protocol/metadata updates and validate them
         // individually, instead we just re-parse so that we can recycle the try_new validation
         // (instead of duplicating it here).
-        Self::try_new(
+        Self::try_new_with_schema_limits(
             new_metadata.unwrap_or_else(|| table_configuration.metadata.clone()),
             new_protocol.unwrap_or_else(|| table_configuration.protocol.clone()),
             table_configuration.table_root.clone(),
             new_version,
+            table_configuration.schema_limits,
         )
     }
 
@@ -163,6 +186,11 @@ impl TableConfiguration {
         self.version
     }
 
+    /// The [`SchemaLimits`] the table schema was checked against.
+    pub(crate) fn schema_limits(&self) -> SchemaLimits {
+        self.schema_limits
+    }
+
     /// Returns `true` if the kernel supports writing to this table. This checks that the
     /// protocol's writer features are all supported.
     #[internal_api]
@@ -174,9 +202,11 @@ impl TableConfiguration {
         if self.is_invariants_supported()
             && InvariantChecker::has_invariants(self.schema().as_ref())
         {
-            return Err(Error::unsupported(
-                "Column invariants are not yet supported",
-            ));
+            return Err(Error::UnsupportedFeatures(vec![UnsupportedFeature::new(
+                WriterFeature::Invariants,
+                FeatureOperation::Write,
+                KernelCapability::FeatureImplementation,
+            )]));
         }
 
         // Fail if row tracking is both enabled and suspended
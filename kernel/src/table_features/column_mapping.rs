@@ -2,12 +2,14 @@
 use super::ReaderFeature;
 use crate::actions::Protocol;
 use crate::schema::{
-    ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField, StructType,
+    ColumnMetadataKey, ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField,
+    StructType,
 };
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error};
 
 use std::borrow::Cow;
+use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
@@ -160,6 +162,101 @@ impl<'a> SchemaTransform<'a> for ValidateColumnMappings<'a> {
     }
 }
 
+/// Translates a logical column path into the physical path used in parquet files. Every element of
+/// the path must name a (possibly nested) struct field.
+pub(crate) fn logical_to_physical_column(
+    schema: &StructType,
+    column: &ColumnName,
+) -> DeltaResult<ColumnName> {
+    let fields = resolve_column_path(schema, column.path(), |field, name| field.name() == name)?;
+    Ok(ColumnName::new(fields.iter().map(|f| f.physical_name())))
+}
+
+/// Translates a physical column path, as found in parquet files, back into its logical path.
+pub(crate) fn physical_to_logical_column(
+    schema: &StructType,
+    column: &ColumnName,
+) -> DeltaResult<ColumnName> {
+    let fields = resolve_column_path(schema, column.path(), |field, name| {
+        field.physical_name() == name
+    })?;
+    Ok(ColumnName::new(fields.iter().map(|f| f.name())))
+}
+
+/// Returns the column mapping field ids of each element of a logical column path.
+pub(crate) fn logical_column_field_ids(
+    schema: &StructType,
+    column: &ColumnName,
+    mode: ColumnMappingMode,
+) -> DeltaResult<Vec<i64>> {
+    require_column_mapping(mode)?;
+    let fields = resolve_column_path(schema, column.path(), |field, name| field.name() == name)?;
+    fields.into_iter().map(field_id).collect()
+}
+
+/// Translates a path of column mapping field ids back into its logical column path.
+pub(crate) fn field_ids_to_logical_column(
+    schema: &StructType,
+    field_ids: &[i64],
+    mode: ColumnMappingMode,
+) -> DeltaResult<ColumnName> {
+    require_column_mapping(mode)?;
+    let fields = resolve_column_path(schema, field_ids, |field, id| {
+        field_id(field).is_ok_and(|field_id| field_id == *id)
+    })?;
+    Ok(ColumnName::new(fields.iter().map(|f| f.name())))
+}
+
+fn require_column_mapping(mode: ColumnMappingMode) -> DeltaResult<()> {
+    match mode {
+        ColumnMappingMode::None => Err(Error::invalid_column_mapping_mode(
+            "Field ids are only available when column mapping is enabled",
+        )),
+        ColumnMappingMode::Id | ColumnMappingMode::Name => Ok(()),
+    }
+}
+
+fn field_id(field: &StructField) -> DeltaResult<i64> {
+    match field.get_config_value(&ColumnMetadataKey::ColumnMappingId) {
+        Some(MetadataValue::Number(id)) => Ok(*id),
+        _ => Err(Error::invalid_column_mapping_mode(format!(
+            "Field '{}' lacks a valid {} annotation",
+            field.name(),
+            ColumnMetadataKey::ColumnMappingId.as_ref()
+        ))),
+    }
+}
+
+/// Walks `path` through the nested structs of `schema`, returning the field matched by each path
+/// element. `is_match` decides whether a field is the one a path element refers to.
+fn resolve_column_path<'a, T: Display>(
+    schema: &'a StructType,
+    path: &[T],
+    is_match: impl Fn(&StructField, &T) -> bool,
+) -> DeltaResult<Vec<&'a StructField>> {
+    let mut fields: Vec<&'a StructField> = Vec::with_capacity(path.len());
+    for elem in path {
+        let parent = match fields.last() {
+            None => schema,
+            Some(&field) => match field.data_type() {
+                DataType::Struct(inner) => inner.as_ref(),
+                _ => {
+                    return Err(Error::unexpected_column_type(format!(
+                        "Cannot resolve '{elem}' inside non-struct field '{}'",
+                        field.name()
+                    )))
+                }
+            },
+        };
+        let field = parent
+            .fields()
+            .find(|field| is_match(field, elem))
+            .ok_or_else(|| Error::missing_column(format!("No field matches '{elem}'")))?;
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -354,4 +451,65 @@ mod tests {
         let schema = create_schema(None, None, None, "\"col-5f422f40\"");
         validate_schema_column_mapping(&schema, ColumnMappingMode::None).expect_err("field name");
     }
+
+    #[test]
+    fn test_column_path_translation() {
+        let schema: StructType = serde_json::from_str(
+            r#"{
+            "type": "struct",
+            "fields": [{
+                "name": "a",
+                "type": {
+                    "type": "struct",
+                    "fields": [{
+                        "name": "b",
+                        "type": "integer",
+                        "nullable": true,
+                        "metadata": {
+                            "delta.columnMapping.id": 2,
+                            "delta.columnMapping.physicalName": "col-b"
+                        }
+                    }]
+                },
+                "nullable": true,
+                "metadata": {
+                    "delta.columnMapping.id": 1,
+                    "delta.columnMapping.physicalName": "col-a"
+                }
+            }]
+        }"#,
+        )
+        .unwrap();
+        let logical = ColumnName::new(["a", "b"]);
+        let physical = ColumnName::new(["col-a", "col-b"]);
+
+        assert_eq!(
+            logical_to_physical_column(&schema, &logical).unwrap(),
+            physical
+        );
+        assert_eq!(
+            physical_to_logical_column(&schema, &physical).unwrap(),
+            logical
+        );
+        assert_eq!(
+            logical_column_field_ids(&schema, &logical, ColumnMappingMode::Id).unwrap(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            field_ids_to_logical_column(&schema, &[1, 2], ColumnMappingMode::Name).unwrap(),
+            logical
+        );
+
+        // physical names are not logical names, and vice versa
+        logical_to_physical_column(&schema, &physical).expect_err("not a logical name");
+        physical_to_logical_column(&schema, &logical).expect_err("not a physical name");
+        // paths can't continue past a leaf field
+        logical_to_physical_column(&schema, &ColumnName::new(["a", "b", "c"]))
+            .expect_err("b is not a struct");
+        // field ids require column mapping
+        logical_column_field_ids(&schema, &logical, ColumnMappingMode::None)
+            .expect_err("column mapping disabled");
+        field_ids_to_logical_column(&schema, &[1, 3], ColumnMappingMode::Id)
+            .expect_err("unknown field id");
+    }
 }
@@ -1,3 +1,4 @@
+use std::fmt::{Display, Formatter};
 use std::sync::LazyLock;
 
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,10 @@ use crate::schema::DataType;
 use delta_kernel_derive::internal_api;
 
 pub(crate) use column_mapping::column_mapping_mode;
+pub(crate) use column_mapping::{
+    field_ids_to_logical_column, logical_column_field_ids, logical_to_physical_column,
+    physical_to_logical_column,
+};
 pub use column_mapping::{validate_schema_column_mapping, ColumnMappingMode};
 pub(crate) use timestamp_ntz::validate_timestamp_ntz_feature_support;
 mod column_mapping;
@@ -139,6 +144,8 @@ pub(crate) enum WriterFeature {
     IcebergCompatV1,
     /// Iceberg compatibility support
     IcebergCompatV2,
+    /// Requires log files to be cleaned up together with the checkpoints that protect them
+    CheckpointProtection,
     /// vacuumProtocolCheck ReaderWriter feature ensures consistent application of reader and writer
     /// protocol checks during VACUUM operations
     VacuumProtocolCheck,
@@ -184,6 +191,101 @@ impl From<WriterFeature> for Scalar {
     }
 }
 
+/// An operation on a table that unsupported table features can block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureOperation {
+    Read,
+    Write,
+}
+
+/// The capability the kernel lacks to perform an operation blocked by an [`UnsupportedFeature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KernelCapability {
+    /// Support for this minimum reader protocol version.
+    ReaderVersion(i32),
+    /// Support for this minimum writer protocol version.
+    WriterVersion(i32),
+    /// Knowledge of the feature, which this kernel does not recognize at all. A newer kernel
+    /// version may support it.
+    UnknownFeature,
+    /// An implementation of the feature for the blocked operation. The kernel recognizes the
+    /// feature, but does not support it (or the way the table uses it) yet.
+    FeatureImplementation,
+}
+
+/// A table feature (or protocol version) that blocks an operation on a table because the kernel
+/// does not support it. See [`Error::UnsupportedFeatures`].
+///
+/// [`Error::UnsupportedFeatures`]: crate::Error::UnsupportedFeatures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedFeature {
+    /// The name of the feature as it appears in the table's protocol, e.g. `identityColumns`, or
+    /// `minReaderVersion`/`minWriterVersion` for an unsupported protocol version.
+    pub name: String,
+    /// The operation the feature blocks.
+    pub blocks: FeatureOperation,
+    /// The capability the kernel needs to perform the blocked operation.
+    pub required_capability: KernelCapability,
+}
+
+impl UnsupportedFeature {
+    pub(crate) fn new(
+        name: impl ToString,
+        blocks: FeatureOperation,
+        required_capability: KernelCapability,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            blocks,
+            required_capability,
+        }
+    }
+}
+
+impl Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let blocks = match self.blocks {
+            FeatureOperation::Read => "reads",
+            FeatureOperation::Write => "writes",
+        };
+        write!(f, "{} (blocks {blocks}, requires ", self.name)?;
+        match self.required_capability {
+            KernelCapability::ReaderVersion(version) => write!(f, "reader version {version}")?,
+            KernelCapability::WriterVersion(version) => write!(f, "writer version {version}")?,
+            KernelCapability::UnknownFeature => write!(f, "a kernel that knows the feature")?,
+            KernelCapability::FeatureImplementation => {
+                write!(f, "a kernel that implements the feature")?
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+/// The common behavior of [`ReaderFeature`] and [`WriterFeature`].
+pub(crate) trait TableFeature: Display + Eq {
+    /// The operation that unsupported features of this kind block.
+    const BLOCKS: FeatureOperation;
+
+    /// Whether the kernel does not recognize this feature at all.
+    fn is_unknown(&self) -> bool;
+}
+
+impl TableFeature for ReaderFeature {
+    const BLOCKS: FeatureOperation = FeatureOperation::Read;
+
+    fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
+    }
+}
+
+impl TableFeature for WriterFeature {
+    const BLOCKS: FeatureOperation = FeatureOperation::Write;
+
+    fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown(_))
+    }
+}
+
 #[cfg(test)] // currently only used in tests
 impl ReaderFeature {
     pub(crate) fn unknown(s: impl ToString) -> Self {
@@ -338,6 +440,7 @@ mod tests {
             (WriterFeature::V2Checkpoint, "v2Checkpoint"),
             (WriterFeature::IcebergCompatV1, "icebergCompatV1"),
             (WriterFeature::IcebergCompatV2, "icebergCompatV2"),
+            (WriterFeature::CheckpointProtection, "checkpointProtection"),
             (WriterFeature::VacuumProtocolCheck, "vacuumProtocolCheck"),
             (WriterFeature::ClusteredTable, "clustering"),
             (WriterFeature::VariantType, "variantType"),
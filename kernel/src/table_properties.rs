@@ -70,6 +70,10 @@ pub struct TableProperties {
     /// `delta.dataSkippingNumIndexedCols`.
     pub data_skipping_stats_columns: Option<Vec<ColumnName>>,
 
+    /// The number of characters that writers truncate the min/max statistics of string columns
+    /// to, 32 by default. See [`crate::stats`].
+    pub data_skipping_string_prefix_length: Option<NonZero<u64>>,
+
     /// The shortest duration for Delta Lake to keep logically deleted data files before deleting
     /// them physically. This is to prevent failures in stale readers after compactions or partition
     /// overwrites.
@@ -89,6 +93,10 @@ pub struct TableProperties {
     /// true to enable deletion vectors and predictive I/O for updates.
     pub enable_deletion_vectors: Option<bool>,
 
+    /// true to enable IcebergCompatV2, which restricts the table to what Iceberg readers can read
+    /// (e.g. requires column mapping and disallows deletion vectors).
+    pub enable_iceberg_compat_v2: Option<bool>,
+
     /// The degree to which a transaction must be isolated from modifications made by concurrent
     /// transactions.
     ///
@@ -278,9 +286,11 @@ mod tests {
             ("delta.columnMapping.mode", "id"),
             ("delta.dataSkippingNumIndexedCols", "-1"),
             ("delta.dataSkippingStatsColumns", "col1,col2"),
+            ("delta.dataSkippingStringPrefixLength", "16"),
             ("delta.deletedFileRetentionDuration", "interval 1 second"),
             ("delta.enableChangeDataFeed", "true"),
             ("delta.enableDeletionVectors", "true"),
+            ("delta.enableIcebergCompatV2", "true"),
             ("delta.isolationLevel", "snapshotIsolation"),
             ("delta.logRetentionDuration", "interval 2 seconds"),
             ("delta.enableExpiredLogCleanup", "true"),
@@ -318,9 +328,11 @@ mod tests {
             column_mapping_mode: Some(ColumnMappingMode::Id),
             data_skipping_num_indexed_cols: Some(DataSkippingNumIndexedCols::AllColumns),
             data_skipping_stats_columns: Some(vec![column_name!("col1"), column_name!("col2")]),
+            data_skipping_string_prefix_length: Some(NonZero::new(16).unwrap()),
             deleted_file_retention_duration: Some(Duration::new(1, 0)),
             enable_change_data_feed: Some(true),
             enable_deletion_vectors: Some(true),
+            enable_iceberg_compat_v2: Some(true),
             isolation_level: Some(IsolationLevel::SnapshotIsolation),
             log_retention_duration: Some(Duration::new(2, 0)),
             enable_expired_log_cleanup: Some(true),
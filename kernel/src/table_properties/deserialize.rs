@@ -60,11 +60,15 @@ fn try_parse(props: &mut TableProperties, k: &str, v: &str) -> Option<()> {
         "delta.dataSkippingStatsColumns" => {
             props.data_skipping_stats_columns = Some(parse_column_names(v)?)
         }
+        "delta.dataSkippingStringPrefixLength" => {
+            props.data_skipping_string_prefix_length = Some(parse_positive_int(v)?)
+        }
         "delta.deletedFileRetentionDuration" => {
             props.deleted_file_retention_duration = Some(parse_interval(v)?)
         }
         "delta.enableChangeDataFeed" => props.enable_change_data_feed = Some(parse_bool(v)?),
         "delta.enableDeletionVectors" => props.enable_deletion_vectors = Some(parse_bool(v)?),
+        "delta.enableIcebergCompatV2" => props.enable_iceberg_compat_v2 = Some(parse_bool(v)?),
         "delta.isolationLevel" => props.isolation_level = IsolationLevel::try_from(v).ok(),
         "delta.logRetentionDuration" => props.log_retention_duration = Some(parse_interval(v)?),
         "delta.enableExpiredLogCleanup" => props.enable_expired_log_cleanup = Some(parse_bool(v)?),
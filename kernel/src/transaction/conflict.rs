@@ -0,0 +1,584 @@
+//! Conflict detection between a transaction and the commits that won the race to the versions it
+//! tried to commit, see [`ConflictChecker`].
+//!
+//! When a commit fails because its version already exists, the transaction may still be valid on
+//! top of the winning commit(s), in which case it only needs to be retried at the next version. The
+//! checker decides this the way Delta writers do: each winning commit is summarized into a
+//! [`WinningCommitSummary`], and checked against what the transaction read and wrote, under the
+//! transaction's [`IsolationLevel`]. Engines that run their own commit loop can use it to rebase
+//! their transactions instead of failing (or wrongly succeeding) on every conflict.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use itertools::Itertools as _;
+use url::Url;
+
+use crate::actions::{
+    ADD_NAME, COMMIT_INFO_NAME, DOMAIN_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
+    SET_TRANSACTION_NAME,
+};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{ColumnName, PredicateRef};
+use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
+use crate::partition_values::parse_partition_value;
+use crate::path::ParsedLogPath;
+use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Snapshot, Version};
+
+pub use crate::table_properties::IsolationLevel;
+
+/// The parts of the actions of a winning commit that conflict detection needs.
+static WINNING_COMMIT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let path = || StructField::nullable("path", DataType::STRING);
+    let partition_values = || {
+        StructField::nullable(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        )
+    };
+    let add = StructType::new_unchecked([
+        path(),
+        partition_values(),
+        StructField::nullable("dataChange", DataType::BOOLEAN),
+    ]);
+    let remove = StructType::new_unchecked([path(), partition_values()]);
+    let nested = |name: &str, field: StructField| {
+        StructField::nullable(name, StructType::new_unchecked([field]))
+    };
+    SchemaRef::new(StructType::new_unchecked([
+        StructField::nullable(ADD_NAME, add),
+        StructField::nullable(REMOVE_NAME, remove),
+        nested(METADATA_NAME, StructField::nullable("id", DataType::STRING)),
+        nested(
+            PROTOCOL_NAME,
+            StructField::nullable("minReaderVersion", DataType::INTEGER),
+        ),
+        nested(
+            SET_TRANSACTION_NAME,
+            StructField::nullable("appId", DataType::STRING),
+        ),
+        nested(
+            DOMAIN_METADATA_NAME,
+            StructField::nullable("domain", DataType::STRING),
+        ),
+        nested(
+            COMMIT_INFO_NAME,
+            StructField::nullable("isBlindAppend", DataType::BOOLEAN),
+        ),
+    ]))
+});
+
+/// A file added by a winning commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddedFile {
+    /// The path of the file, relative to the table root unless absolute
+    pub path: String,
+    /// The partition values of the file, keyed by physical column name
+    pub partition_values: HashMap<String, String>,
+    /// Whether the add changes the data of the table, unlike e.g. the adds of a compaction
+    pub data_change: bool,
+}
+
+/// A file removed by a winning commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedFile {
+    /// The path of the file, relative to the table root unless absolute
+    pub path: String,
+    /// The partition values of the file, keyed by physical column name. Empty if the remove
+    /// action doesn't have them, in which case the file may be in any partition.
+    pub partition_values: HashMap<String, String>,
+}
+
+/// What a winning commit changed, as far as conflict detection is concerned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WinningCommitSummary {
+    /// Whether the commit changed the protocol
+    pub protocol_changed: bool,
+    /// Whether the commit changed the metadata (e.g. the schema)
+    pub metadata_changed: bool,
+    /// Whether the commit says it only appended data without reading the table
+    pub is_blind_append: bool,
+    /// The files added by the commit
+    pub added_files: Vec<AddedFile>,
+    /// The files removed by the commit, whether or not the removal changed data
+    pub removed_files: Vec<RemovedFile>,
+    /// The application ids of the commit's set transaction actions
+    pub app_ids: HashSet<String>,
+    /// The domains of the commit's domain metadata actions
+    pub domains: HashSet<String>,
+}
+
+impl WinningCommitSummary {
+    /// The schema to read the actions of a winning commit with, for
+    /// [`Self::try_from_actions`].
+    pub fn read_schema() -> SchemaRef {
+        WINNING_COMMIT_SCHEMA.clone()
+    }
+
+    /// Summarizes the actions of a winning commit, read with [`Self::read_schema`].
+    pub fn try_from_actions(
+        actions: impl IntoIterator<Item = DeltaResult<Box<dyn EngineData>>>,
+    ) -> DeltaResult<Self> {
+        let mut visitor = WinningCommitVisitor::default();
+        for actions in actions {
+            visitor.visit_rows_of(actions?.as_ref())?;
+        }
+        Ok(visitor.summary)
+    }
+
+    /// Reads and summarizes the published commit of the table at `table_root` at `version`.
+    pub fn try_read(engine: &dyn Engine, table_root: &Url, version: Version) -> DeltaResult<Self> {
+        let commit = ParsedLogPath::new_commit(table_root, version)?;
+        let file = FileMeta::new(commit.location, 0, 0);
+        let actions = engine
+            .json_handler()
+            .read_json_files(&[file], Self::read_schema(), None)?;
+        Self::try_from_actions(actions)
+    }
+}
+
+#[derive(Default)]
+struct WinningCommitVisitor {
+    summary: WinningCommitSummary,
+}
+
+impl RowVisitor for WinningCommitVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| WINNING_COMMIT_SCHEMA.leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 10,
+            Error::InternalError(format!(
+                "Wrong number of WinningCommitVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        let summary = &mut self.summary;
+        for i in 0..row_count {
+            if let Some(path) = getters[0].get_opt(i, "add.path")? {
+                let partition_values: Option<HashMap<String, String>> =
+                    getters[1].get_opt(i, "add.partitionValues")?;
+                summary.added_files.push(AddedFile {
+                    path,
+                    partition_values: partition_values.unwrap_or_default(),
+                    data_change: getters[2].get_opt(i, "add.dataChange")?.unwrap_or(true),
+                });
+                continue;
+            }
+            if let Some(path) = getters[3].get_opt(i, "remove.path")? {
+                let partition_values: Option<HashMap<String, String>> =
+                    getters[4].get_opt(i, "remove.partitionValues")?;
+                summary.removed_files.push(RemovedFile {
+                    path,
+                    partition_values: partition_values.unwrap_or_default(),
+                });
+                continue;
+            }
+            let metadata_id: Option<String> = getters[5].get_opt(i, "metaData.id")?;
+            summary.metadata_changed |= metadata_id.is_some();
+            let min_reader_version: Option<i32> =
+                getters[6].get_opt(i, "protocol.minReaderVersion")?;
+            summary.protocol_changed |= min_reader_version.is_some();
+            if let Some(app_id) = getters[7].get_opt(i, "txn.appId")? {
+                summary.app_ids.insert(app_id);
+            }
+            if let Some(domain) = getters[8].get_opt(i, "domainMetadata.domain")? {
+                summary.domains.insert(domain);
+            }
+            if let Some(is_blind_append) = getters[9].get_opt(i, "commitInfo.isBlindAppend")? {
+                summary.is_blind_append = is_blind_append;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A reason a transaction cannot be rebased onto a winning commit.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The winning commit changed the protocol.
+    #[error("A concurrent commit changed the protocol of the table")]
+    ProtocolChanged,
+    /// The winning commit changed the metadata.
+    #[error("A concurrent commit changed the metadata of the table")]
+    MetadataChanged,
+    /// The winning commit added a file that the transaction would have read.
+    #[error("A concurrent commit added {0}, which the transaction would have read")]
+    ConcurrentAppend(String),
+    /// The winning commit removed a file that the transaction read, or would have read.
+    #[error("A concurrent commit removed {0}, which the transaction read")]
+    ConcurrentDeleteRead(String),
+    /// The winning commit removed a file that the transaction also removes.
+    #[error("A concurrent commit removed {0}, which the transaction also removes")]
+    ConcurrentDeleteDelete(String),
+    /// The winning commit has a set transaction action for the same application.
+    #[error("A concurrent commit has a transaction for application {0}")]
+    ConcurrentTransaction(String),
+    /// The winning commit has a domain metadata action for the same domain.
+    #[error("A concurrent commit changed the metadata of domain {0}")]
+    ConcurrentDomainMetadata(String),
+}
+
+/// Checks whether a transaction conflicts with winning commits, see the [module docs].
+///
+/// The checker is configured with what the transaction read and wrote. Reads are described by
+/// predicates, which are evaluated against the partition values of concurrently added and removed
+/// files: a file conflicts unless some predicate is known to reject it, so predicates on data
+/// columns conservatively overlap with every file.
+///
+/// [module docs]: self
+#[derive(Debug, Clone)]
+pub struct ConflictChecker {
+    isolation_level: IsolationLevel,
+    partition_fields: Vec<StructField>,
+    read_predicates: Vec<PredicateRef>,
+    read_whole_table: bool,
+    read_files: HashSet<String>,
+    removed_files: HashSet<String>,
+    app_ids: HashSet<String>,
+    domains: HashSet<String>,
+    changes_metadata: bool,
+}
+
+impl ConflictChecker {
+    /// Create a checker for a transaction that read `snapshot`, with the isolation level of its
+    /// table (`delta.isolationLevel`, [`IsolationLevel::Serializable`] by default). The transaction
+    /// reads and writes nothing until configured otherwise, like a blind append.
+    pub fn try_new(snapshot: &Snapshot) -> DeltaResult<Self> {
+        let schema = snapshot.schema();
+        let partition_fields = snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .map(|name| {
+                schema.field(name).cloned().ok_or_else(|| {
+                    Error::generic(format!("Partition column {name} is not in the schema"))
+                })
+            })
+            .collect::<DeltaResult<_>>()?;
+        Ok(Self {
+            isolation_level: snapshot
+                .table_properties()
+                .isolation_level
+                .unwrap_or_default(),
+            partition_fields,
+            read_predicates: vec![],
+            read_whole_table: false,
+            read_files: HashSet::new(),
+            removed_files: HashSet::new(),
+            app_ids: HashSet::new(),
+            domains: HashSet::new(),
+            changes_metadata: false,
+        })
+    }
+
+    /// Check under `isolation_level` instead of the table's isolation level.
+    pub fn with_isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
+        self.isolation_level = isolation_level;
+        self
+    }
+
+    /// The transaction read the rows matching `predicate` (e.g. the `replaceWhere` predicate of an
+    /// overwrite, or the filter of a scan).
+    pub fn with_read_predicate(mut self, predicate: PredicateRef) -> Self {
+        self.read_predicates.push(predicate);
+        self
+    }
+
+    /// The transaction read the whole table.
+    pub fn with_read_whole_table(mut self) -> Self {
+        self.read_whole_table = true;
+        self
+    }
+
+    /// The transaction read the files at these paths.
+    pub fn with_read_files(mut self, paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.read_files.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// The transaction removes the files at these paths.
+    pub fn with_removed_files(
+        mut self,
+        paths: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.removed_files.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// The transaction has set transaction actions for these application ids.
+    pub fn with_app_ids(mut self, app_ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.app_ids.extend(app_ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// The transaction has domain metadata actions for these domains.
+    pub fn with_domains(mut self, domains: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.domains.extend(domains.into_iter().map(Into::into));
+        self
+    }
+
+    /// The transaction changes the metadata of the table, which makes even blind appends conflict
+    /// with its reads under [`IsolationLevel::WriteSerializable`].
+    pub fn with_metadata_change(mut self) -> Self {
+        self.changes_metadata = true;
+        self
+    }
+
+    /// Returns the first conflict of the transaction with `winning_commit`, or `None` if the
+    /// transaction can be retried at the version after it. With several winning commits, each of
+    /// them must be checked, in order.
+    pub fn check(&self, winning_commit: &WinningCommitSummary) -> DeltaResult<Option<Conflict>> {
+        if winning_commit.protocol_changed {
+            return Ok(Some(Conflict::ProtocolChanged));
+        }
+        if winning_commit.metadata_changed {
+            return Ok(Some(Conflict::MetadataChanged));
+        }
+
+        // Blind appends don't change what the transaction read, unless it also changed the
+        // metadata; only serializable reads must see them.
+        let check_changed_files = match self.isolation_level {
+            IsolationLevel::Serializable => true,
+            IsolationLevel::WriteSerializable => {
+                !winning_commit.is_blind_append || self.changes_metadata
+            }
+            IsolationLevel::SnapshotIsolation => false,
+        };
+        if check_changed_files {
+            for file in winning_commit.added_files.iter().filter(|f| f.data_change) {
+                if self.reads_partition(&file.partition_values)? {
+                    return Ok(Some(Conflict::ConcurrentAppend(file.path.clone())));
+                }
+            }
+        }
+
+        let removed_files = winning_commit
+            .removed_files
+            .iter()
+            .sorted_by(|a, b| a.path.cmp(&b.path));
+        for file in removed_files {
+            let read = self.read_files.contains(&file.path)
+                || (check_changed_files && self.reads_partition(&file.partition_values)?);
+            if read {
+                return Ok(Some(Conflict::ConcurrentDeleteRead(file.path.clone())));
+            }
+            if self.removed_files.contains(&file.path) {
+                return Ok(Some(Conflict::ConcurrentDeleteDelete(file.path.clone())));
+            }
+        }
+        if let Some(app_id) = winning_commit.app_ids.intersection(&self.app_ids).min() {
+            return Ok(Some(Conflict::ConcurrentTransaction(app_id.clone())));
+        }
+        if let Some(domain) = winning_commit.domains.intersection(&self.domains).min() {
+            return Ok(Some(Conflict::ConcurrentDomainMetadata(domain.clone())));
+        }
+        Ok(None)
+    }
+
+    /// Whether the transaction would have read (some rows of) a file with `partition_values`.
+    fn reads_partition(&self, partition_values: &HashMap<String, String>) -> DeltaResult<bool> {
+        if self.read_whole_table {
+            return Ok(true);
+        }
+        if self.read_predicates.is_empty() {
+            return Ok(false);
+        }
+        let partition_values: HashMap<_, _> = self
+            .partition_fields
+            .iter()
+            .map(|field| {
+                let raw = partition_values.get(field.physical_name());
+                let value = parse_partition_value(raw.map(String::as_str), field.data_type())?;
+                Ok((ColumnName::new([field.name()]), value))
+            })
+            .collect::<DeltaResult<_>>()?;
+        let evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
+        Ok(self
+            .read_predicates
+            .iter()
+            .any(|predicate| evaluator.eval_sql_where(predicate) != Some(false)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::expressions::{column_expr, Expression};
+
+    use super::*;
+
+    const REMOVED_FILE: &str = "letter=b/0.parquet";
+
+    fn write_commit(log_dir: &std::path::Path, version: Version, actions: &[&str]) {
+        let path = log_dir.join(format!("{version:020}.json"));
+        std::fs::write(path, actions.join("\n")).unwrap();
+    }
+
+    fn setup() -> (tempfile::TempDir, SyncEngine, Url) {
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        write_commit(
+            &log_dir,
+            0,
+            &[
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                r#"{"metaData":{"id":"id","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"letter\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["letter"],"configuration":{},"createdTime":1}}"#,
+                r#"{"add":{"path":"letter=b/0.parquet","partitionValues":{"letter":"b"},"size":1,"modificationTime":1,"dataChange":true}}"#,
+            ],
+        );
+        write_commit(
+            &log_dir,
+            1,
+            &[
+                r#"{"commitInfo":{"timestamp":2,"operation":"WRITE","isBlindAppend":false}}"#,
+                r#"{"add":{"path":"letter=a/1.parquet","partitionValues":{"letter":"a"},"size":1,"modificationTime":2,"dataChange":true}}"#,
+                r#"{"remove":{"path":"letter=b/0.parquet","partitionValues":{"letter":"b"},"deletionTimestamp":2,"dataChange":true}}"#,
+                r#"{"txn":{"appId":"app","version":1}}"#,
+                r#"{"domainMetadata":{"domain":"domain","configuration":"{}","removed":false}}"#,
+            ],
+        );
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        (dir, SyncEngine::new(), url)
+    }
+
+    fn checker(engine: &SyncEngine, url: &Url) -> ConflictChecker {
+        let snapshot = Snapshot::builder_for(url.clone())
+            .at_version(0)
+            .build(engine)
+            .unwrap();
+        ConflictChecker::try_new(&snapshot).unwrap()
+    }
+
+    #[test]
+    fn test_winning_commit_summary() {
+        let (_dir, engine, url) = setup();
+        let summary = WinningCommitSummary::try_read(&engine, &url, 1).unwrap();
+        let expected = WinningCommitSummary {
+            protocol_changed: false,
+            metadata_changed: false,
+            is_blind_append: false,
+            added_files: vec![AddedFile {
+                path: "letter=a/1.parquet".to_string(),
+                partition_values: HashMap::from([("letter".to_string(), "a".to_string())]),
+                data_change: true,
+            }],
+            removed_files: vec![RemovedFile {
+                path: REMOVED_FILE.to_string(),
+                partition_values: HashMap::from([("letter".to_string(), "b".to_string())]),
+            }],
+            app_ids: HashSet::from(["app".to_string()]),
+            domains: HashSet::from(["domain".to_string()]),
+        };
+        assert_eq!(summary, expected);
+
+        let summary = WinningCommitSummary::try_read(&engine, &url, 0).unwrap();
+        assert!(summary.protocol_changed && summary.metadata_changed);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let (_dir, engine, url) = setup();
+        let winning = WinningCommitSummary::try_read(&engine, &url, 1).unwrap();
+        let check = |checker: ConflictChecker| checker.check(&winning).unwrap();
+        let concurrent_append = Some(Conflict::ConcurrentAppend("letter=a/1.parquet".into()));
+
+        // a blind append only conflicts with other writes
+        assert_eq!(check(checker(&engine, &url)), None);
+
+        // only reads of the added or removed file's partition conflict, and data columns always
+        // overlap
+        let letter = |value: &str| Arc::new(column_expr!("letter").eq(Expression::literal(value)));
+        let read = |predicate| checker(&engine, &url).with_read_predicate(predicate);
+        assert_eq!(check(read(letter("c"))), None);
+        assert_eq!(check(read(letter("a"))), concurrent_append);
+        assert_eq!(
+            check(read(letter("b"))),
+            Some(Conflict::ConcurrentDeleteRead(REMOVED_FILE.into()))
+        );
+        // removes without partition values may be in any partition
+        let winning_without_partition_values = WinningCommitSummary {
+            removed_files: vec![RemovedFile {
+                path: REMOVED_FILE.to_string(),
+                partition_values: HashMap::new(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            read(letter("c"))
+                .check(&winning_without_partition_values)
+                .unwrap(),
+            Some(Conflict::ConcurrentDeleteRead(REMOVED_FILE.into()))
+        );
+        let value = Arc::new(column_expr!("value").gt(Expression::literal(1)));
+        assert_eq!(check(read(value)), concurrent_append);
+        assert_eq!(
+            check(checker(&engine, &url).with_read_whole_table()),
+            concurrent_append
+        );
+
+        assert_eq!(
+            check(checker(&engine, &url).with_read_files([REMOVED_FILE])),
+            Some(Conflict::ConcurrentDeleteRead(REMOVED_FILE.into()))
+        );
+        assert_eq!(
+            check(checker(&engine, &url).with_removed_files([REMOVED_FILE])),
+            Some(Conflict::ConcurrentDeleteDelete(REMOVED_FILE.into()))
+        );
+        assert_eq!(
+            check(checker(&engine, &url).with_app_ids(["app"])),
+            Some(Conflict::ConcurrentTransaction("app".into()))
+        );
+        assert_eq!(
+            check(checker(&engine, &url).with_domains(["domain", "other"])),
+            Some(Conflict::ConcurrentDomainMetadata("domain".into()))
+        );
+    }
+
+    #[test]
+    fn test_isolation_levels() {
+        let (_dir, engine, url) = setup();
+        let winning = WinningCommitSummary {
+            is_blind_append: true,
+            ..WinningCommitSummary::try_read(&engine, &url, 1).unwrap()
+        };
+        let check = |isolation_level| {
+            checker(&engine, &url)
+                .with_isolation_level(isolation_level)
+                .with_read_whole_table()
+                .check(&winning)
+                .unwrap()
+        };
+        assert!(matches!(
+            check(IsolationLevel::Serializable),
+            Some(Conflict::ConcurrentAppend(_))
+        ));
+        assert_eq!(check(IsolationLevel::WriteSerializable), None);
+        assert_eq!(check(IsolationLevel::SnapshotIsolation), None);
+
+        // unless the transaction changes the metadata
+        let conflict = checker(&engine, &url)
+            .with_isolation_level(IsolationLevel::WriteSerializable)
+            .with_read_whole_table()
+            .with_metadata_change()
+            .check(&winning)
+            .unwrap();
+        assert!(matches!(conflict, Some(Conflict::ConcurrentAppend(_))));
+
+        let winning = WinningCommitSummary {
+            metadata_changed: true,
+            ..Default::default()
+        };
+        let conflict = checker(&engine, &url).check(&winning).unwrap();
+        assert_eq!(conflict, Some(Conflict::MetadataChanged));
+    }
+}
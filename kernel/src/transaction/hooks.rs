@@ -0,0 +1,502 @@
+//! Hooks that run after a transaction committed successfully, e.g. to checkpoint the table or to
+//! clean up its expired log files.
+//!
+//! Hooks are registered on a transaction with [`Transaction::with_post_commit_hook`], and run in
+//! registration order once the commit succeeded. Each hook decides for itself whether it has work
+//! to do, based on the [`PostCommitContext`] it is given. The commit is durable by the time hooks
+//! run, so a failing hook neither fails the commit nor stops the remaining hooks: its error is
+//! reported in [`CommitResult::Committed`] instead.
+//!
+//! The kernel provides hooks for the maintenance operations of the Delta protocol, which follow
+//! the table's properties by default:
+//! - [`CheckpointHook`] checkpoints the table every `delta.checkpointInterval` commits (10 by
+//!   default).
+//! - [`LogCleanupHook`] deletes log files older than `delta.logRetentionDuration` (30 days by
+//!   default) when the table is checkpointed, unless `delta.enableExpiredLogCleanup` is false.
+//! - [`ChecksumHook`] writes the version checksum (CRC) file of every committed version.
+//!
+//! [`PostCommitHooks::default_for`] is the default policy: the hooks a table's configuration calls
+//! for, which engines register with [`Transaction::with_default_post_commit_hooks`]. Since the
+//! [`Engine`] APIs cannot write parquet files, the [`CheckpointHook`] delegates writing the
+//! checkpoint to a function the engine provides, and isn't part of the defaults. Engines can
+//! implement [`PostCommitHook`] for any other post-commit work. Converting commits to other table
+//! formats (e.g. Iceberg metadata for UniForm) is not supported.
+//!
+//! [`Transaction::with_post_commit_hook`]: crate::transaction::Transaction::with_post_commit_hook
+//! [`Transaction::with_default_post_commit_hooks`]: crate::transaction::Transaction::with_default_post_commit_hooks
+//! [`CommitResult::Committed`]: crate::transaction::CommitResult::Committed
+
+use std::cell::OnceCell;
+use std::num::NonZero;
+use std::sync::Arc;
+
+use tracing::warn;
+use url::Url;
+
+use crate::actions::crc::write_crc;
+use crate::checkpoint::CheckpointWriter;
+use crate::snapshot::{Snapshot, SnapshotRef};
+use crate::table_configuration::TableConfiguration;
+use crate::table_features::WriterFeature;
+use crate::table_properties::TableProperties;
+use crate::transaction::PostCommitStats;
+use crate::{DeltaResult, Engine, Error, Version};
+
+/// The checkpoint interval of tables that do not set `delta.checkpointInterval`.
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 10;
+
+/// Work to perform after a transaction committed successfully.
+pub trait PostCommitHook: Send + Sync {
+    /// The name of this hook, used to report its failures.
+    fn name(&self) -> &str;
+
+    /// Runs this hook for the commit described by `context`.
+    fn run(&self, engine: &dyn Engine, context: &PostCommitContext<'_>) -> DeltaResult<()>;
+}
+
+/// A failure of a [`PostCommitHook`]. The commit itself succeeded regardless.
+#[derive(Debug)]
+pub struct PostCommitHookFailure {
+    /// The [name](PostCommitHook::name) of the hook that failed
+    pub hook: String,
+    /// The error the hook failed with
+    pub error: Error,
+}
+
+/// Information about a successful commit, for [`PostCommitHook`]s to act on.
+pub struct PostCommitContext<'a> {
+    read_snapshot: &'a SnapshotRef,
+    version: Version,
+    post_commit_stats: &'a PostCommitStats,
+    // the snapshot at the committed version, built on first use and shared between hooks
+    snapshot: OnceCell<SnapshotRef>,
+}
+
+impl<'a> PostCommitContext<'a> {
+    pub(crate) fn new(
+        read_snapshot: &'a SnapshotRef,
+        version: Version,
+        post_commit_stats: &'a PostCommitStats,
+    ) -> Self {
+        Self {
+            read_snapshot,
+            version,
+            post_commit_stats,
+            snapshot: OnceCell::new(),
+        }
+    }
+
+    /// The root of the table that was committed to.
+    pub fn table_root(&self) -> &Url {
+        self.read_snapshot.table_root()
+    }
+
+    /// The version that was committed.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The [`PostCommitStats`] of the commit.
+    pub fn post_commit_stats(&self) -> &PostCommitStats {
+        self.post_commit_stats
+    }
+
+    /// The properties of the table as of the committed version.
+    pub fn table_properties(&self) -> &TableProperties {
+        // transactions cannot change the table metadata yet, so the read snapshot's properties
+        // still apply
+        self.read_snapshot.table_properties()
+    }
+
+    /// Whether the committed version should be checkpointed, i.e. whether it is a multiple of the
+    /// table's checkpoint interval.
+    pub fn is_checkpoint_due(&self) -> bool {
+        is_checkpoint_due(self.version, self.table_properties().checkpoint_interval)
+    }
+
+    /// The snapshot of the table at the committed version. It is built (incrementally from the
+    /// transaction's read snapshot) on the first call and reused afterwards.
+    pub fn snapshot(&self, engine: &dyn Engine) -> DeltaResult<SnapshotRef> {
+        if let Some(snapshot) = self.snapshot.get() {
+            return Ok(snapshot.clone());
+        }
+        let snapshot = Snapshot::builder_from(self.read_snapshot.clone())
+            .at_version(self.version)
+            .build(engine)?;
+        Ok(self.snapshot.get_or_init(|| snapshot).clone())
+    }
+}
+
+/// A list of [`PostCommitHook`]s, which run in order. See [`Self::default_for`] for the hooks the
+/// kernel runs by default.
+#[derive(Clone, Default)]
+pub struct PostCommitHooks {
+    hooks: Vec<Arc<dyn PostCommitHook>>,
+}
+
+impl std::fmt::Debug for PostCommitHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|hook| hook.name()))
+            .finish()
+    }
+}
+
+impl PostCommitHooks {
+    /// An empty list of hooks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The default hooks of a table with the given configuration:
+    /// - [`ChecksumHook`], unless the table enables in-commit timestamps, whose checksums the
+    ///   kernel cannot write.
+    /// - [`LogCleanupHook`], unless the table disables `delta.enableExpiredLogCleanup` or uses the
+    ///   `checkpointProtection` feature, which the kernel does not support.
+    ///
+    /// Engines that checkpoint the table should register their [`CheckpointHook`] before these
+    /// hooks, so that log cleanup takes the new checkpoint into account.
+    pub fn default_for(table_configuration: &TableConfiguration) -> Self {
+        let mut hooks = Self::new();
+        if !table_configuration.is_in_commit_timestamps_enabled() {
+            hooks = hooks.with_hook(Arc::new(ChecksumHook));
+        }
+        let cleanup_disabled = table_configuration
+            .table_properties()
+            .enable_expired_log_cleanup
+            == Some(false);
+        let checkpoints_protected = table_configuration
+            .protocol()
+            .has_writer_feature(&WriterFeature::CheckpointProtection);
+        if !cleanup_disabled && !checkpoints_protected {
+            hooks = hooks.with_hook(Arc::new(LogCleanupHook));
+        }
+        hooks
+    }
+
+    /// Append `hook` to the list.
+    pub fn with_hook(mut self, hook: Arc<dyn PostCommitHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// The hooks, in the order they run.
+    pub fn hooks(&self) -> &[Arc<dyn PostCommitHook>] {
+        &self.hooks
+    }
+}
+
+impl IntoIterator for PostCommitHooks {
+    type Item = Arc<dyn PostCommitHook>;
+    type IntoIter = std::vec::IntoIter<Arc<dyn PostCommitHook>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hooks.into_iter()
+    }
+}
+
+/// Runs `hooks` in order, collecting the failures of those that fail.
+pub(crate) fn run_post_commit_hooks(
+    hooks: &[Arc<dyn PostCommitHook>],
+    engine: &dyn Engine,
+    context: &PostCommitContext<'_>,
+) -> Vec<PostCommitHookFailure> {
+    hooks
+        .iter()
+        .filter_map(|hook| {
+            let error = hook.run(engine, context).err()?;
+            warn!(
+                "post-commit hook {} failed for version {}: {error}",
+                hook.name(),
+                context.version()
+            );
+            Some(PostCommitHookFailure {
+                hook: hook.name().to_string(),
+                error,
+            })
+        })
+        .collect()
+}
+
+/// The function a [`CheckpointHook`] writes checkpoints with.
+type WriteCheckpointFn = dyn Fn(&dyn Engine, CheckpointWriter) -> DeltaResult<()> + Send + Sync;
+
+/// Checkpoints the table when [`PostCommitContext::is_checkpoint_due`].
+///
+/// The hook creates the [`CheckpointWriter`] for the committed version, and hands it to the
+/// engine-provided function, which must write the [checkpoint data] to the [checkpoint path] and
+/// then [finalize] the checkpoint.
+///
+/// [checkpoint data]: CheckpointWriter::checkpoint_data
+/// [checkpoint path]: CheckpointWriter::checkpoint_path
+/// [finalize]: CheckpointWriter::finalize
+pub struct CheckpointHook {
+    write_checkpoint: Box<WriteCheckpointFn>,
+}
+
+impl CheckpointHook {
+    pub fn new(
+        write_checkpoint: impl Fn(&dyn Engine, CheckpointWriter) -> DeltaResult<()>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self {
+            write_checkpoint: Box::new(write_checkpoint),
+        }
+    }
+}
+
+impl PostCommitHook for CheckpointHook {
+    fn name(&self) -> &str {
+        "checkpoint"
+    }
+
+    fn run(&self, engine: &dyn Engine, context: &PostCommitContext<'_>) -> DeltaResult<()> {
+        if !context.is_checkpoint_due() {
+            return Ok(());
+        }
+        let writer = context.snapshot(engine)?.checkpoint()?;
+        (self.write_checkpoint)(engine, writer)
+    }
+}
+
+/// Deletes the expired files of the table's log with [`Snapshot::cleanup_expired_logs`] when
+/// [`PostCommitContext::is_checkpoint_due`], unless the table disables
+/// `delta.enableExpiredLogCleanup`. Like [`Snapshot::cleanup_expired_logs`], the hook fails
+/// without deleting anything for tables it must not clean up. Register this hook after the
+/// [`CheckpointHook`] so that it takes the new checkpoint into account.
+///
+/// The hook deletes files with [`StorageHandler::delete_files`], which the engine must support.
+///
+/// [`StorageHandler::delete_files`]: crate::StorageHandler::delete_files
+#[derive(Debug, Default)]
+pub struct LogCleanupHook;
+
+impl PostCommitHook for LogCleanupHook {
+    fn name(&self) -> &str {
+        "log_cleanup"
+    }
+
+    fn run(&self, engine: &dyn Engine, context: &PostCommitContext<'_>) -> DeltaResult<()> {
+        let properties = context.table_properties();
+        if !context.is_checkpoint_due() || properties.enable_expired_log_cleanup == Some(false) {
+            return Ok(());
+        }
+        context.snapshot(engine)?.cleanup_expired_logs(engine)?;
+        Ok(())
+    }
+}
+
+/// Writes the version checksum (CRC) file of the committed version, `{version}.crc`, which lets
+/// readers validate the table state and answer e.g. the number of files without replaying the log.
+/// The number and total size of the table's files are computed by replaying its log, so the hook
+/// costs as much as reading the metadata of a scan.
+///
+/// The hook fails for tables with in-commit timestamps, whose checksums must record the timestamp
+/// of the version, which the kernel doesn't track.
+#[derive(Debug, Default)]
+pub struct ChecksumHook;
+
+impl PostCommitHook for ChecksumHook {
+    fn name(&self) -> &str {
+        "checksum"
+    }
+
+    fn run(&self, engine: &dyn Engine, context: &PostCommitContext<'_>) -> DeltaResult<()> {
+        write_crc(engine, context.snapshot(engine)?)
+    }
+}
+
+fn is_checkpoint_due(version: Version, checkpoint_interval: Option<NonZero<u64>>) -> bool {
+    let interval = checkpoint_interval.map_or(DEFAULT_CHECKPOINT_INTERVAL, NonZero::get);
+    version > 0 && version % interval == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use object_store::local::LocalFileSystem;
+    use serde_json::{json, Value};
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::transaction::CommitResult;
+
+    type TestEngine = DefaultEngine<TokioBackgroundExecutor>;
+
+    /// Create a local table whose first commit has the `protocol`, the `configuration` and the
+    /// `actions`.
+    fn create_table(
+        protocol: Value,
+        configuration: Value,
+        actions: &[Value],
+    ) -> (TempDir, Url, TestEngine) {
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        fs::create_dir(&log_dir).unwrap();
+        let commit = [
+            json!({ "protocol": protocol }),
+            json!({
+                "metaData": {
+                    "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": { "provider": "parquet", "options": {} },
+                    "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                    "partitionColumns": [],
+                    "configuration": configuration,
+                    "createdTime": 1587968585495i64
+                }
+            }),
+        ]
+        .iter()
+        .chain(actions)
+        .map(|json| json.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+        fs::write(log_dir.join("00000000000000000000.json"), commit).unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let engine = DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        );
+        (dir, table_root, engine)
+    }
+
+    fn protocol() -> Value {
+        json!({ "minReaderVersion": 1, "minWriterVersion": 2 })
+    }
+
+    fn assert_committed(result: CommitResult, expected_version: Version) {
+        let CommitResult::Committed {
+            version,
+            post_commit_hook_failures,
+            ..
+        } = result
+        else {
+            panic!("Commit should have succeeded");
+        };
+        assert_eq!(version, expected_version);
+        assert!(post_commit_hook_failures.is_empty());
+    }
+
+    #[test]
+    fn test_is_checkpoint_due() {
+        assert!(!is_checkpoint_due(0, None));
+        assert!(!is_checkpoint_due(9, None));
+        assert!(is_checkpoint_due(10, None));
+        assert!(is_checkpoint_due(3, NonZero::new(3)));
+        assert!(!is_checkpoint_due(4, NonZero::new(3)));
+    }
+
+    #[test]
+    fn test_default_hooks() {
+        let default_hooks = |protocol: Value, configuration: Value| {
+            let (_dir, table_root, engine) = create_table(protocol, configuration, &[]);
+            let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+            let hooks = PostCommitHooks::default_for(snapshot.table_configuration());
+            hooks
+                .hooks()
+                .iter()
+                .map(|hook| hook.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            default_hooks(protocol(), json!({})),
+            ["checksum", "log_cleanup"]
+        );
+        let configuration = json!({ "delta.enableExpiredLogCleanup": "false" });
+        assert_eq!(default_hooks(protocol(), configuration), ["checksum"]);
+        let protected = json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["checkpointProtection"]
+        });
+        assert_eq!(default_hooks(protected, json!({})), ["checksum"]);
+        let in_commit_timestamps = json!({
+            "minReaderVersion": 1,
+            "minWriterVersion": 7,
+            "writerFeatures": ["inCommitTimestamp"]
+        });
+        let configuration = json!({ "delta.enableInCommitTimestamps": "true" });
+        assert_eq!(
+            default_hooks(in_commit_timestamps, configuration),
+            ["log_cleanup"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checksum_hook() {
+        let add = json!({
+            "add": {
+                "path": "part-00000.parquet",
+                "partitionValues": {},
+                "size": 100,
+                "modificationTime": 1587968586000i64,
+                "dataChange": true
+            }
+        });
+        let (dir, table_root, engine) = create_table(protocol(), json!({}), &[add]);
+        let snapshot = Snapshot::builder_for(table_root.clone())
+            .build(&engine)
+            .unwrap();
+        let result = snapshot
+            .transaction()
+            .unwrap()
+            .with_default_post_commit_hooks()
+            .commit(&engine)
+            .unwrap();
+        assert_committed(result, 1);
+
+        let crc =
+            fs::read_to_string(dir.path().join("_delta_log/00000000000000000001.crc")).unwrap();
+        let crc: Value = serde_json::from_str(&crc).unwrap();
+        assert_eq!(crc["tableSizeBytes"], 100);
+        assert_eq!(crc["numFiles"], 1);
+        assert_eq!(crc["numMetadata"], 1);
+        assert_eq!(crc["numProtocol"], 1);
+        assert_eq!(
+            crc["metadata"]["id"],
+            "5fba94ed-9794-4965-ba6e-6ee3c0d22af9"
+        );
+        assert_eq!(crc["protocol"]["minWriterVersion"], 2);
+
+        // readers use the checksum
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+        assert!(!snapshot.is_known_empty(&engine));
+    }
+
+    #[tokio::test]
+    async fn test_log_cleanup_hook() {
+        for enable_cleanup in [true, false] {
+            let configuration = json!({
+                "delta.checkpointInterval": "1",
+                "delta.enableExpiredLogCleanup": enable_cleanup.to_string(),
+                "delta.logRetentionDuration": "interval 0 seconds"
+            });
+            let (dir, table_root, engine) = create_table(protocol(), configuration, &[]);
+            let first_commit = dir.path().join("_delta_log/00000000000000000000.json");
+
+            // checkpoint version 1, after which the first commit has expired
+            let snapshot = Snapshot::builder_for(table_root.clone())
+                .build(&engine)
+                .unwrap();
+            snapshot.transaction().unwrap().commit(&engine).unwrap();
+            let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+            engine.write_checkpoint(snapshot.clone()).await.unwrap();
+
+            let result = snapshot
+                .transaction()
+                .unwrap()
+                .with_post_commit_hook(Arc::new(LogCleanupHook))
+                .commit(&engine)
+                .unwrap();
+            assert_committed(result, 2);
+            // the hook does nothing for tables that disable log cleanup
+            assert_eq!(first_commit.exists(), !enable_cleanup);
+        }
+    }
+}
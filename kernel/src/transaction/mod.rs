@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::ops::Deref;
 use std::sync::{Arc, LazyLock};
@@ -10,16 +10,27 @@ use crate::actions::{
     get_log_txn_schema, CommitInfo, DomainMetadata, SetTransaction,
 };
 use crate::error::Error;
-use crate::expressions::{ArrayData, Transform, UnaryExpressionOp::ToJson};
+use crate::expressions::{ArrayData, PredicateRef, Transform, UnaryExpressionOp::ToJson};
 use crate::path::ParsedLogPath;
 use crate::row_tracking::{RowTrackingDomainMetadata, RowTrackingVisitor};
 use crate::schema::{ArrayType, MapType, SchemaRef, StructField, StructType};
 use crate::snapshot::SnapshotRef;
+use crate::stats::DEFAULT_STRING_PREFIX_LENGTH;
+use crate::table_properties::IsolationLevel;
 use crate::utils::current_time_ms;
 use crate::{
     DataType, DeltaResult, Engine, EngineData, Expression, ExpressionRef, IntoEngineData,
     RowVisitor, Version,
 };
+use conflict::{ConflictChecker, WinningCommitSummary};
+use hooks::{
+    run_post_commit_hooks, PostCommitContext, PostCommitHook, PostCommitHookFailure,
+    PostCommitHooks,
+};
+
+pub mod conflict;
+pub mod hooks;
+pub mod multi_table;
 
 /// Type alias for an iterator of [`EngineData`] results.
 type EngineDataResultIterator<'a> =
@@ -116,7 +127,9 @@ fn with_row_tracking_cols(schema: &SchemaRef) -> SchemaRef {
 pub struct Transaction {
     read_snapshot: SnapshotRef,
     operation: Option<String>,
+    operation_parameters: HashMap<String, String>,
     engine_info: Option<String>,
+    user_metadata: Option<String>,
     add_files_metadata: Vec<Box<dyn EngineData>>,
     // NB: hashmap would require either duplicating the appid or splitting SetTransaction
     // key/payload. HashSet requires Borrow<&str> with matching Eq, Ord, and Hash. Plus,
@@ -124,9 +137,21 @@ pub struct Transaction {
     // would make error messaging unnecessarily difficult. Thus, we keep Vec here and deduplicate in
     // the commit method.
     set_transactions: Vec<SetTransaction>,
+    // User domain metadata to set or remove in this commit. Like `set_transactions`, duplicates
+    // are only detected (and rejected) in the commit method.
+    domain_metadatas: Vec<DomainMetadata>,
+    // Whether writers should reject values exceeding a CHAR/VARCHAR column's length limit.
+    enforce_char_varchar_lengths: bool,
     // commit-wide timestamp (in milliseconds since epoch) - used in ICT, `txn` action, etc. to
     // keep all timestamps within the same commit consistent.
     commit_timestamp: i64,
+    // Hooks to run, in order, after a successful commit.
+    post_commit_hooks: Vec<Arc<dyn PostCommitHook>>,
+    // The isolation level to resolve conflicts with. Without one, conflicts are not resolved.
+    isolation_level: Option<IsolationLevel>,
+    // What this transaction read, to check concurrent commits against.
+    read_predicates: Vec<PredicateRef>,
+    read_whole_table: bool,
 }
 
 impl std::fmt::Debug for Transaction {
@@ -159,16 +184,105 @@ impl Transaction {
         Ok(Transaction {
             read_snapshot,
             operation: None,
+            operation_parameters: HashMap::new(),
             engine_info: None,
+            user_metadata: None,
             add_files_metadata: vec![],
             set_transactions: vec![],
+            domain_metadatas: vec![],
+            enforce_char_varchar_lengths: false,
             commit_timestamp,
+            post_commit_hooks: vec![],
+            isolation_level: None,
+            read_predicates: vec![],
+            read_whole_table: false,
         })
     }
 
     /// Consume the transaction and commit it to the table. The result is a [CommitResult] which
     /// will include the failed transaction in case of a conflict so the user can retry.
+    ///
+    /// If the transaction has an isolation level (see [`Self::with_isolation_level`]) and
+    /// concurrent commits won the versions it tried to commit, it commits at the next free version
+    /// instead, as long as none of the winning commits conflicts with it.
     pub fn commit(self, engine: &dyn Engine) -> DeltaResult<CommitResult> {
+        let mut commit_version = self.commit_version();
+        loop {
+            // Steps 1-3: Generate the actions of the commit
+            let actions = self.commit_actions(engine, commit_version)?;
+
+            // Step 4: Commit the actions as a JSON file to the Delta log
+            let commit_path =
+                ParsedLogPath::new_commit(self.read_snapshot.table_root(), commit_version)?;
+            let json_handler = engine.json_handler();
+            match json_handler.write_json_file(&commit_path.location, actions, false) {
+                Ok(()) => return Ok(self.into_committed(engine, commit_version)),
+                Err(Error::FileAlreadyExists(_)) => {
+                    if !self.can_commit_after(engine, commit_version)? {
+                        return Ok(CommitResult::Conflict(self, commit_version));
+                    }
+                    commit_version += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Whether this transaction can be committed after the concurrent commit that won
+    /// `commit_version`, i.e. whether it has an isolation level and doesn't conflict with the
+    /// winning commit under it.
+    fn can_commit_after(&self, engine: &dyn Engine, commit_version: Version) -> DeltaResult<bool> {
+        let Some(isolation_level) = self.isolation_level else {
+            return Ok(false);
+        };
+        // Row ids are assigned from the read snapshot's high watermark, which the winning commit
+        // may have moved
+        if self
+            .read_snapshot
+            .table_configuration()
+            .should_write_row_tracking()
+        {
+            return Ok(false);
+        }
+        let winning_commit = match WinningCommitSummary::try_read(
+            engine,
+            self.read_snapshot.table_root(),
+            commit_version,
+        ) {
+            Ok(winning_commit) => winning_commit,
+            // the commit that won the version is not readable (yet)
+            Err(Error::FileNotFound(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let mut checker = ConflictChecker::try_new(&self.read_snapshot)?
+            .with_isolation_level(isolation_level)
+            .with_app_ids(self.set_transactions.iter().map(|txn| txn.app_id.clone()))
+            .with_domains(
+                self.domain_metadatas
+                    .iter()
+                    .map(|dm| dm.domain().to_string()),
+            );
+        for predicate in &self.read_predicates {
+            checker = checker.with_read_predicate(predicate.clone());
+        }
+        if self.read_whole_table {
+            checker = checker.with_read_whole_table();
+        }
+        Ok(checker.check(&winning_commit)?.is_none())
+    }
+
+    /// The version this transaction commits, i.e. the version after its read snapshot's.
+    fn commit_version(&self) -> Version {
+        self.read_snapshot.version() + 1
+    }
+
+    /// Generate the actions of this transaction's commit at `commit_version`, starting with the
+    /// commit info. Generating the actions again yields the same actions.
+    fn commit_actions<'a>(
+        &'a self,
+        engine: &'a dyn Engine,
+        commit_version: Version,
+    ) -> DeltaResult<EngineDataResultIterator<'a>> {
         // Step 1: Check for duplicate app_ids and generate set transactions (`txn`)
         // Note: The commit info must always be the first action in the commit but we generate it in
         // step 2 to fail early on duplicate transaction appIds
@@ -191,17 +305,27 @@ impl Transaction {
             .into_iter()
             .map(|txn| txn.into_engine_data(get_log_txn_schema().clone(), engine));
 
+        // Step 1b: Validate user domain metadata and generate domain metadata actions. This also
+        // happens before the commit info is generated, to fail early on invalid domains.
+        let domain_metadata_actions = self
+            .generate_domain_metadata_actions(engine)?
+            .into_iter()
+            .map(|dm| dm.into_engine_data(get_log_domain_metadata_schema().clone(), engine));
+
         // Step 2: Construct commit info and initialize the action iterator
+        let operation_parameters =
+            (!self.operation_parameters.is_empty()).then(|| self.operation_parameters.clone());
         let commit_info = CommitInfo::new(
             self.commit_timestamp,
             self.operation.clone(),
+            operation_parameters,
             self.engine_info.clone(),
+            self.user_metadata.clone(),
         );
         let commit_info_action =
             commit_info.into_engine_data(get_log_commit_info_schema().clone(), engine);
 
         // Step 3: Generate add actions with or without row tracking metadata
-        let commit_version = self.read_snapshot.version() + 1;
         let add_actions = if self
             .read_snapshot
             .table_configuration()
@@ -217,32 +341,37 @@ impl Transaction {
             )
         };
 
-        // Step 4: Commit the actions as a JSON file to the Delta log
-        let commit_path =
-            ParsedLogPath::new_commit(self.read_snapshot.table_root(), commit_version)?;
-        let actions = iter::once(commit_info_action)
-            .chain(add_actions)
-            .chain(set_transaction_actions);
-
-        let json_handler = engine.json_handler();
-        match json_handler.write_json_file(&commit_path.location, Box::new(actions), false) {
-            Ok(()) => Ok(CommitResult::Committed {
-                version: commit_version,
-                post_commit_stats: PostCommitStats {
-                    commits_since_checkpoint: self
-                        .read_snapshot
-                        .log_segment()
-                        .commits_since_checkpoint()
-                        + 1,
-                    commits_since_log_compaction: self
-                        .read_snapshot
-                        .log_segment()
-                        .commits_since_log_compaction_or_checkpoint()
-                        + 1,
-                },
-            }),
-            Err(Error::FileAlreadyExists(_)) => Ok(CommitResult::Conflict(self, commit_version)),
-            Err(e) => Err(e),
+        Ok(Box::new(
+            iter::once(commit_info_action)
+                .chain(add_actions)
+                .chain(set_transaction_actions)
+                .chain(domain_metadata_actions),
+        ))
+    }
+
+    /// The result of this transaction once its commit at `commit_version` is durable.
+    fn into_committed(self, engine: &dyn Engine, commit_version: Version) -> CommitResult {
+        // the transaction may have committed after concurrent commits
+        let new_commits = commit_version - self.read_snapshot.version();
+        let post_commit_stats = PostCommitStats {
+            commits_since_checkpoint: self.read_snapshot.log_segment().commits_since_checkpoint()
+                + new_commits,
+            commits_since_log_compaction: self
+                .read_snapshot
+                .log_segment()
+                .commits_since_log_compaction_or_checkpoint()
+                + new_commits,
+        };
+        // Step 5: Run the post-commit hooks. The commit is durable now, so hook failures are
+        // reported rather than failing the commit.
+        let context =
+            PostCommitContext::new(&self.read_snapshot, commit_version, &post_commit_stats);
+        let post_commit_hook_failures =
+            run_post_commit_hooks(&self.post_commit_hooks, engine, &context);
+        CommitResult::Committed {
+            version: commit_version,
+            post_commit_stats,
+            post_commit_hook_failures,
         }
     }
 
@@ -253,12 +382,90 @@ impl Transaction {
         self
     }
 
+    /// Add a parameter of the operation that this transaction is performing (see
+    /// [`Self::with_operation`]), e.g. the mode of a write. Parameters are persisted in the
+    /// `operationParameters` of the commit info action. Setting a parameter again replaces its
+    /// value.
+    pub fn with_operation_parameter(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.operation_parameters.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the isolation level of this transaction, which makes it resolve conflicts when
+    /// committing: a concurrent commit that won the version this transaction tried to commit is
+    /// checked against what this transaction read (see [`Self::with_read_predicate`]) and wrote,
+    /// and the transaction is committed at the next version if they don't conflict. For example,
+    /// under [`IsolationLevel::WriteSerializable`], a blind append never conflicts with what this
+    /// transaction read. Without an isolation level, every concurrent commit is reported as a
+    /// [`CommitResult::Conflict`].
+    pub fn with_isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
+        self.isolation_level = Some(isolation_level);
+        self
+    }
+
+    /// Declare that this transaction read the rows of the table matching `predicate` (e.g. the
+    /// `replaceWhere` predicate of an overwrite), so that concurrently added or removed files which
+    /// may contain such rows conflict with it. A transaction that declares no reads is a blind
+    /// append.
+    pub fn with_read_predicate(mut self, predicate: PredicateRef) -> Self {
+        self.read_predicates.push(predicate);
+        self
+    }
+
+    /// Declare that this transaction read the whole table, see [`Self::with_read_predicate`].
+    pub fn with_read_whole_table(mut self) -> Self {
+        self.read_whole_table = true;
+        self
+    }
+
     /// Set the engine info field of this transaction's commit info action. This field is optional.
     pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
         self.engine_info = Some(engine_info.into());
         self
     }
 
+    /// Attach arbitrary user metadata to this transaction's commit, e.g. to tag it. The metadata is
+    /// persisted in the `userMetadata` field of the commit info action. This field is optional.
+    pub fn with_user_metadata(mut self, user_metadata: impl Into<String>) -> Self {
+        self.user_metadata = Some(user_metadata.into());
+        self
+    }
+
+    /// Ask writers to reject data containing values longer than the length limit of a
+    /// `CHAR`/`VARCHAR` column (see [`StructField::char_varchar_type`]). Disabled by default. The
+    /// setting is passed on to writers through [`WriteContext::enforce_char_varchar_lengths`].
+    ///
+    /// [`StructField::char_varchar_type`]: crate::schema::StructField::char_varchar_type
+    pub fn with_char_varchar_length_enforcement(mut self, enforce: bool) -> Self {
+        self.enforce_char_varchar_lengths = enforce;
+        self
+    }
+
+    /// Register a hook to run after this transaction committed successfully. Hooks run in the order
+    /// they were registered. See the [`hooks`] module for the hooks the kernel provides.
+    pub fn with_post_commit_hook(mut self, hook: Arc<dyn PostCommitHook>) -> Self {
+        self.post_commit_hooks.push(hook);
+        self
+    }
+
+    /// Register `hooks` to run after this transaction committed successfully, after the hooks
+    /// registered before.
+    pub fn with_post_commit_hooks(mut self, hooks: PostCommitHooks) -> Self {
+        self.post_commit_hooks.extend(hooks);
+        self
+    }
+
+    /// Register the default hooks of the table (see [`PostCommitHooks::default_for`]) to run after
+    /// this transaction committed successfully, after the hooks registered before.
+    pub fn with_default_post_commit_hooks(self) -> Self {
+        let hooks = PostCommitHooks::default_for(self.read_snapshot.table_configuration());
+        self.with_post_commit_hooks(hooks)
+    }
+
     /// Include a SetTransaction (app_id and version) action for this transaction (with an optional
     /// `last_updated` timestamp).
     /// Note that each app_id can only appear once per transaction. That is, multiple app_ids with
@@ -270,6 +477,70 @@ impl Transaction {
         self
     }
 
+    /// Set the configuration of a user domain in this transaction, replacing any configuration
+    /// the domain previously had. The domain must not be a system-controlled `delta.*` domain, and
+    /// each domain can only be set or removed once per transaction. Both constraints (as well as
+    /// table support for the `domainMetadata` writer feature) are checked when committing.
+    pub fn with_domain_metadata(mut self, domain: String, configuration: String) -> Self {
+        self.domain_metadatas
+            .push(DomainMetadata::new(domain, configuration, false));
+        self
+    }
+
+    /// Remove a user domain in this transaction. Removing a domain that does not exist in the read
+    /// snapshot is a no-op. The same constraints as for [`Transaction::with_domain_metadata`]
+    /// apply.
+    pub fn with_domain_metadata_removed(mut self, domain: String) -> Self {
+        self.domain_metadatas
+            .push(DomainMetadata::new(domain, String::new(), true));
+        self
+    }
+
+    // Validate the user domain metadata of this transaction and produce the domain metadata actions
+    // to commit. Removals are resolved against the read snapshot: the tombstone keeps the last
+    // known configuration, and removals of domains that do not exist are dropped.
+    fn generate_domain_metadata_actions(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Vec<DomainMetadata>> {
+        if self.domain_metadatas.is_empty() {
+            return Ok(vec![]);
+        }
+        if !self
+            .read_snapshot
+            .table_configuration()
+            .is_domain_metadata_supported()
+        {
+            return Err(Error::unsupported(
+                "Domain metadata operations require writer version 7 and the 'domainMetadata' writer feature",
+            ));
+        }
+
+        let mut domains = HashSet::new();
+        let mut actions = Vec::with_capacity(self.domain_metadatas.len());
+        for domain_metadata in &self.domain_metadatas {
+            let domain = domain_metadata.domain();
+            if domain_metadata.is_internal() {
+                return Err(Error::generic(format!(
+                    "Cannot modify system-controlled domain {domain} in a transaction"
+                )));
+            }
+            if !domains.insert(domain) {
+                return Err(Error::generic(format!(
+                    "Domain {domain} already modified in transaction"
+                )));
+            }
+            if !domain_metadata.is_removed() {
+                actions.push(domain_metadata.clone());
+            } else if let Some(configuration) =
+                self.read_snapshot.get_domain_metadata(domain, engine)?
+            {
+                actions.push(DomainMetadata::new(domain.to_string(), configuration, true));
+            }
+        }
+        Ok(actions)
+    }
+
     // Generate the logical-to-physical transform expression which must be evaluated on every data
     // chunk before writing. At the moment, this is a transaction-wide expression.
     fn generate_logical_to_physical(&self) -> Expression {
@@ -293,10 +564,18 @@ impl Transaction {
         let target_dir = self.read_snapshot.table_root();
         let snapshot_schema = self.read_snapshot.schema();
         let logical_to_physical = self.generate_logical_to_physical();
+        let stats_string_prefix_length = self
+            .read_snapshot
+            .table_properties()
+            .data_skipping_string_prefix_length
+            .and_then(|length| usize::try_from(length.get()).ok())
+            .unwrap_or(DEFAULT_STRING_PREFIX_LENGTH);
         WriteContext::new(
             target_dir.clone(),
             snapshot_schema,
             Arc::new(logical_to_physical),
+            self.enforce_char_varchar_lengths,
+            stats_string_prefix_length,
         )
     }
 
@@ -423,14 +702,24 @@ pub struct WriteContext {
     target_dir: Url,
     schema: SchemaRef,
     logical_to_physical: ExpressionRef,
+    enforce_char_varchar_lengths: bool,
+    stats_string_prefix_length: usize,
 }
 
 impl WriteContext {
-    fn new(target_dir: Url, schema: SchemaRef, logical_to_physical: ExpressionRef) -> Self {
+    fn new(
+        target_dir: Url,
+        schema: SchemaRef,
+        logical_to_physical: ExpressionRef,
+        enforce_char_varchar_lengths: bool,
+        stats_string_prefix_length: usize,
+    ) -> Self {
         WriteContext {
             target_dir,
             schema,
             logical_to_physical,
+            enforce_char_varchar_lengths,
+            stats_string_prefix_length,
         }
     }
 
@@ -445,6 +734,23 @@ impl WriteContext {
     pub fn logical_to_physical(&self) -> ExpressionRef {
         self.logical_to_physical.clone()
     }
+
+    /// Whether writers must reject values longer than the length limit of a `CHAR`/`VARCHAR`
+    /// column of [`Self::schema`].
+    pub fn enforce_char_varchar_lengths(&self) -> bool {
+        self.enforce_char_varchar_lengths
+    }
+
+    /// The number of characters that writers collecting min/max statistics of string columns must
+    /// truncate them to: the table's `delta.dataSkippingStringPrefixLength`, or
+    /// [`DEFAULT_STRING_PREFIX_LENGTH`]. See [`truncate_min_string`] and [`truncate_max_string`].
+    ///
+    /// [`DEFAULT_STRING_PREFIX_LENGTH`]: crate::stats::DEFAULT_STRING_PREFIX_LENGTH
+    /// [`truncate_min_string`]: crate::stats::truncate_min_string
+    /// [`truncate_max_string`]: crate::stats::truncate_max_string
+    pub fn stats_string_prefix_length(&self) -> usize {
+        self.stats_string_prefix_length
+    }
 }
 
 /// Kernel exposes information about the state of the table that engines might want to use to
@@ -469,6 +775,9 @@ pub enum CommitResult {
         version: Version,
         /// The [`PostCommitStats`] for this transaction
         post_commit_stats: PostCommitStats,
+        /// The failures of the post-commit hooks registered with
+        /// [`Transaction::with_post_commit_hook`], if any
+        post_commit_hook_failures: Vec<PostCommitHookFailure>,
     },
     /// This transaction conflicted with an existing version (at the version given). The transaction
     /// is returned so the caller can resolve the conflict (along with the version which
@@ -0,0 +1,460 @@
+//! Commits to several tables at once, see [`MultiTableTransaction`].
+//!
+//! Delta commits are atomic per table only: there is no way to make the commits of several tables
+//! visible atomically. A multi-table transaction therefore commits with a best-effort two-phase
+//! protocol:
+//!
+//! 1. [`MultiTableTransaction::prepare`] checks that no table has a commit at the version its
+//!    transaction would commit, then generates the actions of every commit and stages them in
+//!    memory. Nothing is written to any table, so any failure of this phase (an invalid
+//!    transaction, a conflict, an I/O error) leaves every table unchanged.
+//! 2. [`PreparedMultiTableTransaction::commit`] then writes the staged commits one after the other,
+//!    in the order the transactions were added.
+//!
+//! Phase 2 only writes commits that were already generated, but it can still fail part way: if
+//! another writer commits to one of the tables between the two phases, or if writing a commit
+//! fails, the transactions before it stay committed and the others are returned uncommitted, see
+//! [`MultiTableCommitResult::Incomplete`]. The staged commits are never written anywhere but to
+//! their commit files, so neither phase leaves files behind in the tables' logs.
+
+use std::collections::HashSet;
+
+use url::Url;
+
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::{DeltaResult, Engine, EngineData, Error, Version};
+
+use super::{CommitResult, Transaction};
+
+/// A set of transactions against different tables, to commit together. See the
+/// [module documentation](self) for the guarantees this provides.
+///
+/// ```no_run
+/// # use delta_kernel::transaction::multi_table::{
+/// #     MultiTableCommitResult, MultiTableTransaction, PrepareResult,
+/// # };
+/// # use delta_kernel::{Engine, Snapshot};
+/// # use url::Url;
+/// # fn example(engine: &dyn Engine) -> delta_kernel::DeltaResult<()> {
+/// let orders = Snapshot::builder_for(Url::parse("file:///path/to/orders/")?).build(engine)?;
+/// let items = Snapshot::builder_for(Url::parse("file:///path/to/items/")?).build(engine)?;
+/// let transaction = MultiTableTransaction::new()
+///     .with_transaction(orders.transaction()?)
+///     .with_transaction(items.transaction()?);
+/// match transaction.prepare(engine)? {
+///     PrepareResult::Prepared(prepared) => match prepared.commit(engine) {
+///         MultiTableCommitResult::Committed(_) => println!("committed both tables"),
+///         MultiTableCommitResult::Incomplete { committed, .. } => {
+///             println!("only committed {} tables", committed.len())
+///         }
+///     },
+///     PrepareResult::Conflict { table_root, .. } => println!("{table_root} changed, retry"),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MultiTableTransaction {
+    transactions: Vec<Transaction>,
+}
+
+impl MultiTableTransaction {
+    /// Create an empty multi-table transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the transaction of a table. Every transaction must be against a different table.
+    pub fn with_transaction(mut self, transaction: Transaction) -> Self {
+        self.transactions.push(transaction);
+        self
+    }
+
+    /// The transactions of this multi-table transaction, in the order they were added.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Prepare the commits of every table: the first phase of the commit protocol. Returns
+    /// [`PrepareResult::Conflict`] if a table already has a commit at the version its transaction
+    /// would commit. No table changes if preparing fails or conflicts.
+    pub fn prepare(self, engine: &dyn Engine) -> DeltaResult<PrepareResult> {
+        let mut table_roots = HashSet::new();
+        if let Some(dup) = self
+            .transactions
+            .iter()
+            .map(|txn| txn.read_snapshot.table_root())
+            .find(|table_root| !table_roots.insert(*table_root))
+        {
+            return Err(Error::generic(format!(
+                "Table {dup} has more than one transaction in the multi-table transaction"
+            )));
+        }
+
+        // Fail early on conflicts, rather than staging commits that cannot succeed
+        for txn in &self.transactions {
+            let version = txn.commit_version();
+            if has_commit(engine, txn.read_snapshot.table_root(), version)? {
+                let table_root = txn.read_snapshot.table_root().clone();
+                return Ok(PrepareResult::Conflict {
+                    transaction: self,
+                    table_root,
+                    version,
+                });
+            }
+        }
+
+        let commits = self
+            .transactions
+            .into_iter()
+            .map(|transaction| PreparedCommit::try_new(engine, transaction))
+            .collect::<DeltaResult<_>>()?;
+        Ok(PrepareResult::Prepared(PreparedMultiTableTransaction {
+            commits,
+        }))
+    }
+}
+
+/// The result of [`MultiTableTransaction::prepare`].
+#[derive(Debug)]
+pub enum PrepareResult {
+    /// Every commit was staged, and can now be committed.
+    Prepared(PreparedMultiTableTransaction),
+    /// The table at `table_root` already has a commit at `version`, the version its transaction
+    /// would commit. Nothing was staged, and the transaction is returned so the caller can retry.
+    Conflict {
+        transaction: MultiTableTransaction,
+        table_root: Url,
+        version: Version,
+    },
+}
+
+/// A multi-table transaction whose commits were staged by [`MultiTableTransaction::prepare`].
+#[derive(Debug)]
+pub struct PreparedMultiTableTransaction {
+    commits: Vec<PreparedCommit>,
+}
+
+/// The commit of a transaction, staged in memory at the version it commits.
+struct PreparedCommit {
+    transaction: Transaction,
+    version: Version,
+    actions: Vec<Box<dyn EngineData>>,
+}
+
+impl std::fmt::Debug for PreparedCommit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "PreparedCommit {{ transaction: {:?}, version: {} }}",
+            self.transaction, self.version
+        ))
+    }
+}
+
+impl PreparedCommit {
+    /// Generate the actions of `transaction`'s commit at the version after its read snapshot's.
+    fn try_new(engine: &dyn Engine, transaction: Transaction) -> DeltaResult<Self> {
+        let version = transaction.commit_version();
+        let actions = transaction
+            .commit_actions(engine, version)?
+            .collect::<DeltaResult<_>>()?;
+        Ok(Self {
+            transaction,
+            version,
+            actions,
+        })
+    }
+}
+
+impl PreparedMultiTableTransaction {
+    /// The versions the staged commits will be written at, in the order the transactions were
+    /// added.
+    pub fn versions(&self) -> impl Iterator<Item = Version> + '_ {
+        self.commits.iter().map(|commit| commit.version)
+    }
+
+    /// Commit the transactions in the order they were added: the second phase of the commit
+    /// protocol. Stops at the first transaction that fails to commit, leaving it and the ones
+    /// after it uncommitted.
+    pub fn commit(self, engine: &dyn Engine) -> MultiTableCommitResult {
+        let mut committed = vec![];
+        let mut commits = self.commits.into_iter();
+        while let Some(commit) = commits.next() {
+            let PreparedCommit {
+                transaction,
+                version,
+                actions,
+            } = commit;
+            let failure = match write_commit(engine, &transaction, version, actions) {
+                Ok(()) => {
+                    committed.push(transaction.into_committed(engine, version));
+                    continue;
+                }
+                Err(Error::FileAlreadyExists(_)) => CommitFailure::Conflict(version),
+                Err(e) => CommitFailure::Error(e),
+            };
+            return MultiTableCommitResult::Incomplete {
+                committed,
+                failure,
+                uncommitted: std::iter::once(transaction)
+                    .chain(commits.map(|commit| commit.transaction))
+                    .collect(),
+            };
+        }
+        MultiTableCommitResult::Committed(committed)
+    }
+
+    /// Abandon the prepared commits. No table was changed, so the transactions are returned as a
+    /// new multi-table transaction.
+    pub fn abort(self) -> MultiTableTransaction {
+        MultiTableTransaction {
+            transactions: self
+                .commits
+                .into_iter()
+                .map(|commit| commit.transaction)
+                .collect(),
+        }
+    }
+}
+
+/// The result of [`PreparedMultiTableTransaction::commit`].
+#[derive(Debug)]
+pub enum MultiTableCommitResult {
+    /// Every transaction was committed. The results are [`CommitResult::Committed`], in the order
+    /// the transactions were added.
+    Committed(Vec<CommitResult>),
+    /// Only the transactions of `committed` were committed: the next transaction failed to commit
+    /// with `failure`, and the ones after it were not attempted. `uncommitted` holds the failed
+    /// transaction and the ones after it. If `committed` is empty, no table was changed.
+    Incomplete {
+        committed: Vec<CommitResult>,
+        failure: CommitFailure,
+        uncommitted: Vec<Transaction>,
+    },
+}
+
+/// Why a transaction of a [`PreparedMultiTableTransaction`] failed to commit.
+#[derive(Debug)]
+pub enum CommitFailure {
+    /// Another writer committed the version the transaction would commit after it was prepared.
+    Conflict(Version),
+    /// Writing the commit of the transaction failed.
+    Error(Error),
+}
+
+/// Write `actions` as the commit of `transaction`'s table at `version`. Fails with
+/// [`Error::FileAlreadyExists`] if the table already has a commit at `version`.
+fn write_commit(
+    engine: &dyn Engine,
+    transaction: &Transaction,
+    version: Version,
+    actions: Vec<Box<dyn EngineData>>,
+) -> DeltaResult<()> {
+    let commit_path = ParsedLogPath::new_commit(transaction.read_snapshot.table_root(), version)?;
+    engine.json_handler().write_json_file(
+        &commit_path.location,
+        Box::new(actions.into_iter().map(Ok)),
+        false,
+    )
+}
+
+/// Whether the table at `table_root` has a (published) commit at `version`, which must be > 0.
+fn has_commit(engine: &dyn Engine, table_root: &Url, version: Version) -> DeltaResult<bool> {
+    let previous = ParsedLogPath::new_commit(table_root, version - 1)?;
+    for file in engine.storage_handler().list_from(&previous.location)? {
+        let Some(path) = ParsedLogPath::try_from(file?)? else {
+            continue;
+        };
+        if path.version > version {
+            break;
+        }
+        if path.version == version && path.file_type == LogPathFileType::Commit {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt as _;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use test_utils::{actions_to_string, TestAction};
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::Snapshot;
+
+    async fn put_commit(store: &InMemory, table: &str, version: Version, data: String) {
+        let path = Path::from(format!("{table}/_delta_log/{version:020}.json"));
+        store.put(&path, data.into()).await.unwrap();
+    }
+
+    async fn setup() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>) {
+        let store = Arc::new(InMemory::new());
+        for table in ["a", "b"] {
+            put_commit(
+                &store,
+                table,
+                0,
+                actions_to_string(vec![TestAction::Metadata]),
+            )
+            .await;
+        }
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (store, engine)
+    }
+
+    fn table_root(table: &str) -> Url {
+        Url::parse(&format!("memory:///{table}/")).unwrap()
+    }
+
+    fn transaction(engine: &dyn Engine, tables: &[&str]) -> MultiTableTransaction {
+        tables
+            .iter()
+            .fold(MultiTableTransaction::new(), |txn, table| {
+                let snapshot = Snapshot::builder_for(table_root(table))
+                    .build(engine)
+                    .unwrap();
+                txn.with_transaction(snapshot.transaction().unwrap())
+            })
+    }
+
+    fn latest_version(engine: &dyn Engine, table: &str) -> Version {
+        Snapshot::builder_for(table_root(table))
+            .build(engine)
+            .unwrap()
+            .version()
+    }
+
+    /// The names of the files in the log of `table`, including any in its subdirectories.
+    async fn log_files(store: &InMemory, table: &str) -> Vec<String> {
+        let log = Path::from(format!("{table}/_delta_log"));
+        let mut files: Vec<String> = store
+            .list(Some(&log))
+            .map_ok(|meta| {
+                meta.location
+                    .as_ref()
+                    .trim_start_matches(log.as_ref())
+                    .to_string()
+            })
+            .try_collect()
+            .await
+            .unwrap();
+        files.sort();
+        files
+    }
+
+    fn commit_files(versions: impl IntoIterator<Item = Version>) -> Vec<String> {
+        versions
+            .into_iter()
+            .map(|version| format!("/{version:020}.json"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_commit_multiple_tables() {
+        let (store, engine) = setup().await;
+        let PrepareResult::Prepared(prepared) =
+            transaction(&engine, &["a", "b"]).prepare(&engine).unwrap()
+        else {
+            panic!("Expected the transaction to be prepared");
+        };
+        assert_eq!(prepared.versions().collect::<Vec<_>>(), [1, 1]);
+        // preparing writes nothing to the tables
+        assert_eq!(log_files(&store, "a").await, commit_files([0]));
+        assert_eq!(latest_version(&engine, "a"), 0);
+
+        let MultiTableCommitResult::Committed(results) = prepared.commit(&engine) else {
+            panic!("Expected every transaction to be committed");
+        };
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, CommitResult::Committed { version: 1, .. })));
+        assert_eq!(latest_version(&engine, "a"), 1);
+        assert_eq!(latest_version(&engine, "b"), 1);
+        for table in ["a", "b"] {
+            assert_eq!(log_files(&store, table).await, commit_files([0, 1]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conflict_while_preparing() {
+        let (store, engine) = setup().await;
+        let txn = transaction(&engine, &["a", "b"]);
+        put_commit(&store, "b", 1, actions_to_string(vec![])).await;
+
+        let PrepareResult::Conflict {
+            transaction,
+            table_root: conflicting,
+            version,
+        } = txn.prepare(&engine).unwrap()
+        else {
+            panic!("Expected the transaction to conflict");
+        };
+        assert_eq!(transaction.transactions().len(), 2);
+        assert_eq!(conflicting, table_root("b"));
+        assert_eq!(version, 1);
+        assert_eq!(latest_version(&engine, "a"), 0);
+        assert_eq!(log_files(&store, "a").await, commit_files([0]));
+        assert_eq!(log_files(&store, "b").await, commit_files([0, 1]));
+    }
+
+    #[tokio::test]
+    async fn test_conflict_while_committing() {
+        let (store, engine) = setup().await;
+        let PrepareResult::Prepared(prepared) =
+            transaction(&engine, &["a", "b"]).prepare(&engine).unwrap()
+        else {
+            panic!("Expected the transaction to be prepared");
+        };
+        put_commit(&store, "b", 1, actions_to_string(vec![])).await;
+
+        let MultiTableCommitResult::Incomplete {
+            committed,
+            failure,
+            uncommitted,
+        } = prepared.commit(&engine)
+        else {
+            panic!("Expected the commit to be incomplete");
+        };
+        assert_eq!(committed.len(), 1);
+        assert!(matches!(failure, CommitFailure::Conflict(1)));
+        assert_eq!(uncommitted.len(), 1);
+        assert_eq!(latest_version(&engine, "a"), 1);
+        // only the concurrent commit was written to b
+        for table in ["a", "b"] {
+            assert_eq!(log_files(&store, table).await, commit_files([0, 1]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_abort() {
+        let (store, engine) = setup().await;
+        let PrepareResult::Prepared(prepared) =
+            transaction(&engine, &["a", "b"]).prepare(&engine).unwrap()
+        else {
+            panic!("Expected the transaction to be prepared");
+        };
+        let transaction = prepared.abort();
+        assert_eq!(transaction.transactions().len(), 2);
+        for table in ["a", "b"] {
+            assert_eq!(log_files(&store, table).await, commit_files([0]));
+        }
+        assert_eq!(latest_version(&engine, "a"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_tables() {
+        let (_, engine) = setup().await;
+        let res = transaction(&engine, &["a", "a"]).prepare(&engine);
+        assert!(
+            matches!(res, Err(Error::Generic(msg)) if msg.contains("more than one transaction"))
+        );
+    }
+}
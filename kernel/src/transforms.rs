@@ -175,14 +175,7 @@ pub(crate) fn parse_partition_value_raw(
     raw: Option<&String>,
     data_type: &DataType,
 ) -> DeltaResult<crate::expressions::Scalar> {
-    use crate::expressions::Scalar;
-    match (raw, data_type.as_primitive_opt()) {
-        (Some(v), Some(primitive)) => primitive.parse_scalar(v),
-        (Some(_), None) => Err(Error::generic(format!(
-            "Unexpected partition column type: {data_type:?}"
-        ))),
-        _ => Ok(Scalar::Null(data_type.clone())),
-    }
+    crate::partition_values::parse_partition_value(raw.map(String::as_str), data_type)
 }
 
 #[cfg(test)]
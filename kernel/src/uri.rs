@@ -0,0 +1,288 @@
+//! Resolution of table URIs into the URLs of the storage that holds the tables.
+//!
+//! Some table URIs do not name a storage location directly, e.g. `dbfs:/mnt/table` or
+//! `viewfs://cluster/table` are only meaningful to the platform that defines them. A
+//! [`UriResolverRegistry`] maps such URI schemes to [`UriResolver`]s that translate them into
+//! concrete storage URLs (e.g. `s3://bucket/table/`), so embedders can resolve table URIs before
+//! constructing the engine and the snapshot for them.
+//!
+//! The paths of the files a table's log references are resolved against the table root with
+//! [`resolve_file_path`]. They are usually relative, but may also be absolute URIs, e.g. in shallow
+//! clones, whose files live in the cloned table (possibly in another bucket). A [`PathPolicy`] lets
+//! engines veto reads of such files outside of the table root.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use url::Url;
+
+use crate::utils::{ensure_trailing_slash, require, try_parse_uri};
+use crate::{DeltaResult, Error};
+
+/// The maximum number of resolvers applied to a single URI, to detect resolution cycles.
+const MAX_RESOLUTION_DEPTH: usize = 8;
+
+/// Translates URIs of a custom scheme into the URLs of the storage locations they refer to.
+pub trait UriResolver: Send + Sync {
+    /// Resolves `uri`, whose scheme this resolver is registered for. The result may have another
+    /// custom scheme, which is resolved in turn.
+    fn resolve(&self, uri: &Url) -> DeltaResult<Url>;
+}
+
+impl<F> UriResolver for F
+where
+    F: Fn(&Url) -> DeltaResult<Url> + Send + Sync,
+{
+    fn resolve(&self, uri: &Url) -> DeltaResult<Url> {
+        self(uri)
+    }
+}
+
+/// A set of [`UriResolver`]s, keyed by the URI scheme they resolve.
+#[derive(Clone, Default)]
+pub struct UriResolverRegistry {
+    resolvers: HashMap<String, Arc<dyn UriResolver>>,
+}
+
+impl UriResolverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `resolver` for URIs with the given `scheme` (without the trailing `:`), replacing
+    /// any resolver previously registered for it. Schemes are case-insensitive.
+    pub fn with_resolver(
+        mut self,
+        scheme: impl AsRef<str>,
+        resolver: impl UriResolver + 'static,
+    ) -> Self {
+        self.resolvers
+            .insert(scheme.as_ref().to_ascii_lowercase(), Arc::new(resolver));
+        self
+    }
+
+    /// Resolves a table URI into the URL of the table root. The URI is first parsed like
+    /// [`try_parse_uri`] does, i.e. local paths become `file://` URLs, then the resolver registered
+    /// for its scheme (if any) is applied until the URL has a scheme without a resolver.
+    ///
+    /// [`try_parse_uri`]: crate::try_parse_uri
+    pub fn resolve(&self, table_uri: impl AsRef<str>) -> DeltaResult<Url> {
+        let mut url = try_parse_uri(table_uri.as_ref())?;
+        for _ in 0..MAX_RESOLUTION_DEPTH {
+            let Some(resolver) = self.resolvers.get(url.scheme()) else {
+                return Ok(url);
+            };
+            url = resolver.resolve(&url)?;
+            ensure_trailing_slash(&mut url);
+        }
+        Err(Error::invalid_table_location(format!(
+            "{} still has a custom scheme after {MAX_RESOLUTION_DEPTH} resolutions, \
+            the resolvers probably form a cycle",
+            table_uri.as_ref()
+        )))
+    }
+}
+
+impl fmt::Debug for UriResolverRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UriResolverRegistry")
+            .field("schemes", &self.resolvers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Resolves the `path` of a file referenced by the log of the table at `table_root` (e.g. of an add,
+/// remove or cdc action) into its URL.
+///
+/// Per the Delta protocol, paths are URIs: either absolute (with a scheme, e.g.
+/// `s3://other-bucket/part-0.parquet` or `file:///tmp/part-0.parquet`), or relative to the table
+/// root. Relative paths are percent-encoded, e.g. `a=x%20y/part-0.parquet` refers to the file
+/// `part-0.parquet` in the directory `a=x y`. Characters that may not appear in URLs, like spaces,
+/// are encoded if a writer left them unencoded.
+pub fn resolve_file_path(table_root: &Url, path: &str) -> DeltaResult<Url> {
+    require!(
+        !path.is_empty(),
+        Error::generic(format!("Empty file path in the log of table {table_root}"))
+    );
+    if has_scheme(path) {
+        return Ok(Url::parse(path)?);
+    }
+    // The first segment of a relative path can contain colons (e.g. `ts=2024-01-01 00%3A00`, as
+    // written by writers that don't encode them), which `Url::join` would mistake for a scheme.
+    let path = match path.starts_with('/') {
+        true => path.to_string(),
+        false => format!("./{path}"),
+    };
+    Ok(table_root.join(&path)?)
+}
+
+/// Whether `path` starts with a URI scheme, i.e. `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":"`.
+/// Single letters are not considered schemes, as they are more likely Windows drive letters.
+fn has_scheme(path: &str) -> bool {
+    let Some((scheme, _)) = path.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    scheme.len() > 1
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Whether `file` is located in the table at `table_root`, i.e. has the same scheme and authority
+/// and a path under the table root's path.
+pub fn is_in_table(table_root: &Url, file: &Url) -> bool {
+    table_root.scheme() == file.scheme()
+        && table_root.host() == file.host()
+        && table_root.port_or_known_default() == file.port_or_known_default()
+        && table_root.username() == file.username()
+        && file.path().starts_with(table_root.path())
+}
+
+/// Decides whether a table's files that are located outside of its table root (see
+/// [`is_in_table`]) may be read, e.g. to prevent a table from making the engine read files of
+/// other tables it has no permission for.
+pub trait PathPolicy: fmt::Debug + Send + Sync {
+    /// Returns an error to veto reading `file`, a file outside of the table at `table_root`.
+    fn check_external_file(&self, table_root: &Url, file: &Url) -> DeltaResult<()>;
+}
+
+/// A [`PathPolicy`] that rejects all files outside of the table root.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyExternalFiles;
+
+impl PathPolicy for DenyExternalFiles {
+    fn check_external_file(&self, table_root: &Url, file: &Url) -> DeltaResult<()> {
+        Err(Error::generic(format!(
+            "File {file} is outside of the table root {table_root}, and external files are denied"
+        )))
+    }
+}
+
+/// [`resolve_file_path`], followed by checking the file against `policy` if it is outside of the
+/// table root.
+pub(crate) fn resolve_file_path_with_policy(
+    table_root: &Url,
+    path: &str,
+    policy: Option<&dyn PathPolicy>,
+) -> DeltaResult<Url> {
+    let file = resolve_file_path(table_root, path)?;
+    if let Some(policy) = policy {
+        if !is_in_table(table_root, &file) {
+            policy.check_external_file(table_root, &file)?;
+        }
+    }
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn dbfs(uri: &Url) -> DeltaResult<Url> {
+        let path = uri
+            .path()
+            .strip_prefix("/mnt/")
+            .ok_or_else(|| Error::invalid_table_location(format!("{uri} is not a mounted path")))?;
+        Ok(Url::parse(&format!("viewfs://cluster/{path}"))?)
+    }
+
+    #[test]
+    fn test_resolve() {
+        let registry = UriResolverRegistry::new()
+            .with_resolver("DBFS", dbfs)
+            .with_resolver("viewfs", |uri: &Url| -> DeltaResult<Url> {
+                Ok(Url::parse(&format!("s3://bucket{}", uri.path()))?)
+            });
+
+        // resolvers are chained, and the result gets a trailing slash
+        let url = registry.resolve("dbfs:/mnt/my%20table").unwrap();
+        assert_eq!(url.as_str(), "s3://bucket/my%20table/");
+        let url = registry.resolve("viewfs://cluster/a/b/").unwrap();
+        assert_eq!(url.as_str(), "s3://bucket/a/b/");
+        // schemes without a resolver are left alone
+        let url = registry.resolve("gs://bucket/table").unwrap();
+        assert_eq!(url.as_str(), "gs://bucket/table/");
+
+        let result = registry.resolve("dbfs:/tmp/table");
+        assert_result_error_with_message(result, "is not a mounted path");
+    }
+
+    #[test]
+    fn test_resolve_cycle() {
+        let registry = UriResolverRegistry::new()
+            .with_resolver("foo", |uri: &Url| -> DeltaResult<Url> {
+                Ok(Url::parse(&format!("bar:{}", uri.path()))?)
+            })
+            .with_resolver("bar", |uri: &Url| -> DeltaResult<Url> {
+                Ok(Url::parse(&format!("foo:{}", uri.path()))?)
+            });
+        let result = registry.resolve("foo:/table");
+        assert_result_error_with_message(result, "the resolvers probably form a cycle");
+    }
+
+    #[test]
+    fn test_resolve_file_path() {
+        let table_root = Url::parse("s3://bucket/table/").unwrap();
+        let resolve = |path| resolve_file_path(&table_root, path).unwrap().to_string();
+
+        assert_eq!(
+            resolve("part-0.parquet"),
+            "s3://bucket/table/part-0.parquet"
+        );
+        // percent-encoded names stay encoded, and unencoded spaces get encoded
+        assert_eq!(
+            resolve("a=x%20y/part-0.parquet"),
+            "s3://bucket/table/a=x%20y/part-0.parquet"
+        );
+        assert_eq!(
+            resolve("a=x y/part-0.parquet"),
+            "s3://bucket/table/a=x%20y/part-0.parquet"
+        );
+        // colons in relative paths are not schemes
+        assert_eq!(
+            resolve("ts=2024-01-01 00:00/part-0.parquet"),
+            "s3://bucket/table/ts=2024-01-01%2000:00/part-0.parquet"
+        );
+        // absolute URIs, also of other buckets and stores
+        assert_eq!(
+            resolve("s3://other-bucket/clone/part-0.parquet"),
+            "s3://other-bucket/clone/part-0.parquet"
+        );
+        assert_eq!(
+            resolve("file:///tmp/part-0.parquet"),
+            "file:///tmp/part-0.parquet"
+        );
+        assert_eq!(
+            resolve("/other/part-0.parquet"),
+            "s3://bucket/other/part-0.parquet"
+        );
+
+        let result = resolve_file_path(&table_root, "");
+        assert_result_error_with_message(result, "Empty file path");
+    }
+
+    #[test]
+    fn test_path_policy() {
+        let table_root = Url::parse("s3://bucket/table/").unwrap();
+        let resolve = |path, policy: Option<&dyn PathPolicy>| {
+            resolve_file_path_with_policy(&table_root, path, policy)
+        };
+        let policy = Some(&DenyExternalFiles as &dyn PathPolicy);
+
+        assert!(resolve("a/part-0.parquet", policy).is_ok());
+        assert!(resolve("s3://bucket/table/part-0.parquet", policy).is_ok());
+        for external in [
+            "s3://other-bucket/table/part-0.parquet",
+            "s3://bucket/table2/part-0.parquet",
+            "../other/part-0.parquet",
+            "gs://bucket/table/part-0.parquet",
+        ] {
+            let result = resolve(external, policy);
+            assert_result_error_with_message(result, "external files are denied");
+            // without a policy, external files can be read
+            assert!(resolve(external, None).is_ok());
+        }
+    }
+}
@@ -1,6 +1,5 @@
 //! Various utility functions/macros used throughout the kernel
 use std::borrow::Cow;
-use std::ops::Deref;
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -65,33 +64,38 @@ enum UriType {
 }
 
 /// Utility function to figure out whether string representation of the path is either local path or
-/// some kind or URL.
+/// some kind or URL. URLs are normalized to end with a trailing slash, as table roots must.
 ///
 /// Will return an error if the path is not valid.
 #[allow(unused)]
 fn resolve_uri_type(table_uri: impl AsRef<str>) -> DeltaResult<UriType> {
     let table_uri = table_uri.as_ref();
-    let table_uri = if table_uri.ends_with('/') {
-        Cow::Borrowed(table_uri)
-    } else {
-        Cow::Owned(format!("{table_uri}/"))
-    };
-    if let Ok(url) = Url::parse(&table_uri) {
-        let scheme = url.scheme().to_string();
-        if url.scheme() == "file" {
-            Ok(UriType::LocalPath(
-                url.to_file_path()
-                    .map_err(|_| Error::invalid_table_location(table_uri))?,
-            ))
-        } else if scheme.len() == 1 {
-            // NOTE this check is required to support absolute windows paths which may properly
-            // parse as url we assume here that a single character scheme is a windows drive letter
-            Ok(UriType::LocalPath(PathBuf::from(table_uri.as_ref())))
-        } else {
+    match Url::parse(table_uri) {
+        // NOTE this check is required to support absolute windows paths which may properly parse as
+        // url, e.g. `C:\table` or `c:/table`. We assume here that a single character scheme is a
+        // windows drive letter
+        Ok(url) if url.scheme().len() == 1 => Ok(UriType::LocalPath(PathBuf::from(table_uri))),
+        // file URLs are percent-decoded into paths, e.g. `file:///my%20table` is `/my table`
+        Ok(url) if url.scheme() == "file" => Ok(UriType::LocalPath(
+            url.to_file_path()
+                .map_err(|_| Error::invalid_table_location(table_uri))?,
+        )),
+        Ok(mut url) => {
+            ensure_trailing_slash(&mut url);
             Ok(UriType::Url(url))
         }
-    } else {
-        Ok(UriType::LocalPath(table_uri.deref().into()))
+        // relative paths, paths without a scheme (including windows UNC paths) and paths that are
+        // not valid URLs, e.g. because they contain spaces
+        Err(_) => Ok(UriType::LocalPath(PathBuf::from(table_uri))),
+    }
+}
+
+/// Appends a slash to the path of `url` if it does not end with one yet. Unlike appending it to
+/// the URL string, this keeps any query string or fragment intact.
+pub(crate) fn ensure_trailing_slash(url: &mut Url) {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
     }
 }
 
@@ -144,6 +148,36 @@ impl<'a, T: ToOwned + ?Sized> CowExt<(Cow<'a, T>, Cow<'a, T>)> for (Cow<'a, T>,
     }
 }
 
+// Extension trait for iterators whose work should be attributed to a tracing span
+pub(crate) trait SpanIteratorExt: Iterator + Sized {
+    /// Enter `span` whenever the iterator is advanced. Kernel iterators do most of their work
+    /// lazily, so entering a span only while the iterator is created would miss most of it.
+    fn in_span(self, span: tracing::Span) -> InSpan<Self> {
+        InSpan { span, inner: self }
+    }
+}
+
+impl<I: Iterator> SpanIteratorExt for I {}
+
+/// An iterator that enters its span whenever it is advanced, see [`SpanIteratorExt::in_span`].
+pub(crate) struct InSpan<I> {
+    span: tracing::Span,
+    inner: I,
+}
+
+impl<I: Iterator> Iterator for InSpan<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let _entered = self.span.enter();
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test_utils {
     use crate::actions::{get_log_schema, Add, Cdc, CommitInfo, Metadata, Protocol, Remove};
@@ -328,6 +362,44 @@ mod tests {
         resolve_uri_type("file://foo/bar").expect_err("file://foo/bar should not have parsed");
     }
 
+    #[test]
+    fn test_uri_normalization() {
+        // spaces are percent-encoded, and already percent-encoded paths are kept as they are
+        for uri in [
+            "s3://foo/my table",
+            "s3://foo/my%20table",
+            "s3://foo/my%20table/",
+        ] {
+            assert_eq!(try_parse_uri(uri).unwrap().as_str(), "s3://foo/my%20table/");
+        }
+        // the trailing slash goes at the end of the path, not of the query string
+        let url = try_parse_uri("https://foo/bar?sig=a%2Fb").unwrap();
+        assert_eq!(url.as_str(), "https://foo/bar/?sig=a%2Fb");
+        assert_eq!(try_parse_uri("s3://foo").unwrap().as_str(), "s3://foo/");
+
+        match resolve_uri_type(r"\\server\share\table") {
+            Ok(UriType::LocalPath(_)) => {}
+            x => panic!("Should have parsed as a local path {x:?}"),
+        }
+        match resolve_uri_type(r"C:\Users\table") {
+            Ok(UriType::LocalPath(_)) => {}
+            x => panic!("Should have parsed as a local path {x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_local_path_with_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let table = dir.path().join("my table");
+        std::fs::create_dir(&table).unwrap();
+
+        let url = try_parse_uri(table.to_str().unwrap()).unwrap();
+        assert!(url.as_str().ends_with("/my%20table/"), "{url}");
+        // the equivalent file URL is percent-decoded back into the same path
+        assert_eq!(try_parse_uri(url.as_str()).unwrap(), url);
+        assert_eq!(url.to_file_path().unwrap(), table.canonicalize().unwrap());
+    }
+
     #[test]
     fn try_from_uri_without_trailing_slash() {
         let location = "s3://foo/__unitystorage/catalogs/cid/tables/tid";
@@ -0,0 +1,626 @@
+//! Diagnostics for the consistency of a table's log and the files it references, as a foundation
+//! for fsck-style tooling.
+//!
+//! Reads fail on the first problem they encounter, and only look at the files they need. In
+//! contrast, [`verify_table`] walks the entire log and collects every problem it finds into a
+//! [`VerificationReport`] of structured [`Finding`]s. [`verify_snapshot_files`] only checks the
+//! files a snapshot references, so engines can fail fast before scanning it instead of failing
+//! mid-scan on a missing file.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::LazyLock;
+
+use itertools::Itertools;
+use tracing::debug;
+use url::Url;
+
+use crate::actions::visitors::SidecarVisitor;
+use crate::actions::{get_log_schema, SIDECAR_NAME};
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::scan::state::{DvInfo, Stats};
+use crate::schema::SchemaRef;
+use crate::uri::resolve_file_path;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, ExpressionRef, FileDataReadResultIterator,
+    RowVisitor as _, Snapshot, SnapshotRef, StorageHandler, Version,
+};
+
+/// The part of the log schema needed to find the sidecars a checkpoint references.
+#[allow(clippy::unwrap_used)]
+static SIDECAR_SCHEMA: LazyLock<SchemaRef> =
+    LazyLock::new(|| get_log_schema().project(&[SIDECAR_NAME]).unwrap());
+
+/// A problem found by [`verify_table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// The commits for versions `start..=end` are missing, so the log cannot be replayed across
+    /// them. A missing prefix of the log is not reported if a checkpoint supersedes it, as is the
+    /// case after log cleanup.
+    MissingVersions { start: Version, end: Version },
+    /// A file in the log directory is named like a log file, but its name is malformed.
+    InvalidLogPath { path: Url },
+    /// A commit cannot be read.
+    UnreadableCommit {
+        version: Version,
+        path: Url,
+        error: String,
+    },
+    /// A checkpoint (part) cannot be read.
+    UnreadableCheckpoint {
+        version: Version,
+        path: Url,
+        error: String,
+    },
+    /// A checkpoint references a sidecar that does not exist.
+    DanglingSidecar { checkpoint: Url, sidecar: Url },
+    /// The latest snapshot of the table cannot be loaded or scanned, so the files it references
+    /// were not checked.
+    UnreadableSnapshot { error: String },
+    /// A data file of the latest snapshot does not exist.
+    MissingDataFile { path: Url },
+    /// A data file of the latest snapshot does not have the size its add action records.
+    DataFileSizeMismatch {
+        path: Url,
+        expected: i64,
+        actual: u64,
+    },
+    /// A data file of the latest snapshot references a deletion vector that does not exist.
+    DanglingDeletionVector {
+        data_file: Url,
+        deletion_vector: Url,
+    },
+    /// A data file of the latest snapshot has a deletion vector descriptor that cannot be
+    /// resolved to a file.
+    InvalidDeletionVector { data_file: Url, error: String },
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingVersions { start, end } => {
+                write!(f, "Commits for versions {start} to {end} are missing")
+            }
+            Self::InvalidLogPath { path } => write!(f, "Malformed log file name: {path}"),
+            Self::UnreadableCommit {
+                version,
+                path,
+                error,
+            } => write!(f, "Commit {version} at {path} cannot be read: {error}"),
+            Self::UnreadableCheckpoint {
+                version,
+                path,
+                error,
+            } => write!(f, "Checkpoint {version} at {path} cannot be read: {error}"),
+            Self::DanglingSidecar {
+                checkpoint,
+                sidecar,
+            } => write!(
+                f,
+                "Sidecar {sidecar} of checkpoint {checkpoint} does not exist"
+            ),
+            Self::UnreadableSnapshot { error } => {
+                write!(f, "The latest snapshot cannot be read: {error}")
+            }
+            Self::MissingDataFile { path } => write!(f, "Data file {path} does not exist"),
+            Self::DataFileSizeMismatch {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Data file {path} has {actual} bytes, but the log records {expected} bytes"
+            ),
+            Self::DanglingDeletionVector {
+                data_file,
+                deletion_vector,
+            } => write!(
+                f,
+                "Deletion vector {deletion_vector} of data file {data_file} does not exist"
+            ),
+            Self::InvalidDeletionVector { data_file, error } => write!(
+                f,
+                "Deletion vector of data file {data_file} cannot be resolved: {error}"
+            ),
+        }
+    }
+}
+
+/// The result of [`verify_table`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// The latest version of a commit or checkpoint in the log, if any.
+    pub latest_version: Option<Version>,
+    /// The problems found, in the order they were found.
+    pub findings: Vec<Finding>,
+}
+
+impl VerificationReport {
+    /// Whether no problems were found.
+    pub fn is_healthy(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Fails with an [`Error::InvalidTableState`] listing the findings, unless there are none.
+    pub fn ensure_healthy(&self) -> DeltaResult<()> {
+        if self.is_healthy() {
+            return Ok(());
+        }
+        Err(Error::invalid_table_state(format!(
+            "Found {} problems: {}",
+            self.findings.len(),
+            self.findings.iter().join("; ")
+        )))
+    }
+}
+
+/// Walks the log of the table at `table_root` (a directory URL) and reports the problems it finds,
+/// instead of failing on the first one:
+/// - commit versions that are missing (see [`Finding::MissingVersions`])
+/// - log files with malformed names
+/// - commits and checkpoints that cannot be read, and sidecars that checkpoints reference but do
+///   not exist
+/// - data files and deletion vectors of the latest snapshot that do not exist, or that do not
+///   have the size the log records (see [`verify_snapshot_files`])
+///
+/// Returns an error only if the log cannot be listed, or if listing fails while checking whether
+/// referenced files exist.
+///
+/// NOTE: This reads every commit and checkpoint in the log, and lists every directory that
+/// contains a data file or deletion vector of the latest snapshot, so it is much more expensive
+/// than a read of the table.
+pub fn verify_table(engine: &dyn Engine, table_root: &Url) -> DeltaResult<VerificationReport> {
+    let storage = engine.storage_handler();
+    let log_root = table_root.join("_delta_log/")?;
+    let mut findings = vec![];
+
+    let mut log_files = vec![];
+    for file in storage.list_from(&log_root.join(&format!("{:020}", 0))?)? {
+        let file = file?;
+        let location = file.location.clone();
+        match ParsedLogPath::try_from(file) {
+            Ok(Some(log_file)) if log_file.should_list() => log_files.push(log_file),
+            Ok(_) => debug!("Ignoring non-log file {location}"),
+            Err(_) => findings.push(Finding::InvalidLogPath { path: location }),
+        }
+    }
+    let latest_version = log_files
+        .iter()
+        .filter(|log_file| log_file.is_commit() || log_file.is_checkpoint())
+        .map(|log_file| log_file.version)
+        .max();
+
+    let commit_versions: Vec<_> = log_files
+        .iter()
+        .filter(|log_file| log_file.file_type == LogPathFileType::Commit)
+        .map(|log_file| log_file.version)
+        .sorted()
+        .dedup()
+        .collect();
+    let checkpoint_versions: HashSet<_> = log_files
+        .iter()
+        .filter(|log_file| log_file.is_checkpoint())
+        .map(|log_file| log_file.version)
+        .collect();
+    findings.extend(find_missing_versions(
+        &commit_versions,
+        &checkpoint_versions,
+    ));
+
+    let mut file_checker = FileChecker::new(storage.as_ref());
+    for log_file in &log_files {
+        let version = log_file.version;
+        let path = log_file.location.location.clone();
+        if log_file.file_type == LogPathFileType::Commit {
+            let result = engine
+                .json_handler()
+                .read_json_files(&[log_file.location.clone()], get_log_schema().clone(), None)
+                .and_then(|batches| visit_batches(batches, |_| Ok(())));
+            if let Err(e) = result {
+                let error = e.to_string();
+                findings.push(Finding::UnreadableCommit {
+                    version,
+                    path,
+                    error,
+                });
+            }
+        } else if log_file.is_checkpoint() {
+            let files = [log_file.location.clone()];
+            let batches = if log_file.extension == "json" {
+                engine
+                    .json_handler()
+                    .read_json_files(&files, SIDECAR_SCHEMA.clone(), None)
+            } else {
+                engine
+                    .parquet_handler()
+                    .read_parquet_files(&files, SIDECAR_SCHEMA.clone(), None)
+            };
+            let mut visitor = SidecarVisitor::default();
+            let sidecars = batches
+                .and_then(|batches| visit_batches(batches, |batch| visitor.visit_rows_of(batch)))
+                .and_then(|_| {
+                    visitor
+                        .sidecars
+                        .iter()
+                        .map(|sidecar| sidecar.to_filemeta(&log_root).map(|meta| meta.location))
+                        .collect::<DeltaResult<Vec<_>>>()
+                });
+            match sidecars {
+                Ok(sidecars) => {
+                    for sidecar in sidecars {
+                        if !file_checker.exists(&sidecar)? {
+                            let checkpoint = path.clone();
+                            findings.push(Finding::DanglingSidecar {
+                                checkpoint,
+                                sidecar,
+                            });
+                        }
+                    }
+                }
+                Err(e) => {
+                    let error = e.to_string();
+                    findings.push(Finding::UnreadableCheckpoint {
+                        version,
+                        path,
+                        error,
+                    });
+                }
+            }
+        }
+    }
+
+    let snapshot = Snapshot::builder_for(table_root.clone()).build(engine);
+    let result = snapshot
+        .and_then(|snapshot| check_live_files(engine, snapshot, &mut file_checker, &mut findings));
+    if let Err(e) = result {
+        findings.push(Finding::UnreadableSnapshot {
+            error: e.to_string(),
+        });
+    }
+
+    Ok(VerificationReport {
+        latest_version,
+        findings,
+    })
+}
+
+/// Reports the gaps in the (ascending, deduplicated) `commit_versions`. A gap before the first
+/// commit is expected after log cleanup, as long as a checkpoint supersedes the missing commits.
+fn find_missing_versions(
+    commit_versions: &[Version],
+    checkpoint_versions: &HashSet<Version>,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+    let mut next_version = 0;
+    for &version in commit_versions {
+        // log cleanup deletes the commits before a checkpoint, keeping either the checkpoint's
+        // own commit or only the ones after it
+        let superseded = next_version == 0
+            && (checkpoint_versions.contains(&version)
+                || version
+                    .checked_sub(1)
+                    .is_some_and(|previous| checkpoint_versions.contains(&previous)));
+        if version > next_version && !superseded {
+            findings.push(Finding::MissingVersions {
+                start: next_version,
+                end: version - 1,
+            });
+        }
+        next_version = version + 1;
+    }
+    findings
+}
+
+/// Consumes `batches`, passing each one to `visit`.
+fn visit_batches(
+    batches: FileDataReadResultIterator,
+    mut visit: impl FnMut(&dyn EngineData) -> DeltaResult<()>,
+) -> DeltaResult<()> {
+    batches.try_for_each(|batch| visit(batch?.as_ref()))
+}
+
+/// Checks that the data files and deletion vectors `snapshot` references exist, and that the data
+/// files have the size their add actions record, and reports the problems it finds as
+/// [`Finding::MissingDataFile`], [`Finding::DataFileSizeMismatch`],
+/// [`Finding::DanglingDeletionVector`] and [`Finding::InvalidDeletionVector`].
+///
+/// Use [`VerificationReport::ensure_healthy`] to fail when files are missing, e.g. before
+/// scanning the snapshot:
+///
+/// ```no_run
+/// # use delta_kernel::{DeltaResult, Engine, SnapshotRef};
+/// # use delta_kernel::verify::verify_snapshot_files;
+/// # fn check(engine: &dyn Engine, snapshot: SnapshotRef) -> DeltaResult<()> {
+/// verify_snapshot_files(engine, snapshot.clone())?.ensure_healthy()?;
+/// let scan = snapshot.scan_builder().build()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Returns an error if the snapshot cannot be scanned, or if listing a directory fails.
+///
+/// NOTE: This replays the snapshot's log and lists every directory that contains a data file or
+/// deletion vector, i.e. it costs about as much as planning a full scan of the snapshot.
+pub fn verify_snapshot_files(
+    engine: &dyn Engine,
+    snapshot: SnapshotRef,
+) -> DeltaResult<VerificationReport> {
+    let latest_version = Some(snapshot.version());
+    let storage = engine.storage_handler();
+    let mut file_checker = FileChecker::new(storage.as_ref());
+    let mut findings = vec![];
+    check_live_files(engine, snapshot, &mut file_checker, &mut findings)?;
+    Ok(VerificationReport {
+        latest_version,
+        findings,
+    })
+}
+
+/// A data file of a snapshot: its path, size and deletion vector.
+type LiveFile = (String, i64, DvInfo);
+
+/// Reports the data files and deletion vectors of `snapshot` that are missing or have the wrong
+/// size into `findings`.
+fn check_live_files(
+    engine: &dyn Engine,
+    snapshot: SnapshotRef,
+    file_checker: &mut FileChecker<'_>,
+    findings: &mut Vec<Finding>,
+) -> DeltaResult<()> {
+    let table_root = snapshot.table_root().clone();
+    for (path, size, dv_info) in live_files(engine, snapshot)? {
+        let data_file = resolve_file_path(&table_root, &path)?;
+        match file_checker.size(&data_file)? {
+            None => findings.push(Finding::MissingDataFile {
+                path: data_file.clone(),
+            }),
+            Some(actual) if u64::try_from(size).ok() != Some(actual) => {
+                findings.push(Finding::DataFileSizeMismatch {
+                    path: data_file.clone(),
+                    expected: size,
+                    actual,
+                })
+            }
+            Some(_) => {}
+        }
+        let Some(deletion_vector) = dv_info.deletion_vector else {
+            continue;
+        };
+        match deletion_vector.absolute_path(&table_root) {
+            Ok(Some(dv_path)) if !file_checker.exists(&dv_path)? => {
+                findings.push(Finding::DanglingDeletionVector {
+                    data_file,
+                    deletion_vector: dv_path,
+                });
+            }
+            Ok(_) => {}
+            Err(e) => findings.push(Finding::InvalidDeletionVector {
+                data_file,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the path, size and deletion vector of every data file in `snapshot`.
+fn live_files(engine: &dyn Engine, snapshot: SnapshotRef) -> DeltaResult<Vec<LiveFile>> {
+    fn collect(
+        files: &mut Vec<LiveFile>,
+        path: &str,
+        size: i64,
+        _: Option<Stats>,
+        dv_info: DvInfo,
+        _: Option<ExpressionRef>,
+        _: HashMap<String, String>,
+        _: HashMap<String, String>,
+    ) {
+        files.push((path.to_string(), size, dv_info));
+    }
+
+    let scan = snapshot.scan_builder().build()?;
+    scan.scan_metadata(engine)?
+        .try_fold(vec![], |files, scan_metadata| {
+            scan_metadata?.visit_scan_files(files, collect)
+        })
+}
+
+/// Checks whether files exist (and their sizes), listing the directory of each file only once.
+struct FileChecker<'a> {
+    storage: &'a dyn StorageHandler,
+    /// The size of each file in each listed directory
+    listed_dirs: HashMap<Url, HashMap<Url, u64>>,
+}
+
+impl<'a> FileChecker<'a> {
+    fn new(storage: &'a dyn StorageHandler) -> Self {
+        Self {
+            storage,
+            listed_dirs: HashMap::new(),
+        }
+    }
+
+    fn exists(&mut self, file: &Url) -> DeltaResult<bool> {
+        Ok(self.size(file)?.is_some())
+    }
+
+    /// The size of `file`, or `None` if it does not exist.
+    fn size(&mut self, file: &Url) -> DeltaResult<Option<u64>> {
+        let files = match self.listed_dirs.entry(file.join(".")?) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let files = list_dir(self.storage, entry.key())?;
+                entry.insert(files)
+            }
+        };
+        Ok(files.get(file).copied())
+    }
+}
+
+/// Lists the files in `dir`, with their sizes. A directory that does not exist is empty.
+fn list_dir(storage: &dyn StorageHandler, dir: &Url) -> DeltaResult<HashMap<Url, u64>> {
+    let files: DeltaResult<HashMap<_, _>> = storage.list_from(dir).and_then(|files| {
+        files
+            .map_ok(|file| (file.location, file.size))
+            .try_collect()
+    });
+    match files {
+        Err(Error::IOError(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(Error::FileNotFound(_)) => Ok(HashMap::new()),
+        files => files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::engine::sync::SyncEngine;
+
+    fn write_log_file(table: &Path, name: &str, actions: &[&str]) {
+        let log_dir = table.join("_delta_log");
+        std::fs::create_dir_all(&log_dir).unwrap();
+        std::fs::write(log_dir.join(name), actions.join("\n")).unwrap();
+    }
+
+    const PROTOCOL: &str = r#"{"protocol":{"minReaderVersion":3,"minWriterVersion":7,"readerFeatures":["deletionVectors"],"writerFeatures":["deletionVectors"]}}"#;
+    const METADATA: &str = r#"{"metaData":{"id":"id","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1}}"#;
+
+    #[test]
+    fn test_find_missing_versions() {
+        let missing = |start, end| Finding::MissingVersions { start, end };
+        let checkpoints = HashSet::from([4]);
+        assert_eq!(find_missing_versions(&[0, 1, 2], &checkpoints), vec![]);
+        // commits before the checkpoint were cleaned up
+        assert_eq!(find_missing_versions(&[5, 6], &checkpoints), vec![]);
+        assert_eq!(find_missing_versions(&[4, 5], &checkpoints), vec![]);
+        assert_eq!(
+            find_missing_versions(&[3, 6, 9], &checkpoints),
+            vec![missing(0, 2), missing(4, 5), missing(7, 8)]
+        );
+    }
+
+    #[test]
+    fn test_verify_healthy_table() {
+        let engine = SyncEngine::new();
+        for table in ["table-with-dv-small", "basic_partitioned"] {
+            let path = std::fs::canonicalize(PathBuf::from("./tests/data").join(table)).unwrap();
+            let url = Url::from_directory_path(path).unwrap();
+            let report = verify_table(&engine, &url).unwrap();
+            assert!(report.is_healthy(), "{table}: {:?}", report.findings);
+            assert!(report.latest_version.is_some());
+        }
+    }
+
+    #[test]
+    fn test_verify_broken_log() {
+        let dir = tempfile::tempdir().unwrap();
+        write_log_file(
+            dir.path(),
+            &format!("{:020}.json", 0),
+            &[PROTOCOL, METADATA],
+        );
+        write_log_file(dir.path(), &format!("{:020}.json", 2), &["not json"]);
+        let checkpoint = format!(
+            "{:020}.checkpoint.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json",
+            0
+        );
+        let sidecar =
+            r#"{"sidecar":{"path":"missing.parquet","sizeInBytes":1,"modificationTime":1}}"#;
+        write_log_file(dir.path(), &checkpoint, &[PROTOCOL, METADATA, sidecar]);
+        let url = Url::from_directory_path(dir.path()).unwrap();
+
+        let report = verify_table(&SyncEngine::new(), &url).unwrap();
+        assert_eq!(report.latest_version, Some(2));
+        let findings = &report.findings;
+        assert!(findings.contains(&Finding::MissingVersions { start: 1, end: 1 }));
+        assert!(findings
+            .iter()
+            .any(|finding| matches!(finding, Finding::UnreadableCommit { version: 2, .. })));
+        let sidecar = url.join("_delta_log/_sidecars/missing.parquet").unwrap();
+        assert!(findings.iter().any(|finding| matches!(
+            finding,
+            Finding::DanglingSidecar { sidecar: s, .. } if *s == sidecar
+        )));
+        assert!(findings
+            .iter()
+            .any(|finding| matches!(finding, Finding::UnreadableSnapshot { .. })));
+    }
+
+    #[test]
+    fn test_verify_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("present.parquet"), "x").unwrap();
+        let add = |path: &str, dv: &str| {
+            format!(
+                r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1,"dataChange":true{dv}}}}}"#
+            )
+        };
+        let dv = r#","deletionVector":{"storageType":"u","pathOrInlineDv":"ab^-aqEH.-t@S}K{vb[*k^","offset":1,"sizeInBytes":36,"cardinality":2}"#;
+        let (present, missing) = (add("present.parquet", dv), add("missing.parquet", ""));
+        write_log_file(
+            dir.path(),
+            &format!("{:020}.json", 0),
+            &[PROTOCOL, METADATA, &present, &missing],
+        );
+        let url = Url::from_directory_path(dir.path()).unwrap();
+
+        let report = verify_table(&SyncEngine::new(), &url).unwrap();
+        assert_eq!(report.latest_version, Some(0));
+        let data_file = url.join("present.parquet").unwrap();
+        let deletion_vector = url
+            .join("ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin")
+            .unwrap();
+        assert_eq!(
+            report.findings,
+            vec![
+                Finding::DanglingDeletionVector {
+                    data_file,
+                    deletion_vector
+                },
+                Finding::MissingDataFile {
+                    path: url.join("missing.parquet").unwrap()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_snapshot_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.parquet"), "abc").unwrap();
+        std::fs::write(dir.path().join("b.parquet"), "abc").unwrap();
+        let add = |path: &str, size: i64| {
+            format!(
+                r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":{size},"modificationTime":1,"dataChange":true}}}}"#
+            )
+        };
+        let (a, b) = (add("a.parquet", 3), add("b.parquet", 5));
+        write_log_file(
+            dir.path(),
+            &format!("{:020}.json", 0),
+            &[PROTOCOL, METADATA, &a, &b],
+        );
+        let url = Url::from_directory_path(dir.path()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url.clone()).build(&engine).unwrap();
+
+        let report = verify_snapshot_files(&engine, snapshot).unwrap();
+        assert_eq!(report.latest_version, Some(0));
+        assert_eq!(
+            report.findings,
+            vec![Finding::DataFileSizeMismatch {
+                path: url.join("b.parquet").unwrap(),
+                expected: 5,
+                actual: 3,
+            }]
+        );
+        let error = report.ensure_healthy().unwrap_err();
+        assert!(matches!(error, Error::InvalidTableState(_)), "{error}");
+        assert!(error
+            .to_string()
+            .contains("has 3 bytes, but the log records 5"));
+    }
+}
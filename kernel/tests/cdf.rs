@@ -52,7 +52,7 @@ fn read_cdf_for_table(
         .execute(engine)?
         .map(|scan_result| -> DeltaResult<_> {
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch = to_arrow(data)?;
             // Verify that the arrow record batches match the expected schema
@@ -174,7 +174,7 @@ async fn latest_snapshot_test(
     let batches: Vec<RecordBatch> = scan_res
         .map(|scan_result| -> DeltaResult<_> {
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch = to_arrow(data)?;
             if let Some(mask) = mask {
@@ -4,8 +4,9 @@ use std::sync::Arc;
 
 use delta_kernel::actions::deletion_vector::split_vector;
 use delta_kernel::arrow::array::AsArray as _;
+use delta_kernel::arrow::array::RecordBatch;
 use delta_kernel::arrow::compute::{concat_batches, filter_record_batch};
-use delta_kernel::arrow::datatypes::{Int64Type, Schema as ArrowSchema};
+use delta_kernel::arrow::datatypes::{Int32Type, Int64Type, Schema as ArrowSchema};
 use delta_kernel::engine::arrow_conversion::TryFromKernel as _;
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::DefaultEngine;
@@ -13,13 +14,14 @@ use delta_kernel::expressions::{
     column_expr, column_pred, Expression as Expr, ExpressionRef, Predicate as Pred,
 };
 use delta_kernel::parquet::file::properties::{EnabledStatistics, WriterProperties};
-use delta_kernel::scan::state::{transform_to_logical, DvInfo, Stats};
+use delta_kernel::scan::state::{DvInfo, Stats};
 use delta_kernel::scan::Scan;
 use delta_kernel::schema::{DataType, MetadataColumnSpec, Schema, StructField, StructType};
 use delta_kernel::{Engine, FileMeta, Snapshot};
 
 use itertools::Itertools;
 use object_store::{memory::InMemory, path::Path, ObjectStore};
+use test_utils::table_builder::TestTableBuilder;
 use test_utils::{
     actions_to_string, add_commit, generate_batch, generate_simple_batch, into_record_batch,
     load_test_data, read_scan, record_batch_to_bytes, record_batch_to_bytes_with_props, to_arrow,
@@ -317,7 +319,9 @@ fn read_with_execute(
     let batches = read_scan(scan, engine)?;
 
     if expected.is_empty() {
-        assert_eq!(batches.len(), 0);
+        // scans with no results still return (empty) batches
+        assert!(!batches.is_empty());
+        assert!(batches.iter().all(|batch| batch.num_rows() == 0));
     } else {
         let batch = concat_batches(&result_schema, &batches)?;
         assert_batches_sorted_eq!(expected, &[batch]);
@@ -340,6 +344,7 @@ fn scan_metadata_callback(
     dv_info: DvInfo,
     transform: Option<ExpressionRef>,
     _: HashMap<String, String>,
+    _: HashMap<String, String>,
 ) {
     batches.push(ScanFile {
         path: path.to_string(),
@@ -386,18 +391,22 @@ fn read_with_scan_metadata(
             )
             .unwrap();
 
-        for read_result in read_results {
-            let read_result = read_result.unwrap();
-            let len = read_result.len();
-            // to transform the physical data into the correct logical form
-            let logical = transform_to_logical(
-                engine,
-                read_result,
-                scan.physical_schema(),
-                scan.logical_schema(),
-                scan_file.transform.clone(),
-            )
-            .unwrap();
+        // to transform the physical data into the correct logical form
+        let read_results = match scan_file.transform.clone() {
+            Some(transform) => engine
+                .evaluation_handler()
+                .new_expression_evaluator(
+                    scan.physical_schema().clone(),
+                    transform,
+                    scan.logical_schema().clone().into(),
+                )
+                .evaluate_batches(read_results),
+            None => read_results,
+        };
+
+        for logical in read_results {
+            let logical = logical.unwrap();
+            let len = logical.len();
             let record_batch = to_arrow(logical).unwrap();
             let rest = split_vector(selection_vector.as_mut(), len, Some(true));
             let batch = if let Some(mask) = selection_vector.clone() {
@@ -1532,3 +1541,73 @@ async fn test_unsupported_metadata_columns() -> Result<(), Box<dyn std::error::E
 
     Ok(())
 }
+
+fn sorted_ids(batches: &[RecordBatch]) -> Vec<i32> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let ids = batch
+                .column_by_name("id")
+                .unwrap()
+                .as_primitive::<Int32Type>();
+            ids.values().to_vec()
+        })
+        .sorted()
+        .collect()
+}
+
+#[tokio::test]
+async fn generated_table_with_deletion_vectors_and_checkpoint(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::new_unchecked([
+        StructField::nullable("id", DataType::INTEGER),
+        StructField::nullable("val", DataType::STRING),
+    ]));
+    let batch = generate_batch(vec![
+        ("id", vec![1, 2, 3, 4].into_array()),
+        ("val", vec!["a", "a", "b", "a"].into_array()),
+    ])?;
+    let table = TestTableBuilder::new(schema)
+        .with_partition_columns(["val"])
+        .with_deletion_vectors()
+        .append(batch)
+        .delete_rows(0, [0, 2])
+        .checkpoint()
+        .build(Arc::new(InMemory::new()), Url::parse("memory:///table/")?)
+        .await?;
+    // the partition of "a" and the partition of "b" were written to separate files
+    assert_eq!(table.data_files().count(), 2);
+    assert_eq!(table.version(), 2);
+
+    let engine = table.engine();
+    let snapshot = Snapshot::builder_for(table.table_root().clone()).build(engine.as_ref())?;
+    assert_eq!(snapshot.log_segment_view().checkpoint_version(), Some(2));
+    let scan = snapshot.scan_builder().build()?;
+    assert_eq!(sorted_ids(&read_scan(&scan, engine)?), vec![2, 3]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn generated_table_with_column_mapping_and_change_data_feed(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let table = TestTableBuilder::new(Arc::new(StructType::new_unchecked([
+        StructField::nullable("id", DataType::INTEGER),
+        StructField::nullable("val", DataType::STRING),
+    ])))
+    .with_column_mapping()
+    .with_change_data_feed()
+    .append(generate_simple_batch()?)
+    .append(generate_simple_batch()?)
+    .build(Arc::new(InMemory::new()), Url::parse("memory:///table/")?)
+    .await?;
+
+    let engine = table.engine();
+    let snapshot = Snapshot::builder_for(table.table_root().clone()).build(engine.as_ref())?;
+    assert_eq!(snapshot.version(), 2);
+    let scan = snapshot.scan_builder().build()?;
+    assert_eq!(
+        sorted_ids(&read_scan(&scan, engine)?),
+        vec![1, 1, 2, 2, 3, 3]
+    );
+    Ok(())
+}
@@ -557,7 +557,8 @@ async fn test_row_tracking_with_empty_adds() -> DeltaResult<()> {
     let scan = snapshot.scan_builder().build()?;
     let batches = read_scan(&scan, engine)?;
 
-    assert!(batches.is_empty(), "Table should be empty");
+    let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(num_rows, 0, "Table should be empty");
 
     Ok(())
 }
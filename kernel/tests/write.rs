@@ -18,6 +18,7 @@ use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::parquet::DefaultParquetHandler;
 use delta_kernel::engine::default::DefaultEngine;
 
+use delta_kernel::transaction::hooks::{PostCommitContext, PostCommitHook};
 use delta_kernel::transaction::CommitResult;
 
 use test_utils::set_json_value;
@@ -95,6 +96,58 @@ async fn test_commit_info() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_commit_info_customization() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a simple table: one int column named 'number'
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(schema, &[], None, "test_table").await?
+    {
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+        let txn = snapshot
+            .transaction()?
+            .with_operation("WRITE".to_string())
+            .with_operation_parameter("mode", "Append")
+            .with_operation_parameter("partitionBy", "[]")
+            .with_engine_info("default engine")
+            .with_user_metadata("nightly ingest");
+        txn.commit(&engine)?;
+
+        let commit1 = store
+            .get(&Path::from(format!(
+                "/{table_name}/_delta_log/00000000000000000001.json"
+            )))
+            .await?;
+        let mut parsed_commit: serde_json::Value = serde_json::from_slice(&commit1.bytes().await?)?;
+        set_json_value(&mut parsed_commit, "commitInfo.timestamp", json!(0))?;
+        set_json_value(&mut parsed_commit, "commitInfo.txnId", json!(ZERO_UUID))?;
+
+        let expected_commit = json!({
+            "commitInfo": {
+                "timestamp": 0,
+                "operation": "WRITE",
+                "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                "operationParameters": {
+                    "mode": "Append",
+                    "partitionBy": "[]",
+                },
+                "engineInfo": "default engine",
+                "txnId": ZERO_UUID,
+                "userMetadata": "nightly ingest",
+            }
+        });
+        assert_eq!(parsed_commit, expected_commit);
+    }
+    Ok(())
+}
+
 // check that the timestamps in commit_info and add actions are within 10s of SystemTime::now()
 fn check_action_timestamps<'a>(
     parsed_commits: impl Iterator<Item = &'a serde_json::Value>,
@@ -183,7 +236,9 @@ async fn write_data_and_check_result_and_stats(
         CommitResult::Committed {
             version,
             post_commit_stats,
+            post_commit_hook_failures,
         } => {
+            assert!(post_commit_hook_failures.is_empty());
             assert_eq!(version, expected_since_commit as Version);
             assert_eq!(
                 post_commit_stats.commits_since_checkpoint,
@@ -755,6 +810,180 @@ async fn test_write_txn_actions() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_write_domain_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let (store, engine, table_location) = engine_store_setup("test_table", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema,
+        &[],
+        true,
+        vec![],
+        vec!["domainMetadata"],
+    )
+    .await?;
+
+    // system-controlled domains and duplicate domains are rejected
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert!(matches!(
+        snapshot
+            .transaction()?
+            .with_domain_metadata("delta.rowTracking".to_string(), "{}".to_string())
+            .commit(&engine),
+        Err(KernelError::Generic(msg)) if msg.contains("system-controlled domain delta.rowTracking")
+    ));
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert!(matches!(
+        snapshot
+            .transaction()?
+            .with_domain_metadata("app".to_string(), "a".to_string())
+            .with_domain_metadata_removed("app".to_string())
+            .commit(&engine),
+        Err(KernelError::Generic(msg)) if msg == "Domain app already modified in transaction"
+    ));
+
+    // commit 1: set two domains
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    snapshot
+        .transaction()?
+        .with_domain_metadata("app1".to_string(), "config1".to_string())
+        .with_domain_metadata("app2".to_string(), "config2".to_string())
+        .commit(&engine)?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert_eq!(
+        snapshot.get_domain_metadata("app1", &engine)?.as_deref(),
+        Some("config1")
+    );
+    assert_eq!(
+        snapshot.get_domain_metadata("app2", &engine)?.as_deref(),
+        Some("config2")
+    );
+
+    // commit 2: remove one domain, remove a missing domain (no-op), and update the other
+    snapshot
+        .transaction()?
+        .with_domain_metadata_removed("app1".to_string())
+        .with_domain_metadata_removed("missing".to_string())
+        .with_domain_metadata("app2".to_string(), "config2_v2".to_string())
+        .commit(&engine)?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert_eq!(snapshot.get_domain_metadata("app1", &engine)?, None);
+    assert_eq!(
+        snapshot.get_domain_metadata("app2", &engine)?.as_deref(),
+        Some("config2_v2")
+    );
+
+    let commit2 = store
+        .get(&Path::from(
+            "/test_table/_delta_log/00000000000000000002.json",
+        ))
+        .await?;
+    let parsed_commit: Vec<serde_json::Value> = Deserializer::from_slice(&commit2.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .try_collect()?;
+    let domain_metadatas: Vec<_> = parsed_commit
+        .iter()
+        .filter_map(|action| action.get("domainMetadata"))
+        .collect();
+    assert_eq!(
+        domain_metadatas,
+        vec![
+            &json!({"domain": "app1", "configuration": "config1", "removed": true}),
+            &json!({"domain": "app2", "configuration": "config2_v2", "removed": false}),
+        ]
+    );
+    Ok(())
+}
+
+struct RecordingHook {
+    fail: bool,
+    versions: std::sync::Mutex<Vec<Version>>,
+}
+
+impl PostCommitHook for RecordingHook {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn run(&self, _engine: &dyn Engine, context: &PostCommitContext<'_>) -> DeltaResult<()> {
+        self.versions.lock().unwrap().push(context.version());
+        if self.fail {
+            return Err(KernelError::generic("hook failed"));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_post_commit_hooks() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    for (table_url, engine, _store, _table_name) in
+        setup_test_tables(schema, &[], None, "test_table").await?
+    {
+        let failing = Arc::new(RecordingHook {
+            fail: true,
+            versions: Default::default(),
+        });
+        let succeeding = Arc::new(RecordingHook {
+            fail: false,
+            versions: Default::default(),
+        });
+        let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+        let result = snapshot
+            .transaction()?
+            .with_post_commit_hook(failing.clone())
+            .with_post_commit_hook(succeeding.clone())
+            .commit(&engine)?;
+
+        // a failing hook neither fails the commit nor stops the hooks after it
+        let CommitResult::Committed {
+            version,
+            post_commit_hook_failures,
+            ..
+        } = result
+        else {
+            panic!("Commit should have succeeded");
+        };
+        assert_eq!(version, 1);
+        assert_eq!(*failing.versions.lock().unwrap(), [1]);
+        assert_eq!(*succeeding.versions.lock().unwrap(), [1]);
+        assert_eq!(post_commit_hook_failures.len(), 1);
+        assert_eq!(post_commit_hook_failures[0].hook, "recording");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_domain_metadata_unsupported() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    for (table_url, engine, _store, _table_name) in
+        setup_test_tables(schema, &[], None, "test_table").await?
+    {
+        let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+        let res = snapshot
+            .transaction()?
+            .with_domain_metadata("app".to_string(), "config".to_string())
+            .commit(&engine);
+        assert!(matches!(res, Err(KernelError::Unsupported(_))));
+    }
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_append_timestamp_ntz() -> Result<(), Box<dyn std::error::Error>> {
     // setup tracing
@@ -0,0 +1,107 @@
+use reqwest::{header, Client, Response};
+use tracing::{debug, instrument};
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::models::{QueryTableRequest, QueryTableResponseLine};
+use crate::profile::SharingProfile;
+use crate::table::{SharedTable, SharedTableName};
+
+// asks the server to respond with Delta actions, which the kernel can read as a Delta log
+const CAPABILITIES_HEADER: &str = "delta-sharing-capabilities";
+const RESPONSE_FORMAT_DELTA: &str = "responseformat=delta";
+// the header with which the server reports the version of the queried table
+const TABLE_VERSION_HEADER: &str = "delta-table-version";
+
+/// A client for a Delta Sharing server.
+#[derive(Debug, Clone)]
+pub struct SharingClient {
+    client: Client,
+    endpoint: Url,
+}
+
+impl SharingClient {
+    pub fn new(profile: SharingProfile) -> Result<Self> {
+        if profile.is_expired() {
+            return Err(Error::InvalidProfile(
+                "The bearer token of the profile has expired".to_string(),
+            ));
+        }
+        let mut headers = header::HeaderMap::new();
+        let mut authorization =
+            header::HeaderValue::from_str(&format!("Bearer {}", profile.bearer_token))
+                .map_err(|e| Error::InvalidProfile(e.to_string()))?;
+        authorization.set_sensitive(true);
+        headers.insert(header::AUTHORIZATION, authorization);
+        let client = Client::builder().default_headers(headers).build()?;
+        Ok(Self {
+            client,
+            endpoint: profile.endpoint_url()?,
+        })
+    }
+
+    /// Queries the files of the given table, returning the response lines in the `delta` format.
+    /// Also returns the version of the table, if reported by the server.
+    #[instrument(skip(self))]
+    pub async fn query_table(
+        &self,
+        table: &SharedTableName,
+        request: &QueryTableRequest,
+    ) -> Result<(Option<u64>, Vec<QueryTableResponseLine>)> {
+        let url = self.endpoint.join(&format!(
+            "shares/{}/schemas/{}/tables/{}/query",
+            table.share, table.schema, table.table
+        ))?;
+        let response = self
+            .client
+            .post(url)
+            .header(CAPABILITIES_HEADER, RESPONSE_FORMAT_DELTA)
+            .json(request)
+            .send()
+            .await?;
+        let response = check_status(response).await?;
+
+        let version = response
+            .headers()
+            .get(TABLE_VERSION_HEADER)
+            .and_then(|version| version.to_str().ok()?.parse().ok());
+        let body = response.text().await?;
+        let lines = body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        debug!(
+            "Received {} response lines at version {version:?}",
+            lines.len()
+        );
+        Ok((version, lines))
+    }
+
+    /// Queries the given table and exposes it as a kernel [`Snapshot`].
+    ///
+    /// [`Snapshot`]: delta_kernel::Snapshot
+    pub async fn load_table(
+        &self,
+        table: SharedTableName,
+        request: QueryTableRequest,
+    ) -> Result<SharedTable> {
+        let (version, lines) = self.query_table(&table, &request).await?;
+        SharedTable::try_new(table, version, lines).await
+    }
+}
+
+async fn check_status(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let message = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    Err(Error::ApiError {
+        status: status.as_u16(),
+        message,
+    })
+}
@@ -0,0 +1,36 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("URL parse error: {0}")]
+    UrlParse(#[from] url::ParseError),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("API error (status {status}): {message}")]
+    ApiError { status: u16, message: String },
+
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
+
+    #[error("Invalid table name '{0}', expected share.schema.table")]
+    InvalidTableName(String),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Delta kernel error: {0}")]
+    Kernel(#[from] delta_kernel::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
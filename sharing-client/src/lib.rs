@@ -0,0 +1,39 @@
+//! Delta Sharing Client for Rust
+//!
+//! This crate provides a client for the Delta Sharing REST protocol, which exposes shared tables
+//! through the kernel's [`Snapshot`]/[`Scan`] API.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use sharing_client::{models::QueryTableRequest, SharingClient, SharingProfile};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let profile = SharingProfile::try_from_file("config.share")?;
+//!     let client = SharingClient::new(profile)?;
+//!     let table = client
+//!         .load_table("share.schema.table".parse()?, QueryTableRequest::new())
+//!         .await?;
+//!     let scan = table.snapshot().clone().scan_builder().build()?;
+//!     for data in scan.execute(table.engine().clone())? {
+//!         let _data = data?;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`Snapshot`]: delta_kernel::Snapshot
+//! [`Scan`]: delta_kernel::scan::Scan
+
+pub mod client;
+pub mod error;
+pub mod models;
+pub mod predicate;
+pub mod profile;
+pub mod table;
+
+pub use client::SharingClient;
+pub use error::{Error, Result};
+pub use profile::SharingProfile;
+pub use table::{SharedTable, SharedTableName};
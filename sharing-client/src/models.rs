@@ -0,0 +1,117 @@
+use delta_kernel::expressions::Predicate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::predicate::to_json_predicate_hint;
+
+/// The body of a query table request.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryTableRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_predicate_hints: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_hint: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+}
+
+impl QueryTableRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the given predicate to the server as JSON predicate hints, which it may use to skip
+    /// files. Parts of the predicate that can't be expressed as hints are dropped (see
+    /// [`to_json_predicate_hint`]).
+    pub fn with_predicate(mut self, predicate: &Predicate) -> Self {
+        self.json_predicate_hints = to_json_predicate_hint(predicate).map(|hint| hint.to_string());
+        self
+    }
+
+    /// Hint the server that only the given number of rows are needed.
+    pub fn with_limit_hint(mut self, limit: u64) -> Self {
+        self.limit_hint = Some(limit);
+        self
+    }
+
+    /// Query the given version of the table instead of the latest one.
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+}
+
+/// A line of a query table response in the `delta` response format. Each line wraps one Delta
+/// action: the protocol and metadata of the table, or an `add` action for one of its files whose
+/// path is a pre-signed URL.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryTableResponseLine {
+    Protocol {
+        #[serde(rename = "deltaProtocol")]
+        delta_protocol: Value,
+    },
+    MetaData {
+        #[serde(rename = "deltaMetadata")]
+        delta_metadata: Value,
+    },
+    File {
+        #[serde(rename = "deltaSingleAction")]
+        delta_single_action: Value,
+    },
+}
+
+impl QueryTableResponseLine {
+    /// The wrapped action, in the format of a Delta log entry.
+    pub fn into_log_action(self) -> Value {
+        match self {
+            Self::Protocol { delta_protocol } => serde_json::json!({ "protocol": delta_protocol }),
+            Self::MetaData { delta_metadata } => serde_json::json!({ "metaData": delta_metadata }),
+            Self::File {
+                delta_single_action,
+            } => delta_single_action,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_query_table_request() {
+        let request = QueryTableRequest::new().with_limit_hint(10).with_version(3);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({ "limitHint": 10, "version": 3 })
+        );
+    }
+
+    #[test]
+    fn test_response_line_into_log_action() {
+        let line: QueryTableResponseLine = serde_json::from_value(json!({
+            "file": {
+                "id": "591723a8-6a27-4240-a90e-57426f4736d2",
+                "expirationTimestamp": 1652140800000i64,
+                "deltaSingleAction": { "add": { "path": "https://bucket/file.parquet" } }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            line.into_log_action(),
+            json!({ "add": { "path": "https://bucket/file.parquet" } })
+        );
+
+        let line: QueryTableResponseLine = serde_json::from_value(json!({
+            "protocol": { "deltaProtocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }
+        }))
+        .unwrap();
+        assert_eq!(
+            line.into_log_action(),
+            json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } })
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Conversion of kernel predicates to Delta Sharing JSON predicate hints.
+//!
+//! Predicate hints only let the sharing server skip files, so a hint may match more rows than the
+//! predicate it was derived from, but never fewer. Parts of a predicate that have no equivalent
+//! hint are thus dropped where that only widens the hint (i.e. conjuncts of the top-level AND),
+//! and make the entire predicate unconvertible otherwise.
+
+use delta_kernel::expressions::{
+    BinaryPredicate, BinaryPredicateOp, Expression, JunctionPredicate, JunctionPredicateOp,
+    Predicate, Scalar,
+};
+use serde_json::{json, Value};
+
+/// Converts `predicate` to a JSON predicate hint, or returns `None` if no part of it can be
+/// expressed as a hint.
+pub fn to_json_predicate_hint(predicate: &Predicate) -> Option<Value> {
+    match predicate {
+        Predicate::Junction(JunctionPredicate {
+            op: JunctionPredicateOp::And,
+            preds,
+        }) => {
+            let mut children: Vec<_> = preds.iter().filter_map(to_json_predicate_hint).collect();
+            match children.len() {
+                0 => None,
+                1 => children.pop(),
+                _ => Some(json!({ "op": "and", "children": children })),
+            }
+        }
+        _ => to_exact_hint(predicate),
+    }
+}
+
+// Converts `predicate` to a hint that matches exactly the same rows, if possible.
+fn to_exact_hint(predicate: &Predicate) -> Option<Value> {
+    match predicate {
+        Predicate::Not(pred) => Some(json!({ "op": "not", "children": [to_exact_hint(pred)?] })),
+        Predicate::Junction(JunctionPredicate { op, preds }) => {
+            let op = match op {
+                JunctionPredicateOp::And => "and",
+                JunctionPredicateOp::Or => "or",
+            };
+            let children = preds
+                .iter()
+                .map(to_exact_hint)
+                .collect::<Option<Vec<_>>>()?;
+            Some(json!({ "op": op, "children": children }))
+        }
+        Predicate::Binary(BinaryPredicate { op, left, right }) => {
+            let op = match op {
+                BinaryPredicateOp::LessThan => "lessThan",
+                BinaryPredicateOp::GreaterThan => "greaterThan",
+                BinaryPredicateOp::Equal => "equal",
+                BinaryPredicateOp::Distinct | BinaryPredicateOp::In => return None,
+            };
+            // columns have no type of their own, so they take the type of the literal they are
+            // compared with
+            let (left, right) = match (left.as_ref(), right.as_ref()) {
+                (Expression::Column(name), Expression::Literal(value)) => {
+                    let (value_type, value) = literal(value)?;
+                    (column(name.path(), value_type)?, value)
+                }
+                (Expression::Literal(value), Expression::Column(name)) => {
+                    let (value_type, value) = literal(value)?;
+                    (value, column(name.path(), value_type)?)
+                }
+                _ => return None,
+            };
+            Some(json!({ "op": op, "children": [left, right] }))
+        }
+        _ => None,
+    }
+}
+
+fn column(path: &[String], value_type: &str) -> Option<Value> {
+    // hints can only reference top-level columns
+    let [name] = path else {
+        return None;
+    };
+    Some(json!({ "op": "column", "name": name, "valueType": value_type }))
+}
+
+// Returns the value type of the literal, along with the literal hint.
+fn literal(value: &Scalar) -> Option<(&'static str, Value)> {
+    let (value_type, value) = match value {
+        Scalar::Boolean(v) => ("bool", v.to_string()),
+        Scalar::Byte(v) => ("int", v.to_string()),
+        Scalar::Short(v) => ("int", v.to_string()),
+        Scalar::Integer(v) => ("int", v.to_string()),
+        Scalar::Long(v) => ("long", v.to_string()),
+        Scalar::Float(v) => ("float", v.to_string()),
+        Scalar::Double(v) => ("double", v.to_string()),
+        Scalar::String(v) => ("string", v.clone()),
+        Scalar::Date(days) => {
+            let date = chrono::DateTime::from_timestamp(i64::from(*days) * 86_400, 0)?;
+            ("date", date.date_naive().to_string())
+        }
+        _ => return None,
+    };
+    let hint = json!({ "op": "literal", "value": value, "valueType": value_type });
+    Some((value_type, hint))
+}
+
+#[cfg(test)]
+mod tests {
+    use delta_kernel::expressions::column_expr;
+
+    use super::*;
+
+    #[test]
+    fn test_comparison_hint() {
+        let predicate = Predicate::lt(column_expr!("a"), Expression::literal(5i64));
+        let expected = json!({
+            "op": "lessThan",
+            "children": [
+                { "op": "column", "name": "a", "valueType": "long" },
+                { "op": "literal", "value": "5", "valueType": "long" },
+            ]
+        });
+        assert_eq!(to_json_predicate_hint(&predicate), Some(expected));
+    }
+
+    #[test]
+    fn test_unconvertible_conjuncts_are_dropped() {
+        let supported = Predicate::eq(column_expr!("a"), Expression::literal("x"));
+        let unsupported = Predicate::is_null(column_expr!("b"));
+
+        let predicate = Predicate::and(supported.clone(), unsupported.clone());
+        assert_eq!(
+            to_json_predicate_hint(&predicate),
+            to_json_predicate_hint(&supported)
+        );
+
+        // dropping a disjunct (or a conjunct below a NOT) would narrow the hint
+        let predicate = Predicate::or(supported.clone(), unsupported.clone());
+        assert_eq!(to_json_predicate_hint(&predicate), None);
+        let predicate = Predicate::not(Predicate::and(supported, unsupported));
+        assert_eq!(to_json_predicate_hint(&predicate), None);
+    }
+}
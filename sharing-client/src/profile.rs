@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::{Error, Result};
+
+/// The highest version of the profile file format this client understands.
+pub const MAX_SHARE_CREDENTIALS_VERSION: u32 = 1;
+
+/// A Delta Sharing profile, i.e. the contents of a `.share` profile file handed out by a data
+/// provider. It holds the sharing server's endpoint and the bearer token to authenticate with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharingProfile {
+    pub share_credentials_version: u32,
+    pub endpoint: String,
+    pub bearer_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_time: Option<DateTime<Utc>>,
+}
+
+impl SharingProfile {
+    /// Reads a profile from the given profile file.
+    pub fn try_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let profile = std::fs::read_to_string(path)?;
+        profile.parse()
+    }
+
+    /// The endpoint of the sharing server, normalized with a trailing slash.
+    pub fn endpoint_url(&self) -> Result<Url> {
+        let mut url = Url::parse(&self.endpoint)?;
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Ok(url)
+    }
+
+    /// Whether the bearer token of this profile has expired. Profiles without an expiration time
+    /// never expire.
+    pub fn is_expired(&self) -> bool {
+        self.expiration_time.is_some_and(|exp| exp < Utc::now())
+    }
+}
+
+impl std::str::FromStr for SharingProfile {
+    type Err = Error;
+
+    fn from_str(profile: &str) -> Result<Self> {
+        let profile: Self = serde_json::from_str(profile)?;
+        if profile.share_credentials_version > MAX_SHARE_CREDENTIALS_VERSION {
+            return Err(Error::InvalidProfile(format!(
+                "Unsupported shareCredentialsVersion {}, the highest supported version is {}",
+                profile.share_credentials_version, MAX_SHARE_CREDENTIALS_VERSION
+            )));
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let profile: SharingProfile = r#"{
+            "shareCredentialsVersion": 1,
+            "endpoint": "https://sharing.delta.io/delta-sharing",
+            "bearerToken": "token",
+            "expirationTime": "2021-11-12T00:12:29.0Z"
+        }"#
+        .parse()
+        .unwrap();
+        assert_eq!(profile.bearer_token, "token");
+        assert!(profile.is_expired());
+        assert_eq!(
+            profile.endpoint_url().unwrap().as_str(),
+            "https://sharing.delta.io/delta-sharing/"
+        );
+
+        let profile: SharingProfile =
+            r#"{"shareCredentialsVersion": 1, "endpoint": "https://a.b/", "bearerToken": "t"}"#
+                .parse()
+                .unwrap();
+        assert!(!profile.is_expired());
+
+        let result: Result<SharingProfile> =
+            r#"{"shareCredentialsVersion": 2, "endpoint": "https://a.b/", "bearerToken": "t"}"#
+                .parse();
+        assert!(matches!(result, Err(Error::InvalidProfile(_))));
+    }
+}
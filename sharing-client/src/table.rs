@@ -0,0 +1,206 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::{Snapshot, SnapshotRef};
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::models::QueryTableResponseLine;
+
+/// The default engine type constructed for shared tables.
+pub type SharingEngine = DefaultEngine<TokioBackgroundExecutor>;
+
+// the single commit holding all actions of a query response
+const COMMIT_PATH: &str = "_delta_log/00000000000000000000.json";
+
+/// The three-level name of a shared table, i.e. `share.schema.table`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedTableName {
+    pub share: String,
+    pub schema: String,
+    pub table: String,
+}
+
+impl FromStr for SharedTableName {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.split('.').collect::<Vec<_>>()[..] {
+            [share, schema, table] if [share, schema, table].iter().all(|p| !p.is_empty()) => {
+                Ok(Self {
+                    share: share.to_string(),
+                    schema: schema.to_string(),
+                    table: table.to_string(),
+                })
+            }
+            _ => Err(Error::InvalidTableName(name.to_string())),
+        }
+    }
+}
+
+impl Display for SharedTableName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.share, self.schema, self.table)
+    }
+}
+
+/// A shared table, exposed as a kernel [`Snapshot`] together with the engine to scan it with.
+///
+/// The actions of the query response are written to an in-memory Delta log with a single commit,
+/// so the version of the snapshot is always 0; the version of the shared table is available from
+/// [`version`]. The data files of the table are read through their pre-signed URLs, which expire,
+/// so long-running readers must load the table again.
+///
+/// [`version`]: Self::version
+#[derive(Debug, Clone)]
+pub struct SharedTable {
+    name: SharedTableName,
+    version: Option<u64>,
+    engine: Arc<SharingEngine>,
+    snapshot: SnapshotRef,
+}
+
+impl SharedTable {
+    pub(crate) async fn try_new(
+        name: SharedTableName,
+        version: Option<u64>,
+        lines: Vec<QueryTableResponseLine>,
+    ) -> Result<Self> {
+        let has_protocol = lines
+            .iter()
+            .any(|line| matches!(line, QueryTableResponseLine::Protocol { .. }));
+        let has_metadata = lines
+            .iter()
+            .any(|line| matches!(line, QueryTableResponseLine::MetaData { .. }));
+        if !has_protocol || !has_metadata {
+            return Err(Error::InvalidResponse(format!(
+                "The response for {name} lacks the protocol or metadata of the table"
+            )));
+        }
+
+        let commit = lines
+            .into_iter()
+            .map(|line| line.into_log_action().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let store = Arc::new(InMemory::new());
+        store.put(&Path::from(COMMIT_PATH), commit.into()).await?;
+
+        let engine = Arc::new(DefaultEngine::new(
+            store,
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let table_root = Url::parse("memory:///")?;
+        let snapshot_engine = engine.clone();
+        // building a snapshot does blocking IO through the engine
+        let snapshot = tokio::task::spawn_blocking(move || {
+            Snapshot::builder_for(table_root).build(snapshot_engine.as_ref())
+        })
+        .await
+        .map_err(|e| Error::Kernel(delta_kernel::Error::generic(e.to_string())))??;
+
+        Ok(Self {
+            name,
+            version,
+            engine,
+            snapshot,
+        })
+    }
+
+    /// The name of the table.
+    pub fn name(&self) -> &SharedTableName {
+        &self.name
+    }
+
+    /// The version of the shared table, if reported by the sharing server.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// The engine to scan the table with.
+    pub fn engine(&self) -> &Arc<SharingEngine> {
+        &self.engine
+    }
+
+    /// The snapshot of the table.
+    pub fn snapshot(&self) -> &SnapshotRef {
+        &self.snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_table_name() {
+        let name: SharedTableName = "share.schema.table".parse().unwrap();
+        assert_eq!(name.share, "share");
+        assert_eq!(name.schema, "schema");
+        assert_eq!(name.table, "table");
+        assert_eq!(name.to_string(), "share.schema.table");
+
+        for invalid in ["schema.table", "share..table", "a.b.c.d"] {
+            assert!(invalid.parse::<SharedTableName>().is_err(), "{invalid}");
+        }
+    }
+
+    fn line(value: serde_json::Value) -> QueryTableResponseLine {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_shared_table_snapshot() {
+        let schema = r#"{"type":"struct","fields":[{"name":"id","type":"long","nullable":true,"metadata":{}}]}"#;
+        let lines = vec![
+            line(json!({
+                "protocol": { "deltaProtocol": { "minReaderVersion": 1, "minWriterVersion": 2 } }
+            })),
+            line(json!({
+                "metaData": {
+                    "version": 7,
+                    "deltaMetadata": {
+                        "id": "table-id",
+                        "format": { "provider": "parquet", "options": {} },
+                        "schemaString": schema,
+                        "partitionColumns": [],
+                        "configuration": {},
+                        "createdTime": 1587968585495i64
+                    }
+                }
+            })),
+            line(json!({
+                "file": {
+                    "id": "file-id",
+                    "deltaSingleAction": {
+                        "add": {
+                            "path": "https://bucket.s3.amazonaws.com/file.parquet?X-Amz-Signature=abc",
+                            "partitionValues": {},
+                            "size": 1024,
+                            "modificationTime": 1587968586000i64,
+                            "dataChange": true
+                        }
+                    }
+                }
+            })),
+        ];
+        let name: SharedTableName = "share.schema.table".parse().unwrap();
+        let table = SharedTable::try_new(name.clone(), Some(7), lines)
+            .await
+            .unwrap();
+        assert_eq!(table.version(), Some(7));
+        assert_eq!(table.snapshot().version(), 0);
+        assert_eq!(table.snapshot().schema().fields().count(), 1);
+
+        let result = SharedTable::try_new(name, None, vec![]).await;
+        assert!(matches!(result, Err(Error::InvalidResponse(_))));
+    }
+}
@@ -26,6 +26,8 @@ use object_store::{path::Path, ObjectStore};
 use serde_json::{json, to_vec};
 use url::Url;
 
+pub mod table_builder;
+
 /// unpack the test data from {test_parent_dir}/{test_name}.tar.zst into a temp dir, and return the
 /// dir it was unpacked into
 pub fn load_test_data(
@@ -379,7 +381,7 @@ pub fn read_scan(scan: &Scan, engine: Arc<dyn Engine>) -> DeltaResult<Vec<Record
     scan_results
         .map(|scan_result| -> DeltaResult<_> {
             let scan_result = scan_result?;
-            let mask = scan_result.full_mask();
+            let mask = scan_result.full_mask_buffer();
             let data = scan_result.raw_data?;
             let record_batch = to_arrow(data)?;
             if let Some(mask) = mask {
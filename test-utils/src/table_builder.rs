@@ -0,0 +1,559 @@
+//! Generate Delta tables with chosen features for tests, instead of checking in fixtures.
+//!
+//! Appends go through the kernel's write path ([`Transaction`]) whenever the kernel can write to
+//! the table. The builder writes the data files and commits of tables with features the kernel
+//! can't write yet (column mapping, change data feed) itself, as well as the deletion vectors of
+//! deletes. Checkpoints are written with [`DefaultEngine::write_checkpoint`].
+//!
+//! [`Transaction`]: delta_kernel::transaction::Transaction
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use delta_kernel::arrow::array::{
+    Array as _, AsArray as _, BooleanArray, RecordBatch, StringArray,
+};
+use delta_kernel::arrow::compute::{cast, filter_record_batch};
+use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Int64Type, Schema};
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::parquet::arrow::arrow_writer::ArrowWriter;
+use delta_kernel::schema::{ColumnMetadataKey, DataType, MetadataValue, SchemaRef, StructType};
+use delta_kernel::transaction::CommitResult;
+use delta_kernel::{Snapshot, Version};
+use itertools::Itertools;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use roaring::RoaringTreemap;
+use serde_json::{json, Value};
+use url::Url;
+use uuid::Uuid;
+
+type BuildResult<T> = Result<T, Box<dyn Error>>;
+
+/// The magic number of a deletion vector's serialized roaring bitmap, in the "portable" format.
+const DV_MAGIC: u32 = 1681511377;
+
+enum Operation {
+    Append(RecordBatch),
+    DeleteRows { file: usize, row_indexes: Vec<u64> },
+    Checkpoint,
+}
+
+/// Builds a Delta table with the chosen features, then applies a sequence of operations to it,
+/// each of which becomes a version of the table (except checkpoints).
+///
+/// ```ignore
+/// let table = TestTableBuilder::new(schema)
+///     .with_partition_columns(["letter"])
+///     .with_deletion_vectors()
+///     .append(batch)
+///     .delete_rows(0, [1, 3])
+///     .checkpoint()
+///     .build(store, Url::parse("memory:///table/")?)
+///     .await?;
+/// ```
+pub struct TestTableBuilder {
+    schema: SchemaRef,
+    partition_columns: Vec<String>,
+    properties: HashMap<String, String>,
+    deletion_vectors: bool,
+    column_mapping: bool,
+    change_data_feed: bool,
+    operations: Vec<Operation>,
+}
+
+impl TestTableBuilder {
+    /// A builder of an unpartitioned table with the given (logical) schema and no features.
+    pub fn new(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            partition_columns: vec![],
+            properties: HashMap::new(),
+            deletion_vectors: false,
+            column_mapping: false,
+            change_data_feed: false,
+            operations: vec![],
+        }
+    }
+
+    /// Partition the table by the given columns. Partition values are serialized with arrow's
+    /// cast to strings, which matches Delta's serialization for e.g. strings, numbers and dates,
+    /// and must not be null.
+    pub fn with_partition_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.partition_columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set a table property, e.g. `delta.checkpointInterval`.
+    pub fn with_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Enable the `deletionVectors` table feature, which [`Self::delete_rows`] requires.
+    pub fn with_deletion_vectors(mut self) -> Self {
+        self.deletion_vectors = true;
+        self
+    }
+
+    /// Enable column mapping in `name` mode, with random physical names. Only supported for
+    /// schemas of primitive columns.
+    pub fn with_column_mapping(mut self) -> Self {
+        self.column_mapping = true;
+        self
+    }
+
+    /// Enable the change data feed.
+    pub fn with_change_data_feed(mut self) -> Self {
+        self.change_data_feed = true;
+        self
+    }
+
+    /// Append the rows of `batch` (with the table's logical schema) in a new version. The rows
+    /// of each partition are written to a separate data file.
+    pub fn append(mut self, batch: RecordBatch) -> Self {
+        self.operations.push(Operation::Append(batch));
+        self
+    }
+
+    /// Delete the rows at `row_indexes` of the `file`-th data file the builder wrote (counting
+    /// from 0, in write order) in a new version, by adding a deletion vector to the file.
+    pub fn delete_rows(mut self, file: usize, row_indexes: impl IntoIterator<Item = u64>) -> Self {
+        let row_indexes = row_indexes.into_iter().collect();
+        self.operations
+            .push(Operation::DeleteRows { file, row_indexes });
+        self
+    }
+
+    /// Write a checkpoint of the latest version.
+    pub fn checkpoint(mut self) -> Self {
+        self.operations.push(Operation::Checkpoint);
+        self
+    }
+
+    /// Create the table at `table_root` in `store`, then apply the operations to it.
+    pub async fn build(
+        self,
+        store: Arc<dyn ObjectStore>,
+        table_root: Url,
+    ) -> BuildResult<TestTable> {
+        let engine = Arc::new(DefaultEngine::new(
+            store.clone(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let schema = if self.column_mapping {
+            Arc::new(with_column_mapping_metadata(&self.schema)?)
+        } else {
+            self.schema.clone()
+        };
+        let mut table = TestTable {
+            table_root,
+            store,
+            engine,
+            schema,
+            partition_columns: self.partition_columns.clone(),
+            data_files: vec![],
+            version: None,
+        };
+        table
+            .commit(self.create_table_actions(&table.schema)?)
+            .await?;
+
+        // the kernel can't write to tables with these features yet
+        let kernel_writable = !self.column_mapping && !self.change_data_feed;
+        for operation in self.operations {
+            match operation {
+                Operation::Append(batch) if kernel_writable => table.kernel_append(batch).await?,
+                Operation::Append(batch) => table.append(batch).await?,
+                Operation::DeleteRows { file, row_indexes } => {
+                    if !self.deletion_vectors {
+                        return Err("Deleting rows requires the deletionVectors feature".into());
+                    }
+                    table.delete_rows(file, row_indexes).await?
+                }
+                Operation::Checkpoint => table.checkpoint().await?,
+            }
+        }
+        Ok(table)
+    }
+
+    fn create_table_actions(&self, schema: &StructType) -> BuildResult<Vec<Value>> {
+        let mut reader_features = vec![];
+        let mut writer_features = vec![];
+        let mut configuration = self.properties.clone();
+        if self.deletion_vectors {
+            reader_features.push("deletionVectors");
+            writer_features.push("deletionVectors");
+            configuration.insert("delta.enableDeletionVectors".into(), "true".into());
+        }
+        if self.column_mapping {
+            reader_features.push("columnMapping");
+            writer_features.push("columnMapping");
+            configuration.insert("delta.columnMapping.mode".into(), "name".into());
+            configuration.insert(
+                "delta.columnMapping.maxColumnId".into(),
+                schema.fields().count().to_string(),
+            );
+        }
+        if self.change_data_feed {
+            writer_features.push("changeDataFeed");
+            configuration.insert("delta.enableChangeDataFeed".into(), "true".into());
+        }
+
+        let protocol = if writer_features.is_empty() {
+            json!({"minReaderVersion": 1, "minWriterVersion": 2})
+        } else {
+            json!({
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": reader_features,
+                "writerFeatures": writer_features,
+            })
+        };
+        Ok(vec![
+            commit_info("CREATE TABLE"),
+            json!({"protocol": protocol}),
+            json!({"metaData": {
+                "id": Uuid::new_v4().to_string(),
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(schema)?,
+                "partitionColumns": self.partition_columns,
+                "configuration": configuration,
+                "createdTime": now_millis(),
+            }}),
+        ])
+    }
+}
+
+/// A data file that the builder wrote.
+struct DataFile {
+    path: String,
+    size: u64,
+    num_records: usize,
+    /// Keyed by physical column names
+    partition_values: HashMap<String, String>,
+    deleted_rows: RoaringTreemap,
+}
+
+impl DataFile {
+    fn stats(&self) -> String {
+        json!({"numRecords": self.num_records}).to_string()
+    }
+}
+
+/// A table generated by a [`TestTableBuilder`].
+pub struct TestTable {
+    table_root: Url,
+    store: Arc<dyn ObjectStore>,
+    engine: Arc<DefaultEngine<TokioBackgroundExecutor>>,
+    /// The schema of the table, with column mapping metadata if enabled
+    schema: SchemaRef,
+    partition_columns: Vec<String>,
+    data_files: Vec<DataFile>,
+    /// The latest version, or `None` before the table is created
+    version: Option<Version>,
+}
+
+impl TestTable {
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    pub fn engine(&self) -> Arc<DefaultEngine<TokioBackgroundExecutor>> {
+        self.engine.clone()
+    }
+
+    /// The latest version of the table.
+    pub fn version(&self) -> Version {
+        self.version.unwrap_or_default()
+    }
+
+    /// The paths of the data files the builder wrote, as in their add actions, in write order.
+    pub fn data_files(&self) -> impl Iterator<Item = &str> {
+        self.data_files.iter().map(|file| file.path.as_str())
+    }
+
+    fn snapshot(&self) -> BuildResult<Arc<Snapshot>> {
+        Ok(Snapshot::builder_for(self.table_root.clone()).build(self.engine.as_ref())?)
+    }
+
+    fn physical_name<'a>(&'a self, column: &'a str) -> &'a str {
+        let physical_name = self.schema.field(column).and_then(|field| {
+            field.get_config_value(&ColumnMetadataKey::ColumnMappingPhysicalName)
+        });
+        match physical_name {
+            Some(MetadataValue::String(name)) => name.as_str(),
+            _ => column,
+        }
+    }
+
+    /// Write `actions` to the commit file of the next version.
+    async fn commit(&mut self, actions: Vec<Value>) -> BuildResult<()> {
+        let version = self.version.map_or(0, |version| version + 1);
+        let commit = actions.iter().map(Value::to_string).join("\n");
+        let path = self.log_path(&format!("{version:020}.json"))?;
+        self.store.put(&path, commit.into()).await?;
+        self.version = Some(version);
+        Ok(())
+    }
+
+    fn log_path(&self, file_name: &str) -> BuildResult<Path> {
+        let url = self.table_root.join("_delta_log/")?.join(file_name)?;
+        Ok(Path::from_url_path(url.path())?)
+    }
+
+    /// Append `batch` through a kernel [`Transaction`](delta_kernel::transaction::Transaction).
+    async fn kernel_append(&mut self, batch: RecordBatch) -> BuildResult<()> {
+        let mut txn = self
+            .snapshot()?
+            .transaction()?
+            .with_operation("WRITE".to_string())
+            .with_engine_info("test_utils/table_builder");
+        let write_context = txn.get_write_context();
+        let mut data_files = vec![];
+        for (partition_values, batch) in self.split_by_partition(&batch)? {
+            let num_records = batch.num_rows();
+            let add_files = self
+                .engine
+                .write_parquet(
+                    &ArrowEngineData::new(batch),
+                    &write_context,
+                    partition_values.clone(),
+                    true,
+                )
+                .await?;
+            let add_files: RecordBatch = ArrowEngineData::try_from_engine_data(add_files)?.into();
+            let paths = add_files
+                .column_by_name("path")
+                .ok_or("Add file metadata has no path")?
+                .as_string::<i32>();
+            let sizes = add_files
+                .column_by_name("size")
+                .ok_or("Add file metadata has no size")?
+                .as_primitive::<Int64Type>();
+            data_files.push(DataFile {
+                path: paths.value(0).to_string(),
+                size: sizes.value(0).try_into()?,
+                num_records,
+                partition_values,
+                deleted_rows: RoaringTreemap::new(),
+            });
+            txn.add_files(Box::new(ArrowEngineData::new(add_files)));
+        }
+        match txn.commit(self.engine.as_ref())? {
+            CommitResult::Committed { version, .. } => self.version = Some(version),
+            _ => return Err("Append to the test table conflicted".into()),
+        }
+        self.data_files.extend(data_files);
+        Ok(())
+    }
+
+    /// Append `batch` by writing its data files and commit directly, for tables with features
+    /// the kernel can't write yet.
+    async fn append(&mut self, batch: RecordBatch) -> BuildResult<()> {
+        let mut actions = vec![commit_info("WRITE")];
+        for (partition_values, batch) in self.split_by_partition(&batch)? {
+            let batch = self.to_physical(&batch)?;
+            let mut buffer = vec![];
+            let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+
+            let file = DataFile {
+                path: format!("{}.parquet", Uuid::new_v4()),
+                size: buffer.len().try_into()?,
+                num_records: batch.num_rows(),
+                partition_values,
+                deleted_rows: RoaringTreemap::new(),
+            };
+            let url = self.table_root.join(&file.path)?;
+            self.store
+                .put(&Path::from_url_path(url.path())?, buffer.into())
+                .await?;
+            actions.push(json!({"add": {
+                "path": file.path,
+                "partitionValues": file.partition_values,
+                "size": file.size,
+                "modificationTime": now_millis(),
+                "dataChange": true,
+                "stats": file.stats(),
+            }}));
+            self.data_files.push(file);
+        }
+        self.commit(actions).await
+    }
+
+    /// The rows of `batch` of each partition, keyed by their partition values. The partition
+    /// values are keyed by physical column names.
+    fn split_by_partition(
+        &self,
+        batch: &RecordBatch,
+    ) -> BuildResult<Vec<(HashMap<String, String>, RecordBatch)>> {
+        if self.partition_columns.is_empty() {
+            return Ok(vec![(HashMap::new(), batch.clone())]);
+        }
+        let values: Vec<StringArray> = self
+            .partition_columns
+            .iter()
+            .map(|column| -> BuildResult<_> {
+                let array = batch
+                    .column_by_name(column)
+                    .ok_or_else(|| format!("Batch has no partition column {column}"))?;
+                if array.null_count() > 0 {
+                    return Err(format!("Partition column {column} has null values").into());
+                }
+                Ok(cast(array, &ArrowDataType::Utf8)?
+                    .as_string::<i32>()
+                    .clone())
+            })
+            .try_collect()?;
+        let keys: Vec<Vec<&str>> = (0..batch.num_rows())
+            .map(|row| values.iter().map(|array| array.value(row)).collect())
+            .collect();
+        keys.iter()
+            .unique()
+            .map(|key| {
+                let mask: BooleanArray = keys.iter().map(|row_key| Some(row_key == key)).collect();
+                let partition_values = self
+                    .partition_columns
+                    .iter()
+                    .zip(key)
+                    .map(|(column, value)| {
+                        (self.physical_name(column).to_string(), value.to_string())
+                    })
+                    .collect();
+                Ok((partition_values, filter_record_batch(batch, &mask)?))
+            })
+            .try_collect()
+    }
+
+    /// The data of `batch` as it is stored in data files: without partition columns, and with
+    /// physical column names.
+    fn to_physical(&self, batch: &RecordBatch) -> BuildResult<RecordBatch> {
+        let (fields, columns): (Vec<_>, Vec<_>) = batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .filter(|(field, _)| !self.partition_columns.contains(field.name()))
+            .map(|(field, column)| {
+                let field = Field::new(
+                    self.physical_name(field.name()),
+                    field.data_type().clone(),
+                    field.is_nullable(),
+                );
+                (field, column.clone())
+            })
+            .unzip();
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// Delete rows of a data file by replacing its add action with one that has an (inline)
+    /// deletion vector of all its deleted rows.
+    async fn delete_rows(&mut self, file: usize, row_indexes: Vec<u64>) -> BuildResult<()> {
+        let file = self
+            .data_files
+            .get_mut(file)
+            .ok_or_else(|| format!("The table has no data file {file}"))?;
+        let old_deletion_vector = inline_deletion_vector(&file.deleted_rows)?;
+        file.deleted_rows.extend(row_indexes);
+        let now = now_millis();
+        let actions = vec![
+            commit_info("DELETE"),
+            json!({"remove": {
+                "path": file.path,
+                "deletionTimestamp": now,
+                "dataChange": true,
+                "extendedFileMetadata": true,
+                "partitionValues": file.partition_values,
+                "size": file.size,
+                "deletionVector": old_deletion_vector,
+            }}),
+            json!({"add": {
+                "path": file.path,
+                "partitionValues": file.partition_values,
+                "size": file.size,
+                "modificationTime": now,
+                "dataChange": true,
+                "stats": file.stats(),
+                "deletionVector": inline_deletion_vector(&file.deleted_rows)?,
+            }}),
+        ];
+        self.commit(actions).await
+    }
+
+    async fn checkpoint(&mut self) -> BuildResult<()> {
+        self.engine.write_checkpoint(self.snapshot()?).await?;
+        Ok(())
+    }
+}
+
+/// `schema` with column mapping ids and (random) physical names.
+fn with_column_mapping_metadata(schema: &StructType) -> BuildResult<StructType> {
+    let fields = schema
+        .fields()
+        .enumerate()
+        .map(|(i, field)| -> BuildResult<_> {
+            if !matches!(field.data_type(), DataType::Primitive(_)) {
+                return Err(format!(
+                    "Column mapping of nested column {} is not supported",
+                    field.name()
+                )
+                .into());
+            }
+            let id = i64::try_from(i)? + 1;
+            Ok(field.clone().add_metadata([
+                (
+                    ColumnMetadataKey::ColumnMappingId.as_ref(),
+                    MetadataValue::Number(id),
+                ),
+                (
+                    ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+                    MetadataValue::String(format!("col-{}", Uuid::new_v4())),
+                ),
+            ]))
+        });
+    Ok(StructType::try_new(fields.try_collect::<_, Vec<_>, _>()?)?)
+}
+
+/// The descriptor of an inline deletion vector of `deleted_rows`, or null if no rows are deleted.
+pub fn inline_deletion_vector(deleted_rows: &RoaringTreemap) -> std::io::Result<Value> {
+    if deleted_rows.is_empty() {
+        return Ok(Value::Null);
+    }
+    let mut bytes = DV_MAGIC.to_le_bytes().to_vec();
+    deleted_rows.serialize_into(&mut bytes)?;
+    let size_in_bytes = bytes.len();
+    // z85 encodes 4 byte chunks
+    bytes.resize(size_in_bytes.next_multiple_of(4), 0);
+    Ok(json!({
+        "storageType": "i",
+        "pathOrInlineDv": z85::encode(bytes),
+        "sizeInBytes": size_in_bytes,
+        "cardinality": deleted_rows.len(),
+    }))
+}
+
+fn commit_info(operation: &str) -> Value {
+    json!({"commitInfo": {
+        "timestamp": now_millis(),
+        "operation": operation,
+        "engineInfo": "test_utils/table_builder",
+    }})
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as i64)
+}
@@ -31,6 +31,10 @@ pub enum Error {
 
     #[error("Max retries exceeded")]
     MaxRetriesExceeded,
+
+    #[cfg(feature = "delta-kernel")]
+    #[error("Delta kernel error: {0}")]
+    Kernel(#[from] delta_kernel::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,337 @@
+//! Integration with [`delta_kernel`] (requires the `delta-kernel` feature).
+//!
+//! Resolves `uc://catalog.schema.table` URIs through Unity Catalog, vends temporary table
+//! credentials into a [`DefaultEngine`], and builds snapshots of the resolved table. For
+//! catalog-managed tables, the latest (not yet published) commits are only known to the catalog,
+//! so they are fetched from Unity Catalog and passed to the kernel as the snapshot's log tail.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use uc_client::{models::credentials::Operation, UCClient};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let client = UCClient::builder("uc.awesome.org", "your-token").build()?;
+//!     let (engine, table) = client
+//!         .load_table("uc://catalog.schema.table", Operation::Read)
+//!         .await?;
+//!     let snapshot = table.snapshot(&client, engine).await?;
+//!     println!("{} is at version {}", table.name(), snapshot.version());
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::snapshot::SnapshotRef;
+use delta_kernel::{FileMeta, Snapshot};
+use tracing::{debug, instrument};
+use url::Url;
+
+use crate::client::UCClient;
+use crate::error::{Error, Result};
+use crate::models::commits::{Commit, CommitsRequest};
+use crate::models::credentials::{Operation, TemporaryTableCredentials};
+use crate::models::tables::TablesResponse;
+
+/// The URI scheme of Unity Catalog table URIs, e.g. `uc://catalog.schema.table`.
+pub const UC_URI_SCHEME: &str = "uc";
+
+/// The default engine type constructed for Unity Catalog tables.
+pub type UCEngine = DefaultEngine<TokioBackgroundExecutor>;
+
+// table properties by which Unity Catalog marks a table as catalog-managed
+const CATALOG_MANAGED_PROPERTIES: [&str; 2] = [
+    "delta.feature.catalogManaged",
+    "delta.feature.catalogOwned-preview",
+];
+
+/// The three-level name of a Unity Catalog table, parsed from a `uc://catalog.schema.table` URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UCTableName {
+    pub catalog: String,
+    pub schema: String,
+    pub table: String,
+}
+
+impl UCTableName {
+    /// The full name of the table, i.e. `catalog.schema.table`.
+    pub fn full_name(&self) -> String {
+        format!("{}.{}.{}", self.catalog, self.schema, self.table)
+    }
+}
+
+impl FromStr for UCTableName {
+    type Err = Error;
+
+    fn from_str(uri: &str) -> Result<Self> {
+        let invalid = || {
+            Error::InvalidConfiguration(format!(
+                "Invalid table URI '{uri}', expected {UC_URI_SCHEME}://catalog.schema.table"
+            ))
+        };
+        let name = uri
+            .strip_prefix(UC_URI_SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(invalid)?;
+        match name.split('.').collect::<Vec<_>>()[..] {
+            [catalog, schema, table]
+                if [catalog, schema, table].iter().all(|part| !part.is_empty()) =>
+            {
+                Ok(Self {
+                    catalog: catalog.to_string(),
+                    schema: schema.to_string(),
+                    table: table.to_string(),
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Display for UCTableName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{UC_URI_SCHEME}://{}", self.full_name())
+    }
+}
+
+/// A Delta table resolved through Unity Catalog.
+#[derive(Debug, Clone)]
+pub struct UCTable {
+    name: UCTableName,
+    info: TablesResponse,
+    table_root: Url,
+}
+
+impl UCTable {
+    fn try_new(name: UCTableName, info: TablesResponse) -> Result<Self> {
+        if !info.is_delta_table() {
+            return Err(Error::UnsupportedOperation(format!(
+                "{} is not a Delta table (format: {})",
+                name.full_name(),
+                info.data_source_format
+            )));
+        }
+        let mut table_root = Url::parse(&info.storage_location)?;
+        // the kernel requires table roots to be directories
+        if !table_root.path().ends_with('/') {
+            table_root.set_path(&format!("{}/", table_root.path()));
+        }
+        Ok(Self {
+            name,
+            info,
+            table_root,
+        })
+    }
+
+    /// The name of the table.
+    pub fn name(&self) -> &UCTableName {
+        &self.name
+    }
+
+    /// The table metadata returned by Unity Catalog.
+    pub fn info(&self) -> &TablesResponse {
+        &self.info
+    }
+
+    /// The storage location of the table.
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// Whether the table is catalog-managed, i.e. Unity Catalog (rather than the `_delta_log`
+    /// directory) is the source of truth for the table's latest commits.
+    pub fn is_catalog_managed(&self) -> bool {
+        CATALOG_MANAGED_PROPERTIES
+            .iter()
+            .any(|property| self.info.properties.contains_key(*property))
+    }
+
+    /// Fetches the commits of a catalog-managed table that Unity Catalog has ratified but which
+    /// have not been published to the `_delta_log` directory yet, in ascending version order.
+    /// Returns no commits for tables that are not catalog-managed.
+    #[instrument(skip(self, client), fields(table = %self.name))]
+    pub async fn log_tail(&self, client: &UCClient) -> Result<Vec<FileMeta>> {
+        if !self.is_catalog_managed() {
+            return Ok(vec![]);
+        }
+        let request = CommitsRequest::new(&self.info.table_id, &self.info.storage_location)
+            .with_start_version(0);
+        let response = client.get_commits(request).await?;
+        let mut commits = response.commits.unwrap_or_default();
+        commits.sort_by_key(|commit| commit.version);
+        debug!(
+            "Fetched {} unpublished commits, latest table version is {}",
+            commits.len(),
+            response.latest_table_version
+        );
+
+        let staged_commits = self.table_root.join("_delta_log/_staged_commits/")?;
+        commits
+            .iter()
+            .map(|commit| commit_file_meta(&staged_commits, commit))
+            .collect()
+    }
+
+    /// Builds a snapshot of the latest version of the table, including the unpublished commits
+    /// of catalog-managed tables (see [`log_tail`]).
+    ///
+    /// [`log_tail`]: Self::log_tail
+    pub async fn snapshot(&self, client: &UCClient, engine: Arc<UCEngine>) -> Result<SnapshotRef> {
+        let log_tail = self.log_tail(client).await?;
+        let table_root = self.table_root.clone();
+        // building a snapshot does blocking IO through the engine
+        let snapshot = tokio::task::spawn_blocking(move || {
+            Snapshot::builder_for(table_root)
+                .with_log_tail(log_tail)
+                .build(engine.as_ref())
+        })
+        .await
+        .map_err(|e| Error::Kernel(delta_kernel::Error::generic(e.to_string())))??;
+        Ok(snapshot)
+    }
+}
+
+fn commit_file_meta(staged_commits: &Url, commit: &Commit) -> Result<FileMeta> {
+    let size = commit.file_size.try_into().map_err(|_| {
+        Error::InvalidConfiguration(format!(
+            "Invalid size {} of commit {}",
+            commit.file_size, commit.file_name
+        ))
+    })?;
+    Ok(FileMeta {
+        location: staged_commits.join(&commit.file_name)?,
+        last_modified: commit.file_modification_timestamp,
+        size,
+    })
+}
+
+/// The object store options that authorize the [`DefaultEngine`] with the given credentials.
+fn storage_options(credentials: &TemporaryTableCredentials) -> Vec<(&'static str, String)> {
+    match &credentials.aws_temp_credentials {
+        Some(aws) => vec![
+            ("aws_access_key_id", aws.access_key_id.clone()),
+            ("aws_secret_access_key", aws.secret_access_key.clone()),
+            ("aws_session_token", aws.session_token.clone()),
+        ],
+        None => vec![],
+    }
+}
+
+impl UCClient {
+    /// Resolves a `uc://catalog.schema.table` URI, obtains temporary credentials for the given
+    /// `operation` on the table, and returns a [`DefaultEngine`] authorized with them together
+    /// with the resolved [`UCTable`].
+    ///
+    /// Note that the credentials expire (see [`TemporaryTableCredentials::expiration_time`]), so
+    /// long-lived users must load the table again to refresh them.
+    #[instrument(skip(self))]
+    pub async fn load_table(
+        &self,
+        uri: &str,
+        operation: Operation,
+    ) -> Result<(Arc<UCEngine>, UCTable)> {
+        let name: UCTableName = uri.parse()?;
+        let info = self.get_table(&name.full_name()).await?;
+        let table = UCTable::try_new(name, info)?;
+        let credentials = self
+            .get_credentials(&table.info.table_id, operation)
+            .await?;
+        let engine = DefaultEngine::try_new(
+            &table.table_root,
+            storage_options(&credentials),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )?;
+        Ok((Arc::new(engine), table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn table_info(properties: HashMap<String, String>) -> TablesResponse {
+        TablesResponse {
+            name: "my_table".to_string(),
+            catalog_name: "catalog".to_string(),
+            schema_name: "schema".to_string(),
+            table_type: "MANAGED".to_string(),
+            data_source_format: "DELTA".to_string(),
+            storage_location: "s3://bucket/path/to/table".to_string(),
+            owner: "user".to_string(),
+            properties,
+            securable_kind: "TABLE".to_string(),
+            metastore_id: "metastore-id".to_string(),
+            table_id: "table-id".to_string(),
+            schema_id: "schema-id".to_string(),
+            catalog_id: "catalog-id".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_table_name() {
+        let name: UCTableName = "uc://catalog.schema.table".parse().unwrap();
+        assert_eq!(name.catalog, "catalog");
+        assert_eq!(name.schema, "schema");
+        assert_eq!(name.table, "table");
+        assert_eq!(name.full_name(), "catalog.schema.table");
+        assert_eq!(name.to_string(), "uc://catalog.schema.table");
+
+        for invalid in [
+            "catalog.schema.table",
+            "s3://catalog.schema.table",
+            "uc://schema.table",
+            "uc://catalog..table",
+            "uc://a.b.c.d",
+        ] {
+            assert!(invalid.parse::<UCTableName>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_uc_table() {
+        let name: UCTableName = "uc://catalog.schema.my_table".parse().unwrap();
+        let table = UCTable::try_new(name.clone(), table_info(HashMap::new())).unwrap();
+        assert_eq!(table.table_root().as_str(), "s3://bucket/path/to/table/");
+        assert!(!table.is_catalog_managed());
+
+        let properties = HashMap::from([(
+            "delta.feature.catalogOwned-preview".to_string(),
+            "supported".to_string(),
+        )]);
+        let table = UCTable::try_new(name.clone(), table_info(properties)).unwrap();
+        assert!(table.is_catalog_managed());
+
+        let mut info = table_info(HashMap::new());
+        info.data_source_format = "PARQUET".to_string();
+        assert!(UCTable::try_new(name, info).is_err());
+    }
+
+    #[test]
+    fn test_commit_file_meta() {
+        let staged_commits = Url::parse("s3://bucket/table/_delta_log/_staged_commits/").unwrap();
+        let commit = Commit {
+            version: 3,
+            timestamp: 1000,
+            file_name: "00000000000000000003.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json".to_string(),
+            file_size: 42,
+            file_modification_timestamp: 2000,
+            is_disown_commit: None,
+        };
+        let file = commit_file_meta(&staged_commits, &commit).unwrap();
+        assert_eq!(
+            file.location.as_str(),
+            "s3://bucket/table/_delta_log/_staged_commits/\
+             00000000000000000003.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json"
+        );
+        assert_eq!(file.size, 42);
+        assert_eq!(file.last_modified, 2000);
+    }
+}
@@ -22,6 +22,8 @@
 pub mod client;
 pub mod config;
 pub mod error;
+#[cfg(feature = "delta-kernel")]
+pub mod kernel;
 pub mod models;
 
 #[cfg(test)]